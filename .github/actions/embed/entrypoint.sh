@@ -0,0 +1,12 @@
+#!/bin/sh
+set -e
+
+cargo test --all --release --all-features --no-fail-fast
+
+# Runs the integration suite inside the embed SAPI, failing when no
+# integration test was compiled in.
+cargo test -p tests --release --features embed --no-fail-fast -- integration:: | tee /tmp/integration.log
+if ! grep -q "^test integration::" /tmp/integration.log; then
+    echo "no integration test was run" >&2
+    exit 1
+fi
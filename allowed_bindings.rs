@@ -25,6 +25,7 @@ bind! {
     _call_user_function_impl,
     _efree,
     _emalloc,
+    _estrndup,
     _zend_executor_globals,
     _sapi_globals_struct,
     _sapi_module_struct,
@@ -37,6 +38,7 @@ bind! {
     _zend_expected_type_Z_EXPECTED_RESOURCE,
     _zend_expected_type_Z_EXPECTED_STRING,
     _zend_new_array,
+    _zend_hash_init,
     _zval_struct__bindgen_ty_1,
     _zval_struct__bindgen_ty_2,
     // ext_php_rs_executor_globals,
@@ -47,22 +49,61 @@ bind! {
     // ext_php_rs_zend_string_release,
     // ext_php_rs_is_known_valid_utf8,
     // ext_php_rs_set_known_valid_utf8,
+    object_init_ex,
     object_properties_init,
+    php_array_merge,
+    php_array_merge_recursive,
     php_error_docref,
     php_info_print_table_end,
+    php_json_decode_ex,
+    php_json_exception_ce,
+    php_json_encode_ex,
+    php_json_error_code,
+    php_json_error_code_PHP_JSON_ERROR_NONE,
+    php_json_error_code_PHP_JSON_ERROR_DEPTH,
+    php_json_error_code_PHP_JSON_ERROR_STATE_MISMATCH,
+    php_json_error_code_PHP_JSON_ERROR_CTRL_CHAR,
+    php_json_error_code_PHP_JSON_ERROR_SYNTAX,
+    php_json_error_code_PHP_JSON_ERROR_UTF8,
+    php_json_error_code_PHP_JSON_ERROR_RECURSION,
+    php_json_error_code_PHP_JSON_ERROR_INF_OR_NAN,
+    php_json_error_code_PHP_JSON_ERROR_UNSUPPORTED_TYPE,
+    php_json_error_code_PHP_JSON_ERROR_INVALID_PROPERTY_NAME,
+    php_json_error_code_PHP_JSON_ERROR_UTF16,
+    php_json_error_code_PHP_JSON_ERROR_NON_BACKED_ENUM,
+    php_log_err_with_severity,
     php_info_print_table_header,
     php_info_print_table_row,
     php_info_print_table_start,
+    start_memory_manager,
     std_object_handlers,
     zend_array_destroy,
+    zend_hash_destroy,
+    BP_VAR_R,
+    GC_PERSISTENT,
+    IS_STR_PERMANENT,
+    GC_IMMUTABLE,
+    GC_NOT_COLLECTABLE,
+    HASH_FLAG_PACKED,
+    HASH_FLAG_UNINITIALIZED,
+    zend_hash_real_init_packed,
+    zend_hash_extend,
     zend_array_dup,
     zend_call_known_function,
     zend_fetch_function_str,
     zend_hash_str_find_ptr_lc,
+    zend_attribute,
+    zend_attribute_arg,
+    zend_get_attribute_value,
+    zend_add_attribute,
+    ZEND_ATTRIBUTE_PERSISTENT,
+    zend_class_constant,
+    zend_property_info,
     zend_ce_argument_count_error,
     zend_ce_arithmetic_error,
     zend_ce_compile_error,
     zend_ce_division_by_zero_error,
+    zend_ce_error,
     zend_ce_error_exception,
     zend_ce_exception,
     zend_ce_parse_error,
@@ -79,11 +120,16 @@ bind! {
     zend_ce_stringable,
     zend_class_entry,
     zend_declare_class_constant,
+    zend_declare_class_constant_ex,
     zend_declare_property,
+    zend_declare_property_ex,
+    zend_declare_typed_property,
     zend_do_implement_interface,
     zend_execute_data,
     zend_function_entry,
     zend_hash_clean,
+    zend_hash_find,
+    zend_hash_func,
     zend_hash_index_del,
     zend_hash_index_find,
     zend_hash_index_update,
@@ -91,12 +137,17 @@ bind! {
     zend_hash_str_del,
     zend_hash_str_find,
     zend_hash_str_update,
+    zend_hash_update,
     zend_internal_arg_info,
     zend_is_callable,
     zend_is_identical,
     zend_is_iterable,
+    zend_iterator_init,
     zend_long,
     zend_lookup_class_ex,
+    zend_get_constant_str,
+    zend_get_class_constant_ex,
+    ZEND_FETCH_CLASS_SILENT,
     zend_module_entry,
     zend_object,
     zend_object_handlers,
@@ -107,11 +158,16 @@ bind! {
     zend_register_ini_entries,
     zend_ini_entry_def,
     zend_register_internal_class_ex,
+    zend_register_internal_enum,
+    zend_enum_add_case_cstr,
+    zend_enum_get_case_cstr,
     zend_register_long_constant,
     zend_register_string_constant,
     zend_resource,
     zend_string,
     zend_string_init_interned,
+    zend_string_tolower_ex,
+    zend_string_toupper_ex,
     zend_throw_exception_ex,
     zend_throw_exception_object,
     zend_type,
@@ -143,6 +199,11 @@ bind! {
     IS_CALLABLE,
     IS_CONSTANT_AST,
     IS_CONSTANT_AST_EX,
+    zend_ast,
+    zend_ast_ref,
+    zend_ast_zval,
+    _zend_ast_kind_ZEND_AST_CLASS_CONST,
+    _zend_ast_kind_ZEND_AST_ZVAL,
     IS_DOUBLE,
     IS_FALSE,
     IS_INDIRECT,
@@ -152,6 +213,7 @@ bind! {
     IS_NULL,
     IS_OBJECT,
     IS_OBJECT_EX,
+    IS_OBJ_DESTRUCTOR_CALLED,
     IS_REFERENCE,
     IS_REFERENCE_EX,
     IS_RESOURCE,
@@ -168,9 +230,34 @@ bind! {
     MAY_BE_ANY,
     MAY_BE_BOOL,
     PHP_INI_USER,
+    PHP_JSON_HEX_TAG,
+    PHP_JSON_HEX_AMP,
+    PHP_JSON_HEX_APOS,
+    PHP_JSON_HEX_QUOT,
+    PHP_JSON_FORCE_OBJECT,
+    PHP_JSON_NUMERIC_CHECK,
+    PHP_JSON_UNESCAPED_SLASHES,
+    PHP_JSON_PRETTY_PRINT,
+    PHP_JSON_UNESCAPED_UNICODE,
+    PHP_JSON_PARTIAL_OUTPUT_ON_ERROR,
+    PHP_JSON_PRESERVE_ZERO_FRACTION,
+    PHP_JSON_UNESCAPED_LINE_TERMINATORS,
+    PHP_JSON_OBJECT_AS_ARRAY,
+    PHP_JSON_BIGINT_AS_STRING,
+    PHP_JSON_INVALID_UTF8_IGNORE,
+    PHP_JSON_INVALID_UTF8_SUBSTITUTE,
+    PHP_JSON_THROW_ON_ERROR,
     PHP_INI_PERDIR,
     PHP_INI_SYSTEM,
     PHP_INI_ALL,
+    ZEND_INI_STAGE_STARTUP,
+    ZEND_INI_STAGE_SHUTDOWN,
+    ZEND_INI_STAGE_ACTIVATE,
+    ZEND_INI_STAGE_DEACTIVATE,
+    ZEND_INI_STAGE_RUNTIME,
+    ZEND_INI_STAGE_HTACCESS,
+    zend_alter_ini_entry_ex,
+    zend_restore_ini_entry,
     USING_ZTS,
     ZEND_ACC_ABSTRACT,
     ZEND_ACC_ANON_CLASS,
@@ -226,7 +313,9 @@ bind! {
     ZEND_PROPERTY_ISSET,
     Z_TYPE_FLAGS_SHIFT,
     _IS_BOOL,
+    _IS_NUMBER,
     _ZEND_IS_VARIADIC_BIT,
+    _ZEND_IS_PROMOTED_BIT,
     _ZEND_SEND_MODE_SHIFT,
     _ZEND_TYPE_NULLABLE_BIT,
     ts_rsrc_id,
@@ -234,15 +323,29 @@ bind! {
     _ZEND_TYPE_LITERAL_NAME_BIT,
     ZEND_INTERNAL_FUNCTION,
     ZEND_USER_FUNCTION,
+    ZEND_USER_CLASS,
     ZEND_EVAL_CODE,
     zval_ptr_dtor,
+    zval_try_get_string_func,
     zend_refcounted_h,
     zend_is_true,
     zend_object_std_dtor,
+    zend_get_property_info,
+    zend_read_static_property_ex,
+    zend_update_static_property_ex,
     zend_std_read_property,
     zend_std_write_property,
     zend_std_get_properties,
     zend_std_has_property,
+    zend_std_unset_property,
+    zend_std_get_property_ptr_ptr,
+    zend_std_cast_object_tostring,
+    zend_std_compare_objects,
+    ZEND_UNCOMPARABLE,
+    BP_VAR_IS,
+    BP_VAR_W,
+    BP_VAR_RW,
+    BP_VAR_UNSET,
     zend_objects_new,
     zend_standard_class_def,
     zend_class_serialize_deny,
@@ -257,6 +360,8 @@ bind! {
     zend_hash_move_backwards_ex,
     zend_array_count,
     gc_possible_root,
+    zend_get_gc_buffer_create,
+    zend_get_gc_buffer_grow,
     ZEND_ACC_NOT_SERIALIZABLE,
     executor_globals,
     php_core_globals,
@@ -282,7 +387,31 @@ bind! {
     TRACK_VARS_REQUEST,
     sapi_request_info,
     sapi_header_struct,
+    sapi_header_line,
+    sapi_header_op,
+    SAPI_HEADER_SENT_SUCCESSFULLY,
+    sapi_read_post_block,
+    _php_stream_read,
+    _php_stream_write,
+    _php_stream_seek,
+    _php_stream_tell,
+    _php_stream_flush,
+    _php_stream_free,
+    _php_stream_open_wrapper_ex,
+    php_file_le_stream,
+    php_file_le_pstream,
+    zend_fetch_resource2,
+    REPORT_ERRORS,
+    PHP_STREAM_FREE_CLOSE,
+    SEEK_SET,
+    SEEK_CUR,
+    SEEK_END,
     zend_is_auto_global,
+    zend_register_auto_global,
+    smart_str,
+    smart_str_erealloc,
+    smart_str_realloc,
+    zend_auto_global_callback,
     zend_llist_get_next_ex,
     zend_llist_get_prev_ex,
     php_register_url_stream_wrapper,
@@ -294,6 +423,30 @@ bind! {
     php_stream_stdio_ops,
     zend_atomic_bool_store,
     zend_interrupt_function,
+    zend_observer_fcall_register,
+    zend_set_user_opcode_handler,
+    zend_get_user_opcode_handler,
+    ZEND_INIT_FCALL_BY_NAME,
+    ZEND_DO_FCALL,
+    ZEND_INIT_FCALL,
+    ZEND_NEW,
+    ZEND_INIT_NS_FCALL_BY_NAME,
+    ZEND_INCLUDE_OR_EVAL,
+    ZEND_EXIT,
+    ZEND_INIT_METHOD_CALL,
+    ZEND_INIT_STATIC_METHOD_CALL,
+    ZEND_INIT_USER_CALL,
+    ZEND_INIT_DYNAMIC_CALL,
+    ZEND_DO_ICALL,
+    ZEND_DO_UCALL,
+    ZEND_DO_FCALL_BY_NAME,
+    ZEND_ECHO,
+    ZEND_USER_OPCODE_CONTINUE,
+    ZEND_USER_OPCODE_RETURN,
+    ZEND_USER_OPCODE_DISPATCH,
+    ZEND_USER_OPCODE_ENTER,
+    ZEND_USER_OPCODE_LEAVE,
+    ZEND_USER_OPCODE_DISPATCH_TO,
     zend_eval_string,
     zend_file_handle,
     zend_stream_init_filename,
@@ -0,0 +1,68 @@
+//! Measures the cost of calling Rust functions from PHP. Functions which only
+//! take required scalar and string parameters are parsed directly from the
+//! call frame, while the others go through the generic argument parser. The
+//! built-in `intdiv()` function is used as a baseline for a C extension.
+//!
+//! Run with `cargo bench --features embed --bench arguments`.
+
+#![cfg_attr(windows, feature(abi_vectorcall))]
+extern crate ext_php_rs;
+
+mod common;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use ext_php_rs::embed::Embed;
+use ext_php_rs::prelude::*;
+
+#[php_function]
+pub fn bench_scalar(a: i64, b: f64, c: bool, d: &str) -> i64 {
+    a + b as i64 + c as i64 + d.len() as i64
+}
+
+#[php_function]
+pub fn bench_generic(a: i64, b: f64, c: bool, d: &str, e: Option<i64>) -> i64 {
+    a + b as i64 + c as i64 + d.len() as i64 + e.unwrap_or_default()
+}
+
+#[php_function]
+pub fn bench_intdiv(a: i64, b: i64) -> i64 {
+    a / b
+}
+
+#[php_module]
+pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+    module
+}
+
+fn bench_arguments(c: &mut Criterion) {
+    common::with_request(get_module(), || {
+        c.bench_function("scalar arguments", |b| {
+            b.iter(|| {
+                Embed::eval("for ($i = 0; $i < 1000; $i++) { bench_scalar($i, 1.5, true, 'abc'); }")
+                    .unwrap()
+            })
+        });
+
+        c.bench_function("generic arguments", |b| {
+            b.iter(|| {
+                Embed::eval(
+                    "for ($i = 0; $i < 1000; $i++) { bench_generic($i, 1.5, true, 'abc', null); }",
+                )
+                .unwrap()
+            })
+        });
+
+        c.bench_function("rust intdiv", |b| {
+            b.iter(|| {
+                Embed::eval("for ($i = 0; $i < 1000; $i++) { bench_intdiv($i, 3); }").unwrap()
+            })
+        });
+
+        c.bench_function("builtin intdiv", |b| {
+            b.iter(|| Embed::eval("for ($i = 0; $i < 1000; $i++) { intdiv($i, 3); }").unwrap())
+        });
+    });
+}
+
+criterion_group!(benches, bench_arguments);
+criterion_main!(benches);
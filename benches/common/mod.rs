@@ -0,0 +1,50 @@
+//! Shared setup for the benchmarks, which run PHP scripts through a minimal
+//! SAPI that discards all output.
+
+use ext_php_rs::builders::SapiBuilder;
+use ext_php_rs::embed::ext_php_rs_sapi_startup;
+use ext_php_rs::ffi::{
+    php_module_shutdown, php_module_startup, php_request_shutdown, php_request_startup,
+    sapi_shutdown, sapi_startup, ZEND_RESULT_CODE_SUCCESS,
+};
+use ext_php_rs::zend::{try_catch_first, ModuleEntry};
+use std::ffi::c_char;
+use std::panic::AssertUnwindSafe;
+
+extern "C" fn discard_output(_str: *const c_char, str_length: usize) -> usize {
+    str_length
+}
+
+/// Starts PHP with the given module loaded, and calls `f` inside a request.
+pub fn with_request(module: *mut ModuleEntry, f: impl FnOnce()) {
+    let sapi = SapiBuilder::new("bench", "Bench")
+        .ub_write_function(discard_output)
+        .build()
+        .unwrap()
+        .into_raw();
+
+    unsafe {
+        ext_php_rs_sapi_startup();
+        sapi_startup(sapi);
+        php_module_startup(sapi, module);
+    }
+
+    assert_eq!(unsafe { php_request_startup() }, ZEND_RESULT_CODE_SUCCESS);
+
+    // The benchmark closures borrow the `Criterion` instance mutably, which is not
+    // unwind safe. A bailout aborts the benchmark, so this is not observable.
+    let mut f = Some(f);
+    let mut run = || {
+        if let Some(f) = f.take() {
+            f()
+        }
+    };
+    let run = AssertUnwindSafe(&mut run);
+    let _ = try_catch_first(move || (run.0)());
+
+    unsafe {
+        php_request_shutdown(std::ptr::null_mut());
+        php_module_shutdown();
+        sapi_shutdown();
+    }
+}
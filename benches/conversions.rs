@@ -0,0 +1,143 @@
+//! Measures the cost of converting values between Rust and PHP. Most of the
+//! benchmarks run without any PHP code, while the calls from PHP compare Rust
+//! functions converting their arguments and return values against built-in C
+//! functions doing the same work.
+//!
+//! Run with `cargo bench --features embed --bench conversions`.
+
+#![cfg_attr(windows, feature(abi_vectorcall))]
+extern crate ext_php_rs;
+
+mod common;
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ext_php_rs::boxed::ZBox;
+use ext_php_rs::convert::{FromZval, IntoZval};
+use ext_php_rs::embed::Embed;
+use ext_php_rs::prelude::*;
+use ext_php_rs::types::{ZendHashTable, Zval};
+
+#[php_function]
+pub fn bench_values(values: Vec<i64>) -> Vec<i64> {
+    values
+}
+
+#[php_function]
+pub fn bench_trim(value: String) -> String {
+    value.trim().to_owned()
+}
+
+#[php_module]
+pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+    module
+}
+
+fn bench_scalars(c: &mut Criterion) {
+    c.bench_function("i64 round-trip", |b| {
+        b.iter(|| {
+            let zv = black_box(42i64).into_zval(false).unwrap();
+            i64::from_zval(&zv).unwrap()
+        })
+    });
+
+    c.bench_function("f64 round-trip", |b| {
+        b.iter(|| {
+            let zv = black_box(1.5f64).into_zval(false).unwrap();
+            f64::from_zval(&zv).unwrap()
+        })
+    });
+
+    let text = "The quick brown fox jumps over the lazy dog. ".repeat(16);
+    c.bench_function("String into zval", |b| {
+        b.iter(|| black_box(text.as_str()).into_zval(false).unwrap())
+    });
+
+    let zv = text.as_str().into_zval(false).unwrap();
+    c.bench_function("&str from zval", |b| {
+        b.iter(|| <&str>::from_zval(black_box(&zv)).unwrap().len())
+    });
+
+    c.bench_function("&str from new zval", |b| {
+        b.iter(|| {
+            // Strings created from bytes have not been validated as UTF-8 yet.
+            let mut zv = Zval::new();
+            zv.set_bytes(text.as_bytes(), false);
+            <&str>::from_zval(&zv).unwrap().len()
+        })
+    });
+}
+
+fn bench_hashtables(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Vec<i64> round-trip");
+    for size in [16usize, 1024, 65536] {
+        let vec: Vec<i64> = (0..size as i64).collect();
+        group.bench_with_input(BenchmarkId::from_parameter(size), &vec, |b, vec| {
+            b.iter(|| {
+                let ht = ZBox::<ZendHashTable>::try_from(vec.clone()).unwrap();
+                Vec::<i64>::try_from(&*ht).unwrap()
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("HashMap<String, String> round-trip");
+    for size in [16usize, 1024] {
+        let map: HashMap<String, String> = (0..size)
+            .map(|i| (format!("key{}", i), format!("value{}", i)))
+            .collect();
+        group.bench_with_input(BenchmarkId::from_parameter(size), &map, |b, map| {
+            b.iter(|| {
+                let zv = map.clone().into_zval(false).unwrap();
+                HashMap::<String, String>::from_zval(&zv).unwrap()
+            })
+        });
+    }
+    group.finish();
+
+    let mut ht = ZendHashTable::new();
+    for i in 0..1024 {
+        ht.insert(&format!("key{}", i), i).unwrap();
+    }
+    c.bench_function("hashtable string lookup", |b| {
+        b.iter(|| ht.get(black_box("key512")).and_then(Zval::long))
+    });
+    c.bench_function("hashtable iteration", |b| {
+        b.iter(|| ht.values().filter_map(Zval::long).sum::<i64>())
+    });
+}
+
+fn bench_calls(c: &mut Criterion) {
+    Embed::eval("$list = range(0, 1023); $text = '  ' . str_repeat('abc', 256) . '  ';").unwrap();
+
+    c.bench_function("rust array argument and return value", |b| {
+        b.iter(|| Embed::eval("for ($i = 0; $i < 100; $i++) { bench_values($list); }").unwrap())
+    });
+
+    c.bench_function("builtin array_values", |b| {
+        b.iter(|| Embed::eval("for ($i = 0; $i < 100; $i++) { array_values($list); }").unwrap())
+    });
+
+    c.bench_function("rust string argument and return value", |b| {
+        b.iter(|| Embed::eval("for ($i = 0; $i < 1000; $i++) { bench_trim($text); }").unwrap())
+    });
+
+    c.bench_function("builtin trim", |b| {
+        b.iter(|| Embed::eval("for ($i = 0; $i < 1000; $i++) { trim($text); }").unwrap())
+    });
+}
+
+fn bench_conversions(c: &mut Criterion) {
+    // Zend strings and arrays are allocated with the request allocator.
+    common::with_request(get_module(), || {
+        bench_scalars(c);
+        bench_hashtables(c);
+        bench_calls(c);
+    });
+}
+
+criterion_group!(benches, bench_conversions);
+criterion_main!(benches);
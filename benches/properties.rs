@@ -0,0 +1,96 @@
+//! Measures the cost of reading and writing properties backed by Rust struct
+//! fields, which go through the class property table on every access. The
+//! properties of the built-in `DateInterval` class, which are read through a C
+//! handler, are used as a baseline for a C extension.
+//!
+//! Run with `cargo bench --features embed --bench properties`.
+
+#![cfg_attr(windows, feature(abi_vectorcall))]
+extern crate ext_php_rs;
+
+mod common;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use ext_php_rs::embed::Embed;
+use ext_php_rs::prelude::*;
+
+#[php_class]
+pub struct BenchProperties {
+    #[prop]
+    a: i64,
+    #[prop]
+    b: i64,
+    #[prop]
+    c: String,
+    #[prop]
+    d: String,
+    #[prop]
+    e: f64,
+    #[prop]
+    f: bool,
+}
+
+#[php_impl]
+impl BenchProperties {
+    pub fn __construct() -> Self {
+        Self {
+            a: 0,
+            b: 1,
+            c: String::new(),
+            d: "d".into(),
+            e: 0.0,
+            f: false,
+        }
+    }
+}
+
+#[php_module]
+pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+    module
+}
+
+fn bench_properties(c: &mut Criterion) {
+    common::with_request(get_module(), || {
+        Embed::eval("$obj = new BenchProperties();").unwrap();
+
+        c.bench_function("read rust properties", |b| {
+            b.iter(|| {
+                Embed::eval("for ($i = 0; $i < 1000; $i++) { $obj->a; $obj->d; $obj->f; }").unwrap()
+            })
+        });
+
+        c.bench_function("write rust properties", |b| {
+            b.iter(|| {
+                Embed::eval("for ($i = 0; $i < 1000; $i++) { $obj->a = $i; $obj->e = 1.5; }")
+                    .unwrap()
+            })
+        });
+
+        c.bench_function("isset rust properties", |b| {
+            b.iter(|| {
+                Embed::eval("for ($i = 0; $i < 1000; $i++) { isset($obj->b, $obj->c); }").unwrap()
+            })
+        });
+
+        c.bench_function("read builtin properties", |b| {
+            Embed::eval("$interval = new DateInterval('P1Y2M3D');").unwrap();
+            b.iter(|| {
+                Embed::eval(
+                    "for ($i = 0; $i < 1000; $i++) { $interval->y; $interval->m; $interval->d; }",
+                )
+                .unwrap()
+            })
+        });
+
+        c.bench_function("read dynamic properties", |b| {
+            Embed::eval("$std = new stdClass(); $std->a = 0; $std->d = 'd'; $std->f = false;")
+                .unwrap();
+            b.iter(|| {
+                Embed::eval("for ($i = 0; $i < 1000; $i++) { $std->a; $std->d; $std->f; }").unwrap()
+            })
+        });
+    });
+}
+
+criterion_group!(benches, bench_properties);
+criterion_main!(benches);
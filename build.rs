@@ -143,6 +143,8 @@ fn build_wrapper(defines: &[(&str, &str)], includes: &[PathBuf]) -> Result<()> {
     for (var, val) in defines {
         build.define(var, *val);
     }
+    #[cfg(feature = "coverage")]
+    coverage_flags(&mut build);
     build
         .file("src/wrapper.c")
         .includes(includes)
@@ -158,6 +160,8 @@ fn build_embed(defines: &[(&str, &str)], includes: &[PathBuf]) -> Result<()> {
     for (var, val) in defines {
         build.define(var, *val);
     }
+    #[cfg(feature = "coverage")]
+    coverage_flags(&mut build);
     build
         .file("src/embed/embed.c")
         .includes(includes)
@@ -166,6 +170,27 @@ fn build_embed(defines: &[(&str, &str)], includes: &[PathBuf]) -> Result<()> {
     Ok(())
 }
 
+#[cfg(feature = "coverage")]
+/// Keeps the frame pointers of the C interface and disables inlining, so
+/// coverage tools and debuggers attribute the lines of mixed PHP and Rust
+/// stacks to the right functions.
+fn coverage_flags(build: &mut cc::Build) {
+    build
+        .flag_if_supported("-fno-omit-frame-pointer")
+        .flag_if_supported("-fno-inline");
+}
+
+#[cfg(feature = "coverage")]
+/// Warns when the Rust code of the crate is compiled without frame pointers,
+/// which can only be enabled through `RUSTFLAGS`.
+fn check_frame_pointers() {
+    println!("cargo:rerun-if-env-changed=CARGO_ENCODED_RUSTFLAGS");
+    let rustflags = env::var("CARGO_ENCODED_RUSTFLAGS").unwrap_or_default();
+    if !rustflags.contains("force-frame-pointers") {
+        println!("cargo:warning=The `coverage` feature is enabled without frame pointers. Set `RUSTFLAGS=\"-C force-frame-pointers=yes\"` to keep them in the Rust code as well.");
+    }
+}
+
 /// Generates bindings to the Zend API.
 fn generate_bindings(defines: &[(&str, &str)], includes: &[PathBuf]) -> Result<String> {
     let mut bindgen = bindgen::Builder::default();
@@ -299,6 +324,8 @@ fn main() -> Result<()> {
     let defines = provider.get_defines()?;
 
     check_php_version(&info)?;
+    #[cfg(feature = "coverage")]
+    check_frame_pointers();
     build_wrapper(&defines, &includes)?;
 
     #[cfg(feature = "embed")]
@@ -326,6 +353,41 @@ fn main() -> Result<()> {
         .into_iter()
         .filter(|p| p.file_stem() != Some(std::ffi::OsStr::new("closure")))
         .collect();
+    #[cfg(not(feature = "serde"))]
+    let test_md: Vec<_> = test_md
+        .into_iter()
+        .filter(|p| p.file_stem() != Some(std::ffi::OsStr::new("serde")))
+        .collect();
+    #[cfg(not(feature = "http"))]
+    let test_md: Vec<_> = test_md
+        .into_iter()
+        .filter(|p| p.file_stem() != Some(std::ffi::OsStr::new("http")))
+        .collect();
+    #[cfg(not(all(feature = "log", feature = "tracing")))]
+    let test_md: Vec<_> = test_md
+        .into_iter()
+        .filter(|p| p.file_stem() != Some(std::ffi::OsStr::new("logging")))
+        .collect();
+    #[cfg(not(feature = "psr3"))]
+    let test_md: Vec<_> = test_md
+        .into_iter()
+        .filter(|p| p.file_stem() != Some(std::ffi::OsStr::new("psr3")))
+        .collect();
+    #[cfg(not(feature = "session"))]
+    let test_md: Vec<_> = test_md
+        .into_iter()
+        .filter(|p| p.file_stem() != Some(std::ffi::OsStr::new("session")))
+        .collect();
+    #[cfg(not(feature = "embed"))]
+    let test_md: Vec<_> = test_md
+        .into_iter()
+        .filter(|p| p.file_stem() != Some(std::ffi::OsStr::new("test")))
+        .collect();
+    #[cfg(not(feature = "alloc-tracking"))]
+    let test_md: Vec<_> = test_md
+        .into_iter()
+        .filter(|p| p.file_stem() != Some(std::ffi::OsStr::new("alloc-tracking")))
+        .collect();
     skeptic::generate_doc_tests(&test_md);
 
     Ok(())
@@ -2,6 +2,8 @@
 
 #[cfg(not(windows))]
 mod ext;
+#[cfg(not(windows))]
+mod phpt;
 
 use anyhow::{bail, Context, Result as AResult};
 use cargo_metadata::{camino::Utf8PathBuf, Target};
@@ -83,6 +85,14 @@ enum Args {
     /// extension classes, functions and constants.
     #[cfg(not(windows))]
     Stubs(Stubs),
+    /// Runs the tests of the extension.
+    ///
+    /// By default, this runs `cargo test`. With `--phpt`, the extension is
+    /// built and the `.phpt` tests in the test directory are run with the
+    /// extension loaded, using the `run-tests.php` script installed alongside
+    /// PHP.
+    #[cfg(not(windows))]
+    Test(Test),
 }
 
 #[derive(Parser)]
@@ -152,6 +162,33 @@ struct Stubs {
     manifest: Option<PathBuf>,
 }
 
+#[cfg(not(windows))]
+#[derive(Parser)]
+struct Test {
+    /// Runs the `.phpt` tests of the extension rather than `cargo test`.
+    #[arg(long)]
+    phpt: bool,
+    /// Generates a `.phpt` test skeleton for each exported function and class
+    /// which does not have a test yet, rather than running the tests.
+    #[arg(long, requires = "phpt")]
+    generate: bool,
+    /// Directory containing the `.phpt` tests. Defaults to `tests` in the
+    /// current directory.
+    #[arg(long, default_value = "tests")]
+    dir: PathBuf,
+    /// Path to the `run-tests.php` script. Defaults to the script installed
+    /// alongside PHP.
+    #[arg(long)]
+    run_tests: Option<PathBuf>,
+    /// Whether to test the release version of the extension.
+    #[arg(long)]
+    release: bool,
+    /// Path to the Cargo manifest of the extension. Defaults to the manifest in
+    /// the directory the command is called.
+    #[arg(long)]
+    manifest: Option<PathBuf>,
+}
+
 impl Args {
     pub fn handle(self) -> CrateResult {
         match self {
@@ -159,6 +196,8 @@ impl Args {
             Args::Remove(remove) => remove.handle(),
             #[cfg(not(windows))]
             Args::Stubs(stubs) => stubs.handle(),
+            #[cfg(not(windows))]
+            Args::Test(test) => test.handle(),
         }
     }
 }
@@ -408,6 +447,46 @@ impl Stubs {
     }
 }
 
+#[cfg(not(windows))]
+impl Test {
+    pub fn handle(self) -> CrateResult {
+        if !self.phpt {
+            let mut cmd = Command::new("cargo");
+            cmd.arg("test");
+            if let Some(manifest) = &self.manifest {
+                cmd.arg("--manifest-path").arg(manifest);
+            }
+            if self.release {
+                cmd.arg("--release");
+            }
+
+            let status = cmd
+                .status()
+                .with_context(|| "Failed to call `cargo test`")?;
+            if !status.success() {
+                bail!("Tests failed.");
+            }
+            return Ok(());
+        }
+
+        let target = find_ext(&self.manifest)?;
+        // Skeletons are generated from the description of the extension, which
+        // is only generated for debug builds.
+        let ext_path = build_ext(&target, self.release && !self.generate)?;
+
+        if self.generate {
+            let ext = self::ext::Ext::load(ext_path.into())?;
+            let skeletons = self::phpt::skeletons(&ext.describe().module)?;
+            for path in self::phpt::write_skeletons(&self.dir, skeletons)? {
+                println!("Created {}", path.display());
+            }
+            return Ok(());
+        }
+
+        self::phpt::run(ext_path.as_std_path(), &self.dir, self.run_tests)
+    }
+}
+
 /// Attempts to find an extension in the target directory.
 fn find_ext(manifest: &Option<PathBuf>) -> AResult<cargo_metadata::Target> {
     // TODO(david): Look for cargo manifest option or env
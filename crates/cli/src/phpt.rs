@@ -0,0 +1,372 @@
+//! Generates `.phpt` test skeletons and runs `.phpt` suites with PHP's
+//! `run-tests.php`.
+
+use std::{
+    fmt::Write as _,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{bail, Context, Result};
+use ext_php_rs::describe::{Class, Enum, Function, MethodType, Module, ToStub};
+
+/// A `.phpt` test skeleton.
+pub struct Skeleton {
+    /// The file name of the test, e.g. `hello_world.phpt`.
+    pub file_name: String,
+    /// The contents of the test.
+    pub contents: String,
+}
+
+/// Generates a test skeleton for each function, class and enum exported by an
+/// extension.
+///
+/// # Parameters
+///
+/// * `module` - The description of the extension.
+pub fn skeletons(module: &Module) -> Result<Vec<Skeleton>> {
+    let functions = module
+        .functions
+        .iter()
+        .map(|func| function_skeleton(module, func));
+    let classes = module
+        .classes
+        .iter()
+        .map(|class| class_skeleton(module, class));
+    let enums = module
+        .enums
+        .iter()
+        .map(|enum_| enum_skeleton(module, enum_));
+
+    functions.chain(classes).chain(enums).collect()
+}
+
+/// Generates the test skeleton of a function, calling it with its parameters
+/// left to be filled in.
+fn function_skeleton(module: &Module, func: &Function) -> Result<Skeleton> {
+    let name = func.name.as_ref();
+    let mut code = String::new();
+    writeln!(code, "var_dump(\\{}({}));", name, params(&func.params)?)?;
+
+    skeleton(module, name, &format!("{name}()"), &code)
+}
+
+/// Generates the test skeleton of a class, creating an instance of it when it
+/// has a constructor.
+fn class_skeleton(module: &Module, class: &Class) -> Result<Skeleton> {
+    let name = class.name.as_ref();
+    let mut code = String::new();
+    match class
+        .methods
+        .iter()
+        .find(|method| matches!(method.ty, MethodType::Constructor))
+    {
+        Some(constructor) => writeln!(
+            code,
+            "$obj = new \\{}({});\nvar_dump($obj);",
+            name,
+            params(&constructor.params)?
+        )?,
+        None => writeln!(code, "var_dump(class_exists(\\{name}::class));")?,
+    }
+
+    skeleton(module, name, &format!("class {name}"), &code)
+}
+
+/// Generates the test skeleton of an enum, dumping its cases.
+fn enum_skeleton(module: &Module, enum_: &Enum) -> Result<Skeleton> {
+    let name = enum_.name.as_ref();
+    let code = format!("var_dump(\\{name}::cases());\n");
+
+    skeleton(module, name, &format!("enum {name}"), &code)
+}
+
+/// Renders parameters as comments, to be replaced by the arguments of the
+/// call.
+fn params(params: &[ext_php_rs::describe::Parameter]) -> Result<String> {
+    Ok(params
+        .iter()
+        .map(|param| Ok(format!("/* {} */", param.to_stub()?)))
+        .collect::<Result<Vec<_>>>()?
+        .join(", "))
+}
+
+/// Builds a test skeleton, skipped when the extension is not loaded.
+fn skeleton(module: &Module, name: &str, title: &str, code: &str) -> Result<Skeleton> {
+    let ext_name = module.name.as_ref();
+    let mut contents = String::new();
+    writeln!(contents, "--TEST--")?;
+    writeln!(contents, "{title}")?;
+    writeln!(contents, "--SKIPIF--")?;
+    writeln!(
+        contents,
+        "<?php if (!extension_loaded('{ext_name}')) die('skip {ext_name} extension not loaded'); ?>"
+    )?;
+    writeln!(contents, "--FILE--")?;
+    writeln!(contents, "<?php")?;
+    write!(contents, "{code}")?;
+    writeln!(contents, "?>")?;
+    writeln!(contents, "--EXPECT--")?;
+
+    Ok(Skeleton {
+        file_name: format!("{}.phpt", name.replace('\\', "_").to_lowercase()),
+        contents,
+    })
+}
+
+/// Writes the given skeletons to a directory, skipping tests which already
+/// exist. Returns the paths of the created tests.
+///
+/// # Parameters
+///
+/// * `dir` - The directory to write the tests to.
+/// * `skeletons` - The test skeletons.
+pub fn write_skeletons(dir: &Path, skeletons: Vec<Skeleton>) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create test directory at {dir:?}"))?;
+
+    let mut created = vec![];
+    for skeleton in skeletons {
+        let path = dir.join(&skeleton.file_name);
+        if path.exists() {
+            continue;
+        }
+
+        std::fs::write(&path, skeleton.contents)
+            .with_context(|| format!("Failed to write test to {path:?}"))?;
+        created.push(path);
+    }
+
+    Ok(created)
+}
+
+/// Runs the `.phpt` tests in a directory with the extension loaded, using the
+/// `run-tests.php` script shipped with PHP.
+///
+/// # Parameters
+///
+/// * `ext_path` - The path to the extension library.
+/// * `dir` - The directory containing the tests.
+/// * `run_tests` - The path to `run-tests.php`. Defaults to the script
+///   installed alongside PHP.
+pub fn run(ext_path: &Path, dir: &Path, run_tests: Option<PathBuf>) -> Result<()> {
+    let run_tests = match run_tests {
+        Some(run_tests) => run_tests,
+        None => find_run_tests()?,
+    };
+    if !run_tests.is_file() {
+        bail!("`run-tests.php` does not exist at the given path: {run_tests:?}");
+    }
+
+    let php = php_binary()?;
+    let status = Command::new(&php)
+        .arg(&run_tests)
+        .arg("-q")
+        .arg("--show-diff")
+        .arg("-d")
+        .arg(format!("extension={}", ext_path.display()))
+        .arg(dir)
+        // `run-tests.php` reads the PHP binary to test from the environment.
+        .env("TEST_PHP_EXECUTABLE", &php)
+        .status()
+        .context("Failed to call `run-tests.php`")?;
+
+    if !status.success() {
+        bail!("Some `.phpt` tests failed.");
+    }
+
+    Ok(())
+}
+
+/// Returns the path to `run-tests.php` installed alongside PHP.
+fn find_run_tests() -> Result<PathBuf> {
+    let php_config = std::env::var("PHP_CONFIG").unwrap_or_else(|_| "php-config".to_string());
+    let cmd = Command::new(&php_config)
+        .arg("--prefix")
+        .output()
+        .with_context(|| format!("Failed to call `{php_config}`"))?;
+    if !cmd.status.success() {
+        bail!("Failed to call `{}`: {:?}", php_config, cmd);
+    }
+
+    let prefix = String::from_utf8_lossy(&cmd.stdout);
+    let path: PathBuf = [prefix.trim(), "lib", "php", "build", "run-tests.php"]
+        .iter()
+        .collect();
+    if !path.is_file() {
+        bail!(
+            "Unable to find `run-tests.php` at {:?}, pass the path to the script with `--run-tests`.",
+            path
+        );
+    }
+    Ok(path)
+}
+
+/// Returns the path to the PHP binary.
+fn php_binary() -> Result<PathBuf> {
+    let cmd = Command::new("php")
+        .arg("-r")
+        .arg("echo PHP_BINARY;")
+        .output()
+        .context("Failed to call PHP")?;
+    if !cmd.status.success() {
+        bail!("Failed to call PHP: {:?}", cmd);
+    }
+    Ok(PathBuf::from(&*String::from_utf8_lossy(&cmd.stdout)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ext_php_rs::{
+        describe::{DocBlock, EnumCase, Method, Parameter, Visibility},
+        flags::DataType,
+    };
+
+    fn class(name: &'static str, methods: Vec<Method>) -> Class {
+        Class {
+            name: name.into(),
+            docs: DocBlock(vec![].into()),
+            extends: None.into(),
+            implements: vec![].into(),
+            uses: vec![].into(),
+            properties: vec![].into(),
+            methods: methods.into(),
+            constants: vec![].into(),
+        }
+    }
+
+    fn module() -> Module {
+        let param = |name: &'static str, ty| Parameter {
+            name: name.into(),
+            ty: Some(ty).into(),
+            nullable: false,
+            default: None.into(),
+        };
+        let constructor = Method {
+            name: "__construct".into(),
+            docs: DocBlock(vec![].into()),
+            ty: MethodType::Constructor,
+            params: vec![param("name", DataType::String)].into(),
+            retval: None.into(),
+            _static: false,
+            visibility: Visibility::Public,
+        };
+
+        Module {
+            name: "my_ext".into(),
+            functions: vec![Function {
+                name: "My\\hello_world".into(),
+                docs: DocBlock(vec![].into()),
+                ret: None.into(),
+                params: vec![
+                    param("name", DataType::String),
+                    param("times", DataType::Long),
+                ]
+                .into(),
+            }]
+            .into(),
+            classes: vec![
+                class("Greeter", vec![constructor]),
+                class("My\\Helper", vec![]),
+            ]
+            .into(),
+            enums: vec![Enum {
+                name: "Suit".into(),
+                docs: DocBlock(vec![].into()),
+                backing: None.into(),
+                implements: vec![].into(),
+                cases: vec![EnumCase {
+                    name: "Hearts".into(),
+                    docs: DocBlock(vec![].into()),
+                    value: None.into(),
+                    attributes: vec![].into(),
+                }]
+                .into(),
+                methods: vec![].into(),
+                constants: vec![].into(),
+            }]
+            .into(),
+            constants: vec![].into(),
+        }
+    }
+
+    /// Returns the expected contents of a skeleton.
+    fn expected(title: &str, code: &str) -> String {
+        format!(
+            "--TEST--\n{title}\n--SKIPIF--\n<?php if (!extension_loaded('my_ext')) \
+             die('skip my_ext extension not loaded'); ?>\n--FILE--\n<?php\n{code}?>\n--EXPECT--\n"
+        )
+    }
+
+    #[test]
+    fn test_skeletons() {
+        let skeletons = skeletons(&module()).unwrap();
+        let files: Vec<_> = skeletons
+            .iter()
+            .map(|skeleton| skeleton.file_name.as_str())
+            .collect();
+        assert_eq!(
+            files,
+            [
+                "my_hello_world.phpt",
+                "greeter.phpt",
+                "my_helper.phpt",
+                "suit.phpt"
+            ]
+        );
+
+        assert_eq!(
+            skeletons[0].contents,
+            expected(
+                "My\\hello_world()",
+                "var_dump(\\My\\hello_world(/* string $name */, /* int $times */));\n"
+            )
+        );
+        assert_eq!(
+            skeletons[1].contents,
+            expected(
+                "class Greeter",
+                "$obj = new \\Greeter(/* string $name */);\nvar_dump($obj);\n"
+            )
+        );
+        assert_eq!(
+            skeletons[2].contents,
+            expected(
+                "class My\\Helper",
+                "var_dump(class_exists(\\My\\Helper::class));\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_enum_skeleton() {
+        let module = module();
+        let skeleton = enum_skeleton(&module, &module.enums[0]).unwrap();
+
+        assert_eq!(skeleton.file_name, "suit.phpt");
+        assert_eq!(
+            skeleton.contents,
+            expected("enum Suit", "var_dump(\\Suit::cases());\n")
+        );
+    }
+
+    #[test]
+    fn test_write_skeletons() {
+        let dir = std::env::temp_dir().join(format!("cargo-php-phpt-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let created = write_skeletons(&dir, skeletons(&module()).unwrap()).unwrap();
+        assert_eq!(created.len(), 4);
+        assert!(created.iter().all(|path| path.is_file()));
+
+        // Existing tests are left untouched.
+        let path = dir.join("suit.phpt");
+        std::fs::write(&path, "edited").unwrap();
+        let created = write_skeletons(&dir, skeletons(&module()).unwrap()).unwrap();
+        assert!(created.is_empty());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "edited");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,35 +1,96 @@
 use std::collections::HashMap;
 
+use crate::impl_::Visibility;
 use crate::STATE;
 use anyhow::{anyhow, bail, Context, Result};
 use darling::{FromMeta, ToTokens};
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 use syn::parse::ParseStream;
-use syn::{Attribute, AttributeArgs, Expr, Fields, FieldsNamed, ItemStruct, LitStr, Token};
+use syn::{Attribute, AttributeArgs, Expr, ItemStruct, LitStr, Token};
 
 #[derive(Debug, Default)]
 pub struct Class {
     pub class_name: String,
     pub struct_path: String,
     pub parent: Option<String>,
+    /// The path of the Rust class extended with `#[extends_class]`.
+    pub parent_class: Option<String>,
     pub interfaces: Vec<String>,
+    /// Traits used with `#[use_trait]`.
+    pub traits: Vec<UseTraitAttr>,
     pub docs: Vec<String>,
     pub methods: Vec<crate::method::Method>,
     pub constructor: Option<crate::method::Method>,
     pub constants: Vec<crate::constant::Constant>,
     pub properties: HashMap<String, Property>,
+    /// Fields whose properties are flattened into the class.
+    pub flattened: Vec<FlattenedField>,
     /// A function name called when creating the class entry. Given an instance
     /// of `ClassBuilder` and must return it.
     pub modifier: Option<String>,
     pub flags: Option<String>,
+    /// Whether the struct implements `CollectGarbage` itself.
+    pub gc: bool,
+    /// Fields which are passed to the cycle collector.
+    pub gc_fields: Vec<String>,
+    /// The field calls are forwarded to, annotated with `#[delegate]`.
+    pub delegate: Option<String>,
+    /// Whether the struct implements `PhpDebugInfo`.
+    pub debug_info: bool,
+    /// Whether the debug info is built from the `Debug` implementation.
+    pub debug: bool,
+    /// Whether the struct implements `PhpCast`.
+    pub cast: bool,
+    /// Whether the struct implements `PhpCompare`.
+    pub compare: bool,
+    /// Whether the struct implements `PhpArrayAccess`.
+    pub array_access: bool,
+    /// Whether the struct implements `PhpPropertyObserver`.
+    pub observe: bool,
+    /// Whether the struct implements `PhpNativeIterator`.
+    pub iterator: bool,
+}
+
+/// A field annotated with `#[prop(flatten)]`.
+#[derive(Debug)]
+pub struct FlattenedField {
+    pub field_name: String,
+    pub ty: String,
+    pub prefix: String,
+}
+
+impl FlattenedField {
+    /// Returns the type of the field.
+    pub fn ty(&self) -> syn::Type {
+        syn::parse_str(&self.ty).expect("failed to parse previously parsed field type")
+    }
+
+    /// Generates the expression returning the flattened properties of the field.
+    pub fn as_props_expr(&self) -> TokenStream {
+        let field = field_member(&self.field_name);
+        let ty = self.ty();
+        let prefix = &self.prefix;
+
+        quote! {
+            ::ext_php_rs::props::Property::flatten::<#ty>(
+                #prefix,
+                |this: &Self| &this.#field,
+                |this: &mut Self| &mut this.#field,
+            )
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum ParsedAttribute {
     Extends(Expr),
+    ExtendsClass(syn::Path),
     Implements(Expr),
+    UseTrait(UseTraitAttr),
     Property(PropertyAttr),
+    Gc,
+    Delegate,
     Comment(String),
 }
 
@@ -39,6 +100,14 @@ pub struct AttrArgs {
     name: Option<String>,
     modifier: Option<String>,
     flags: Option<Expr>,
+    gc: bool,
+    debug_info: bool,
+    debug: bool,
+    cast: bool,
+    compare: bool,
+    array_access: bool,
+    observe: bool,
+    iterator: bool,
 }
 
 pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream> {
@@ -46,8 +115,13 @@ pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream>
         .map_err(|e| anyhow!("Unable to parse attribute arguments: {:?}", e))?;
 
     let mut parent = None;
+    let mut parent_class = None;
     let mut interfaces = vec![];
+    let mut traits = vec![];
     let mut properties = HashMap::new();
+    let mut flattened = vec![];
+    let mut gc_fields = vec![];
+    let mut delegate = None;
     let mut comments = vec![];
 
     input.attrs = {
@@ -56,11 +130,27 @@ pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream>
             match parse_attribute(&attr)? {
                 Some(parsed) => match parsed {
                     ParsedAttribute::Extends(class) => {
+                        if parent.is_some() {
+                            bail!("Only one parent class can be given.");
+                        }
                         parent = Some(class.to_token_stream().to_string());
                     }
+                    ParsedAttribute::ExtendsClass(class) => {
+                        if parent.is_some() {
+                            bail!("Only one parent class can be given.");
+                        }
+                        parent = Some(
+                            quote! {
+                                <#class as ::ext_php_rs::class::RegisteredClass>::get_metadata().ce()
+                            }
+                            .to_string(),
+                        );
+                        parent_class = Some(class.to_token_stream().to_string());
+                    }
                     ParsedAttribute::Implements(class) => {
                         interfaces.push(class.to_token_stream().to_string());
                     }
+                    ParsedAttribute::UseTrait(trait_) => traits.push(trait_),
                     ParsedAttribute::Comment(comment) => {
                         comments.push(comment);
                     }
@@ -72,46 +162,47 @@ pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream>
         unused
     };
 
-    if let Fields::Named(FieldsNamed {
-        brace_token: _,
-        named,
-    }) = &mut input.fields
-    {
-        for field in named.iter_mut() {
-            let mut docs = vec![];
-            let mut attrs = vec![];
-            attrs.append(&mut field.attrs);
-
-            for attr in attrs.into_iter() {
-                let mut result_prop = None;
-                match parse_attribute(&attr)? {
-                    Some(parsed) => match parsed {
-                        ParsedAttribute::Property(prop) => {
-                            let field_name = field
-                                .ident
-                                .as_ref()
-                                .ok_or_else(|| anyhow!("Only named fields can be properties."))?
-                                .to_string();
-                            let prop_name = prop.rename.unwrap_or_else(|| field_name.clone());
-                            result_prop = Some((
-                                prop_name,
-                                Property::field(
-                                    field_name,
-                                    vec![],
-                                    prop.flags.map(|flags| flags.to_token_stream().to_string()),
-                                ),
-                            ));
+    // Positional fields of tuple structs are named by their index.
+    for (index, field) in input.fields.iter_mut().enumerate() {
+        let field_name = field
+            .ident
+            .as_ref()
+            .map_or_else(|| index.to_string(), ToString::to_string);
+        let mut docs = vec![];
+        let mut attrs = vec![];
+        attrs.append(&mut field.attrs);
+
+        for attr in attrs.into_iter() {
+            let mut result_prop = None;
+            match parse_attribute(&attr)? {
+                Some(parsed) => match parsed {
+                    ParsedAttribute::Property(prop) => {
+                        match parse_field_property(prop, field, field_name.clone())? {
+                            FieldProperty::Flattened(field) => {
+                                flattened.push(field);
+                                continue;
+                            }
+                            FieldProperty::Property(name, property) => {
+                                result_prop = Some((name, property))
+                            }
                         }
-                        ParsedAttribute::Comment(doc) => docs.push(doc),
-                        _ => bail!("Attribute {:?} is not valid for struct fields.", attr),
-                    },
-                    None => field.attrs.push(attr),
-                }
+                    }
+                    ParsedAttribute::Gc => gc_fields.push(field_name.clone()),
+                    ParsedAttribute::Delegate => {
+                        if delegate.is_some() {
+                            bail!("Only one field can be annotated with `#[delegate]`.");
+                        }
+                        delegate = Some(field_name.clone());
+                    }
+                    ParsedAttribute::Comment(doc) => docs.push(doc),
+                    _ => bail!("Attribute {:?} is not valid for struct fields.", attr),
+                },
+                None => field.attrs.push(attr),
+            }
 
-                if let Some(mut prop) = result_prop {
-                    prop.1.docs.append(&mut docs);
-                    properties.insert(prop.0, prop.1);
-                }
+            if let Some(mut prop) = result_prop {
+                prop.1.docs.append(&mut docs);
+                properties.insert(prop.0, prop.1);
             }
         }
     }
@@ -120,15 +211,34 @@ pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream>
     let class_name = args.name.unwrap_or_else(|| ident.to_string());
     let struct_path = ident.to_string();
     let flags = args.flags.map(|flags| flags.to_token_stream().to_string());
+    if args.gc && !gc_fields.is_empty() {
+        bail!("`#[gc]` fields cannot be used when the class implements `CollectGarbage` itself.");
+    }
+    if args.debug_info && args.debug {
+        bail!("Only one of `debug_info` and `debug` can be given.");
+    }
     let class = Class {
         class_name,
         struct_path,
         parent,
+        parent_class,
         interfaces,
+        traits,
         docs: comments,
         properties,
+        flattened,
         modifier: args.modifier,
         flags,
+        gc: args.gc,
+        gc_fields,
+        delegate,
+        debug_info: args.debug_info,
+        debug: args.debug,
+        cast: args.cast,
+        compare: args.compare,
+        array_access: args.array_access,
+        observe: args.observe,
+        iterator: args.iterator,
         ..Default::default()
     };
 
@@ -151,22 +261,251 @@ pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream>
     })
 }
 
+/// A property parsed from a field annotated with `#[prop]`.
+pub enum FieldProperty {
+    Flattened(FlattenedField),
+    Property(String, Property),
+}
+
+/// Parses the `#[prop]` attribute of a field.
+///
+/// # Parameters
+///
+/// * `prop` - The parsed attribute.
+/// * `field` - The field the attribute was applied to.
+/// * `field_name` - The name of the field, or its index in tuple structs.
+pub fn parse_field_property(
+    prop: PropertyAttr,
+    field: &syn::Field,
+    field_name: String,
+) -> Result<FieldProperty> {
+    if prop.flatten {
+        if prop.rename.is_some()
+            || prop.default.is_some()
+            || prop.vis.is_some()
+            || prop.flags.is_some()
+            || prop.cached
+        {
+            bail!("Flattened properties only accept the `prefix` option.");
+        }
+        return Ok(FieldProperty::Flattened(FlattenedField {
+            field_name,
+            ty: field.ty.to_token_stream().to_string(),
+            prefix: prop.prefix.unwrap_or_default(),
+        }));
+    }
+    if prop.prefix.is_some() {
+        bail!("The `prefix` option can only be used on flattened properties.");
+    }
+    let prop_name = match prop.rename {
+        Some(name) => name,
+        None if field.ident.is_none() => bail!(
+            "Properties of tuple structs must be given a name with `#[prop(name = \"...\")]`."
+        ),
+        None => field_name.clone(),
+    };
+    let mut property = Property::field(
+        field_name,
+        &field.ty,
+        prop.default,
+        vec![],
+        prop.flags.map(|flags| flags.to_token_stream().to_string()),
+    );
+    property.vis = prop.vis;
+    Ok(FieldProperty::Property(prop_name, property))
+}
+
+/// Parses a struct deriving `FlattenProps`, which exposes the fields annotated
+/// with `#[prop]` without exporting the struct as a class.
+pub fn flatten_props_parser(input: syn::DeriveInput) -> Result<TokenStream> {
+    let fields = match &input.data {
+        syn::Data::Struct(data) => &data.fields,
+        _ => bail!("`FlattenProps` can only be derived for structs."),
+    };
+    let mut properties = HashMap::new();
+    let mut flattened = vec![];
+    for (index, field) in fields.iter().enumerate() {
+        let field_name = field
+            .ident
+            .as_ref()
+            .map_or_else(|| index.to_string(), ToString::to_string);
+        let mut docs = vec![];
+        for attr in &field.attrs {
+            match parse_attribute(attr)? {
+                Some(ParsedAttribute::Property(prop)) => {
+                    match parse_field_property(prop, field, field_name.clone())? {
+                        FieldProperty::Flattened(field) => flattened.push(field),
+                        FieldProperty::Property(name, mut property) => {
+                            property.docs.append(&mut docs);
+                            properties.insert(name, property);
+                        }
+                    }
+                }
+                Some(ParsedAttribute::Comment(doc)) => docs.push(doc),
+                _ => {}
+            }
+        }
+    }
+
+    let props = properties_expr(&properties, &flattened, None);
+    Ok(generate_flatten_props_impl(
+        &input.ident,
+        &properties,
+        &flattened,
+        props,
+    ))
+}
+
+/// Generates the expression building the map of the properties of a struct.
+///
+/// # Parameters
+///
+/// * `properties` - The properties declared on the struct.
+/// * `flattened` - The flattened fields of the struct.
+/// * `parent` - The expression returning the properties of the parent class.
+pub fn properties_expr(
+    properties: &HashMap<String, Property>,
+    flattened: &[FlattenedField],
+    parent: Option<TokenStream>,
+) -> TokenStream {
+    let prop_tuples = properties
+        .iter()
+        .filter_map(|(name, prop)| prop.as_prop_tuple(name));
+    let flattened = flattened.iter().map(|field| field.as_props_expr());
+
+    quote! {{
+        use ::std::iter::FromIterator;
+
+        ::std::collections::HashMap::from_iter(
+            ::std::iter::IntoIterator::into_iter([
+                #(#prop_tuples)*
+            ])
+            #(.chain(#flattened))*
+            #parent
+        )
+    }}
+}
+
+/// Generates an implementation of `FlattenProps` on a struct, and rejects
+/// flattened properties whose names collide with other properties.
+///
+/// # Parameters
+///
+/// * `ident` - The struct.
+/// * `properties` - The properties declared on the struct.
+/// * `flattened` - The flattened fields of the struct.
+/// * `props` - The expression returning the properties of the struct.
+pub fn generate_flatten_props_impl(
+    ident: &Ident,
+    properties: &HashMap<String, Property>,
+    flattened: &[FlattenedField],
+    props: TokenStream,
+) -> TokenStream {
+    let mut instance_props = properties
+        .iter()
+        .filter(|(_, prop)| !prop.is_static())
+        .collect::<Vec<_>>();
+    instance_props.sort_by_key(|(name, _)| *name);
+    let names = instance_props.iter().map(|(name, _)| name);
+    let declarations = instance_props
+        .iter()
+        .filter_map(|(name, prop)| prop.as_flattened_declaration(name));
+    let flattened_names = flattened.iter().map(|field| {
+        let ty = field.ty();
+        let prefix = &field.prefix;
+        quote! { (#prefix, &<#ty as ::ext_php_rs::props::FlattenProps>::PROPERTY_NAMES) }
+    });
+    let flattened_declarations = flattened.iter().map(|field| {
+        let ty = field.ty();
+        let prefix = &field.prefix;
+        quote! {
+            let builder = <#ty as ::ext_php_rs::props::FlattenProps>::declare_properties(
+                builder,
+                &::std::format!("{}{}", prefix, #prefix),
+            );
+        }
+    });
+    let collision_check = if flattened.is_empty() {
+        None
+    } else {
+        let message = format!(
+            "The names of the flattened properties of `{}` collide with other properties.",
+            ident
+        );
+        Some(quote! {
+            const _: () = ::std::assert!(
+                !<#ident as ::ext_php_rs::props::FlattenProps>::PROPERTY_NAMES.has_collision(),
+                #message
+            );
+        })
+    };
+
+    quote! {
+        impl ::ext_php_rs::props::FlattenProps for #ident {
+            const PROPERTY_NAMES: ::ext_php_rs::props::PropertyNames =
+                ::ext_php_rs::props::PropertyNames {
+                    names: &[#(#names),*],
+                    flattened: &[#(#flattened_names),*],
+                };
+
+            fn flatten_properties<'a>() -> ::std::collections::HashMap<&'static str, ::ext_php_rs::props::Property<'a, Self>> {
+                #props
+            }
+
+            #[allow(unused_variables)]
+            fn declare_properties(
+                builder: ::ext_php_rs::builders::ClassBuilder,
+                prefix: &str,
+            ) -> ::ext_php_rs::builders::ClassBuilder {
+                use ::ext_php_rs::flags::PropertyFlags;
+
+                let builder = builder #(#declarations)*;
+                #(#flattened_declarations)*
+                builder
+            }
+        }
+
+        #collision_check
+    }
+}
+
 #[derive(Debug)]
 pub struct Property {
     pub ty: PropertyType,
     pub docs: Vec<String>,
-    #[allow(dead_code)]
     pub flags: Option<String>,
+    pub vis: Option<Visibility>,
 }
 
 #[derive(Debug)]
 pub enum PropertyType {
     Field {
         field_name: String,
+        /// The type of the field.
+        ty: String,
+        /// Whether the field is an `Option`, and can therefore be set to null.
+        nullable: bool,
+        /// The default value of the property declared on the class.
+        default: Option<PropertyDefault>,
     },
     Method {
         getter: Option<String>,
         setter: Option<String>,
+        /// The return type of the getter and whether it is nullable, if the
+        /// value returned by the getter is cached.
+        cached: Option<(String, bool)>,
+    },
+    /// A static property declared from an associated constant, which holds
+    /// the default value of the property.
+    Static {
+        /// The type of the constant.
+        ty: String,
+        /// Whether the constant is an `Option`.
+        nullable: bool,
+        /// The path of the constant.
+        path: String,
+        /// The value of the constant.
+        value: String,
     },
 }
 
@@ -174,7 +513,8 @@ impl Property {
     pub fn add_getter(&mut self, new_getter: String) -> Result<()> {
         match &mut self.ty {
             PropertyType::Field { .. } => bail!("Cannot add getter to field property."),
-            PropertyType::Method { getter, setter: _ } => match getter {
+            PropertyType::Static { .. } => bail!("Cannot add getter to static property."),
+            PropertyType::Method { getter, .. } => match getter {
                 Some(getter) => bail!(
                     "Attempted to add getter `{}` to property that already has a getter `{}`.",
                     new_getter,
@@ -191,7 +531,8 @@ impl Property {
     pub fn add_setter(&mut self, new_setter: String) -> Result<()> {
         match &mut self.ty {
             PropertyType::Field { .. } => bail!("Cannot add setter to field property."),
-            PropertyType::Method { getter: _, setter } => match setter {
+            PropertyType::Static { .. } => bail!("Cannot add setter to static property."),
+            PropertyType::Method { setter, .. } => match setter {
                 Some(getter) => bail!(
                     "Attempted to add setter `{}` to property that already has a setter `{}`.",
                     new_setter,
@@ -205,34 +546,247 @@ impl Property {
         }
     }
 
-    pub fn field(field_name: String, docs: Vec<String>, flags: Option<String>) -> Self {
+    /// Caches the value returned by the getter of the property.
+    ///
+    /// # Parameters
+    ///
+    /// * `output` - The return type of the getter and whether it is nullable.
+    pub fn set_cached(&mut self, output: (String, bool)) -> Result<()> {
+        match &mut self.ty {
+            PropertyType::Field { .. } | PropertyType::Static { .. } => {
+                bail!("Cannot cache field property.")
+            }
+            PropertyType::Method { cached, .. } => {
+                cached.replace(output);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn field(
+        field_name: String,
+        ty: &syn::Type,
+        default: Option<PropertyDefault>,
+        docs: Vec<String>,
+        flags: Option<String>,
+    ) -> Self {
+        Self {
+            ty: PropertyType::Field {
+                field_name,
+                ty: ty.to_token_stream().to_string(),
+                nullable: is_option(ty),
+                default,
+            },
+            docs,
+            flags,
+            vis: None,
+        }
+    }
+
+    /// Creates a static property from an associated constant.
+    ///
+    /// # Parameters
+    ///
+    /// * `constant` - The constant holding the default value of the property.
+    /// * `class` - The path of the struct the constant is associated with.
+    pub fn static_(
+        constant: &syn::ImplItemConst,
+        class: &str,
+        docs: Vec<String>,
+        flags: Option<String>,
+    ) -> Self {
         Self {
-            ty: PropertyType::Field { field_name },
+            ty: PropertyType::Static {
+                ty: constant.ty.to_token_stream().to_string(),
+                nullable: is_option(&constant.ty),
+                path: format!("{}::{}", class, constant.ident),
+                value: constant.expr.to_token_stream().to_string(),
+            },
             docs,
             flags,
+            vis: None,
         }
     }
 
+    /// Returns the name of the field of a field property.
+    pub fn field_name(&self) -> Option<&str> {
+        match &self.ty {
+            PropertyType::Field { field_name, .. } => Some(field_name),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the property is static.
+    pub fn is_static(&self) -> bool {
+        matches!(self.ty, PropertyType::Static { .. })
+    }
+
     pub fn method(docs: Vec<String>, flags: Option<String>) -> Self {
         Self {
             ty: PropertyType::Method {
                 getter: None,
                 setter: None,
+                cached: None,
             },
             docs,
             flags,
+            vis: None,
         }
     }
 
-    pub fn as_prop_tuple(&self, name: &str) -> TokenStream {
+    /// Returns the PHP type of the property and whether it is nullable, if
+    /// the type is known.
+    pub fn php_type(&self) -> Option<(TokenStream, bool)> {
+        let (ty, nullable) = match &self.ty {
+            PropertyType::Field { ty, nullable, .. }
+            | PropertyType::Static { ty, nullable, .. } => (ty, *nullable),
+            PropertyType::Method {
+                cached: Some((ty, nullable)),
+                ..
+            } => (ty, *nullable),
+            PropertyType::Method { .. } => return None,
+        };
+        let ty: syn::Type = syn::parse_str(ty).expect("failed to parse previously parsed type");
+        Some((
+            quote! { <#ty as ::ext_php_rs::convert::IntoZval>::TYPE },
+            nullable,
+        ))
+    }
+
+    /// Returns the declaration of the property on the class builder. Only
+    /// properties with a known type are declared.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property.
+    /// * `promoted` - Whether the property is initialized by a constructor
+    ///   argument.
+    pub fn as_declaration(&self, name: &str, promoted: bool) -> Option<TokenStream> {
+        self.declaration(name, quote! { #name }, promoted)
+    }
+
+    /// Returns the declaration of the property on the builder of a class the
+    /// struct is flattened into, with the name prefixed by the `prefix`
+    /// variable in scope.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property.
+    pub fn as_flattened_declaration(&self, name: &str) -> Option<TokenStream> {
+        self.declaration(
+            name,
+            quote! { ::std::format!("{}{}", prefix, #name) },
+            false,
+        )
+    }
+
+    fn declaration(
+        &self,
+        name: &str,
+        name_expr: TokenStream,
+        promoted: bool,
+    ) -> Option<TokenStream> {
+        let (ty, nullable) = self.php_type()?;
+        let vis = match self.vis {
+            Some(Visibility::Protected) => quote! { PropertyFlags::Protected },
+            Some(Visibility::Private) => quote! { PropertyFlags::Private },
+            Some(Visibility::Public) | None => quote! { PropertyFlags::Public },
+        };
+        let vis = if self.is_static() {
+            quote! { #vis | PropertyFlags::Static }
+        } else if promoted {
+            quote! { #vis | PropertyFlags::Promoted }
+        } else {
+            vis
+        };
+        let flags = match &self.flags {
+            Some(flags) => {
+                let flags: Expr =
+                    syn::parse_str(flags).expect("failed to parse previously parsed flags");
+                quote! { #flags | #vis }
+            }
+            None => vis,
+        };
+        let default = match &self.ty {
+            // As in PHP, promoted properties are initialized by the constructor
+            // and do not have a default value.
+            PropertyType::Field { .. } if promoted => quote! { None },
+            PropertyType::Field {
+                ty: field_ty,
+                default: Some(default),
+                ..
+            } => {
+                let field_ty: syn::Type =
+                    syn::parse_str(field_ty).expect("failed to parse previously parsed type");
+                let value = default.value_tokens();
+                quote! {
+                    Some({
+                        let default: #field_ty = #value;
+                        ::ext_php_rs::convert::IntoZval::into_zval(default, true)
+                            .expect(concat!("Invalid default value for property `", #name, "`"))
+                    })
+                }
+            }
+            PropertyType::Static { ty, path, .. } => {
+                let ty: syn::Type =
+                    syn::parse_str(ty).expect("failed to parse previously parsed type");
+                let path: syn::Path =
+                    syn::parse_str(path).expect("failed to parse previously parsed path");
+                quote! {
+                    Some({
+                        let default: #ty = #path;
+                        ::ext_php_rs::convert::IntoZval::into_zval(default, true)
+                            .expect(concat!("Invalid default value for property `", #name, "`"))
+                    })
+                }
+            }
+            _ => quote! { None },
+        };
+        let doc_comment = if self.docs.is_empty() {
+            None
+        } else {
+            let mut doc_comment = String::from("/**\n");
+            for line in &self.docs {
+                doc_comment.push_str(&format!(" *{}\n", line));
+            }
+            doc_comment.push_str(" */");
+            Some(quote! { .property_doc_comment(&#name_expr, #doc_comment) })
+        };
+        Some(quote! { .typed_property(#name_expr, #ty, #nullable, #default, #flags) #doc_comment })
+    }
+
+    /// Returns the default value of the property as it is written in PHP, if
+    /// it is given as a literal.
+    pub fn default_literal(&self) -> Option<String> {
         match &self.ty {
-            PropertyType::Field { field_name } => {
-                let field_name = Ident::new(field_name, Span::call_site());
+            PropertyType::Field {
+                default: Some(PropertyDefault::Expr(expr)),
+                ..
+            }
+            | PropertyType::Static { value: expr, .. } => match syn::parse_str(expr) {
+                Ok(Expr::Lit(lit)) => Some(lit.to_token_stream().to_string()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the entry of the property in the properties of the class.
+    /// Static properties are stored on the class entry, so they do not have
+    /// an entry.
+    pub fn as_prop_tuple(&self, name: &str) -> Option<TokenStream> {
+        Some(match &self.ty {
+            PropertyType::Field { field_name, .. } => {
+                let field_name = field_member(field_name);
                 quote! {
                     (#name, ::ext_php_rs::props::Property::field(|obj: &mut Self| &mut obj.#field_name)),
                 }
             }
-            PropertyType::Method { getter, setter } => {
+            PropertyType::Method {
+                getter,
+                setter,
+                cached,
+            } => {
                 let getter = if let Some(getter) = getter {
                     let ident = Ident::new(getter, Span::call_site());
                     quote! { Some(Self::#ident) }
@@ -245,11 +799,31 @@ impl Property {
                 } else {
                     quote! { None }
                 };
+                let cached = cached.as_ref().map(|_| quote! { .cached() });
                 quote! {
-                    (#name, ::ext_php_rs::props::Property::method(#getter, #setter)),
+                    (#name, ::ext_php_rs::props::Property::method(#getter, #setter)#cached),
                 }
             }
-        }
+            PropertyType::Static { .. } => return None,
+        })
+    }
+}
+
+/// Returns the member used to access the field with the given name, which is
+/// the index of the field in tuple structs.
+pub fn field_member(field_name: &str) -> syn::Member {
+    syn::parse_str(field_name).expect("failed to parse previously parsed field name")
+}
+
+/// Returns whether the given type is an `Option`.
+fn is_option(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|seg| seg.ident == "Option"),
+        _ => false,
     }
 }
 
@@ -257,6 +831,34 @@ impl Property {
 pub struct PropertyAttr {
     pub rename: Option<String>,
     pub flags: Option<Expr>,
+    pub vis: Option<Visibility>,
+    pub default: Option<PropertyDefault>,
+    pub cached: bool,
+    pub flatten: bool,
+    pub prefix: Option<String>,
+}
+
+/// The default value of a property, given with `#[prop(default)]` or
+/// `#[prop(default = expr)]`.
+#[derive(Debug)]
+pub enum PropertyDefault {
+    /// The `Default` implementation of the field type.
+    Default,
+    /// An expression converted into the field type with `Into`.
+    Expr(String),
+}
+
+impl PropertyDefault {
+    fn value_tokens(&self) -> TokenStream {
+        match self {
+            Self::Default => quote! { ::std::default::Default::default() },
+            Self::Expr(expr) => {
+                let expr: Expr =
+                    syn::parse_str(expr).expect("failed to parse previously parsed expression");
+                quote! { ::std::convert::Into::into(#expr) }
+            }
+        }
+    }
 }
 
 impl syn::parse::Parse for PropertyAttr {
@@ -264,15 +866,48 @@ impl syn::parse::Parse for PropertyAttr {
         let mut this = Self::default();
         while !input.is_empty() {
             let field = input.parse::<Ident>()?.to_string();
+            if !input.peek(Token![=]) {
+                match field.as_str() {
+                    "default" => {
+                        this.default.replace(PropertyDefault::Default);
+                    }
+                    "cached" => this.cached = true,
+                    "flatten" => this.flatten = true,
+                    _ => return Err(input.error("expected `=`")),
+                }
+                let _ = input.parse::<Token![,]>();
+                continue;
+            }
             input.parse::<Token![=]>()?;
 
             match field.as_str() {
-                "rename" => {
+                "rename" | "name" => {
                     this.rename.replace(input.parse::<LitStr>()?.value());
                 }
+                "prefix" => {
+                    this.prefix.replace(input.parse::<LitStr>()?.value());
+                }
                 "flags" => {
                     this.flags.replace(input.parse::<Expr>()?);
                 }
+                "vis" => {
+                    let vis = input.parse::<LitStr>()?;
+                    this.vis.replace(match vis.value().as_str() {
+                        "public" => Visibility::Public,
+                        "protected" => Visibility::Protected,
+                        "private" => Visibility::Private,
+                        _ => {
+                            return Err(syn::Error::new(
+                                vis.span(),
+                                "expected `public`, `protected` or `private`",
+                            ))
+                        }
+                    });
+                }
+                "default" => {
+                    let expr = input.parse::<Expr>()?.to_token_stream().to_string();
+                    this.default.replace(PropertyDefault::Expr(expr));
+                }
                 _ => return Err(input.error("invalid attribute field")),
             }
 
@@ -283,6 +918,51 @@ impl syn::parse::Parse for PropertyAttr {
     }
 }
 
+/// Arguments of the `#[use_trait]` attribute.
+#[derive(Debug)]
+pub struct UseTraitAttr {
+    /// Expression returning the class entry of the trait.
+    pub ce: String,
+    /// Name of the trait used in stubs, defaulting to the expression.
+    pub stub: Option<String>,
+}
+
+impl UseTraitAttr {
+    /// Returns the name of the trait used in stubs.
+    pub fn stub_name(&self) -> &str {
+        self.stub.as_deref().unwrap_or(&self.ce)
+    }
+}
+
+impl syn::parse::Parse for UseTraitAttr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut ce = None;
+        let mut stub = None;
+
+        while !input.is_empty() {
+            let field: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+
+            match field.to_string().as_str() {
+                "ce" => {
+                    ce.replace(input.parse::<Expr>()?.to_token_stream().to_string());
+                }
+                "stub" => {
+                    stub.replace(input.parse::<LitStr>()?.value());
+                }
+                _ => return Err(syn::Error::new(field.span(), "expected `ce` or `stub`")),
+            }
+
+            let _ = input.parse::<Token![,]>();
+        }
+
+        Ok(Self {
+            ce: ce.ok_or_else(|| input.error("expected `ce`"))?,
+            stub,
+        })
+    }
+}
+
 pub fn parse_attribute(attr: &Attribute) -> Result<Option<ParsedAttribute>> {
     let name = attr.path.to_token_stream().to_string();
 
@@ -293,12 +973,24 @@ pub fn parse_attribute(attr: &Attribute) -> Result<Option<ParsedAttribute>> {
                 .map_err(|_| anyhow!("Unable to parse `#[{}]` attribute.", name))?;
             Some(ParsedAttribute::Extends(meta))
         }
+        "extends_class" => {
+            let meta: syn::Path = attr
+                .parse_args()
+                .map_err(|_| anyhow!("Unable to parse `#[{}]` attribute.", name))?;
+            Some(ParsedAttribute::ExtendsClass(meta))
+        }
         "implements" => {
             let meta: Expr = attr
                 .parse_args()
                 .map_err(|_| anyhow!("Unable to parse `#[{}]` attribute.", name))?;
             Some(ParsedAttribute::Implements(meta))
         }
+        "use_trait" => {
+            let meta: UseTraitAttr = attr
+                .parse_args()
+                .map_err(|e| anyhow!("Unable to parse `#[{}]` attribute: {}", name, e))?;
+            Some(ParsedAttribute::UseTrait(meta))
+        }
         "doc" => {
             struct DocComment(pub String);
 
@@ -324,6 +1016,8 @@ pub fn parse_attribute(attr: &Attribute) -> Result<Option<ParsedAttribute>> {
 
             Some(ParsedAttribute::Property(attr))
         }
+        "gc" => Some(ParsedAttribute::Gc),
+        "delegate" => Some(ParsedAttribute::Delegate),
         _ => None,
     })
 }
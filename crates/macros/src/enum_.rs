@@ -0,0 +1,533 @@
+use anyhow::{anyhow, bail, Result};
+use darling::{FromMeta, ToTokens};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{
+    punctuated::Punctuated, Attribute, AttributeArgs, Expr, Fields, ImplItem, ItemEnum, Lit,
+    LitStr, Token, Type, UnOp,
+};
+
+use crate::{
+    constant::Constant,
+    helpers::get_docs,
+    impl_::RenameRule,
+    method::{self, Method},
+    STATE,
+};
+
+#[derive(Debug)]
+pub struct Enum {
+    pub enum_name: String,
+    pub ident: String,
+    pub docs: Vec<String>,
+    pub interfaces: Vec<String>,
+    pub cases: Vec<EnumCase>,
+    pub methods: Vec<Method>,
+    pub constants: Vec<Constant>,
+}
+
+#[derive(Debug)]
+pub struct EnumCase {
+    pub ident: String,
+    pub name: String,
+    pub docs: Vec<String>,
+    pub value: Option<CaseValue>,
+    pub attributes: Vec<CaseAttribute>,
+}
+
+/// A PHP attribute applied to a case.
+#[derive(Debug)]
+pub struct CaseAttribute {
+    /// The fully qualified name of the attribute class.
+    pub name: String,
+    /// The arguments passed to the attribute, as Rust expressions.
+    pub args: Vec<String>,
+    /// The arguments passed to the attribute, as PHP literals.
+    pub literals: Vec<String>,
+}
+
+/// The value backing a case of a backed enum.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CaseValue {
+    Int(i64),
+    String(String),
+}
+
+/// The rule used to name the cases of an enum in PHP, applied to the names of
+/// the variants.
+#[derive(Debug, Copy, Clone, FromMeta, Default)]
+pub enum CaseRenameRule {
+    #[darling(rename = "none")]
+    #[default]
+    None,
+    #[darling(rename = "lowercase")]
+    Lower,
+    #[darling(rename = "PascalCase")]
+    Pascal,
+    #[darling(rename = "camelCase")]
+    Camel,
+    #[darling(rename = "snake_case")]
+    Snake,
+    #[darling(rename = "SCREAMING_SNAKE_CASE")]
+    ScreamingSnake,
+}
+
+impl CaseRenameRule {
+    /// Changes the case of the name of a variant.
+    pub fn rename(&self, name: impl AsRef<str>) -> String {
+        let rule = match self {
+            Self::None => ident_case::RenameRule::None,
+            Self::Lower => ident_case::RenameRule::LowerCase,
+            Self::Pascal => ident_case::RenameRule::PascalCase,
+            Self::Camel => ident_case::RenameRule::CamelCase,
+            Self::Snake => ident_case::RenameRule::SnakeCase,
+            Self::ScreamingSnake => ident_case::RenameRule::ScreamingSnakeCase,
+        };
+        rule.apply_to_variant(name)
+    }
+}
+
+#[derive(Default, Debug, FromMeta)]
+#[darling(default)]
+pub struct AttrArgs {
+    name: Option<String>,
+    rename_all: CaseRenameRule,
+}
+
+pub fn parser(args: AttributeArgs, mut input: ItemEnum) -> Result<TokenStream> {
+    let args = AttrArgs::from_list(&args)
+        .map_err(|e| anyhow!("Unable to parse attribute arguments: {:?}", e))?;
+
+    if !input.generics.params.is_empty() {
+        bail!("Enums exported to PHP cannot be generic.");
+    }
+
+    let mut cases = vec![];
+    for variant in input.variants.iter_mut() {
+        if !matches!(variant.fields, Fields::Unit) {
+            bail!(
+                "Variant `{}` must be a unit variant to be exported as an enum case.",
+                variant.ident
+            );
+        }
+
+        let mut value = match &variant.discriminant {
+            Some((_, expr)) => Some(CaseValue::Int(parse_int(expr)?)),
+            None => None,
+        };
+        let docs = get_docs(&variant.attrs);
+        let mut name = None;
+        let mut attributes = vec![];
+        let mut attrs = vec![];
+        for attr in std::mem::take(&mut variant.attrs) {
+            if attr.path.is_ident("value") {
+                if value.is_some() {
+                    bail!("Variant `{}` was given more than one value.", variant.ident);
+                }
+                value = Some(parse_value(&attr.parse_args()?)?);
+            } else if attr.path.is_ident("rename") {
+                let rename: LitStr = attr
+                    .parse_args()
+                    .map_err(|_| anyhow!("Invalid argument given for `#[rename]` macro."))?;
+                name = Some(rename.value());
+            } else if attr.path.is_ident("attribute") {
+                attributes.push(parse_attribute(&attr)?);
+            } else {
+                attrs.push(attr);
+            }
+        }
+        variant.attrs = attrs;
+
+        let name = name.unwrap_or_else(|| args.rename_all.rename(variant.ident.to_string()));
+        if !is_valid_case_name(&name) {
+            bail!(
+                "`{}` is not a valid name for the case of variant `{}`.",
+                name,
+                variant.ident
+            );
+        }
+        cases.push(EnumCase {
+            ident: variant.ident.to_string(),
+            name,
+            docs,
+            value,
+            attributes,
+        });
+    }
+
+    let ints = cases
+        .iter()
+        .filter(|case| matches!(case.value, Some(CaseValue::Int(_))))
+        .count();
+    let strings = cases
+        .iter()
+        .filter(|case| matches!(case.value, Some(CaseValue::String(_))))
+        .count();
+    if ints + strings != 0 && ints + strings != cases.len() {
+        bail!("Either all or none of the cases of an enum must be given a value.");
+    }
+    if ints != 0 && strings != 0 {
+        bail!("The values of the cases of an enum must all be integers or all be strings.");
+    }
+    for (i, case) in cases.iter().enumerate() {
+        if let Some(other) = cases[..i].iter().find(|other| {
+            other.name == case.name || (case.value.is_some() && other.value == case.value)
+        }) {
+            bail!(
+                "Case `{}` has the same name or value as case `{}`.",
+                case.ident,
+                other.ident
+            );
+        }
+    }
+
+    let mut interfaces = vec![];
+    let mut attrs = vec![];
+    for attr in std::mem::take(&mut input.attrs) {
+        if attr.path.is_ident("implements") {
+            let interface: Expr = attr
+                .parse_args()
+                .map_err(|_| anyhow!("Unable to parse `#[implements]` attribute."))?;
+            interfaces.push(interface.to_token_stream().to_string());
+        } else {
+            attrs.push(attr);
+        }
+    }
+    input.attrs = attrs;
+
+    let ident = &input.ident;
+    let enum_ = Enum {
+        enum_name: args.name.unwrap_or_else(|| ident.to_string()),
+        ident: ident.to_string(),
+        docs: get_docs(&input.attrs),
+        interfaces,
+        cases,
+        methods: vec![],
+        constants: vec![],
+    };
+    let registered_enum = enum_.registered_enum_impl();
+
+    let mut state = STATE.lock();
+
+    if state.built_module {
+        bail!("The `#[php_module]` macro must be called last to ensure functions and classes are registered.");
+    }
+
+    if state.startup_function.is_some() {
+        bail!("The `#[php_startup]` macro must be called after all the enums have been defined.");
+    }
+
+    state.enums.insert(ident.to_string(), enum_);
+
+    Ok(quote! {
+        #input
+
+        #registered_enum
+    })
+}
+
+/// Parses the methods and constants of an `impl` block of an enum.
+pub fn parse_impl(
+    enum_: &mut Enum,
+    self_ty: &Type,
+    items: Vec<ImplItem>,
+    rename_rule: RenameRule,
+) -> Result<Vec<TokenStream>> {
+    items
+        .into_iter()
+        .map(|item| {
+            Ok(match item {
+                ImplItem::Method(method) => {
+                    let parsed_method = method::parser(self_ty, method, rename_rule, true)?;
+                    if parsed_method.constructor {
+                        bail!("Enums cannot have constructors.");
+                    }
+                    if parsed_method.property.is_some() {
+                        bail!("Enums cannot have properties.");
+                    }
+                    enum_.methods.push(parsed_method.method);
+                    parsed_method.tokens
+                }
+                ImplItem::Const(constant) => {
+                    let name = constant.ident.to_string();
+                    if enum_.cases.iter().any(|case| case.name == name) {
+                        bail!(
+                            "Constant `{}` has the same name as a case of the enum.",
+                            name
+                        );
+                    }
+                    enum_.constants.push(Constant {
+                        name,
+                        docs: get_docs(&constant.attrs),
+                        value: constant.expr.to_token_stream().to_string(),
+                    });
+
+                    quote! {
+                        #[allow(dead_code)]
+                        #constant
+                    }
+                }
+                item => item.to_token_stream(),
+            })
+        })
+        .collect()
+}
+
+/// Checks if the name can be used as the name of a case in PHP, which must be
+/// an identifier other than `class`.
+fn is_valid_case_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c == '_' || c.is_alphabetic())
+        && chars.all(|c| c == '_' || c.is_alphanumeric())
+        && !name.eq_ignore_ascii_case("class")
+}
+
+/// Parses a PHP attribute applied to a case with
+/// `#[attribute("Name", args...)]`.
+fn parse_attribute(attr: &Attribute) -> Result<CaseAttribute> {
+    let args = attr
+        .parse_args_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+        .map_err(|_| anyhow!("Invalid arguments given for `#[attribute]` macro."))?;
+    let mut args = args.into_iter();
+    let name = match args.next() {
+        Some(Expr::Lit(syn::ExprLit {
+            lit: Lit::Str(name),
+            ..
+        })) => name.value(),
+        _ => bail!("The first argument of `#[attribute]` must be the name of the attribute class."),
+    };
+    let name = name.trim_start_matches('\\').to_string();
+    if name.is_empty() {
+        bail!("The first argument of `#[attribute]` must be the name of the attribute class.");
+    }
+    let (args, literals) = args
+        .map(|arg| {
+            let literal = php_literal(&arg)?;
+            Ok((arg.to_token_stream().to_string(), literal))
+        })
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+
+    Ok(CaseAttribute {
+        name,
+        args,
+        literals,
+    })
+}
+
+/// Writes a literal passed to an attribute as a PHP literal.
+fn php_literal(expr: &Expr) -> Result<String> {
+    Ok(match expr {
+        Expr::Lit(syn::ExprLit { lit, .. }) => match lit {
+            Lit::Str(str) => CaseValue::String(str.value()).literal(),
+            Lit::Int(int) => int.base10_digits().to_string(),
+            Lit::Float(float) => float.base10_digits().to_string(),
+            Lit::Bool(bool) => bool.value.to_string(),
+            _ => bail!("The arguments of `#[attribute]` must be string, integer, float or boolean literals."),
+        },
+        Expr::Unary(syn::ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => format!("-{}", php_literal(expr)?),
+        Expr::Group(group) => php_literal(&group.expr)?,
+        _ => bail!("The arguments of `#[attribute]` must be string, integer, float or boolean literals."),
+    })
+}
+
+/// Parses an integer literal given as the value of a case, which may be
+/// negative.
+fn parse_int(expr: &Expr) -> Result<i64> {
+    match expr {
+        Expr::Lit(syn::ExprLit {
+            lit: Lit::Int(int), ..
+        }) => Ok(int.base10_parse()?),
+        Expr::Unary(syn::ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => Ok(-parse_int(expr)?),
+        Expr::Group(group) => parse_int(&group.expr),
+        Expr::Paren(paren) => parse_int(&paren.expr),
+        _ => bail!("The value of an enum case must be an integer or string literal."),
+    }
+}
+
+/// Parses the value given to a case with `#[value(...)]`.
+fn parse_value(expr: &Expr) -> Result<CaseValue> {
+    match expr {
+        Expr::Lit(syn::ExprLit {
+            lit: Lit::Str(str), ..
+        }) => Ok(CaseValue::String(str.value())),
+        expr => Ok(CaseValue::Int(parse_int(expr)?)),
+    }
+}
+
+impl CaseValue {
+    /// Returns the value as a `Discriminant`.
+    pub fn discriminant(&self) -> TokenStream {
+        match self {
+            Self::Int(value) => quote! { ::ext_php_rs::enum_::Discriminant::Int(#value) },
+            Self::String(value) => quote! { ::ext_php_rs::enum_::Discriminant::String(#value) },
+        }
+    }
+
+    /// Returns the value written as a PHP literal.
+    pub fn literal(&self) -> String {
+        match self {
+            Self::Int(value) => value.to_string(),
+            Self::String(value) => {
+                format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+            }
+        }
+    }
+}
+
+impl Enum {
+    /// Generates an implementation for `RegisteredEnum` on the enum.
+    fn registered_enum_impl(&self) -> TokenStream {
+        let ident = Ident::new(&self.ident, Span::call_site());
+        let meta = Ident::new(&format!("_{}_ENUM_META", self.ident), Span::call_site());
+        let enum_name = &self.enum_name;
+        let cases = self.cases.iter().map(|case| {
+            let name = &case.name;
+            let discriminant = match &case.value {
+                Some(value) => {
+                    let value = value.discriminant();
+                    quote! { ::std::option::Option::Some(#value) }
+                }
+                None => quote! { ::std::option::Option::None },
+            };
+            quote! {
+                ::ext_php_rs::enum_::EnumCase {
+                    name: #name,
+                    discriminant: #discriminant,
+                }
+            }
+        });
+        let variants = self
+            .cases
+            .iter()
+            .map(|case| Ident::new(&case.ident, Span::call_site()))
+            .collect::<Vec<_>>();
+        let indices = 0..self.cases.len();
+        let names = self.cases.iter().map(|case| &case.name);
+        let conversions = self.backed_conversions(&ident, &variants);
+
+        quote! {
+            static #meta: ::ext_php_rs::enum_::EnumMetadata = ::ext_php_rs::enum_::EnumMetadata::new();
+
+            impl ::ext_php_rs::enum_::RegisteredEnum for #ident {
+                const ENUM_NAME: &'static str = #enum_name;
+                const CASES: &'static [::ext_php_rs::enum_::EnumCase] = &[#(#cases,)*];
+
+                fn get_metadata() -> &'static ::ext_php_rs::enum_::EnumMetadata {
+                    &#meta
+                }
+
+                fn case(&self) -> &'static ::ext_php_rs::enum_::EnumCase {
+                    match *self {
+                        #(Self::#variants => &Self::CASES[#indices],)*
+                    }
+                }
+
+                fn from_case(name: &str) -> ::std::option::Option<Self> {
+                    match name {
+                        #(#names => ::std::option::Option::Some(Self::#variants),)*
+                        _ => ::std::option::Option::None,
+                    }
+                }
+            }
+
+            ::ext_php_rs::enum_derives!(#ident);
+
+            #conversions
+        }
+    }
+
+    /// Generates the conversions of a backed enum from and into its backing
+    /// values, which are generated from the same values as the cases
+    /// registered in PHP, so `from()` and `tryFrom()` agree with them.
+    fn backed_conversions(&self, ident: &Ident, variants: &[Ident]) -> Option<TokenStream> {
+        let (from_ty, ty, values, error_value) = match self.cases.first()?.value.as_ref()? {
+            CaseValue::Int(_) => (
+                quote! { i64 },
+                quote! { i64 },
+                self.cases
+                    .iter()
+                    .map(|case| match &case.value {
+                        Some(CaseValue::Int(value)) => quote! { #value },
+                        _ => unreachable!("cases of backed enums all have values of the same type"),
+                    })
+                    .collect::<Vec<_>>(),
+                quote! { value.to_string() },
+            ),
+            CaseValue::String(_) => (
+                quote! { &str },
+                quote! { &'static str },
+                self.cases
+                    .iter()
+                    .map(|case| match &case.value {
+                        Some(CaseValue::String(value)) => quote! { #value },
+                        _ => unreachable!("cases of backed enums all have values of the same type"),
+                    })
+                    .collect::<Vec<_>>(),
+                quote! { ::std::format!("\"{}\"", value) },
+            ),
+        };
+        let enum_name = &self.enum_name;
+
+        Some(quote! {
+            impl ::std::convert::TryFrom<#from_ty> for #ident {
+                type Error = ::ext_php_rs::error::Error;
+
+                fn try_from(value: #from_ty) -> ::std::result::Result<Self, Self::Error> {
+                    match value {
+                        #(#values => ::std::result::Result::Ok(Self::#variants),)*
+                        value => ::std::result::Result::Err(
+                            ::ext_php_rs::error::Error::InvalidEnumValue(#enum_name, #error_value),
+                        ),
+                    }
+                }
+            }
+
+            impl ::std::convert::From<#ident> for #ty {
+                fn from(value: #ident) -> Self {
+                    match value {
+                        #(#ident::#variants => #values,)*
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_valid_case_name, CaseRenameRule};
+
+    #[test]
+    fn test_rename_cases() {
+        let original = "InProgress";
+        assert_eq!("InProgress", CaseRenameRule::None.rename(original));
+        assert_eq!("inprogress", CaseRenameRule::Lower.rename(original));
+        assert_eq!("InProgress", CaseRenameRule::Pascal.rename(original));
+        assert_eq!("inProgress", CaseRenameRule::Camel.rename(original));
+        assert_eq!("in_progress", CaseRenameRule::Snake.rename(original));
+        assert_eq!(
+            "IN_PROGRESS",
+            CaseRenameRule::ScreamingSnake.rename(original)
+        );
+    }
+
+    #[test]
+    fn test_valid_case_names() {
+        for name in ["Hearts", "IN_PROGRESS", "_private", "Été", "v2"] {
+            assert!(is_valid_case_name(name), "{}", name);
+        }
+        for name in ["", "2fast", "in-progress", "two words", "class", "CLASS"] {
+            assert!(!is_valid_case_name(name), "{}", name);
+        }
+    }
+}
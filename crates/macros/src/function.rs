@@ -7,8 +7,8 @@ use darling::{FromMeta, ToTokens};
 use proc_macro2::{Ident, Literal, Span, TokenStream};
 use quote::quote;
 use syn::{
-    punctuated::Punctuated, AttributeArgs, FnArg, GenericArgument, ItemFn, Lit, PathArguments,
-    ReturnType, Signature, Token, Type, TypePath,
+    parse_quote, punctuated::Punctuated, Attribute, AttributeArgs, FnArg, GenericArgument, ItemFn,
+    Lit, PathArguments, ReturnType, Signature, Token, Type, TypePath,
 };
 
 #[derive(Default, Debug, FromMeta)]
@@ -40,12 +40,14 @@ pub struct Function {
     pub output: Option<(String, bool)>,
 }
 
-pub fn parser(args: AttributeArgs, input: ItemFn) -> Result<(TokenStream, Function)> {
+pub fn parser(args: AttributeArgs, mut input: ItemFn) -> Result<(TokenStream, Function)> {
     let attr_args = match AttrArgs::from_list(&args) {
         Ok(args) => args,
         Err(e) => bail!("Unable to parse attribute arguments: {:?}", e),
     };
 
+    keep_frame(&mut input.attrs);
+
     let ItemFn { sig, .. } = &input;
     let Signature {
         ident,
@@ -57,14 +59,21 @@ pub fn parser(args: AttributeArgs, input: ItemFn) -> Result<(TokenStream, Functi
     let internal_ident = Ident::new(&format!("_internal_php_{ident}"), Span::call_site());
     let args = build_args(inputs, &attr_args.defaults)?;
     let optional = find_optional_parameter(args.iter(), attr_args.optional);
-    let arg_definitions = build_arg_definitions(&args);
-    let arg_parser = build_arg_parser(
-        args.iter(),
-        &optional,
-        &quote! { return; },
-        ParserType::Function,
-    )?;
-    let arg_accessors = build_arg_accessors(&args);
+    let (arg_definitions, arg_parser, arg_accessors) =
+        if optional.is_none() && args.iter().all(Arg::is_scalar) {
+            build_scalar_arg_parser(&args)
+        } else {
+            (
+                build_arg_definitions(&args),
+                build_arg_parser(
+                    args.iter(),
+                    &optional,
+                    &quote! { return; },
+                    ParserType::Function,
+                )?,
+                build_arg_accessors(&args),
+            )
+        };
 
     let return_type = get_return_type(output)?;
 
@@ -109,6 +118,16 @@ pub fn parser(args: AttributeArgs, input: ItemFn) -> Result<(TokenStream, Functi
     Ok((func, function))
 }
 
+/// Prevents the function from being inlined into its handler with the
+/// `coverage` feature, so coverage tools attribute its lines to the function
+/// itself rather than to the handler.
+pub fn keep_frame(attrs: &mut Vec<Attribute>) {
+    if cfg!(feature = "coverage") {
+        attrs.retain(|attr| !attr.path.is_ident("inline"));
+        attrs.push(parse_quote!(#[inline(never)]));
+    }
+}
+
 fn build_args(
     inputs: &Punctuated<FnArg, Token![,]>,
     defaults: &HashMap<String, Lit>,
@@ -166,7 +185,13 @@ pub fn find_optional_parameter<'a>(
 
 pub enum ParserType {
     Function,
+    /// A method taking `&self` or `&mut self`, which can also be called on
+    /// instances of Rust classes extending the class.
     Method,
+    /// A method taking `#[this]` as a `ZendClassObject`.
+    ClassObjectMethod,
+    /// A method of an enum taking `&self`, which is called on a case.
+    EnumMethod,
     StaticMethod,
 }
 
@@ -206,7 +231,10 @@ pub fn build_arg_parser<'a>(
             (quote! { let parser = ex.parser(); }, None)
         }
         ParserType::Method => (
-            quote! { let (parser, this) = ex.parser_method::<Self>(); },
+            quote! {
+                let (parser, this) = ex.parser_object();
+                let this = this.and_then(::ext_php_rs::types::ZendClassObject::<Self>::upcast_mut);
+            },
             Some(quote! {
                 let this = match this {
                     Some(this) => this,
@@ -219,6 +247,50 @@ pub fn build_arg_parser<'a>(
                 };
             }),
         ),
+        ParserType::EnumMethod => (
+            quote! {
+                let (parser, this) = ex.parser_object();
+                let this = this.and_then(|this| {
+                    <Self as ::ext_php_rs::enum_::RegisteredEnum>::from_object(this)
+                });
+            },
+            Some(quote! {
+                let this = match this {
+                    Some(this) => this,
+                    None => {
+                        ::ext_php_rs::exception::PhpException::default("Failed to retrieve reference to `$this`".into())
+                            .throw()
+                            .unwrap();
+                        return;
+                    },
+                };
+            }),
+        ),
+        ParserType::ClassObjectMethod => (
+            quote! {
+                let (parser, this) = ex.parser_object();
+                let this = this.map(::ext_php_rs::types::ZendClassObject::<Self>::from_zend_obj_mut);
+            },
+            Some(quote! {
+                let this = match this {
+                    Some(Some(this)) => this,
+                    // `$this` is an instance of a Rust class extending the class, whose
+                    // layout differs from the layout of `ZendClassObject<Self>`.
+                    Some(None) => {
+                        ::ext_php_rs::exception::PhpException::default("Methods taking `#[this]` cannot be called on instances of Rust classes extending the class".into())
+                            .throw()
+                            .unwrap();
+                        return;
+                    },
+                    None => {
+                        ::ext_php_rs::exception::PhpException::default("Failed to retrieve reference to `$this`".into())
+                            .throw()
+                            .unwrap();
+                        return;
+                    },
+                };
+            }),
+        ),
     };
 
     Ok(quote! {
@@ -235,6 +307,53 @@ pub fn build_arg_parser<'a>(
     })
 }
 
+/// Builds a parser for functions which only take required scalar and string
+/// parameters. The arguments are converted directly from the call frame,
+/// without allocating an `ArgParser` and an `Arg` for each parameter.
+///
+/// Returns the argument definitions, the parser and the argument accessors,
+/// in the same form as the generic parser.
+fn build_scalar_arg_parser(args: &[Arg]) -> (Vec<TokenStream>, TokenStream, Vec<TokenStream>) {
+    let num_args = args.len() as u32;
+    let definitions = args
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| {
+            let name = &arg.name;
+            let ident = arg.get_name_ident();
+            let ty: Type = syn::parse_str(&arg.ty).unwrap();
+            quote! {
+                // SAFETY: Each argument is only borrowed once, and does not outlive the call.
+                let #ident = match unsafe { ex.arg_unchecked(#i) }
+                    .and_then(|zv| <#ty as ::ext_php_rs::convert::FromZvalMut>::from_zval_mut(zv.dereference_mut()))
+                {
+                    Some(val) => val,
+                    None => {
+                        ::ext_php_rs::exception::PhpException::default(
+                            concat!("Invalid value given for argument `", #name, "`.").into()
+                        )
+                        .throw()
+                        .expect(concat!("Failed to throw exception: Invalid value given for argument `", #name, "`."));
+                        return;
+                    }
+                };
+            }
+        })
+        .collect();
+    let parser = quote! {
+        if ex.num_args() != #num_args {
+            ::ext_php_rs::args::wrong_parameters_count_error(#num_args, #num_args, ex.num_args());
+            return;
+        }
+    };
+    let accessors = args
+        .iter()
+        .map(|arg| arg.get_name_ident().to_token_stream())
+        .collect();
+
+    (definitions, parser, accessors)
+}
+
 fn build_arg_accessors(args: &[Arg]) -> Vec<TokenStream> {
     args.iter()
         .map(|arg| arg.get_accessor(&quote! { return; }))
@@ -364,7 +483,23 @@ impl Arg {
         }
     }
 
-    #[inline]
+    /// Returns whether the argument is a required integer, float, boolean or
+    /// string, which can be parsed without the generic argument parser.
+    pub fn is_scalar(&self) -> bool {
+        const SCALARS: &[&str] = &[
+            "i8", "i16", "i32", "i64", "isize", "u8", "u16", "u32", "u64", "usize", "f32", "f64",
+            "bool", "String", "& str",
+        ];
+
+        !self.nullable
+            && !self.as_ref
+            && !self.variadic
+            && self.default.is_none()
+            && SCALARS.contains(&self.ty.as_str())
+    }
+
+    #[cfg_attr(not(feature = "coverage"), inline)]
+    #[cfg_attr(feature = "coverage", inline(never))]
     pub fn get_type_ident(&self) -> TokenStream {
         let ty: Type = syn::parse_str(&self.ty).unwrap();
         quote! {
@@ -372,7 +507,8 @@ impl Arg {
         }
     }
 
-    #[inline]
+    #[cfg_attr(not(feature = "coverage"), inline)]
+    #[cfg_attr(feature = "coverage", inline(never))]
     pub fn get_name_ident(&self) -> Ident {
         Ident::new(&self.name, Span::call_site())
     }
@@ -434,6 +570,30 @@ impl Arg {
             ::ext_php_rs::args::Arg::new(#name, #ty) #null #passed_by_ref #default #is_variadic
         }
     }
+
+    /// Returns a constant expression creating the argument information of the
+    /// argument, for use with [`Function::get_static_entry`].
+    pub fn get_static_arg_info(&self) -> TokenStream {
+        let name = &self.name;
+        let ty = if self.variadic {
+            quote! { ::ext_php_rs::flags::DataType::Mixed }
+        } else {
+            self.get_type_ident()
+        };
+        let as_ref = self.as_ref;
+        let nullable = self.nullable;
+        let variadic = self.variadic;
+        let default = match &self.default {
+            Some(val) => quote! { Some(concat!(#val, "\0")) },
+            None => quote! { None },
+        };
+
+        quote! {
+            ::ext_php_rs::args::ArgInfo::new_arg(
+                concat!(#name, "\0"), #ty, #as_ref, #nullable, #variadic, #default
+            )
+        }
+    }
 }
 
 impl Function {
@@ -476,4 +636,90 @@ impl Function {
                 .build()
         }
     }
+
+    /// Returns an expression evaluating to a function entry which is built at
+    /// compile time, or [`None`] if any of the arguments or the return type
+    /// are class objects.
+    pub fn get_static_entry(&self) -> TokenStream {
+        let name = &self.name;
+        let name_ident = self.get_name_ident();
+        let required_args = self
+            .optional
+            .as_ref()
+            .and_then(|opt| self.args.iter().position(|arg| arg.name.eq(opt)))
+            .unwrap_or(self.args.len());
+        let args = self
+            .args
+            .iter()
+            .map(|arg| arg.get_static_arg_info())
+            .collect::<Vec<_>>();
+        let len = args.len() + 1;
+        let (retval, ret_nullable) = match &self.output {
+            Some((ty, nullable)) => {
+                let ty: Type = syn::parse_str(ty).expect("failed to parse ty");
+                (
+                    quote! { Some(<#ty as ::ext_php_rs::convert::IntoZval>::TYPE) },
+                    *nullable,
+                )
+            }
+            None => (quote! { None }, false),
+        };
+
+        quote! {
+            {
+                const ARG_INFO: ::std::option::Option<[::ext_php_rs::args::ArgInfo; #len]> =
+                    ::ext_php_rs::args::ArgInfo::table([
+                        ::ext_php_rs::args::ArgInfo::new_return(#required_args, #retval, false, #ret_nullable),
+                        #(#args,)*
+                    ]);
+                const ENTRY: ::std::option::Option<::ext_php_rs::zend::FunctionEntry> = match &ARG_INFO {
+                    Some(arg_info) => Some(::ext_php_rs::zend::FunctionEntry::new_static(
+                        concat!(#name, "\0"),
+                        #name_ident,
+                        arg_info,
+                    )),
+                    None => None,
+                };
+                ENTRY
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, File, Item};
+
+    use super::parser;
+
+    #[test]
+    fn test_keep_frame() {
+        let (tokens, _) = parser(
+            vec![],
+            parse_quote! {
+                #[inline]
+                pub fn hello(n: i64) -> i64 { n }
+            },
+        )
+        .unwrap();
+        let file: File = syn::parse2(tokens).unwrap();
+        let inline = file
+            .items
+            .iter()
+            .find_map(|item| match item {
+                Item::Fn(func) if func.sig.ident == "hello" => Some(&func.attrs),
+                _ => None,
+            })
+            .unwrap()
+            .iter()
+            .filter(|attr| attr.path.is_ident("inline"))
+            .map(|attr| attr.tokens.to_string())
+            .collect::<Vec<_>>();
+
+        if cfg!(feature = "coverage") {
+            assert_eq!(inline, ["(never)"]);
+        } else {
+            assert_eq!(inline, [""]);
+        }
+    }
 }
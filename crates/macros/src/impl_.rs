@@ -90,7 +90,7 @@ pub struct AttrArgs {
 
 #[derive(Debug)]
 pub enum PropAttrTy {
-    Getter,
+    Getter { cached: bool },
     Setter,
 }
 
@@ -113,9 +113,23 @@ pub fn parser(args: AttributeArgs, input: ItemImpl) -> Result<TokenStream> {
         );
     }
 
+    if let Some(enum_) = state.enums.get_mut(&class_name) {
+        let tokens = crate::enum_::parse_impl(
+            enum_,
+            &self_ty,
+            items,
+            args.rename_methods.unwrap_or_default(),
+        )?;
+        return Ok(quote! {
+            impl #self_ty {
+                #(#tokens)*
+            }
+        });
+    }
+
     let class = state.classes.get_mut(&class_name).ok_or_else(|| {
         anyhow!(
-            "You must use `#[php_class]` on the struct before using this attribute on the impl."
+            "You must use `#[php_class]` on the struct or `#[php_enum]` on the enum before using this attribute on the impl."
         )
     })?;
 
@@ -123,7 +137,49 @@ pub fn parser(args: AttributeArgs, input: ItemImpl) -> Result<TokenStream> {
         .into_iter()
         .map(|item| {
             Ok(match item {
-                syn::ImplItem::Const(constant) => {
+                syn::ImplItem::Const(mut constant) => {
+                    let static_prop = constant
+                        .attrs
+                        .iter()
+                        .position(|attr| attr.path.is_ident("static_prop"));
+                    if let Some(idx) = static_prop {
+                        let attr = constant.attrs.remove(idx);
+                        let parsed = if attr.tokens.is_empty() {
+                            PropertyAttr::default()
+                        } else {
+                            attr.parse_args::<PropertyAttr>().map_err(|e| {
+                                anyhow!("Unable to parse `#[static_prop]` attribute: {}", e)
+                            })?
+                        };
+                        if parsed.default.is_some()
+                            || parsed.cached
+                            || parsed.flatten
+                            || parsed.prefix.is_some()
+                        {
+                            bail!("Static properties only accept the `rename`, `vis` and `flags` options.");
+                        }
+
+                        let name = parsed
+                            .rename
+                            .unwrap_or_else(|| constant.ident.to_string());
+                        if class.properties.contains_key(&name) {
+                            bail!("Static property `{}` conflicts with another property.", name);
+                        }
+                        let mut prop = Property::static_(
+                            &constant,
+                            &class_name,
+                            get_docs(&constant.attrs),
+                            parsed.flags.map(|flags| flags.to_token_stream().to_string()),
+                        );
+                        prop.vis = parsed.vis;
+                        class.properties.insert(name, prop);
+
+                        return Ok(quote! {
+                            #[allow(dead_code)]
+                            #constant
+                        });
+                    }
+
                     class.constants.push(Constant {
                         name: constant.ident.to_string(),
                         // visibility: Visibility::Public,
@@ -137,8 +193,12 @@ pub fn parser(args: AttributeArgs, input: ItemImpl) -> Result<TokenStream> {
                     }
                 }
                 syn::ImplItem::Method(method) => {
-                    let parsed_method =
-                        method::parser(&self_ty, method, args.rename_methods.unwrap_or_default())?;
+                    let parsed_method = method::parser(
+                        &self_ty,
+                        method,
+                        args.rename_methods.unwrap_or_default(),
+                        false,
+                    )?;
 
                     // TODO(david): How do we handle comments for getter/setter? Take the comments
                     // from the methods??
@@ -150,7 +210,19 @@ pub fn parser(args: AttributeArgs, input: ItemImpl) -> Result<TokenStream> {
                         let ident = parsed_method.method.orig_ident.clone();
 
                         match ty {
-                            PropAttrTy::Getter => prop.add_getter(ident)?,
+                            PropAttrTy::Getter { cached } => {
+                                prop.add_getter(ident.clone())?;
+                                if cached {
+                                    let output =
+                                        parsed_method.method.output.clone().ok_or_else(|| {
+                                            anyhow!(
+                                                "Cached getter `{}` must return a value.",
+                                                ident
+                                            )
+                                        })?;
+                                    prop.set_cached(output)?;
+                                }
+                            }
                             PropAttrTy::Setter => prop.add_setter(ident)?,
                         }
                     }
@@ -160,6 +232,13 @@ pub fn parser(args: AttributeArgs, input: ItemImpl) -> Result<TokenStream> {
                         }
                         class.constructor = Some(parsed_method.method);
                     } else {
+                        let name = parsed_method.method.name.to_ascii_lowercase();
+                        if class.delegate.is_some() && (name == "__call" || name == "__get") {
+                            bail!(
+                                "Classes with a `#[delegate]` field cannot declare `{}()`.",
+                                parsed_method.method.name
+                            );
+                        }
                         class.methods.push(parsed_method.method);
                     }
                     parsed_method.tokens
@@ -223,17 +302,17 @@ pub fn parse_attribute(attr: &Attribute) -> Result<Option<ParsedAttribute>> {
             ParsedAttribute::Rename(ident)
         }
         "getter" => {
-            let prop_name = if attr.tokens.is_empty() {
-                None
+            let parsed = if attr.tokens.is_empty() {
+                PropertyAttr::default()
             } else {
-                let parsed: PropertyAttr = attr
-                    .parse_args()
-                    .map_err(|e| anyhow!("Unable to parse `#[getter]` attribute: {}", e))?;
-                parsed.rename
+                attr.parse_args()
+                    .map_err(|e| anyhow!("Unable to parse `#[getter]` attribute: {}", e))?
             };
             ParsedAttribute::Property {
-                prop_name,
-                ty: PropAttrTy::Getter,
+                prop_name: parsed.rename,
+                ty: PropAttrTy::Getter {
+                    cached: parsed.cached,
+                },
             }
         }
         "setter" => {
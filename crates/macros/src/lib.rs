@@ -1,5 +1,6 @@
 mod class;
 mod constant;
+mod enum_;
 mod extern_;
 mod fastcall;
 mod function;
@@ -9,6 +10,7 @@ mod method;
 mod module;
 mod startup_function;
 mod syn_ext;
+mod test_function;
 mod zval;
 
 use std::{
@@ -20,8 +22,8 @@ use constant::Constant;
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use syn::{
-    parse_macro_input, AttributeArgs, DeriveInput, ItemConst, ItemFn, ItemForeignMod, ItemImpl,
-    ItemStruct,
+    parse_macro_input, AttributeArgs, DeriveInput, ItemConst, ItemEnum, ItemFn, ItemForeignMod,
+    ItemImpl, ItemStruct,
 };
 
 extern crate proc_macro;
@@ -30,6 +32,7 @@ extern crate proc_macro;
 struct State {
     functions: Vec<function::Function>,
     classes: HashMap<String, class::Class>,
+    enums: HashMap<String, enum_::Enum>,
     constants: Vec<Constant>,
     startup_function: Option<String>,
     built_module: bool,
@@ -63,6 +66,18 @@ pub fn php_class(args: TokenStream, input: TokenStream) -> TokenStream {
     .into()
 }
 
+#[proc_macro_attribute]
+pub fn php_enum(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as AttributeArgs);
+    let input = parse_macro_input!(input as ItemEnum);
+
+    match enum_::parser(args, input) {
+        Ok(parsed) => parsed,
+        Err(e) => syn::Error::new(Span::call_site(), e).to_compile_error(),
+    }
+    .into()
+}
+
 #[proc_macro_attribute]
 pub fn php_function(args: TokenStream, input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(args as AttributeArgs);
@@ -76,10 +91,11 @@ pub fn php_function(args: TokenStream, input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro_attribute]
-pub fn php_module(_: TokenStream, input: TokenStream) -> TokenStream {
+pub fn php_module(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as AttributeArgs);
     let input = parse_macro_input!(input as ItemFn);
 
-    match module::parser(input) {
+    match module::parser(args, input) {
         Ok(parsed) => parsed,
         Err(e) => syn::Error::new(Span::call_site(), e).to_compile_error(),
     }
@@ -98,6 +114,18 @@ pub fn php_startup(args: TokenStream, input: TokenStream) -> TokenStream {
     .into()
 }
 
+#[proc_macro_attribute]
+pub fn php_test(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as AttributeArgs);
+    let input = parse_macro_input!(input as ItemFn);
+
+    match test_function::parser(args, input) {
+        Ok(parsed) => parsed,
+        Err(e) => syn::Error::new(Span::call_site(), e).to_compile_error(),
+    }
+    .into()
+}
+
 #[proc_macro_attribute]
 pub fn php_impl(args: TokenStream, input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(args as AttributeArgs);
@@ -132,7 +160,7 @@ pub fn php_extern(_: TokenStream, input: TokenStream) -> TokenStream {
     .into()
 }
 
-#[proc_macro_derive(ZvalConvert)]
+#[proc_macro_derive(ZvalConvert, attributes(php))]
 pub fn zval_convert_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
@@ -143,6 +171,17 @@ pub fn zval_convert_derive(input: TokenStream) -> TokenStream {
     .into()
 }
 
+#[proc_macro_derive(FlattenProps, attributes(prop))]
+pub fn flatten_props_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match class::flatten_props_parser(input) {
+        Ok(parsed) => parsed,
+        Err(e) => syn::Error::new(Span::call_site(), e).to_compile_error(),
+    }
+    .into()
+}
+
 #[proc_macro]
 pub fn zend_fastcall(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as ItemFn);
@@ -5,6 +5,7 @@ use syn::ReturnType;
 
 use crate::helpers::get_docs;
 use crate::{
+    class::Property,
     function::{self, ParserType},
     impl_::{parse_attribute, ParsedAttribute, PropAttrTy, RenameRule, Visibility},
 };
@@ -65,10 +66,12 @@ impl ParsedMethod {
     }
 }
 
+/// Parses a method of a class, or of an enum when `is_enum` is set.
 pub fn parser(
     struct_ty: &Type,
     mut input: ImplItemMethod,
     rename_rule: RenameRule,
+    is_enum: bool,
 ) -> Result<ParsedMethod> {
     let mut defaults = HashMap::new();
     let mut optional = None;
@@ -113,6 +116,7 @@ pub fn parser(
     }
 
     input.attrs.clear();
+    function::keep_frame(&mut input.attrs);
 
     let ident = &input.sig.ident;
     let name = identifier.unwrap_or_else(|| rename_rule.rename(ident.to_string()));
@@ -139,13 +143,24 @@ pub fn parser(
         optional,
     );
     let (arg_definitions, method_type) = build_arg_definitions(&args);
+    if is_enum {
+        if matches!(method_type, MethodType::ReceiverClassObject) {
+            bail!("`#[this]` cannot be used in the methods of enums.");
+        }
+        if matches!(input.sig.inputs.first(), Some(FnArg::Receiver(receiver)) if receiver.mutability.is_some())
+        {
+            bail!("The methods of enums cannot take `&mut self`, as enum cases are immutable.");
+        }
+    }
     let arg_parser = build_arg_parser(
         args.iter(),
         &optional,
         &bail,
         match method_type {
             MethodType::Static => ParserType::StaticMethod,
-            _ => ParserType::Method,
+            MethodType::ReceiverClassObject => ParserType::ClassObjectMethod,
+            MethodType::Receiver if is_enum => ParserType::EnumMethod,
+            MethodType::Receiver => ParserType::Method,
         },
     )?;
     let arg_accessors = build_arg_accessors(&args, &bail);
@@ -394,6 +409,42 @@ impl Method {
     }
 
     pub fn get_arg_definitions(&self) -> impl Iterator<Item = TokenStream> + '_ {
+        self.arg_definitions(|_| false)
+    }
+
+    /// Returns the argument definitions of a constructor, marking the
+    /// arguments which share their name with the field of a field property as
+    /// promoted.
+    ///
+    /// # Parameters
+    ///
+    /// * `properties` - The properties of the class.
+    pub fn get_constructor_arg_definitions<'a>(
+        &'a self,
+        properties: &'a HashMap<String, Property>,
+    ) -> impl Iterator<Item = TokenStream> + 'a {
+        self.arg_definitions(move |name| {
+            properties
+                .values()
+                .any(|prop| prop.field_name() == Some(name))
+        })
+    }
+
+    /// Returns whether the constructor has an argument initializing the given
+    /// field property. Arguments are matched with the name of the field, as
+    /// the property may be renamed.
+    pub fn promotes(&self, prop: &Property) -> bool {
+        prop.field_name().is_some_and(|field_name| {
+            self.args
+                .iter()
+                .any(|arg| matches!(arg, Arg::Typed(arg) if arg.name == field_name))
+        })
+    }
+
+    fn arg_definitions<'a>(
+        &'a self,
+        promoted: impl Fn(&str) -> bool + 'a,
+    ) -> impl Iterator<Item = TokenStream> + 'a {
         self.args.iter().filter_map(move |arg| match arg {
             Arg::Typed(arg) => {
                 let def = arg.get_arg_definition();
@@ -404,7 +455,8 @@ impl Method {
                         None
                     }
                 });
-                Some(quote! { #prelude.arg(#def) })
+                let promoted = promoted(&arg.name).then(|| quote! { .promoted() });
+                Some(quote! { #prelude.arg(#def #promoted) })
             }
             _ => None,
         })
@@ -1,17 +1,27 @@
 use std::sync::MutexGuard;
 
 use anyhow::{anyhow, bail, Result};
+use darling::FromMeta;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
-use syn::{ItemFn, Signature, Type};
+use syn::{AttributeArgs, ItemFn, Signature, Type};
 
 use crate::{
-    class::{Class, Property},
+    class::{generate_flatten_props_impl, properties_expr, Class, Property},
+    enum_::{Enum, EnumCase},
     function::{Arg, Function},
     startup_function, State, STATE,
 };
 
-pub fn parser(input: ItemFn) -> Result<TokenStream> {
+#[derive(Default, Debug, FromMeta)]
+#[darling(default)]
+struct ModuleArgs {
+    static_functions: bool,
+}
+
+pub fn parser(args: AttributeArgs, input: ItemFn) -> Result<TokenStream> {
+    let args = ModuleArgs::from_list(&args)
+        .map_err(|e| anyhow!("Unable to parse attribute arguments: {:?}", e))?;
     let ItemFn { sig, block, .. } = input;
     let Signature { output, inputs, .. } = sig;
     let stmts = &block.stmts;
@@ -25,9 +35,9 @@ pub fn parser(input: ItemFn) -> Result<TokenStream> {
     state.built_module = true;
 
     // Generate startup function if one hasn't already been tagged with the macro.
-    let startup_fn = if (!state.classes.is_empty() || !state.constants.is_empty())
-        && state.startup_function.is_none()
-    {
+    let has_exports =
+        !state.classes.is_empty() || !state.enums.is_empty() || !state.constants.is_empty();
+    let startup_fn = if has_exports && state.startup_function.is_none() {
         drop(state);
 
         let parsed = syn::parse2(quote! {
@@ -45,7 +55,20 @@ pub fn parser(input: ItemFn) -> Result<TokenStream> {
     let functions = state
         .functions
         .iter()
-        .map(|func| func.get_builder())
+        .map(|func| {
+            let builder = func.get_builder();
+            if args.static_functions {
+                let entry = func.get_static_entry();
+                quote! {
+                    match #entry {
+                        Some(entry) => entry,
+                        None => #builder.unwrap(),
+                    }
+                }
+            } else {
+                quote! { #builder.unwrap() }
+            }
+        })
         .collect::<Vec<_>>();
     let startup = state.startup_function.as_ref().map(|ident| {
         let ident = Ident::new(ident, Span::call_site());
@@ -77,7 +100,7 @@ pub fn parser(input: ItemFn) -> Result<TokenStream> {
                 env!("CARGO_PKG_VERSION")
             )
             #startup
-            #(.function(#functions.unwrap()))*
+            #(.function(#functions))*
             ;
 
             // TODO allow result return types
@@ -99,13 +122,9 @@ pub fn generate_registered_class_impl(class: &Class) -> Result<TokenStream> {
     let self_ty = Ident::new(&class.struct_path, Span::call_site());
     let class_name = &class.class_name;
     let meta = Ident::new(&format!("_{}_META", &class.struct_path), Span::call_site());
-    let prop_tuples = class
-        .properties
-        .iter()
-        .map(|(name, prop)| prop.as_prop_tuple(name));
     let constructor = if let Some(constructor) = &class.constructor {
         let func = Ident::new(&constructor.ident, Span::call_site());
-        let args = constructor.get_arg_definitions();
+        let args = constructor.get_constructor_arg_definitions(&class.properties);
         quote! {
             Some(::ext_php_rs::class::ConstructorMeta {
                 constructor: Self::#func,
@@ -122,6 +141,131 @@ pub fn generate_registered_class_impl(class: &Class) -> Result<TokenStream> {
     } else {
         quote! { None }
     };
+    let gc = if class.gc {
+        quote! { Some(<Self as ::ext_php_rs::zend::CollectGarbage>::collect_garbage) }
+    } else if !class.gc_fields.is_empty() {
+        let fields = class
+            .gc_fields
+            .iter()
+            .map(|field| crate::class::field_member(field));
+        quote! {
+            Some({
+                fn gc(this: &#self_ty, buf: &mut ::ext_php_rs::zend::GcBuffer) {
+                    #(::ext_php_rs::zend::CollectGarbage::collect_garbage(&this.#fields, buf);)*
+                }
+                gc
+            })
+        }
+    } else {
+        quote! { None }
+    };
+    let delegate = match &class.delegate {
+        Some(field) => {
+            let field = crate::class::field_member(field);
+            quote! {
+                Some({
+                    fn delegate(this: &mut #self_ty) -> ::std::option::Option<&mut ::ext_php_rs::types::ZendObject> {
+                        ::ext_php_rs::class::Delegate::delegate_object(&mut this.#field)
+                    }
+                    delegate
+                })
+            }
+        }
+        None => quote! { None },
+    };
+
+    let debug_info = if class.debug_info {
+        quote! { Some(<Self as ::ext_php_rs::class::PhpDebugInfo>::debug_info) }
+    } else if class.debug {
+        quote! { Some(::ext_php_rs::class::debug_info_from_debug::<Self>) }
+    } else {
+        quote! { None }
+    };
+
+    let cast = if class.cast {
+        quote! { Some(<Self as ::ext_php_rs::class::PhpCast>::cast) }
+    } else {
+        quote! { None }
+    };
+
+    let compare = if class.compare {
+        quote! { Some(<Self as ::ext_php_rs::class::PhpCompare>::compare) }
+    } else {
+        quote! { None }
+    };
+
+    let dimensions = if class.array_access {
+        quote! {
+            Some(::ext_php_rs::class::DimensionMeta {
+                exists: <Self as ::ext_php_rs::class::PhpArrayAccess>::offset_exists,
+                get: <Self as ::ext_php_rs::class::PhpArrayAccess>::offset_get,
+                set: <Self as ::ext_php_rs::class::PhpArrayAccess>::offset_set,
+                unset: <Self as ::ext_php_rs::class::PhpArrayAccess>::offset_unset,
+            })
+        }
+    } else {
+        quote! { None }
+    };
+
+    let property_changed = if class.observe {
+        quote! { Some(<Self as ::ext_php_rs::class::PhpPropertyObserver>::property_changed) }
+    } else {
+        quote! { None }
+    };
+
+    let iterator = if class.iterator {
+        quote! { Some(::ext_php_rs::class::native_iter::<Self>) }
+    } else {
+        quote! { None }
+    };
+
+    // Classes extending a Rust class include the properties of the parent, and
+    // can be used as the parent through `Deref`.
+    let parent_class = class.parent_class.as_ref().map(|parent| {
+        let parent: syn::Path =
+            syn::parse_str(parent).expect("failed to parse previously parsed path");
+        (
+            quote! {
+                .chain(::ext_php_rs::props::Property::flatten::<#parent>(
+                    "",
+                    |this: &Self| ::std::ops::Deref::deref(this),
+                    |this: &mut Self| ::std::ops::DerefMut::deref_mut(this),
+                ))
+            },
+            quote! {
+                fn upcast(&self, ty: ::std::any::TypeId) -> ::std::option::Option<&dyn ::std::any::Any> {
+                    if ty == ::std::any::TypeId::of::<Self>() {
+                        return ::std::option::Option::Some(self);
+                    }
+                    <#parent as ::ext_php_rs::class::RegisteredClass>::upcast(
+                        ::std::ops::Deref::deref(self),
+                        ty,
+                    )
+                }
+
+                fn upcast_mut(&mut self, ty: ::std::any::TypeId) -> ::std::option::Option<&mut dyn ::std::any::Any> {
+                    if ty == ::std::any::TypeId::of::<Self>() {
+                        return ::std::option::Option::Some(self);
+                    }
+                    <#parent as ::ext_php_rs::class::RegisteredClass>::upcast_mut(
+                        ::std::ops::DerefMut::deref_mut(self),
+                        ty,
+                    )
+                }
+            },
+        )
+    });
+    let (parent_props, upcast) = match parent_class {
+        Some((props, upcast)) => (Some(props), Some(upcast)),
+        None => (None, None),
+    };
+    let props = properties_expr(&class.properties, &class.flattened, parent_props);
+    let flatten_props = generate_flatten_props_impl(
+        &self_ty,
+        &class.properties,
+        &class.flattened,
+        quote! { <Self as ::ext_php_rs::class::RegisteredClass>::get_properties() },
+    );
 
     Ok(quote! {
         static #meta: ::ext_php_rs::class::ClassMetadata<#self_ty> = ::ext_php_rs::class::ClassMetadata::new();
@@ -131,19 +275,41 @@ pub fn generate_registered_class_impl(class: &Class) -> Result<TokenStream> {
             const CONSTRUCTOR: ::std::option::Option<
                 ::ext_php_rs::class::ConstructorMeta<Self>
             > = #constructor;
+            const GC: ::std::option::Option<
+                fn(&Self, &mut ::ext_php_rs::zend::GcBuffer)
+            > = #gc;
+            const DEBUG_INFO: ::std::option::Option<
+                fn(&Self, &mut ::ext_php_rs::types::ZendHashTable) -> ::ext_php_rs::exception::PhpResult
+            > = #debug_info;
+            const CAST: ::std::option::Option<
+                fn(&Self, ::ext_php_rs::class::CastType) -> ::ext_php_rs::class::CastResult
+            > = #cast;
+            const COMPARE: ::std::option::Option<
+                fn(&Self, &Self) -> ::std::option::Option<::std::cmp::Ordering>
+            > = #compare;
+            const DIMENSIONS: ::std::option::Option<
+                ::ext_php_rs::class::DimensionMeta<Self>
+            > = #dimensions;
+            const PROPERTY_CHANGED: ::std::option::Option<fn(&mut Self, &str)> = #property_changed;
+            const ITERATOR: ::std::option::Option<
+                fn(&Self) -> ::ext_php_rs::class::NativeIter
+            > = #iterator;
+            const DELEGATE: ::std::option::Option<
+                fn(&mut Self) -> ::std::option::Option<&mut ::ext_php_rs::types::ZendObject>
+            > = #delegate;
 
             fn get_metadata() -> &'static ::ext_php_rs::class::ClassMetadata<Self> {
                 &#meta
             }
 
             fn get_properties<'a>() -> ::std::collections::HashMap<&'static str, ::ext_php_rs::props::Property<'a, Self>> {
-                use ::std::iter::FromIterator;
-
-                ::std::collections::HashMap::from_iter([
-                    #(#prop_tuples)*
-                ])
+                #props
             }
+
+            #upcast
         }
+
+        #flatten_props
     })
 }
 
@@ -228,12 +394,20 @@ impl Describe for Arg {
 impl Describe for Class {
     fn describe(&self) -> TokenStream {
         let name = &self.class_name;
-        let extends = if let Some(parent) = &self.parent {
+        let extends = if let Some(parent) = &self.parent_class {
+            let parent: syn::Path =
+                syn::parse_str(parent).expect("failed to parse previously parsed path");
+            quote! { Some(<#parent as ::ext_php_rs::class::RegisteredClass>::CLASS_NAME.into()) }
+        } else if let Some(parent) = &self.parent {
             quote! { Some(#parent.into()) }
         } else {
             quote! { None }
         };
         let interfaces = self.interfaces.iter().map(|iface| quote! { #iface.into() });
+        let traits = self.traits.iter().map(|trait_| {
+            let name = trait_.stub_name();
+            quote! { #name.into() }
+        });
         let properties = self.properties.iter().map(|d| d.describe());
         let mut methods: Vec<_> = self.methods.iter().map(Describe::describe).collect();
         let docs = self.docs.iter().map(|c| {
@@ -253,6 +427,7 @@ impl Describe for Class {
                 docs: DocBlock(vec![#(#docs,)*].into()),
                 extends: abi::Option::#extends,
                 implements: vec![#(#interfaces,)*].into(),
+                uses: vec![#(#traits,)*].into(),
                 properties: vec![#(#properties,)*].into(),
                 methods: vec![#(#methods,)*].into(),
                 constants: vec![#(#constants,)*].into(),
@@ -261,6 +436,73 @@ impl Describe for Class {
     }
 }
 
+impl Describe for Enum {
+    fn describe(&self) -> TokenStream {
+        let name = &self.enum_name;
+        let docs = self.docs.iter().map(|c| {
+            quote! {
+                #c.into()
+            }
+        });
+        let backing = match self.cases.first().and_then(|case| case.value.as_ref()) {
+            Some(crate::enum_::CaseValue::Int(_)) => {
+                quote! { Some(::ext_php_rs::flags::DataType::Long) }
+            }
+            Some(crate::enum_::CaseValue::String(_)) => {
+                quote! { Some(::ext_php_rs::flags::DataType::String) }
+            }
+            None => quote! { None },
+        };
+        let interfaces = self.interfaces.iter().map(|iface| quote! { #iface.into() });
+        let cases = self.cases.iter().map(Describe::describe);
+        let methods = self.methods.iter().map(Describe::describe);
+        let constants = self.constants.iter().map(Describe::describe);
+
+        quote! {
+            Enum {
+                name: #name.into(),
+                docs: DocBlock(vec![#(#docs,)*].into()),
+                backing: abi::Option::#backing,
+                implements: vec![#(#interfaces,)*].into(),
+                cases: vec![#(#cases,)*].into(),
+                methods: vec![#(#methods,)*].into(),
+                constants: vec![#(#constants,)*].into(),
+            }
+        }
+    }
+}
+
+impl Describe for EnumCase {
+    fn describe(&self) -> TokenStream {
+        let name = &self.name;
+        let docs = self.docs.iter().map(|doc| {
+            quote! {
+                #doc.into()
+            }
+        });
+        let value = match &self.value {
+            Some(value) => {
+                let value = value.literal();
+                quote! { Some(#value.into()) }
+            }
+            None => quote! { None },
+        };
+        let attributes = self
+            .attributes
+            .iter()
+            .map(|attr| format!("{}({})", attr.name, attr.literals.join(", ")));
+
+        quote! {
+            EnumCase {
+                name: #name.into(),
+                docs: DocBlock(vec![#(#docs,)*].into()),
+                value: abi::Option::#value,
+                attributes: vec![#(#attributes.into(),)*].into(),
+            }
+        }
+    }
+}
+
 impl Describe for (&String, &Property) {
     fn describe(&self) -> TokenStream {
         let name = self.0;
@@ -270,16 +512,33 @@ impl Describe for (&String, &Property) {
             }
         });
 
-        // TODO(david): store metadata for ty, vis, static, null, default
+        let (ty, nullable) = match self.1.php_type() {
+            Some((ty, nullable)) => (quote! { Some(#ty) }, nullable),
+            None => (quote! { None }, false),
+        };
+
+        let default = match self.1.default_literal() {
+            Some(default) => quote! { Some(#default.into()) },
+            None => quote! { None },
+        };
+
+        let vis = self
+            .1
+            .vis
+            .as_ref()
+            .unwrap_or(&crate::impl_::Visibility::Public)
+            .describe();
+
+        let static_ = self.1.is_static();
         quote! {
             Property {
                 name: #name.into(),
                 docs: DocBlock(vec![#(#docs,)*].into()),
-                ty: abi::Option::None,
-                vis: Visibility::Public,
-                static_: false,
-                nullable: false,
-                default: abi::Option::None,
+                ty: abi::Option::#ty,
+                vis: #vis,
+                static_: #static_,
+                nullable: #nullable,
+                default: abi::Option::#default,
             }
         }
     }
@@ -367,6 +626,9 @@ impl Describe for State {
     fn describe(&self) -> TokenStream {
         let functs = self.functions.iter().map(Describe::describe);
         let classes = self.classes.values().map(|class| class.describe());
+        let mut enums = self.enums.values().collect::<Vec<_>>();
+        enums.sort_by(|a, b| a.enum_name.cmp(&b.enum_name));
+        let enums = enums.into_iter().map(Describe::describe);
         let constants = self.constants.iter().map(Describe::describe);
 
         quote! {
@@ -374,6 +636,7 @@ impl Describe for State {
                 name: env!("CARGO_PKG_NAME").into(),
                 functions: vec![#(#functs,)*].into(),
                 classes: vec![#(#classes,)*].into(),
+                enums: vec![#(#enums,)*].into(),
                 constants: vec![#(#constants,)*].into(),
             }
         }
@@ -1,12 +1,12 @@
 use std::collections::HashMap;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use darling::FromMeta;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 use syn::{AttributeArgs, Expr, ItemFn, Signature};
 
-use crate::{class::Class, constant::Constant, STATE};
+use crate::{class::Class, constant::Constant, enum_::Enum, STATE};
 
 #[derive(Default, Debug, FromMeta)]
 #[darling(default)]
@@ -30,6 +30,7 @@ pub fn parser(args: Option<AttributeArgs>, input: ItemFn) -> Result<TokenStream>
     state.startup_function = Some(ident.to_string());
 
     let classes = build_classes(&state.classes)?;
+    let enums = build_enums(&state.enums)?;
     let constants = build_constants(&state.constants);
     let (before, after) = if args.before {
         (Some(quote! { internal(ty, module_number); }), None)
@@ -51,6 +52,7 @@ pub fn parser(args: Option<AttributeArgs>, input: ItemFn) -> Result<TokenStream>
 
             #before
             #(#classes)*
+            #(#enums)*
             #(#constants)*
             #after
 
@@ -63,8 +65,8 @@ pub fn parser(args: Option<AttributeArgs>, input: ItemFn) -> Result<TokenStream>
 
 /// Returns a vector of `ClassBuilder`s for each class.
 fn build_classes(classes: &HashMap<String, Class>) -> Result<Vec<TokenStream>> {
-    classes
-        .iter()
+    sort_classes(classes)?
+        .into_iter()
         .map(|(name, class)| {
             let Class { class_name, .. } = &class;
             let ident = Ident::new(name, Span::call_site());
@@ -103,35 +105,38 @@ fn build_classes(classes: &HashMap<String, Class>) -> Result<Vec<TokenStream>> {
                     Ok(quote! { .implements(#expr) })
                 })
                 .collect::<Result<Vec<_>>>()?;
-            // TODO(david): register properties for reflection (somehow)
-            // let properties = class
-            //     .properties
-            //     .iter()
-            //     .map(|(name, (default, flags))| {
-            //         let default_expr: Expr = syn::parse_str(default).map_err(|_| {
-            //             anyhow!(
-            //                 "Invalid default value given for property `{}` type: `{}`",
-            //                 name,
-            //                 default
-            //             )
-            //         })?;
-            //         let flags_expr: Expr = syn::parse_str(
-            //             flags
-            //                 .as_ref()
-            //                 .map(|flags| flags.as_str())
-            //                 .unwrap_or("PropertyFlags::Public"),
-            //         )
-            //         .map_err(|_| {
-            //             anyhow!(
-            //                 "Invalid default value given for property `{}` type: `{}`",
-            //                 name,
-            //                 default
-            //             )
-            //         })?;
-
-            //         Ok(quote! { .property(#name, #default_expr, #flags_expr) })
-            //     })
-            //     .collect::<Result<Vec<_>>>()?;
+            let traits = class
+                .traits
+                .iter()
+                .map(|trait_| {
+                    let expr: Expr = syn::parse_str(&trait_.ce).map_err(|_| {
+                        anyhow!(
+                            "Invalid expression given for `{}` trait: `{}`",
+                            class_name,
+                            trait_.ce
+                        )
+                    })?;
+                    Ok(quote! { .uses_trait(#expr) })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let mut properties = class.properties.iter().collect::<Vec<_>>();
+            properties.sort_by_key(|(name, _)| *name);
+            let properties = properties
+                .into_iter()
+                .filter_map(|(name, prop)| {
+                    let promoted = class
+                        .constructor
+                        .as_ref()
+                        .is_some_and(|ctor| ctor.promotes(prop));
+                    prop.as_declaration(name, promoted)
+                });
+            let flattened = class.flattened.iter().map(|field| {
+                let ty = field.ty();
+                let prefix = &field.prefix;
+                quote! {
+                    let builder = <#ty as ::ext_php_rs::props::FlattenProps>::declare_properties(builder, #prefix);
+                }
+            });
             let class_modifier = class.modifier.as_ref().map(|modifier| {
                 let modifier = Ident::new(modifier, Span::call_site());
                 quote! {
@@ -169,11 +174,13 @@ fn build_classes(classes: &HashMap<String, Class>) -> Result<Vec<TokenStream>> {
                     #(#methods)*
                     #(#constants)*
                     #(#interfaces)*
-                    // #(#properties)*
+                    #(#traits)*
+                    #(#properties)*
                     #parent
                     #flags
                     #object_override
                     ;
+                #(#flattened)*
                 #class_modifier
                 let class = builder.build()
                     .expect(concat!("Unable to build class `", #class_name, "`"));
@@ -184,6 +191,123 @@ fn build_classes(classes: &HashMap<String, Class>) -> Result<Vec<TokenStream>> {
         .collect::<Result<Vec<_>>>()
 }
 
+/// Orders the classes so that classes extending a Rust class with
+/// `#[extends_class]` are built after their parent.
+fn sort_classes(classes: &HashMap<String, Class>) -> Result<Vec<(&String, &Class)>> {
+    fn visit<'a>(
+        name: &'a String,
+        classes: &'a HashMap<String, Class>,
+        visiting: &mut Vec<&'a String>,
+        sorted: &mut Vec<(&'a String, &'a Class)>,
+    ) -> Result<()> {
+        if sorted.iter().any(|(sorted, _)| *sorted == name) {
+            return Ok(());
+        }
+        if visiting.contains(&name) {
+            bail!("Class `{}` extends itself.", name);
+        }
+        let class = &classes[name];
+        let parent = class
+            .parent_class
+            .as_ref()
+            .and_then(|parent| parent.rsplit("::").next())
+            .map(str::trim)
+            .and_then(|parent| classes.get_key_value(parent));
+        if let Some((parent, _)) = parent {
+            visiting.push(name);
+            visit(parent, classes, visiting, sorted)?;
+            visiting.pop();
+        }
+        sorted.push((name, class));
+        Ok(())
+    }
+
+    let mut names = classes.keys().collect::<Vec<_>>();
+    names.sort();
+    let mut sorted = Vec::with_capacity(classes.len());
+    for name in names {
+        visit(name, classes, &mut vec![], &mut sorted)?;
+    }
+    Ok(sorted)
+}
+
+/// Returns an `EnumBuilder` for each enum, which registers the cases from the
+/// `RegisteredEnum` implementation of the enum.
+fn build_enums(enums: &HashMap<String, Enum>) -> Result<Vec<TokenStream>> {
+    let mut enums = enums.iter().collect::<Vec<_>>();
+    enums.sort_by_key(|(name, _)| *name);
+    enums
+        .into_iter()
+        .map(|(name, enum_)| {
+            let Enum { enum_name, .. } = &enum_;
+            let ident = Ident::new(name, Span::call_site());
+            let methods = enum_.methods.iter().map(|method| {
+                let builder = method.get_builder(&ident);
+                let flags = method.get_flags();
+                quote! { .method(#builder.unwrap(), #flags) }
+            });
+            let constants = enum_.constants.iter().map(|constant| {
+                let name = &constant.name;
+                let val = constant.val_tokens();
+                quote! { .constant(#name, #val).unwrap() }
+            });
+            let interfaces = enum_
+                .interfaces
+                .iter()
+                .map(|interface| {
+                    let expr: Expr = syn::parse_str(interface).map_err(|_| {
+                        anyhow!(
+                            "Invalid expression given for `{}` interface: `{}`",
+                            enum_name,
+                            interface
+                        )
+                    })?;
+                    Ok(quote! { .implements(#expr) })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let attributes = enum_
+                .cases
+                .iter()
+                .flat_map(|case| case.attributes.iter().map(move |attr| (case, attr)))
+                .map(|(case, attr)| {
+                    let case_name = &case.name;
+                    let attr_name = &attr.name;
+                    let args = attr
+                        .args
+                        .iter()
+                        .map(|arg| syn::parse_str::<Expr>(arg))
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|_| {
+                            anyhow!("Invalid argument given for `{}` attribute", attr_name)
+                        })?;
+                    Ok(quote! {
+                        .case_attribute(#case_name, #attr_name, vec![
+                            #(::ext_php_rs::convert::IntoZval::into_zval(#args, true).unwrap(),)*
+                        ])
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok(quote! {{
+                use ::ext_php_rs::enum_::RegisteredEnum;
+
+                let mut builder = ::ext_php_rs::builders::EnumBuilder::new(#enum_name)
+                    #(#methods)*
+                    #(#constants)*
+                    #(#interfaces)*;
+                for case in <#ident as RegisteredEnum>::CASES {
+                    builder = builder.case(case.name, case.discriminant);
+                }
+                builder = builder #(#attributes)*;
+                let enum_ = builder.build()
+                    .expect(concat!("Unable to build enum `", #enum_name, "`"));
+
+                <#ident as RegisteredEnum>::get_metadata().set_ce(enum_);
+            }})
+        })
+        .collect()
+}
+
 fn build_constants(constants: &[Constant]) -> Vec<TokenStream> {
     constants
         .iter()
@@ -0,0 +1,44 @@
+use anyhow::{anyhow, bail, Result};
+use darling::FromMeta;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{AttributeArgs, ItemFn, Path};
+
+#[derive(Default, Debug, FromMeta)]
+#[darling(default)]
+struct TestArgs {
+    module: Option<Path>,
+}
+
+pub fn parser(args: AttributeArgs, input: ItemFn) -> Result<TokenStream> {
+    let args = TestArgs::from_list(&args)
+        .map_err(|e| anyhow!("Unable to parse attribute arguments: {:?}", e))?;
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = input;
+
+    if !sig.inputs.is_empty() {
+        bail!("Functions annotated with `#[php_test]` cannot take arguments.");
+    }
+    if sig.asyncness.is_some() {
+        bail!("Functions annotated with `#[php_test]` cannot be `async`.");
+    }
+
+    let module = args
+        .module
+        .map_or_else(|| quote! { crate::get_module }, |module| quote! { #module });
+
+    Ok(quote! {
+        #[test]
+        #(#attrs)*
+        #vis #sig {
+            match ::ext_php_rs::embed::Embed::run_with_module(#module, || #block) {
+                Ok(result) => result,
+                Err(e) => panic!("Failed to run the test inside a PHP request: {:?}", e),
+            }
+        }
+    })
+}
@@ -1,15 +1,54 @@
-use anyhow::{bail, Context, Result};
-use darling::ToTokens;
+use anyhow::{anyhow, bail, Context, Result};
+use darling::{FromMeta, ToTokens};
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use syn::{
-    token::Where, DataEnum, DataStruct, DeriveInput, GenericParam, Generics, Ident, ImplGenerics,
-    Lifetime, LifetimeDef, TypeGenerics, Variant, WhereClause,
+    token::Where, Attribute, DataEnum, DataStruct, DeriveInput, GenericParam, Generics, Ident,
+    ImplGenerics, Lifetime, LifetimeDef, Meta, NestedMeta, TypeGenerics, Variant, WhereClause,
 };
 
+use crate::impl_::RenameRule;
+
+/// Options given in `#[php(...)]` attributes on the struct.
+#[derive(Default, Debug, FromMeta)]
+#[darling(default)]
+struct ContainerAttrs {
+    rename_all: Option<RenameRule>,
+}
+
+/// Options given in `#[php(...)]` attributes on a field.
+#[derive(Default, Debug, FromMeta)]
+#[darling(default)]
+struct FieldAttrs {
+    rename: Option<String>,
+}
+
+/// Parses the options of the `#[php(...)]` attributes in the given list.
+fn parse_attrs<T: FromMeta + Default>(attrs: &[Attribute]) -> Result<T> {
+    let nested = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("php"))
+        .map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => Ok(list.nested.into_iter()),
+            _ => bail!("Expected `#[php(...)]` attribute."),
+        })
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<NestedMeta>>();
+    if nested.is_empty() {
+        return Ok(T::default());
+    }
+
+    T::from_list(&nested).map_err(|e| anyhow!("Unable to parse `#[php]` attribute: {}", e))
+}
+
 pub fn parser(input: DeriveInput) -> Result<TokenStream> {
     let DeriveInput {
-        generics, ident, ..
+        attrs,
+        generics,
+        ident,
+        ..
     } = input;
 
     let (into_impl_generics, ty_generics, where_clause) = generics.split_for_impl();
@@ -66,7 +105,7 @@ pub fn parser(input: DeriveInput) -> Result<TokenStream> {
 
     match input.data {
         syn::Data::Struct(data) => parse_struct(
-            data,
+            struct_fields(&data, &attrs)?,
             ident,
             into_impl_generics,
             from_impl_generics,
@@ -87,8 +126,30 @@ pub fn parser(input: DeriveInput) -> Result<TokenStream> {
     }
 }
 
+/// Returns the identifiers of the fields of a struct, with the names of the
+/// properties they are mapped to.
+fn struct_fields(data: &DataStruct, attrs: &[Attribute]) -> Result<Vec<(Ident, String)>> {
+    let container: ContainerAttrs = parse_attrs(attrs)?;
+
+    data.fields
+        .iter()
+        .map(|field| {
+            let ident = field.ident.clone().with_context(|| {
+                "Fields require names when using the `#[derive(ZvalConvert)]` macro on a struct."
+            })?;
+            let attrs: FieldAttrs = parse_attrs(&field.attrs)?;
+            let name = match (attrs.rename, container.rename_all) {
+                (Some(name), _) => name,
+                (None, Some(rule)) => rule.rename(ident.to_string()),
+                (None, None) => ident.to_string(),
+            };
+            Ok((ident, name))
+        })
+        .collect()
+}
+
 fn parse_struct(
-    data: DataStruct,
+    fields: Vec<(Ident, String)>,
     ident: Ident,
     into_impl_generics: ImplGenerics,
     from_impl_generics: Generics,
@@ -96,35 +157,17 @@ fn parse_struct(
     from_where_clause: WhereClause,
     ty_generics: TypeGenerics,
 ) -> Result<TokenStream> {
-    let into_fields = data
-        .fields
-        .iter()
-        .map(|field| {
-            let ident = field.ident.as_ref().with_context(|| {
-                "Fields require names when using the `#[derive(ZvalConvert)]` macro on a struct."
-            })?;
-            let field_name = ident.to_string();
-
-            Ok(quote! {
-                obj.set_property(#field_name, self.#ident)?;
-            })
-        })
-        .collect::<Result<Vec<_>>>()?;
-
-    let from_fields = data
-        .fields
-        .iter()
-        .map(|field| {
-            let ident = field.ident.as_ref().with_context(|| {
-                "Fields require names when using the `#[derive(ZvalConvert)]` macro on a struct."
-            })?;
-            let field_name = ident.to_string();
+    let into_fields = fields.iter().map(|(ident, field_name)| {
+        quote! {
+            obj.set_property(#field_name, self.#ident)?;
+        }
+    });
 
-            Ok(quote! {
-                #ident: obj.get_property(#field_name)?,
-            })
-        })
-        .collect::<Result<Vec<_>>>()?;
+    let from_fields = fields.iter().map(|(ident, field_name)| {
+        quote! {
+            #ident: obj.get_property(#field_name)?,
+        }
+    });
 
     Ok(quote! {
         impl #into_impl_generics ::ext_php_rs::convert::IntoZendObject for #ident #ty_generics #into_where_clause {
@@ -103,9 +103,11 @@ pub const IS_MIXED: u32 = 16;
 pub const IS_INDIRECT: u32 = 12;
 pub const IS_PTR: u32 = 13;
 pub const _IS_BOOL: u32 = 18;
+pub const _IS_NUMBER: u32 = 19;
 pub const Z_TYPE_FLAGS_SHIFT: u32 = 8;
 pub const IS_TYPE_REFCOUNTED: u32 = 1;
 pub const IS_TYPE_COLLECTABLE: u32 = 2;
+pub const IS_OBJ_DESTRUCTOR_CALLED: u32 = 256;
 pub const IS_INTERNED_STRING_EX: u32 = 6;
 pub const IS_STRING_EX: u32 = 262;
 pub const IS_ARRAY_EX: u32 = 775;
@@ -113,6 +115,12 @@ pub const IS_OBJECT_EX: u32 = 776;
 pub const IS_RESOURCE_EX: u32 = 265;
 pub const IS_REFERENCE_EX: u32 = 266;
 pub const IS_CONSTANT_AST_EX: u32 = 267;
+pub const GC_NOT_COLLECTABLE: u32 = 16;
+pub const GC_IMMUTABLE: u32 = 64;
+pub const GC_PERSISTENT: u32 = 128;
+pub const IS_STR_PERMANENT: u32 = 256;
+pub const HASH_FLAG_PACKED: u32 = 4;
+pub const HASH_FLAG_UNINITIALIZED: u32 = 8;
 pub const E_ERROR: u32 = 1;
 pub const E_WARNING: u32 = 2;
 pub const E_PARSE: u32 = 4;
@@ -128,6 +136,9 @@ pub const E_STRICT: u32 = 2048;
 pub const E_RECOVERABLE_ERROR: u32 = 4096;
 pub const E_DEPRECATED: u32 = 8192;
 pub const E_USER_DEPRECATED: u32 = 16384;
+pub const BP_VAR_R: u32 = 0;
+pub const BP_VAR_W: u32 = 1;
+pub const BP_VAR_RW: u32 = 2;
 pub const ZEND_PROPERTY_ISSET: u32 = 0;
 pub const ZEND_PROPERTY_EXISTS: u32 = 2;
 pub const ZEND_ACC_PUBLIC: u32 = 1;
@@ -173,12 +184,39 @@ pub const ZEND_ACC_GENERATOR: u32 = 16777216;
 pub const ZEND_ACC_DONE_PASS_TWO: u32 = 33554432;
 pub const ZEND_ACC_HEAP_RT_CACHE: u32 = 67108864;
 pub const ZEND_ACC_STRICT_TYPES: u32 = 2147483648;
+pub const ZEND_ATTRIBUTE_PERSISTENT: u32 = 1;
 pub const ZEND_INTERNAL_FUNCTION: u32 = 1;
 pub const ZEND_USER_FUNCTION: u32 = 2;
+pub const ZEND_USER_CLASS: u32 = 2;
 pub const ZEND_EVAL_CODE: u32 = 4;
 pub const ZEND_ISEMPTY: u32 = 1;
+pub const BP_VAR_IS: u32 = 3;
+pub const BP_VAR_UNSET: u32 = 5;
 pub const _ZEND_SEND_MODE_SHIFT: u32 = 25;
 pub const _ZEND_IS_VARIADIC_BIT: u32 = 134217728;
+pub const _ZEND_IS_PROMOTED_BIT: u32 = 268435456;
+pub const ZEND_INIT_FCALL_BY_NAME: u32 = 59;
+pub const ZEND_DO_FCALL: u32 = 60;
+pub const ZEND_INIT_FCALL: u32 = 61;
+pub const ZEND_NEW: u32 = 68;
+pub const ZEND_INIT_NS_FCALL_BY_NAME: u32 = 69;
+pub const ZEND_INCLUDE_OR_EVAL: u32 = 73;
+pub const ZEND_EXIT: u32 = 79;
+pub const ZEND_INIT_METHOD_CALL: u32 = 112;
+pub const ZEND_INIT_STATIC_METHOD_CALL: u32 = 113;
+pub const ZEND_INIT_USER_CALL: u32 = 118;
+pub const ZEND_INIT_DYNAMIC_CALL: u32 = 128;
+pub const ZEND_DO_ICALL: u32 = 129;
+pub const ZEND_DO_UCALL: u32 = 130;
+pub const ZEND_DO_FCALL_BY_NAME: u32 = 131;
+pub const ZEND_ECHO: u32 = 136;
+pub const ZEND_UNCOMPARABLE: u32 = 1;
+pub const ZEND_USER_OPCODE_CONTINUE: u32 = 0;
+pub const ZEND_USER_OPCODE_RETURN: u32 = 1;
+pub const ZEND_USER_OPCODE_DISPATCH: u32 = 2;
+pub const ZEND_USER_OPCODE_ENTER: u32 = 3;
+pub const ZEND_USER_OPCODE_LEAVE: u32 = 4;
+pub const ZEND_USER_OPCODE_DISPATCH_TO: u32 = 256;
 pub const ZEND_MODULE_API_NO: u32 = 20230831;
 pub const USING_ZTS: u32 = 0;
 pub const MAY_BE_BOOL: u32 = 12;
@@ -194,6 +232,13 @@ pub const PHP_INI_USER: u32 = 1;
 pub const PHP_INI_PERDIR: u32 = 2;
 pub const PHP_INI_SYSTEM: u32 = 4;
 pub const PHP_INI_ALL: u32 = 7;
+pub const ZEND_FETCH_CLASS_SILENT: u32 = 256;
+pub const ZEND_INI_STAGE_STARTUP: u32 = 1;
+pub const ZEND_INI_STAGE_SHUTDOWN: u32 = 2;
+pub const ZEND_INI_STAGE_ACTIVATE: u32 = 4;
+pub const ZEND_INI_STAGE_DEACTIVATE: u32 = 8;
+pub const ZEND_INI_STAGE_RUNTIME: u32 = 16;
+pub const ZEND_INI_STAGE_HTACCESS: u32 = 32;
 pub const CONST_CS: u32 = 0;
 pub const CONST_PERSISTENT: u32 = 1;
 pub const CONST_NO_FILE_CACHE: u32 = 2;
@@ -274,8 +319,30 @@ pub struct _IO_FILE {
     pub _unused2: [::std::os::raw::c_char; 20usize],
 }
 pub type zend_long = i64;
+pub const PHP_JSON_HEX_TAG: u32 = 1;
+pub const PHP_JSON_HEX_AMP: u32 = 2;
+pub const PHP_JSON_HEX_APOS: u32 = 4;
+pub const PHP_JSON_HEX_QUOT: u32 = 8;
+pub const PHP_JSON_FORCE_OBJECT: u32 = 16;
+pub const PHP_JSON_NUMERIC_CHECK: u32 = 32;
+pub const PHP_JSON_UNESCAPED_SLASHES: u32 = 64;
+pub const PHP_JSON_PRETTY_PRINT: u32 = 128;
+pub const PHP_JSON_UNESCAPED_UNICODE: u32 = 256;
+pub const PHP_JSON_PARTIAL_OUTPUT_ON_ERROR: u32 = 512;
+pub const PHP_JSON_PRESERVE_ZERO_FRACTION: u32 = 1024;
+pub const PHP_JSON_UNESCAPED_LINE_TERMINATORS: u32 = 2048;
+pub const PHP_JSON_OBJECT_AS_ARRAY: u32 = 1;
+pub const PHP_JSON_BIGINT_AS_STRING: u32 = 2;
+pub const PHP_JSON_INVALID_UTF8_IGNORE: u32 = 1048576;
+pub const PHP_JSON_INVALID_UTF8_SUBSTITUTE: u32 = 2097152;
+pub const PHP_JSON_THROW_ON_ERROR: u32 = 4194304;
 pub type zend_ulong = u64;
 pub type zend_off_t = i64;
+pub const SEEK_SET: u32 = 0;
+pub const SEEK_CUR: u32 = 1;
+pub const SEEK_END: u32 = 2;
+pub const REPORT_ERRORS: u32 = 8;
+pub const PHP_STREAM_FREE_CLOSE: u32 = 3;
 pub const ZEND_RESULT_CODE_SUCCESS: ZEND_RESULT_CODE = 0;
 pub const ZEND_RESULT_CODE_FAILURE: ZEND_RESULT_CODE = -1;
 pub type ZEND_RESULT_CODE = ::std::os::raw::c_int;
@@ -471,6 +538,27 @@ pub struct _zend_reference {
 pub struct _zend_ast_ref {
     pub gc: zend_refcounted_h,
 }
+pub const _zend_ast_kind_ZEND_AST_ZVAL: _zend_ast_kind = 64;
+pub const _zend_ast_kind_ZEND_AST_CLASS_CONST: _zend_ast_kind = 517;
+pub type _zend_ast_kind = ::std::os::raw::c_uint;
+pub type zend_ast_kind = u16;
+pub type zend_ast_attr = u16;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _zend_ast {
+    pub kind: zend_ast_kind,
+    pub attr: zend_ast_attr,
+    pub lineno: u32,
+    pub child: [*mut zend_ast; 1usize],
+}
+pub type zend_ast = _zend_ast;
+#[repr(C)]
+pub struct _zend_ast_zval {
+    pub kind: zend_ast_kind,
+    pub attr: zend_ast_attr,
+    pub val: zval,
+}
+pub type zend_ast_zval = _zend_ast_zval;
 extern "C" {
     pub fn _emalloc(
         size: usize,
@@ -489,6 +577,16 @@ extern "C" {
         __zend_orig_lineno: u32,
     );
 }
+extern "C" {
+    pub fn _estrndup(
+        s: *const ::std::os::raw::c_char,
+        length: usize,
+        __zend_filename: *const ::std::os::raw::c_char,
+        __zend_lineno: u32,
+        __zend_orig_filename: *const ::std::os::raw::c_char,
+        __zend_orig_lineno: u32,
+    ) -> *mut ::std::os::raw::c_char;
+}
 extern "C" {
     pub fn __zend_malloc(len: usize) -> *mut ::std::os::raw::c_void;
 }
@@ -537,9 +635,25 @@ pub type zend_string_init_interned_func_t = ::std::option::Option<
 extern "C" {
     pub static mut zend_string_init_interned: zend_string_init_interned_func_t;
 }
+extern "C" {
+    pub fn zend_hash_func(str_: *const ::std::os::raw::c_char, len: usize) -> zend_ulong;
+}
+extern "C" {
+    pub fn zend_string_tolower_ex(str_: *mut zend_string, persistent: bool) -> *mut zend_string;
+}
+extern "C" {
+    pub fn zend_string_toupper_ex(str_: *mut zend_string, persistent: bool) -> *mut zend_string;
+}
 extern "C" {
     pub fn zend_hash_clean(ht: *mut HashTable);
 }
+extern "C" {
+    pub fn zend_hash_update(
+        ht: *mut HashTable,
+        key: *mut zend_string,
+        pData: *mut zval,
+    ) -> *mut zval;
+}
 extern "C" {
     pub fn zend_hash_str_update(
         ht: *mut HashTable,
@@ -565,6 +679,9 @@ extern "C" {
 extern "C" {
     pub fn zend_hash_index_del(ht: *mut HashTable, h: zend_ulong) -> zend_result;
 }
+extern "C" {
+    pub fn zend_hash_find(ht: *const HashTable, key: *mut zend_string) -> *mut zval;
+}
 extern "C" {
     pub fn zend_hash_str_find(
         ht: *const HashTable,
@@ -597,6 +714,23 @@ extern "C" {
 extern "C" {
     pub fn zend_hash_get_current_data_ex(ht: *mut HashTable, pos: *mut HashPosition) -> *mut zval;
 }
+extern "C" {
+    pub fn zend_hash_real_init_packed(ht: *mut HashTable);
+}
+extern "C" {
+    pub fn zend_hash_extend(ht: *mut HashTable, nSize: u32, packed: bool);
+}
+extern "C" {
+    pub fn _zend_hash_init(
+        ht: *mut HashTable,
+        nSize: u32,
+        pDestructor: dtor_func_t,
+        persistent: bool,
+    );
+}
+extern "C" {
+    pub fn zend_hash_destroy(ht: *mut HashTable);
+}
 extern "C" {
     pub fn _zend_new_array(size: u32) -> *mut HashTable;
 }
@@ -626,9 +760,18 @@ pub struct zend_get_gc_buffer {
     pub end: *mut zval,
     pub start: *mut zval,
 }
+extern "C" {
+    pub fn zend_get_gc_buffer_create() -> *mut zend_get_gc_buffer;
+}
+extern "C" {
+    pub fn zend_get_gc_buffer_grow(gc_buffer: *mut zend_get_gc_buffer);
+}
 extern "C" {
     pub fn zval_ptr_dtor(zval_ptr: *mut zval);
 }
+extern "C" {
+    pub fn zval_try_get_string_func(op: *mut zval) -> *mut zend_string;
+}
 pub type zend_object_iterator = _zend_object_iterator;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
@@ -673,6 +816,9 @@ pub struct _zend_class_iterator_funcs {
     pub zf_rewind: *mut zend_function,
 }
 pub type zend_class_iterator_funcs = _zend_class_iterator_funcs;
+extern "C" {
+    pub fn zend_iterator_init(iter: *mut zend_object_iterator);
+}
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct _zend_class_arrayaccess_funcs {
@@ -1135,6 +1281,27 @@ extern "C" {
 extern "C" {
     pub fn zend_std_get_properties(object: *mut zend_object) -> *mut HashTable;
 }
+extern "C" {
+    pub fn zend_read_static_property_ex(
+        scope: *mut zend_class_entry,
+        name: *mut zend_string,
+        silent: bool,
+    ) -> *mut zval;
+}
+extern "C" {
+    pub fn zend_update_static_property_ex(
+        scope: *mut zend_class_entry,
+        name: *mut zend_string,
+        value: *mut zval,
+    ) -> zend_result;
+}
+extern "C" {
+    pub fn zend_get_property_info(
+        ce: *const zend_class_entry,
+        member: *mut zend_string,
+        silent: ::std::os::raw::c_int,
+    ) -> *mut zend_property_info;
+}
 extern "C" {
     pub fn zend_std_read_property(
         object: *mut zend_object,
@@ -1160,6 +1327,31 @@ extern "C" {
         cache_slot: *mut *mut ::std::os::raw::c_void,
     ) -> ::std::os::raw::c_int;
 }
+extern "C" {
+    pub fn zend_std_unset_property(
+        object: *mut zend_object,
+        member: *mut zend_string,
+        cache_slot: *mut *mut ::std::os::raw::c_void,
+    );
+}
+extern "C" {
+    pub fn zend_std_get_property_ptr_ptr(
+        object: *mut zend_object,
+        member: *mut zend_string,
+        type_: ::std::os::raw::c_int,
+        cache_slot: *mut *mut ::std::os::raw::c_void,
+    ) -> *mut zval;
+}
+extern "C" {
+    pub fn zend_std_compare_objects(o1: *mut zval, o2: *mut zval) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn zend_std_cast_object_tostring(
+        readobj: *mut zend_object,
+        writeobj: *mut zval,
+        type_: ::std::os::raw::c_int,
+    ) -> zend_result;
+}
 extern "C" {
     pub fn zend_is_identical(op1: *const zval, op2: *const zval) -> bool;
 }
@@ -1226,6 +1418,49 @@ pub struct _zend_property_info {
     pub ce: *mut zend_class_entry,
     pub type_: zend_type,
 }
+pub type zend_property_info = _zend_property_info;
+#[repr(C)]
+pub struct _zend_class_constant {
+    pub value: zval,
+    pub doc_comment: *mut zend_string,
+    pub attributes: *mut HashTable,
+    pub ce: *mut zend_class_entry,
+}
+pub type zend_class_constant = _zend_class_constant;
+#[repr(C)]
+pub struct zend_attribute_arg {
+    pub name: *mut zend_string,
+    pub value: zval,
+}
+#[repr(C)]
+pub struct _zend_attribute {
+    pub name: *mut zend_string,
+    pub lcname: *mut zend_string,
+    pub flags: u32,
+    pub lineno: u32,
+    pub offset: u32,
+    pub argc: u32,
+    pub args: [zend_attribute_arg; 1usize],
+}
+pub type zend_attribute = _zend_attribute;
+extern "C" {
+    pub fn zend_get_attribute_value(
+        ret: *mut zval,
+        attr: *mut zend_attribute,
+        i: u32,
+        scope: *mut zend_class_entry,
+    ) -> zend_result;
+}
+extern "C" {
+    pub fn zend_add_attribute(
+        attributes: *mut *mut HashTable,
+        name: *mut zend_string,
+        argc: u32,
+        flags: u32,
+        offset: u32,
+        lineno: u32,
+    ) -> *mut zend_attribute;
+}
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct _zend_internal_arg_info {
@@ -1361,6 +1596,33 @@ pub type zend_atomic_bool = zend_atomic_bool_s;
 extern "C" {
     pub fn zend_atomic_bool_store(obj: *mut zend_atomic_bool, desired: bool);
 }
+pub type zend_observer_fcall_begin_handler =
+    ::std::option::Option<unsafe extern "C" fn(execute_data: *mut zend_execute_data)>;
+pub type zend_observer_fcall_end_handler = ::std::option::Option<
+    unsafe extern "C" fn(execute_data: *mut zend_execute_data, retval: *mut zval),
+>;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _zend_observer_fcall_handlers {
+    pub begin: zend_observer_fcall_begin_handler,
+    pub end: zend_observer_fcall_end_handler,
+}
+pub type zend_observer_fcall_handlers = _zend_observer_fcall_handlers;
+pub type zend_observer_fcall_init = ::std::option::Option<
+    unsafe extern "C" fn(execute_data: *mut zend_execute_data) -> zend_observer_fcall_handlers,
+>;
+extern "C" {
+    pub fn zend_observer_fcall_register(arg1: zend_observer_fcall_init);
+}
+pub type user_opcode_handler_t = ::std::option::Option<
+    unsafe extern "C" fn(execute_data: *mut zend_execute_data) -> ::std::os::raw::c_int,
+>;
+extern "C" {
+    pub fn zend_set_user_opcode_handler(opcode: u8, handler: user_opcode_handler_t) -> zend_result;
+}
+extern "C" {
+    pub fn zend_get_user_opcode_handler(opcode: u8) -> user_opcode_handler_t;
+}
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct _zend_stack {
@@ -1502,6 +1764,15 @@ pub struct _zend_executor_globals {
 extern "C" {
     pub fn zend_is_auto_global(name: *mut zend_string) -> bool;
 }
+pub type zend_auto_global_callback =
+    ::std::option::Option<unsafe extern "C" fn(name: *mut zend_string) -> bool>;
+extern "C" {
+    pub fn zend_register_auto_global(
+        name: *mut zend_string,
+        jit: bool,
+        auto_global_callback: zend_auto_global_callback,
+    ) -> zend_result;
+}
 pub type zend_module_entry = _zend_module_entry;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
@@ -1561,6 +1832,20 @@ pub struct _zend_module_dep {
     pub version: *const ::std::os::raw::c_char,
     pub type_: ::std::os::raw::c_uchar,
 }
+extern "C" {
+    pub fn zend_get_constant_str(
+        name: *const ::std::os::raw::c_char,
+        name_len: usize,
+    ) -> *mut zval;
+}
+extern "C" {
+    pub fn zend_get_class_constant_ex(
+        class_name: *mut zend_string,
+        constant_name: *mut zend_string,
+        scope: *mut zend_class_entry,
+        flags: u32,
+    ) -> *mut zval;
+}
 extern "C" {
     pub fn zend_lookup_class_ex(
         name: *mut zend_string,
@@ -1617,6 +1902,26 @@ extern "C" {
         parent_ce: *mut zend_class_entry,
     ) -> *mut zend_class_entry;
 }
+extern "C" {
+    pub fn zend_register_internal_enum(
+        name: *const ::std::os::raw::c_char,
+        type_: u8,
+        functions: *const zend_function_entry,
+    ) -> *mut zend_class_entry;
+}
+extern "C" {
+    pub fn zend_enum_add_case_cstr(
+        ce: *mut zend_class_entry,
+        name: *const ::std::os::raw::c_char,
+        value: *mut zval,
+    );
+}
+extern "C" {
+    pub fn zend_enum_get_case_cstr(
+        ce: *mut zend_class_entry,
+        name: *const ::std::os::raw::c_char,
+    ) -> *mut zend_object;
+}
 extern "C" {
     pub fn zend_is_callable(
         callable: *mut zval,
@@ -1633,6 +1938,25 @@ extern "C" {
         access_type: ::std::os::raw::c_int,
     );
 }
+extern "C" {
+    pub fn zend_declare_property_ex(
+        ce: *mut zend_class_entry,
+        name: *mut zend_string,
+        property: *mut zval,
+        access_type: ::std::os::raw::c_int,
+        doc_comment: *mut zend_string,
+    );
+}
+extern "C" {
+    pub fn zend_declare_typed_property(
+        ce: *mut zend_class_entry,
+        name: *mut zend_string,
+        property: *mut zval,
+        access_type: ::std::os::raw::c_int,
+        doc_comment: *mut zend_string,
+        type_: zend_type,
+    ) -> *mut zend_property_info;
+}
 extern "C" {
     pub fn zend_declare_class_constant(
         ce: *mut zend_class_entry,
@@ -1641,6 +1965,18 @@ extern "C" {
         value: *mut zval,
     );
 }
+extern "C" {
+    pub fn zend_declare_class_constant_ex(
+        ce: *mut zend_class_entry,
+        name: *mut zend_string,
+        value: *mut zval,
+        access_type: ::std::os::raw::c_int,
+        doc_comment: *mut zend_string,
+    ) -> *mut zend_class_constant;
+}
+extern "C" {
+    pub fn object_init_ex(arg: *mut zval, ce: *mut zend_class_entry) -> zend_result;
+}
 extern "C" {
     pub fn object_properties_init(object: *mut zend_object, class_type: *mut zend_class_entry);
 }
@@ -1707,6 +2043,9 @@ pub type _zend_expected_type = ::std::os::raw::c_uint;
 extern "C" {
     pub fn zend_wrong_parameters_count_error(min_num_args: u32, max_num_args: u32);
 }
+extern "C" {
+    pub fn start_memory_manager();
+}
 extern "C" {
     pub fn php_printf(format: *const ::std::os::raw::c_char, ...) -> usize;
 }
@@ -1718,6 +2057,45 @@ extern "C" {
         ...
     );
 }
+pub type php_json_error_code = ::std::os::raw::c_uint;
+pub const php_json_error_code_PHP_JSON_ERROR_NONE: php_json_error_code = 0;
+pub const php_json_error_code_PHP_JSON_ERROR_DEPTH: php_json_error_code = 1;
+pub const php_json_error_code_PHP_JSON_ERROR_STATE_MISMATCH: php_json_error_code = 2;
+pub const php_json_error_code_PHP_JSON_ERROR_CTRL_CHAR: php_json_error_code = 3;
+pub const php_json_error_code_PHP_JSON_ERROR_SYNTAX: php_json_error_code = 4;
+pub const php_json_error_code_PHP_JSON_ERROR_UTF8: php_json_error_code = 5;
+pub const php_json_error_code_PHP_JSON_ERROR_RECURSION: php_json_error_code = 6;
+pub const php_json_error_code_PHP_JSON_ERROR_INF_OR_NAN: php_json_error_code = 7;
+pub const php_json_error_code_PHP_JSON_ERROR_UNSUPPORTED_TYPE: php_json_error_code = 8;
+pub const php_json_error_code_PHP_JSON_ERROR_INVALID_PROPERTY_NAME: php_json_error_code = 9;
+pub const php_json_error_code_PHP_JSON_ERROR_UTF16: php_json_error_code = 10;
+pub const php_json_error_code_PHP_JSON_ERROR_NON_BACKED_ENUM: php_json_error_code = 11;
+extern "C" {
+    pub static mut php_json_exception_ce: *mut zend_class_entry;
+}
+extern "C" {
+    pub fn php_json_encode_ex(
+        buf: *mut smart_str,
+        val: *mut zval,
+        options: ::std::os::raw::c_int,
+        depth: zend_long,
+    ) -> zend_result;
+}
+extern "C" {
+    pub fn php_json_decode_ex(
+        return_value: *mut zval,
+        str_: *const ::std::os::raw::c_char,
+        str_len: usize,
+        options: zend_long,
+        depth: zend_long,
+    ) -> zend_result;
+}
+extern "C" {
+    pub fn php_log_err_with_severity(
+        log_message: *const ::std::os::raw::c_char,
+        syslog_type_int: ::std::os::raw::c_int,
+    );
+}
 pub type php_stream = _php_stream;
 pub type php_stream_wrapper = _php_stream_wrapper;
 pub type php_stream_context = _php_stream_context;
@@ -2113,6 +2491,82 @@ impl _php_stream {
         __bindgen_bitfield_unit
     }
 }
+extern "C" {
+    pub fn _php_stream_read(
+        stream: *mut php_stream,
+        buf: *mut ::std::os::raw::c_char,
+        count: usize,
+    ) -> isize;
+}
+extern "C" {
+    pub fn _php_stream_write(
+        stream: *mut php_stream,
+        buf: *const ::std::os::raw::c_char,
+        count: usize,
+    ) -> isize;
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct smart_str {
+    pub s: *mut zend_string,
+    pub a: usize,
+}
+extern "C" {
+    pub fn smart_str_erealloc(str_: *mut smart_str, len: usize);
+}
+extern "C" {
+    pub fn smart_str_realloc(str_: *mut smart_str, len: usize);
+}
+extern "C" {
+    pub fn _php_stream_tell(stream: *mut php_stream) -> zend_off_t;
+}
+extern "C" {
+    pub fn _php_stream_flush(
+        stream: *mut php_stream,
+        closing: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn _php_stream_free(
+        stream: *mut php_stream,
+        close_options: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn _php_stream_open_wrapper_ex(
+        path: *const ::std::os::raw::c_char,
+        mode: *const ::std::os::raw::c_char,
+        options: ::std::os::raw::c_int,
+        opened_path: *mut *mut zend_string,
+        context: *mut php_stream_context,
+        __php_stream_call_depth: ::std::os::raw::c_int,
+        __zend_filename: *const ::std::os::raw::c_char,
+        __zend_lineno: u32,
+        __zend_orig_filename: *const ::std::os::raw::c_char,
+        __zend_orig_lineno: u32,
+    ) -> *mut php_stream;
+}
+extern "C" {
+    pub fn zend_fetch_resource2(
+        res: *mut zend_resource,
+        resource_type_name: *const ::std::os::raw::c_char,
+        resource_type: ::std::os::raw::c_int,
+        resource_type2: ::std::os::raw::c_int,
+    ) -> *mut ::std::os::raw::c_void;
+}
+extern "C" {
+    pub fn php_file_le_stream() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn php_file_le_pstream() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn _php_stream_seek(
+        stream: *mut php_stream,
+        offset: zend_off_t,
+        whence: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
 extern "C" {
     pub static mut php_stream_stdio_ops: php_stream_ops;
 }
@@ -2287,6 +2741,21 @@ pub struct _zend_ini_entry {
     pub orig_modifiable: u8,
     pub modified: u8,
 }
+extern "C" {
+    pub fn zend_alter_ini_entry_ex(
+        name: *mut zend_string,
+        new_value: *mut zend_string,
+        modify_type: ::std::os::raw::c_int,
+        stage: ::std::os::raw::c_int,
+        force_change: bool,
+    ) -> zend_result;
+}
+extern "C" {
+    pub fn zend_restore_ini_entry(
+        name: *mut zend_string,
+        stage: ::std::os::raw::c_int,
+    ) -> zend_result;
+}
 extern "C" {
     pub fn zend_register_ini_entries(
         ini_entry: *const zend_ini_entry_def,
@@ -2341,6 +2810,15 @@ extern "C" {
 extern "C" {
     pub fn php_info_print_table_end();
 }
+extern "C" {
+    pub fn php_array_merge(dest: *mut HashTable, src: *mut HashTable) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn php_array_merge_recursive(
+        dest: *mut HashTable,
+        src: *mut HashTable,
+    ) -> ::std::os::raw::c_int;
+}
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct hostent {
@@ -2378,6 +2856,9 @@ extern "C" {
 extern "C" {
     pub static mut zend_ce_exception: *mut zend_class_entry;
 }
+extern "C" {
+    pub static mut zend_ce_error: *mut zend_class_entry;
+}
 extern "C" {
     pub static mut zend_ce_error_exception: *mut zend_class_entry;
 }
@@ -2516,6 +2997,7 @@ extern "C" {
 extern "C" {
     pub fn sapi_shutdown();
 }
+pub const SAPI_HEADER_SENT_SUCCESSFULLY: u32 = 1;
 pub const sapi_header_op_enum_SAPI_HEADER_REPLACE: sapi_header_op_enum = 0;
 pub const sapi_header_op_enum_SAPI_HEADER_ADD: sapi_header_op_enum = 1;
 pub const sapi_header_op_enum_SAPI_HEADER_DELETE: sapi_header_op_enum = 2;
@@ -2524,6 +3006,22 @@ pub const sapi_header_op_enum_SAPI_HEADER_SET_STATUS: sapi_header_op_enum = 4;
 pub type sapi_header_op_enum = ::std::os::raw::c_uint;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+pub struct sapi_header_line {
+    pub line: *const ::std::os::raw::c_char,
+    pub line_len: usize,
+    pub response_code: zend_long,
+}
+extern "C" {
+    pub fn sapi_read_post_block(buffer: *mut ::std::os::raw::c_char, buflen: usize) -> usize;
+}
+extern "C" {
+    pub fn sapi_header_op(
+        op: sapi_header_op_enum,
+        arg: *mut ::std::os::raw::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
 pub struct _sapi_module_struct {
     pub name: *mut ::std::os::raw::c_char,
     pub pretty_name: *mut ::std::os::raw::c_char,
@@ -2634,3 +3132,12 @@ pub struct _sapi_post_entry {
         ),
     >,
 }
+extern "C" {
+    pub fn php_request_startup() -> zend_result;
+}
+extern "C" {
+    pub fn php_request_shutdown(dummy: *mut ::std::os::raw::c_void);
+}
+extern "C" {
+    pub fn php_execute_script(primary_file: *mut zend_file_handle) -> bool;
+}
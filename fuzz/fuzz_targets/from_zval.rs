@@ -0,0 +1,44 @@
+//! Converts arbitrary PHP values into each of the Rust types implementing
+//! `FromZval`. Conversions may fail, but must not panic or crash.
+
+#![no_main]
+
+use std::collections::HashMap;
+
+use ext_php_rs::{
+    binary::Binary,
+    convert::{FromZval, IntoZval},
+    types::Zval,
+};
+use ext_php_rs_fuzz::Value;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|value: Value| {
+    ext_php_rs_fuzz::run(|| {
+        let zv = value.to_zval();
+
+        let _ = bool::from_zval(&zv);
+        let _ = i64::from_zval(&zv);
+        let _ = i32::from_zval(&zv);
+        let _ = u8::from_zval(&zv);
+        let _ = f64::from_zval(&zv);
+        let _ = <&str>::from_zval(&zv);
+        let _ = Binary::<u8>::from_zval(&zv);
+        let _ = Binary::<u64>::from_zval(&zv);
+        let _ = Option::<i64>::from_zval(&zv);
+        let _ = Vec::<i64>::from_zval(&zv);
+        let _ = Vec::<Vec<String>>::from_zval(&zv);
+        let _ = HashMap::<String, i64>::from_zval(&zv);
+        let _ = HashMap::<String, HashMap<String, f64>>::from_zval(&zv);
+
+        // Values converted from zvals convert back to equal zvals.
+        if let Some(string) = String::from_zval(&zv) {
+            let back = string.clone().into_zval(false).unwrap();
+            assert_eq!(back.str(), Some(string.as_str()));
+        }
+        if let Some(vec) = Vec::<&Zval>::from_zval(&zv) {
+            let len = zv.array().map(|ht| ht.len());
+            assert_eq!(Some(vec.len()), len);
+        }
+    });
+});
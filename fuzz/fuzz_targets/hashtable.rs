@@ -0,0 +1,97 @@
+//! Applies arbitrary operations to a hashtable, checking its contents against
+//! a model of a PHP array after each operation.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use ext_php_rs::{
+    convert::FromZval,
+    types::{ArrayKey, ZendHashTable},
+};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Debug, Arbitrary)]
+enum Op {
+    Insert(String, i64),
+    InsertAtIndex(u64, i64),
+    Push(i64),
+    Remove(String),
+    RemoveIndex(u64),
+    Clear,
+    ShrinkToFit,
+}
+
+/// A PHP array, as a list of keys and values in insertion order.
+#[derive(Default)]
+struct Model {
+    elements: Vec<(ArrayKey, i64)>,
+}
+
+impl Model {
+    fn insert(&mut self, key: ArrayKey, val: i64) {
+        match self.elements.iter_mut().find(|(k, _)| *k == key) {
+            Some(element) => element.1 = val,
+            None => self.elements.push((key, val)),
+        }
+    }
+
+    fn remove(&mut self, key: &ArrayKey) {
+        self.elements.retain(|(k, _)| k != key);
+    }
+}
+
+fuzz_target!(|ops: Vec<Op>| {
+    ext_php_rs_fuzz::run(|| {
+        let mut ht = ZendHashTable::new();
+        let mut model = Model::default();
+
+        for op in &ops {
+            match op {
+                Op::Insert(key, val) => {
+                    // Numeric string keys are stored as integer keys by PHP,
+                    // so only keys which are not numeric are compared.
+                    if key.parse::<i64>().is_ok() {
+                        continue;
+                    }
+                    // Keys containing NUL bytes are rejected.
+                    if ht.insert(key, *val).is_ok() {
+                        model.insert(ArrayKey::String(key.clone()), *val);
+                    }
+                }
+                Op::InsertAtIndex(index, val) => {
+                    ht.insert_at_index(*index, *val).unwrap();
+                    model.insert(ArrayKey::Long(*index as i64), *val);
+                }
+                Op::Push(val) => {
+                    if ht.push(*val).is_ok() {
+                        let key = ht.keys_vec().pop().unwrap();
+                        model.insert(key, *val);
+                    }
+                }
+                Op::Remove(key) => {
+                    if key.parse::<i64>().is_ok() {
+                        continue;
+                    }
+                    let _ = ht.remove(key);
+                    model.remove(&ArrayKey::String(key.clone()));
+                }
+                Op::RemoveIndex(index) => {
+                    let _ = ht.remove_index(*index);
+                    model.remove(&ArrayKey::Long(*index as i64));
+                }
+                Op::Clear => {
+                    ht.clear();
+                    model.elements.clear();
+                }
+                Op::ShrinkToFit => ht.shrink_to_fit(),
+            }
+
+            assert_eq!(ht.len(), model.elements.len());
+            let elements: Vec<_> = ht
+                .iter()
+                .map(|(key, val)| (key, i64::from_zval(val).unwrap()))
+                .collect();
+            assert_eq!(elements, model.elements);
+        }
+    });
+});
@@ -0,0 +1,44 @@
+//! Creates Zend strings from arbitrary bytes, checking the string functions
+//! against the same operations on byte slices.
+
+#![no_main]
+
+use ext_php_rs::{convert::FromZval, types::ZendStr, types::Zval};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (Vec<u8>, Vec<u8>)| {
+    let (bytes, needle) = &input;
+
+    ext_php_rs_fuzz::run(|| {
+        let s = ZendStr::new(bytes, false);
+        assert_eq!(s.len(), bytes.len());
+        assert_eq!(s.as_bytes(), &bytes[..]);
+        assert_eq!(s.as_str().ok(), std::str::from_utf8(bytes).ok());
+        assert_eq!(s.as_c_str().is_ok(), !bytes.contains(&0));
+
+        assert_eq!(s.to_lowercase().as_bytes(), bytes.to_ascii_lowercase());
+        assert_eq!(s.to_uppercase().as_bytes(), bytes.to_ascii_uppercase());
+        assert_eq!(s.starts_with(needle), bytes.starts_with(needle));
+        assert_eq!(s.ends_with(needle), bytes.ends_with(needle));
+
+        let end = needle.len().min(bytes.len());
+        assert_eq!(s.substr(..end).as_bytes(), &bytes[..end]);
+
+        if !needle.is_empty() {
+            assert_eq!(
+                s.find(needle),
+                bytes.windows(needle.len()).position(|w| w == &needle[..])
+            );
+            let parts: Vec<_> = s.split(needle).collect();
+            assert_eq!(parts.join(&needle[..]), &bytes[..]);
+        }
+
+        let mut zv = Zval::new();
+        zv.set_zend_string(s);
+        assert_eq!(
+            String::from_zval(&zv),
+            std::str::from_utf8(bytes).ok().map(String::from)
+        );
+        assert_eq!(zv.str(), std::str::from_utf8(bytes).ok());
+    });
+});
@@ -0,0 +1,104 @@
+//! Code shared by the fuzz targets.
+//!
+//! Each input is run in a request of the embed SAPI, so values are allocated
+//! by the Zend memory manager as they are in an extension. A panic, or a
+//! bailout of PHP, is reported as a crash by the fuzzer.
+
+use std::panic::RefUnwindSafe;
+
+use arbitrary::Arbitrary;
+use ext_php_rs::{
+    builders::ModuleBuilder,
+    embed::Embed,
+    ffi::zend_hash_str_update,
+    types::{ZendHashTable, Zval},
+    zend::ModuleEntry,
+};
+
+extern "C" fn get_module() -> *mut ModuleEntry {
+    ModuleBuilder::new("fuzz", "0.0.0")
+        .build()
+        .expect("Failed to build the fuzzing module")
+        .into_raw()
+}
+
+/// Runs a function in a new request of the embed SAPI, starting the SAPI on
+/// the first call.
+///
+/// # Panics
+///
+/// Panics if the request could not be started, or if PHP bailed out while
+/// running the function.
+pub fn run(func: impl FnMut() + RefUnwindSafe) {
+    Embed::run_with_module(get_module, func).expect("Failed to run the input in a PHP request");
+}
+
+/// An arbitrary PHP value.
+#[derive(Debug, Arbitrary)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Long(i64),
+    Double(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    Array(Vec<(Key, Value)>),
+}
+
+/// The key of an element of an arbitrary PHP array.
+#[derive(Debug, Arbitrary)]
+pub enum Key {
+    Index(u64),
+    String(String),
+    /// A key which is not valid UTF-8 or contains NUL bytes, as created by
+    /// e.g. `unserialize()`. Inserted as it is, so numeric keys are kept as
+    /// string keys.
+    Bytes(Vec<u8>),
+    /// Appends the element with the next free index, as `$a[] = $value` does.
+    Next,
+}
+
+impl Value {
+    /// Converts the value into a zval. Elements which cannot be inserted into
+    /// an array, e.g. as the next free index would overflow, are skipped.
+    pub fn to_zval(&self) -> Zval {
+        let mut zv = Zval::new();
+        match self {
+            Self::Null => zv.set_null(),
+            Self::Bool(val) => zv.set_bool(*val),
+            Self::Long(val) => zv.set_long(*val),
+            Self::Double(val) => zv.set_double(*val),
+            Self::String(val) => zv
+                .set_string(val, false)
+                .expect("Failed to set string of zval"),
+            Self::Bytes(val) => zv.set_bytes(val, false),
+            Self::Array(elements) => {
+                let mut ht = ZendHashTable::new();
+                for (key, val) in elements {
+                    let val = val.to_zval();
+                    let _ = match key {
+                        Key::Index(index) => ht.insert_at_index(*index, val),
+                        Key::String(key) => ht.insert(key, val),
+                        Key::Bytes(key) => {
+                            let mut val = val;
+                            unsafe {
+                                zend_hash_str_update(
+                                    &mut *ht,
+                                    key.as_ptr().cast(),
+                                    key.len() as _,
+                                    &mut val,
+                                )
+                            };
+                            // The hashtable took ownership of the value.
+                            std::mem::forget(val);
+                            Ok(())
+                        }
+                        Key::Next => ht.push(val),
+                    };
+                }
+                zv.set_hashtable(ht);
+            }
+        }
+        zv
+    }
+}
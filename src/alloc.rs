@@ -13,11 +13,12 @@ use std::{alloc::Layout, ffi::c_void};
 /// # Returns
 ///
 /// A pointer to the memory allocated.
+#[cfg_attr(feature = "alloc-tracking", track_caller)]
 pub fn emalloc(layout: Layout) -> *mut u8 {
     // TODO account for alignment
     let size = layout.size();
 
-    (unsafe {
+    let ptr = (unsafe {
         #[cfg(php_debug)]
         {
             _emalloc(size as _, std::ptr::null_mut(), 0, std::ptr::null_mut(), 0)
@@ -26,7 +27,12 @@ pub fn emalloc(layout: Layout) -> *mut u8 {
         {
             _emalloc(size as _)
         }
-    }) as *mut u8
+    }) as *mut u8;
+
+    #[cfg(feature = "alloc-tracking")]
+    tracking::record(ptr, size, std::panic::Location::caller());
+
+    ptr
 }
 
 /// Frees a given memory pointer which was allocated through the PHP memory
@@ -41,6 +47,9 @@ pub fn emalloc(layout: Layout) -> *mut u8 {
 /// Caller must guarantee that the given pointer is valid (aligned and non-null)
 /// and was originally allocated through the Zend memory manager.
 pub unsafe fn efree(ptr: *mut u8) {
+    #[cfg(feature = "alloc-tracking")]
+    tracking::release(ptr);
+
     #[cfg(php_debug)]
     {
         _efree(
@@ -56,3 +65,189 @@ pub unsafe fn efree(ptr: *mut u8) {
         _efree(ptr as *mut c_void)
     }
 }
+
+/// Tracking of the memory allocated with [`emalloc`], to find memory which is
+/// never freed with [`efree`].
+///
+/// Each allocation is recorded along with the location of the call to
+/// [`emalloc`]. Modules built with [`ModuleBuilder`] report the allocations
+/// which were not freed once a request has ended, just before PHP releases
+/// the memory of the request, by printing them to standard error:
+///
+/// ```text
+/// ext-php-rs: 2 allocations (48 bytes) were not freed by the end of the request:
+///   1 allocation (32 bytes) at src/lib.rs:12:15
+///   1 allocation (16 bytes) at src/lib.rs:20:9
+/// ```
+///
+/// Only calls to [`emalloc`] are tracked. The strings, arrays, objects and
+/// other values created through the types of this crate, such as
+/// [`ZendStr`] and [`ZendHashTable`], are allocated by PHP directly and are
+/// not tracked, and neither is memory allocated by PHP itself or by
+/// `emalloc()` calls from C. Within the crate, only the iterators of Rust
+/// classes are allocated with [`emalloc`].
+///
+/// Memory allocated with [`emalloc`] which is freed by PHP rather than with
+/// [`efree`] is reported as well, as the crate cannot know it was freed.
+/// Tracking slows down allocations, so the feature should only be enabled in
+/// debug builds.
+///
+/// [`ModuleBuilder`]: crate::builders::ModuleBuilder
+/// [`ZendStr`]: crate::types::ZendStr
+/// [`ZendHashTable`]: crate::types::ZendHashTable
+#[cfg(any(docs, feature = "alloc-tracking"))]
+#[cfg_attr(docs, doc(cfg(feature = "alloc-tracking")))]
+pub mod tracking {
+    use std::{
+        cell::{Cell, RefCell},
+        collections::{BTreeMap, HashMap},
+        fmt::Write,
+        panic::Location,
+    };
+
+    thread_local! {
+        // The memory manager is per thread with thread-safe PHP builds, as
+        // are requests, so allocations are tracked per thread.
+        static ALLOCATIONS: RefCell<HashMap<usize, Allocation>> = RefCell::new(HashMap::new());
+        static STATS: Cell<Stats> = const { Cell::new(Stats { allocations: 0, frees: 0 }) };
+    }
+
+    /// Memory allocated with [`emalloc`](super::emalloc) which has not been
+    /// freed yet.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Allocation {
+        /// The size of the allocation in bytes.
+        pub size: usize,
+        /// The location of the call to [`emalloc`](super::emalloc).
+        pub location: &'static Location<'static>,
+    }
+
+    /// The number of allocations and frees since the tracking was last
+    /// reset.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub struct Stats {
+        /// The number of calls to [`emalloc`](super::emalloc).
+        pub allocations: usize,
+        /// The number of calls to [`efree`](super::efree) freeing tracked
+        /// memory.
+        pub frees: usize,
+    }
+
+    /// Records memory allocated at the given location.
+    pub(crate) fn record(ptr: *mut u8, size: usize, location: &'static Location<'static>) {
+        if ptr.is_null() {
+            return;
+        }
+        ALLOCATIONS.with(|allocations| {
+            allocations
+                .borrow_mut()
+                .insert(ptr as usize, Allocation { size, location })
+        });
+        STATS.with(|stats| {
+            let mut s = stats.get();
+            s.allocations += 1;
+            stats.set(s);
+        });
+    }
+
+    /// Records that memory was freed. Memory which is not tracked, e.g. as it
+    /// was allocated by PHP, is ignored.
+    pub(crate) fn release(ptr: *mut u8) {
+        let tracked = ALLOCATIONS
+            .with(|allocations| allocations.borrow_mut().remove(&(ptr as usize)).is_some());
+        if tracked {
+            STATS.with(|stats| {
+                let mut s = stats.get();
+                s.frees += 1;
+                stats.set(s);
+            });
+        }
+    }
+
+    /// Returns the number of allocations and frees on this thread since the
+    /// tracking was last reset.
+    pub fn stats() -> Stats {
+        STATS.with(Cell::get)
+    }
+
+    /// Returns the allocations on this thread which have not been freed,
+    /// ordered by the location they were made at.
+    pub fn outstanding() -> Vec<Allocation> {
+        let mut outstanding: Vec<_> =
+            ALLOCATIONS.with(|allocations| allocations.borrow().values().copied().collect());
+        outstanding.sort_by_key(|allocation| location_key(allocation.location));
+        outstanding
+    }
+
+    /// Forgets all allocations and resets the statistics on this thread.
+    pub fn reset() {
+        ALLOCATIONS.with(|allocations| allocations.borrow_mut().clear());
+        STATS.with(|stats| stats.set(Stats::default()));
+    }
+
+    /// Prints the allocations on this thread which have not been freed to
+    /// standard error, grouped by location, and resets the tracking.
+    ///
+    /// Called at the end of each request by modules built with
+    /// [`ModuleBuilder`](crate::builders::ModuleBuilder).
+    ///
+    /// # Returns
+    ///
+    /// Returns whether any allocations were not freed.
+    pub fn report() -> bool {
+        let outstanding = outstanding();
+        reset();
+        if outstanding.is_empty() {
+            return false;
+        }
+
+        let mut locations: BTreeMap<_, (&Location, usize, usize)> = BTreeMap::new();
+        for allocation in &outstanding {
+            let entry = locations
+                .entry(location_key(allocation.location))
+                .or_insert((allocation.location, 0, 0));
+            entry.1 += 1;
+            entry.2 += allocation.size;
+        }
+
+        let mut report = format!(
+            "ext-php-rs: {} ({}) were not freed by the end of the request:",
+            count(outstanding.len()),
+            bytes(outstanding.iter().map(|allocation| allocation.size).sum()),
+        );
+        for (location, allocations, size) in locations.values() {
+            let _ = write!(
+                report,
+                "\n  {} ({}) at {}",
+                count(*allocations),
+                bytes(*size),
+                location
+            );
+        }
+        eprintln!("{report}");
+
+        true
+    }
+
+    fn location_key(location: &Location) -> (String, u32, u32) {
+        (
+            location.file().to_string(),
+            location.line(),
+            location.column(),
+        )
+    }
+
+    fn count(allocations: usize) -> String {
+        match allocations {
+            1 => "1 allocation".into(),
+            n => format!("{n} allocations"),
+        }
+    }
+
+    fn bytes(size: usize) -> String {
+        match size {
+            1 => "1 byte".into(),
+            n => format!("{n} bytes"),
+        }
+    }
+}
@@ -10,9 +10,10 @@ use crate::{
         _zend_expected_type_Z_EXPECTED_BOOL, _zend_expected_type_Z_EXPECTED_DOUBLE,
         _zend_expected_type_Z_EXPECTED_LONG, _zend_expected_type_Z_EXPECTED_OBJECT,
         _zend_expected_type_Z_EXPECTED_RESOURCE, _zend_expected_type_Z_EXPECTED_STRING,
-        zend_internal_arg_info, zend_wrong_parameters_count_error,
+        zend_internal_arg_info, zend_wrong_parameters_count_error, _ZEND_IS_PROMOTED_BIT,
     },
     flags::DataType,
+    test::ThrownException,
     types::Zval,
     zend::ZendType,
 };
@@ -25,6 +26,7 @@ pub struct Arg<'a> {
     as_ref: bool,
     allow_null: bool,
     variadic: bool,
+    promoted: bool,
     default_value: Option<String>,
     zval: Option<&'a mut Zval>,
     variadic_zvals: Vec<Option<&'a mut Zval>>,
@@ -44,6 +46,7 @@ impl<'a> Arg<'a> {
             as_ref: false,
             allow_null: false,
             variadic: false,
+            promoted: false,
             default_value: None,
             zval: None,
             variadic_zvals: vec![],
@@ -69,6 +72,17 @@ impl<'a> Arg<'a> {
         self
     }
 
+    /// Marks the argument of a constructor as promoted, i.e. as initializing
+    /// the property of the same name, so that
+    /// `ReflectionParameter::isPromoted()` returns true. The property should
+    /// be declared with [`PropertyFlags::Promoted`].
+    ///
+    /// [`PropertyFlags::Promoted`]: crate::flags::PropertyFlags::Promoted
+    pub fn promoted(mut self) -> Self {
+        self.promoted = true;
+        self
+    }
+
     /// Sets the default value for the argument.
     pub fn default<T: Into<String>>(mut self, default: T) -> Self {
         self.default_value = Some(default.into());
@@ -146,15 +160,16 @@ impl<'a> Arg<'a> {
 
     /// Returns the internal PHP argument info.
     pub(crate) fn as_arg_info(&self) -> Result<ArgInfo> {
+        let mut type_ =
+            ZendType::empty_from_type(self._type, self.as_ref, self.variadic, self.allow_null)
+                .ok_or(Error::InvalidCString)?;
+        if self.promoted {
+            type_.type_mask |= _ZEND_IS_PROMOTED_BIT;
+        }
+
         Ok(ArgInfo {
             name: CString::new(self.name.as_str())?.into_raw(),
-            type_: ZendType::empty_from_type(
-                self._type,
-                self.as_ref,
-                self.variadic,
-                self.allow_null,
-            )
-            .ok_or(Error::InvalidCString)?,
+            type_,
             default_value: match &self.default_value {
                 Some(val) => CString::new(val.as_str())?.into_raw(),
                 None => ptr::null(),
@@ -187,6 +202,127 @@ impl From<Arg<'_>> for _zend_expected_type {
 /// Internal argument information used by Zend.
 pub type ArgInfo = zend_internal_arg_info;
 
+impl ArgInfo {
+    /// Creates the header of an argument information table in a constant
+    /// context, describing the return type of the function and the number of
+    /// required arguments.
+    ///
+    /// Returns [`None`] if the return type is a class object, which cannot be
+    /// described in a constant context.
+    ///
+    /// # Parameters
+    ///
+    /// * `required_args` - The number of required arguments.
+    /// * `retval` - The return type of the function, if any.
+    /// * `as_ref` - Whether the function returns a reference.
+    /// * `allow_null` - Whether the return value is nullable.
+    pub const fn new_return(
+        required_args: usize,
+        retval: Option<DataType>,
+        as_ref: bool,
+        allow_null: bool,
+    ) -> Option<Self> {
+        let type_ = match retval {
+            Some(retval) => {
+                match ZendType::try_from_type_const(retval, as_ref, false, allow_null) {
+                    Some(type_) => type_,
+                    None => return None,
+                }
+            }
+            None => ZendType::empty(false, false),
+        };
+
+        Some(Self {
+            // The header stores the number of required arguments in place of a name.
+            name: required_args as *const _,
+            type_,
+            default_value: ptr::null(),
+        })
+    }
+
+    /// Creates the information of an argument in a constant context.
+    ///
+    /// Returns [`None`] if the argument is a class object, which cannot be
+    /// described in a constant context.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the argument, terminated with a NUL byte.
+    /// * `type_` - The type of the argument.
+    /// * `as_ref` - Whether the argument is passed by reference.
+    /// * `allow_null` - Whether the argument is nullable.
+    /// * `variadic` - Whether the argument is variadic.
+    /// * `default_value` - The default value of the argument as PHP code,
+    ///   terminated with a NUL byte.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` or `default_value` are not terminated with a NUL
+    /// byte. When evaluated in a constant, this is a compile error.
+    pub const fn new_arg(
+        name: &'static str,
+        type_: DataType,
+        as_ref: bool,
+        allow_null: bool,
+        variadic: bool,
+        default_value: Option<&'static str>,
+    ) -> Option<Self> {
+        assert!(
+            is_nul_terminated(name),
+            "argument name must be NUL-terminated"
+        );
+        let default_value = match default_value {
+            Some(val) => {
+                assert!(
+                    is_nul_terminated(val),
+                    "default value must be NUL-terminated"
+                );
+                val.as_ptr().cast()
+            }
+            None => ptr::null(),
+        };
+
+        match ZendType::try_from_type_const(type_, as_ref, variadic, allow_null) {
+            Some(type_) => Some(Self {
+                name: name.as_ptr().cast(),
+                type_,
+                default_value,
+            }),
+            None => None,
+        }
+    }
+
+    /// Collects argument information created in a constant context into a
+    /// table, returning [`None`] if any of the entries could not be created.
+    ///
+    /// # Parameters
+    ///
+    /// * `entries` - The header of the table, followed by each argument.
+    pub const fn table<const N: usize>(entries: [Option<Self>; N]) -> Option<[Self; N]> {
+        const EMPTY: ArgInfo = ArgInfo {
+            name: ptr::null(),
+            type_: ZendType::empty(false, false),
+            default_value: ptr::null(),
+        };
+
+        let mut table = [EMPTY; N];
+        let mut i = 0;
+        while i < N {
+            table[i] = match entries[i] {
+                Some(entry) => entry,
+                None => return None,
+            };
+            i += 1;
+        }
+        Some(table)
+    }
+}
+
+const fn is_nul_terminated(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    !bytes.is_empty() && bytes[bytes.len() - 1] == 0
+}
+
 /// Parses the arguments of a function.
 pub struct ArgParser<'a, 'b> {
     args: Vec<&'b mut Arg<'a>>,
@@ -243,9 +379,7 @@ impl<'a, 'b> ArgParser<'a, 'b> {
         let has_variadic = self.args.last().is_some_and(|arg| arg.variadic);
 
         if num_args < min_num_args || (!has_variadic && num_args > max_num_args) {
-            // SAFETY: Exported C function is safe, return value is unused and parameters
-            // are copied.
-            unsafe { zend_wrong_parameters_count_error(min_num_args as _, max_num_args as _) };
+            wrong_parameters_count_error(min_num_args as _, max_num_args as _, num_args as _);
             return Err(Error::IncorrectArguments(num_args, min_num_args));
         }
 
@@ -267,3 +401,40 @@ impl<'a, 'b> ArgParser<'a, 'b> {
         Ok(())
     }
 }
+
+/// Throws an `ArgumentCountError` for a function expecting between `min` and
+/// `max` arguments, as `zend_wrong_parameters_count_error()` does. Used by the
+/// argument parsers generated by [`macro@crate::php_function`].
+///
+/// The error is recorded instead when the function was called by
+/// [`test::call_function`](crate::test::call_function).
+#[doc(hidden)]
+pub fn wrong_parameters_count_error(min: u32, max: u32, given: u32) {
+    let captured = crate::test::capture(|| {
+        let expected = if min == max {
+            "exactly"
+        } else if given < min {
+            "at least"
+        } else {
+            "at most"
+        };
+        let count = if given < min { min } else { max };
+        ThrownException {
+            class: "ArgumentCountError".into(),
+            message: format!(
+                "expects {} {} argument{}, {} given",
+                expected,
+                count,
+                if count == 1 { "" } else { "s" },
+                given
+            ),
+            code: 0,
+        }
+    });
+
+    if !captured {
+        // SAFETY: Exported C function is safe, return value is unused and parameters
+        // are copied.
+        unsafe { zend_wrong_parameters_count_error(min, max) };
+    }
+}
@@ -17,7 +17,15 @@
 //! on the downstream type. For example, [`ZendStr`] has a function `new` which
 //! returns a [`ZBox<ZendStr>`].
 //!
+//! In debug builds, the boxes of request-bound values are recorded while they
+//! are alive. Boxes which are still alive once a request has ended, e.g. as
+//! they were stored in a static or leaked with [`std::mem::forget`], point to
+//! memory which PHP has released. Modules built with [`ModuleBuilder`] print a
+//! warning listing these boxes, along with the backtrace of where they were
+//! created when backtraces are enabled with `RUST_BACKTRACE=1`.
+//!
 //! [memory arenas]: https://en.wikipedia.org/wiki/Region-based_memory_management
+//! [`ModuleBuilder`]: crate::builders::ModuleBuilder
 //! [`ZendStr`]: crate::types::ZendStr
 //! [`emalloc`]: super::alloc::efree
 
@@ -31,6 +39,17 @@ use std::{
 
 use super::alloc::efree;
 
+#[cfg(debug_assertions)]
+use std::{backtrace::Backtrace, cell::RefCell, collections::HashMap};
+
+#[cfg(debug_assertions)]
+thread_local! {
+    /// The request-bound boxes which are alive on this thread, with the type
+    /// they hold and the backtrace of where they were created. Requests run on
+    /// a single thread, so boxes are recorded per thread.
+    static LIVE: RefCell<HashMap<usize, (&'static str, Backtrace)>> = RefCell::new(HashMap::new());
+}
+
 /// A pointer type for heap allocation using the Zend memory manager.
 ///
 /// See the [module level documentation](../index.html) for more.
@@ -48,7 +67,17 @@ impl<T: ZBoxable> ZBox<T> {
     /// Caller must ensure that `ptr` is non-null, well-aligned and pointing to
     /// a `T`.
     pub unsafe fn from_raw(ptr: *mut T) -> Self {
-        Self(NonNull::new_unchecked(ptr))
+        let this = Self(NonNull::new_unchecked(ptr));
+        #[cfg(debug_assertions)]
+        if this.is_request_bound() {
+            let _ = LIVE.try_with(|live| {
+                live.borrow_mut().insert(
+                    ptr as usize,
+                    (std::any::type_name::<T>(), Backtrace::capture()),
+                )
+            });
+        }
+        this
     }
 
     /// Returns the pointer contained by the box, dropping the box in the
@@ -61,19 +90,72 @@ impl<T: ZBoxable> ZBox<T> {
     /// returned pointer, including freeing the memory.
     pub fn into_raw(self) -> &'static mut T {
         let mut this = ManuallyDrop::new(self);
+        this.forget_live();
         // SAFETY: All constructors ensure the contained pointer is well-aligned and
         // dereferenceable.
         unsafe { this.0.as_mut() }
     }
+
+    /// Removes the box from the boxes which are alive, as its value is freed
+    /// or no longer owned by the box.
+    #[inline]
+    fn forget_live(&self) {
+        #[cfg(debug_assertions)]
+        let _ = LIVE.try_with(|live| live.borrow_mut().remove(&(self.0.as_ptr() as usize)));
+    }
 }
 
 impl<T: ZBoxable> Drop for ZBox<T> {
     #[inline]
     fn drop(&mut self) {
+        self.forget_live();
         self.deref_mut().free()
     }
 }
 
+/// Prints a warning listing the boxes of request-bound values on this thread
+/// which are still alive, and forgets them. Called once a request has ended,
+/// as these boxes point to memory which PHP is about to release.
+///
+/// Returns whether any boxes were still alive.
+#[cfg(debug_assertions)]
+pub(crate) fn report_leaks() -> bool {
+    use std::backtrace::BacktraceStatus;
+
+    let live = LIVE
+        .try_with(|live| std::mem::take(&mut *live.borrow_mut()))
+        .unwrap_or_default();
+    if live.is_empty() {
+        return false;
+    }
+
+    let mut report = format!(
+        "ext-php-rs: warning: {} ZBox value(s) outlived the request and point to freed memory:",
+        live.len()
+    );
+    let mut captured = false;
+    for (ty, backtrace) in live.values() {
+        report.push_str(&format!("\n  ZBox<{ty}>"));
+        if backtrace.status() == BacktraceStatus::Captured {
+            captured = true;
+            report.push_str(&format!(" created at:\n{backtrace}"));
+        }
+    }
+    if !captured {
+        report.push_str("\nRun with `RUST_BACKTRACE=1` to show where the values were created.");
+    }
+    eprintln!("{report}");
+
+    true
+}
+
+/// Returns whether the box of the value at the given address is recorded as
+/// alive.
+#[cfg(all(test, debug_assertions))]
+fn is_live<T>(ptr: *const T) -> bool {
+    LIVE.with(|live| live.borrow().contains_key(&(ptr as usize)))
+}
+
 impl<T: ZBoxable> Deref for ZBox<T> {
     type Target = T;
 
@@ -135,4 +217,39 @@ pub unsafe trait ZBoxable {
     fn free(&mut self) {
         unsafe { efree(self as *mut _ as *mut u8) };
     }
+
+    /// Returns whether the value is released by PHP at the end of the
+    /// request, and therefore must not outlive the request. Used by debug
+    /// builds to find boxes which are still alive once the request has ended.
+    ///
+    /// The default implementation returns `true`. Types which can also be
+    /// allocated persistently should return `false` for persistent values.
+    fn is_request_bound(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(all(test, debug_assertions))]
+mod test {
+    use super::{is_live, ZBox};
+    use crate::types::{ZendHashTable, ZendStr};
+
+    #[test]
+    fn test_live_boxes() {
+        crate::test::run(|| {
+            let s = ZendStr::new("foo", false);
+            let ptr = s.as_ptr();
+            assert!(is_live(ptr));
+            drop(s);
+            assert!(!is_live(ptr));
+
+            let s = ZendStr::new_persistent("foo");
+            assert!(!is_live(s.as_ptr()));
+
+            let ht = ZendHashTable::new();
+            let raw = ht.into_raw();
+            assert!(!is_live(raw));
+            drop(unsafe { ZBox::from_raw(raw) });
+        });
+    }
 }
@@ -1,30 +1,87 @@
-use std::{ffi::CString, mem::MaybeUninit};
+use std::{
+    collections::BTreeMap,
+    ffi::{c_void, CStr},
+    mem::{self, MaybeUninit},
+    os::raw::c_int,
+    ptr,
+    sync::Arc,
+};
+
+use parking_lot::{const_rwlock, RwLock};
 
 use crate::{
-    builders::FunctionBuilder,
+    args::Arg,
+    boxed::ZBox,
+    builders::{function::FunctionPointerHandler, FunctionBuilder, FunctionHandler},
     class::{ConstructorMeta, ConstructorResult, RegisteredClass},
     convert::IntoZval,
     error::{Error, Result},
-    exception::PhpException,
+    exception::{throw_object, PhpException},
     ffi::{
-        zend_declare_class_constant, zend_declare_property, zend_do_implement_interface,
-        zend_register_internal_class_ex,
+        __zend_malloc, zend_class_name, zend_declare_class_constant_ex, zend_declare_property_ex,
+        zend_declare_typed_property, zend_do_implement_interface, zend_hash_find,
+        zend_hash_str_find_ptr_lc, zend_hash_str_update, zend_internal_function,
+        zend_register_internal_class_ex, _ZEND_TYPE_NAME_BIT, _ZEND_TYPE_NULLABLE_BIT,
+        GC_IMMUTABLE, ZEND_ACC_ABSTRACT,
+    },
+    flags::{
+        ClassFlags, ConstantFlags, DataType, FunctionType, MethodFlags, PropertyFlags,
+        ZvalTypeFlags,
+    },
+    types::{ZendClassObject, ZendIterator, ZendObject, ZendStr, Zval},
+    zend::{
+        ce, get_iterator, ClassConstant, ClassEntry, ExecuteData, Function, FunctionEntry,
+        PropertyInfo, ZendType,
     },
-    flags::{ClassFlags, MethodFlags, PropertyFlags},
-    types::{ZendClassObject, ZendObject, ZendStr, Zval},
-    zend::{ClassEntry, ExecuteData, FunctionEntry},
     zend_fastcall,
 };
 
+/// Handler of a method added with [`ClassBuilder::dynamic_method`].
+pub type DynamicMethodHandler = dyn Fn(&mut ExecuteData, &mut Zval) + Send + Sync;
+
+/// Closures backing the methods added with [`ClassBuilder::dynamic_method`],
+/// keyed by the name of the class and the lowercase name of the method.
+static DYNAMIC_METHODS: RwLock<BTreeMap<(String, String), Arc<DynamicMethodHandler>>> =
+    const_rwlock(BTreeMap::new());
+
+zend_fastcall! {
+    /// Handler of all methods added with [`ClassBuilder::dynamic_method`],
+    /// which calls the closure registered for the method being executed.
+    pub(crate) extern fn dynamic_method_handler(ex: &mut ExecuteData, retval: &mut Zval) {
+        let handler = ex.function().and_then(|func| {
+            let class = func.scope()?.name()?.to_string();
+            let name = func.name()?.as_str().ok()?.to_ascii_lowercase();
+            DYNAMIC_METHODS.read().get(&(class, name)).cloned()
+        });
+
+        match handler {
+            Some(handler) => handler(ex, retval),
+            None => {
+                let _ = PhpException::default("No handler is registered for this method.".into())
+                    .throw();
+            }
+        }
+    }
+}
+
 /// Builder for registering a class in PHP.
 pub struct ClassBuilder {
     name: String,
     ce: ClassEntry,
     extends: Option<&'static ClassEntry>,
     interfaces: Vec<&'static ClassEntry>,
+    traits: Vec<&'static ClassEntry>,
     methods: Vec<FunctionEntry>,
+    dynamic_methods: Vec<(String, Arc<DynamicMethodHandler>)>,
     object_override: Option<unsafe extern "C" fn(class_type: *mut ClassEntry) -> *mut ZendObject>,
-    properties: Vec<(String, Zval, PropertyFlags)>,
+    get_iterator: Option<
+        unsafe extern "C" fn(
+            ce: *mut ClassEntry,
+            object: *mut Zval,
+            by_ref: c_int,
+        ) -> *mut ZendIterator,
+    >,
+    properties: Vec<ClassProperty>,
     constants: Vec<(String, Zval)>,
 }
 
@@ -43,8 +100,11 @@ impl ClassBuilder {
             ce: unsafe { MaybeUninit::zeroed().assume_init() },
             extends: None,
             interfaces: vec![],
+            traits: vec![],
             methods: vec![],
+            dynamic_methods: vec![],
             object_override: None,
+            get_iterator: None,
             properties: vec![],
             constants: vec![],
         }
@@ -78,6 +138,37 @@ impl ClassBuilder {
         self
     }
 
+    /// Uses a trait in the class, copying the members of the trait into the
+    /// class when it is built. Methods declared on the class itself take
+    /// precedence over the methods of the trait, while methods inherited from
+    /// the parent class are overridden.
+    ///
+    /// The methods keep the trait as their scope, so protected and private
+    /// methods of the trait are only accessible from the trait. Abstract
+    /// methods of the trait are not copied, and magic methods are not
+    /// registered as the handlers of the class.
+    ///
+    /// The properties and constants of the trait are declared on the class,
+    /// unless the class declares or inherits a member with the same name.
+    /// Properties with union or intersection types are declared without a
+    /// type.
+    ///
+    /// Only traits defined by extensions can be used, as traits declared in
+    /// PHP scripts are not available while the extension starts up.
+    ///
+    /// # Parameters
+    ///
+    /// * `trait_` - Trait to use in the class.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the given class entry `trait_` is not a trait.
+    pub fn uses_trait(mut self, trait_: &'static ClassEntry) -> Self {
+        assert!(trait_.is_trait(), "Given class entry was not a trait.");
+        self.traits.push(trait_);
+        self
+    }
+
     /// Adds a method to the class.
     ///
     /// # Parameters
@@ -90,6 +181,70 @@ impl ClassBuilder {
         self
     }
 
+    /// Adds a method to the class which is handled by a Rust closure rather
+    /// than an `extern` function. This allows classes to be built at module
+    /// startup from method lists which are only known at runtime, for example
+    /// when they are read from a configuration or schema file.
+    ///
+    /// The closure receives the execute data of the call, from which the
+    /// arguments and `$this` can be retrieved, and sets the return value.
+    ///
+    /// # Parameters
+    ///
+    /// * `func` - The function entry to add to the class, usually built with
+    ///   [`FunctionBuilder::new_dynamic`]. Its handler is replaced.
+    /// * `flags` - Flags relating to the function. See [`MethodFlags`].
+    /// * `handler` - The closure called when the method is invoked from PHP.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::{
+    ///     args::Arg,
+    ///     builders::{ClassBuilder, FunctionBuilder},
+    ///     flags::{DataType, MethodFlags},
+    /// };
+    ///
+    /// let mut builder = ClassBuilder::new("Greeter");
+    /// for greeting in ["hello", "goodbye"] {
+    ///     let func = FunctionBuilder::new_dynamic(greeting)
+    ///         .arg(Arg::new("name", DataType::String))
+    ///         .returns(DataType::String, false, false)
+    ///         .build()
+    ///         .unwrap();
+    ///     builder = builder.dynamic_method(func, MethodFlags::Public, move |ex, retval| {
+    ///         let mut name = Arg::new("name", DataType::String);
+    ///         if ex.parser().arg(&mut name).parse().is_err() {
+    ///             return;
+    ///         }
+    ///         let name: String = name.val().unwrap_or_default();
+    ///         let _ = retval.set_string(&format!("{}, {}!", greeting, name), false);
+    ///     });
+    /// }
+    /// builder.build().unwrap();
+    /// ```
+    pub fn dynamic_method<F>(
+        mut self,
+        mut func: FunctionEntry,
+        flags: MethodFlags,
+        handler: F,
+    ) -> Self
+    where
+        F: Fn(&mut ExecuteData, &mut Zval) + Send + Sync + 'static,
+    {
+        assert!(!func.fname.is_null(), "Function entry has not been built.");
+        // SAFETY: Names of built function entries are valid C strings.
+        let name = unsafe { CStr::from_ptr(func.fname) }
+            .to_string_lossy()
+            .to_ascii_lowercase();
+        // SAFETY: See `FunctionBuilder::new`.
+        func.handler = Some(unsafe {
+            mem::transmute::<FunctionHandler, FunctionPointerHandler>(dynamic_method_handler)
+        });
+        self.dynamic_methods.push((name, Arc::new(handler)));
+        self.method(func, flags)
+    }
+
     /// Adds a property to the class. The initial type of the property is given
     /// by the type of the given default. Note that the user can change the
     /// type.
@@ -115,13 +270,108 @@ impl ClassBuilder {
             Err(_) => panic!("Invalid default value for property `{}`.", name.into()),
         };
 
-        self.properties.push((name.into(), default, flags));
+        self.properties.push(ClassProperty {
+            name: name.into(),
+            default: Some(default),
+            flags,
+            ty: None,
+            doc_comment: None,
+        });
+        self
+    }
+
+    /// Adds a static property to the class. The property is stored on the
+    /// class entry and shared by all instances of the class, and can be
+    /// accessed from Rust with [`ClassEntry::get_static_property`] and
+    /// [`ClassEntry::set_static_property`].
+    ///
+    /// To declare a typed static property, pass [`PropertyFlags::Static`] to
+    /// [`ClassBuilder::typed_property`] instead.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property to add to the class.
+    /// * `default` - The default value of the property.
+    /// * `flags` - Flags relating to the property. [`PropertyFlags::Static`] is
+    ///   added to the given flags.
+    ///
+    /// # Panics
+    ///
+    /// Function will panic if the given `default` cannot be converted into a
+    /// [`Zval`].
+    pub fn static_property<T: Into<String>>(
+        self,
+        name: T,
+        default: impl IntoZval,
+        flags: PropertyFlags,
+    ) -> Self {
+        self.property(name, default, flags | PropertyFlags::Static)
+    }
+
+    /// Adds a typed property to the class. The engine rejects values of other
+    /// types, and reflection reports the type of the property.
+    ///
+    /// Properties without a default value are uninitialized until they are
+    /// assigned. Types which cannot be used for properties, such as
+    /// [`DataType::Callable`], declare an untyped property defaulting to null
+    /// instead.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property to add to the class.
+    /// * `ty` - The type of the property.
+    /// * `allow_null` - Whether the property can be set to null.
+    /// * `default` - The default value of the property, which must be
+    ///   persistent and of the type of the property.
+    /// * `flags` - Flags relating to the property. See [`PropertyFlags`].
+    pub fn typed_property<T: Into<String>>(
+        mut self,
+        name: T,
+        ty: DataType,
+        allow_null: bool,
+        default: Option<Zval>,
+        flags: PropertyFlags,
+    ) -> Self {
+        self.properties.push(ClassProperty {
+            name: name.into(),
+            default,
+            flags,
+            ty: Some((ty, allow_null)),
+            doc_comment: None,
+        });
+        self
+    }
+
+    /// Sets the doc comment of a property which has been added to the class,
+    /// which is returned by `ReflectionProperty::getDocComment()`. Has no
+    /// effect if the class does not have a property with the given name.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property.
+    /// * `doc_comment` - The doc comment, including the `/**` and `*/`
+    ///   delimiters.
+    pub fn property_doc_comment<T: Into<String>>(mut self, name: &str, doc_comment: T) -> Self {
+        if let Some(prop) = self
+            .properties
+            .iter_mut()
+            .rev()
+            .find(|prop| prop.name == name)
+        {
+            prop.doc_comment = Some(doc_comment.into());
+        }
         self
     }
 
     /// Adds a constant to the class. The type of the constant is defined by the
     /// type of the given default.
     ///
+    /// Besides scalars and strings, constants may be arrays, such as a
+    /// [`Vec`] or a fixed-size array of scalars. These are shared between all
+    /// requests without being copied, like constants declared in PHP. Cases of
+    /// enums exported with [`php_enum`](crate::php_enum) refer to the case,
+    /// which is created when the constant is first read in a request.
+    ///
     /// Returns a result containing the class builder if the constant was
     /// successfully added.
     ///
@@ -208,7 +458,10 @@ impl ClassBuilder {
             "Class name in builder does not match class name in `impl RegisteredClass`."
         );
         self.object_override = Some(create_object::<T>);
-        self.method(
+        if T::ITERATOR.is_some() {
+            self.get_iterator = Some(get_iterator::<T>);
+        }
+        let builder = self.method(
             {
                 let mut func = FunctionBuilder::new("__construct", constructor::<T>);
                 if let Some(ConstructorMeta { build_fn, .. }) = T::CONSTRUCTOR {
@@ -217,11 +470,32 @@ impl ClassBuilder {
                 func.build().expect("Failed to build constructor function")
             },
             MethodFlags::Public,
-        )
+        );
+        if T::DELEGATE.is_none() {
+            return builder;
+        }
+        builder
+            .method(
+                FunctionBuilder::new("__call", delegate_call::<T>)
+                    .arg(Arg::new("name", DataType::String))
+                    .arg(Arg::new("arguments", DataType::Array))
+                    .build()
+                    .expect("Failed to build `__call` function"),
+                MethodFlags::Public,
+            )
+            .method(
+                FunctionBuilder::new("__get", delegate_get::<T>)
+                    .arg(Arg::new("name", DataType::String))
+                    .build()
+                    .expect("Failed to build `__get` function"),
+                MethodFlags::Public,
+            )
     }
 
     /// Builds the class, returning a reference to the class entry.
     ///
+    /// The class is marked as immutable, as it cannot be changed once built.
+    ///
     /// # Errors
     ///
     /// Returns an [`Error`] variant if the class could not be registered.
@@ -266,34 +540,446 @@ impl ClassBuilder {
             };
         }
 
-        for (name, mut default, flags) in self.properties {
-            unsafe {
-                zend_declare_property(
-                    class,
-                    CString::new(name.as_str())?.as_ptr(),
-                    name.len() as _,
-                    &mut default,
-                    flags.bits() as _,
-                );
+        // Names are interned once here, as the engine would otherwise create an interned
+        // copy of each name itself. Interned strings are never freed, so they can be
+        // handed to the engine without transferring ownership.
+        for prop in self.properties {
+            let mut name = ZendStr::new_interned(&prop.name, true);
+            let ty = prop
+                .ty
+                .and_then(|(ty, allow_null)| property_type(ty, allow_null));
+            let mut default = match (prop.default, &ty) {
+                (Some(default), _) => default,
+                // Typed properties without a default start out uninitialized.
+                (None, Some(_)) => {
+                    let mut undef = Zval::new();
+                    undef.u1.type_info = ZvalTypeFlags::Undef.bits();
+                    undef
+                }
+                (None, None) => Zval::new(),
+            };
+            make_immutable(&mut default);
+            let doc_comment = prop.doc_comment.map_or(ptr::null_mut(), |doc_comment| {
+                ZendStr::new_interned(&doc_comment, true).as_mut_ptr()
+            });
+            match ty {
+                Some(ty) => unsafe {
+                    zend_declare_typed_property(
+                        class,
+                        name.as_mut_ptr(),
+                        &mut default,
+                        prop.flags.bits() as _,
+                        doc_comment,
+                        ty,
+                    );
+                },
+                None => unsafe {
+                    zend_declare_property_ex(
+                        class,
+                        name.as_mut_ptr(),
+                        &mut default,
+                        prop.flags.bits() as _,
+                        doc_comment,
+                    );
+                },
             }
         }
 
-        for (name, value) in self.constants {
+        for (name, mut value) in self.constants {
+            let mut name = ZendStr::new_interned(&name, true);
+            make_immutable(&mut value);
             let value = Box::into_raw(Box::new(value));
             unsafe {
-                zend_declare_class_constant(
+                zend_declare_class_constant_ex(
                     class,
-                    CString::new(name.as_str())?.as_ptr(),
-                    name.len(),
+                    name.as_mut_ptr(),
                     value,
+                    ConstantFlags::Public.bits() as _,
+                    ptr::null_mut(),
                 )
             };
         }
 
+        if !self.traits.is_empty() {
+            for trait_ in &self.traits {
+                bind_trait(class, trait_);
+            }
+
+            // Trait names are recorded so `class_uses()` and reflection list the
+            // traits. The engine does not free them for internal classes.
+            let names = self
+                .traits
+                .iter()
+                .filter_map(|trait_| trait_.name())
+                .map(|name| zend_class_name {
+                    name: ZendStr::new_interned(name, true).into_raw(),
+                    lc_name: ZendStr::new_interned(name.to_ascii_lowercase(), true).into_raw(),
+                })
+                .collect::<Vec<_>>();
+            class.num_traits = names.len() as _;
+            class.trait_names = Box::leak(names.into_boxed_slice()).as_mut_ptr();
+        }
+
         if let Some(object_override) = self.object_override {
             class.__bindgen_anon_2.create_object = Some(object_override);
         }
 
+        if let Some(get_iterator) = self.get_iterator {
+            class.get_iterator = Some(get_iterator);
+        }
+
+        if !self.dynamic_methods.is_empty() {
+            let mut methods = DYNAMIC_METHODS.write();
+            for (name, handler) in self.dynamic_methods {
+                methods.insert((self.name.clone(), name), handler);
+            }
+        }
+
+        // The class is not modified after it has been built, so it is marked as
+        // immutable like the classes OPcache stores in shared memory.
+        class.ce_flags |= ClassFlags::Immutable.bits();
+
         Ok(class)
     }
 }
+
+zend_fastcall! {
+    /// Handler of the `__call()` method of classes with a delegate, which calls
+    /// the method on the delegate from the scope of the class.
+    extern fn delegate_call<T: RegisteredClass>(ex: &mut ExecuteData, retval: &mut Zval) {
+        let mut name = Arg::new("name", DataType::String);
+        let mut arguments = Arg::new("arguments", DataType::Array);
+        let (parser, this) = ex.parser_method::<T>();
+        if parser.arg(&mut name).arg(&mut arguments).parse().is_err() {
+            return;
+        }
+
+        let name: String = name.val().unwrap_or_default();
+        let params = arguments
+            .zval()
+            .and_then(|args| args.array())
+            .map(|args| args.values().map(Zval::shallow_clone).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let result = match this.and_then(delegate::<T>) {
+            Some(inner) => inner.call_method_in_scope(&name, &params, None).map_err(|err| {
+                match err {
+                    Error::Callable => undefined_method(inner, &name),
+                    err => err,
+                }
+            }),
+            None => Err(Error::Callable),
+        };
+        if let Err(err) = delegate_result(result, retval) {
+            let _ = PhpException::from(err).throw();
+        }
+    }
+}
+
+zend_fastcall! {
+    /// Handler of the `__get()` method of classes with a delegate, which reads
+    /// the property from the delegate.
+    extern fn delegate_get<T: RegisteredClass>(ex: &mut ExecuteData, retval: &mut Zval) {
+        let mut name = Arg::new("name", DataType::String);
+        let (parser, this) = ex.parser_method::<T>();
+        if parser.arg(&mut name).parse().is_err() {
+            return;
+        }
+
+        let name: String = name.val().unwrap_or_default();
+        let result = match this.and_then(delegate::<T>) {
+            Some(inner) => inner.read_property(&name),
+            None => Err(Error::InvalidProperty),
+        };
+        if let Err(err) = delegate_result(result, retval) {
+            let _ = PhpException::from(err).throw();
+        }
+    }
+}
+
+/// Returns the delegate of an object of a class with a delegate.
+fn delegate<T: RegisteredClass>(this: &mut ZendClassObject<T>) -> Option<&mut ZendObject> {
+    let delegate = T::DELEGATE?;
+    delegate(this.obj.as_mut()?)
+}
+
+/// Returns the error thrown by PHP when an undefined method is called.
+fn undefined_method(obj: &ZendObject, name: &str) -> Error {
+    let message = format!(
+        "Call to undefined method {}::{}()",
+        obj.get_class_name().unwrap_or_default(),
+        name
+    );
+    match ZendObject::new_with_args(ce::error(), vec![&message]) {
+        Ok(e) => Error::Exception(e),
+        Err(err) => err,
+    }
+}
+
+/// Returns the result of a forwarded call, rethrowing exceptions thrown by the
+/// delegate.
+///
+/// # Errors
+///
+/// Returns the error of the call if it did not throw an exception, or the
+/// error which occurred while rethrowing the exception.
+fn delegate_result(result: Result<Zval>, retval: &mut Zval) -> Result<()> {
+    match result {
+        Ok(value) => {
+            *retval = value;
+            Ok(())
+        }
+        Err(Error::Exception(e)) => e.into_zval(false).and_then(throw_object),
+        Err(err) => Err(err),
+    }
+}
+
+/// Copies the methods, properties and constants of a trait into a class.
+/// Members declared on the class, or inherited by it, take precedence.
+///
+/// The copies of methods keep the trait as their scope. The engine frees the
+/// argument information of methods scoped to an internal class when the class
+/// is destroyed, which would otherwise happen twice for every trait method.
+fn bind_trait(class: &mut ClassEntry, trait_: &ClassEntry) {
+    bind_trait_methods(class, trait_);
+    bind_trait_properties(class, trait_);
+    bind_trait_constants(class, trait_);
+}
+
+fn bind_trait_methods(class: &mut ClassEntry, trait_: &ClassEntry) {
+    for (name, zv) in trait_.function_table.iter() {
+        // SAFETY: The function table only contains pointers to functions.
+        let func = match unsafe { zv.ptr::<Function>().and_then(|func| func.as_ref()) } {
+            Some(func) => func,
+            None => continue,
+        };
+        if func.function_type() != FunctionType::Internal
+            || unsafe { func.common.fn_flags } & ZEND_ACC_ABSTRACT != 0
+        {
+            continue;
+        }
+
+        let name = String::from(name);
+        let existing = unsafe {
+            zend_hash_str_find_ptr_lc(&class.function_table, name.as_ptr() as _, name.len())
+                as *const Function
+        };
+        if let Some(existing) = unsafe { existing.as_ref() } {
+            if ptr::eq(unsafe { existing.common.scope }, class as *const _) {
+                continue;
+            }
+        }
+
+        // The function table of an internal class frees its functions with
+        // `free()`, so the copy must come from the system allocator.
+        unsafe {
+            let copy = __zend_malloc(mem::size_of::<zend_internal_function>())
+                as *mut zend_internal_function;
+            ptr::write(copy, func.internal_function);
+            let function_name = &mut *(*copy).function_name;
+            if function_name.gc.u.type_info & GC_IMMUTABLE == 0 {
+                function_name.gc.refcount += 1;
+            }
+
+            let mut zv = Zval::new();
+            zv.set_ptr(copy);
+            zend_hash_str_update(
+                &mut class.function_table,
+                name.as_ptr() as _,
+                name.len(),
+                &mut zv,
+            );
+        }
+    }
+}
+
+/// Declares the properties of a trait on a class. Properties with union or
+/// intersection types are declared without a type, as the type lists are
+/// freed with the class which declares them.
+fn bind_trait_properties(class: &mut ClassEntry, trait_: &ClassEntry) {
+    // The offset of a declared property is the offset of its slot from the start
+    // of the object, mirroring the `OBJ_PROP_TO_NUM` macro.
+    let table_offset = {
+        let obj = MaybeUninit::<ZendObject>::uninit();
+        unsafe { ptr::addr_of!((*obj.as_ptr()).properties_table) as usize - obj.as_ptr() as usize }
+    };
+
+    for element in trait_.properties_info.raw_elements() {
+        // SAFETY: The property table only contains pointers to property information,
+        // keyed by the unmangled name of the property.
+        let (key, info) = match (element.key, unsafe {
+            element
+                .val
+                .ptr::<PropertyInfo>()
+                .and_then(|info| info.as_ref())
+        }) {
+            (Some(key), Some(info)) => (key, info),
+            _ => continue,
+        };
+        let exists = unsafe {
+            !zend_hash_find(
+                &class.properties_info,
+                key as *const ZendStr as *mut ZendStr,
+            )
+            .is_null()
+        };
+        let name = match key.as_str() {
+            Ok(name) if !exists => name,
+            _ => continue,
+        };
+
+        let flags = info.flags()
+            & (PropertyFlags::Public
+                | PropertyFlags::Protected
+                | PropertyFlags::Private
+                | PropertyFlags::Static);
+        // SAFETY: The offset of a static property is its index in the static members.
+        let default = unsafe {
+            if flags.contains(PropertyFlags::Static) {
+                &*trait_
+                    .default_static_members_table
+                    .add(info.offset as usize)
+            } else {
+                &*trait_
+                    .default_properties_table
+                    .add((info.offset as usize - table_offset) / mem::size_of::<Zval>())
+            }
+        };
+        let mut default = default.shallow_clone();
+        make_immutable(&mut default);
+        let mut name = ZendStr::new_interned(name, true);
+        let ty = info.type_;
+        unsafe {
+            if ty.ptr.is_null() || ty.type_mask & _ZEND_TYPE_NAME_BIT != 0 {
+                zend_declare_typed_property(
+                    class,
+                    name.as_mut_ptr(),
+                    &mut default,
+                    flags.bits() as _,
+                    info.doc_comment,
+                    ty,
+                );
+            } else {
+                zend_declare_property_ex(
+                    class,
+                    name.as_mut_ptr(),
+                    &mut default,
+                    flags.bits() as _,
+                    info.doc_comment,
+                );
+            }
+        }
+    }
+}
+
+/// Declares the constants of a trait on a class.
+fn bind_trait_constants(class: &mut ClassEntry, trait_: &ClassEntry) {
+    for element in trait_.constants_table.raw_elements() {
+        // SAFETY: The constant table only contains pointers to constants.
+        let (key, constant) = match (element.key, unsafe {
+            element
+                .val
+                .ptr::<ClassConstant>()
+                .and_then(|constant| constant.as_ref())
+        }) {
+            (Some(key), Some(constant)) => (key, constant),
+            _ => continue,
+        };
+        let exists = unsafe {
+            !zend_hash_find(
+                &class.constants_table,
+                key as *const ZendStr as *mut ZendStr,
+            )
+            .is_null()
+        };
+        let name = match key.as_str() {
+            Ok(name) if !exists => name,
+            _ => continue,
+        };
+
+        let mut name = ZendStr::new_interned(name, true);
+        // The engine copies the value into the constant it allocates.
+        let mut value = constant.value().shallow_clone();
+        make_immutable(&mut value);
+        unsafe {
+            zend_declare_class_constant_ex(
+                class,
+                name.as_mut_ptr(),
+                &mut value,
+                constant.flags().bits() as _,
+                constant.doc_comment,
+            )
+        };
+    }
+}
+
+/// A property declared on a class by a [`ClassBuilder`].
+struct ClassProperty {
+    name: String,
+    default: Option<Zval>,
+    flags: PropertyFlags,
+    /// The type of the property and whether it is nullable, if it is typed.
+    ty: Option<(DataType, bool)>,
+    doc_comment: Option<String>,
+}
+
+/// Returns the type of a property declared with the given type, or [`None`]
+/// if the type cannot be used for properties.
+fn property_type(ty: DataType, allow_null: bool) -> Option<ZendType> {
+    match ty {
+        // Class names of property types are stored as strings rather than the C
+        // strings used by argument types.
+        DataType::Object(Some(class)) => Some(ZendType {
+            ptr: ZendStr::new_interned(class, true).into_raw() as *mut ZendStr as *mut c_void,
+            type_mask: _ZEND_TYPE_NAME_BIT
+                | if allow_null {
+                    _ZEND_TYPE_NULLABLE_BIT
+                } else {
+                    0
+                },
+        }),
+        DataType::Bool
+        | DataType::False
+        | DataType::True
+        | DataType::Long
+        | DataType::Double
+        | DataType::String
+        | DataType::Array
+        | DataType::Iterable
+        | DataType::Object(None)
+        | DataType::Mixed => ZendType::empty_from_type(ty, false, false, allow_null),
+        _ => None,
+    }
+}
+
+/// Prepares the value of a class constant to be read by every request. Strings,
+/// including the keys of arrays, are interned and arrays are marked as
+/// immutable, so the engine copies them without touching their reference
+/// counts, which would race between threads on ZTS builds.
+///
+/// Must be called during module startup, so the interned strings are
+/// permanent.
+pub(crate) fn make_immutable(zv: &mut Zval) {
+    if let Some(interned) = zv
+        .zend_str()
+        .map(|str| ZendStr::new_interned(str.as_bytes(), true))
+    {
+        zv.set_zend_string(interned);
+        zv.u1.type_info = ZvalTypeFlags::InternedStringEx.bits();
+    } else if let Some(ht) = zv.array_mut() {
+        for element in ht.raw_elements_mut() {
+            if let Some(key) = element.key {
+                // SAFETY: String keys of buckets are valid strings. The interned key is
+                // equal to the old key, so the element keeps its position in the hash.
+                unsafe {
+                    if (**key).gc.u.type_info & GC_IMMUTABLE == 0 {
+                        let old = ZBox::from_raw(*key);
+                        *key = ZendStr::new_interned(old.as_bytes(), true).into_raw();
+                    }
+                }
+            }
+            make_immutable(element.val);
+        }
+        unsafe { ht.gc.u.type_info |= GC_IMMUTABLE };
+        zv.u1.type_info = ZvalTypeFlags::Array.bits();
+    }
+}
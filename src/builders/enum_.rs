@@ -0,0 +1,259 @@
+use std::{collections::HashSet, ffi::CString, ptr, slice};
+
+use crate::{
+    builders::class::make_immutable,
+    convert::IntoZval,
+    enum_::Discriminant,
+    error::{Error, Result},
+    ffi::{
+        zend_add_attribute, zend_declare_class_constant_ex, zend_do_implement_interface,
+        zend_enum_add_case_cstr, zend_register_internal_enum, IS_LONG, IS_STRING, IS_UNDEF,
+        ZEND_ATTRIBUTE_PERSISTENT,
+    },
+    flags::{ConstantFlags, MethodFlags},
+    types::{ZendStr, Zval},
+    zend::{ClassConstant, ClassEntry, FunctionEntry},
+};
+
+/// Builder for registering a native enum in PHP.
+///
+/// The `cases()` method, and the `from()` and `tryFrom()` methods of backed
+/// enums, are added by PHP.
+pub struct EnumBuilder {
+    name: String,
+    cases: Vec<(String, Option<Discriminant>)>,
+    case_attributes: Vec<(String, String, Vec<Zval>)>,
+    methods: Vec<FunctionEntry>,
+    interfaces: Vec<&'static ClassEntry>,
+    constants: Vec<(String, Zval)>,
+}
+
+impl EnumBuilder {
+    /// Creates a new enum builder, used to build enums to be exported to PHP.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the enum.
+    pub fn new<T: Into<String>>(name: T) -> Self {
+        Self {
+            name: name.into(),
+            cases: vec![],
+            case_attributes: vec![],
+            methods: vec![],
+            interfaces: vec![],
+            constants: vec![],
+        }
+    }
+
+    /// Adds a case to the enum. Either all or none of the cases of an enum
+    /// must be given a value, and the values must be of the same type.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the case.
+    /// * `value` - The value backing the case, if the enum is a backed enum.
+    pub fn case<T: Into<String>>(mut self, name: T, value: Option<Discriminant>) -> Self {
+        self.cases.push((name.into(), value));
+        self
+    }
+
+    /// Applies an attribute to a case of the enum, which can be read with
+    /// reflection, e.g. `ReflectionEnumUnitCase::getAttributes()`. The
+    /// attribute class is not required to exist.
+    ///
+    /// # Parameters
+    ///
+    /// * `case` - The name of the case.
+    /// * `name` - The fully qualified name of the attribute class, without a
+    ///   leading backslash.
+    /// * `args` - The arguments passed to the attribute, which must be scalars
+    ///   or arrays.
+    pub fn case_attribute<C: Into<String>, N: Into<String>>(
+        mut self,
+        case: C,
+        name: N,
+        args: Vec<Zval>,
+    ) -> Self {
+        self.case_attributes.push((case.into(), name.into(), args));
+        self
+    }
+
+    /// Adds a method to the enum.
+    ///
+    /// # Parameters
+    ///
+    /// * `func` - The function entry to add to the enum.
+    /// * `flags` - Flags relating to the function. See [`MethodFlags`].
+    pub fn method(mut self, mut func: FunctionEntry, flags: MethodFlags) -> Self {
+        func.flags |= flags.bits();
+        self.methods.push(func);
+        self
+    }
+
+    /// Implements an interface on the enum. The methods of the interface must
+    /// be added to the enum.
+    ///
+    /// # Parameters
+    ///
+    /// * `interface` - Interface to implement on the enum.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the given class entry `interface` is not an interface.
+    pub fn implements(mut self, interface: &'static ClassEntry) -> Self {
+        assert!(
+            interface.is_interface(),
+            "Given class entry was not an interface."
+        );
+        self.interfaces.push(interface);
+        self
+    }
+
+    /// Adds a constant to the enum. The value of the constant is converted into
+    /// a persistent value and shared between all requests, in the same way as
+    /// the constants of classes.
+    ///
+    /// Returns a result containing the enum builder if the constant was
+    /// successfully added.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the constant to add to the enum.
+    /// * `value` - The value of the constant.
+    pub fn constant<T: Into<String>>(mut self, name: T, value: impl IntoZval) -> Result<Self> {
+        let value = value.into_zval(true)?;
+
+        self.constants.push((name.into(), value));
+        Ok(self)
+    }
+
+    /// Builds the enum, returning a reference to the class entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidEnumCase`] if two cases have the same name or
+    /// value, if a case has the same name as a constant, if the values of the
+    /// cases are not all of the same type, or if an attribute is applied to a
+    /// case which does not exist or is given an object or resource argument.
+    /// Returns another [`Error`] variant if the enum could not be registered.
+    pub fn build(mut self) -> Result<&'static mut ClassEntry> {
+        let backing = match self.cases.first() {
+            Some((_, Some(Discriminant::Int(_)))) => IS_LONG,
+            Some((_, Some(Discriminant::String(_)))) => IS_STRING,
+            _ => IS_UNDEF,
+        };
+        let mut names = HashSet::new();
+        let mut values = HashSet::new();
+        for (name, value) in &self.cases {
+            let valid = names.insert(name.as_str())
+                && match value {
+                    Some(Discriminant::Int(_)) => backing == IS_LONG && values.insert(*value),
+                    Some(Discriminant::String(_)) => backing == IS_STRING && values.insert(*value),
+                    None => backing == IS_UNDEF,
+                };
+            if !valid {
+                return Err(Error::InvalidEnumCase(name.clone()));
+            }
+        }
+        if let Some((name, _, _)) = self.case_attributes.iter().find(|(case, _, args)| {
+            !names.contains(case.as_str())
+                || args
+                    .iter()
+                    .any(|arg| arg.is_object() || arg.is_resource() || arg.is_reference())
+        }) {
+            return Err(Error::InvalidEnumCase(name.clone()));
+        }
+        if let Some((name, _)) = self
+            .constants
+            .iter()
+            .find(|(name, _)| names.contains(name.as_str()))
+        {
+            return Err(Error::InvalidEnumCase(name.clone()));
+        }
+
+        let name = CString::new(self.name)?;
+        self.methods.push(FunctionEntry::end());
+        let func = Box::into_raw(self.methods.into_boxed_slice()) as *const FunctionEntry;
+
+        let class = unsafe {
+            zend_register_internal_enum(name.as_ptr(), backing as _, func)
+                .as_mut()
+                .ok_or(Error::InvalidPointer)?
+        };
+
+        for (name, value) in self.cases {
+            let name = CString::new(name)?;
+            let mut value = match value {
+                Some(Discriminant::Int(value)) => Some(value.into_zval(true)?),
+                Some(Discriminant::String(value)) => Some(value.into_zval(true)?),
+                None => None,
+            };
+            // The engine requires string values to be interned.
+            if let Some(value) = &mut value {
+                make_immutable(value);
+            }
+            unsafe {
+                zend_enum_add_case_cstr(
+                    class,
+                    name.as_ptr(),
+                    value
+                        .as_mut()
+                        .map_or(ptr::null_mut(), |value| value as *mut Zval),
+                )
+            };
+        }
+
+        for (case, name, args) in self.case_attributes {
+            // SAFETY: Cases are stored as constants of the enum, which were
+            // added above.
+            let constant = class.constant(&case).ok_or(Error::InvalidPointer)?
+                as *const ClassConstant as *mut ClassConstant;
+            let mut name = ZendStr::new_interned(&name, true);
+            unsafe {
+                let attr = &mut *zend_add_attribute(
+                    &mut (*constant).attributes,
+                    name.as_mut_ptr(),
+                    args.len() as _,
+                    ZEND_ATTRIBUTE_PERSISTENT,
+                    0,
+                    0,
+                );
+                // SAFETY: The arguments are allocated inline, after the
+                // attribute, with room for `argc` arguments.
+                let slots = slice::from_raw_parts_mut(attr.args.as_mut_ptr(), args.len());
+                for (slot, mut arg) in slots.iter_mut().zip(args) {
+                    make_immutable(&mut arg);
+                    slot.name = ptr::null_mut();
+                    slot.value = arg;
+                }
+            }
+        }
+
+        for (name, mut value) in self.constants {
+            let mut name = ZendStr::new_interned(&name, true);
+            make_immutable(&mut value);
+            let value = Box::into_raw(Box::new(value));
+            unsafe {
+                zend_declare_class_constant_ex(
+                    class,
+                    name.as_mut_ptr(),
+                    value,
+                    ConstantFlags::Public.bits() as _,
+                    ptr::null_mut(),
+                )
+            };
+        }
+
+        for iface in self.interfaces {
+            unsafe {
+                zend_do_implement_interface(
+                    class,
+                    iface as *const crate::ffi::_zend_class_entry
+                        as *mut crate::ffi::_zend_class_entry,
+                )
+            };
+        }
+
+        Ok(class)
+    }
+}
@@ -1,11 +1,15 @@
 use crate::{
     args::{Arg, ArgInfo},
     error::{Error, Result},
+    ffi::{zend_string_init_interned, IS_STR_PERMANENT},
     flags::{DataType, MethodFlags},
-    types::Zval,
+    types::{ZendStr, Zval},
     zend::{ExecuteData, FunctionEntry, ZendType},
 };
-use std::{ffi::CString, mem, ptr};
+use std::{
+    ffi::{c_char, CString},
+    mem, ptr,
+};
 
 /// Function representation in Rust.
 #[cfg(not(windows))]
@@ -16,9 +20,10 @@ pub type FunctionHandler =
 
 /// Function representation in Rust using pointers.
 #[cfg(not(windows))]
-type FunctionPointerHandler = extern "C" fn(execute_data: *mut ExecuteData, retval: *mut Zval);
+pub(crate) type FunctionPointerHandler =
+    extern "C" fn(execute_data: *mut ExecuteData, retval: *mut Zval);
 #[cfg(windows)]
-type FunctionPointerHandler =
+pub(crate) type FunctionPointerHandler =
     extern "vectorcall" fn(execute_data: *mut ExecuteData, retval: *mut Zval);
 
 /// Builder for registering a function in PHP.
@@ -68,6 +73,19 @@ impl<'a> FunctionBuilder<'a> {
         }
     }
 
+    /// Creates a new function builder for a method which is handled by a Rust
+    /// closure. The function entry must be added to a class with
+    /// [`ClassBuilder::dynamic_method`].
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the method.
+    ///
+    /// [`ClassBuilder::dynamic_method`]: super::ClassBuilder::dynamic_method
+    pub fn new_dynamic<T: Into<String>>(name: T) -> Self {
+        Self::new(name, super::class::dynamic_method_handler)
+    }
+
     /// Create a new function builder for an abstract function that can be used
     /// on an abstract class or an interface.
     ///
@@ -169,10 +187,33 @@ impl<'a> FunctionBuilder<'a> {
                 .collect::<Result<Vec<_>>>()?,
         );
 
-        self.function.fname = CString::new(self.name)?.into_raw();
+        self.function.fname = permanent_name(self.name)?;
         self.function.num_args = (args.len() - 1) as u32;
         self.function.arg_info = Box::into_raw(args.into_boxed_slice()) as *const ArgInfo;
 
         Ok(self.function)
     }
 }
+
+/// Returns a null-terminated copy of a function name which lives for the rest
+/// of the process.
+///
+/// During startup the name is interned, so the engine reuses the same string
+/// when it interns the names of the functions it registers, rather than
+/// allocating another copy. Before the engine has started, or once requests
+/// are being served, interned strings do not outlive the request, so the name
+/// is copied into a [`CString`] instead.
+fn permanent_name(name: String) -> Result<*const c_char> {
+    if name.as_bytes().contains(&0) {
+        return Err(Error::InvalidCString);
+    }
+
+    if unsafe { zend_string_init_interned }.is_some() {
+        let interned = ZendStr::new_interned(&name, true);
+        if unsafe { interned.gc.u.type_info } & IS_STR_PERMANENT != 0 {
+            return Ok(interned.into_raw().val.as_ptr());
+        }
+    }
+
+    Ok(CString::new(name)?.into_raw())
+}
@@ -2,13 +2,23 @@
 //! Generally zero-cost abstractions.
 
 mod class;
-mod function;
+#[cfg(any(php81, php82))]
+mod enum_;
+pub(crate) mod function;
 mod module;
 #[cfg(feature = "embed")]
 mod sapi;
 
-pub use class::ClassBuilder;
-pub use function::FunctionBuilder;
+pub use class::{ClassBuilder, DynamicMethodHandler};
+#[cfg(any(php81, php82))]
+pub use enum_::EnumBuilder;
+pub use function::{FunctionBuilder, FunctionHandler};
 pub use module::ModuleBuilder;
 #[cfg(feature = "embed")]
-pub use sapi::SapiBuilder;
+pub use sapi::{
+    SapiActivateFunc, SapiBuilder, SapiDeactivateFunc, SapiFlushFunc, SapiGetEnvFunc,
+    SapiGetRequestTimeFunc, SapiHeaderHandlerFunc, SapiHeaderOp, SapiLogMessageFunc,
+    SapiReadCookiesFunc, SapiReadPostFunc, SapiRegisterServerVariablesFunc, SapiSendHeaderFunc,
+    SapiSendHeadersFunc, SapiShutdownFunc, SapiStartupFunc, SapiTerminateProcessFunc,
+    SapiUbWriteFunc,
+};
@@ -5,6 +5,7 @@ use crate::{
     PHP_DEBUG, PHP_ZTS,
 };
 
+use once_cell::sync::OnceCell;
 use std::{ffi::CString, mem, ptr};
 
 /// Builds a Zend module extension to be registered with PHP. Must be called
@@ -98,6 +99,9 @@ impl ModuleBuilder {
 
     /// Sets the shutdown function for the extension.
     ///
+    /// Persistent values (see [`crate::persistent`]) are dropped after the
+    /// function returns.
+    ///
     /// # Arguments
     ///
     /// * `func` - The function to be called on shutdown.
@@ -170,11 +174,62 @@ impl ModuleBuilder {
             Box::into_raw(self.functions.into_boxed_slice()) as *const FunctionEntry;
         self.module.name = CString::new(self.name)?.into_raw();
         self.module.version = CString::new(self.version)?.into_raw();
+        let _ = MODULE_SHUTDOWN.set(self.module.module_shutdown_func);
+        self.module.module_shutdown_func = Some(module_shutdown);
+        #[cfg(any(debug_assertions, feature = "alloc-tracking"))]
+        {
+            let _ = POST_DEACTIVATE.set(self.module.post_deactivate_func);
+            self.module.post_deactivate_func = Some(post_deactivate);
+        }
 
         Ok(self.module)
     }
 }
 
+/// The shutdown function of the extension, called by [`module_shutdown`].
+static MODULE_SHUTDOWN: OnceCell<Option<unsafe extern "C" fn(i32, i32) -> i32>> = OnceCell::new();
+
+/// Calls the shutdown function of the extension, then drops the values which
+/// are stored across requests. PHP calls this function when the extension is
+/// shut down.
+extern "C" fn module_shutdown(type_: i32, module_number: i32) -> i32 {
+    let result = match MODULE_SHUTDOWN.get().copied().flatten() {
+        // SAFETY: The function was set as the shutdown function of the
+        // extension, which PHP calls in the same way.
+        Some(func) => unsafe { func(type_, module_number) },
+        None => 0,
+    };
+
+    crate::persistent::shutdown();
+
+    result
+}
+
+/// The post deactivate function of the extension, called by
+/// [`post_deactivate`].
+#[cfg(any(debug_assertions, feature = "alloc-tracking"))]
+static POST_DEACTIVATE: OnceCell<Option<unsafe extern "C" fn() -> i32>> = OnceCell::new();
+
+/// Calls the post deactivate function of the extension, then reports the
+/// memory of the request which was not freed. PHP calls this function after
+/// the request has ended, and before the memory of the request is released.
+#[cfg(any(debug_assertions, feature = "alloc-tracking"))]
+extern "C" fn post_deactivate() -> i32 {
+    let result = match POST_DEACTIVATE.get().copied().flatten() {
+        // SAFETY: The function was set as the post deactivate function of the
+        // extension, which PHP calls in the same way.
+        Some(func) => unsafe { func() },
+        None => 0,
+    };
+
+    #[cfg(debug_assertions)]
+    crate::boxed::report_leaks();
+    #[cfg(feature = "alloc-tracking")]
+    crate::alloc::tracking::report();
+
+    result
+}
+
 /// A function to be called when the extension is starting up or shutting down.
 pub type StartupShutdownFunc = extern "C" fn(_type: i32, _module_number: i32) -> i32;
 
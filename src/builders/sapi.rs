@@ -1,12 +1,54 @@
-use crate::ffi::sapi_header_struct;
+use crate::ffi::{
+    sapi_header_op_enum, sapi_header_op_enum_SAPI_HEADER_ADD,
+    sapi_header_op_enum_SAPI_HEADER_DELETE, sapi_header_op_enum_SAPI_HEADER_DELETE_ALL,
+    sapi_header_op_enum_SAPI_HEADER_REPLACE, sapi_header_struct, sapi_headers_struct, zend_result,
+    zval,
+};
 use crate::{embed::SapiModule, error::Result};
 
-use std::ffi::{c_char, c_void};
-use std::{ffi::CString, ptr};
+use parking_lot::{const_rwlock, RwLock};
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, c_void, CStr};
+use std::sync::Arc;
+use std::{ffi::CString, ptr, slice};
+
+type UbWriteFn = dyn Fn(&[u8]) -> usize + Send + Sync;
+type HeaderHandlerFn = dyn Fn(&[u8], SapiHeaderOp) -> bool + Send + Sync;
+type ReadPostFn = dyn Fn(&mut [u8]) -> usize + Send + Sync;
+type ReadCookiesFn = dyn Fn() -> Option<String> + Send + Sync;
+type LogMessageFn = dyn Fn(&str, i32) + Send + Sync;
+
+/// Closures set with the closure-based setters of [`SapiBuilder`].
+#[derive(Default)]
+struct SapiClosures {
+    ub_write: Option<Arc<UbWriteFn>>,
+    header_handler: Option<Arc<HeaderHandlerFn>>,
+    read_post: Option<Arc<ReadPostFn>>,
+    read_cookies: Option<Arc<ReadCookiesFn>>,
+    log_message: Option<Arc<LogMessageFn>>,
+}
+
+/// Closures of the SAPI which was built last. A process only runs one SAPI,
+/// so the closures are shared by all threads.
+static CLOSURES: RwLock<SapiClosures> = const_rwlock(SapiClosures {
+    ub_write: None,
+    header_handler: None,
+    read_post: None,
+    read_cookies: None,
+    log_message: None,
+});
+
+thread_local! {
+    /// Cookies returned by the read cookies closure, which must outlive the
+    /// request they were read for.
+    static COOKIES: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
 
 pub struct SapiBuilder {
     name: String,
     pretty_name: String,
+    ini_entries: Option<String>,
+    closures: SapiClosures,
     module: SapiModule,
 }
 
@@ -15,6 +57,8 @@ impl SapiBuilder {
         Self {
             name: name.into(),
             pretty_name: pretty_name.into(),
+            ini_entries: None,
+            closures: SapiClosures::default(),
             module: SapiModule {
                 name: ptr::null_mut(),
                 pretty_name: ptr::null_mut(),
@@ -56,11 +100,109 @@ impl SapiBuilder {
         }
     }
 
+    /// Sets the startup function for this SAPI, called when the SAPI is
+    /// started with `php_module_startup`.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function to be called on startup.
+    pub fn startup_function(mut self, func: SapiStartupFunc) -> Self {
+        self.module.startup = Some(func);
+        self
+    }
+
+    /// Sets the shutdown function for this SAPI.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function to be called on shutdown.
+    pub fn shutdown_function(mut self, func: SapiShutdownFunc) -> Self {
+        self.module.shutdown = Some(func);
+        self
+    }
+
+    /// Sets the activate function for this SAPI, called at the start of each
+    /// request.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function to be called on request startup.
+    pub fn activate_function(mut self, func: SapiActivateFunc) -> Self {
+        self.module.activate = Some(func);
+        self
+    }
+
+    /// Sets the deactivate function for this SAPI, called at the end of each
+    /// request.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function to be called on request shutdown.
+    pub fn deactivate_function(mut self, func: SapiDeactivateFunc) -> Self {
+        self.module.deactivate = Some(func);
+        self
+    }
+
+    /// Sets the unbuffered write function for this SAPI, which receives all
+    /// output of the script.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function to be called when PHP writes output.
     pub fn ub_write_function(mut self, func: SapiUbWriteFunc) -> Self {
         self.module.ub_write = Some(func);
         self
     }
 
+    /// Sets the flush function for this SAPI, called when PHP flushes output
+    /// to the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function to be called on flush.
+    pub fn flush_function(mut self, func: SapiFlushFunc) -> Self {
+        self.module.flush = Some(func);
+        self
+    }
+
+    /// Sets the getenv function for this SAPI, used to look up environment
+    /// variables provided by the server before falling back to the process
+    /// environment.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function to be called to look up a variable.
+    pub fn getenv_function(mut self, func: SapiGetEnvFunc) -> Self {
+        self.module.getenv = Some(func);
+        self
+    }
+
+    /// Sets the header handler for this SAPI, called whenever a header is
+    /// added, replaced or removed. The handler may modify the header, and
+    /// returns whether PHP should store the header in its list of headers.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function to be called on each header operation.
+    pub fn header_handler_function(mut self, func: SapiHeaderHandlerFunc) -> Self {
+        self.module.header_handler = Some(func);
+        self
+    }
+
+    /// Sets the send headers function for this SAPI, called once before the
+    /// first output with the complete list of headers. The function returns
+    /// `SAPI_HEADER_SENT_SUCCESSFULLY` if it has sent the headers itself,
+    /// otherwise the headers are passed to the send header function one at a
+    /// time.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function to be called to send the headers.
+    pub fn send_headers_function(mut self, func: SapiSendHeadersFunc) -> Self {
+        self.module.send_headers = Some(func);
+        self
+    }
+
     /// Sets the send header function for this SAPI
     ///
     /// # Arguments
@@ -71,26 +213,346 @@ impl SapiBuilder {
         self
     }
 
+    /// Sets the read post function for this SAPI, which reads the body of the
+    /// request into the given buffer and returns the number of bytes read.
+    /// Returning `0` indicates the end of the body.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function to be called to read the request body.
+    pub fn read_post_function(mut self, func: SapiReadPostFunc) -> Self {
+        self.module.read_post = Some(func);
+        self
+    }
+
+    /// Sets the read cookies function for this SAPI, which returns the raw
+    /// value of the `Cookie` request header, or a null pointer if there is
+    /// none. The string must remain valid until the end of the request.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function to be called to read the cookies.
+    pub fn read_cookies_function(mut self, func: SapiReadCookiesFunc) -> Self {
+        self.module.read_cookies = Some(func);
+        self
+    }
+
+    /// Sets the function used to populate `$_SERVER` for each request.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function to be called with the `$_SERVER` array.
+    pub fn register_server_variables_function(
+        mut self,
+        func: SapiRegisterServerVariablesFunc,
+    ) -> Self {
+        self.module.register_server_variables = Some(func);
+        self
+    }
+
+    /// Sets the log message function for this SAPI, which receives messages
+    /// written to the error log when no `error_log` is configured.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function to be called with each message.
+    pub fn log_message_function(mut self, func: SapiLogMessageFunc) -> Self {
+        self.module.log_message = Some(func);
+        self
+    }
+
+    /// Sets the function returning the time at which the current request
+    /// started, as used by `$_SERVER['REQUEST_TIME']`.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function to be called to get the request time.
+    pub fn get_request_time_function(mut self, func: SapiGetRequestTimeFunc) -> Self {
+        self.module.get_request_time = Some(func);
+        self
+    }
+
+    /// Sets the function called when the script calls `exit()` while the
+    /// SAPI is configured to terminate the process.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function to be called to terminate the process.
+    pub fn terminate_process_function(mut self, func: SapiTerminateProcessFunc) -> Self {
+        self.module.terminate_process = Some(func);
+        self
+    }
+
+    /// Sets a closure which receives all output of the script, and returns the
+    /// number of bytes which were written.
+    ///
+    /// Closures are shared by every request of the process, and replace the
+    /// closures of any SAPI built earlier.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The closure to be called when PHP writes output.
+    pub fn ub_write<F>(mut self, func: F) -> Self
+    where
+        F: Fn(&[u8]) -> usize + Send + Sync + 'static,
+    {
+        self.closures.ub_write = Some(Arc::new(func));
+        self.module.ub_write = Some(ub_write_closure);
+        self
+    }
+
+    /// Sets a closure which is called whenever a header is added, replaced or
+    /// removed, and returns whether PHP should store the header in its list
+    /// of headers. The header is empty when all headers are removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The closure to be called on each header operation.
+    pub fn header_handler<F>(mut self, func: F) -> Self
+    where
+        F: Fn(&[u8], SapiHeaderOp) -> bool + Send + Sync + 'static,
+    {
+        self.closures.header_handler = Some(Arc::new(func));
+        self.module.header_handler = Some(header_handler_closure);
+        self
+    }
+
+    /// Sets a closure which reads the body of the request into the given
+    /// buffer, and returns the number of bytes read. Returning `0` indicates
+    /// the end of the body.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The closure to be called to read the request body.
+    pub fn read_post<F>(mut self, func: F) -> Self
+    where
+        F: Fn(&mut [u8]) -> usize + Send + Sync + 'static,
+    {
+        self.closures.read_post = Some(Arc::new(func));
+        self.module.read_post = Some(read_post_closure);
+        self
+    }
+
+    /// Sets a closure which returns the raw value of the `Cookie` request
+    /// header, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The closure to be called to read the cookies.
+    pub fn read_cookies<F>(mut self, func: F) -> Self
+    where
+        F: Fn() -> Option<String> + Send + Sync + 'static,
+    {
+        self.closures.read_cookies = Some(Arc::new(func));
+        self.module.read_cookies = Some(read_cookies_closure);
+        self
+    }
+
+    /// Sets a closure which receives messages written to the error log when
+    /// no `error_log` is configured, along with their syslog priority.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The closure to be called with each message.
+    pub fn log_message<F>(mut self, func: F) -> Self
+    where
+        F: Fn(&str, i32) + Send + Sync + 'static,
+    {
+        self.closures.log_message = Some(Arc::new(func));
+        self.module.log_message = Some(log_message_closure);
+        self
+    }
+
+    /// Sets the default INI directives for this SAPI, in the same format as
+    /// `php.ini`. These are applied after `php.ini` has been loaded, so take
+    /// precedence over it.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - The INI directives, e.g. `"display_errors=1\nhtml_errors=0"`.
+    pub fn ini_entries<T: Into<String>>(mut self, entries: T) -> Self {
+        self.ini_entries = Some(entries.into());
+        self
+    }
+
     /// Builds the extension and returns a `SapiModule`.
     ///
     /// Returns a result containing the sapi module if successful.
     pub fn build(mut self) -> Result<SapiModule> {
         self.module.name = CString::new(self.name)?.into_raw();
         self.module.pretty_name = CString::new(self.pretty_name)?.into_raw();
+        if let Some(ini_entries) = self.ini_entries {
+            self.module.ini_entries = CString::new(ini_entries)?.into_raw();
+        }
 
         if self.module.send_header.is_none() {
             self.module.send_header = Some(dummy_send_header);
         }
 
+        *CLOSURES.write() = self.closures;
+
         Ok(self.module)
     }
 }
 
+/// A function to be called when the SAPI is started
+pub type SapiStartupFunc = extern "C" fn(sapi: *mut SapiModule) -> c_int;
+
+/// A function to be called when the SAPI is shut down
+pub type SapiShutdownFunc = extern "C" fn(sapi: *mut SapiModule) -> c_int;
+
+/// A function to be called at the start of each request
+pub type SapiActivateFunc = extern "C" fn() -> c_int;
+
+/// A function to be called at the end of each request
+pub type SapiDeactivateFunc = extern "C" fn() -> c_int;
+
+/// A function to be called when PHP write to the output buffer
+pub type SapiUbWriteFunc = extern "C" fn(str: *const c_char, str_length: usize) -> usize;
+
+/// A function to be called when PHP flushes the output buffer
+pub type SapiFlushFunc = extern "C" fn(server_context: *mut c_void);
+
+/// A function to be called when PHP looks up an environment variable
+pub type SapiGetEnvFunc = extern "C" fn(name: *const c_char, name_len: usize) -> *mut c_char;
+
+/// A function to be called when PHP adds, replaces or removes a header
+pub type SapiHeaderHandlerFunc = extern "C" fn(
+    header: *mut sapi_header_struct,
+    op: sapi_header_op_enum,
+    headers: *mut sapi_headers_struct,
+) -> c_int;
+
+/// A function to be called when PHP sends all headers
+pub type SapiSendHeadersFunc = extern "C" fn(headers: *mut sapi_headers_struct) -> c_int;
+
 /// A function to be called when PHP send a header
 pub type SapiSendHeaderFunc =
     extern "C" fn(header: *mut sapi_header_struct, server_context: *mut c_void);
 
-/// A function to be called when PHP write to the output buffer
-pub type SapiUbWriteFunc = extern "C" fn(str: *const c_char, str_length: usize) -> usize;
+/// A function to be called when PHP reads the request body
+pub type SapiReadPostFunc = extern "C" fn(buffer: *mut c_char, count_bytes: usize) -> usize;
+
+/// A function to be called when PHP reads the request cookies
+pub type SapiReadCookiesFunc = extern "C" fn() -> *mut c_char;
+
+/// A function to be called when PHP populates `$_SERVER`
+pub type SapiRegisterServerVariablesFunc = extern "C" fn(vars: *mut zval);
+
+/// A function to be called when PHP logs a message
+pub type SapiLogMessageFunc = extern "C" fn(message: *const c_char, syslog_type: c_int);
+
+/// A function to be called when PHP retrieves the request start time
+pub type SapiGetRequestTimeFunc = extern "C" fn(request_time: *mut f64) -> zend_result;
+
+/// A function to be called when PHP terminates the process
+pub type SapiTerminateProcessFunc = extern "C" fn();
+
+/// The operation passed to a header handler closure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SapiHeaderOp {
+    /// The header replaces existing headers with the same name.
+    Replace,
+    /// The header is added alongside existing headers with the same name.
+    Add,
+    /// Headers with the given name are removed.
+    Delete,
+    /// All headers are removed.
+    DeleteAll,
+    /// The response code is set.
+    SetStatus,
+}
+
+impl From<sapi_header_op_enum> for SapiHeaderOp {
+    #[allow(non_upper_case_globals)]
+    fn from(op: sapi_header_op_enum) -> Self {
+        match op {
+            sapi_header_op_enum_SAPI_HEADER_REPLACE => Self::Replace,
+            sapi_header_op_enum_SAPI_HEADER_ADD => Self::Add,
+            sapi_header_op_enum_SAPI_HEADER_DELETE => Self::Delete,
+            sapi_header_op_enum_SAPI_HEADER_DELETE_ALL => Self::DeleteAll,
+            _ => Self::SetStatus,
+        }
+    }
+}
 
 extern "C" fn dummy_send_header(_header: *mut sapi_header_struct, _server_context: *mut c_void) {}
+
+// The closures are cloned out of the lock before they are called, so they may
+// build another SAPI themselves.
+
+extern "C" fn ub_write_closure(str: *const c_char, str_length: usize) -> usize {
+    let func = CLOSURES.read().ub_write.clone();
+    match func {
+        // SAFETY: PHP passes a buffer of the given length.
+        Some(func) if !str.is_null() => {
+            func(unsafe { slice::from_raw_parts(str.cast(), str_length) })
+        }
+        _ => str_length,
+    }
+}
+
+extern "C" fn header_handler_closure(
+    header: *mut sapi_header_struct,
+    op: sapi_header_op_enum,
+    _headers: *mut sapi_headers_struct,
+) -> c_int {
+    let func = CLOSURES.read().header_handler.clone();
+    let Some(func) = func else {
+        return sapi_header_op_enum_SAPI_HEADER_ADD as c_int;
+    };
+
+    // SAFETY: PHP passes a valid header, whose line is null when all headers are
+    // removed.
+    let line = match unsafe { header.as_ref() } {
+        Some(header) if !header.header.is_null() => unsafe {
+            slice::from_raw_parts(header.header.cast(), header.header_len)
+        },
+        _ => &[],
+    };
+
+    if func(line, op.into()) {
+        sapi_header_op_enum_SAPI_HEADER_ADD as c_int
+    } else {
+        0
+    }
+}
+
+extern "C" fn read_post_closure(buffer: *mut c_char, count_bytes: usize) -> usize {
+    let func = CLOSURES.read().read_post.clone();
+    match func {
+        // SAFETY: PHP passes a writable buffer of the given length.
+        Some(func) if !buffer.is_null() => {
+            func(unsafe { slice::from_raw_parts_mut(buffer.cast(), count_bytes) }).min(count_bytes)
+        }
+        _ => 0,
+    }
+}
+
+extern "C" fn read_cookies_closure() -> *mut c_char {
+    let func = CLOSURES.read().read_cookies.clone();
+    let cookies = func
+        .and_then(|func| func())
+        .and_then(|cookies| CString::new(cookies).ok());
+
+    // PHP does not free the cookies, which are kept until the next request on
+    // this thread reads its cookies.
+    COOKIES.with(|stored| {
+        let mut stored = stored.borrow_mut();
+        *stored = cookies;
+        stored
+            .as_ref()
+            .map_or(ptr::null_mut(), |cookies| cookies.as_ptr() as *mut c_char)
+    })
+}
+
+extern "C" fn log_message_closure(message: *const c_char, syslog_type: c_int) {
+    let func = CLOSURES.read().log_message.clone();
+    if let Some(func) = func {
+        if !message.is_null() {
+            // SAFETY: PHP passes a null-terminated message.
+            let message = unsafe { CStr::from_ptr(message) }.to_string_lossy();
+            func(&message, syslog_type);
+        }
+    }
+}
@@ -0,0 +1,454 @@
+//! Message channels shared between PHP and Rust.
+//!
+//! A [`Channel`] is exported to PHP as the `ExtPhpRs\Channel` class, and
+//! allows PHP code to exchange messages with Rust code running on other
+//! threads, such as background tasks started by the extension.
+//!
+//! As [`Zval`]s cannot be sent between threads, values passed through a channel
+//! are first converted into an owned [`Message`]. Only scalars, strings and
+//! arrays of these can be sent, objects and resources are rejected.
+
+use std::{collections::HashMap, convert::TryInto, time::Duration};
+
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender, TrySendError};
+
+use crate::{
+    args::Arg,
+    builders::{ClassBuilder, FunctionBuilder},
+    class::{ClassMetadata, ConstructorMeta, ConstructorResult, RegisteredClass},
+    convert::{FromZval, IntoZval},
+    error::{Error, Result},
+    exception::{PhpException, PhpResult},
+    flags::{DataType, MethodFlags},
+    props::Property,
+    types::{ArrayKey, ZendHashTable, ZendLong, ZendStr, Zval},
+    zend::{ce, CancellationToken, ExecuteData},
+    zend_fastcall,
+};
+
+/// Class entry and handlers for channels.
+static CHANNEL_META: ClassMetadata<Channel> = ClassMetadata::new();
+
+/// The maximum depth of nested arrays in a message, which stops arrays
+/// containing references to themselves from being copied forever.
+const MAX_DEPTH: usize = 128;
+
+/// How often a blocking `recv()` checks whether the request has timed out or
+/// been aborted.
+const RECV_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An owned value which can be sent through a [`Channel`].
+///
+/// Messages are created from [`Zval`]s when PHP code sends a value through a
+/// channel, and converted back into [`Zval`]s when PHP code receives a value.
+/// The contents of the message are copied out of the Zend heap, so a message
+/// can freely move between threads.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    /// PHP `null`.
+    Null,
+    /// PHP `bool`.
+    Bool(bool),
+    /// PHP `int`.
+    Long(ZendLong),
+    /// PHP `float`.
+    Double(f64),
+    /// PHP `string`. Strings are binary safe and are not required to be UTF-8.
+    String(Vec<u8>),
+    /// PHP `array`, stored as a list of key-value pairs in insertion order.
+    Array(Vec<(ArrayKey, Message)>),
+}
+
+impl<'a> FromZval<'a> for Message {
+    const TYPE: DataType = DataType::Mixed;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        Self::from_nested_zval(zval, 0)
+    }
+}
+
+impl Message {
+    /// Copies a value nested `depth` arrays deep into a message. Returns
+    /// [`None`] if the value cannot be sent, or if the arrays are nested deeper
+    /// than [`MAX_DEPTH`], which is the case for recursive arrays.
+    fn from_nested_zval(zval: &Zval, depth: usize) -> Option<Self> {
+        let zval = zval.dereference();
+
+        Some(match zval.get_type() {
+            DataType::Undef | DataType::Null => Self::Null,
+            DataType::True | DataType::False => Self::Bool(zval.bool()?),
+            DataType::Long => Self::Long(zval.long()?),
+            DataType::Double => Self::Double(zval.double()?),
+            DataType::String => Self::String(zval.zend_str()?.as_bytes().to_vec()),
+            DataType::Array if depth < MAX_DEPTH => Self::Array(
+                zval.array()?
+                    .iter()
+                    .map(|(key, val)| Some((key, Self::from_nested_zval(val, depth + 1)?)))
+                    .collect::<Option<_>>()?,
+            ),
+            _ => return None,
+        })
+    }
+}
+
+impl IntoZval for Message {
+    const TYPE: DataType = DataType::Mixed;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        match self {
+            Self::Null => zv.set_null(),
+            Self::Bool(val) => zv.set_bool(val),
+            Self::Long(val) => zv.set_long(val),
+            Self::Double(val) => zv.set_double(val),
+            Self::String(val) => zv.set_zend_string(ZendStr::new(val, persistent)),
+            Self::Array(items) => {
+                let size = items.len().try_into().map_err(|_| Error::IntegerOverflow)?;
+                let mut ht = if persistent {
+                    // SAFETY: Persistent zvals are only stored in persistent hashtables.
+                    unsafe { ZendHashTable::with_capacity_persistent(size) }
+                } else {
+                    ZendHashTable::with_capacity(size)
+                };
+                for (key, val) in items {
+                    match key {
+                        ArrayKey::Long(index) => ht.insert_at_index(index as _, val)?,
+                        ArrayKey::String(key) => ht.insert(&key, val)?,
+                    }
+                }
+                zv.set_hashtable(ht);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+macro_rules! message_from {
+    ($($type: ty => $variant: ident),*) => {
+        $(
+            impl From<$type> for Message {
+                fn from(val: $type) -> Self {
+                    Self::$variant(val.into())
+                }
+            }
+        )*
+    };
+}
+
+message_from!(bool => Bool, i32 => Long, i64 => Long, f64 => Double, Vec<u8> => String);
+
+impl From<&str> for Message {
+    fn from(val: &str) -> Self {
+        Self::String(val.as_bytes().to_vec())
+    }
+}
+
+impl From<String> for Message {
+    fn from(val: String) -> Self {
+        Self::String(val.into_bytes())
+    }
+}
+
+impl<T: Into<Message>> From<Vec<T>> for Message {
+    fn from(val: Vec<T>) -> Self {
+        Self::Array(
+            val.into_iter()
+                .enumerate()
+                .map(|(i, val)| (ArrayKey::Long(i as _), val.into()))
+                .collect(),
+        )
+    }
+}
+
+impl<T: Into<Message>> From<Option<T>> for Message {
+    fn from(val: Option<T>) -> Self {
+        val.map(Into::into).unwrap_or(Self::Null)
+    }
+}
+
+/// A multi-producer, multi-consumer channel which can be used from both PHP and
+/// Rust.
+///
+/// The channel is registered with PHP as the `ExtPhpRs\Channel` class:
+///
+/// ```php
+/// <?php
+///
+/// namespace ExtPhpRs;
+///
+/// class Channel implements \Countable
+/// {
+///     public function __construct(?int $capacity = null);
+///     public function send(mixed $value): void;
+///     public function trySend(mixed $value): bool;
+///     public function recv(): mixed;
+///     public function tryRecv(): mixed;
+///     public function recvTimeout(float $seconds): mixed;
+///     public function count(): int;
+/// }
+/// ```
+///
+/// When constructed with a capacity the channel is bounded, and `send()` blocks
+/// while the channel is full. Without a capacity the channel is unbounded.
+/// `recv()` blocks until a message is available, or throws once the request
+/// times out or is aborted, while `tryRecv()` and `recvTimeout()` return `null`
+/// when no message arrives in time.
+///
+/// On the Rust side, [`Channel::sender`] and [`Channel::receiver`] return
+/// handles which can be moved to other threads. A channel never disconnects
+/// while the PHP object is alive, as the object holds both ends.
+///
+/// The class is only registered when the `channel` feature is enabled.
+pub struct Channel {
+    sender: Sender<Message>,
+    receiver: Receiver<Message>,
+}
+
+impl Channel {
+    /// Creates a new channel which can hold at most `capacity` messages.
+    ///
+    /// A capacity of zero creates a rendezvous channel, where each send blocks
+    /// until a matching receive occurs.
+    ///
+    /// # Parameters
+    ///
+    /// * `capacity` - The maximum number of messages held by the channel.
+    pub fn bounded(capacity: usize) -> Self {
+        let (sender, receiver) = crossbeam_channel::bounded(capacity);
+        Self { sender, receiver }
+    }
+
+    /// Creates a new channel with no limit on the number of messages it can
+    /// hold.
+    pub fn unbounded() -> Self {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        Self { sender, receiver }
+    }
+
+    /// Returns a handle which can be used to send messages into the channel
+    /// from any thread.
+    pub fn sender(&self) -> Sender<Message> {
+        self.sender.clone()
+    }
+
+    /// Returns a handle which can be used to receive messages from the channel
+    /// on any thread.
+    pub fn receiver(&self) -> Receiver<Message> {
+        self.receiver.clone()
+    }
+
+    /// Builds the class entry for [`Channel`], registering it with PHP. This
+    /// function should only be called once inside your module startup
+    /// function.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the function is called more than once.
+    pub fn build() {
+        if CHANNEL_META.has_ce() {
+            panic!("Channel has already been built.");
+        }
+
+        let methods = vec![
+            FunctionBuilder::new("send", Self::send)
+                .arg(Arg::new("value", DataType::Mixed))
+                .returns(DataType::Void, false, false),
+            FunctionBuilder::new("trySend", Self::try_send)
+                .arg(Arg::new("value", DataType::Mixed))
+                .returns(DataType::Bool, false, false),
+            FunctionBuilder::new("recv", Self::recv).returns(DataType::Mixed, false, true),
+            FunctionBuilder::new("tryRecv", Self::try_recv).returns(DataType::Mixed, false, true),
+            FunctionBuilder::new("recvTimeout", Self::recv_timeout)
+                .arg(Arg::new("seconds", DataType::Double))
+                .returns(DataType::Mixed, false, true),
+            FunctionBuilder::new("count", Self::count).returns(DataType::Long, false, false),
+        ];
+
+        let ce = methods
+            .into_iter()
+            .fold(ClassBuilder::new(Self::CLASS_NAME), |class, method| {
+                class.method(
+                    method
+                        .build()
+                        .expect("Failed to build `ExtPhpRs\\Channel` PHP class."),
+                    MethodFlags::Public,
+                )
+            })
+            .implements(ce::countable())
+            .object_override::<Self>()
+            .build()
+            .expect("Failed to build `ExtPhpRs\\Channel` PHP class.");
+        CHANNEL_META.set_ce(ce);
+    }
+
+    fn construct(ex: &mut ExecuteData) -> ConstructorResult<Self> {
+        let mut capacity = Arg::new("capacity", DataType::Long);
+        if ex
+            .parser()
+            .not_required()
+            .arg(&mut capacity)
+            .parse()
+            .is_err()
+        {
+            return ConstructorResult::ArgError;
+        }
+
+        match capacity.consume::<Option<ZendLong>>().ok().flatten() {
+            None => Self::unbounded().into(),
+            Some(capacity) if capacity >= 0 => Self::bounded(capacity as _).into(),
+            Some(_) => ConstructorResult::Exception(PhpException::new(
+                "Channel capacity must be greater than or equal to 0.".into(),
+                0,
+                ce::value_error(),
+            )),
+        }
+    }
+
+    fn build_constructor(func: FunctionBuilder) -> FunctionBuilder {
+        func.not_required().arg(
+            Arg::new("capacity", DataType::Long)
+                .allow_null()
+                .default("null"),
+        )
+    }
+
+    /// Parses the arguments of a channel method and calls `f` with the
+    /// channel and the parsed arguments, writing the result into `ret`.
+    fn dispatch<'a, const N: usize, R: IntoZval>(
+        ex: &'a mut ExecuteData,
+        ret: &mut Zval,
+        mut args: [Arg<'a>; N],
+        f: impl FnOnce(&Self, [Arg<'a>; N]) -> PhpResult<R>,
+    ) {
+        let (parser, this) = ex.parser_method::<Self>();
+        if args
+            .iter_mut()
+            .fold(parser, |parser, arg| parser.arg(arg))
+            .parse()
+            .is_err()
+        {
+            return;
+        }
+
+        let this = this.expect("Internal channel method called on non-channel class");
+        let result = match this.obj.as_ref() {
+            Some(this) => f(this, args),
+            None => Err("Channel has not been constructed.".into()),
+        };
+
+        // The implementation of `IntoZval` on `Result` throws the exception for us.
+        let _ = result.set_zval(ret, false);
+    }
+
+    zend_fastcall! {
+        extern "C" fn send(ex: &mut ExecuteData, ret: &mut Zval) {
+            Self::dispatch(ex, ret, [Arg::new("value", DataType::Mixed)], |this, [value]| {
+                this.sender
+                    .send(to_message(value)?)
+                    .map_err(|_| "Channel has been disconnected.".into())
+            })
+        }
+    }
+
+    zend_fastcall! {
+        extern "C" fn try_send(ex: &mut ExecuteData, ret: &mut Zval) {
+            Self::dispatch(ex, ret, [Arg::new("value", DataType::Mixed)], |this, [value]| {
+                match this.sender.try_send(to_message(value)?) {
+                    Ok(()) => Ok(true),
+                    Err(TrySendError::Full(_)) => Ok(false),
+                    Err(TrySendError::Disconnected(_)) => {
+                        Err("Channel has been disconnected.".into())
+                    }
+                }
+            })
+        }
+    }
+
+    zend_fastcall! {
+        extern "C" fn recv(ex: &mut ExecuteData, ret: &mut Zval) {
+            Self::dispatch(ex, ret, [], |this, []| {
+                // The VM cannot interrupt a Rust function, so the request is checked
+                // between waits instead.
+                let token = CancellationToken::for_request();
+                loop {
+                    match this.receiver.recv_timeout(RECV_POLL_INTERVAL) {
+                        Ok(message) => return Ok(message),
+                        Err(RecvTimeoutError::Timeout) => token.check()?,
+                        Err(RecvTimeoutError::Disconnected) => {
+                            return Err("Channel has been disconnected.".into())
+                        }
+                    }
+                }
+            })
+        }
+    }
+
+    zend_fastcall! {
+        extern "C" fn try_recv(ex: &mut ExecuteData, ret: &mut Zval) {
+            Self::dispatch(ex, ret, [], |this, []| Ok(this.receiver.try_recv().ok()))
+        }
+    }
+
+    zend_fastcall! {
+        extern "C" fn recv_timeout(ex: &mut ExecuteData, ret: &mut Zval) {
+            Self::dispatch(ex, ret, [Arg::new("seconds", DataType::Double)], |this, [seconds]| {
+                let timeout = seconds
+                    .consume::<f64>()
+                    .ok()
+                    .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+                    .map(Duration::from_secs_f64)
+                    .ok_or("Timeout must be a non-negative number of seconds.")?;
+
+                match this.receiver.recv_timeout(timeout) {
+                    Ok(message) => Ok(Some(message)),
+                    Err(RecvTimeoutError::Timeout) => Ok(None),
+                    Err(RecvTimeoutError::Disconnected) => {
+                        Err("Channel has been disconnected.".into())
+                    }
+                }
+            })
+        }
+    }
+
+    zend_fastcall! {
+        extern "C" fn count(ex: &mut ExecuteData, ret: &mut Zval) {
+            Self::dispatch(ex, ret, [], |this, []| Ok(this.receiver.len() as ZendLong))
+        }
+    }
+}
+
+/// Converts a method argument into a message, returning an exception if the
+/// value cannot be sent through a channel.
+fn to_message(mut arg: Arg) -> PhpResult<Message> {
+    let ty = arg
+        .zval()
+        .map(|zv| zv.dereference().get_type())
+        .unwrap_or(DataType::Null);
+
+    arg.consume().map_err(|_| {
+        PhpException::new(
+            format!("Value of type {} cannot be sent through a channel.", ty),
+            0,
+            ce::type_error(),
+        )
+    })
+}
+
+impl RegisteredClass for Channel {
+    const CLASS_NAME: &'static str = "ExtPhpRs\\Channel";
+
+    const CONSTRUCTOR: Option<ConstructorMeta<Self>> = Some(ConstructorMeta {
+        constructor: Self::construct,
+        build_fn: Self::build_constructor,
+    });
+
+    fn get_metadata() -> &'static ClassMetadata<Self> {
+        &CHANNEL_META
+    }
+
+    fn get_properties<'a>() -> HashMap<&'static str, Property<'a, Self>> {
+        HashMap::new()
+    }
+}
+
+class_derives!(Channel);
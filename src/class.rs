@@ -1,18 +1,28 @@
 //! Types and traits used for registering classes with PHP.
 
 use std::{
-    collections::HashMap,
+    any::{Any, TypeId},
+    cmp::Ordering as CmpOrdering,
+    collections::{BTreeSet, HashMap},
+    convert::TryFrom,
+    fmt::Debug,
     marker::PhantomData,
     sync::atomic::{AtomicPtr, Ordering},
 };
 
 use once_cell::sync::OnceCell;
+use parking_lot::{const_rwlock, RwLock};
 
 use crate::{
+    boxed::ZBox,
     builders::FunctionBuilder,
-    exception::PhpException,
+    convert::IntoZval,
+    exception::{PhpException, PhpResult},
+    ffi::{_IS_BOOL, _IS_NUMBER, IS_DOUBLE, IS_LONG, IS_STRING},
+    flags::DataType,
     props::Property,
-    zend::{ClassEntry, ExecuteData, ZendObjectHandlers},
+    types::{ZendClassObject, ZendHashTable, ZendObject, ZendStr, Zval},
+    zend::{ClassEntry, ExecuteData, GcBuffer, ZendObjectHandlers},
 };
 
 /// Implemented on Rust types which are exported to PHP. Allows users to get and
@@ -24,6 +34,43 @@ pub trait RegisteredClass: Sized + 'static {
     /// Optional class constructor.
     const CONSTRUCTOR: Option<ConstructorMeta<Self>> = None;
 
+    /// Optional function which adds the PHP values held by the object to the
+    /// cycle collector's buffer. Usually generated from `#[gc]` fields or a
+    /// [`CollectGarbage`] implementation.
+    ///
+    /// [`CollectGarbage`]: crate::zend::CollectGarbage
+    const GC: Option<fn(&Self, &mut GcBuffer)> = None;
+
+    /// Optional function which builds the representation of the object shown
+    /// by `var_dump()`. Usually set with `#[php_class(debug_info)]` or
+    /// `#[php_class(debug)]`.
+    const DEBUG_INFO: Option<fn(&Self, &mut ZendHashTable) -> PhpResult> = None;
+
+    /// Optional function which converts the object into a scalar when it is
+    /// cast. Usually set with `#[php_class(cast)]`.
+    const CAST: Option<fn(&Self, CastType) -> CastResult> = None;
+
+    /// Optional function which compares two objects of the class. Usually set
+    /// with `#[php_class(compare)]`.
+    const COMPARE: Option<fn(&Self, &Self) -> Option<CmpOrdering>> = None;
+
+    /// Optional functions which handle array access on objects of the class.
+    /// Usually set with `#[php_class(array_access)]`.
+    const DIMENSIONS: Option<DimensionMeta<Self>> = None;
+
+    /// Optional function called after PHP writes to a declared property of the
+    /// object. Usually set with `#[php_class(observe)]`.
+    const PROPERTY_CHANGED: Option<fn(&mut Self, &str)> = None;
+
+    /// Optional function which creates the iterator used when the object is
+    /// iterated with `foreach`. Usually set with `#[php_class(iterator)]`.
+    const ITERATOR: Option<fn(&Self) -> NativeIter> = None;
+
+    /// Optional function returning the object which calls to undefined methods
+    /// and reads of undefined properties are forwarded to. Usually generated
+    /// from a `#[delegate]` field.
+    const DELEGATE: Option<fn(&mut Self) -> Option<&mut ZendObject>> = None;
+
     /// Returns a reference to the class metadata, which stores the class entry
     /// and handlers.
     ///
@@ -43,6 +90,454 @@ pub trait RegisteredClass: Sized + 'static {
     /// through the [`ClassMetadata::get_properties`] function, which builds the
     /// hashmap one and stores it in memory.
     fn get_properties<'a>() -> HashMap<&'static str, Property<'a, Self>>;
+
+    /// Returns `self` as the Rust class with the given type ID, which is
+    /// either `Self` or one of the Rust classes it extends. Used to call the
+    /// methods of parent classes on instances of this class.
+    ///
+    /// Generated by the `#[extends_class]` attribute, which requires the
+    /// struct to dereference to its parent.
+    ///
+    /// # Parameters
+    ///
+    /// * `ty` - The type ID of the class to return.
+    fn upcast(&self, ty: TypeId) -> Option<&dyn Any> {
+        (ty == TypeId::of::<Self>()).then_some(self as &dyn Any)
+    }
+
+    /// Returns `self` as the Rust class with the given type ID. See
+    /// [`RegisteredClass::upcast`].
+    ///
+    /// # Parameters
+    ///
+    /// * `ty` - The type ID of the class to return.
+    fn upcast_mut(&mut self, ty: TypeId) -> Option<&mut dyn Any> {
+        (ty == TypeId::of::<Self>()).then_some(self as &mut dyn Any)
+    }
+}
+
+/// Implemented on classes which customise their representation in
+/// `var_dump()` and `print_r()`.
+///
+/// Annotate the struct with `#[php_class(debug_info)]` to use the
+/// implementation.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{class::PhpDebugInfo, prelude::*, types::ZendHashTable};
+///
+/// #[php_class(debug_info)]
+/// pub struct Point {
+///     x: f64,
+///     y: f64,
+/// }
+///
+/// impl PhpDebugInfo for Point {
+///     fn debug_info(&self, info: &mut ZendHashTable) -> PhpResult {
+///         info.insert("x", self.x)?;
+///         info.insert("y", self.y)?;
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait PhpDebugInfo {
+    /// Inserts the entries shown when the object is dumped into `info`,
+    /// which is initially empty.
+    ///
+    /// # Parameters
+    ///
+    /// * `info` - The table to insert the entries into.
+    fn debug_info(&self, info: &mut ZendHashTable) -> PhpResult;
+}
+
+/// Builds the debug info of an object from its [`Debug`] implementation,
+/// inserting it under the `debug` key. Used by `#[php_class(debug)]`.
+///
+/// # Parameters
+///
+/// * `obj` - The object to describe.
+/// * `info` - The table to insert the description into.
+pub fn debug_info_from_debug<T: Debug>(obj: &T, info: &mut ZendHashTable) -> PhpResult {
+    info.insert("debug", format!("{obj:#?}"))?;
+    Ok(())
+}
+
+/// The type an object is being cast to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastType {
+    /// A `(string)` cast, or use of the object as a string.
+    String,
+    /// An `(int)` cast.
+    Long,
+    /// A `(float)` cast.
+    Double,
+    /// A `(bool)` cast, or use of the object in a condition.
+    Bool,
+    /// Use of the object in an arithmetic operation. Either an integer or a
+    /// float may be returned.
+    Number,
+}
+
+impl CastType {
+    /// Returns whether the given zval is a valid result for a cast to this
+    /// type.
+    pub fn accepts(self, zv: &Zval) -> bool {
+        matches!(
+            (self, zv.get_type()),
+            (Self::String, DataType::String)
+                | (Self::Long, DataType::Long)
+                | (Self::Double, DataType::Double)
+                | (Self::Bool, DataType::True | DataType::False)
+                | (Self::Number, DataType::Long | DataType::Double)
+        )
+    }
+}
+
+impl TryFrom<u32> for CastType {
+    type Error = crate::error::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Ok(match value {
+            IS_STRING => Self::String,
+            IS_LONG => Self::Long,
+            IS_DOUBLE => Self::Double,
+            _IS_BOOL => Self::Bool,
+            _IS_NUMBER => Self::Number,
+            _ => return Err(crate::error::Error::UnknownDatatype(value)),
+        })
+    }
+}
+
+/// Implemented on classes which define how their objects are converted by
+/// casts such as `(string)`, `(int)` and `(bool)`.
+///
+/// Annotate the struct with `#[php_class(cast)]` to use the implementation.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{
+///     class::{CastResult, CastType, PhpCast},
+///     convert::IntoZval,
+///     prelude::*,
+///     types::Zval,
+/// };
+///
+/// #[php_class(cast)]
+/// pub struct Money {
+///     cents: i64,
+/// }
+///
+/// impl PhpCast for Money {
+///     fn cast(&self, ty: CastType) -> CastResult {
+///         Ok(match ty {
+///             CastType::Long | CastType::Number => Some(self.cents.into_zval(false)?),
+///             CastType::String => Some(format!("{:.2}", self.cents as f64 / 100.0).into_zval(false)?),
+///             _ => None,
+///         })
+///     }
+/// }
+/// ```
+pub trait PhpCast {
+    /// Converts the object into a value of the given type.
+    ///
+    /// Returns [`None`] to fall back to the default behaviour, where only
+    /// `(bool)` casts and `(string)` casts on objects with a `__toString()`
+    /// method succeed. The returned zval must be of the requested type,
+    /// otherwise the cast fails.
+    ///
+    /// # Parameters
+    ///
+    /// * `ty` - The type to convert the object into.
+    fn cast(&self, ty: CastType) -> CastResult;
+}
+
+/// The result of casting an object with [`PhpCast`].
+pub type CastResult = PhpResult<Option<Zval>>;
+
+/// Implemented on classes whose objects can be compared with `==`, `<`, `>`
+/// and `<=>`, which is also used by functions such as `sort()`.
+///
+/// Annotate the struct with `#[php_class(compare)]` to use the
+/// implementation. Comparisons with values which are not objects of the same
+/// class fall back to the default comparison of objects.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::cmp::Ordering;
+/// use ext_php_rs::{class::PhpCompare, prelude::*};
+///
+/// #[php_class(compare)]
+/// pub struct Version {
+///     major: u32,
+///     minor: u32,
+/// }
+///
+/// impl PhpCompare for Version {
+///     fn compare(&self, other: &Self) -> Option<Ordering> {
+///         Some((self.major, self.minor).cmp(&(other.major, other.minor)))
+///     }
+/// }
+/// ```
+pub trait PhpCompare {
+    /// Compares the object with another object of the same class.
+    ///
+    /// Returns [`None`] if the objects cannot be ordered, in which case all
+    /// comparisons between them, including `==`, are false.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The object to compare with.
+    fn compare(&self, other: &Self) -> Option<CmpOrdering>;
+}
+
+/// Implemented on classes which are notified when PHP writes to one of their
+/// declared properties, e.g. to track which fields of a record are dirty.
+///
+/// Annotate the struct with `#[php_class(observe)]` to use the
+/// implementation. Writes to `#[prop]` fields, method properties and
+/// properties declared with [`ClassBuilder::property`] are reported, while
+/// writes to dynamic properties and writes which throw an exception are not.
+///
+/// Properties declared with [`ClassBuilder::property`] are stored in the
+/// properties table of the object, and PHP modifies them in place without
+/// calling `write_property` when they are incremented (`$obj->count++`),
+/// appended to (`$obj->items[] = $item`), assigned with compound operators
+/// (`$obj->name .= '!'`) or written through a reference. These writes bypass
+/// the observer. `#[prop]` fields and method properties are not stored in the
+/// table, so all writes to them are reported.
+///
+/// [`ClassBuilder::property`]: crate::builders::ClassBuilder::property
+///
+/// # Example
+///
+/// ```no_run
+/// use std::collections::HashSet;
+/// use ext_php_rs::{class::PhpPropertyObserver, prelude::*};
+///
+/// #[php_class(observe)]
+/// pub struct User {
+///     #[prop]
+///     name: String,
+///     dirty: HashSet<String>,
+/// }
+///
+/// impl PhpPropertyObserver for User {
+///     fn property_changed(&mut self, name: &str) {
+///         self.dirty.insert(name.to_string());
+///     }
+/// }
+/// ```
+pub trait PhpPropertyObserver {
+    /// Called after a declared property of the object has been written.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property which was written.
+    fn property_changed(&mut self, name: &str);
+}
+
+/// Implemented on classes whose objects can be accessed like arrays, e.g.
+/// `$obj['key']`, `isset($obj['key'])` and `unset($obj['key'])`.
+///
+/// Annotate the struct with `#[php_class(array_access)]` to use the
+/// implementation. Unlike the `ArrayAccess` interface, the methods are called
+/// directly by the object handlers, without going through PHP method calls.
+/// Objects do not become instances of `ArrayAccess`.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::collections::HashMap;
+/// use ext_php_rs::{class::PhpArrayAccess, convert::IntoZval, prelude::*, types::Zval};
+///
+/// #[php_class(array_access)]
+/// pub struct Counters {
+///     counts: HashMap<String, i64>,
+/// }
+///
+/// impl PhpArrayAccess for Counters {
+///     fn offset_exists(&self, offset: &Zval) -> PhpResult<bool> {
+///         Ok(offset.str().is_some_and(|key| self.counts.contains_key(key)))
+///     }
+///
+///     fn offset_get(&self, offset: &Zval) -> PhpResult<Zval> {
+///         let count = offset.str().and_then(|key| self.counts.get(key)).copied();
+///         Ok(count.unwrap_or(0).into_zval(false)?)
+///     }
+///
+///     fn offset_set(&mut self, offset: Option<&Zval>, value: &Zval) -> PhpResult {
+///         let key = offset.and_then(|offset| offset.str()).ok_or("Invalid key")?;
+///         let count = value.long().ok_or("Counts must be integers")?;
+///         self.counts.insert(key.to_string(), count);
+///         Ok(())
+///     }
+///
+///     fn offset_unset(&mut self, offset: &Zval) -> PhpResult {
+///         if let Some(key) = offset.str() {
+///             self.counts.remove(key);
+///         }
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait PhpArrayAccess {
+    /// Returns whether the offset exists. Used by `isset()` and `empty()`.
+    ///
+    /// # Parameters
+    ///
+    /// * `offset` - The offset being checked.
+    fn offset_exists(&self, offset: &Zval) -> PhpResult<bool>;
+
+    /// Returns the value at the offset.
+    ///
+    /// Nested writes such as `$obj['a'][] = 1` modify the returned value, so
+    /// they throw an `Error` unless the value is an object or a reference.
+    ///
+    /// # Parameters
+    ///
+    /// * `offset` - The offset being read.
+    fn offset_get(&self, offset: &Zval) -> PhpResult<Zval>;
+
+    /// Sets the value at the offset.
+    ///
+    /// # Parameters
+    ///
+    /// * `offset` - The offset being written, or [`None`] when appending with
+    ///   `$obj[] = $value`.
+    /// * `value` - The value being written.
+    fn offset_set(&mut self, offset: Option<&Zval>, value: &Zval) -> PhpResult;
+
+    /// Removes the value at the offset.
+    ///
+    /// # Parameters
+    ///
+    /// * `offset` - The offset being removed.
+    fn offset_unset(&mut self, offset: &Zval) -> PhpResult;
+}
+
+/// Stores the functions used to handle array access on a class. Usually
+/// generated from a [`PhpArrayAccess`] implementation.
+pub struct DimensionMeta<T> {
+    /// Checks whether an offset exists.
+    pub exists: fn(&T, &Zval) -> PhpResult<bool>,
+    /// Reads the value at an offset.
+    pub get: fn(&T, &Zval) -> PhpResult<Zval>,
+    /// Writes the value at an offset.
+    pub set: fn(&mut T, Option<&Zval>, &Zval) -> PhpResult,
+    /// Removes the value at an offset.
+    pub unset: fn(&mut T, &Zval) -> PhpResult,
+}
+
+/// Implemented on classes whose objects can be iterated with `foreach`.
+///
+/// Annotate the struct with `#[php_class(iterator)]` to use the
+/// implementation. The iterator is driven directly by the engine, without
+/// calling the methods of the `Iterator` interface for each element. Objects
+/// do not become instances of `Traversable`, so functions such as
+/// `iterator_to_array()` cannot be used on them, and iterating by reference
+/// throws an error.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{class::PhpNativeIterator, prelude::*};
+///
+/// #[php_class(iterator)]
+/// pub struct Range {
+///     start: i64,
+///     end: i64,
+/// }
+///
+/// impl PhpNativeIterator for Range {
+///     type Key = i64;
+///     type Value = i64;
+///
+///     fn php_iter(&self) -> Box<dyn Iterator<Item = (i64, i64)>> {
+///         Box::new((self.start..self.end).zip(0..).map(|(n, i)| (i, n)))
+///     }
+/// }
+/// ```
+pub trait PhpNativeIterator {
+    /// The type of the keys of the iterated elements.
+    type Key: IntoZval + 'static;
+    /// The type of the values of the iterated elements.
+    type Value: IntoZval + 'static;
+
+    /// Returns an iterator over the keys and values of the object. This is
+    /// called each time the object is rewound, e.g. at the start of every
+    /// `foreach` loop.
+    ///
+    /// The iterator cannot borrow from the object, so data which is iterated
+    /// over must be cloned or shared with the iterator.
+    fn php_iter(&self) -> Box<dyn Iterator<Item = (Self::Key, Self::Value)>>;
+}
+
+/// An iterator over the keys and values of an object, converted into zvals.
+/// Created by [`RegisteredClass::ITERATOR`].
+pub type NativeIter = Box<dyn Iterator<Item = crate::error::Result<(Zval, Zval)>>>;
+
+/// Creates the iterator of an object from its [`PhpNativeIterator`]
+/// implementation. Used by `#[php_class(iterator)]`.
+///
+/// # Parameters
+///
+/// * `obj` - The object to iterate over.
+pub fn native_iter<T: PhpNativeIterator>(obj: &T) -> NativeIter {
+    Box::new(
+        obj.php_iter()
+            .map(|(key, value)| Ok((key.into_zval(false)?, value.into_zval(false)?))),
+    )
+}
+
+/// Implemented on types holding a PHP object which a class can delegate to
+/// with a `#[delegate]` field. The class is given `__call()` and `__get()`
+/// methods which forward calls to undefined methods and reads of undefined
+/// properties to the object, so decorators and proxies can be written without
+/// implementing every method of the wrapped class.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{boxed::ZBox, prelude::*, types::ZendObject};
+///
+/// #[php_class]
+/// pub struct LoggingProxy {
+///     #[delegate]
+///     #[gc]
+///     inner: ZBox<ZendObject>,
+/// }
+/// ```
+pub trait Delegate {
+    /// Returns the object calls are forwarded to, or [`None`] if there is
+    /// currently no object.
+    fn delegate_object(&mut self) -> Option<&mut ZendObject>;
+}
+
+impl Delegate for ZBox<ZendObject> {
+    fn delegate_object(&mut self) -> Option<&mut ZendObject> {
+        Some(self)
+    }
+}
+
+impl<T: RegisteredClass> Delegate for ZBox<ZendClassObject<T>> {
+    fn delegate_object(&mut self) -> Option<&mut ZendObject> {
+        Some(&mut self.std)
+    }
+}
+
+impl Delegate for Zval {
+    fn delegate_object(&mut self) -> Option<&mut ZendObject> {
+        self.object_mut()
+    }
+}
+
+impl<T: Delegate> Delegate for Option<T> {
+    fn delegate_object(&mut self) -> Option<&mut ZendObject> {
+        self.as_mut()?.delegate_object()
+    }
 }
 
 /// Stores metadata about a classes Rust constructor, including the function
@@ -83,11 +578,60 @@ impl<T> From<T> for ConstructorResult<T> {
     }
 }
 
+/// The object handlers of a Rust class, followed by the functions which find
+/// the value of a Rust parent class in an instance of the class. Objects only
+/// point to the handlers, so the functions can be found from objects whose
+/// Rust type is not known.
+#[repr(C)]
+pub(crate) struct ClassHandlers {
+    pub(crate) handlers: ZendObjectHandlers,
+    pub(crate) upcast: for<'a> fn(&'a ZendObject, TypeId) -> Option<&'a dyn Any>,
+    pub(crate) upcast_mut: for<'a> fn(&'a mut ZendObject, TypeId) -> Option<&'a mut dyn Any>,
+}
+
+/// The addresses of the handlers of the Rust classes which have been
+/// registered, used to check that the handlers of an object are
+/// [`ClassHandlers`] before reading past the standard handlers.
+static CLASS_HANDLERS: RwLock<BTreeSet<usize>> = const_rwlock(BTreeSet::new());
+
+impl ClassHandlers {
+    /// Returns the handlers of an object as [`ClassHandlers`], if the object
+    /// is an instance of a Rust class.
+    ///
+    /// # Parameters
+    ///
+    /// * `obj` - The object to get the handlers of.
+    pub(crate) fn of(obj: &ZendObject) -> Option<&'static Self> {
+        let handlers = obj.handlers as *const Self;
+        if !CLASS_HANDLERS.read().contains(&(handlers as usize)) {
+            return None;
+        }
+        // SAFETY: The address is only registered by `ClassMetadata::set_ce`, for
+        // handlers stored in the static metadata of a class.
+        unsafe { handlers.as_ref() }
+    }
+}
+
+fn upcast<T: RegisteredClass>(obj: &ZendObject, ty: TypeId) -> Option<&dyn Any> {
+    ZendClassObject::<T>::from_zend_obj(obj)?
+        .obj
+        .as_ref()?
+        .upcast(ty)
+}
+
+fn upcast_mut<T: RegisteredClass>(obj: &mut ZendObject, ty: TypeId) -> Option<&mut dyn Any> {
+    ZendClassObject::<T>::from_zend_obj_mut(obj)?
+        .obj
+        .as_mut()?
+        .upcast_mut(ty)
+}
+
 /// Stores the class entry and handlers for a Rust type which has been exported
 /// to PHP. Usually allocated statically.
-pub struct ClassMetadata<T> {
-    handlers: OnceCell<ZendObjectHandlers>,
+pub struct ClassMetadata<T: 'static> {
+    handlers: OnceCell<ClassHandlers>,
     properties: OnceCell<HashMap<&'static str, Property<'static, T>>>,
+    property_lookup: OnceCell<HashMap<&'static [u8], &'static Property<'static, T>>>,
     ce: AtomicPtr<ClassEntry>,
 
     // `AtomicPtr` is used here because it is `Send + Sync`.
@@ -96,19 +640,20 @@ pub struct ClassMetadata<T> {
     phantom: PhantomData<AtomicPtr<T>>,
 }
 
-impl<T> ClassMetadata<T> {
+impl<T: 'static> ClassMetadata<T> {
     /// Creates a new class metadata instance.
     pub const fn new() -> Self {
         Self {
             handlers: OnceCell::new(),
             properties: OnceCell::new(),
+            property_lookup: OnceCell::new(),
             ce: AtomicPtr::new(std::ptr::null_mut()),
             phantom: PhantomData,
         }
     }
 }
 
-impl<T> Default for ClassMetadata<T> {
+impl<T: 'static> Default for ClassMetadata<T> {
     fn default() -> Self {
         Self::new()
     }
@@ -118,7 +663,15 @@ impl<T: RegisteredClass> ClassMetadata<T> {
     /// Returns an immutable reference to the object handlers contained inside
     /// the class metadata.
     pub fn handlers(&self) -> &ZendObjectHandlers {
-        self.handlers.get_or_init(ZendObjectHandlers::new::<T>)
+        &self.class_handlers().handlers
+    }
+
+    pub(crate) fn class_handlers(&self) -> &ClassHandlers {
+        self.handlers.get_or_init(|| ClassHandlers {
+            handlers: ZendObjectHandlers::new::<T>(),
+            upcast: upcast::<T>,
+            upcast_mut: upcast_mut::<T>,
+        })
     }
 
     /// Checks if the class entry has been stored, returning a boolean.
@@ -158,6 +711,9 @@ impl<T: RegisteredClass> ClassMetadata<T> {
                 Ordering::Relaxed,
             )
             .expect("Class entry has already been set");
+        CLASS_HANDLERS
+            .write()
+            .insert(self.class_handlers() as *const ClassHandlers as usize);
     }
 
     /// Retrieves a reference to the hashmap storing the classes property
@@ -169,4 +725,29 @@ impl<T: RegisteredClass> ClassMetadata<T> {
     pub fn get_properties(&self) -> &HashMap<&'static str, Property<'static, T>> {
         self.properties.get_or_init(T::get_properties)
     }
+
+    /// Looks up a property accessor by the name passed to the object
+    /// handlers.
+    ///
+    /// Names are matched by their bytes, so the name does not have to be
+    /// validated as UTF-8 on every property access. The lookup table is built
+    /// once per class, on first use.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property.
+    pub(crate) fn find_property(
+        &'static self,
+        name: &ZendStr,
+    ) -> Option<&'static Property<'static, T>> {
+        self.property_lookup
+            .get_or_init(|| {
+                self.get_properties()
+                    .iter()
+                    .map(|(name, prop)| (name.as_bytes(), prop))
+                    .collect()
+            })
+            .get(name.as_bytes())
+            .copied()
+    }
 }
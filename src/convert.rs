@@ -34,6 +34,14 @@ where
     }
 }
 
+impl FromZval<'_> for () {
+    const TYPE: DataType = DataType::Void;
+
+    fn from_zval(zval: &Zval) -> Option<Self> {
+        zval.is_null().then_some(())
+    }
+}
+
 /// Allows mutable zvals to be converted into Rust types in a fallible way.
 ///
 /// If `Self` does not require the zval to be mutable to be extracted, you
@@ -13,7 +13,7 @@
 //! [`usize`] should not be in use, but rather `size_t` or a similar type,
 //! however these are currently unstable.
 
-use std::{fmt::Display, ops::Deref, vec::Vec as StdVec};
+use std::{fmt::Display, ops::Deref, option::Option as StdOption, vec::Vec as StdVec};
 
 /// An immutable, ABI-stable [`Vec`][std::vec::Vec].
 #[repr(C)]
@@ -91,3 +91,12 @@ pub enum Option<T> {
     Some(T),
     None,
 }
+
+impl<T> From<StdOption<T>> for Option<T> {
+    fn from(val: StdOption<T>) -> Self {
+        match val {
+            StdOption::Some(val) => Self::Some(val),
+            StdOption::None => Self::None,
+        }
+    }
+}
@@ -4,6 +4,8 @@
 pub mod abi;
 mod stub;
 
+use std::fmt::Error as FmtError;
+
 use crate::flags::DataType;
 use abi::*;
 
@@ -37,6 +39,7 @@ pub struct Module {
     pub name: Str,
     pub functions: Vec<Function>,
     pub classes: Vec<Class>,
+    pub enums: Vec<Enum>,
     pub constants: Vec<Constant>,
 }
 
@@ -69,11 +72,37 @@ pub struct Class {
     pub docs: DocBlock,
     pub extends: Option<Str>,
     pub implements: Vec<Str>,
+    pub uses: Vec<Str>,
     pub properties: Vec<Property>,
     pub methods: Vec<Method>,
     pub constants: Vec<Constant>,
 }
 
+/// Represents an exported enum.
+#[repr(C)]
+pub struct Enum {
+    pub name: Str,
+    pub docs: DocBlock,
+    /// The type of the values backing the cases, if the enum is a backed enum.
+    pub backing: Option<DataType>,
+    pub implements: Vec<Str>,
+    pub cases: Vec<EnumCase>,
+    pub methods: Vec<Method>,
+    pub constants: Vec<Constant>,
+}
+
+/// Represents a case of an exported enum.
+#[repr(C)]
+pub struct EnumCase {
+    pub name: Str,
+    pub docs: DocBlock,
+    /// The value backing the case, written as a PHP literal.
+    pub value: Option<Str>,
+    /// The attributes applied to the case, written as the name of the
+    /// attribute class followed by its arguments, e.g. `Label('a')`.
+    pub attributes: Vec<Str>,
+}
+
 /// Represents a property attached to an exported class.
 #[repr(C)]
 pub struct Property {
@@ -131,3 +160,104 @@ pub struct Constant {
     pub docs: DocBlock,
     pub value: Option<Str>,
 }
+
+/// Generates the PHP stubs of an extension from its description, as
+/// `cargo php stubs` does, so the public API of the extension can be snapshot
+/// tested, e.g. by comparing the stubs against a checked-in `.stubs.php` file
+/// in CI.
+///
+/// The description is returned by the `ext_php_rs_describe_module` function
+/// generated by [`macro@crate::php_module`], which is only generated in debug
+/// builds.
+///
+/// # Parameters
+///
+/// * `description` - The description of the extension.
+///
+/// # Returns
+///
+/// Returns the stubs on success. Returns an error if there was an error
+/// writing into the string.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{describe, prelude::*};
+///
+/// #[php_function]
+/// pub fn hello_world(name: String) -> String {
+///     format!("Hello, {}!", name)
+/// }
+///
+/// #[php_module]
+/// pub fn get_module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+///
+/// # fn main() {
+/// let stubs = describe::stubs(ext_php_rs_describe_module()).unwrap();
+/// assert!(stubs.contains("function hello_world(string $name): string {}"));
+/// # }
+/// ```
+pub fn stubs(description: Description) -> Result<String, FmtError> {
+    description.module.to_stub()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{stubs, Class, Description, DocBlock, Function, Module, Parameter, Retval};
+    use crate::flags::DataType;
+
+    fn describe() -> Description {
+        Description::new(Module {
+            name: "test".into(),
+            functions: vec![Function {
+                name: "Test\\find".into(),
+                docs: DocBlock(vec![" Finds an item.".into()].into()),
+                ret: Some(Retval {
+                    ty: DataType::Object(Some("Test\\Item")),
+                    nullable: true,
+                })
+                .into(),
+                params: vec![Parameter {
+                    name: "value".into(),
+                    ty: Some(DataType::String).into(),
+                    nullable: true,
+                    default: None.into(),
+                }]
+                .into(),
+            }]
+            .into(),
+            classes: vec![Class {
+                name: "Test\\Item".into(),
+                docs: DocBlock(vec![].into()),
+                extends: None.into(),
+                implements: vec![].into(),
+                uses: vec![].into(),
+                properties: vec![].into(),
+                methods: vec![].into(),
+                constants: vec![].into(),
+            }]
+            .into(),
+            enums: vec![].into(),
+            constants: vec![].into(),
+        })
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    pub fn test_stubs() {
+        assert_eq!(
+            stubs(describe()).unwrap(),
+            "<?php\n\n// Stubs for test\n\n\
+             namespace Test {\n    \
+             /**\n     \
+             * Finds an item.\n     \
+             */\n    \
+             function find(?string $value): ?\\Test\\Item {}\n\n    \
+             class Item {\n    \
+             }\n\
+             }\n"
+        );
+    }
+}
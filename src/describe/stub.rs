@@ -4,8 +4,8 @@ use crate::flags::DataType;
 use std::{cmp::Ordering, collections::HashMap};
 
 use super::{
-    abi::*, Class, Constant, DocBlock, Function, Method, MethodType, Module, Parameter, Property,
-    Visibility,
+    abi::*, Class, Constant, DocBlock, Enum, EnumCase, Function, Method, MethodType, Module,
+    Parameter, Property, Visibility,
 };
 use std::fmt::{Error as FmtError, Result as FmtResult, Write};
 use std::{option::Option as StdOption, vec::Vec as StdVec};
@@ -71,6 +71,11 @@ impl ToStub for Module {
             insert(ns, class.to_stub()?);
         }
 
+        for enum_ in &*self.enums {
+            let (ns, _) = split_namespace(enum_.name.as_ref());
+            insert(ns, enum_.to_stub()?);
+        }
+
         let mut entries: StdVec<_> = entries.iter().collect();
         entries.sort_by(|(l, _), (r, _)| match (l, r) {
             (None, _) => Ordering::Greater,
@@ -223,8 +228,20 @@ impl ToStub for Class {
                 .map(|item| item.to_stub().map(|stub| indent(&stub, 4)))
         }
 
+        let uses = if self.uses.is_empty() {
+            None
+        } else {
+            Some(Ok(self
+                .uses
+                .iter()
+                .map(|trait_| format!("    use {};\n", trait_.str()))
+                .collect::<String>()))
+        };
+
         buf.push_str(
-            &stub(&self.constants)
+            &uses
+                .into_iter()
+                .chain(stub(&self.constants))
                 .chain(stub(&self.properties))
                 .chain(stub(&self.methods))
                 .collect::<Result<StdVec<_>, FmtError>>()?
@@ -235,6 +252,70 @@ impl ToStub for Class {
     }
 }
 
+impl ToStub for Enum {
+    fn fmt_stub(&self, buf: &mut String) -> FmtResult {
+        self.docs.fmt_stub(buf)?;
+
+        let (_, name) = split_namespace(self.name.as_ref());
+        write!(buf, "enum {name}")?;
+
+        if let Option::Some(backing) = &self.backing {
+            write!(buf, ": ")?;
+            backing.fmt_stub(buf)?;
+        }
+
+        if !self.implements.is_empty() {
+            write!(
+                buf,
+                " implements {}",
+                self.implements
+                    .iter()
+                    .map(|s| s.str())
+                    .collect::<StdVec<_>>()
+                    .join(", ")
+            )?;
+        }
+
+        writeln!(buf, " {{")?;
+
+        // Cases are grouped together, while constants and methods are separated
+        // by blank lines.
+        let mut sections = StdVec::new();
+        if !self.cases.is_empty() {
+            sections.push(
+                self.cases
+                    .iter()
+                    .map(|case| case.to_stub().map(|stub| indent(&stub, 4)))
+                    .collect::<Result<String, FmtError>>()?,
+            );
+        }
+        for constant in &*self.constants {
+            sections.push(indent(&constant.to_stub()?, 4));
+        }
+        for method in &*self.methods {
+            sections.push(indent(&method.to_stub()?, 4));
+        }
+        buf.push_str(&sections.join(NEW_LINE_SEPARATOR));
+
+        writeln!(buf, "}}")
+    }
+}
+
+impl ToStub for EnumCase {
+    fn fmt_stub(&self, buf: &mut String) -> FmtResult {
+        self.docs.fmt_stub(buf)?;
+        for attr in self.attributes.iter() {
+            writeln!(buf, "#[\\{attr}]")?;
+        }
+
+        write!(buf, "case {}", self.name)?;
+        if let Option::Some(value) = &self.value {
+            write!(buf, " = {value}")?;
+        }
+        writeln!(buf, ";")
+    }
+}
+
 impl ToStub for Property {
     fn fmt_stub(&self, buf: &mut String) -> FmtResult {
         self.docs.fmt_stub(buf)?;
@@ -393,4 +474,63 @@ mod test {
             format!("    hello{nl}    world{nl}", nl = NEW_LINE_SEPARATOR)
         );
     }
+
+    #[test]
+    #[cfg(not(windows))]
+    pub fn test_enum_stub() {
+        use super::ToStub;
+        use crate::describe::{
+            Constant, DocBlock, Enum, EnumCase, Method, MethodType, Retval, Visibility,
+        };
+        use crate::flags::DataType;
+
+        let case = |name: &'static str, value: &'static str, attributes: Vec<_>| EnumCase {
+            name: name.into(),
+            docs: DocBlock(vec![].into()),
+            value: Some(value.into()).into(),
+            attributes: attributes.into(),
+        };
+        let enum_ = Enum {
+            name: "Test\\Suit".into(),
+            docs: DocBlock(vec![].into()),
+            backing: Some(DataType::String).into(),
+            implements: vec!["\\Countable".into()].into(),
+            cases: vec![
+                case("Hearts", "'H'", vec!["Test\\Label('red', 1)".into()]),
+                case("Spades", "'S'", vec![]),
+            ]
+            .into(),
+            methods: vec![Method {
+                name: "label".into(),
+                docs: DocBlock(vec![].into()),
+                ty: MethodType::Member,
+                params: vec![].into(),
+                retval: Some(Retval {
+                    ty: DataType::String,
+                    nullable: false,
+                })
+                .into(),
+                _static: false,
+                visibility: Visibility::Public,
+            }]
+            .into(),
+            constants: vec![Constant {
+                name: "WILD".into(),
+                docs: DocBlock(vec![].into()),
+                value: Some("'W'".into()).into(),
+            }]
+            .into(),
+        };
+
+        assert_eq!(
+            enum_.to_stub().unwrap(),
+            "enum Suit: string implements \\Countable {\n    \
+             #[\\Test\\Label('red', 1)]\n    \
+             case Hearts = 'H';\n    \
+             case Spades = 'S';\n\n    \
+             const WILD = 'W';\n\n    \
+             public function label(): string {}\n\
+             }\n"
+        );
+    }
 }
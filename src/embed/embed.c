@@ -28,3 +28,45 @@ void ext_php_rs_sapi_startup() {
 
   zend_signal_startup();
 }
+
+static zend_module_entry *ext_php_rs_embed_module_entry = NULL;
+
+static int ext_php_rs_embed_module_startup(sapi_module_struct *sapi_module) {
+#if PHP_VERSION_ID >= 80200
+  return php_module_startup(sapi_module, ext_php_rs_embed_module_entry);
+#else
+  return php_module_startup(sapi_module, ext_php_rs_embed_module_entry, ext_php_rs_embed_module_entry ? 1 : 0);
+#endif
+}
+
+// Starts the embed SAPI with an additional module, which is started along with
+// the built-in extensions. The engine is left running with a request started.
+zend_result ext_php_rs_embed_init(zend_module_entry *module) {
+  ext_php_rs_embed_module_entry = module;
+  php_embed_module.startup = ext_php_rs_embed_module_startup;
+
+  return php_embed_init(0, NULL);
+}
+
+// Starts a request the same way `php_embed_init` starts the first one.
+zend_result ext_php_rs_embed_request_startup() {
+  if (php_request_startup() == FAILURE) {
+    return FAILURE;
+  }
+
+  SG(headers_sent) = 1;
+  SG(request_info).no_headers = 1;
+
+  return SUCCESS;
+}
+
+// Starts the engine with the given SAPI and an additional module, which is
+// started along with the built-in extensions. No request is started.
+zend_result ext_php_rs_sapi_init(sapi_module_struct *sapi, zend_module_entry *module) {
+  ext_php_rs_sapi_startup();
+  sapi_startup(sapi);
+
+  ext_php_rs_embed_module_entry = module;
+
+  return ext_php_rs_embed_module_startup(sapi);
+}
@@ -4,3 +4,9 @@
 void* ext_php_rs_embed_callback(int argc, char** argv, void* (*callback)(void *), void *ctx);
 
 void ext_php_rs_sapi_startup();
+
+zend_result ext_php_rs_embed_init(zend_module_entry *module);
+
+zend_result ext_php_rs_embed_request_startup();
+
+zend_result ext_php_rs_sapi_init(sapi_module_struct *sapi, zend_module_entry *module);
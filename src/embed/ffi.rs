@@ -5,6 +5,8 @@
 
 use std::ffi::{c_char, c_int, c_void};
 
+use crate::ffi::{sapi_module_struct, zend_module_entry, ZEND_RESULT_CODE};
+
 #[link(name = "wrapper")]
 extern "C" {
     pub fn ext_php_rs_embed_callback(
@@ -15,4 +17,13 @@ extern "C" {
     ) -> *mut c_void;
 
     pub fn ext_php_rs_sapi_startup();
+
+    pub fn ext_php_rs_embed_init(module: *mut zend_module_entry) -> ZEND_RESULT_CODE;
+
+    pub fn ext_php_rs_embed_request_startup() -> ZEND_RESULT_CODE;
+
+    pub fn ext_php_rs_sapi_init(
+        sapi: *mut sapi_module_struct,
+        module: *mut zend_module_entry,
+    ) -> ZEND_RESULT_CODE;
 }
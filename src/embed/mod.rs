@@ -7,23 +7,31 @@
 
 mod ffi;
 mod sapi;
+mod test_sapi;
 
 use crate::boxed::ZBox;
-use crate::embed::ffi::ext_php_rs_embed_callback;
+use crate::embed::ffi::{
+    ext_php_rs_embed_callback, ext_php_rs_embed_init, ext_php_rs_embed_request_startup,
+};
 use crate::ffi::{
-    _zend_file_handle__bindgen_ty_1, php_execute_script, zend_eval_string, zend_file_handle,
-    zend_stream_init_filename, ZEND_RESULT_CODE_SUCCESS,
+    _zend_file_handle__bindgen_ty_1, php_execute_script, php_request_shutdown, zend_eval_string,
+    zend_file_handle, zend_stream_init_filename, ZEND_RESULT_CODE_SUCCESS,
+};
+use crate::types::{ZendCallable, ZendObject, Zval};
+use crate::zend::{
+    panic_wrapper, try_catch, try_catch_first, ExecutorGlobals, Ini, IniStage, ModuleEntry,
 };
-use crate::types::{ZendObject, Zval};
-use crate::zend::{panic_wrapper, try_catch, ExecutorGlobals};
-use parking_lot::{const_rwlock, RwLock};
+use once_cell::sync::OnceCell;
+use parking_lot::{const_mutex, const_rwlock, Mutex, RwLock};
 use std::ffi::{c_char, c_void, CString, NulError};
 use std::panic::{resume_unwind, RefUnwindSafe};
 use std::path::Path;
 use std::ptr::null_mut;
+use std::thread::{self, ThreadId};
 
 pub use ffi::ext_php_rs_sapi_startup;
 pub use sapi::SapiModule;
+pub use test_sapi::{TestRequest, TestResponse, TestSapi};
 
 pub struct Embed;
 
@@ -45,6 +53,46 @@ impl EmbedError {
 
 static RUN_FN_LOCK: RwLock<()> = const_rwlock(());
 
+/// State of the engine booted by [`Embed::run_with_module`] or
+/// [`TestSapi`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EngineState {
+    /// The engine has not been booted.
+    Stopped,
+    /// The engine has been booted, and no request is running.
+    Idle,
+    /// A request has been started, and nothing has run in it yet.
+    Fresh,
+    /// A request has been started, and code has run in it.
+    Used,
+    /// Starting the last request failed.
+    Failed,
+}
+
+static ENGINE: Mutex<EngineState> = const_mutex(EngineState::Stopped);
+
+/// The thread which booted the engine of [`Embed::run_with_module`] or
+/// [`TestSapi`].
+static ENGINE_THREAD: OnceCell<ThreadId> = OnceCell::new();
+
+/// Records the current thread as the one which booted the engine.
+fn set_engine_thread() {
+    let _ = ENGINE_THREAD.set(thread::current().id());
+}
+
+/// Panics if PHP is thread-safe (ZTS) and the engine was booted by another
+/// thread, as the PHP globals of the current thread have not been allocated.
+fn assert_engine_thread() {
+    assert!(
+        !crate::PHP_ZTS || ENGINE_THREAD.get() == Some(&thread::current().id()),
+        "The PHP engine was booted by another thread. With a thread-safe (ZTS) \
+         build of PHP, `Embed::run_with_module`, `TestSapi` and `#[php_test]` can \
+         only run on the thread which first called them, and the test harness \
+         runs each test on its own thread. Use a non thread-safe build of PHP to \
+         run several tests."
+    );
+}
+
 impl Embed {
     /// Run a php script from a file
     ///
@@ -137,6 +185,14 @@ impl Embed {
         // avoid doing that in this case
         let _guard = RUN_FN_LOCK.write();
 
+        // The engine booted by `run_with_module` cannot be started a second
+        // time, so the function runs in a request of that engine instead.
+        let mut engine = ENGINE.lock();
+        if *engine != EngineState::Stopped {
+            return Self::run_request(&mut engine, func).unwrap_or_default();
+        }
+        drop(engine);
+
         let panic = unsafe {
             ext_php_rs_embed_callback(
                 0,
@@ -160,6 +216,128 @@ impl Embed {
         }
     }
 
+    /// Run a function inside a PHP request, with the given module loaded
+    ///
+    /// Unlike [`Embed::run`], the embed SAPI is only started by the first call
+    /// and keeps running until the process exits. The module is started along
+    /// with the built-in extensions, so its startup function runs and its
+    /// classes and functions are registered once. Each call runs in a request
+    /// of its own, so globals and objects of one call are not visible to the
+    /// next one.
+    ///
+    /// The module passed to the first call is the one which is loaded, later
+    /// calls use the same engine regardless of the module passed. Calls are
+    /// run one at a time, as the engine is shared by the whole process.
+    ///
+    /// With a thread-safe (ZTS) build of PHP, the engine can only be used from
+    /// the thread which booted it, as each thread has its own PHP globals.
+    ///
+    /// This is used by the [`macro@crate::php_test`] macro.
+    ///
+    /// # Parameters
+    ///
+    /// * `module` - The function returning the module entry, usually the
+    ///   `get_module` function generated by [`macro@crate::php_module`].
+    /// * `func` - The function to run.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(R)` - The result of the function passed to this method
+    /// * `Err(EmbedError)` - The engine or the request could not be started,
+    ///   or a bailout occurred
+    ///
+    /// # Panics
+    ///
+    /// Panics when PHP is thread-safe (ZTS) and the engine was booted by
+    /// another thread.
+    pub fn run_with_module<R, F: FnMut() -> R + RefUnwindSafe>(
+        module: extern "C" fn() -> *mut ModuleEntry,
+        func: F,
+    ) -> Result<R, EmbedError> {
+        let _guard = RUN_FN_LOCK.write();
+        let mut engine = ENGINE.lock();
+
+        if *engine == EngineState::Stopped {
+            if unsafe { ext_php_rs_embed_init(module()) } != ZEND_RESULT_CODE_SUCCESS {
+                return Err(EmbedError::InitError);
+            }
+            *engine = EngineState::Fresh;
+            set_engine_thread();
+        }
+
+        Self::run_request(&mut engine, func)
+    }
+
+    /// Runs a function in a new request of the engine booted by
+    /// [`Embed::run_with_module`], shutting down the previous request first.
+    fn run_request<R, F: FnMut() -> R + RefUnwindSafe>(
+        engine: &mut EngineState,
+        func: F,
+    ) -> Result<R, EmbedError> {
+        assert_engine_thread();
+        if *engine == EngineState::Used {
+            unsafe { php_request_shutdown(null_mut()) };
+        }
+        if *engine != EngineState::Fresh
+            && unsafe { ext_php_rs_embed_request_startup() } != ZEND_RESULT_CODE_SUCCESS
+        {
+            *engine = EngineState::Failed;
+            return Err(EmbedError::InitError);
+        }
+        *engine = EngineState::Used;
+
+        try_catch_first(func).map_err(|_| EmbedError::CatchError)
+    }
+
+    /// Run PHP statements, such as `$a = 1; assert($a === 1);`
+    ///
+    /// Unlike [`Embed::eval`], the code can contain any number of statements,
+    /// and no value is returned. The code must not start with an opening
+    /// `<?php` tag.
+    ///
+    /// This function will only work correctly when used inside the `Embed::run`
+    /// function
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The code ran successfully
+    /// * `Err(EmbedError)` - An error occurred while running the code, such as
+    ///   an uncaught exception
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ext_php_rs::embed::Embed;
+    ///
+    /// Embed::run(|| {
+    ///    let result = Embed::exec("$foo = 'foo'; assert($foo === 'foo');");
+    ///    assert!(result.is_ok());
+    /// });
+    /// ```
+    pub fn exec(code: &str) -> Result<(), EmbedError> {
+        let cstr = match CString::new(code) {
+            Ok(cstr) => cstr,
+            Err(err) => return Err(EmbedError::InvalidEvalString(err)),
+        };
+
+        let exec_result = try_catch(|| unsafe {
+            zend_eval_string(
+                cstr.as_ptr() as *const c_char,
+                null_mut(),
+                b"run\0".as_ptr() as *const _,
+            )
+        });
+
+        match exec_result {
+            Err(_) => Err(EmbedError::CatchError),
+            Ok(ZEND_RESULT_CODE_SUCCESS) => match ExecutorGlobals::take_exception() {
+                Some(exception) => Err(EmbedError::ExecuteError(Some(exception))),
+                None => Ok(()),
+            },
+            Ok(_) => Err(EmbedError::ExecuteError(ExecutorGlobals::take_exception())),
+        }
+    }
+
     /// Evaluate a php code
     ///
     /// This function will only work correctly when used inside the `Embed::run`
@@ -204,9 +382,77 @@ impl Embed {
     }
 }
 
+/// Runs a PHP snippet inside a request with the module loaded, panicking with
+/// the uncaught exception and the output of the snippet if it fails. Used by
+/// [`php_assert!`](crate::php_assert).
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_php(module: extern "C" fn() -> *mut ModuleEntry, code: &str) {
+    let result = Embed::run_with_module(module, || {
+        // The output is buffered so warnings and notices printed by the snippet
+        // can be included in the failure message.
+        let _ = ZendCallable::try_from_name("ob_start").and_then(|f| f.try_call(vec![]));
+        let result = enable_assertions().and_then(|_| Embed::exec(code).map_err(describe_error));
+        let output = ZendCallable::try_from_name("ob_get_clean")
+            .and_then(|f| f.try_call(vec![]))
+            .ok()
+            .and_then(|output| output.string())
+            .unwrap_or_default();
+        (result, output)
+    });
+
+    let (error, output) = match result {
+        Ok((Ok(()), _)) => return,
+        Ok((Err(error), output)) => (error, output),
+        Err(e) => (describe_error(e), String::new()),
+    };
+    panic!(
+        "PHP snippet failed: {}\n\nsnippet:\n{}\n\noutput:\n{}",
+        error, code, output
+    );
+}
+
+/// Enables `assert()` for the current request, and makes failed assertions
+/// throw an `AssertionError`, whatever the configuration of PHP is. Fails when
+/// assertions are not compiled, as `zend.assertions` cannot be changed from
+/// `-1` once PHP has started.
+fn enable_assertions() -> Result<(), String> {
+    Ini::set("assert.active", "1", IniStage::Activate)
+        .and_then(|_| Ini::set("assert.exception", "1", IniStage::Activate))
+        .map_err(|e| format!("could not enable assertion exceptions: {}", e))?;
+    Ini::set("zend.assertions", "1", IniStage::Activate).map_err(|_| {
+        "assertions are disabled with `zend.assertions = -1`, which can only be changed in \
+         php.ini"
+            .into()
+    })
+}
+
+/// Describes an error returned while running PHP code. Must be called inside
+/// the request the error occurred in, as the exception object is read.
+fn describe_error(error: EmbedError) -> String {
+    let mut exception = match error {
+        EmbedError::ExecuteError(Some(exception)) => exception,
+        EmbedError::CatchError => return "a fatal error occurred".into(),
+        e => return format!("{:?}", e),
+    };
+
+    let class = exception.get_class_name().unwrap_or_default();
+    let mut call = |method| {
+        exception
+            .call_method_in_scope(method, &[], None)
+            .ok()
+            .and_then(|value| value.string())
+            .unwrap_or_default()
+    };
+    let message = call("getMessage");
+    let file = call("getFile");
+    let line = call("getLine");
+    format!("Uncaught {}: {} in {}:{}", class, message, file, line)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Embed;
+    use super::{Embed, EmbedError};
 
     #[test]
     fn test_run() {
@@ -226,6 +472,25 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_exec() {
+        Embed::run(|| {
+            let result = Embed::exec("$foo = 'foo'; $bar = $foo . 'bar';");
+
+            assert!(result.is_ok());
+            assert_eq!(Embed::eval("$bar;").unwrap().string().unwrap(), "foobar");
+        });
+    }
+
+    #[test]
+    fn test_exec_exception() {
+        Embed::run(|| {
+            let result = Embed::exec("throw new Exception('test');");
+
+            assert!(matches!(result, Err(EmbedError::ExecuteError(Some(_)))));
+        });
+    }
+
     #[test]
     fn test_run_script() {
         Embed::run(|| {
@@ -0,0 +1,613 @@
+//! A SAPI running requests described from Rust, used to test how extensions
+//! behave in web requests.
+
+use super::ffi::ext_php_rs_sapi_init;
+use super::{
+    assert_engine_thread, set_engine_thread, EmbedError, EngineState, ENGINE, RUN_FN_LOCK,
+};
+use crate::builders::SapiBuilder;
+use crate::ffi::{
+    _estrndup, ext_php_rs_sapi_globals, php_request_shutdown, php_request_startup,
+    sapi_header_struct, sapi_headers_struct, zend_result, zval, SAPI_HEADER_SENT_SUCCESSFULLY,
+    ZEND_RESULT_CODE_FAILURE, ZEND_RESULT_CODE_SUCCESS,
+};
+use crate::zend::{try_catch_first, ModuleEntry};
+use std::borrow::Cow;
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::io::Write;
+use std::panic::RefUnwindSafe;
+use std::ptr::{self, null_mut};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The INI directives of the SAPI. `php.ini` is ignored, and errors are
+/// written to [`TestResponse::logs`] rather than the body, so responses do
+/// not depend on the PHP installation.
+const INI_ENTRIES: &str = "\
+display_errors=0
+log_errors=1
+html_errors=0
+expose_php=0
+output_buffering=0
+implicit_flush=1
+max_execution_time=0
+date.timezone=UTC
+";
+
+/// Whether the engine was booted by [`TestSapi`]. Only read and written while
+/// holding the [`ENGINE`] lock.
+static BOOTED: AtomicBool = AtomicBool::new(false);
+
+/// Runs functions in web requests of a SAPI whose requests are described from
+/// Rust, capturing the response of each request.
+///
+/// The request method, URI, headers, cookies, body, server and environment
+/// variables are provided with a [`TestRequest`], and are seen by PHP as they
+/// would be with a web server: `$_GET`, `$_POST`, `$_COOKIE`, `$_SERVER`,
+/// `php://input` and `getenv()` are populated from the request. The start
+/// time of the request, as returned by `$_SERVER['REQUEST_TIME']` and
+/// `$_SERVER['REQUEST_TIME_FLOAT']`, is set by the request rather than read
+/// from the clock. Only the start time is fixed: `time()`, `microtime()`,
+/// `date()` and `new DateTime()` still return the current time.
+///
+/// The status, headers, output and logged errors of the request are returned
+/// in a [`TestResponse`].
+///
+/// The SAPI is started by the first request, along with the given module, and
+/// keeps running until the process exits. Like [`Embed::run_with_module`],
+/// requests run one at a time, and the module passed to the first request is
+/// the one which is loaded. Functions passed to [`Embed::run`] and
+/// [`Embed::run_with_module`] afterwards run in requests of this SAPI, without
+/// a request being described. The SAPI cannot be started once the embed SAPI
+/// has been started by [`Embed::run_with_module`] in the same process.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::embed::{Embed, TestRequest, TestSapi};
+/// # use ext_php_rs::zend::ModuleEntry;
+/// # extern "C" fn get_module() -> *mut ModuleEntry { unimplemented!() }
+///
+/// let sapi = TestSapi::new(get_module);
+/// let request = TestRequest::get("/hello?name=foo").header("Accept", "text/plain");
+/// let response = sapi
+///     .run(request, || {
+///         Embed::exec("header('Content-Type: text/plain'); echo 'Hello, ', $_GET['name'];")
+///     })
+///     .unwrap();
+///
+/// assert!(matches!(response.result, Ok(Ok(()))));
+/// assert_eq!(response.status, 200);
+/// assert_eq!(response.header("content-type"), Some("text/plain"));
+/// assert_eq!(response.text(), "Hello, foo");
+/// ```
+///
+/// [`Embed::run`]: super::Embed::run
+/// [`Embed::run_with_module`]: super::Embed::run_with_module
+#[derive(Debug, Clone, Copy)]
+pub struct TestSapi {
+    module: extern "C" fn() -> *mut ModuleEntry,
+}
+
+impl TestSapi {
+    /// Creates a SAPI loading the given module.
+    ///
+    /// # Parameters
+    ///
+    /// * `module` - The function returning the module entry, usually the
+    ///   `get_module` function generated by [`macro@crate::php_module`].
+    pub fn new(module: extern "C" fn() -> *mut ModuleEntry) -> Self {
+        Self { module }
+    }
+
+    /// Runs a function in a new request, described by the given request.
+    ///
+    /// The request is shut down before the response is returned, so the
+    /// response includes output and headers sent by shutdown functions and
+    /// destructors.
+    ///
+    /// # Parameters
+    ///
+    /// * `request` - The request to run the function in.
+    /// * `func` - The function to run, e.g. calling [`Embed::exec`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(TestResponse)` - The response to the request, which contains the
+    ///   result of the function or the bailout which occurred while running
+    ///   it
+    /// * `Err(EmbedError)` - The SAPI or the request could not be started
+    ///
+    /// # Panics
+    ///
+    /// Panics when PHP is thread-safe (ZTS) and the SAPI was started by another
+    /// thread.
+    ///
+    /// [`Embed::exec`]: super::Embed::exec
+    pub fn run<R, F: FnMut() -> R + RefUnwindSafe>(
+        &self,
+        request: TestRequest,
+        func: F,
+    ) -> Result<TestResponse<R>, EmbedError> {
+        let _guard = RUN_FN_LOCK.write();
+        let mut engine = ENGINE.lock();
+
+        match *engine {
+            EngineState::Stopped => {
+                self.boot()?;
+                BOOTED.store(true, Ordering::Relaxed);
+                set_engine_thread();
+            }
+            _ if !BOOTED.load(Ordering::Relaxed) => return Err(EmbedError::InitError),
+            EngineState::Used => unsafe { php_request_shutdown(null_mut()) },
+            _ => {}
+        }
+        assert_engine_thread();
+        *engine = EngineState::Idle;
+
+        let mut state = RequestState::new(request)?;
+        unsafe { state.install() };
+
+        if unsafe { php_request_startup() } != ZEND_RESULT_CODE_SUCCESS {
+            unsafe { RequestState::uninstall() };
+            *engine = EngineState::Failed;
+            return Err(EmbedError::InitError);
+        }
+
+        let result = try_catch_first(func).map_err(|_| EmbedError::CatchError);
+
+        unsafe {
+            php_request_shutdown(null_mut());
+            RequestState::uninstall();
+        }
+
+        Ok(TestResponse {
+            status: state.status,
+            headers: state.headers,
+            body: state.body,
+            logs: state.logs,
+            result,
+        })
+    }
+
+    /// Starts the engine with the SAPI and the module.
+    fn boot(&self) -> Result<(), EmbedError> {
+        let mut sapi = SapiBuilder::new("ext-php-rs-test", "ext-php-rs test SAPI")
+            .ub_write_function(ub_write)
+            .send_headers_function(send_headers)
+            .read_post_function(read_post)
+            .read_cookies_function(read_cookies)
+            .register_server_variables_function(register_server_variables)
+            .log_message_function(log_message)
+            .get_request_time_function(get_request_time)
+            .getenv_function(getenv)
+            .ini_entries(INI_ENTRIES)
+            .build()
+            .map_err(|_| EmbedError::InitError)?;
+        sapi.php_ini_ignore = 1;
+
+        if unsafe { ext_php_rs_sapi_init(sapi.into_raw(), (self.module)()) }
+            != ZEND_RESULT_CODE_SUCCESS
+        {
+            return Err(EmbedError::InitError);
+        }
+
+        Ok(())
+    }
+}
+
+/// A web request to run with [`TestSapi::run`].
+#[derive(Debug, Clone)]
+pub struct TestRequest {
+    method: String,
+    uri: String,
+    headers: Vec<(String, String)>,
+    cookies: Vec<(String, String)>,
+    body: Vec<u8>,
+    server: Vec<(String, String)>,
+    env: Vec<(String, String)>,
+    time: f64,
+}
+
+impl TestRequest {
+    /// Creates a request with the given method and URI. The URI may contain
+    /// a query string, which is used to populate `$_GET`.
+    ///
+    /// # Parameters
+    ///
+    /// * `method` - The request method, e.g. `GET`.
+    /// * `uri` - The request URI, e.g. `/index.php?foo=bar`.
+    pub fn new<T: Into<String>, U: Into<String>>(method: T, uri: U) -> Self {
+        Self {
+            method: method.into(),
+            uri: uri.into(),
+            headers: vec![],
+            cookies: vec![],
+            body: vec![],
+            server: vec![],
+            env: vec![],
+            time: 0.0,
+        }
+    }
+
+    /// Creates a `GET` request with the given URI.
+    ///
+    /// # Parameters
+    ///
+    /// * `uri` - The request URI.
+    pub fn get<T: Into<String>>(uri: T) -> Self {
+        Self::new("GET", uri)
+    }
+
+    /// Creates a `POST` request with the given URI.
+    ///
+    /// # Parameters
+    ///
+    /// * `uri` - The request URI.
+    pub fn post<T: Into<String>>(uri: T) -> Self {
+        Self::new("POST", uri)
+    }
+
+    /// Adds a header to the request. Headers are available in `$_SERVER` as
+    /// `HTTP_*` variables, except for `Content-Type` and `Content-Length`,
+    /// which are available as `CONTENT_TYPE` and `CONTENT_LENGTH`.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the header.
+    /// * `value` - The value of the header.
+    pub fn header<T: Into<String>, U: Into<String>>(mut self, name: T, value: U) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Adds a cookie to the request, which is sent along with the cookies of
+    /// the `Cookie` header, if any.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the cookie.
+    /// * `value` - The value of the cookie, which is not encoded.
+    pub fn cookie<T: Into<String>, U: Into<String>>(mut self, name: T, value: U) -> Self {
+        self.cookies.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the body of the request. The body is read through `php://input`,
+    /// and used to populate `$_POST` for `POST` requests with a form content
+    /// type.
+    ///
+    /// # Parameters
+    ///
+    /// * `body` - The body of the request.
+    pub fn body<T: Into<Vec<u8>>>(mut self, body: T) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Adds a variable to `$_SERVER`, replacing the variable set from the
+    /// request if there is one.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the variable.
+    /// * `value` - The value of the variable.
+    pub fn server<T: Into<String>, U: Into<String>>(mut self, name: T, value: U) -> Self {
+        self.server.push((name.into(), value.into()));
+        self
+    }
+
+    /// Adds an environment variable, returned by `getenv()` in place of the
+    /// environment variable of the process.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the variable.
+    /// * `value` - The value of the variable.
+    pub fn env<T: Into<String>, U: Into<String>>(mut self, name: T, value: U) -> Self {
+        self.env.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the time the request started at, in seconds since the Unix epoch,
+    /// as returned by `$_SERVER['REQUEST_TIME']` and
+    /// `$_SERVER['REQUEST_TIME_FLOAT']`. Defaults to the Unix epoch. Functions
+    /// reading the clock, such as `time()`, are not affected.
+    ///
+    /// # Parameters
+    ///
+    /// * `time` - The time of the request.
+    pub fn time(mut self, time: f64) -> Self {
+        self.time = time;
+        self
+    }
+
+    /// Returns the query string of the URI, if any.
+    fn query_string(&self) -> Option<&str> {
+        self.uri.split_once('?').map(|(_, query)| query)
+    }
+
+    /// Returns the values of the headers with the given name.
+    fn header_values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.headers
+            .iter()
+            .filter(move |(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the cookies of the request, as sent in the `Cookie` header.
+    fn cookie_header(&self) -> Option<String> {
+        let cookies: Vec<_> = self
+            .header_values("Cookie")
+            .map(Cow::from)
+            .chain(
+                self.cookies
+                    .iter()
+                    .map(|(name, value)| Cow::from(format!("{name}={value}"))),
+            )
+            .collect();
+        if cookies.is_empty() {
+            None
+        } else {
+            Some(cookies.join("; "))
+        }
+    }
+}
+
+/// The response to a request run with [`TestSapi::run`].
+#[derive(Debug)]
+pub struct TestResponse<R> {
+    /// The status code of the response.
+    pub status: u16,
+    /// The headers of the response, in the order they were sent.
+    pub headers: Vec<(String, String)>,
+    /// The output of the request.
+    pub body: Vec<u8>,
+    /// The messages written to the error log, including errors, warnings and
+    /// notices raised by the request.
+    pub logs: Vec<String>,
+    /// The result of the function run in the request, or
+    /// [`EmbedError::CatchError`] if a bailout occurred while running it.
+    pub result: Result<R, EmbedError>,
+}
+
+impl<R> TestResponse<R> {
+    /// Returns the value of the first header with the given name, compared
+    /// case-insensitively.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the header.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the output of the request as a string, replacing invalid UTF-8
+    /// sequences.
+    pub fn text(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.body)
+    }
+}
+
+/// The state of the request being run, pointed to by the server context of the
+/// SAPI while the request is running.
+struct RequestState {
+    request: TestRequest,
+    method: CString,
+    uri: CString,
+    query_string: Option<CString>,
+    content_type: Option<CString>,
+    cookies: Option<CString>,
+    body_read: usize,
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    logs: Vec<String>,
+}
+
+impl RequestState {
+    fn new(request: TestRequest) -> Result<Self, EmbedError> {
+        let cstr = |s: &str| CString::new(s).map_err(EmbedError::InvalidEvalString);
+        let content_type = request.header_values("Content-Type").next().map(cstr);
+        let cookies = request.cookie_header().map(|cookies| cstr(&cookies));
+
+        Ok(Self {
+            method: cstr(&request.method)?,
+            uri: cstr(&request.uri)?,
+            query_string: request.query_string().map(cstr).transpose()?,
+            content_type: content_type.transpose()?,
+            cookies: cookies.transpose()?,
+            request,
+            body_read: 0,
+            status: 200,
+            headers: vec![],
+            body: vec![],
+            logs: vec![],
+        })
+    }
+
+    /// Sets the request info of the SAPI globals from the request, before the
+    /// request is started. The state must not move until it is uninstalled.
+    unsafe fn install(&mut self) {
+        let sg = &mut *ext_php_rs_sapi_globals();
+        sg.server_context = self as *mut Self as *mut c_void;
+
+        let info = &mut sg.request_info;
+        info.request_method = self.method.as_ptr();
+        info.request_uri = self.uri.as_ptr() as *mut c_char;
+        info.query_string = self
+            .query_string
+            .as_ref()
+            .map_or(null_mut(), |query| query.as_ptr() as *mut c_char);
+        info.content_type = self
+            .content_type
+            .as_ref()
+            .map_or(ptr::null(), |content_type| content_type.as_ptr());
+        info.content_length = self.request.body.len() as _;
+        info.path_translated = null_mut();
+    }
+
+    /// Clears the request info of the SAPI globals once the request has been
+    /// shut down, as it points to the state.
+    unsafe fn uninstall() {
+        let sg = &mut *ext_php_rs_sapi_globals();
+        sg.server_context = null_mut();
+
+        let info = &mut sg.request_info;
+        info.request_method = ptr::null();
+        info.request_uri = null_mut();
+        info.query_string = null_mut();
+        info.content_type = ptr::null();
+        info.content_length = 0;
+        info.cookie_data = null_mut();
+    }
+
+    /// Returns the state of the request being run, or [`None`] when the
+    /// request was started by [`Embed`](super::Embed).
+    fn current<'a>() -> Option<&'a mut Self> {
+        // SAFETY: The server context is only set by `install`, and cleared once the
+        // request has been shut down.
+        unsafe { ((*ext_php_rs_sapi_globals()).server_context as *mut Self).as_mut() }
+    }
+}
+
+extern "C" fn ub_write(str: *const c_char, str_length: usize) -> usize {
+    let bytes = unsafe { std::slice::from_raw_parts(str as *const u8, str_length) };
+    match RequestState::current() {
+        Some(state) => state.body.extend_from_slice(bytes),
+        None => {
+            let _ = std::io::stdout().write_all(bytes);
+        }
+    }
+    str_length
+}
+
+extern "C" fn send_headers(headers: *mut sapi_headers_struct) -> c_int {
+    if let Some(state) = RequestState::current() {
+        let headers = unsafe { &mut *headers };
+        if headers.http_response_code > 0 {
+            state.status = headers.http_response_code as u16;
+        }
+        state.headers = headers
+            .headers
+            .iter::<sapi_header_struct>()
+            .map(|header| match header.as_str().split_once(':') {
+                Some((name, value)) => (name.trim().to_string(), value.trim().to_string()),
+                None => (header.as_str().trim().to_string(), String::new()),
+            })
+            .collect();
+    }
+    SAPI_HEADER_SENT_SUCCESSFULLY as c_int
+}
+
+extern "C" fn read_post(buffer: *mut c_char, count_bytes: usize) -> usize {
+    let state = match RequestState::current() {
+        Some(state) => state,
+        None => return 0,
+    };
+    let remaining = &state.request.body[state.body_read..];
+    let read = remaining.len().min(count_bytes);
+    unsafe { ptr::copy_nonoverlapping(remaining.as_ptr(), buffer as *mut u8, read) };
+    state.body_read += read;
+    read
+}
+
+extern "C" fn read_cookies() -> *mut c_char {
+    RequestState::current()
+        .and_then(|state| state.cookies.as_ref())
+        .map_or(null_mut(), |cookies| cookies.as_ptr() as *mut c_char)
+}
+
+extern "C" fn register_server_variables(vars: *mut zval) {
+    let (state, vars) = match (RequestState::current(), unsafe { vars.as_mut() }) {
+        (Some(state), Some(vars)) => (state, vars),
+        _ => return,
+    };
+    let vars = match vars.array_mut() {
+        Some(vars) => vars,
+        None => return,
+    };
+    let request = &state.request;
+
+    let mut variables = vec![
+        ("SERVER_PROTOCOL".to_string(), "HTTP/1.1".to_string()),
+        ("REQUEST_METHOD".to_string(), request.method.clone()),
+        ("REQUEST_URI".to_string(), request.uri.clone()),
+        (
+            "QUERY_STRING".to_string(),
+            request.query_string().unwrap_or_default().to_string(),
+        ),
+    ];
+    if !request.body.is_empty() {
+        variables.push(("CONTENT_LENGTH".to_string(), request.body.len().to_string()));
+    }
+    for (name, value) in &request.headers {
+        let name = name.to_ascii_uppercase().replace('-', "_");
+        let name = match name.as_str() {
+            "CONTENT_TYPE" | "CONTENT_LENGTH" => name,
+            _ => format!("HTTP_{name}"),
+        };
+        variables.push((name, value.clone()));
+    }
+    if let Some(cookies) = request.cookie_header() {
+        variables.push(("HTTP_COOKIE".to_string(), cookies));
+    }
+    variables.extend(request.server.iter().cloned());
+
+    for (name, value) in variables {
+        let _ = vars.insert(&name, value);
+    }
+}
+
+extern "C" fn log_message(message: *const c_char, _syslog_type: c_int) {
+    let message = unsafe { CStr::from_ptr(message) }
+        .to_string_lossy()
+        .into_owned();
+    match RequestState::current() {
+        Some(state) => state.logs.push(message),
+        None => eprintln!("{message}"),
+    }
+}
+
+extern "C" fn get_request_time(request_time: *mut f64) -> zend_result {
+    match RequestState::current() {
+        Some(state) => {
+            unsafe { *request_time = state.request.time };
+            ZEND_RESULT_CODE_SUCCESS
+        }
+        None => ZEND_RESULT_CODE_FAILURE,
+    }
+}
+
+extern "C" fn getenv(name: *const c_char, name_len: usize) -> *mut c_char {
+    let state = match RequestState::current() {
+        Some(state) => state,
+        None => return null_mut(),
+    };
+    let name = unsafe { std::slice::from_raw_parts(name as *const u8, name_len) };
+    let value = match state.request.env.iter().find(|(n, _)| n.as_bytes() == name) {
+        Some((_, value)) => value,
+        None => return null_mut(),
+    };
+
+    // PHP frees the value once it has been read.
+    unsafe {
+        #[cfg(php_debug)]
+        {
+            _estrndup(
+                value.as_ptr() as *const c_char,
+                value.len(),
+                ptr::null(),
+                0,
+                ptr::null(),
+                0,
+            )
+        }
+        #[cfg(not(php_debug))]
+        {
+            _estrndup(value.as_ptr() as *const c_char, value.len())
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Types and traits used to export Rust enums to PHP as native enums, which
+//! are available from PHP 8.1.
+
+use std::{
+    ffi::CString,
+    marker::PhantomData,
+    slice,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use crate::{
+    boxed::ZBox, ffi::zend_enum_get_case_cstr, rc::PhpRc, types::ZendObject, zend::ClassEntry,
+};
+
+/// The value backing a case of a backed enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Discriminant {
+    /// The case is backed by an integer.
+    Int(i64),
+    /// The case is backed by a string.
+    String(&'static str),
+}
+
+/// A case of an enum exported to PHP.
+#[derive(Debug)]
+pub struct EnumCase {
+    /// The name of the case in PHP.
+    pub name: &'static str,
+    /// The value backing the case, if the enum is a backed enum.
+    pub discriminant: Option<Discriminant>,
+}
+
+/// Implemented on Rust enums which are exported to PHP as native enums.
+/// Usually implemented by the [`php_enum`](crate::php_enum) macro.
+pub trait RegisteredEnum: Sized + 'static {
+    /// PHP name of the enum.
+    const ENUM_NAME: &'static str;
+
+    /// The cases of the enum, in declaration order.
+    const CASES: &'static [EnumCase];
+
+    /// Returns a reference to the metadata of the enum.
+    fn get_metadata() -> &'static EnumMetadata;
+
+    /// Returns the case representing the variant.
+    fn case(&self) -> &'static EnumCase;
+
+    /// Returns the variant represented by the case with the given name.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the case in PHP.
+    fn from_case(name: &str) -> Option<Self>;
+
+    /// Returns the variant represented by a case object of the enum. Returns
+    /// [`None`] if the object is not a case of this enum.
+    ///
+    /// # Parameters
+    ///
+    /// * `obj` - The case object.
+    fn from_object(obj: &ZendObject) -> Option<Self> {
+        if !std::ptr::eq(obj.ce, Self::get_metadata().ce()) {
+            return None;
+        }
+        // SAFETY: The name of the case is the first property declared on every
+        // enum, so it is stored at the start of the properties table.
+        let name = unsafe { &*obj.properties_table.as_ptr() };
+        Self::from_case(name.str()?)
+    }
+
+    /// Returns the PHP object of the case representing the variant. Each case
+    /// has a single object, so the returned object is identical (`===`) to the
+    /// case accessed from PHP. Only available during a request.
+    fn to_object(&self) -> ZBox<ZendObject> {
+        case_object(Self::get_metadata().ce(), self.case().name)
+    }
+
+    /// Returns an iterator over the cases of the enum in declaration order,
+    /// the Rust equivalent of calling `cases()` on the enum in PHP. Each item
+    /// contains the case, which holds its name and discriminant, and the PHP
+    /// object of the case.
+    ///
+    /// The case objects are only available during a request, so the iterator
+    /// must not be advanced outside of one.
+    fn iter_cases() -> Cases<Self> {
+        Cases {
+            cases: Self::CASES.iter(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// Iterator over the cases of an enum exported to PHP, returned by
+/// [`RegisteredEnum::iter_cases`].
+pub struct Cases<T: RegisteredEnum> {
+    cases: slice::Iter<'static, EnumCase>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: RegisteredEnum> Iterator for Cases<T> {
+    type Item = (&'static EnumCase, ZBox<ZendObject>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let case = self.cases.next()?;
+        Some((case, case_object(T::get_metadata().ce(), case.name)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.cases.size_hint()
+    }
+}
+
+impl<T: RegisteredEnum> ExactSizeIterator for Cases<T> {}
+
+/// Returns the object of a case of an enum. Each case has a single object,
+/// so the returned object is identical (`===`) to the case when accessed from
+/// PHP.
+///
+/// # Parameters
+///
+/// * `ce` - The class entry of the enum.
+/// * `name` - The name of the case.
+///
+/// # Panics
+///
+/// Panics if the name contains a NUL byte. The enum must have a case with the
+/// given name.
+pub(crate) fn case_object(ce: &ClassEntry, name: &str) -> ZBox<ZendObject> {
+    let name = CString::new(name).expect("Enum case names cannot contain NUL bytes.");
+    // SAFETY: The engine returns the object of the case, which is owned by the
+    // class constant of the case, so the reference count is incremented before
+    // taking ownership.
+    unsafe {
+        let obj = &mut *zend_enum_get_case_cstr(
+            ce as *const ClassEntry as *mut ClassEntry,
+            name.as_ptr(),
+        );
+        obj.inc_count();
+        ZBox::from_raw(obj)
+    }
+}
+
+/// Stores the class entry of a Rust enum which has been exported to PHP.
+/// Usually allocated statically.
+pub struct EnumMetadata {
+    ce: AtomicPtr<ClassEntry>,
+}
+
+impl EnumMetadata {
+    /// Creates a new enum metadata instance.
+    pub const fn new() -> Self {
+        Self {
+            ce: AtomicPtr::new(std::ptr::null_mut()),
+        }
+    }
+
+    /// Checks if the class entry has been stored, returning a boolean.
+    pub fn has_ce(&self) -> bool {
+        !self.ce.load(Ordering::SeqCst).is_null()
+    }
+
+    /// Retrieves a reference to the stored class entry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no class entry stored inside the enum metadata.
+    pub fn ce(&self) -> &'static ClassEntry {
+        // SAFETY: There are only two values that can be stored in the atomic ptr: null
+        // or a static reference to a class entry. On the latter case,
+        // `as_ref()` will return `None` and the function will panic.
+        unsafe { self.ce.load(Ordering::SeqCst).as_ref() }
+            .expect("Attempted to retrieve class entry before it has been stored.")
+    }
+
+    /// Stores a reference to a class entry inside the enum metadata.
+    ///
+    /// # Parameters
+    ///
+    /// * `ce` - The class entry to store.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the class entry has already been set in the enum metadata.
+    /// This function should only be called once.
+    pub fn set_ce(&self, ce: &'static mut ClassEntry) {
+        self.ce
+            .compare_exchange(
+                std::ptr::null_mut(),
+                ce,
+                Ordering::SeqCst,
+                Ordering::Relaxed,
+            )
+            .expect("Class entry has already been set");
+    }
+}
+
+impl Default for EnumMetadata {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -12,6 +12,7 @@ use crate::{
     ffi::php_error_docref,
     flags::{ClassFlags, DataType, ErrorType, ZvalTypeFlags},
     types::ZendObject,
+    zend::json::JsonError,
 };
 
 /// The main result type which is passed by the library.
@@ -69,6 +70,60 @@ pub enum Error {
     StreamWrapperRegistrationFailure,
     /// A failure occurred while unregistering the stream wrapper
     StreamWrapperUnregistrationFailure,
+    /// A failure occurred while registering a user opcode handler
+    OpcodeHandlerRegistrationFailure,
+    /// The SAPI rejected a header operation, e.g. because the headers had
+    /// already been sent
+    HeaderOperationFailure,
+    /// A failure occurred while registering an auto-global
+    AutoGlobalRegistrationFailure,
+    /// A stream could not be opened
+    StreamOpenFailure,
+    /// The given ini entry is not registered
+    IniEntryNotFound,
+    /// The given ini entry cannot be modified at the requested stage
+    IniEntryNotModifiable,
+    /// The new value of the ini entry was rejected
+    IniValueRejected,
+    /// An array could not be merged as it contains a reference to itself
+    ArrayRecursion,
+    /// An empty path was given to insert a value into nested arrays
+    EmptyPath,
+    /// Neither the mbstring nor the iconv extension is loaded
+    EncodingUnavailable,
+    /// A string could not be converted between encodings
+    EncodingConversion,
+    /// A binary string was too short to unpack a value from
+    UnpackOutOfBounds,
+    /// A value could not be encoded as JSON, or a string could not be
+    /// decoded from JSON
+    Json(JsonError),
+    /// The class could not be found, even after running the autoloaders
+    ClassNotFound(String),
+    /// The minimum of a range is greater than its maximum
+    InvalidRange,
+    /// A random engine did not return a non-empty string
+    BrokenRandomEngine,
+    /// The session could not be started, or its save handler could not be
+    /// changed
+    SessionStartFailure,
+    /// The session of the current request has not been started
+    SessionInactive,
+    /// A value which belongs to the request was stored in a persistent
+    /// hashtable, or a persistent hashtable was merged into a request one
+    NotPersistent,
+    /// An enum case has the same name or value as another case, or its value
+    /// does not match the backing type of the enum
+    InvalidEnumCase(String),
+    /// The value does not back any case of the enum. The enum carries the name
+    /// of the enum and the value
+    InvalidEnumValue(&'static str, String),
+    /// The object is not a case of the enum. The enum carries the name of the
+    /// enum
+    InvalidEnumObject(&'static str),
+    /// The date extension returned a time zone offset which could not be
+    /// parsed. The enum carries the formatted offset
+    InvalidTimeZoneOffset(String),
 }
 
 impl Display for Error {
@@ -112,6 +167,59 @@ impl Display for Error {
                     "A failure occurred while unregistering the stream wrapper"
                 )
             }
+            Error::OpcodeHandlerRegistrationFailure => {
+                write!(
+                    f,
+                    "A failure occurred while registering the user opcode handler"
+                )
+            }
+            Error::HeaderOperationFailure => {
+                write!(f, "The SAPI rejected the header operation")
+            }
+            Error::AutoGlobalRegistrationFailure => {
+                write!(f, "A failure occurred while registering the auto-global")
+            }
+            Error::StreamOpenFailure => write!(f, "The stream could not be opened"),
+            Error::IniEntryNotFound => write!(f, "The ini entry is not registered"),
+            Error::IniEntryNotModifiable => {
+                write!(f, "The ini entry cannot be modified at this stage")
+            }
+            Error::IniValueRejected => write!(f, "The new value of the ini entry was rejected"),
+            Error::ArrayRecursion => write!(f, "Recursion was detected while merging arrays"),
+            Error::EmptyPath => write!(f, "The path to insert the value at is empty"),
+            Error::EncodingUnavailable => {
+                write!(f, "Neither the mbstring nor the iconv extension is loaded")
+            }
+            Error::EncodingConversion => {
+                write!(f, "The string could not be converted between the encodings")
+            }
+            Error::UnpackOutOfBounds => {
+                write!(f, "The binary string is too short to unpack the value")
+            }
+            Error::Json(e) => write!(f, "{e}"),
+            Error::ClassNotFound(name) => write!(f, "Class \"{name}\" not found"),
+            Error::InvalidRange => {
+                write!(f, "The minimum must be less than or equal to the maximum")
+            }
+            Error::BrokenRandomEngine => {
+                write!(f, "A random engine must return a non-empty string")
+            }
+            Error::SessionStartFailure => write!(f, "The session could not be started"),
+            Error::SessionInactive => write!(f, "The session has not been started"),
+            Error::NotPersistent => {
+                write!(
+                    f,
+                    "Request values cannot be stored in persistent hashtables"
+                )
+            }
+            Error::InvalidEnumCase(name) => write!(f, "Invalid enum case `{name}`"),
+            Error::InvalidEnumValue(name, value) => {
+                write!(f, "{value} is not a valid backing value for enum {name}")
+            }
+            Error::InvalidEnumObject(name) => write!(f, "Object is not a case of enum {name}"),
+            Error::InvalidTimeZoneOffset(offset) => {
+                write!(f, "Invalid time zone offset `{offset}`")
+            }
         }
     }
 }
@@ -126,6 +234,9 @@ impl From<NulError> for Error {
 
 impl From<Error> for PhpException {
     fn from(err: Error) -> Self {
+        if let Error::Json(e) = err {
+            return e.into();
+        }
         Self::default(err.to_string())
     }
 }
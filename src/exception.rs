@@ -8,6 +8,7 @@ use crate::{
     ffi::zend_throw_exception_ex,
     ffi::zend_throw_exception_object,
     flags::ClassFlags,
+    test::ThrownException,
     types::Zval,
     zend::{ce, ClassEntry},
 };
@@ -26,7 +27,7 @@ pub type PhpResult<T = ()> = std::result::Result<T, PhpException>;
 pub struct PhpException {
     message: String,
     code: i32,
-    ex: &'static ClassEntry,
+    ex: Option<&'static ClassEntry>,
     object: Option<Zval>,
 }
 
@@ -42,7 +43,7 @@ impl PhpException {
         Self {
             message,
             code,
-            ex,
+            ex: Some(ex),
             object: None,
         }
     }
@@ -55,7 +56,14 @@ impl PhpException {
     ///
     /// * `message` - Message to contain in the exception.
     pub fn default(message: String) -> Self {
-        Self::new(message, 0, ce::exception())
+        // The class is looked up when the exception is thrown, so exceptions
+        // can be created without a running engine.
+        Self {
+            message,
+            code: 0,
+            ex: None,
+            object: None,
+        }
     }
 
     /// Creates an instance of an exception from a PHP class type and a message.
@@ -82,9 +90,38 @@ impl PhpException {
     /// Throws the exception, returning nothing inside a result if successful
     /// and an error otherwise.
     pub fn throw(self) -> Result<()> {
+        if crate::test::capture(|| self.to_thrown()) {
+            return Ok(());
+        }
+
         match self.object {
             Some(object) => throw_object(object),
-            None => throw_with_code(self.ex, self.code, &self.message),
+            None => throw_with_code(
+                self.ex.unwrap_or_else(ce::exception),
+                self.code,
+                &self.message,
+            ),
+        }
+    }
+
+    /// Returns the exception as recorded by
+    /// [`test::call_function`](crate::test::call_function).
+    fn to_thrown(&self) -> ThrownException {
+        let object = self.object.as_ref().and_then(Zval::object);
+        let class = match (object, self.ex) {
+            (Some(object), _) => object.get_class_name().ok(),
+            (None, Some(ex)) => ex.name().map(String::from),
+            (None, None) => None,
+        };
+        let message = match object {
+            Some(object) => object.get_property("message").unwrap_or_default(),
+            None => self.message.clone(),
+        };
+
+        ThrownException {
+            class: class.unwrap_or_else(|| "Exception".into()),
+            message,
+            code: self.code,
         }
     }
 }
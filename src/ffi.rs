@@ -30,6 +30,13 @@ extern "C" {
     pub fn ext_php_rs_sapi_globals() -> *mut sapi_globals_struct;
     pub fn ext_php_rs_file_globals() -> *mut php_file_globals;
     pub fn ext_php_rs_sapi_module() -> *mut sapi_module_struct;
+    pub fn ext_php_rs_json_error_code() -> php_json_error_code;
+    pub fn ext_php_rs_random_bytes(bytes: *mut c_void, size: usize) -> zend_result;
+    pub fn ext_php_rs_random_int(
+        min: zend_long,
+        max: zend_long,
+        result: *mut zend_long,
+    ) -> zend_result;
     pub fn ext_php_rs_zend_try_catch(
         func: unsafe extern "C" fn(*const c_void) -> *const c_void,
         ctx: *const c_void,
@@ -0,0 +1,259 @@
+//! Classes exposing Rust [`bitflags`] types to PHP as sets of flags.
+//!
+//! PHP enums cannot be combined, so extensions which accept a set of options
+//! usually expose them as integer class constants which are combined with
+//! `|`. A [`FlagSet`] registers such a class from a type declared with the
+//! [`bitflags::bitflags`] macro, with a constant for each named flag. Instances
+//! of the class wrap an integer, and provide `has()`, `with()` and `without()`
+//! methods which accept either an integer or another instance.
+//!
+//! ```php
+//! $perms = new Permissions(Permissions::READ | Permissions::WRITE);
+//! $perms->has(Permissions::READ); // true
+//! $perms = $perms->without(Permissions::WRITE);
+//! $perms->value(); // 1
+//! ```
+
+use std::{convert::TryFrom, marker::PhantomData};
+
+use bitflags::Flags;
+
+use crate::{
+    args::Arg,
+    boxed::ZBox,
+    builders::{ClassBuilder, FunctionBuilder},
+    convert::IntoZval,
+    error::{Error, Result},
+    exception::{PhpException, PhpResult},
+    flags::{DataType, MethodFlags, PropertyFlags},
+    types::{ZendObject, Zval},
+    zend::{ce, ClassEntry, ExecuteData},
+    zend_fastcall,
+};
+
+/// Name of the property holding the bits of a flag set.
+const VALUE: &str = "value";
+
+/// Builds PHP classes representing sets of the flags in `F`.
+///
+/// # Example
+///
+/// ```no_run
+/// use bitflags::bitflags;
+/// use ext_php_rs::{flag_set::FlagSet, prelude::*};
+///
+/// bitflags! {
+///     pub struct Permissions: u32 {
+///         const READ = 0b001;
+///         const WRITE = 0b010;
+///         const EXECUTE = 0b100;
+///     }
+/// }
+///
+/// #[php_startup]
+/// pub fn startup() {
+///     FlagSet::<Permissions>::builder("Permissions")
+///         .and_then(|class| class.build())
+///         .expect("failed to build `Permissions` class");
+/// }
+/// # fn main() {}
+/// ```
+pub struct FlagSet<F>(PhantomData<F>);
+
+impl<F> FlagSet<F>
+where
+    F: Flags,
+    i64: TryFrom<F::Bits>,
+    F::Bits: TryFrom<i64>,
+{
+    /// Returns a builder for a class representing sets of the flags in `F`.
+    /// The class can be customised further, e.g. to implement interfaces,
+    /// before it is built.
+    ///
+    /// Returns an error if the bits of a flag do not fit in a PHP integer.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the class.
+    pub fn builder(name: &str) -> Result<ClassBuilder> {
+        let mut class = ClassBuilder::new(name).property(VALUE, 0, PropertyFlags::Protected);
+        for flag in F::FLAGS.iter().filter(|flag| !flag.name().is_empty()) {
+            class = class.constant(flag.name(), Self::to_long(flag.value())?)?;
+        }
+
+        let flags_arg = || Arg::new("flags", DataType::Mixed);
+        let methods = vec![
+            FunctionBuilder::new("__construct", Self::construct)
+                .not_required()
+                .arg(Arg::new("value", DataType::Long).default("0")),
+            FunctionBuilder::new("has", Self::has)
+                .arg(flags_arg())
+                .returns(DataType::Bool, false, false),
+            FunctionBuilder::new("with", Self::with)
+                .arg(flags_arg())
+                .returns(DataType::Object(None), false, false),
+            FunctionBuilder::new("without", Self::without)
+                .arg(flags_arg())
+                .returns(DataType::Object(None), false, false),
+            FunctionBuilder::new("value", Self::value).returns(DataType::Long, false, false),
+        ];
+
+        methods.into_iter().try_fold(class, |class, method| {
+            Ok(class.method(method.build()?, MethodFlags::Public))
+        })
+    }
+
+    /// Reads a set of flags from a zval, which may be an integer or an
+    /// instance of the given flag set class. Returns [`None`] if the value is
+    /// of another type or contains unknown bits.
+    ///
+    /// # Parameters
+    ///
+    /// * `zv` - The zval to read the flags from.
+    /// * `ce` - The class built by [`FlagSet::builder`].
+    pub fn from_zval(zv: &Zval, ce: &ClassEntry) -> Option<F> {
+        let zv = zv.dereference();
+        if let Some(value) = zv.long() {
+            return Self::from_long(value);
+        }
+
+        let obj = zv.object().filter(|obj| obj.instance_of(ce))?;
+        Self::from_long(obj.declared_property(VALUE)?.long()?)
+    }
+
+    /// Creates an instance of the given flag set class holding `flags`.
+    ///
+    /// # Parameters
+    ///
+    /// * `flags` - The flags held by the instance.
+    /// * `ce` - The class built by [`FlagSet::builder`].
+    pub fn into_object(flags: F, ce: &ClassEntry) -> Result<ZBox<ZendObject>> {
+        let mut obj = ZendObject::new(ce);
+        obj.declared_property_mut(VALUE)
+            .ok_or(Error::InvalidProperty)?
+            .set_long(Self::to_long(&flags)?);
+        Ok(obj)
+    }
+
+    fn to_long(flags: &F) -> Result<i64> {
+        i64::try_from(flags.bits()).map_err(|_| Error::IntegerOverflow)
+    }
+
+    fn from_long(value: i64) -> Option<F> {
+        F::from_bits(F::Bits::try_from(value).ok()?)
+    }
+
+    /// Parses the arguments of a flag set method and calls `f` with the
+    /// instance, the class declaring the method and the parsed flags, writing
+    /// the result into `ret`.
+    fn dispatch<R: IntoZval>(
+        ex: &mut ExecuteData,
+        ret: &mut Zval,
+        f: impl FnOnce(&mut ZendObject, &ClassEntry, Option<F>) -> PhpResult<R>,
+    ) {
+        // SAFETY: Class entries outlive the calls to their methods, so the class can be
+        // held while the arguments are parsed.
+        let ce = match ex.function().and_then(|func| func.scope()) {
+            Some(ce) => unsafe { &*(ce as *const ClassEntry) },
+            None => return,
+        };
+        let mut flags = Arg::new("flags", DataType::Mixed);
+        let (parser, this) = ex.parser_object();
+        if parser.arg(&mut flags).parse().is_err() {
+            return;
+        }
+        let this = match this {
+            Some(this) => this,
+            None => return,
+        };
+
+        let result = match flags.zval() {
+            Some(zv) => match Self::from_zval(zv, ce) {
+                Some(flags) => f(this, ce, Some(flags)),
+                None => Err(Self::invalid_flags()),
+            },
+            None => f(this, ce, None),
+        };
+
+        // The implementation of `IntoZval` on `Result` throws the exception for us.
+        let _ = result.set_zval(ret, false);
+    }
+
+    fn invalid_flags() -> PhpException {
+        PhpException::new("Invalid flags given.".into(), 0, ce::value_error())
+    }
+
+    fn current(this: &ZendObject) -> PhpResult<F> {
+        this.declared_property(VALUE)
+            .and_then(Zval::long)
+            .and_then(Self::from_long)
+            .ok_or_else(Self::invalid_flags)
+    }
+
+    zend_fastcall! {
+        extern "C" fn construct(ex: &mut ExecuteData, ret: &mut Zval) {
+            let mut value = Arg::new("value", DataType::Long);
+            let (parser, this) = ex.parser_object();
+            if parser.not_required().arg(&mut value).parse().is_err() {
+                return;
+            }
+
+            let result = value
+                .consume::<Option<i64>>()
+                .ok()
+                .flatten()
+                .map_or(Some(F::empty()), Self::from_long)
+                .ok_or_else(Self::invalid_flags)
+                .and_then(|flags| Ok(Self::to_long(&flags)?));
+            match (result, this) {
+                (Ok(bits), Some(this)) => {
+                    if let Some(value) = this.declared_property_mut(VALUE) {
+                        value.set_long(bits);
+                    }
+                }
+                (Err(e), _) => {
+                    let _ = Err::<(), _>(e).set_zval(ret, false);
+                }
+                (Ok(_), None) => {}
+            }
+        }
+    }
+
+    zend_fastcall! {
+        extern "C" fn has(ex: &mut ExecuteData, ret: &mut Zval) {
+            Self::dispatch(ex, ret, |this, _, flags| {
+                Ok(Self::current(this)?.contains(flags.unwrap_or_else(F::empty)))
+            })
+        }
+    }
+
+    zend_fastcall! {
+        extern "C" fn with(ex: &mut ExecuteData, ret: &mut Zval) {
+            Self::dispatch(ex, ret, |this, _, flags| {
+                let flags = Self::current(this)?.union(flags.unwrap_or_else(F::empty));
+                Ok(Self::into_object(flags, this.get_class_entry())?)
+            })
+        }
+    }
+
+    zend_fastcall! {
+        extern "C" fn without(ex: &mut ExecuteData, ret: &mut Zval) {
+            Self::dispatch(ex, ret, |this, _, flags| {
+                let flags = Self::current(this)?.difference(flags.unwrap_or_else(F::empty));
+                Ok(Self::into_object(flags, this.get_class_entry())?)
+            })
+        }
+    }
+
+    zend_fastcall! {
+        extern "C" fn value(ex: &mut ExecuteData, ret: &mut Zval) {
+            let this = match ex.get_self() {
+                Some(this) => this,
+                None => return,
+            };
+            let _ = Self::current(this)
+                .and_then(|flags| Ok(Self::to_long(&flags)?))
+                .set_zval(ret, false);
+        }
+    }
+}
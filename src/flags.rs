@@ -5,13 +5,18 @@ use bitflags::bitflags;
 #[cfg(not(php82))]
 use crate::ffi::ZEND_ACC_REUSE_GET_ITERATOR;
 use crate::ffi::{
-    CONST_CS, CONST_DEPRECATED, CONST_NO_FILE_CACHE, CONST_PERSISTENT, E_COMPILE_ERROR,
+    _IS_BOOL, CONST_CS, CONST_DEPRECATED, CONST_NO_FILE_CACHE, CONST_PERSISTENT, E_COMPILE_ERROR,
     E_COMPILE_WARNING, E_CORE_ERROR, E_CORE_WARNING, E_DEPRECATED, E_ERROR, E_NOTICE, E_PARSE,
     E_RECOVERABLE_ERROR, E_STRICT, E_USER_DEPRECATED, E_USER_ERROR, E_USER_NOTICE, E_USER_WARNING,
     E_WARNING, IS_ARRAY, IS_CALLABLE, IS_CONSTANT_AST, IS_DOUBLE, IS_FALSE, IS_INDIRECT,
     IS_ITERABLE, IS_LONG, IS_MIXED, IS_NULL, IS_OBJECT, IS_PTR, IS_REFERENCE, IS_RESOURCE,
     IS_STRING, IS_TRUE, IS_TYPE_COLLECTABLE, IS_TYPE_REFCOUNTED, IS_UNDEF, IS_VOID, PHP_INI_ALL,
-    PHP_INI_PERDIR, PHP_INI_SYSTEM, PHP_INI_USER, ZEND_ACC_ABSTRACT, ZEND_ACC_ANON_CLASS,
+    PHP_INI_PERDIR, PHP_INI_SYSTEM, PHP_INI_USER, PHP_JSON_BIGINT_AS_STRING, PHP_JSON_FORCE_OBJECT,
+    PHP_JSON_HEX_AMP, PHP_JSON_HEX_APOS, PHP_JSON_HEX_QUOT, PHP_JSON_HEX_TAG,
+    PHP_JSON_INVALID_UTF8_IGNORE, PHP_JSON_INVALID_UTF8_SUBSTITUTE, PHP_JSON_NUMERIC_CHECK,
+    PHP_JSON_OBJECT_AS_ARRAY, PHP_JSON_PARTIAL_OUTPUT_ON_ERROR, PHP_JSON_PRESERVE_ZERO_FRACTION,
+    PHP_JSON_PRETTY_PRINT, PHP_JSON_THROW_ON_ERROR, PHP_JSON_UNESCAPED_LINE_TERMINATORS,
+    PHP_JSON_UNESCAPED_SLASHES, PHP_JSON_UNESCAPED_UNICODE, ZEND_ACC_ABSTRACT, ZEND_ACC_ANON_CLASS,
     ZEND_ACC_CALL_VIA_TRAMPOLINE, ZEND_ACC_CHANGED, ZEND_ACC_CLOSURE, ZEND_ACC_CONSTANTS_UPDATED,
     ZEND_ACC_CTOR, ZEND_ACC_DEPRECATED, ZEND_ACC_DONE_PASS_TWO, ZEND_ACC_EARLY_BINDING,
     ZEND_ACC_FAKE_CLOSURE, ZEND_ACC_FINAL, ZEND_ACC_GENERATOR, ZEND_ACC_HAS_FINALLY_BLOCK,
@@ -23,7 +28,6 @@ use crate::ffi::{
     ZEND_ACC_TOP_LEVEL, ZEND_ACC_TRAIT, ZEND_ACC_TRAIT_CLONE, ZEND_ACC_UNRESOLVED_VARIANCE,
     ZEND_ACC_USES_THIS, ZEND_ACC_USE_GUARDS, ZEND_ACC_VARIADIC, ZEND_EVAL_CODE,
     ZEND_HAS_STATIC_IN_METHODS, ZEND_INTERNAL_FUNCTION, ZEND_USER_FUNCTION, Z_TYPE_FLAGS_SHIFT,
-    _IS_BOOL,
 };
 
 use std::{convert::TryFrom, fmt::Display};
@@ -207,6 +211,34 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Options for encoding and decoding JSON, the same as the `JSON_*`
+    /// constants passed to `json_encode()` and `json_decode()`.
+    ///
+    /// Some encoding and decoding options share a value, e.g. `HexTag` and
+    /// `ObjectAsArray`, as each only has a meaning in one direction.
+    #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+    pub struct JsonFlags: u32 {
+        const HexTag = PHP_JSON_HEX_TAG;
+        const HexAmp = PHP_JSON_HEX_AMP;
+        const HexApos = PHP_JSON_HEX_APOS;
+        const HexQuot = PHP_JSON_HEX_QUOT;
+        const ForceObject = PHP_JSON_FORCE_OBJECT;
+        const NumericCheck = PHP_JSON_NUMERIC_CHECK;
+        const UnescapedSlashes = PHP_JSON_UNESCAPED_SLASHES;
+        const PrettyPrint = PHP_JSON_PRETTY_PRINT;
+        const UnescapedUnicode = PHP_JSON_UNESCAPED_UNICODE;
+        const PartialOutputOnError = PHP_JSON_PARTIAL_OUTPUT_ON_ERROR;
+        const PreserveZeroFraction = PHP_JSON_PRESERVE_ZERO_FRACTION;
+        const UnescapedLineTerminators = PHP_JSON_UNESCAPED_LINE_TERMINATORS;
+        const ObjectAsArray = PHP_JSON_OBJECT_AS_ARRAY;
+        const BigintAsString = PHP_JSON_BIGINT_AS_STRING;
+        const InvalidUtf8Ignore = PHP_JSON_INVALID_UTF8_IGNORE;
+        const InvalidUtf8Substitute = PHP_JSON_INVALID_UTF8_SUBSTITUTE;
+        const ThrowOnError = PHP_JSON_THROW_ON_ERROR;
+    }
+}
+
 #[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 pub enum FunctionType {
     Internal,
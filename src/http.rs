@@ -0,0 +1,353 @@
+//! Conversions between the request and response types of the [`http`] crate
+//! and PSR-7 message objects.
+//!
+//! PSR-7 objects are used through the methods of `RequestInterface`,
+//! `ResponseInterface` and `StreamInterface`, so any implementation works
+//! without the interfaces being loaded. Objects are created through PSR-17
+//! factories, as PSR-7 does not specify how messages are constructed:
+//!
+//! * Messages are read with [`request_from_psr7`] and [`response_from_psr7`].
+//!   The body stream is read in full with `__toString()`.
+//! * Messages are created with [`request_into_psr7`] and
+//!   [`response_into_psr7`], given a request or response factory and a stream
+//!   factory.
+//!
+//! ```no_run
+//! use ext_php_rs::{prelude::*, types::{ZendObject, Zval}};
+//!
+//! #[php_function]
+//! pub fn handle(
+//!     request: &mut ZendObject,
+//!     response_factory: &mut ZendObject,
+//!     stream_factory: &mut ZendObject,
+//! ) -> PhpResult<Zval> {
+//!     let request = ext_php_rs::http::request_from_psr7(request)?;
+//!     let response = http::Response::builder()
+//!         .header("content-type", "text/plain")
+//!         .body(format!("{} {}", request.method(), request.uri()))
+//!         .map_err(|e| e.to_string())?;
+//!     Ok(ext_php_rs::http::response_into_psr7(
+//!         response,
+//!         response_factory,
+//!         stream_factory,
+//!     )?)
+//! }
+//! # fn main() {}
+//! ```
+
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display},
+};
+
+use ::http::{
+    header::{HeaderName, HeaderValue},
+    HeaderMap, Method, Request, Response, StatusCode, Uri, Version,
+};
+
+use crate::{
+    boxed::ZBox,
+    convert::IntoZval,
+    exception::PhpException,
+    types::{ZendObject, Zval},
+};
+
+/// An error which occurred while converting a PSR-7 message.
+///
+/// Exceptions thrown by the methods of PSR-7 objects are kept, and rethrown
+/// when the error is converted into a [`PhpException`].
+#[derive(Debug)]
+pub struct Error {
+    message: String,
+    exception: Option<ZBox<ZendObject>>,
+}
+
+/// Result type returned by the PSR-7 conversions.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            exception: None,
+        }
+    }
+
+    /// Returns the exception thrown by a method of a PSR-7 object, if the
+    /// error was caused by one.
+    pub fn exception(&self) -> Option<&ZendObject> {
+        self.exception.as_deref()
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<crate::error::Error> for Error {
+    fn from(err: crate::error::Error) -> Self {
+        let message = err.to_string();
+        match err {
+            crate::error::Error::Exception(exception) => Self {
+                message,
+                exception: Some(exception),
+            },
+            _ => Self::new(message),
+        }
+    }
+}
+
+impl From<::http::Error> for Error {
+    fn from(err: ::http::Error) -> Self {
+        Self::new(err.to_string())
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        let mut exception = Self::default(err.message);
+        exception.set_object(err.exception.and_then(|e| e.into_zval(false).ok()));
+        exception
+    }
+}
+
+/// Reads a PSR-7 `RequestInterface` object into a request.
+///
+/// # Parameters
+///
+/// * `request` - The PSR-7 request.
+pub fn request_from_psr7(request: &mut ZendObject) -> Result<Request<Vec<u8>>> {
+    let method = call(request, "getMethod", &[])?;
+    let method = Method::from_bytes(bytes(&method, "getMethod")?)
+        .map_err(|e| Error::new(format!("Invalid request method: {}", e)))?;
+    let mut uri = call(request, "getUri", &[])?;
+    let uri = call(object(&mut uri)?, "__toString", &[])?;
+    let uri = Uri::try_from(bytes(&uri, "__toString")?)
+        .map_err(|e| Error::new(format!("Invalid request URI: {}", e)))?;
+
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .version(version(request)?);
+    if let Some(headers) = builder.headers_mut() {
+        *headers = headers_from_psr7(request)?;
+    }
+    Ok(builder.body(body_from_psr7(request)?)?)
+}
+
+/// Reads a PSR-7 `ResponseInterface` object into a response.
+///
+/// # Parameters
+///
+/// * `response` - The PSR-7 response.
+pub fn response_from_psr7(response: &mut ZendObject) -> Result<Response<Vec<u8>>> {
+    let status = call(response, "getStatusCode", &[])?;
+    let status = status
+        .long()
+        .and_then(|status| u16::try_from(status).ok())
+        .and_then(|status| StatusCode::from_u16(status).ok())
+        .ok_or_else(|| Error::new(format!("Invalid response status code: {:?}", status)))?;
+
+    let mut builder = Response::builder()
+        .status(status)
+        .version(version(response)?);
+    if let Some(headers) = builder.headers_mut() {
+        *headers = headers_from_psr7(response)?;
+    }
+    Ok(builder.body(body_from_psr7(response)?)?)
+}
+
+/// Creates a PSR-7 request from a request.
+///
+/// # Parameters
+///
+/// * `request` - The request to convert.
+/// * `request_factory` - A PSR-17 `RequestFactoryInterface` creating the
+///   request.
+/// * `stream_factory` - A PSR-17 `StreamFactoryInterface` creating the body
+///   of the request.
+pub fn request_into_psr7<B: AsRef<[u8]>>(
+    request: Request<B>,
+    request_factory: &mut ZendObject,
+    stream_factory: &mut ZendObject,
+) -> Result<Zval> {
+    let (parts, body) = request.into_parts();
+    let message = call(
+        request_factory,
+        "createRequest",
+        &[
+            parts.method.as_str().into_zval(false)?,
+            parts.uri.to_string().into_zval(false)?,
+        ],
+    )?;
+    message_into_psr7(
+        message,
+        parts.version,
+        &parts.headers,
+        body.as_ref(),
+        stream_factory,
+    )
+}
+
+/// Creates a PSR-7 response from a response.
+///
+/// # Parameters
+///
+/// * `response` - The response to convert.
+/// * `response_factory` - A PSR-17 `ResponseFactoryInterface` creating the
+///   response.
+/// * `stream_factory` - A PSR-17 `StreamFactoryInterface` creating the body
+///   of the response.
+pub fn response_into_psr7<B: AsRef<[u8]>>(
+    response: Response<B>,
+    response_factory: &mut ZendObject,
+    stream_factory: &mut ZendObject,
+) -> Result<Zval> {
+    let (parts, body) = response.into_parts();
+    let message = call(
+        response_factory,
+        "createResponse",
+        &[
+            i64::from(parts.status.as_u16()).into_zval(false)?,
+            parts
+                .status
+                .canonical_reason()
+                .unwrap_or_default()
+                .into_zval(false)?,
+        ],
+    )?;
+    message_into_psr7(
+        message,
+        parts.version,
+        &parts.headers,
+        body.as_ref(),
+        stream_factory,
+    )
+}
+
+/// Sets the protocol version, headers and body of a PSR-7 message. Messages
+/// are immutable, so each call returns a new message.
+fn message_into_psr7(
+    mut message: Zval,
+    version: Version,
+    headers: &HeaderMap,
+    body: &[u8],
+    stream_factory: &mut ZendObject,
+) -> Result<Zval> {
+    let version = match version {
+        Version::HTTP_09 => "0.9",
+        Version::HTTP_10 => "1.0",
+        Version::HTTP_11 => "1.1",
+        Version::HTTP_2 => "2",
+        Version::HTTP_3 => "3",
+        _ => {
+            return Err(Error::new(format!(
+                "Unsupported HTTP version: {:?}",
+                version
+            )))
+        }
+    };
+    message = call(
+        object(&mut message)?,
+        "withProtocolVersion",
+        &[version.into_zval(false)?],
+    )?;
+
+    for (name, value) in headers {
+        let mut value_zv = Zval::new();
+        value_zv.set_bytes(value.as_bytes(), false);
+        message = call(
+            object(&mut message)?,
+            "withAddedHeader",
+            &[name.as_str().into_zval(false)?, value_zv],
+        )?;
+    }
+
+    let mut body_zv = Zval::new();
+    body_zv.set_bytes(body, false);
+    let stream = call(stream_factory, "createStream", &[body_zv])?;
+    call(object(&mut message)?, "withBody", &[stream])
+}
+
+/// Reads the protocol version of a PSR-7 message.
+fn version(message: &mut ZendObject) -> Result<Version> {
+    let version = call(message, "getProtocolVersion", &[])?;
+    Ok(match bytes(&version, "getProtocolVersion")? {
+        b"0.9" => Version::HTTP_09,
+        b"1.0" => Version::HTTP_10,
+        b"1.1" => Version::HTTP_11,
+        b"2" | b"2.0" => Version::HTTP_2,
+        b"3" | b"3.0" => Version::HTTP_3,
+        version => {
+            return Err(Error::new(format!(
+                "Unsupported HTTP version: {}",
+                String::from_utf8_lossy(version)
+            )))
+        }
+    })
+}
+
+/// Reads the headers of a PSR-7 message, which are returned as an array of
+/// the values of each header keyed by the name of the header.
+fn headers_from_psr7(message: &mut ZendObject) -> Result<HeaderMap> {
+    let headers = call(message, "getHeaders", &[])?;
+    let headers = headers
+        .array()
+        .ok_or_else(|| Error::new("`getHeaders()` did not return an array"))?;
+
+    let mut map = HeaderMap::new();
+    for element in headers.raw_elements() {
+        // Numeric names are stored as integer keys by PHP.
+        let name = match element.key {
+            Some(key) => HeaderName::from_bytes(key.as_bytes()),
+            None => HeaderName::from_bytes((element.h as i64).to_string().as_bytes()),
+        }
+        .map_err(|e| Error::new(format!("Invalid header name: {}", e)))?;
+        let values = element.val.array().ok_or_else(|| {
+            Error::new(format!(
+                "Values of the `{}` header are not an array",
+                name.as_str()
+            ))
+        })?;
+        for value in values.values() {
+            let value = HeaderValue::from_bytes(bytes(value, "getHeaders")?).map_err(|e| {
+                Error::new(format!("Invalid value of the `{}` header: {}", name, e))
+            })?;
+            map.append(name.clone(), value);
+        }
+    }
+    Ok(map)
+}
+
+/// Reads the body stream of a PSR-7 message in full.
+fn body_from_psr7(message: &mut ZendObject) -> Result<Vec<u8>> {
+    let mut body = call(message, "getBody", &[])?;
+    let body = call(object(&mut body)?, "__toString", &[])?;
+    Ok(bytes(&body, "__toString")?.to_vec())
+}
+
+/// Calls a public method of a PSR-7 object.
+fn call(obj: &mut ZendObject, name: &str, params: &[Zval]) -> Result<Zval> {
+    obj.call_method_in_scope(name, params, None).map_err(|err| {
+        let mut err = Error::from(err);
+        err.message = format!("Failed to call `{}()`: {}", name, err.message);
+        err
+    })
+}
+
+/// Returns the object held by a value returned by a PSR-7 method.
+fn object(zv: &mut Zval) -> Result<&mut ZendObject> {
+    let ty = zv.get_type();
+    zv.object_mut()
+        .ok_or_else(|| Error::new(format!("Expected an object, got {}", ty)))
+}
+
+/// Returns the string returned by a method.
+fn bytes<'a>(zv: &'a Zval, method: &str) -> Result<&'a [u8]> {
+    zv.bytes()
+        .ok_or_else(|| Error::new(format!("`{}()` did not return a string", method)))
+}
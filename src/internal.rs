@@ -8,4 +8,10 @@
 pub fn ext_php_rs_startup() {
     #[cfg(feature = "closure")]
     crate::closure::Closure::build();
+    #[cfg(feature = "channel")]
+    crate::channel::Channel::build();
+    #[cfg(feature = "psr3")]
+    crate::psr3::Logger::build();
+    #[cfg(feature = "session")]
+    crate::session::SessionHandler::build();
 }
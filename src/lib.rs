@@ -15,10 +15,14 @@ pub mod convert;
 pub mod error;
 pub mod exception;
 pub mod ffi;
+pub mod flag_set;
 pub mod flags;
 #[macro_use]
 pub mod macros;
 pub mod boxed;
+#[cfg(any(docs, feature = "channel"))]
+#[cfg_attr(docs, doc(cfg(feature = "channel")))]
+pub mod channel;
 pub mod class;
 #[cfg(any(docs, feature = "closure"))]
 #[cfg_attr(docs, doc(cfg(feature = "closure")))]
@@ -27,10 +31,34 @@ pub mod constant;
 pub mod describe;
 #[cfg(feature = "embed")]
 pub mod embed;
+#[cfg(any(php81, php82))]
+pub mod enum_;
+#[cfg(feature = "http")]
+#[cfg_attr(docs, doc(cfg(feature = "http")))]
+pub mod http;
 #[doc(hidden)]
 pub mod internal;
+#[cfg(any(feature = "log", feature = "tracing", feature = "psr3"))]
+#[cfg_attr(
+    docs,
+    doc(cfg(any(feature = "log", feature = "tracing", feature = "psr3")))
+)]
+pub mod logging;
+pub mod pack;
+pub mod persistent;
 pub mod props;
+#[cfg(any(docs, feature = "psr3"))]
+#[cfg_attr(docs, doc(cfg(feature = "psr3")))]
+pub mod psr3;
 pub mod rc;
+#[cfg(feature = "serde")]
+#[cfg_attr(docs, doc(cfg(feature = "serde")))]
+pub mod serde;
+#[cfg(any(docs, feature = "session"))]
+#[cfg_attr(docs, doc(cfg(feature = "session")))]
+pub mod session;
+pub mod task;
+pub mod test;
 pub mod types;
 pub mod zend;
 
@@ -39,12 +67,19 @@ pub mod zend;
 pub mod prelude {
 
     pub use crate::builders::ModuleBuilder;
+    #[cfg(any(docs, feature = "channel"))]
+    #[cfg_attr(docs, doc(cfg(feature = "channel")))]
+    pub use crate::channel::Channel;
     #[cfg(any(docs, feature = "closure"))]
     #[cfg_attr(docs, doc(cfg(feature = "closure")))]
     pub use crate::closure::Closure;
     pub use crate::exception::{PhpException, PhpResult};
+    #[cfg(feature = "embed")]
+    pub use crate::php_assert;
     pub use crate::php_class;
     pub use crate::php_const;
+    #[cfg(any(php81, php82))]
+    pub use crate::php_enum;
     pub use crate::php_extern;
     pub use crate::php_function;
     pub use crate::php_impl;
@@ -52,7 +87,10 @@ pub mod prelude {
     pub use crate::php_print;
     pub use crate::php_println;
     pub use crate::php_startup;
+    #[cfg(feature = "embed")]
+    pub use crate::php_test;
     pub use crate::types::ZendCallable;
+    pub use crate::FlattenProps;
     pub use crate::ZvalConvert;
 }
 
@@ -507,6 +545,68 @@ pub use ext_php_rs_derive::php_module;
 /// ```
 pub use ext_php_rs_derive::php_class;
 
+/// Annotates an enum which will be exported to PHP as a native enum. Requires
+/// PHP 8.1 or later.
+///
+/// Each variant of the enum must be a unit variant, and is exported as a case.
+/// Cases are backed by a value when the variants are given an integer
+/// discriminant, or when they are annotated with `#[value(...)]`, which
+/// accepts an integer or string literal. Either all or none of the variants
+/// must be given a value, and the values must be of the same type. Backed
+/// enums implement [`TryFrom`] their backing type, and the backing type
+/// implements [`From`] the enum.
+///
+/// The enum is exported with the same name as the Rust enum, which can be
+/// changed with `#[php_enum(name = "...")]`. The cases are exported with the
+/// names of the variants, which can be changed with
+/// `#[php_enum(rename_all = "...")]`, accepting `none`, `lowercase`,
+/// `PascalCase`, `camelCase`, `snake_case` and `SCREAMING_SNAKE_CASE`, or with
+/// `#[rename("...")]` on a variant. Methods and constants are added to the enum
+/// with the [`macro@php_impl`] macro. Methods cannot take `&mut self`, as cases
+/// are immutable.
+///
+/// Interfaces are implemented on the enum with `#[implements(ce)]`, placed
+/// underneath this attribute, where `ce` may be any valid expression.
+///
+/// PHP attributes are applied to a case with `#[attribute("Name", args...)]`
+/// on the variant, where the arguments are literals.
+///
+/// The [`RegisteredEnum`](crate::enum_::RegisteredEnum) trait is implemented on
+/// the enum, which returns the cases of the enum and the variant represented by
+/// a case object.
+///
+/// # Example
+///
+/// ```no_run
+/// # #![cfg_attr(windows, feature(abi_vectorcall))]
+/// # use ext_php_rs::prelude::*;
+/// #[php_enum]
+/// pub enum Suit {
+///     #[value("H")]
+///     Hearts,
+///     #[value("S")]
+///     Spades,
+/// }
+///
+/// #[php_impl]
+/// impl Suit {
+///     pub fn color(&self) -> &'static str {
+///         match self {
+///             Self::Hearts => "Red",
+///             Self::Spades => "Black",
+///         }
+///     }
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+/// # fn main() {}
+/// ```
+#[cfg(any(php81, php82))]
+pub use ext_php_rs_derive::php_enum;
+
 /// Annotates a function that will be called by PHP when the module starts up.
 /// Generally used to register classes and constants.
 ///
@@ -542,6 +642,56 @@ pub use ext_php_rs_derive::php_class;
 /// ```
 pub use ext_php_rs_derive::php_startup;
 
+/// Attribute used to annotate a test which runs inside a PHP request, with the
+/// extension loaded. Requires the `embed` feature, and `php-embed` to be
+/// installed.
+///
+/// The embed SAPI is started by the first test and keeps running until the
+/// test binary exits, with the module returned by the `get_module` function
+/// generated by [`macro@php_module`] loaded. Each test then runs in a request
+/// of its own, so functions and classes of the extension can be called from
+/// the test, e.g. with [`Embed::eval`](crate::embed::Embed::eval), without
+/// building the extension and running the `php` binary.
+///
+/// The module is found at `crate::get_module` by default. Another module is
+/// used with `#[php_test(module = "path::to::get_module")]`. Only the module
+/// of the first test to run is loaded, so all tests in a test binary should
+/// use the same module.
+///
+/// Tests are run one at a time, as the engine is shared. A test fails when it
+/// panics or when PHP bails out, e.g. on a fatal error. The function can
+/// return any type the `#[test]` attribute accepts, such as `Result<(), E>`.
+///
+/// With a thread-safe (ZTS) build of PHP, the engine can only be used from the
+/// thread which started it. The test harness runs each test on its own thread,
+/// so tests after the first one panic; use a non thread-safe build of PHP.
+///
+/// # Example
+///
+/// ```no_run
+/// # #![cfg_attr(windows, feature(abi_vectorcall))]
+/// use ext_php_rs::{embed::Embed, prelude::*};
+///
+/// #[php_function]
+/// pub fn hello_world(name: String) -> String {
+///     format!("Hello, {}!", name)
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+///
+/// #[php_test]
+/// fn test_hello_world() {
+///     let result = Embed::eval("hello_world('foo');").unwrap();
+///     assert_eq!(result.string().unwrap(), "Hello, foo!");
+/// }
+/// # fn main() {}
+/// ```
+#[cfg(feature = "embed")]
+pub use ext_php_rs_derive::php_test;
+
 /// Derives the traits required to convert a struct or enum to and from a
 /// [`Zval`]. Both [`FromZval`] and [`IntoZval`] are implemented on types which
 /// use this macro.
@@ -559,6 +709,11 @@ pub use ext_php_rs_derive::php_startup;
 /// be added. If one property cannot be retrieved from the object, the whole
 /// conversion will fail.
 ///
+/// Fields are mapped to properties of the same name. A field can be mapped to
+/// a different property with `#[php(rename = "name")]`, and all fields can be
+/// renamed with `#[php(rename_all = "camelCase")]` on the struct, which also
+/// accepts `"snake_case"` and `"none"`.
+///
 /// ## Examples
 ///
 /// Basic example with some primitive PHP type.
@@ -681,6 +836,42 @@ pub use ext_php_rs_derive::php_startup;
 /// [`Zval::string`]: crate::types::Zval.::string
 pub use ext_php_rs_derive::ZvalConvert;
 
+/// Derives [`FlattenProps`] on a struct which is not exported as a class, so
+/// its fields annotated with `#[prop]` can be flattened into a class with
+/// `#[prop(flatten)]`. The `#[prop]` attribute accepts the same options as on
+/// the fields of a class.
+///
+/// Structs annotated with [`macro@php_class`] implement [`FlattenProps`]
+/// themselves.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ext_php_rs::prelude::*;
+/// #[derive(FlattenProps)]
+/// pub struct Point {
+///     #[prop]
+///     x: i64,
+///     #[prop]
+///     y: i64,
+/// }
+///
+/// #[php_class]
+/// pub struct Rect {
+///     #[prop(flatten, prefix = "origin_")]
+///     origin: Point,
+///     #[prop]
+///     width: i64,
+/// }
+/// # #[php_module]
+/// # pub fn get_module(module: ModuleBuilder) -> ModuleBuilder {
+/// #     module
+/// # }
+/// ```
+///
+/// [`FlattenProps`]: crate::props::FlattenProps
+pub use ext_php_rs_derive::FlattenProps;
+
 /// Defines an `extern` function with the Zend fastcall convention based on
 /// operating system.
 ///
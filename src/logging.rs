@@ -0,0 +1,361 @@
+//! Forwarding of records from the [`log`] and [`tracing`] crates into the PHP
+//! error log.
+//!
+//! Records are written with `php_log_err`, so they end up wherever the
+//! `error_log` INI setting points. When it is not set, PHP hands the record
+//! to the SAPI, e.g. stderr for the CLI or the web server log for FPM and
+//! Apache.
+//!
+//! Levels are mapped to syslog priorities, which are used when `error_log` is
+//! set to `syslog`:
+//!
+//! | Rust level | syslog priority |
+//! | ---------- | --------------- |
+//! | `ERROR`    | `LOG_ERR`       |
+//! | `WARN`     | `LOG_WARNING`   |
+//! | `INFO`     | `LOG_INFO`      |
+//! | `DEBUG`    | `LOG_DEBUG`     |
+//! | `TRACE`    | `LOG_DEBUG`     |
+//!
+//! Each record is formatted as `LEVEL target: message`. A request ID set with
+//! [`set_request_id`] is added after the target, so the records of a single
+//! request can be told apart: `LEVEL target [id]: message`.
+//!
+//! The PHP error log can only be written to from threads PHP runs on. Records
+//! logged from other threads are queued, and written on the next record
+//! logged from a PHP thread or when [`flush`] is called. At most
+//! [`MAX_PENDING`] records are queued, and the oldest records are dropped
+//! when more are logged.
+//!
+//! [`log`]: https://docs.rs/log
+//! [`tracing`]: https://docs.rs/tracing
+
+use std::{cell::RefCell, collections::VecDeque, ffi::CString, os::raw::c_int};
+
+use parking_lot::{const_mutex, Mutex};
+
+use crate::ffi::php_log_err_with_severity;
+
+// syslog priorities, as accepted by `php_log_err_with_severity`.
+#[cfg(any(feature = "log", feature = "tracing"))]
+const LOG_ERR: c_int = 3;
+#[cfg(any(feature = "log", feature = "tracing"))]
+const LOG_WARNING: c_int = 4;
+#[cfg(any(feature = "log", feature = "tracing"))]
+const LOG_INFO: c_int = 6;
+#[cfg(any(feature = "log", feature = "tracing"))]
+const LOG_DEBUG: c_int = 7;
+
+/// The maximum number of records queued from threads PHP does not run on,
+/// both for the PHP error log and for the PSR-3 bridge.
+pub const MAX_PENDING: usize = 1024;
+
+/// Records logged from threads PHP does not run on, waiting to be written.
+static PENDING: PendingQueue<(c_int, CString)> = PendingQueue::new();
+
+/// A queue of records logged from threads PHP does not run on. Holds at most
+/// [`MAX_PENDING`] records, dropping the oldest record when another one is
+/// pushed to a full queue.
+pub(crate) struct PendingQueue<T> {
+    records: Mutex<VecDeque<T>>,
+}
+
+impl<T> PendingQueue<T> {
+    /// Creates an empty queue.
+    pub(crate) const fn new() -> Self {
+        Self {
+            records: const_mutex(VecDeque::new()),
+        }
+    }
+
+    /// Adds a record to the queue, dropping the oldest record if the queue is
+    /// full.
+    pub(crate) fn push(&self, record: T) {
+        let mut records = self.records.lock();
+        if records.len() >= MAX_PENDING {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    /// Removes the records matching the predicate from the queue, returning
+    /// them in the order they were pushed.
+    pub(crate) fn take(&self, mut predicate: impl FnMut(&T) -> bool) -> Vec<T> {
+        let mut records = self.records.lock();
+        let (taken, rest) = std::mem::take(&mut *records)
+            .into_iter()
+            .partition::<Vec<_>, _>(|record| predicate(record));
+        *records = rest.into();
+        taken
+    }
+}
+
+#[cfg(not(php_zts))]
+static PHP_THREAD: once_cell::sync::OnceCell<std::thread::ThreadId> =
+    once_cell::sync::OnceCell::new();
+
+thread_local! {
+    static REQUEST_ID: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// A [`log::Log`] implementation writing records to the PHP error log.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{logging::PhpLogger, prelude::*};
+///
+/// #[php_startup]
+/// pub fn startup() {
+///     PhpLogger::init(log::LevelFilter::Info).expect("logger already set");
+/// }
+/// # fn main() {}
+/// ```
+#[cfg(feature = "log")]
+#[cfg_attr(docs, doc(cfg(feature = "log")))]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PhpLogger;
+
+#[cfg(feature = "log")]
+impl PhpLogger {
+    /// Sets the PHP logger as the global logger, logging records up to and
+    /// including the given level.
+    ///
+    /// Must be called from a PHP thread, usually in the module startup
+    /// function.
+    ///
+    /// # Parameters
+    ///
+    /// * `level` - The most verbose level which is logged.
+    ///
+    /// # Returns
+    ///
+    /// An error if a global logger has already been set.
+    pub fn init(level: ::log::LevelFilter) -> Result<(), ::log::SetLoggerError> {
+        static LOGGER: PhpLogger = PhpLogger;
+
+        mark_php_thread();
+        ::log::set_logger(&LOGGER)?;
+        ::log::set_max_level(level);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "log")]
+impl ::log::Log for PhpLogger {
+    fn enabled(&self, metadata: &::log::Metadata) -> bool {
+        metadata.level() <= ::log::max_level()
+    }
+
+    fn log(&self, record: &::log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let severity = match record.level() {
+            ::log::Level::Error => LOG_ERR,
+            ::log::Level::Warn => LOG_WARNING,
+            ::log::Level::Info => LOG_INFO,
+            ::log::Level::Debug | ::log::Level::Trace => LOG_DEBUG,
+        };
+        write(
+            severity,
+            record.level().as_str(),
+            record.target(),
+            &record.args().to_string(),
+        );
+    }
+
+    fn flush(&self) {
+        flush();
+    }
+}
+
+/// A [`tracing_subscriber::Layer`] writing events to the PHP error log.
+///
+/// Fields other than the message of an event are appended to the message as
+/// `name=value` pairs. Spans are not recorded.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{logging::PhpLayer, prelude::*};
+/// use tracing_subscriber::{layer::SubscriberExt, Registry};
+///
+/// #[php_startup]
+/// pub fn startup() {
+///     let subscriber = Registry::default().with(PhpLayer::new());
+///     tracing::subscriber::set_global_default(subscriber).expect("subscriber already set");
+/// }
+/// # fn main() {}
+/// ```
+///
+/// [`tracing_subscriber::Layer`]: https://docs.rs/tracing-subscriber/latest/tracing_subscriber/layer/trait.Layer.html
+#[cfg(feature = "tracing")]
+#[cfg_attr(docs, doc(cfg(feature = "tracing")))]
+#[derive(Debug, Clone, Copy)]
+pub struct PhpLayer {
+    _priv: (),
+}
+
+#[cfg(feature = "tracing")]
+impl PhpLayer {
+    /// Creates a new layer.
+    ///
+    /// Must be called from a PHP thread, usually in the module startup
+    /// function.
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        mark_php_thread();
+        Self { _priv: () }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl<S: tracing_core::Subscriber> tracing_subscriber::Layer<S> for PhpLayer {
+    fn on_event(
+        &self,
+        event: &tracing_core::Event<'_>,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        use tracing_core::Level;
+
+        let metadata = event.metadata();
+        let severity = match *metadata.level() {
+            Level::ERROR => LOG_ERR,
+            Level::WARN => LOG_WARNING,
+            Level::INFO => LOG_INFO,
+            _ => LOG_DEBUG,
+        };
+
+        let mut visitor = EventVisitor::default();
+        event.record(&mut visitor);
+        write(
+            severity,
+            metadata.level().as_str(),
+            metadata.target(),
+            &visitor.message,
+        );
+    }
+}
+
+/// Collects the message and fields of a tracing event.
+#[cfg(feature = "tracing")]
+#[derive(Default)]
+struct EventVisitor {
+    message: String,
+}
+
+#[cfg(feature = "tracing")]
+impl tracing_core::field::Visit for EventVisitor {
+    fn record_str(&mut self, field: &tracing_core::Field, value: &str) {
+        if field.name() == "message" {
+            self.message.insert_str(0, value);
+        } else {
+            self.record_debug(field, &value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &tracing_core::Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message.insert_str(0, &format!("{:?}", value));
+        } else {
+            self.message
+                .push_str(&format!(" {}={:?}", field.name(), value));
+        }
+    }
+}
+
+/// Sets the request ID added to records logged from the current thread, until
+/// it is cleared by passing [`None`].
+///
+/// Usually set in the request startup function, e.g. from the `X-Request-Id`
+/// header, and cleared in the request shutdown function.
+///
+/// # Parameters
+///
+/// * `id` - The ID of the current request.
+pub fn set_request_id(id: Option<String>) {
+    REQUEST_ID.with(|cell| *cell.borrow_mut() = id);
+}
+
+/// Writes the records which were logged from threads PHP does not run on.
+///
+/// Must be called from a PHP thread, usually in the request shutdown
+/// function. Does nothing otherwise.
+pub fn flush() {
+    if !on_php_thread() {
+        return;
+    }
+
+    for (severity, line) in PENDING.take(|_| true) {
+        // SAFETY: We are on a PHP thread and the line is a valid C string.
+        unsafe { php_log_err_with_severity(line.as_ptr(), severity) };
+    }
+}
+
+/// Formats a record and writes it to the PHP error log, or queues it when not
+/// on a PHP thread.
+#[cfg(any(feature = "log", feature = "tracing"))]
+fn write(severity: c_int, level: &str, target: &str, message: &str) {
+    let line = REQUEST_ID.with(|id| match &*id.borrow() {
+        Some(id) => format!("{} {} [{}]: {}", level, target, id, message),
+        None => format!("{} {}: {}", level, target, message),
+    });
+    // Interior nul bytes would truncate the record.
+    let line = match CString::new(line) {
+        Ok(line) => line,
+        Err(e) => {
+            let mut line = e.into_vec();
+            line.retain(|&b| b != 0);
+            CString::new(line).unwrap_or_default()
+        }
+    };
+
+    if on_php_thread() {
+        flush();
+        // SAFETY: We are on a PHP thread and the line is a valid C string.
+        unsafe { php_log_err_with_severity(line.as_ptr(), severity) };
+    } else {
+        PENDING.push((severity, line));
+    }
+}
+
+/// Records the current thread as the thread PHP runs on. Only required for
+/// non-ZTS builds, where PHP runs on a single thread.
+#[cfg(any(feature = "log", feature = "tracing"))]
+fn mark_php_thread() {
+    #[cfg(not(php_zts))]
+    let _ = PHP_THREAD.set(std::thread::current().id());
+}
+
+/// Returns whether PHP runs on the current thread.
+fn on_php_thread() -> bool {
+    #[cfg(php_zts)]
+    {
+        // SAFETY: Returns null on threads which have not been registered with TSRM.
+        !unsafe { crate::ffi::tsrm_get_ls_cache() }.is_null()
+    }
+    #[cfg(not(php_zts))]
+    {
+        PHP_THREAD.get() == Some(&std::thread::current().id())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_queue() {
+        let queue = PendingQueue::new();
+        for i in 0..MAX_PENDING + 2 {
+            queue.push(i);
+        }
+        assert_eq!(queue.take(|i| i % 2 == 0).first(), Some(&2));
+        let rest = queue.take(|_| true);
+        assert_eq!(rest.first(), Some(&3));
+        assert_eq!(rest.last(), Some(&(MAX_PENDING + 1)));
+        assert!(queue.take(|_| true).is_empty());
+    }
+}
@@ -246,9 +246,8 @@ macro_rules! class_derives {
         impl<'a> $crate::convert::FromZendObject<'a> for &'a $type {
             #[inline]
             fn from_zend_object(obj: &'a $crate::types::ZendObject) -> $crate::error::Result<Self> {
-                let obj = $crate::types::ZendClassObject::<$type>::from_zend_obj(obj)
-                    .ok_or($crate::error::Error::InvalidScope)?;
-                Ok(&**obj)
+                $crate::types::ZendClassObject::<$type>::upcast(obj)
+                    .ok_or($crate::error::Error::InvalidScope)
             }
         }
 
@@ -257,9 +256,8 @@ macro_rules! class_derives {
             fn from_zend_object_mut(
                 obj: &'a mut $crate::types::ZendObject,
             ) -> $crate::error::Result<Self> {
-                let obj = $crate::types::ZendClassObject::<$type>::from_zend_obj_mut(obj)
-                    .ok_or($crate::error::Error::InvalidScope)?;
-                Ok(&mut **obj)
+                $crate::types::ZendClassObject::<$type>::upcast_mut(obj)
+                    .ok_or($crate::error::Error::InvalidScope)
             }
         }
 
@@ -316,6 +314,87 @@ macro_rules! class_derives {
     };
 }
 
+/// Derives additional traits for enums exported to PHP. Should only be used on
+/// types which implement [`RegisteredEnum`], and is called by the
+/// [`php_enum`] macro.
+///
+/// Implements the following traits:
+///
+/// * `FromZendObject for T`
+/// * `FromZval for T`
+/// * `IntoZendObject for T`
+/// * `IntoZval for T`
+///
+/// Enums are converted into the existing object of the case rather than a new
+/// object, so the converted value is identical (`===`) to the case in PHP.
+/// Converting an object which is not a case of the enum returns
+/// [`Error::InvalidEnumObject`].
+///
+/// [`RegisteredEnum`]: crate::enum_::RegisteredEnum
+/// [`Error::InvalidEnumObject`]: crate::error::Error::InvalidEnumObject
+/// [`php_enum`]: crate::php_enum
+#[macro_export]
+macro_rules! enum_derives {
+    ($type: ty) => {
+        impl<'a> $crate::convert::FromZendObject<'a> for $type {
+            #[inline]
+            fn from_zend_object(obj: &'a $crate::types::ZendObject) -> $crate::error::Result<Self> {
+                <$type as $crate::enum_::RegisteredEnum>::from_object(obj).ok_or(
+                    $crate::error::Error::InvalidEnumObject(
+                        <$type as $crate::enum_::RegisteredEnum>::ENUM_NAME,
+                    ),
+                )
+            }
+        }
+
+        impl<'a> $crate::convert::FromZval<'a> for $type {
+            const TYPE: $crate::flags::DataType = $crate::flags::DataType::Object(Some(
+                <$type as $crate::enum_::RegisteredEnum>::ENUM_NAME,
+            ));
+
+            #[inline]
+            fn from_zval(zval: &'a $crate::types::Zval) -> ::std::option::Option<Self> {
+                <$type as $crate::enum_::RegisteredEnum>::from_object(zval.object()?)
+            }
+        }
+
+        impl $crate::convert::IntoZendObject for $type {
+            #[inline]
+            fn into_zend_object(
+                self,
+            ) -> $crate::error::Result<$crate::boxed::ZBox<$crate::types::ZendObject>> {
+                Ok(<$type as $crate::enum_::RegisteredEnum>::to_object(&self))
+            }
+        }
+
+        impl $crate::convert::IntoZval for $type {
+            const TYPE: $crate::flags::DataType = $crate::flags::DataType::Object(Some(
+                <$type as $crate::enum_::RegisteredEnum>::ENUM_NAME,
+            ));
+
+            #[inline]
+            fn set_zval(
+                self,
+                zv: &mut $crate::types::Zval,
+                persistent: bool,
+            ) -> $crate::error::Result<()> {
+                use $crate::convert::IntoZendObject;
+
+                // Case objects only live for the request, so values which outlive
+                // the request, such as class constants, refer to the case instead.
+                if persistent {
+                    zv.set_class_constant_expr(
+                        <$type as $crate::enum_::RegisteredEnum>::ENUM_NAME,
+                        <$type as $crate::enum_::RegisteredEnum>::case(&self).name,
+                    );
+                    return Ok(());
+                }
+                self.into_zend_object()?.set_zval(zv, persistent)
+            }
+        }
+    };
+}
+
 /// Derives `From<T> for Zval` and `IntoZval` for a given type.
 macro_rules! into_zval {
     ($type: ty, $fn: ident, $dt: ident) => {
@@ -408,5 +487,59 @@ macro_rules! php_println {
     };
 }
 
+/// Runs a snippet of PHP statements inside a request with the extension
+/// loaded, panicking if the snippet throws an uncaught exception, e.g. from a
+/// failed `assert()`, or causes a fatal error. The panic message contains the
+/// exception as well as the output of the snippet, such as warnings.
+///
+/// Assertions are enabled and throw an `AssertionError` while the snippet
+/// runs, whatever `php.ini` says, unless they are not compiled at all with
+/// `zend.assertions = -1`, in which case the snippet fails.
+///
+/// The engine is started by the first snippet of the process, as with
+/// [`macro@crate::php_test`]. The module is given by the function returning
+/// its module entry, which defaults to `crate::get_module`, the function
+/// generated by [`macro@crate::php_module`]. Requires the `embed` feature.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #![cfg_attr(windows, feature(abi_vectorcall))]
+/// use ext_php_rs::{php_assert, prelude::*};
+///
+/// #[php_function]
+/// pub fn double(n: i64) -> i64 {
+///     n * 2
+/// }
+///
+/// #[php_module]
+/// pub fn get_module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+///
+/// #[test]
+/// fn test_double() {
+///     php_assert!(get_module, r#"assert(double(2) === 4);"#);
+///     php_assert!(r#"
+///         $values = array_map('double', [1, 2, 3]);
+///         assert($values === [2, 4, 6]);
+///     "#);
+/// }
+/// # fn main() {}
+/// ```
+#[cfg(feature = "embed")]
+#[macro_export]
+#[allow(clippy::crate_in_macro_def)]
+macro_rules! php_assert {
+    ($code: expr $(,)?) => {
+        // `crate` refers to the crate using the macro, which declares the module.
+        $crate::embed::assert_php(crate::get_module, $code)
+    };
+
+    ($module: expr, $code: expr $(,)?) => {
+        $crate::embed::assert_php($module, $code)
+    };
+}
+
 pub(crate) use into_zval;
 pub(crate) use try_from_zval;
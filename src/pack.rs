@@ -0,0 +1,352 @@
+//! Typed equivalents of PHP's [`pack`] and [`unpack`] functions, for
+//! exchanging binary strings with PHP code which uses them.
+//!
+//! Rather than parsing a format string, each format code is a method on
+//! [`Packer`] and [`Unpacker`]:
+//!
+//! | Code | Packer method                  | Unpacker method       |
+//! | ---- | ------------------------------ | --------------------- |
+//! | `a`  | [`Packer::padded`] with `0`    | [`Unpacker::bytes`]   |
+//! | `A`  | [`Packer::padded`] with `b' '` | [`Unpacker::trimmed`] |
+//! | `c`  | [`Packer::i8`]                 | [`Unpacker::i8`]      |
+//! | `C`  | [`Packer::u8`]                 | [`Unpacker::u8`]      |
+//! | `s`  | [`Packer::i16`]                | [`Unpacker::i16`]     |
+//! | `S`  | [`Packer::u16`]                | [`Unpacker::u16`]     |
+//! | `n`  | [`Packer::u16_be`]             | [`Unpacker::u16_be`]  |
+//! | `v`  | [`Packer::u16_le`]             | [`Unpacker::u16_le`]  |
+//! | `l`  | [`Packer::i32`]                | [`Unpacker::i32`]     |
+//! | `L`  | [`Packer::u32`]                | [`Unpacker::u32`]     |
+//! | `N`  | [`Packer::u32_be`]             | [`Unpacker::u32_be`]  |
+//! | `V`  | [`Packer::u32_le`]             | [`Unpacker::u32_le`]  |
+//! | `q`  | [`Packer::i64`]                | [`Unpacker::i64`]     |
+//! | `Q`  | [`Packer::u64`]                | [`Unpacker::u64`]     |
+//! | `J`  | [`Packer::u64_be`]             | [`Unpacker::u64_be`]  |
+//! | `P`  | [`Packer::u64_le`]             | [`Unpacker::u64_le`]  |
+//! | `f`  | [`Packer::f32`]                | [`Unpacker::f32`]     |
+//! | `g`  | [`Packer::f32_le`]             | [`Unpacker::f32_le`]  |
+//! | `G`  | [`Packer::f32_be`]             | [`Unpacker::f32_be`]  |
+//! | `d`  | [`Packer::f64`]                | [`Unpacker::f64`]     |
+//! | `e`  | [`Packer::f64_le`]             | [`Unpacker::f64_le`]  |
+//! | `E`  | [`Packer::f64_be`]             | [`Unpacker::f64_be`]  |
+//! | `x`  | [`Packer::nul`]                | [`Unpacker::skip`]    |
+//!
+//! Codes without an explicit byte order use the byte order of the machine, as
+//! in PHP.
+//!
+//! The `Z`, `h`, `H`, `@` and `X` codes are not supported.
+//!
+//! [`pack`]: https://www.php.net/manual/en/function.pack.php
+//! [`unpack`]: https://www.php.net/manual/en/function.unpack.php
+
+use crate::{
+    boxed::ZBox,
+    convert::IntoZval,
+    error::{Error, Result},
+    flags::DataType,
+    types::{ZendStr, Zval, ZvalWriter},
+};
+
+/// Builds a binary string in the same way as PHP's `pack()`. The string is
+/// written directly into a Zend string, which can be returned to PHP.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{prelude::*, pack::Packer};
+///
+/// /// Equivalent to `pack('nNa4', $kind, $id, $tag)`.
+/// #[php_function]
+/// pub fn header(kind: u16, id: u32, tag: &[u8]) -> Packer {
+///     Packer::new().u16_be(kind).u32_be(id).padded(tag, 4, 0)
+/// }
+/// # fn main() {}
+/// ```
+#[derive(Default)]
+pub struct Packer {
+    buf: ZvalWriter,
+}
+
+macro_rules! pack_number {
+    ($($(#[$attr: meta])* $name: ident($t: ty) => $to_bytes: ident;)*) => {
+        $(
+            $(#[$attr])*
+            #[must_use]
+            pub fn $name(mut self, value: $t) -> Self {
+                self.buf.push_bytes(&value.$to_bytes());
+                self
+            }
+        )*
+    };
+}
+
+impl Packer {
+    /// Creates a new, empty packer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new packer with space for at least `capacity` bytes.
+    ///
+    /// # Parameters
+    ///
+    /// * `capacity` - The number of bytes to allocate.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buf: ZvalWriter::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the bytes packed so far.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.buf.as_bytes()
+    }
+
+    /// Appends bytes as they are, as the `a*` code does.
+    ///
+    /// # Parameters
+    ///
+    /// * `bytes` - The bytes to append.
+    #[must_use]
+    pub fn bytes(mut self, bytes: impl AsRef<[u8]>) -> Self {
+        self.buf.push_bytes(bytes.as_ref());
+        self
+    }
+
+    /// Appends bytes truncated or padded to exactly `len` bytes, as the `aN`
+    /// (padded with NUL bytes) and `AN` (padded with spaces) codes do.
+    ///
+    /// # Parameters
+    ///
+    /// * `bytes` - The bytes to append.
+    /// * `len` - The number of bytes to write.
+    /// * `pad` - The byte to pad with.
+    #[must_use]
+    pub fn padded(mut self, bytes: impl AsRef<[u8]>, len: usize, pad: u8) -> Self {
+        let bytes = bytes.as_ref();
+        let bytes = &bytes[..bytes.len().min(len)];
+        self.buf.push_bytes(bytes);
+        self.nul_with(len - bytes.len(), pad)
+    }
+
+    /// Appends `count` NUL bytes, as the `x` code does.
+    ///
+    /// # Parameters
+    ///
+    /// * `count` - The number of bytes to append.
+    #[must_use]
+    pub fn nul(self, count: usize) -> Self {
+        self.nul_with(count, 0)
+    }
+
+    fn nul_with(mut self, count: usize, byte: u8) -> Self {
+        let chunk = [byte; 64];
+        let mut remaining = count;
+        while remaining > 0 {
+            let n = remaining.min(chunk.len());
+            self.buf.push_bytes(&chunk[..n]);
+            remaining -= n;
+        }
+        self
+    }
+
+    pack_number! {
+        /// Appends a signed byte (`c`).
+        i8(i8) => to_ne_bytes;
+        /// Appends an unsigned byte (`C`).
+        u8(u8) => to_ne_bytes;
+        /// Appends a signed 16 bit integer in machine byte order (`s`).
+        i16(i16) => to_ne_bytes;
+        /// Appends an unsigned 16 bit integer in machine byte order (`S`).
+        u16(u16) => to_ne_bytes;
+        /// Appends an unsigned 16 bit integer in big endian byte order (`n`).
+        u16_be(u16) => to_be_bytes;
+        /// Appends an unsigned 16 bit integer in little endian byte order
+        /// (`v`).
+        u16_le(u16) => to_le_bytes;
+        /// Appends a signed 32 bit integer in machine byte order (`l`).
+        i32(i32) => to_ne_bytes;
+        /// Appends an unsigned 32 bit integer in machine byte order (`L`).
+        u32(u32) => to_ne_bytes;
+        /// Appends an unsigned 32 bit integer in big endian byte order (`N`).
+        u32_be(u32) => to_be_bytes;
+        /// Appends an unsigned 32 bit integer in little endian byte order
+        /// (`V`).
+        u32_le(u32) => to_le_bytes;
+        /// Appends a signed 64 bit integer in machine byte order (`q`).
+        i64(i64) => to_ne_bytes;
+        /// Appends an unsigned 64 bit integer in machine byte order (`Q`).
+        u64(u64) => to_ne_bytes;
+        /// Appends an unsigned 64 bit integer in big endian byte order (`J`).
+        u64_be(u64) => to_be_bytes;
+        /// Appends an unsigned 64 bit integer in little endian byte order
+        /// (`P`).
+        u64_le(u64) => to_le_bytes;
+        /// Appends a float in machine byte order (`f`).
+        f32(f32) => to_ne_bytes;
+        /// Appends a float in little endian byte order (`g`).
+        f32_le(f32) => to_le_bytes;
+        /// Appends a float in big endian byte order (`G`).
+        f32_be(f32) => to_be_bytes;
+        /// Appends a double in machine byte order (`d`).
+        f64(f64) => to_ne_bytes;
+        /// Appends a double in little endian byte order (`e`).
+        f64_le(f64) => to_le_bytes;
+        /// Appends a double in big endian byte order (`E`).
+        f64_be(f64) => to_be_bytes;
+    }
+
+    /// Consumes the packer, returning the packed string.
+    pub fn finish(self) -> ZBox<ZendStr> {
+        self.buf.finish()
+    }
+}
+
+impl IntoZval for Packer {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        self.buf.set_zval(zv, persistent)
+    }
+}
+
+/// Reads values from a binary string in the same way as PHP's `unpack()`.
+///
+/// Each read advances past the value which was read, and returns
+/// [`Error::UnpackOutOfBounds`] if the string is too short to contain it.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{prelude::*, pack::Unpacker};
+///
+/// /// Equivalent to `unpack('nkind/Nid', $header)`.
+/// #[php_function]
+/// pub fn header_id(header: &[u8]) -> PhpResult<u32> {
+///     let mut data = Unpacker::new(header);
+///     let _kind = data.u16_be()?;
+///     Ok(data.u32_be()?)
+/// }
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone)]
+pub struct Unpacker<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+macro_rules! unpack_number {
+    ($($(#[$attr: meta])* $name: ident($t: ty) => $from_bytes: ident;)*) => {
+        $(
+            $(#[$attr])*
+            pub fn $name(&mut self) -> Result<$t> {
+                let mut bytes = [0; std::mem::size_of::<$t>()];
+                bytes.copy_from_slice(self.bytes(std::mem::size_of::<$t>())?);
+                Ok(<$t>::$from_bytes(bytes))
+            }
+        )*
+    };
+}
+
+impl<'a> Unpacker<'a> {
+    /// Creates an unpacker reading from the start of the given bytes.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - The binary string to read.
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// Returns the offset of the next byte to be read.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the bytes which have not been read yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.data[self.pos..]
+    }
+
+    /// Reads `len` bytes, as the `aN` code does. The bytes are borrowed from
+    /// the string.
+    ///
+    /// # Parameters
+    ///
+    /// * `len` - The number of bytes to read.
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        let bytes = self
+            .remaining()
+            .get(..len)
+            .ok_or(Error::UnpackOutOfBounds)?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    /// Reads `len` bytes and strips trailing spaces, tabs, carriage returns,
+    /// line feeds and NUL bytes, as the `AN` code does. The bytes are
+    /// borrowed from the string.
+    ///
+    /// # Parameters
+    ///
+    /// * `len` - The number of bytes to read.
+    pub fn trimmed(&mut self, len: usize) -> Result<&'a [u8]> {
+        let bytes = self.bytes(len)?;
+        let end = bytes
+            .iter()
+            .rposition(|b| !matches!(b, b' ' | b'\t' | b'\r' | b'\n' | 0))
+            .map_or(0, |i| i + 1);
+        Ok(&bytes[..end])
+    }
+
+    /// Skips `count` bytes, as the `x` code does.
+    ///
+    /// # Parameters
+    ///
+    /// * `count` - The number of bytes to skip.
+    pub fn skip(&mut self, count: usize) -> Result<()> {
+        self.bytes(count).map(|_| ())
+    }
+
+    unpack_number! {
+        /// Reads a signed byte (`c`).
+        i8(i8) => from_ne_bytes;
+        /// Reads an unsigned byte (`C`).
+        u8(u8) => from_ne_bytes;
+        /// Reads a signed 16 bit integer in machine byte order (`s`).
+        i16(i16) => from_ne_bytes;
+        /// Reads an unsigned 16 bit integer in machine byte order (`S`).
+        u16(u16) => from_ne_bytes;
+        /// Reads an unsigned 16 bit integer in big endian byte order (`n`).
+        u16_be(u16) => from_be_bytes;
+        /// Reads an unsigned 16 bit integer in little endian byte order (`v`).
+        u16_le(u16) => from_le_bytes;
+        /// Reads a signed 32 bit integer in machine byte order (`l`).
+        i32(i32) => from_ne_bytes;
+        /// Reads an unsigned 32 bit integer in machine byte order (`L`).
+        u32(u32) => from_ne_bytes;
+        /// Reads an unsigned 32 bit integer in big endian byte order (`N`).
+        u32_be(u32) => from_be_bytes;
+        /// Reads an unsigned 32 bit integer in little endian byte order (`V`).
+        u32_le(u32) => from_le_bytes;
+        /// Reads a signed 64 bit integer in machine byte order (`q`).
+        i64(i64) => from_ne_bytes;
+        /// Reads an unsigned 64 bit integer in machine byte order (`Q`).
+        u64(u64) => from_ne_bytes;
+        /// Reads an unsigned 64 bit integer in big endian byte order (`J`).
+        u64_be(u64) => from_be_bytes;
+        /// Reads an unsigned 64 bit integer in little endian byte order (`P`).
+        u64_le(u64) => from_le_bytes;
+        /// Reads a float in machine byte order (`f`).
+        f32(f32) => from_ne_bytes;
+        /// Reads a float in little endian byte order (`g`).
+        f32_le(f32) => from_le_bytes;
+        /// Reads a float in big endian byte order (`G`).
+        f32_be(f32) => from_be_bytes;
+        /// Reads a double in machine byte order (`d`).
+        f64(f64) => from_ne_bytes;
+        /// Reads a double in little endian byte order (`e`).
+        f64_le(f64) => from_le_bytes;
+        /// Reads a double in big endian byte order (`E`).
+        f64_be(f64) => from_be_bytes;
+    }
+}
@@ -0,0 +1,228 @@
+//! Values which outlive a single request, such as database connections, HTTP
+//! connection pools or gRPC channels.
+//!
+//! PHP releases everything a request allocated when the request ends, so
+//! objects returned to PHP code cannot hold on to a client between requests.
+//! A [`PersistentHandle`] stores the client in a process-wide registry
+//! instead, where it is found again by key in later requests, as persistent
+//! resources such as those of `pg_pconnect()` are.
+//!
+//! # Shutdown
+//!
+//! The registry is emptied when the extension is shut down, after the shutdown
+//! function of the module ran. Values are dropped in the reverse order they
+//! were created in, so a value created from another one, e.g. a prepared
+//! statement cache created from a connection pool, is dropped first. A value is only
+//! dropped once the last handle to it is dropped as well.
+//!
+//! # Threads and processes
+//!
+//! With a thread-safe (ZTS) build of PHP, all threads share the registry, so
+//! values must be [`Send`] and [`Sync`]. Wrap clients which cannot be used
+//! from several threads at once in a [`Mutex`](std::sync::Mutex), or register
+//! a separate value per thread by adding the thread ID to the key.
+//!
+//! PHP-FPM and the Apache prefork module fork worker processes after the
+//! module startup function ran. Create values lazily when they are first
+//! needed by a request, rather than in the module startup function, so each
+//! worker opens its own connections instead of sharing sockets with the other
+//! workers.
+
+use std::{
+    any::{Any, TypeId},
+    fmt::{self, Debug},
+    ops::Deref,
+    sync::Arc,
+};
+
+use parking_lot::{const_mutex, Mutex};
+
+/// A value stored in the registry.
+struct Entry {
+    type_id: TypeId,
+    key: String,
+    value: Arc<dyn Any + Send + Sync>,
+}
+
+/// The registry of persistent values, in the order they were created in.
+static REGISTRY: Mutex<Vec<Entry>> = const_mutex(Vec::new());
+
+/// A handle to a value which lives across requests, identified by its type
+/// and a key.
+///
+/// Handles are cheap to clone and can be sent to other threads.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::sync::atomic::{AtomicU64, Ordering};
+///
+/// use ext_php_rs::{persistent::PersistentHandle, prelude::*};
+///
+/// struct Pool {
+///     requests: AtomicU64,
+/// }
+///
+/// impl Pool {
+///     fn connect(dsn: &str) -> Result<Self, String> {
+///         // Open the connections to `dsn`...
+///         Ok(Self { requests: AtomicU64::new(0) })
+///     }
+/// }
+///
+/// #[php_function]
+/// pub fn query(dsn: &str) -> PhpResult<u64> {
+///     let pool = PersistentHandle::get_or_try_init(dsn, || Pool::connect(dsn))?;
+///     Ok(pool.requests.fetch_add(1, Ordering::Relaxed) + 1)
+/// }
+/// # fn main() {}
+/// ```
+pub struct PersistentHandle<T: Send + Sync + 'static> {
+    key: Arc<str>,
+    value: Arc<T>,
+}
+
+impl<T: Send + Sync + 'static> PersistentHandle<T> {
+    /// Returns a handle to the value of type `T` with the given key, or
+    /// [`None`] if there is no such value.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key of the value.
+    pub fn get(key: &str) -> Option<Self> {
+        let registry = REGISTRY.lock();
+        find(&registry, key)
+    }
+
+    /// Returns a handle to the value of type `T` with the given key, creating
+    /// the value if there is no such value.
+    ///
+    /// The value is created without holding a lock, so `init` may use other
+    /// persistent values. When two threads create the same value at once,
+    /// the value created first is kept and the other is dropped.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key of the value.
+    /// * `init` - Creates the value.
+    pub fn get_or_init(key: &str, init: impl FnOnce() -> T) -> Self {
+        match Self::get_or_try_init(key, || Ok::<_, std::convert::Infallible>(init())) {
+            Ok(handle) => handle,
+            Err(e) => match e {},
+        }
+    }
+
+    /// Returns a handle to the value of type `T` with the given key, creating
+    /// the value if there is no such value. Nothing is stored when `init`
+    /// returns an error, so the value is created again on the next call.
+    ///
+    /// See [`PersistentHandle::get_or_init`] for how the value is created.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key of the value.
+    /// * `init` - Creates the value, or returns an error.
+    pub fn get_or_try_init<E>(key: &str, init: impl FnOnce() -> Result<T, E>) -> Result<Self, E> {
+        if let Some(handle) = Self::get(key) {
+            return Ok(handle);
+        }
+
+        let value = init()?;
+        let mut registry = REGISTRY.lock();
+        if let Some(handle) = find(&registry, key) {
+            return Ok(handle);
+        }
+
+        let value = Arc::new(value);
+        registry.push(Entry {
+            type_id: TypeId::of::<T>(),
+            key: key.into(),
+            value: value.clone(),
+        });
+        Ok(Self {
+            key: key.into(),
+            value,
+        })
+    }
+
+    /// Removes the value of type `T` with the given key from the registry,
+    /// returning a handle to it. The value is dropped once all handles to it
+    /// are dropped, and the next call to [`PersistentHandle::get_or_init`]
+    /// creates a new value, e.g. to reconnect after the connection was lost.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key of the value.
+    pub fn remove(key: &str) -> Option<Self> {
+        let entry = {
+            let mut registry = REGISTRY.lock();
+            let index = registry
+                .iter()
+                .position(|entry| entry.type_id == TypeId::of::<T>() && entry.key == key)?;
+            registry.remove(index)
+        };
+
+        Some(Self {
+            key: key.into(),
+            value: entry.value.downcast().ok()?,
+        })
+    }
+
+    /// Returns the key of the value.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+impl<T: Send + Sync + 'static> Clone for PersistentHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key.clone(),
+            value: self.value.clone(),
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> Deref for PersistentHandle<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T: Send + Sync + Debug + 'static> Debug for PersistentHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PersistentHandle")
+            .field("key", &self.key)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+/// Returns a handle to a value in the registry.
+fn find<T: Send + Sync + 'static>(registry: &[Entry], key: &str) -> Option<PersistentHandle<T>> {
+    let entry = registry
+        .iter()
+        .find(|entry| entry.type_id == TypeId::of::<T>() && entry.key == key)?;
+
+    Some(PersistentHandle {
+        key: key.into(),
+        value: entry.value.clone().downcast().ok()?,
+    })
+}
+
+/// Removes all values from the registry, dropping them in the reverse order
+/// they were created in. Values still referenced by a handle are dropped when
+/// the last handle is dropped.
+///
+/// Called by the module shutdown function set by
+/// [`ModuleBuilder`](crate::builders::ModuleBuilder).
+pub(crate) fn shutdown() {
+    let entries = std::mem::take(&mut *REGISTRY.lock());
+    // Values are dropped outside the lock, as their destructors may use the
+    // registry.
+    for entry in entries.into_iter().rev() {
+        drop(entry);
+    }
+}
@@ -19,7 +19,12 @@
 //!
 //! [`RegisteredClass`]: crate::class::RegisteredClass
 
+use std::collections::{BTreeSet, HashMap};
+
+use parking_lot::{const_mutex, Mutex};
+
 use crate::{
+    builders::ClassBuilder,
     convert::{FromZval, IntoZval},
     error::{Error, Result},
     exception::PhpResult,
@@ -74,6 +79,8 @@ pub enum Property<'a, T> {
     Method {
         get: PropertyGetter<'a, T>,
         set: PropertySetter<'a, T>,
+        /// Whether the value returned by the getter is cached on the object.
+        cached: bool,
     },
 }
 
@@ -154,7 +161,109 @@ impl<'a, T: 'a> Property<'a, T> {
             }) as Box<dyn Fn(&mut T, &Zval) -> PhpResult + Send + Sync + 'a>
         });
 
-        Self::Method { get, set }
+        Self::Method {
+            get,
+            set,
+            cached: false,
+        }
+    }
+
+    /// Maps the properties of a struct stored in a field of `T` onto `T`, so
+    /// they can be accessed as properties of the class of `T`. Used by
+    /// `#[prop(flatten)]`.
+    ///
+    /// The properties are named after the properties of `U`, with the given
+    /// prefix. Each distinct prefixed name is allocated once and never freed.
+    ///
+    /// # Parameters
+    ///
+    /// * `prefix` - The prefix added to the names of the properties.
+    /// * `field` - Returns a reference to the struct in the field.
+    /// * `field_mut` - Returns a mutable reference to the struct in the field.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ext_php_rs::{prelude::*, props::Property};
+    /// #[derive(FlattenProps)]
+    /// pub struct Address {
+    ///     #[prop]
+    ///     city: String,
+    /// }
+    ///
+    /// pub struct Customer {
+    ///     address: Address,
+    /// }
+    ///
+    /// let props: Vec<(&'static str, Property<Customer>)> = Property::flatten(
+    ///     "address_",
+    ///     |customer: &Customer| &customer.address,
+    ///     |customer: &mut Customer| &mut customer.address,
+    /// );
+    /// ```
+    pub fn flatten<U: FlattenProps>(
+        prefix: &str,
+        field: fn(&T) -> &U,
+        field_mut: fn(&mut T) -> &mut U,
+    ) -> Vec<(&'static str, Self)>
+    where
+        T: 'static,
+    {
+        U::flatten_properties()
+            .into_iter()
+            .map(|(name, prop)| {
+                let name = if prefix.is_empty() {
+                    name
+                } else {
+                    intern_name(format!("{}{}", prefix, name))
+                };
+                let prop = match prop {
+                    Property::Field(get) => {
+                        Self::Field(Box::new(move |self_: &mut T| get(field_mut(self_))))
+                    }
+                    Property::Method { get, set, cached } => Self::Method {
+                        get: get.map(|get| {
+                            Box::new(move |self_: &T, retval: &mut Zval| get(field(self_), retval))
+                                as Box<dyn Fn(&T, &mut Zval) -> PhpResult + Send + Sync + 'a>
+                        }),
+                        set: set.map(|set| {
+                            Box::new(move |self_: &mut T, value: &Zval| {
+                                set(field_mut(self_), value)
+                            })
+                                as Box<dyn Fn(&mut T, &Zval) -> PhpResult + Send + Sync + 'a>
+                        }),
+                        cached,
+                    },
+                };
+                (name, prop)
+            })
+            .collect()
+    }
+
+    /// Caches the value returned by the getter of a method property in the
+    /// properties table of the object, so the getter is only called again
+    /// after the cache is invalidated. Field properties are never cached.
+    ///
+    /// The property must be declared on the class, which provides the slot the
+    /// value is cached in. Setting the property invalidates the cached value,
+    /// and [`ZendClassObject::invalidate_prop`] invalidates it explicitly.
+    ///
+    /// [`ZendClassObject::invalidate_prop`]: crate::types::ZendClassObject::invalidate_prop
+    pub fn cached(self) -> Self {
+        match self {
+            Self::Method { get, set, .. } => Self::Method {
+                get,
+                set,
+                cached: true,
+            },
+            field => field,
+        }
+    }
+
+    /// Returns whether the value of the property is cached. See
+    /// [`Property::cached`].
+    pub fn is_cached(&self) -> bool {
+        matches!(self, Self::Method { cached: true, .. })
     }
 
     /// Attempts to retrieve the value of the property from the given object
@@ -197,7 +306,7 @@ impl<'a, T: 'a> Property<'a, T> {
             Property::Field(field) => field(self_)
                 .get(retval)
                 .map_err(|e| format!("Failed to get property value: {e:?}").into()),
-            Property::Method { get, set: _ } => match get {
+            Property::Method { get, .. } => match get {
                 Some(get) => get(self_, retval),
                 None => Err("No getter available for this property.".into()),
             },
@@ -245,10 +354,198 @@ impl<'a, T: 'a> Property<'a, T> {
             Property::Field(field) => field(self_)
                 .set(value)
                 .map_err(|e| format!("Failed to set property value: {e:?}").into()),
-            Property::Method { get: _, set } => match set {
+            Property::Method { set, .. } => match set {
                 Some(set) => set(self_, value),
                 None => Err("No setter available for this property.".into()),
             },
         }
     }
 }
+
+/// Returns a static copy of a property name, allocating each distinct name
+/// once.
+fn intern_name(name: String) -> &'static str {
+    static NAMES: Mutex<BTreeSet<&'static str>> = const_mutex(BTreeSet::new());
+
+    let mut names = NAMES.lock();
+    match names.get(name.as_str()) {
+        Some(name) => name,
+        None => {
+            let name = Box::leak(name.into_boxed_str());
+            names.insert(name);
+            name
+        }
+    }
+}
+
+/// Implemented on structs whose properties can be flattened into a class with
+/// `#[prop(flatten)]`.
+///
+/// Implemented on classes by the [`php_class`] macro, and on structs which are
+/// not exported as classes by the [`FlattenProps`](crate::FlattenProps)
+/// derive macro.
+///
+/// [`php_class`]: crate::php_class
+pub trait FlattenProps: Sized + 'static {
+    /// The names of the properties, used to reject flattened properties with
+    /// colliding names at compile time.
+    const PROPERTY_NAMES: PropertyNames;
+
+    /// Returns the properties of the struct.
+    fn flatten_properties<'a>() -> HashMap<&'static str, Property<'a, Self>>;
+
+    /// Declares the properties with a known type on the class the struct is
+    /// flattened into.
+    ///
+    /// # Parameters
+    ///
+    /// * `builder` - The builder of the class.
+    /// * `prefix` - The prefix added to the names of the properties.
+    fn declare_properties(builder: ClassBuilder, prefix: &str) -> ClassBuilder;
+}
+
+/// The names of the properties of a struct, including the properties of its
+/// flattened fields.
+#[derive(Debug, Clone, Copy)]
+pub struct PropertyNames {
+    /// The names of the properties declared on the struct itself.
+    pub names: &'static [&'static str],
+    /// The prefixes and property names of the flattened fields.
+    pub flattened: &'static [(&'static str, &'static PropertyNames)],
+}
+
+impl PropertyNames {
+    /// Returns whether two of the properties have the same name once the
+    /// prefixes of flattened fields are applied. Evaluated at compile time, so
+    /// flattened fields can be nested at most 7 levels deep.
+    pub const fn has_collision(&self) -> bool {
+        let count = self.count();
+        let mut i = 0;
+        while i < count {
+            let name = self.nth(i, PrefixedName::EMPTY);
+            let mut j = i + 1;
+            while j < count {
+                if name.eq(&self.nth(j, PrefixedName::EMPTY)) {
+                    return true;
+                }
+                j += 1;
+            }
+            i += 1;
+        }
+        false
+    }
+
+    /// Returns the number of properties, including flattened properties.
+    const fn count(&self) -> usize {
+        let mut count = self.names.len();
+        let mut i = 0;
+        while i < self.flattened.len() {
+            count += self.flattened[i].1.count();
+            i += 1;
+        }
+        count
+    }
+
+    /// Returns the name of the property at the given index, appended to the
+    /// given prefixes.
+    const fn nth(&self, mut index: usize, mut name: PrefixedName) -> PrefixedName {
+        if index < self.names.len() {
+            name.parts[name.len] = self.names[index];
+            name.len += 1;
+            return name;
+        }
+        index -= self.names.len();
+        let mut i = 0;
+        loop {
+            let (prefix, names) = self.flattened[i];
+            let count = names.count();
+            if index < count {
+                name.parts[name.len] = prefix;
+                name.len += 1;
+                return names.nth(index, name);
+            }
+            index -= count;
+            i += 1;
+        }
+    }
+}
+
+/// A property name split into the prefixes of the flattened fields it is
+/// nested in, followed by the name itself.
+#[derive(Clone, Copy)]
+struct PrefixedName {
+    parts: [&'static str; 8],
+    len: usize,
+}
+
+impl PrefixedName {
+    const EMPTY: Self = Self {
+        parts: [""; 8],
+        len: 0,
+    };
+
+    /// Compares the concatenated parts of two names.
+    const fn eq(&self, other: &Self) -> bool {
+        let (mut a, mut a_pos) = (0, 0);
+        let (mut b, mut b_pos) = (0, 0);
+        loop {
+            while a < self.len && a_pos == self.parts[a].len() {
+                a += 1;
+                a_pos = 0;
+            }
+            while b < other.len && b_pos == other.parts[b].len() {
+                b += 1;
+                b_pos = 0;
+            }
+            if a == self.len || b == other.len {
+                return a == self.len && b == other.len;
+            }
+            if self.parts[a].as_bytes()[a_pos] != other.parts[b].as_bytes()[b_pos] {
+                return false;
+            }
+            a_pos += 1;
+            b_pos += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PropertyNames;
+
+    const POINT: PropertyNames = PropertyNames {
+        names: &["x", "y"],
+        flattened: &[],
+    };
+
+    #[test]
+    fn test_property_names_collision() {
+        let names = PropertyNames {
+            names: &["width"],
+            flattened: &[("origin_", &POINT), ("end_", &POINT)],
+        };
+        assert!(!names.has_collision());
+
+        let names = PropertyNames {
+            names: &["origin_x"],
+            flattened: &[("origin_", &POINT)],
+        };
+        assert!(names.has_collision());
+
+        let names = PropertyNames {
+            names: &[],
+            flattened: &[("", &POINT), ("", &POINT)],
+        };
+        assert!(names.has_collision());
+
+        const NESTED: PropertyNames = PropertyNames {
+            names: &[],
+            flattened: &[("a_", &POINT)],
+        };
+        let names = PropertyNames {
+            names: &["b_a_x"],
+            flattened: &[("b_", &NESTED)],
+        };
+        assert!(names.has_collision());
+    }
+}
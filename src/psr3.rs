@@ -0,0 +1,349 @@
+//! A bridge from Rust code to a PSR-3 logger provided by PHP code.
+//!
+//! PHP code registers a logger at the start of each request with the
+//! `ExtPhpRs\Logger` class:
+//!
+//! ```php
+//! <?php
+//!
+//! namespace ExtPhpRs;
+//!
+//! final class Logger
+//! {
+//!     public static function register(?object $logger): void;
+//!     public static function flush(): void;
+//! }
+//! ```
+//!
+//! Rust code then obtains a [`Logger`] handle with [`Logger::current`], and
+//! logs through it. Records are passed to the `log()` method of the registered
+//! logger, which is used through duck typing, so any implementation of
+//! `Psr\Log\LoggerInterface` works without the interface being loaded.
+//!
+//! The registered logger is held in a static property of the class, so it is
+//! released at the end of each request.
+
+use std::{
+    cell::Cell,
+    ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+    thread::{self, ThreadId},
+};
+
+use crate::{
+    args::Arg,
+    builders::{ClassBuilder, FunctionBuilder},
+    convert::IntoZval,
+    error::{Error, Result},
+    exception::{throw_object, PhpException},
+    flags::{ClassFlags, DataType, MethodFlags, PropertyFlags},
+    logging::PendingQueue,
+    types::{ZendHashTable, Zval},
+    zend::{ClassEntry, ExecuteData},
+    zend_fastcall,
+};
+
+/// Class entry of `ExtPhpRs\Logger`.
+static LOGGER_CE: AtomicPtr<ClassEntry> = AtomicPtr::new(ptr::null_mut());
+
+/// Records logged from threads PHP does not run on, waiting to be passed to
+/// the logger of the request which created the handle.
+static PENDING: PendingQueue<Record> = PendingQueue::new();
+
+thread_local! {
+    /// Counts the requests which registered a logger on the current thread, so
+    /// buffered records are not passed to the logger of a later request.
+    static GENERATION: Cell<u64> = const { Cell::new(0) };
+}
+
+/// The severity of a record, as defined by PSR-3.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    /// System is unusable.
+    Emergency,
+    /// Action must be taken immediately.
+    Alert,
+    /// Critical conditions.
+    Critical,
+    /// Runtime errors that do not require immediate action.
+    Error,
+    /// Exceptional occurrences that are not errors.
+    Warning,
+    /// Normal but significant events.
+    Notice,
+    /// Interesting events.
+    Info,
+    /// Detailed debug information.
+    Debug,
+}
+
+impl Level {
+    /// Returns the name of the level passed to `LoggerInterface::log()`, as
+    /// defined by `Psr\Log\LogLevel`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Emergency => "emergency",
+            Self::Alert => "alert",
+            Self::Critical => "critical",
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Notice => "notice",
+            Self::Info => "info",
+            Self::Debug => "debug",
+        }
+    }
+}
+
+/// The request a handle was created for: the thread running the request, and
+/// the generation of the logger on that thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RequestTag {
+    thread: ThreadId,
+    generation: u64,
+}
+
+impl RequestTag {
+    fn current() -> Self {
+        Self {
+            thread: thread::current().id(),
+            generation: GENERATION.with(Cell::get),
+        }
+    }
+}
+
+/// A record waiting to be passed to a logger.
+#[derive(Debug)]
+struct Record {
+    request: RequestTag,
+    level: Level,
+    message: String,
+    context: Vec<(String, String)>,
+}
+
+/// A handle to the PSR-3 logger registered by PHP code.
+///
+/// The handle can be sent to other threads. Records logged from the thread
+/// which created the handle are passed to the logger straight away, while
+/// records logged from other threads are buffered until the next record is
+/// logged from the thread which created the handle, [`Logger::flush`] is
+/// called, or PHP code calls `ExtPhpRs\Logger::flush()`.
+///
+/// Records are dropped when no logger is registered. Buffered records are
+/// also dropped once the request which created the handle has ended, and when
+/// more than [`MAX_PENDING`] records are buffered, in which case the oldest
+/// records are dropped.
+///
+/// [`MAX_PENDING`]: crate::logging::MAX_PENDING
+///
+/// The class is only registered when the `psr3` feature is enabled.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{prelude::*, psr3::{Level, Logger}};
+///
+/// #[php_function]
+/// pub fn import(path: &str) -> PhpResult<()> {
+///     let logger = Logger::current();
+///     logger.log(Level::Info, "Importing {path}", &[("path", path)])?;
+///
+///     let worker = logger.clone();
+///     std::thread::spawn(move || {
+///         let _ = worker.log(Level::Debug, "Import started", &[]);
+///     });
+///     Ok(())
+/// }
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone)]
+pub struct Logger {
+    request: RequestTag,
+}
+
+impl Logger {
+    /// Returns a handle to the logger registered for the current request.
+    ///
+    /// Must be called from the thread which is running the request, after the
+    /// logger has been registered. Handles obtained before the first logger of
+    /// a request is registered belong to the previous request.
+    pub fn current() -> Self {
+        Self {
+            request: RequestTag::current(),
+        }
+    }
+
+    /// Logs a record with the registered logger, or buffers the record when
+    /// called from another thread than the one which created the handle.
+    ///
+    /// Returns an error if the logger threw an exception. Records logged from
+    /// other threads always succeed.
+    ///
+    /// # Parameters
+    ///
+    /// * `level` - The severity of the record.
+    /// * `message` - The message, which may contain `{placeholders}` for
+    ///   values of the context.
+    /// * `context` - Values passed to the logger with the message.
+    pub fn log(&self, level: Level, message: &str, context: &[(&str, &str)]) -> Result<()> {
+        let record = Record {
+            request: self.request,
+            level,
+            message: message.into(),
+            context: context
+                .iter()
+                .map(|(key, value)| ((*key).into(), (*value).into()))
+                .collect(),
+        };
+
+        if thread::current().id() != self.request.thread {
+            PENDING.push(record);
+            return Ok(());
+        }
+        if RequestTag::current() != self.request {
+            return Ok(());
+        }
+
+        flush_current()?;
+        write(record)
+    }
+
+    /// Passes the records buffered from other threads to the registered
+    /// logger.
+    ///
+    /// Does nothing when called from another thread than the one which created
+    /// the handle.
+    pub fn flush(&self) -> Result<()> {
+        if thread::current().id() != self.request.thread {
+            return Ok(());
+        }
+        flush_current()
+    }
+
+    /// Builds the `ExtPhpRs\Logger` class, registering it with PHP. This
+    /// function should only be called once inside your module startup
+    /// function.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the function is called more than once.
+    pub fn build() {
+        if !LOGGER_CE.load(Ordering::Acquire).is_null() {
+            panic!("Logger has already been built.");
+        }
+
+        let ce = ClassBuilder::new("ExtPhpRs\\Logger")
+            .flags(ClassFlags::Final)
+            .static_property("logger", (), PropertyFlags::Private)
+            .method(
+                FunctionBuilder::new("register", Self::register)
+                    .arg(Arg::new("logger", DataType::Object(None)).allow_null())
+                    .returns(DataType::Void, false, false)
+                    .build()
+                    .expect("Failed to build `ExtPhpRs\\Logger` PHP class."),
+                MethodFlags::Public | MethodFlags::Static,
+            )
+            .method(
+                FunctionBuilder::new("flush", Self::flush_php)
+                    .returns(DataType::Void, false, false)
+                    .build()
+                    .expect("Failed to build `ExtPhpRs\\Logger` PHP class."),
+                MethodFlags::Public | MethodFlags::Static,
+            )
+            .build()
+            .expect("Failed to build `ExtPhpRs\\Logger` PHP class.");
+        LOGGER_CE.store(ce, Ordering::Release);
+    }
+
+    zend_fastcall! {
+        extern "C" fn register(ex: &mut ExecuteData, _: &mut Zval) {
+            let mut logger = Arg::new("logger", DataType::Object(None)).allow_null();
+            if ex.parser().arg(&mut logger).parse().is_err() {
+                return;
+            }
+
+            let logger = logger
+                .zval()
+                .map(|zv| zv.shallow_clone())
+                .unwrap_or_default();
+            // The static property is reset at the end of each request, so the
+            // first logger registered by a request starts a new generation.
+            let registered = class_entry()
+                .get_static_property("logger")
+                .is_some_and(|logger| logger.is_object());
+            if !registered {
+                GENERATION.with(|generation| generation.set(generation.get() + 1));
+            }
+            let result = class_entry()
+                .set_static_property("logger", logger)
+                .and_then(|_| flush_current());
+            throw(result);
+        }
+    }
+
+    zend_fastcall! {
+        extern "C" fn flush_php(ex: &mut ExecuteData, _: &mut Zval) {
+            if ex.parser().parse().is_err() {
+                return;
+            }
+
+            throw(flush_current());
+        }
+    }
+}
+
+/// Returns the class entry of `ExtPhpRs\Logger`.
+fn class_entry() -> &'static ClassEntry {
+    // SAFETY: The class is built in the module startup function, before any
+    // request runs.
+    unsafe { LOGGER_CE.load(Ordering::Acquire).as_ref() }
+        .expect("`ExtPhpRs\\Logger` has not been built")
+}
+
+/// Passes the records buffered for the current request to the logger, and
+/// drops the records buffered for earlier requests on the current thread.
+fn flush_current() -> Result<()> {
+    let current = RequestTag::current();
+    PENDING
+        .take(|record| record.request.thread == current.thread)
+        .into_iter()
+        .filter(|record| record.request == current)
+        .try_for_each(write)
+}
+
+/// Passes a record to the registered logger.
+fn write(record: Record) -> Result<()> {
+    let mut logger = match class_entry().get_static_property("logger") {
+        Some(logger) => logger.shallow_clone(),
+        None => return Ok(()),
+    };
+    let logger = match logger.object_mut() {
+        Some(logger) => logger,
+        None => return Ok(()),
+    };
+
+    let mut context = ZendHashTable::with_capacity(record.context.len() as _);
+    for (key, value) in record.context {
+        context.insert(&key, value)?;
+    }
+
+    logger.call_method_in_scope(
+        "log",
+        &[
+            record.level.as_str().into_zval(false)?,
+            record.message.into_zval(false)?,
+            context.into_zval(false)?,
+        ],
+        None,
+    )?;
+    Ok(())
+}
+
+/// Throws the error returned by the logger, if any.
+fn throw(result: Result<()>) {
+    let err = match result {
+        Ok(()) => return,
+        Err(Error::Exception(e)) => e.into_zval(false).and_then(throw_object),
+        Err(err) => PhpException::default(err.to_string()).throw(),
+    };
+    err.expect("Failed to throw exception from logger");
+}
@@ -0,0 +1,425 @@
+use ::serde::de::{
+    self, value::StrDeserializer, DeserializeSeed, IntoDeserializer, Unexpected, Visitor,
+};
+use ::serde::forward_to_deserialize_any;
+
+use super::{Error, Result};
+use crate::{
+    flags::DataType,
+    types::{RawElement, Values, ZendHashTable, ZendStr, Zval},
+};
+
+/// The maximum depth of nested arrays and objects, which stops arrays
+/// containing a reference to themselves from being deserialized forever.
+const MAX_DEPTH: usize = 128;
+
+/// Deserializes Rust values from [`Zval`]s and hashtables. Usually used
+/// through [`from_zval`](super::from_zval).
+///
+/// Arrays and objects can be nested at most 128 levels deep. Only the public
+/// properties of objects are deserialized.
+#[derive(Debug, Clone, Copy)]
+pub struct Deserializer<'de> {
+    input: Input<'de>,
+    depth: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Input<'de> {
+    Zval(&'de Zval),
+    Array(&'de ZendHashTable),
+}
+
+impl<'de> Deserializer<'de> {
+    /// Creates a deserializer reading from a zval.
+    ///
+    /// # Parameters
+    ///
+    /// * `zv` - The zval to deserialize.
+    pub fn new(zv: &'de Zval) -> Self {
+        Self::nested(zv, 0)
+    }
+
+    /// Creates a deserializer reading from a zval nested in an array or
+    /// object.
+    fn nested(zv: &'de Zval, depth: usize) -> Self {
+        // Properties of objects are stored as indirect zvals.
+        let zv = zv.indirect().unwrap_or(zv).dereference();
+        let input = match zv.array() {
+            Some(ht) => Input::Array(ht),
+            None => Input::Zval(zv),
+        };
+        Self { input, depth }
+    }
+
+    /// Creates a deserializer reading from an array.
+    ///
+    /// # Parameters
+    ///
+    /// * `ht` - The array to deserialize.
+    pub fn from_array(ht: &'de ZendHashTable) -> Self {
+        Self {
+            input: Input::Array(ht),
+            depth: 0,
+        }
+    }
+
+    /// Returns the depth of the values nested in the array or object being
+    /// deserialized, or an error if it exceeds [`MAX_DEPTH`].
+    fn child_depth(&self) -> Result<usize> {
+        if self.depth >= MAX_DEPTH {
+            return Err(Error(format!(
+                "Arrays and objects cannot be nested more than {} levels deep",
+                MAX_DEPTH
+            )));
+        }
+        Ok(self.depth + 1)
+    }
+
+    fn unexpected(zv: &Zval) -> Error {
+        Error(format!(
+            "Cannot deserialize a value of type {}",
+            zv.get_type()
+        ))
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let zv = match self.input {
+            Input::Zval(zv) => zv,
+            Input::Array(ht) if ht.has_sequential_keys() => return self.deserialize_seq(visitor),
+            Input::Array(_) => return self.deserialize_map(visitor),
+        };
+
+        match zv.get_type() {
+            DataType::Undef | DataType::Null => visitor.visit_unit(),
+            DataType::False | DataType::True | DataType::Bool => {
+                visitor.visit_bool(zv.bool().unwrap_or_default())
+            }
+            DataType::Long => visitor.visit_i64(zv.long().unwrap_or_default()),
+            DataType::Double => visitor.visit_f64(zv.double().unwrap_or_default()),
+            DataType::String => match zv.str() {
+                Some(s) => visitor.visit_borrowed_str(s),
+                None => visitor.visit_borrowed_bytes(zv.bytes().unwrap_or_default()),
+            },
+            DataType::Object(_) => self.deserialize_map(visitor),
+            _ => Err(Self::unexpected(zv)),
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.input {
+            Input::Zval(zv) if zv.is_string() => {
+                visitor.visit_borrowed_bytes(zv.bytes().unwrap_or_default())
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.input {
+            Input::Zval(zv) if zv.is_null() || zv.is_undef() => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.input {
+            Input::Array(ht) => visitor.visit_seq(SeqAccess {
+                values: ht.values(),
+                depth: self.child_depth()?,
+            }),
+            Input::Zval(zv) => Err(de::Error::invalid_type(unexpected(zv), &visitor)),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let depth = self.child_depth()?;
+        let (ht, public_only) = match self.input {
+            Input::Array(ht) => (ht, false),
+            Input::Zval(zv) => match zv.object() {
+                Some(obj) => (obj.get_properties()?, true),
+                None => return Err(de::Error::invalid_type(unexpected(zv), &visitor)),
+            },
+        };
+        visitor.visit_map(MapAccess {
+            elements: Box::new(ht.raw_elements()),
+            len: ht.len(),
+            public_only,
+            value: None,
+            depth,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self.input {
+            Input::Zval(zv) if zv.is_string() => {
+                let variant: StrDeserializer<'_, Error> =
+                    zv.str().unwrap_or_default().into_deserializer();
+                visitor.visit_enum(variant)
+            }
+            Input::Array(ht) if ht.len() == 1 => match ht.raw_elements().next() {
+                Some(element) => visitor.visit_enum(EnumAccess {
+                    variant: Key::from(&element),
+                    value: element.val,
+                    depth: self.child_depth()?,
+                }),
+                None => Err(Error("Expected an array with a single element".into())),
+            },
+            _ => Err(Error(
+                "Expected a string or an array with a single element".into(),
+            )),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        unit unit_struct identifier ignored_any
+    }
+}
+
+fn unexpected(zv: &Zval) -> Unexpected<'_> {
+    match zv.get_type() {
+        DataType::Undef | DataType::Null => Unexpected::Unit,
+        DataType::False | DataType::True | DataType::Bool => {
+            Unexpected::Bool(zv.bool().unwrap_or_default())
+        }
+        DataType::Long => Unexpected::Signed(zv.long().unwrap_or_default()),
+        DataType::Double => Unexpected::Float(zv.double().unwrap_or_default()),
+        DataType::String => match zv.str() {
+            Some(s) => Unexpected::Str(s),
+            None => Unexpected::Bytes(zv.bytes().unwrap_or_default()),
+        },
+        _ => Unexpected::Other("PHP value"),
+    }
+}
+
+/// Presents the values of an array as a sequence.
+struct SeqAccess<'de> {
+    values: Values<'de>,
+    depth: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for SeqAccess<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.values.next() {
+            Some(zv) => seed
+                .deserialize(Deserializer::nested(zv, self.depth))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+/// Presents the elements of an array, or the properties of an object, as a
+/// map.
+struct MapAccess<'de> {
+    elements: Box<dyn Iterator<Item = RawElement<'de>> + 'de>,
+    len: usize,
+    /// Whether protected and private properties, which are stored under
+    /// mangled names starting with a NUL byte, are skipped.
+    public_only: bool,
+    value: Option<&'de Zval>,
+    depth: usize,
+}
+
+impl<'de> de::MapAccess<'de> for MapAccess<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let public_only = self.public_only;
+        let element = self.elements.by_ref().find(|element| match element.key {
+            Some(key) if public_only => key.as_bytes().first() != Some(&0),
+            _ => true,
+        });
+        match element {
+            Some(element) => {
+                self.len = self.len.saturating_sub(1);
+                self.value = Some(element.val);
+                seed.deserialize(KeyDeserializer(Key::from(&element)))
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| Error("Map value was deserialized before its key".into()))?;
+        seed.deserialize(Deserializer::nested(value, self.depth))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        // Skipped properties are not known in advance.
+        (!self.public_only).then_some(self.len)
+    }
+}
+
+/// The key of an array element, borrowed from the array.
+#[derive(Clone, Copy)]
+enum Key<'de> {
+    Long(i64),
+    String(&'de ZendStr),
+}
+
+impl<'de> From<&RawElement<'de>> for Key<'de> {
+    fn from(element: &RawElement<'de>) -> Self {
+        match element.key {
+            Some(key) => Self::String(key),
+            None => Self::Long(element.h as i64),
+        }
+    }
+}
+
+/// Deserializes the key of an array element. Integer keys can be read as
+/// strings, as PHP stores numeric string keys as integers. String keys which
+/// are not valid UTF-8 are passed to the visitor as bytes.
+struct KeyDeserializer<'de>(Key<'de>);
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Key::Long(key) => visitor.visit_i64(key),
+            Key::String(_) => self.deserialize_str(visitor),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Key::Long(key) => visitor.visit_string(key.to_string()),
+            Key::String(key) => match key.as_str() {
+                Ok(key) => visitor.visit_borrowed_str(key),
+                Err(_) => visitor.visit_borrowed_bytes(key.as_bytes()),
+            },
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char bytes byte_buf
+        option unit unit_struct seq tuple tuple_struct map struct enum ignored_any
+    }
+}
+
+/// Deserializes an enum variant stored as an array with a single element.
+struct EnumAccess<'de> {
+    variant: Key<'de>,
+    value: &'de Zval,
+    depth: usize,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumAccess<'de> {
+    type Error = Error;
+    type Variant = Deserializer<'de>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Deserializer<'de>)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(KeyDeserializer(self.variant))?;
+        Ok((variant, Deserializer::nested(self.value, self.depth)))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Deserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
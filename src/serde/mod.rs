@@ -0,0 +1,177 @@
+//! Conversions between PHP values and Rust types implementing
+//! [`Serialize`] and [`Deserialize`].
+//!
+//! Values are converted directly into and out of [`Zval`]s and hashtables,
+//! without going through an intermediate format such as JSON:
+//!
+//! * Booleans, integers, floats and strings map onto their PHP equivalents.
+//!   Unsigned integers which do not fit in a PHP integer cannot be
+//!   serialized.
+//! * `None`, `()` and unit structs map onto `null`.
+//! * Sequences and tuples map onto lists, and maps and structs map onto
+//!   arrays with string or integer keys.
+//! * Unit enum variants map onto their name, and other variants map onto an
+//!   array with a single element whose key is the name of the variant.
+//!
+//! When deserializing, arrays whose keys are sequential are presented as
+//! sequences to self-describing types such as `serde_json::Value`, and as
+//! maps otherwise. Objects are deserialized from their public properties.
+//! String keys which are not valid UTF-8 are passed to the visitor as bytes,
+//! and arrays and objects nested more than 128 levels deep, such as arrays
+//! containing a reference to themselves, are rejected.
+//!
+//! ```no_run
+//! use ext_php_rs::{prelude::*, types::Zval};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Config {
+//!     host: String,
+//!     ports: Vec<u16>,
+//! }
+//!
+//! #[php_function]
+//! pub fn normalize_config(config: &Zval) -> PhpResult<Zval> {
+//!     let config: Config = ext_php_rs::serde::from_zval(config)?;
+//!     Ok(ext_php_rs::serde::to_zval(&config)?)
+//! }
+//! # fn main() {}
+//! ```
+
+mod de;
+mod ser;
+
+use std::fmt::{self, Display};
+
+use ::serde::{Deserialize, Serialize};
+
+use crate::{
+    boxed::ZBox,
+    exception::PhpException,
+    types::{ZendHashTable, Zval},
+};
+
+pub use de::Deserializer;
+pub use ser::Serializer;
+
+/// An error which occurred while serializing or deserializing a PHP value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error(String);
+
+/// Result type returned by the serde conversions.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ::serde::ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl ::serde::de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl From<crate::error::Error> for Error {
+    fn from(err: crate::error::Error) -> Self {
+        Self(err.to_string())
+    }
+}
+
+impl From<Error> for PhpException {
+    fn from(err: Error) -> Self {
+        Self::default(err.0)
+    }
+}
+
+/// Serializes a Rust value into a PHP value.
+///
+/// # Parameters
+///
+/// * `value` - The value to serialize.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::collections::HashMap;
+///
+/// let zv = ext_php_rs::serde::to_zval(&HashMap::from([("a", 1)])).unwrap();
+/// assert_eq!(zv.array().and_then(|ht| ht.get_or_default::<i64>("a")), Some(1));
+/// ```
+pub fn to_zval<T>(value: &T) -> Result<Zval>
+where
+    T: Serialize + ?Sized,
+{
+    value.serialize(Serializer)
+}
+
+/// Serializes a Rust value into a PHP array. Returns an error if the value
+/// is not serialized as an array, e.g. if it is a string.
+///
+/// # Parameters
+///
+/// * `value` - The value to serialize.
+pub fn to_array<T>(value: &T) -> Result<ZBox<ZendHashTable>>
+where
+    T: Serialize + ?Sized,
+{
+    let zv = to_zval(value)?;
+    let ht = match zv.array() {
+        Some(ht) => ht as *const ZendHashTable as *mut ZendHashTable,
+        None => {
+            return Err(Error(format!(
+                "Expected a value serialized as an array, got {}",
+                zv.get_type()
+            )))
+        }
+    };
+
+    // SAFETY: The array was created by the serializer and is only referenced by the
+    // zval, which hands its reference over to the box.
+    zv.release();
+    Ok(unsafe { ZBox::from_raw(ht) })
+}
+
+/// Deserializes a Rust value from a PHP value. Strings are borrowed from the
+/// PHP value where possible.
+///
+/// # Parameters
+///
+/// * `zv` - The value to deserialize.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::types::Zval;
+///
+/// fn ports(zv: &Zval) -> Vec<u16> {
+///     ext_php_rs::serde::from_zval(zv).unwrap_or_default()
+/// }
+/// ```
+pub fn from_zval<'de, T>(zv: &'de Zval) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    T::deserialize(Deserializer::new(zv))
+}
+
+/// Deserializes a Rust value from a PHP array.
+///
+/// # Parameters
+///
+/// * `ht` - The array to deserialize.
+pub fn from_array<'de, T>(ht: &'de ZendHashTable) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    T::deserialize(Deserializer::from_array(ht))
+}
@@ -0,0 +1,396 @@
+use std::convert::TryFrom;
+
+use ::serde::ser::{self, Serialize};
+
+use super::{Error, Result};
+use crate::{
+    boxed::ZBox,
+    types::{canonical_index, ZendHashTable, Zval},
+};
+
+/// Serializes Rust values into [`Zval`]s. Usually used through
+/// [`to_zval`](super::to_zval).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Serializer;
+
+fn array_zval(ht: ZBox<ZendHashTable>) -> Zval {
+    let mut zv = Zval::new();
+    zv.set_hashtable(ht);
+    zv
+}
+
+/// Wraps the value of an enum variant in an array with the name of the
+/// variant as its only key.
+fn variant_zval(variant: &'static str, value: Zval) -> Result<Zval> {
+    let mut ht = ZendHashTable::new();
+    ht.insert(variant, value)?;
+    Ok(array_zval(ht))
+}
+
+/// Inserts a value into an array under a serialized key. String keys which
+/// are integers in canonical form are inserted as integer keys, as PHP does.
+fn insert_key(ht: &mut ZendHashTable, key: &Zval, value: Zval) -> Result<()> {
+    if let Some(key) = key.long() {
+        ht.insert_at_index(key as u64, value)?;
+    } else if let Some(key) = key.str() {
+        match canonical_index(key) {
+            Some(index) => ht.insert_at_index(index, value)?,
+            None => ht.insert(key, value)?,
+        }
+    } else {
+        return Err(Error(format!(
+            "Array keys must be integers or strings, got {}",
+            key.get_type()
+        )));
+    }
+    Ok(())
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = Zval;
+    type Error = Error;
+    type SerializeSeq = SerializeList;
+    type SerializeTuple = SerializeList;
+    type SerializeTupleStruct = SerializeList;
+    type SerializeTupleVariant = SerializeList;
+    type SerializeMap = SerializeArray;
+    type SerializeStruct = SerializeArray;
+    type SerializeStructVariant = SerializeArray;
+
+    fn serialize_bool(self, v: bool) -> Result<Zval> {
+        let mut zv = Zval::new();
+        zv.set_bool(v);
+        Ok(zv)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Zval> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Zval> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Zval> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Zval> {
+        let mut zv = Zval::new();
+        zv.set_long(v);
+        Ok(zv)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Zval> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Zval> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Zval> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Zval> {
+        let v = i64::try_from(v).map_err(|_| crate::error::Error::IntegerOverflow)?;
+        self.serialize_i64(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Zval> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Zval> {
+        let mut zv = Zval::new();
+        zv.set_double(v);
+        Ok(zv)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Zval> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Zval> {
+        let mut zv = Zval::new();
+        zv.set_string(v, false)?;
+        Ok(zv)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Zval> {
+        let mut zv = Zval::new();
+        zv.set_bytes(v, false);
+        Ok(zv)
+    }
+
+    fn serialize_none(self) -> Result<Zval> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Zval>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Zval> {
+        Ok(Zval::new())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Zval> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<Zval> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _: &'static str, value: &T) -> Result<Zval>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Zval>
+    where
+        T: Serialize + ?Sized,
+    {
+        variant_zval(variant, value.serialize(self)?)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<SerializeList> {
+        Ok(SerializeList::new(None))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<SerializeList> {
+        Ok(SerializeList::new(None))
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<SerializeList> {
+        Ok(SerializeList::new(None))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<SerializeList> {
+        Ok(SerializeList::new(Some(variant)))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<SerializeArray> {
+        Ok(SerializeArray::new(None))
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<SerializeArray> {
+        Ok(SerializeArray::new(None))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<SerializeArray> {
+        Ok(SerializeArray::new(Some(variant)))
+    }
+}
+
+/// Serializes sequences and tuples into lists.
+pub struct SerializeList {
+    ht: ZBox<ZendHashTable>,
+    variant: Option<&'static str>,
+}
+
+impl SerializeList {
+    fn new(variant: Option<&'static str>) -> Self {
+        Self {
+            ht: ZendHashTable::new(),
+            variant,
+        }
+    }
+
+    fn push<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.ht.push(value.serialize(Serializer)?)?;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Zval> {
+        let zv = array_zval(self.ht);
+        match self.variant {
+            Some(variant) => variant_zval(variant, zv),
+            None => Ok(zv),
+        }
+    }
+}
+
+impl ser::SerializeSeq for SerializeList {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Zval> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SerializeList {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Zval> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeList {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Zval> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeList {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Zval> {
+        self.finish()
+    }
+}
+
+/// Serializes maps and structs into arrays.
+pub struct SerializeArray {
+    ht: ZBox<ZendHashTable>,
+    key: Option<Zval>,
+    variant: Option<&'static str>,
+}
+
+impl SerializeArray {
+    fn new(variant: Option<&'static str>) -> Self {
+        Self {
+            ht: ZendHashTable::new(),
+            key: None,
+            variant,
+        }
+    }
+
+    fn finish(self) -> Result<Zval> {
+        let zv = array_zval(self.ht);
+        match self.variant {
+            Some(variant) => variant_zval(variant, zv),
+            None => Ok(zv),
+        }
+    }
+}
+
+impl ser::SerializeMap for SerializeArray {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.key = Some(key.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error("Map value was serialized before its key".into()))?;
+        insert_key(&mut self.ht, &key, value.serialize(Serializer)?)
+    }
+
+    fn end(self) -> Result<Zval> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for SerializeArray {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.ht.insert(key, value.serialize(Serializer)?)?;
+        Ok(())
+    }
+
+    fn end(self) -> Result<Zval> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeArray {
+    type Ok = Zval;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.ht.insert(key, value.serialize(Serializer)?)?;
+        Ok(())
+    }
+
+    fn end(self) -> Result<Zval> {
+        self.finish()
+    }
+}
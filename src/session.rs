@@ -0,0 +1,488 @@
+//! Access to the session of the current request, and session save handlers
+//! implemented in Rust.
+//!
+//! The values of the session are read and written through `$_SESSION`, once
+//! the session has been started by PHP code or with [`start`].
+//!
+//! A save handler implementing [`SaveHandler`] is set with
+//! [`set_save_handler`] in the module startup function. It is then used by
+//! the `ExtPhpRs\SessionHandler` class, which implements
+//! `SessionHandlerInterface`:
+//!
+//! ```php
+//! <?php
+//!
+//! namespace ExtPhpRs;
+//!
+//! final class SessionHandler implements \SessionHandlerInterface
+//! {
+//!     public function open(string $path, string $name): bool;
+//!     public function close(): bool;
+//!     public function read(string $id): string;
+//!     public function write(string $id, string $data): bool;
+//!     public function destroy(string $id): bool;
+//!     public function gc(int $max_lifetime): int;
+//! }
+//! ```
+//!
+//! The save handler of a request is selected with
+//! `session_set_save_handler(new ExtPhpRs\SessionHandler())`, or with
+//! [`install`] from the request startup function.
+//!
+//! The class is only registered when the session extension was started
+//! before the extension, as it has to implement `SessionHandlerInterface`.
+
+use std::{
+    ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use once_cell::sync::OnceCell;
+
+use crate::{
+    args::Arg,
+    builders::{ClassBuilder, FunctionBuilder, FunctionHandler},
+    convert::IntoZval,
+    error::{Error, Result},
+    exception::{PhpException, PhpResult},
+    flags::{ClassFlags, DataType, MethodFlags},
+    types::{ZendCallable, ZendHashTable, Zval},
+    zend::{ClassEntry, ExecuteData, ExecutorGlobals},
+    zend_fastcall,
+};
+
+/// Class entry of `ExtPhpRs\SessionHandler`.
+static HANDLER_CE: AtomicPtr<ClassEntry> = AtomicPtr::new(ptr::null_mut());
+
+/// The save handler set with [`set_save_handler`].
+static HANDLER: OnceCell<Box<dyn SaveHandler>> = OnceCell::new();
+
+/// The status of the session of the current request, as returned by
+/// `session_status()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Status {
+    /// The session extension is not loaded, or sessions are disabled.
+    Disabled,
+    /// Sessions are enabled, but no session has been started.
+    None,
+    /// A session has been started.
+    Active,
+}
+
+/// Returns the status of the session of the current request.
+pub fn status() -> Status {
+    let status = ZendCallable::try_from_name("session_status")
+        .and_then(|status| status.try_call(vec![]))
+        .ok()
+        .and_then(|status| status.long());
+
+    match status {
+        Some(2) => Status::Active,
+        Some(1) => Status::None,
+        _ => Status::Disabled,
+    }
+}
+
+/// Starts the session of the current request, as `session_start()` does.
+/// Does nothing if the session has already been started.
+///
+/// Returns [`Error::SessionStartFailure`] if the session could not be
+/// started, or [`Error::Exception`] if the save handler threw an exception.
+pub fn start() -> Result<()> {
+    match status() {
+        Status::Active => return Ok(()),
+        Status::Disabled => return Err(Error::SessionStartFailure),
+        Status::None => {}
+    }
+
+    let started = ZendCallable::try_from_name("session_start")?.try_call(vec![])?;
+    if started.bool() != Some(true) {
+        return Err(Error::SessionStartFailure);
+    }
+    Ok(())
+}
+
+/// Returns a value of `$_SESSION`, or [`None`] if the session has not been
+/// started or does not contain the key.
+///
+/// # Parameters
+///
+/// * `key` - The key of the value.
+pub fn get(key: &str) -> Option<Zval> {
+    with_vars(|vars| vars.get(key).map(Zval::shallow_clone))
+        .ok()
+        .flatten()
+}
+
+/// Sets a value of `$_SESSION`.
+///
+/// Returns [`Error::SessionInactive`] if the session has not been started.
+///
+/// # Parameters
+///
+/// * `key` - The key of the value.
+/// * `value` - The value to set.
+pub fn set(key: &str, value: impl IntoZval) -> Result<()> {
+    let value = value.into_zval(false)?;
+    with_vars(|vars| vars.insert(key, value))?
+}
+
+/// Removes a value from `$_SESSION`, returning whether the session contained
+/// the key.
+///
+/// Returns [`Error::SessionInactive`] if the session has not been started.
+///
+/// # Parameters
+///
+/// * `key` - The key of the value.
+pub fn remove(key: &str) -> Result<bool> {
+    with_vars(|vars| vars.remove(key).is_some())
+}
+
+/// Calls a function with the array of `$_SESSION`, separating it first if it
+/// is shared.
+fn with_vars<R>(f: impl FnOnce(&mut ZendHashTable) -> R) -> Result<R> {
+    if status() != Status::Active {
+        return Err(Error::SessionInactive);
+    }
+
+    let eg = ExecutorGlobals::get_mut();
+    let vars = eg
+        .symbol_table
+        .get_mut("_SESSION")
+        .map(Zval::dereference_mut)
+        .and_then(Zval::separate_array)
+        .ok_or(Error::SessionInactive)?;
+    Ok(f(vars))
+}
+
+/// A session save handler, storing the data of sessions.
+///
+/// The methods are called from the thread running the request. Errors are
+/// thrown as exceptions from the method of `ExtPhpRs\SessionHandler` which
+/// called the handler, which makes the calling session function fail.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::{collections::HashMap, sync::Mutex};
+///
+/// use ext_php_rs::{prelude::*, session::{self, SaveHandler}};
+///
+/// #[derive(Default)]
+/// struct MemoryStore(Mutex<HashMap<String, Vec<u8>>>);
+///
+/// impl SaveHandler for MemoryStore {
+///     fn read(&self, id: &str) -> PhpResult<Option<Vec<u8>>> {
+///         Ok(self.0.lock().unwrap().get(id).cloned())
+///     }
+///
+///     fn write(&self, id: &str, data: &[u8]) -> PhpResult<()> {
+///         self.0.lock().unwrap().insert(id.into(), data.into());
+///         Ok(())
+///     }
+///
+///     fn destroy(&self, id: &str) -> PhpResult<()> {
+///         self.0.lock().unwrap().remove(id);
+///         Ok(())
+///     }
+///
+///     fn gc(&self, _max_lifetime: i64) -> PhpResult<i64> {
+///         Ok(0)
+///     }
+/// }
+///
+/// #[php_startup]
+/// pub fn startup() {
+///     session::set_save_handler(MemoryStore::default());
+/// }
+/// # fn main() {}
+/// ```
+pub trait SaveHandler: Send + Sync {
+    /// Called when a session is started.
+    ///
+    /// # Parameters
+    ///
+    /// * `save_path` - The value of the `session.save_path` INI setting.
+    /// * `name` - The name of the session, e.g. `PHPSESSID`.
+    fn open(&self, _save_path: &str, _name: &str) -> PhpResult<()> {
+        Ok(())
+    }
+
+    /// Called when a session is closed, after its data has been written.
+    fn close(&self) -> PhpResult<()> {
+        Ok(())
+    }
+
+    /// Returns the serialized data of a session, or [`None`] if the session
+    /// does not exist.
+    ///
+    /// # Parameters
+    ///
+    /// * `id` - The ID of the session.
+    fn read(&self, id: &str) -> PhpResult<Option<Vec<u8>>>;
+
+    /// Stores the serialized data of a session.
+    ///
+    /// # Parameters
+    ///
+    /// * `id` - The ID of the session.
+    /// * `data` - The serialized data of the session.
+    fn write(&self, id: &str, data: &[u8]) -> PhpResult<()>;
+
+    /// Deletes a session.
+    ///
+    /// # Parameters
+    ///
+    /// * `id` - The ID of the session.
+    fn destroy(&self, id: &str) -> PhpResult<()>;
+
+    /// Deletes the sessions which have not been written to recently, returning
+    /// the number of deleted sessions.
+    ///
+    /// # Parameters
+    ///
+    /// * `max_lifetime` - The number of seconds after which a session is
+    ///   considered to be garbage.
+    fn gc(&self, max_lifetime: i64) -> PhpResult<i64>;
+}
+
+/// Sets the save handler used by `ExtPhpRs\SessionHandler`. This function
+/// should only be called once inside your module startup function.
+///
+/// # Panics
+///
+/// Panics if a save handler has already been set.
+///
+/// # Parameters
+///
+/// * `handler` - The save handler.
+pub fn set_save_handler<H: SaveHandler + 'static>(handler: H) {
+    if HANDLER.set(Box::new(handler)).is_err() {
+        panic!("A session save handler has already been set.");
+    }
+}
+
+/// Selects `ExtPhpRs\SessionHandler` as the save handler of the current
+/// request, as `session_set_save_handler(new ExtPhpRs\SessionHandler())`
+/// does. Usually called from the request startup function.
+///
+/// Returns [`Error::ClassNotFound`] if the class was not registered, or
+/// [`Error::SessionStartFailure`] if the session has already been started.
+pub fn install() -> Result<()> {
+    let ce =
+        class_entry().ok_or_else(|| Error::ClassNotFound("ExtPhpRs\\SessionHandler".into()))?;
+    let mut handler = Zval::new();
+    handler.set_object(&mut ce.new());
+
+    let set = ZendCallable::try_from_name("session_set_save_handler")?.try_call(vec![&handler])?;
+    if set.bool() != Some(true) {
+        return Err(Error::SessionStartFailure);
+    }
+    Ok(())
+}
+
+/// The `ExtPhpRs\SessionHandler` class.
+pub(crate) struct SessionHandler;
+
+impl SessionHandler {
+    /// Builds the `ExtPhpRs\SessionHandler` class, registering it with PHP.
+    /// The class is not registered when the session extension is not loaded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the function is called more than once.
+    pub(crate) fn build() {
+        if !HANDLER_CE.load(Ordering::Acquire).is_null() {
+            panic!("SessionHandler has already been built.");
+        }
+        let interface = match ClassEntry::try_find("SessionHandlerInterface") {
+            Some(interface) => interface,
+            None => return,
+        };
+
+        let method = |name, handler: FunctionHandler, args: &[(&str, DataType)], returns| {
+            let mut func = FunctionBuilder::new(name, handler);
+            for (arg, ty) in args {
+                func = func.arg(Arg::new(*arg, *ty));
+            }
+            func.returns(returns, false, false)
+                .build()
+                .expect("Failed to build `ExtPhpRs\\SessionHandler` PHP class.")
+        };
+
+        let ce = ClassBuilder::new("ExtPhpRs\\SessionHandler")
+            .flags(ClassFlags::Final)
+            .implements(interface)
+            .method(
+                method(
+                    "open",
+                    Self::open,
+                    &[("path", DataType::String), ("name", DataType::String)],
+                    DataType::Bool,
+                ),
+                MethodFlags::Public,
+            )
+            .method(
+                method("close", Self::close, &[], DataType::Bool),
+                MethodFlags::Public,
+            )
+            .method(
+                method(
+                    "read",
+                    Self::read,
+                    &[("id", DataType::String)],
+                    DataType::String,
+                ),
+                MethodFlags::Public,
+            )
+            .method(
+                method(
+                    "write",
+                    Self::write,
+                    &[("id", DataType::String), ("data", DataType::String)],
+                    DataType::Bool,
+                ),
+                MethodFlags::Public,
+            )
+            .method(
+                method(
+                    "destroy",
+                    Self::destroy,
+                    &[("id", DataType::String)],
+                    DataType::Bool,
+                ),
+                MethodFlags::Public,
+            )
+            .method(
+                method(
+                    "gc",
+                    Self::gc,
+                    &[("max_lifetime", DataType::Long)],
+                    DataType::Long,
+                ),
+                MethodFlags::Public,
+            )
+            .build()
+            .expect("Failed to build `ExtPhpRs\\SessionHandler` PHP class.");
+        HANDLER_CE.store(ce, Ordering::Release);
+    }
+
+    zend_fastcall! {
+        extern "C" fn open(ex: &mut ExecuteData, retval: &mut Zval) {
+            let mut path = Arg::new("path", DataType::String);
+            let mut name = Arg::new("name", DataType::String);
+            if ex.parser().arg(&mut path).arg(&mut name).parse().is_err() {
+                return;
+            }
+
+            let path = path.zval().and_then(|zv| zv.str()).unwrap_or_default();
+            let name = name.zval().and_then(|zv| zv.str()).unwrap_or_default();
+            if let Some(()) = call(|handler| handler.open(path, name)) {
+                retval.set_bool(true);
+            }
+        }
+    }
+
+    zend_fastcall! {
+        extern "C" fn close(ex: &mut ExecuteData, retval: &mut Zval) {
+            if ex.parser().parse().is_err() {
+                return;
+            }
+
+            if let Some(()) = call(|handler| handler.close()) {
+                retval.set_bool(true);
+            }
+        }
+    }
+
+    zend_fastcall! {
+        extern "C" fn read(ex: &mut ExecuteData, retval: &mut Zval) {
+            let mut id = Arg::new("id", DataType::String);
+            if ex.parser().arg(&mut id).parse().is_err() {
+                return;
+            }
+
+            let id = id.zval().and_then(|zv| zv.str()).unwrap_or_default();
+            if let Some(data) = call(|handler| handler.read(id)) {
+                retval.set_bytes(&data.unwrap_or_default(), false);
+            }
+        }
+    }
+
+    zend_fastcall! {
+        extern "C" fn write(ex: &mut ExecuteData, retval: &mut Zval) {
+            let mut id = Arg::new("id", DataType::String);
+            let mut data = Arg::new("data", DataType::String);
+            if ex.parser().arg(&mut id).arg(&mut data).parse().is_err() {
+                return;
+            }
+
+            let id = id.zval().and_then(|zv| zv.str()).unwrap_or_default();
+            let data = data
+                .zval()
+                .and_then(|zv| zv.zend_str())
+                .map(|data| data.as_bytes())
+                .unwrap_or_default();
+            if let Some(()) = call(|handler| handler.write(id, data)) {
+                retval.set_bool(true);
+            }
+        }
+    }
+
+    zend_fastcall! {
+        extern "C" fn destroy(ex: &mut ExecuteData, retval: &mut Zval) {
+            let mut id = Arg::new("id", DataType::String);
+            if ex.parser().arg(&mut id).parse().is_err() {
+                return;
+            }
+
+            let id = id.zval().and_then(|zv| zv.str()).unwrap_or_default();
+            if let Some(()) = call(|handler| handler.destroy(id)) {
+                retval.set_bool(true);
+            }
+        }
+    }
+
+    zend_fastcall! {
+        extern "C" fn gc(ex: &mut ExecuteData, retval: &mut Zval) {
+            let mut max_lifetime = Arg::new("max_lifetime", DataType::Long);
+            if ex.parser().arg(&mut max_lifetime).parse().is_err() {
+                return;
+            }
+
+            let max_lifetime = max_lifetime.zval().and_then(|zv| zv.long()).unwrap_or_default();
+            if let Some(deleted) = call(|handler| handler.gc(max_lifetime)) {
+                retval.set_long(deleted);
+            }
+        }
+    }
+}
+
+/// Returns the class entry of `ExtPhpRs\SessionHandler`, if it was
+/// registered.
+fn class_entry() -> Option<&'static ClassEntry> {
+    // SAFETY: The class is built in the module startup function, before any
+    // request runs.
+    unsafe { HANDLER_CE.load(Ordering::Acquire).as_ref() }
+}
+
+/// Calls the save handler, throwing an exception if it returns an error or
+/// no save handler has been set.
+fn call<T>(f: impl FnOnce(&dyn SaveHandler) -> PhpResult<T>) -> Option<T> {
+    let result = match HANDLER.get() {
+        Some(handler) => f(handler.as_ref()),
+        None => Err(PhpException::default(
+            "No session save handler has been set.".into(),
+        )),
+    };
+
+    match result {
+        Ok(value) => Some(value),
+        Err(e) => {
+            e.throw()
+                .expect("Failed to throw exception from session handler");
+            None
+        }
+    }
+}
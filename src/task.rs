@@ -0,0 +1,363 @@
+//! A pool of worker threads for running Rust code in the background.
+//!
+//! PHP requests are single-threaded, but an extension may want to run
+//! expensive Rust code in parallel, or continue working after a PHP function
+//! has returned. A [`TaskPool`] runs closures on a fixed set of worker threads,
+//! and returns a [`TaskHandle`] which can be used to retrieve the result later
+//! in the same request.
+//!
+//! Results can also be stored in the pool under a key with
+//! [`TaskPool::spawn_stored`], which allows a later request to pick the result
+//! up with [`TaskPool::take_stored`].
+//!
+//! Closures submitted to the pool must be [`Send`] and `'static`. PHP values
+//! such as [`Zval`], [`ZendObject`] and [`ZBox`] are bound to the request and
+//! the thread that created them, and do not implement [`Send`], so they cannot
+//! be captured by a task:
+//!
+//! ```compile_fail
+//! use ext_php_rs::{task::TaskPool, types::Zval};
+//!
+//! let zv = Zval::new();
+//! TaskPool::global().spawn(move || drop(zv));
+//! ```
+//!
+//! Convert values into owned Rust types before submitting a task, and convert
+//! the result back into a PHP value once the task has finished.
+//!
+//! [`Zval`]: crate::types::Zval
+//! [`ZendObject`]: crate::types::ZendObject
+//! [`ZBox`]: crate::boxed::ZBox
+
+use std::{
+    any::Any,
+    collections::HashMap,
+    fmt::Display,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::mpsc::{self, Receiver, Sender, TryRecvError},
+    thread::{self, JoinHandle},
+};
+
+use once_cell::sync::{Lazy, OnceCell};
+use parking_lot::Mutex;
+
+use crate::exception::PhpException;
+
+/// A job which is run on a worker thread.
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// The global task pool, created on first use.
+static GLOBAL_POOL: OnceCell<TaskPool> = OnceCell::new();
+
+/// Tasks spawned with [`TaskPool::spawn_stored`], keyed by name.
+type StoredTasks = HashMap<String, TaskHandle<Box<dyn Any + Send>>>;
+
+/// Results of tasks spawned with [`TaskPool::spawn_stored`].
+static STORED: Lazy<Mutex<StoredTasks>> = Lazy::new(Default::default);
+
+/// A fixed-size pool of worker threads.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::task::TaskPool;
+///
+/// let pool = TaskPool::new(4);
+/// let handle = pool.spawn(|| (1..=10).product::<u64>());
+///
+/// assert_eq!(handle.join().unwrap(), 3628800);
+/// ```
+pub struct TaskPool {
+    sender: Mutex<Option<Sender<Job>>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl TaskPool {
+    /// Creates a new task pool with a given number of worker threads.
+    ///
+    /// # Parameters
+    ///
+    /// * `threads` - The number of worker threads. At least one thread is
+    ///   always created.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operating system fails to create a thread.
+    pub fn new(threads: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = std::sync::Arc::new(Mutex::new(receiver));
+
+        let workers = (0..threads.max(1))
+            .map(|i| {
+                let receiver = receiver.clone();
+                thread::Builder::new()
+                    .name(format!("ext-php-rs-task-{}", i))
+                    .spawn(move || loop {
+                        // The lock is released before the job is run, allowing other workers
+                        // to pick up jobs in the meantime.
+                        let job = receiver.lock().recv();
+                        match job {
+                            Ok(job) => job(),
+                            Err(_) => break,
+                        }
+                    })
+                    .expect("Failed to spawn task pool worker thread")
+            })
+            .collect();
+
+        Self {
+            sender: Mutex::new(Some(sender)),
+            workers,
+        }
+    }
+
+    /// Returns the global task pool, creating it if it does not exist yet.
+    ///
+    /// The global pool has one worker thread per available CPU, and lives
+    /// until the process exits.
+    pub fn global() -> &'static Self {
+        GLOBAL_POOL.get_or_init(|| {
+            Self::new(
+                thread::available_parallelism()
+                    .map(|n| n.get())
+                    .unwrap_or(1),
+            )
+        })
+    }
+
+    /// Returns the number of worker threads in the pool.
+    pub fn threads(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Submits a closure to be run on the pool.
+    ///
+    /// Returns a handle which can be used to retrieve the result of the
+    /// closure. If the closure panics, the panic is caught and returned as a
+    /// [`TaskError::Panicked`] when joining the handle.
+    ///
+    /// # Parameters
+    ///
+    /// * `f` - The closure to run.
+    pub fn spawn<F, T>(&self, f: F) -> TaskHandle<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let job: Job = Box::new(move || {
+            // The handle may have been dropped, in which case nobody is interested in the
+            // result.
+            let _ = sender.send(catch_unwind(AssertUnwindSafe(f)));
+        });
+
+        // If the pool has been shut down the job is dropped, which disconnects the
+        // result channel and results in `TaskError::Cancelled` when joined.
+        if let Some(pool) = self.sender.lock().as_ref() {
+            let _ = pool.send(job);
+        }
+
+        TaskHandle {
+            receiver,
+            result: None,
+        }
+    }
+
+    /// Submits a closure to be run on the pool, storing the result under the
+    /// given key once it is available.
+    ///
+    /// The result is stored globally, outliving the current request, and can
+    /// be retrieved with [`TaskPool::take_stored`]. Spawning a task with a key
+    /// that is already in use replaces the previous task, whose result is
+    /// discarded.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key used to retrieve the result.
+    /// * `f` - The closure to run.
+    pub fn spawn_stored<K, F, T>(&self, key: K, f: F)
+    where
+        K: Into<String>,
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let handle = self.spawn(move || Box::new(f()) as Box<dyn Any + Send>);
+        STORED.lock().insert(key.into(), handle);
+    }
+
+    /// Attempts to take the result of a task spawned with
+    /// [`TaskPool::spawn_stored`].
+    ///
+    /// Returns [`None`] if there is no task stored under the key, or if the
+    /// task has not finished yet. Once a result has been returned, the key is
+    /// removed from the store.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key the task was stored under.
+    pub fn take_stored<T: 'static>(key: &str) -> Option<Result<T, TaskError>> {
+        let mut stored = STORED.lock();
+        let result = stored.get_mut(key)?.try_take()?;
+        stored.remove(key);
+
+        Some(result.and_then(|val| {
+            val.downcast::<T>()
+                .map(|val| *val)
+                .map_err(|_| TaskError::InvalidType)
+        }))
+    }
+
+    /// Returns whether a task has been stored under the given key, regardless
+    /// of whether it has finished.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key the task was stored under.
+    pub fn has_stored(key: &str) -> bool {
+        STORED.lock().contains_key(key)
+    }
+}
+
+impl Drop for TaskPool {
+    fn drop(&mut self) {
+        // Dropping the sender disconnects the job channel, which causes the workers to
+        // exit once they have finished the remaining jobs.
+        self.sender.lock().take();
+
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// A handle to the result of a task running on a [`TaskPool`].
+///
+/// Dropping the handle does not cancel the task, the result is simply
+/// discarded.
+pub struct TaskHandle<T> {
+    receiver: Receiver<thread::Result<T>>,
+    result: Option<Result<T, TaskError>>,
+}
+
+impl<T> TaskHandle<T> {
+    /// Returns whether the task has finished running.
+    pub fn is_finished(&mut self) -> bool {
+        self.poll();
+        self.result.is_some()
+    }
+
+    /// Attempts to take the result of the task without blocking.
+    ///
+    /// Returns [`None`] if the task has not finished yet, or if the result has
+    /// already been taken.
+    pub fn try_take(&mut self) -> Option<Result<T, TaskError>> {
+        self.poll();
+        self.result.take()
+    }
+
+    /// Blocks the current thread until the task has finished, returning the
+    /// result.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`TaskError`] if the task panicked or was never run.
+    pub fn join(mut self) -> Result<T, TaskError> {
+        match self.result.take() {
+            Some(result) => result,
+            None => Self::convert(self.receiver.recv().map_err(|_| TryRecvError::Disconnected)),
+        }
+    }
+
+    /// Moves the result of the task into the handle if it has finished.
+    fn poll(&mut self) {
+        if self.result.is_none() {
+            match self.receiver.try_recv() {
+                Err(TryRecvError::Empty) => {}
+                result => self.result = Some(Self::convert(result)),
+            }
+        }
+    }
+
+    fn convert(result: Result<thread::Result<T>, TryRecvError>) -> Result<T, TaskError> {
+        match result {
+            Ok(Ok(val)) => Ok(val),
+            Ok(Err(panic)) => Err(TaskError::Panicked(
+                panic
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "Box<dyn Any>".into()),
+            )),
+            Err(_) => Err(TaskError::Cancelled),
+        }
+    }
+}
+
+/// Errors returned when retrieving the result of a task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskError {
+    /// The task panicked. Contains the panic message.
+    Panicked(String),
+    /// The task was never run, as the pool was shut down.
+    Cancelled,
+    /// The stored result was not of the requested type.
+    InvalidType,
+}
+
+impl Display for TaskError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaskError::Panicked(msg) => write!(f, "Task panicked: {msg}"),
+            TaskError::Cancelled => write!(f, "Task was cancelled before it could run."),
+            TaskError::InvalidType => write!(f, "Task result was not of the requested type."),
+        }
+    }
+}
+
+impl std::error::Error for TaskError {}
+
+impl From<TaskError> for PhpException {
+    fn from(err: TaskError) -> Self {
+        Self::default(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TaskError, TaskPool};
+
+    #[test]
+    fn test_spawn_join() {
+        let pool = TaskPool::new(2);
+        let handles: Vec<_> = (0..8).map(|i| pool.spawn(move || i * 2)).collect();
+        let results: Vec<_> = handles.into_iter().map(|h| h.join()).collect();
+
+        assert_eq!(results, (0..8).map(|i| Ok(i * 2)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_panic() {
+        let pool = TaskPool::new(1);
+        let handle = pool.spawn(|| -> i32 { panic!("oops") });
+
+        assert_eq!(handle.join(), Err(TaskError::Panicked("oops".into())));
+        // The worker survives the panic.
+        assert_eq!(pool.spawn(|| 1).join(), Ok(1));
+    }
+
+    #[test]
+    fn test_stored() {
+        let pool = TaskPool::new(1);
+        pool.spawn_stored("test_stored", || 42u32);
+        assert!(TaskPool::has_stored("test_stored"));
+
+        // Wait for the stored task to finish.
+        assert_eq!(pool.spawn(|| ()).join(), Ok(()));
+        while TaskPool::has_stored("test_stored") {
+            if let Some(result) = TaskPool::take_stored::<u32>("test_stored") {
+                assert_eq!(result, Ok(42));
+            }
+        }
+        assert!(TaskPool::take_stored::<u32>("test_stored").is_none());
+    }
+}
@@ -0,0 +1,248 @@
+//! Test doubles for calling functions exported to PHP without a running
+//! interpreter.
+//!
+//! [`call_function`] calls the handler generated by
+//! [`macro@crate::php_function`] with a fake [`ExecuteData`], so argument
+//! parsing, conversions and return values can be tested in plain unit tests
+//! and fuzzers. Exceptions thrown by the function, including errors raised
+//! while parsing its arguments, are returned as a [`ThrownException`] rather
+//! than being thrown into PHP.
+//!
+//! When the engine has not been started, e.g. in a unit test, the Zend memory
+//! manager is started on the first call so strings and arrays can be
+//! allocated. Only conversions which do not require the engine, such as those
+//! of scalars, strings and arrays, are supported in that case. Objects, class
+//! lookups and calls back into PHP require the engine, and should be tested
+//! with [`macro@crate::php_test`] instead.
+
+use std::{cell::RefCell, mem, ptr, sync::Once};
+
+use parking_lot::{const_mutex, Mutex};
+
+use crate::{
+    builders::FunctionHandler,
+    convert::{FromZval, IntoZvalDyn},
+    ffi::{start_memory_manager, zend_ce_exception},
+    types::Zval,
+    zend::ExecuteData,
+};
+
+/// Ensures only one thread uses the memory manager at a time, as it is not
+/// thread-safe.
+static LOCK: Mutex<()> = const_mutex(());
+
+/// Starts the memory manager once, if the engine did not start it.
+static START: Once = Once::new();
+
+thread_local! {
+    /// The exceptions thrown by the function being called on this thread, or
+    /// [`None`] when no function is being called through a [`CallFrame`].
+    static THROWN: RefCell<Option<Vec<ThrownException>>> = const { RefCell::new(None) };
+}
+
+/// An exception thrown by a function called through a [`CallFrame`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThrownException {
+    /// The name of the exception class, e.g. `ArgumentCountError`.
+    pub class: String,
+    /// The message of the exception. Argument count errors do not include the
+    /// name of the function, e.g. `expects exactly 2 arguments, 1 given`.
+    pub message: String,
+    /// The code of the exception.
+    pub code: i32,
+}
+
+/// Records an exception when a function is being called through a
+/// [`CallFrame`] on this thread, returning whether it was recorded.
+pub(crate) fn capture(exception: impl FnOnce() -> ThrownException) -> bool {
+    THROWN.with(|thrown| match thrown.borrow_mut().as_mut() {
+        Some(thrown) => {
+            thrown.push(exception());
+            true
+        }
+        None => false,
+    })
+}
+
+/// Runs a function with the Zend memory manager available, starting it if
+/// the engine has not been started. Calls are run one at a time.
+///
+/// Zvals allocated by Zend, such as strings and arrays, should only be
+/// created and dropped inside this function when the engine is not running.
+///
+/// # Panics
+///
+/// Panics if the engine has not been started and PHP is thread-safe (ZTS), as
+/// the memory manager cannot be started on its own in that case.
+///
+/// # Parameters
+///
+/// * `func` - The function to run.
+pub fn run<R>(func: impl FnOnce() -> R) -> R {
+    let _guard = LOCK.lock();
+
+    START.call_once(|| {
+        // The exception classes are registered when the engine starts, after
+        // the memory manager was started.
+        if unsafe { !zend_ce_exception.is_null() } {
+            return;
+        }
+        if crate::PHP_ZTS {
+            panic!("The memory manager can only be started on its own with non thread-safe PHP builds.");
+        }
+        unsafe { start_memory_manager() };
+    });
+
+    func()
+}
+
+/// Calls a function exported to PHP with the given arguments, converting the
+/// return value to `R`.
+///
+/// The arguments are converted to zvals and the return value is converted
+/// back inside [`run`], so the function can be used without the engine.
+///
+/// # Panics
+///
+/// Panics if an argument cannot be converted to a zval, or if the return
+/// value cannot be converted to `R`.
+///
+/// # Parameters
+///
+/// * `handler` - The handler of the function. The handler generated by
+///   [`macro@crate::php_function`] for a function `foo` is named
+///   `_internal_php_foo`.
+/// * `args` - The arguments to pass to the function.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{prelude::*, test};
+///
+/// #[php_function]
+/// pub fn add(a: i64, b: i64) -> i64 {
+///     a + b
+/// }
+///
+/// # #[php_module]
+/// # pub fn get_module(module: ModuleBuilder) -> ModuleBuilder {
+/// #     module
+/// # }
+/// # fn main() {
+/// assert_eq!(test::call_function::<i64>(_internal_php_add, &[&1i64, &2i64]), Ok(3));
+///
+/// let error = test::call_function::<i64>(_internal_php_add, &[&1i64]).unwrap_err();
+/// assert_eq!(error.class, "ArgumentCountError");
+/// # }
+/// ```
+pub fn call_function<R>(
+    handler: FunctionHandler,
+    args: &[&dyn IntoZvalDyn],
+) -> Result<R, ThrownException>
+where
+    R: for<'a> FromZval<'a>,
+{
+    run(|| {
+        let args = args
+            .iter()
+            .map(|arg| arg.as_zval(false))
+            .collect::<crate::error::Result<Vec<_>>>()
+            .expect("Failed to convert argument to zval");
+        let retval = CallFrame::new(args).call(handler)?;
+
+        Ok(R::from_zval(&retval).unwrap_or_else(|| {
+            panic!(
+                "Failed to convert return value of type {} to {}",
+                retval.get_type(),
+                R::TYPE
+            )
+        }))
+    })
+}
+
+/// A fake call frame, holding an [`ExecuteData`] followed by the arguments of
+/// the call, laid out as the engine lays out the frames of internal
+/// functions.
+///
+/// The frame has no function and no `$this` object.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{args::Arg, flags::DataType, test::{self, CallFrame}, types::Zval};
+///
+/// test::run(|| {
+///     let mut value = Zval::new();
+///     value.set_long(42);
+///
+///     let mut frame = CallFrame::new(vec![value]);
+///     let mut arg = Arg::new("value", DataType::Long);
+///     frame.execute_data().parser().arg(&mut arg).parse().unwrap();
+///     assert_eq!(arg.val::<i64>(), Some(42));
+/// });
+/// ```
+pub struct CallFrame {
+    slots: Vec<Zval>,
+}
+
+impl CallFrame {
+    /// Creates a call frame with the given arguments.
+    ///
+    /// # Parameters
+    ///
+    /// * `args` - The arguments of the call.
+    pub fn new(args: Vec<Zval>) -> Self {
+        let header = ExecuteData::zend_call_frame_slot() as usize;
+        let num_args = args.len() as u32;
+        let mut slots: Vec<Zval> = (0..header).map(|_| Zval::new()).collect();
+        slots.extend(args);
+
+        // SAFETY: The header slots are large enough to hold the execute data,
+        // and an all-zero execute data is valid.
+        unsafe {
+            let ex = slots.as_mut_ptr().cast::<ExecuteData>();
+            ptr::write(ex, mem::zeroed());
+            (*ex).This.u2.num_args = num_args;
+        }
+
+        Self { slots }
+    }
+
+    /// Returns the execute data of the frame, which can be passed to a
+    /// function handler or used to build an [`ArgParser`].
+    ///
+    /// [`ArgParser`]: crate::args::ArgParser
+    pub fn execute_data(&mut self) -> &mut ExecuteData {
+        // SAFETY: The frame starts with the execute data.
+        unsafe { &mut *self.slots.as_mut_ptr().cast::<ExecuteData>() }
+    }
+
+    /// Calls a function handler with the frame, returning its return value
+    /// or the first exception it threw.
+    ///
+    /// # Parameters
+    ///
+    /// * `handler` - The handler of the function.
+    pub fn call(&mut self, handler: FunctionHandler) -> Result<Zval, ThrownException> {
+        let previous = THROWN.with(|thrown| thrown.borrow_mut().replace(vec![]));
+        let mut retval = Zval::new();
+        handler(self.execute_data(), &mut retval);
+        let thrown = THROWN.with(|thrown| mem::replace(&mut *thrown.borrow_mut(), previous));
+
+        match thrown.and_then(|thrown| thrown.into_iter().next()) {
+            Some(exception) => Err(exception),
+            None => Ok(retval),
+        }
+    }
+}
+
+impl Drop for CallFrame {
+    fn drop(&mut self) {
+        let header = ExecuteData::zend_call_frame_slot() as usize;
+        for slot in &mut self.slots[..header] {
+            // SAFETY: The header slots hold the execute data rather than
+            // zvals, so they are replaced without being dropped.
+            unsafe { ptr::write(slot, Zval::new()) };
+        }
+    }
+}
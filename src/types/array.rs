@@ -6,7 +6,10 @@ use std::{
     convert::{TryFrom, TryInto},
     ffi::CString,
     fmt::{Debug, Display},
+    hash::Hash,
     iter::FromIterator,
+    mem::MaybeUninit,
+    ops::{Index, IndexMut},
 };
 
 use crate::{
@@ -14,15 +17,18 @@ use crate::{
     convert::{FromZval, IntoZval},
     error::{Error, Result},
     ffi::{
-        _zend_new_array, zend_array_count, zend_array_destroy, zend_array_dup, zend_hash_clean,
-        zend_hash_get_current_data_ex, zend_hash_get_current_key_type_ex,
-        zend_hash_get_current_key_zval_ex, zend_hash_index_del, zend_hash_index_find,
-        zend_hash_index_update, zend_hash_move_backwards_ex, zend_hash_move_forward_ex,
-        zend_hash_next_index_insert, zend_hash_str_del, zend_hash_str_find, zend_hash_str_update,
-        HashPosition, HT_MIN_SIZE,
+        _zend_hash_init, _zend_new_array, php_array_merge, php_array_merge_recursive,
+        zend_array_count, zend_array_destroy, zend_array_dup, zend_hash_clean, zend_hash_destroy,
+        zend_hash_extend, zend_hash_find, zend_hash_get_current_data_ex,
+        zend_hash_get_current_key_type_ex, zend_hash_get_current_key_zval_ex, zend_hash_index_del,
+        zend_hash_index_find, zend_hash_index_update, zend_hash_move_backwards_ex,
+        zend_hash_move_forward_ex, zend_hash_next_index_insert, zend_hash_real_init_packed,
+        zend_hash_str_del, zend_hash_str_find, zend_hash_str_update, zend_hash_update,
+        HashPosition, GC_IMMUTABLE, GC_PERSISTENT, HASH_FLAG_PACKED, HASH_FLAG_UNINITIALIZED,
+        HT_MIN_SIZE,
     },
     flags::DataType,
-    types::Zval,
+    types::{zval::persistent_zval_dtor, ZendStr, Zval},
 };
 
 /// A PHP hashtable.
@@ -102,6 +108,96 @@ impl ZendHashTable {
         }
     }
 
+    /// Creates a new, empty, persistent PHP hashtable, returned inside a
+    /// [`ZBox`].
+    ///
+    /// Persistent hashtables are allocated outside of the request heap, so
+    /// they survive the end of the request. This allows extensions to keep
+    /// caches which are shared between requests, for example in a static
+    /// variable.
+    ///
+    /// Values inserted into a persistent hashtable are converted into
+    /// persistent zvals. Only scalars, strings and arrays can be stored, as
+    /// objects and resources always belong to a request. Inserting a value
+    /// which belongs to the request, such as a [`Zval`] received from PHP,
+    /// returns [`Error::NotPersistent`].
+    ///
+    /// # Safety
+    ///
+    /// A persistent hashtable must never be given to PHP directly, for example
+    /// by returning it from a function or storing it in a request zval, as the
+    /// engine would attempt to free it with the request allocator. Values must
+    /// be copied out of the hashtable instead, such as by converting them into
+    /// Rust types. In ZTS builds, the hashtable must also not be accessed from
+    /// multiple threads at once.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// // SAFETY: The hashtable is only used from Rust.
+    /// let mut cache = unsafe { ZendHashTable::new_persistent() };
+    /// cache.insert("greeting", "Hello, world!");
+    /// assert!(cache.is_persistent());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if memory for the hashtable could not be allocated.
+    pub unsafe fn new_persistent() -> ZBox<Self> {
+        Self::with_capacity_persistent(HT_MIN_SIZE)
+    }
+
+    /// Creates a new, empty, persistent PHP hashtable with an initial size,
+    /// returned inside a [`ZBox`]. See [`ZendHashTable::new_persistent`] for
+    /// the rules which apply to persistent hashtables.
+    ///
+    /// # Parameters
+    ///
+    /// * `size` - The size to initialize the array with.
+    ///
+    /// # Safety
+    ///
+    /// See [`ZendHashTable::new_persistent`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if memory for the hashtable could not be allocated.
+    pub unsafe fn with_capacity_persistent(size: u32) -> ZBox<Self> {
+        // The hashtable itself is allocated by Rust, and is only ever freed through
+        // the `ZBoxable` implementation below.
+        let ptr = Box::into_raw(Box::new(MaybeUninit::<Self>::uninit())).cast::<Self>();
+        unsafe {
+            _zend_hash_init(ptr, size, Some(persistent_zval_dtor), true);
+            ZBox::from_raw(&mut *ptr)
+        }
+    }
+
+    /// Returns whether the hashtable was allocated with the persistent
+    /// allocator, and therefore survives the end of the request.
+    pub fn is_persistent(&self) -> bool {
+        let type_info = unsafe { self.gc.u.type_info };
+        type_info & GC_PERSISTENT != 0
+    }
+
+    /// Returns whether the hashtable is only referenced once and is not
+    /// immutable, and can therefore be modified without affecting other
+    /// values.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let ht = ZendHashTable::new();
+    /// assert!(ht.is_unique());
+    /// ```
+    pub fn is_unique(&self) -> bool {
+        let type_info = unsafe { self.gc.u.type_info };
+        self.gc.refcount == 1 && type_info & GC_IMMUTABLE == 0
+    }
+
     /// Returns the current number of elements in the array.
     ///
     /// # Example
@@ -159,6 +255,131 @@ impl ZendHashTable {
         unsafe { zend_hash_clean(self) }
     }
 
+    /// Returns the number of elements the hashtable can hold before it has to
+    /// grow. Removed elements keep occupying their slot until the hashtable
+    /// grows or is compacted with [`ZendHashTable::shrink_to_fit`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let ht = ZendHashTable::with_capacity(100);
+    /// assert!(ht.capacity() >= 100);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.nTableSize as usize
+    }
+
+    /// Reserves capacity for at least `additional` more elements, so that they
+    /// can be inserted without the hashtable being resized in between.
+    ///
+    /// Lists keep their packed layout, while an empty hashtable is prepared
+    /// for keys of any type.
+    ///
+    /// # Parameters
+    ///
+    /// * `additional` - The number of elements to reserve space for.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows a [`u32`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    /// ht.reserve(1_000_000);
+    /// assert!(ht.capacity() >= 1_000_000);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        let size = u32::try_from(self.nNumUsed as usize + additional)
+            .expect("hashtable capacity overflow");
+        if size <= self.nTableSize && !self.is_uninitialized() {
+            return;
+        }
+
+        // SAFETY: `zend_hash_extend` requires the layout of an initialized hashtable to
+        // be kept, so the packed flag is passed through.
+        unsafe { zend_hash_extend(self, size, self.is_packed()) }
+    }
+
+    /// Shrinks the capacity of the hashtable as much as possible, removing the
+    /// slots left behind by removed elements. The keys, values and order of
+    /// the elements are kept, and the internal pointer used by `current()` and
+    /// `next()` keeps pointing to the same element.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::with_capacity(1024);
+    /// ht.push(1);
+    /// ht.shrink_to_fit();
+    /// assert!(ht.capacity() < 1024);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        let len = self.len();
+        let size = (len as u32).max(HT_MIN_SIZE).next_power_of_two();
+        if self.is_uninitialized() || (size >= self.nTableSize && len == self.nNumUsed as usize) {
+            return;
+        }
+
+        let mut compact = if self.is_persistent() {
+            // SAFETY: The hashtable replaces a persistent hashtable.
+            unsafe { Self::with_capacity_persistent(size) }
+        } else {
+            Self::with_capacity(size)
+        };
+        // The elements are copied from the underlying storage, so string keys which are
+        // not valid UTF-8 are kept. The internal pointer is moved to the position of the
+        // same element in the compacted storage.
+        let mut internal_pointer = 0;
+        for (new_pos, element) in self.raw_elements().enumerate() {
+            if element.pos < self.nInternalPointer as usize {
+                internal_pointer = new_pos as u32 + 1;
+            }
+            let mut val = element.val.shallow_clone();
+            // SAFETY: The hashtable takes ownership of the value, whose reference count was
+            // incremented when it was cloned, and adds a reference to the key.
+            unsafe {
+                match element.key {
+                    Some(key) => zend_hash_update(
+                        &mut *compact,
+                        key as *const ZendStr as *mut ZendStr,
+                        &mut val,
+                    ),
+                    None => zend_hash_index_update(&mut *compact, element.h, &mut val),
+                };
+            }
+            val.release();
+        }
+        compact.nInternalPointer = internal_pointer;
+        compact.nNextFreeElement = self.nNextFreeElement;
+
+        // Everything but the reference counted header is swapped, so the compacted
+        // storage is moved into `self` and the old storage is freed with `compact`.
+        let header = std::mem::size_of::<crate::ffi::zend_refcounted_h>();
+        // SAFETY: Both pointers point to distinct, valid hashtables.
+        unsafe {
+            std::ptr::swap_nonoverlapping(
+                (self as *mut Self as *mut u8).add(header),
+                (&mut *compact as *mut Self as *mut u8).add(header),
+                std::mem::size_of::<Self>() - header,
+            );
+        }
+    }
+
+    /// Returns whether the storage of the hashtable has not been allocated
+    /// yet, which is the case for empty hashtables until the first insert.
+    fn is_uninitialized(&self) -> bool {
+        let flags = unsafe { self.u.flags };
+        flags & HASH_FLAG_UNINITIALIZED != 0
+    }
+
     /// Attempts to retrieve a value from the hash table with a string key.
     ///
     /// # Parameters
@@ -265,6 +486,133 @@ impl ZendHashTable {
         unsafe { zend_hash_index_find(self, key).as_mut() }
     }
 
+    /// Retrieves a value from the hash table with a string key and converts
+    /// it into a Rust type, returning the default value of the type if the
+    /// key was not found or the value could not be converted.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key to search for in the hash table.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.insert("retries", 3);
+    /// assert_eq!(ht.get_or_default::<i64>("retries"), 3);
+    /// assert_eq!(ht.get_or_default::<i64>("timeout"), 0);
+    /// ```
+    pub fn get_or_default<'a, T>(&'a self, key: &str) -> T
+    where
+        T: FromZval<'a> + Default,
+    {
+        self.get(key).and_then(T::from_zval).unwrap_or_default()
+    }
+
+    /// Retrieves a value from the hash table with an index and converts it
+    /// into a Rust type, returning the default value of the type if the index
+    /// was not found or the value could not be converted.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The index to search for in the hash table.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.push("first");
+    /// assert_eq!(ht.get_index_or_default::<String>(0), "first");
+    /// assert_eq!(ht.get_index_or_default::<String>(1), "");
+    /// ```
+    pub fn get_index_or_default<'a, T>(&'a self, key: u64) -> T
+    where
+        T: FromZval<'a> + Default,
+    {
+        self.get_index(key)
+            .and_then(T::from_zval)
+            .unwrap_or_default()
+    }
+
+    /// Attempts to retrieve a value from nested arrays, where each key of the
+    /// path except the last one refers to an array holding the next key.
+    ///
+    /// Keys which are integers in their canonical form, such as `"0"` or
+    /// `"-5"` but not `"05"`, are looked up as indexes, as PHP does when
+    /// accessing arrays with string keys. References are followed.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The keys leading to the value.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(&Zval)` - A reference to the zval at the end of the path.
+    /// * `None` - The path is empty, a key was not found or an intermediate
+    ///   value is not an array.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.insert_path(&["config", "db", "port"], 5432);
+    /// assert_eq!(
+    ///     ht.get_path(&["config", "db", "port"]).and_then(|zv| zv.long()),
+    ///     Some(5432)
+    /// );
+    /// ```
+    pub fn get_path(&self, path: &[&str]) -> Option<&Zval> {
+        let (key, parents) = path.split_last()?;
+        let mut ht = self;
+        for segment in parents {
+            ht = ht.path_entry(segment)?.dereference().array()?;
+        }
+        ht.path_entry(key).map(Zval::dereference)
+    }
+
+    /// Attempts to retrieve a value from nested arrays and convert it into a
+    /// Rust type. See [`ZendHashTable::get_path`] for how the path is
+    /// followed.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The keys leading to the value.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(T)` - The converted value at the end of the path.
+    /// * `None` - No value was found at the path, or it could not be converted
+    ///   into `T`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.insert_path(&["config", "db", "host"], "localhost");
+    /// assert_eq!(
+    ///     ht.get_path_as::<&str>(&["config", "db", "host"]),
+    ///     Some("localhost")
+    /// );
+    /// ```
+    pub fn get_path_as<'a, T>(&'a self, path: &[&str]) -> Option<T>
+    where
+        T: FromZval<'a>,
+    {
+        T::from_zval(self.get_path(path)?)
+    }
+
     /// Attempts to remove a value from the hash table with a string key.
     ///
     /// # Parameters
@@ -345,8 +693,9 @@ impl ZendHashTable {
     /// # Returns
     ///
     /// Returns nothing in a result on success. Returns an error if the key
-    /// could not be converted into a [`CString`], or converting the value into
-    /// a [`Zval`] failed.
+    /// could not be converted into a [`CString`], if converting the value into
+    /// a [`Zval`] failed, or if the hashtable is persistent and the value
+    /// belongs to the request.
     ///
     /// # Example
     ///
@@ -364,7 +713,8 @@ impl ZendHashTable {
     where
         V: IntoZval,
     {
-        let mut val = val.into_zval(false)?;
+        let mut val = val.into_zval(self.is_persistent())?;
+        self.check_persistent(&val)?;
         unsafe { zend_hash_str_update(self, CString::new(key)?.as_ptr(), key.len(), &mut val) };
         val.release();
         Ok(())
@@ -381,7 +731,8 @@ impl ZendHashTable {
     /// # Returns
     ///
     /// Returns nothing in a result on success. Returns an error if converting
-    /// the value into a [`Zval`] failed.
+    /// the value into a [`Zval`] failed, or if the hashtable is persistent and
+    /// the value belongs to the request.
     ///
     /// # Example
     ///
@@ -399,12 +750,106 @@ impl ZendHashTable {
     where
         V: IntoZval,
     {
-        let mut val = val.into_zval(false)?;
+        let mut val = val.into_zval(self.is_persistent())?;
+        self.check_persistent(&val)?;
         unsafe { zend_hash_index_update(self, key, &mut val) };
         val.release();
         Ok(())
     }
 
+    /// Inserts an item into nested arrays, or updates it if the key already
+    /// exists. Arrays which are missing along the path are created, and
+    /// arrays which are shared with other values are copied before being
+    /// modified. See [`ZendHashTable::get_path`] for how the path is followed.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The keys leading to the value.
+    /// * `val` - The value to insert into the innermost array.
+    ///
+    /// # Returns
+    ///
+    /// Returns nothing in a result on success. Returns [`Error::EmptyPath`] if
+    /// the path is empty. Returns another error if a value along the path
+    /// exists but is not an array, if a key contains a NUL character, or if
+    /// converting the value into a [`Zval`] failed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.insert_path(&["config", "db", "host"], "localhost");
+    /// ht.insert_path(&["config", "db", "port"], 5432);
+    /// assert_eq!(ht.len(), 1);
+    /// ```
+    pub fn insert_path<V>(&mut self, path: &[&str], val: V) -> Result<()>
+    where
+        V: IntoZval,
+    {
+        let (key, parents) = path.split_last().ok_or(Error::EmptyPath)?;
+        let mut ht = self;
+        for segment in parents {
+            if ht.path_entry(segment).is_none() {
+                let nested = if ht.is_persistent() {
+                    // SAFETY: The hashtable is only stored in a persistent hashtable.
+                    unsafe { Self::new_persistent() }
+                } else {
+                    Self::new()
+                };
+                ht.insert_path_entry(segment, nested)?;
+            }
+
+            let zv = ht
+                .path_entry_mut(segment)
+                .ok_or(Error::InvalidPointer)?
+                .dereference_mut();
+            ht = Self::nested_mut(zv)?;
+        }
+        ht.insert_path_entry(key, val)
+    }
+
+    /// Returns an error if the hashtable is persistent and the value belongs to
+    /// the request, as it would be freed at the end of the request.
+    fn check_persistent(&self, val: &Zval) -> Result<()> {
+        if self.is_persistent() && !val.outlives_request() {
+            return Err(Error::NotPersistent);
+        }
+        Ok(())
+    }
+
+    /// Returns the value at a key of a path, which is looked up as an index
+    /// if it is an integer in canonical form.
+    fn path_entry(&self, key: &str) -> Option<&Zval> {
+        match canonical_index(key) {
+            Some(index) => self.get_index(index),
+            None => self.get(key),
+        }
+    }
+
+    fn path_entry_mut(&mut self, key: &str) -> Option<&mut Zval> {
+        match canonical_index(key) {
+            Some(index) => self.get_index_mut(index),
+            None => self.get_mut(key),
+        }
+    }
+
+    fn insert_path_entry<V: IntoZval>(&mut self, key: &str, val: V) -> Result<()> {
+        match canonical_index(key) {
+            Some(index) => self.insert_at_index(index, val),
+            None => self.insert(key, val),
+        }
+    }
+
+    /// Returns the array held by a zval for modification, copying it first if
+    /// it is shared or immutable.
+    fn nested_mut(zv: &mut Zval) -> Result<&mut Self> {
+        let ty = zv.get_type();
+        zv.separate_array().ok_or(Error::ZvalConversion(ty))
+    }
+
     /// Pushes an item onto the end of the hash table. Returns a result
     /// containing nothing if the element was successfully inserted.
     ///
@@ -415,7 +860,8 @@ impl ZendHashTable {
     /// # Returns
     ///
     /// Returns nothing in a result on success. Returns an error if converting
-    /// the value into a [`Zval`] failed.
+    /// the value into a [`Zval`] failed, or if the hashtable is persistent and
+    /// the value belongs to the request.
     ///
     /// # Example
     ///
@@ -433,13 +879,272 @@ impl ZendHashTable {
     where
         V: IntoZval,
     {
-        let mut val = val.into_zval(false)?;
+        let mut val = val.into_zval(self.is_persistent())?;
+        self.check_persistent(&val)?;
         unsafe { zend_hash_next_index_insert(self, &mut val) };
         val.release();
 
         Ok(())
     }
 
+    /// Pushes a slice of items onto the end of the hash table. When the hash
+    /// table is a list, the values are written directly into its storage,
+    /// which is grown once beforehand, rather than being inserted one at a
+    /// time.
+    ///
+    /// # Parameters
+    ///
+    /// * `vals` - The values to push onto the hash table.
+    ///
+    /// # Returns
+    ///
+    /// Returns nothing in a result on success. Returns an error if converting
+    /// a value into a [`Zval`] failed, or if the hashtable is persistent and a
+    /// value belongs to the request, in which case the values before it have
+    /// been pushed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.push_batch(&[1, 2, 3]);
+    /// assert_eq!(ht.len(), 3);
+    /// assert!(ht.is_packed());
+    /// ```
+    pub fn push_batch<V>(&mut self, vals: &[V]) -> Result<()>
+    where
+        V: IntoZval + Clone,
+    {
+        if self.is_uninitialized()
+            || (self.is_packed() && self.nNextFreeElement == self.nNumUsed as _)
+        {
+            self.append_packed(vals.iter().cloned())
+        } else {
+            vals.iter().cloned().try_for_each(|val| self.push(val))
+        }
+    }
+
+    /// Creates a packed hashtable, i.e. a list, from the values of an
+    /// iterator. The keys of the hashtable are `0..n`, so `array_is_list()`
+    /// is true for it, and the values are written directly into its storage.
+    ///
+    /// # Parameters
+    ///
+    /// * `iter` - The values of the hashtable.
+    ///
+    /// # Returns
+    ///
+    /// Returns the hashtable in a result on success. Returns an error if
+    /// converting a value into a [`Zval`] failed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let ht = ZendHashTable::from_iter_packed((1..=3).map(|i| i * 2)).unwrap();
+    /// assert_eq!(ht.get_index(2).and_then(|zv| zv.long()), Some(6));
+    /// assert!(ht.is_packed());
+    /// ```
+    pub fn from_iter_packed<I>(iter: I) -> Result<ZBox<Self>>
+    where
+        I: IntoIterator,
+        I::Item: IntoZval,
+    {
+        let iter = iter.into_iter();
+        let size = iter
+            .size_hint()
+            .0
+            .try_into()
+            .map_err(|_| Error::IntegerOverflow)?;
+        let mut ht = Self::with_capacity(size);
+        ht.append_packed(iter)?;
+        Ok(ht)
+    }
+
+    /// Appends values to an uninitialized or packed hashtable by writing them
+    /// directly into its slots, initializing it as a packed hashtable first
+    /// if required. The next free element of a packed hashtable must be
+    /// directly after its last slot.
+    fn append_packed<V: IntoZval>(&mut self, vals: impl Iterator<Item = V>) -> Result<()> {
+        if self.is_uninitialized() {
+            unsafe { zend_hash_real_init_packed(self) };
+            self.nNextFreeElement = 0;
+        }
+        self.reserve(vals.size_hint().0);
+
+        let persistent = self.is_persistent();
+        for val in vals {
+            let val = val.into_zval(persistent)?;
+            self.check_persistent(&val)?;
+            if self.nNumUsed == self.nTableSize {
+                self.reserve(1);
+            }
+
+            // SAFETY: The hashtable is packed and was grown above if all of its slots were
+            // used. The counters are updated after each write, so the hashtable is valid if
+            // a conversion fails.
+            unsafe { self.packed_slot(self.nNumUsed as usize).write(val) };
+            self.nNumUsed += 1;
+            self.nNumOfElements += 1;
+            self.nNextFreeElement = self.nNumUsed as _;
+        }
+
+        Ok(())
+    }
+
+    /// Merges the elements of another hashtable into the hashtable, in the
+    /// same way as PHP's `array_merge()`. Elements with string keys replace
+    /// the elements with the same key, while elements with integer keys are
+    /// appended and renumbered.
+    ///
+    /// The values are shared with `other` rather than copied, so neither
+    /// hashtable may be persistent.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The hashtable whose elements are merged into this one.
+    ///
+    /// # Returns
+    ///
+    /// Returns nothing in a result on success. Returns an error if either
+    /// hashtable is persistent.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    /// ht.insert("a", 1);
+    /// ht.push(2);
+    ///
+    /// let mut other = ZendHashTable::new();
+    /// other.insert("a", 3);
+    /// other.push(4);
+    ///
+    /// ht.merge(&other).unwrap();
+    /// assert_eq!(ht.get("a").and_then(|zv| zv.long()), Some(3));
+    /// assert_eq!(ht.get_index(1).and_then(|zv| zv.long()), Some(4));
+    /// ```
+    pub fn merge(&mut self, other: &ZendHashTable) -> Result<()> {
+        if self.is_persistent() || other.is_persistent() {
+            return Err(Error::NotPersistent);
+        }
+
+        // SAFETY: `other` is only read from, the pointer is mutable as the function
+        // signature is missing `const`.
+        unsafe { php_array_merge(self, other as *const ZendHashTable as *mut ZendHashTable) };
+        Ok(())
+    }
+
+    /// Merges the elements of another hashtable into the hashtable
+    /// recursively, in the same way as PHP's `array_merge_recursive()`. When
+    /// both hashtables contain an element with the same string key, the
+    /// values are merged into an array, while elements with integer keys are
+    /// appended and renumbered.
+    ///
+    /// The values are shared with `other` rather than copied, so neither
+    /// hashtable may be persistent.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The hashtable whose elements are merged into this one.
+    ///
+    /// # Returns
+    ///
+    /// Returns nothing in a result on success. Returns an error if either
+    /// hashtable is persistent, or if one of the hashtables contains a
+    /// reference to itself, in which case the hashtable may have been
+    /// partially merged.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    /// ht.insert("a", 1);
+    ///
+    /// let mut other = ZendHashTable::new();
+    /// other.insert("a", 2);
+    ///
+    /// ht.merge_recursive(&other).unwrap();
+    /// assert_eq!(ht.get_path_as::<i64>(&["a", "1"]), Some(2));
+    /// ```
+    pub fn merge_recursive(&mut self, other: &ZendHashTable) -> Result<()> {
+        if self.is_persistent() || other.is_persistent() {
+            return Err(Error::NotPersistent);
+        }
+
+        // SAFETY: `other` is only read from, the pointer is mutable as the function
+        // signature is missing `const`.
+        let result = unsafe {
+            php_array_merge_recursive(self, other as *const ZendHashTable as *mut ZendHashTable)
+        };
+
+        if result == 0 {
+            Err(Error::ArrayRecursion)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns a hashtable containing the elements of the hashtable whose keys
+    /// are not present in another hashtable, in the same way as PHP's
+    /// `array_diff_key()`. Keys are preserved.
+    ///
+    /// # Parameters
+    ///
+    /// * `other` - The hashtable containing the keys to exclude.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    /// ht.insert("a", 1);
+    /// ht.insert("b", 2);
+    ///
+    /// let mut other = ZendHashTable::new();
+    /// other.insert("a", "x");
+    ///
+    /// let diff = ht.diff_keys(&other);
+    /// assert_eq!(diff.len(), 1);
+    /// assert_eq!(diff.get("b").and_then(|zv| zv.long()), Some(2));
+    /// ```
+    pub fn diff_keys(&self, other: &ZendHashTable) -> ZBox<ZendHashTable> {
+        let mut diff = Self::new();
+        for element in self.raw_elements() {
+            let key = element.key.map(|key| key as *const ZendStr as *mut ZendStr);
+            // SAFETY: The keys are valid strings read from the hashtable.
+            let exists = unsafe {
+                match key {
+                    Some(key) => !zend_hash_find(other, key).is_null(),
+                    None => !zend_hash_index_find(other, element.h).is_null(),
+                }
+            };
+            if exists {
+                continue;
+            }
+            let mut val = element.val.shallow_clone();
+            // SAFETY: The hashtable takes ownership of the value, whose reference count was
+            // incremented when it was cloned, and adds a reference to the key.
+            unsafe {
+                match key {
+                    Some(key) => zend_hash_update(&mut *diff, key, &mut val),
+                    None => zend_hash_index_update(&mut *diff, element.h, &mut val),
+                };
+            }
+            val.release();
+        }
+        diff
+    }
+
     /// Checks if the hashtable only contains numerical keys.
     ///
     /// # Returns
@@ -494,6 +1199,143 @@ impl ZendHashTable {
             .any(|(i, (k, _))| ArrayKey::Long(i as i64) != k)
     }
 
+    /// Returns whether the hashtable is packed, i.e. it only has integer keys
+    /// in ascending order and its values are stored without a hash index.
+    /// PHP creates packed hashtables for lists, such as `[1, 2, 3]`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// ht.push(1);
+    /// ht.push(2);
+    /// assert!(ht.is_packed());
+    ///
+    /// ht.insert("key", 3);
+    /// assert!(!ht.is_packed());
+    /// ```
+    pub fn is_packed(&self) -> bool {
+        let flags = unsafe { self.u.flags };
+        flags & HASH_FLAG_PACKED != 0
+    }
+
+    /// Returns an iterator over the values of a packed hashtable, read
+    /// directly from the underlying storage, or [`None`] if the hashtable is
+    /// not packed.
+    fn packed_values(&self) -> Option<impl Iterator<Item = &Zval>> {
+        if !self.is_packed() {
+            return None;
+        }
+
+        // SAFETY: The first `nNumUsed` slots of a packed hashtable are initialized, with
+        // removed elements marked as undefined.
+        let slots = unsafe {
+            cfg_if::cfg_if! {
+                if #[cfg(php82)] {
+                    std::slice::from_raw_parts(self.__bindgen_anon_1.arPacked, self.nNumUsed as usize)
+                        .iter()
+                } else {
+                    std::slice::from_raw_parts(self.__bindgen_anon_1.arData, self.nNumUsed as usize)
+                        .iter()
+                        .map(|bucket| &bucket.val)
+                }
+            }
+        };
+        Some(slots.filter(|val| val.get_type() != DataType::Undef))
+    }
+
+    /// Returns an iterator over the elements of the hashtable in order, read
+    /// directly from the underlying storage. Unlike [`iter`](Self::iter), the
+    /// string keys are not required to be valid UTF-8.
+    pub(crate) fn raw_elements(&self) -> impl Iterator<Item = RawElement<'_>> {
+        let ht = self as *const Self as *mut Self;
+        // SAFETY: Only shared references are handed out while the hashtable is
+        // borrowed.
+        (0..self.nNumUsed as usize).filter_map(move |pos| unsafe {
+            let (h, key, val) = Self::raw_slot(ht, pos)?;
+            Some(RawElement {
+                pos,
+                h,
+                key: key.as_ref().and_then(|key| key.as_ref()),
+                val: &*val,
+            })
+        })
+    }
+
+    /// Returns an iterator over the elements of the hashtable in order, read
+    /// directly from the underlying storage, yielding mutable references to
+    /// the string keys and the values.
+    ///
+    /// The string keys may be replaced by equal strings, so the keys are not
+    /// moved in the hash of the hashtable.
+    pub(crate) fn raw_elements_mut(&mut self) -> impl Iterator<Item = RawElementMut<'_>> {
+        let ht = self as *mut Self;
+        // SAFETY: Each slot is only yielded once, so the mutable references do not
+        // alias.
+        (0..self.nNumUsed as usize).filter_map(move |pos| unsafe {
+            let (_, key, val) = Self::raw_slot(ht, pos)?;
+            Some(RawElementMut {
+                key: key.as_mut().filter(|key| !key.is_null()),
+                val: &mut *val,
+            })
+        })
+    }
+
+    /// Reads the slot at `pos` of the hashtable, returning the integer key or
+    /// hash, a pointer to the string key, which is null for packed hashtables
+    /// and points to null for integer keys, and the value. Returns [`None`] if
+    /// the element at the slot was removed.
+    ///
+    /// # Safety
+    ///
+    /// `pos` must be less than `nNumUsed`.
+    unsafe fn raw_slot(ht: *mut Self, pos: usize) -> Option<(u64, *mut *mut ZendStr, *mut Zval)> {
+        cfg_if::cfg_if! {
+            if #[cfg(php82)] {
+                let slot = if (*ht).is_packed() {
+                    (pos as u64, std::ptr::null_mut(), (*ht).__bindgen_anon_1.arPacked.add(pos))
+                } else {
+                    let bucket = (*ht).__bindgen_anon_1.arData.add(pos);
+                    (
+                        (*bucket).h,
+                        std::ptr::addr_of_mut!((*bucket).key),
+                        std::ptr::addr_of_mut!((*bucket).val),
+                    )
+                };
+            } else {
+                let bucket = (*ht).__bindgen_anon_1.arData.add(pos);
+                let slot = (
+                    (*bucket).h,
+                    std::ptr::addr_of_mut!((*bucket).key),
+                    std::ptr::addr_of_mut!((*bucket).val),
+                );
+            }
+        }
+        ((*slot.2).get_type() != DataType::Undef).then_some(slot)
+    }
+
+    /// Returns a pointer to the slot at `index` of a packed hashtable.
+    ///
+    /// # Safety
+    ///
+    /// The hashtable must be packed, and `index` must be less than
+    /// `nTableSize`.
+    unsafe fn packed_slot(&mut self, index: usize) -> *mut Zval {
+        cfg_if::cfg_if! {
+            if #[cfg(php82)] {
+                self.__bindgen_anon_1.arPacked.add(index)
+            } else {
+                let bucket = &mut *self.__bindgen_anon_1.arData.add(index);
+                bucket.h = index as _;
+                bucket.key = std::ptr::null_mut();
+                &mut bucket.val
+            }
+        }
+    }
+
     /// Returns an iterator over the values contained inside the hashtable, as
     /// if it was a set or list.
     ///
@@ -535,15 +1377,152 @@ impl ZendHashTable {
     pub fn iter(&self) -> Iter {
         self.into_iter()
     }
+
+    /// Returns the keys of the hashtable, in order. String keys which are not
+    /// valid UTF-8 are converted lossily, replacing invalid sequences with
+    /// `U+FFFD`, so they may not match the keys of the hashtable.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::{ArrayKey, ZendHashTable};
+    ///
+    /// let mut ht = ZendHashTable::new();
+    /// ht.push("a");
+    /// ht.insert("b", "c");
+    ///
+    /// assert_eq!(
+    ///     ht.keys_vec(),
+    ///     vec![ArrayKey::Long(0), ArrayKey::String("b".to_string())]
+    /// );
+    /// ```
+    pub fn keys_vec(&self) -> Vec<ArrayKey> {
+        self.raw_elements()
+            .map(|element| match element.key {
+                Some(key) => ArrayKey::String(String::from_utf8_lossy(key.as_bytes()).into_owned()),
+                None => ArrayKey::Long(element.h as i64),
+            })
+            .collect()
+    }
+
+    /// Converts the values of the hashtable into a vector, in order,
+    /// reporting the first value which could not be converted.
+    ///
+    /// # Returns
+    ///
+    /// Returns the converted values on success. Returns the position of the
+    /// first value which could not be converted and the reason on failure.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    /// ht.push(1);
+    /// ht.push("two");
+    ///
+    /// match ht.values_to::<i64>() {
+    ///     Ok(values) => println!("Got {} values", values.len()),
+    ///     Err((index, e)) => println!("Value {} is invalid: {}", index, e),
+    /// }
+    /// ```
+    pub fn values_to<'a, T>(&'a self) -> std::result::Result<Vec<T>, (usize, Error)>
+    where
+        T: FromZval<'a>,
+    {
+        self.values()
+            .enumerate()
+            .map(|(i, zv)| T::from_zval(zv).ok_or((i, Error::ZvalConversion(zv.get_type()))))
+            .collect()
+    }
+
+    /// Converts the hashtable into a map, reporting the first key or value
+    /// which could not be converted.
+    ///
+    /// Keys are converted with [`TryFrom<ArrayKey>`], which is implemented for
+    /// [`String`], accepting all keys, and [`i64`], accepting integer keys.
+    ///
+    /// # Returns
+    ///
+    /// Returns the converted map on success. Returns the key of the first
+    /// element which could not be converted and the reason on failure. String
+    /// keys which are not valid UTF-8 cannot be converted, and are reported
+    /// lossily converted with [`Error::InvalidUtf8`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    /// ht.insert("timeout", 30);
+    /// ht.insert("retries", "three");
+    ///
+    /// match ht.try_into_map::<String, i64>() {
+    ///     Ok(map) => println!("Got {} options", map.len()),
+    ///     Err((key, e)) => println!("Option {} is invalid: {}", key, e),
+    /// }
+    /// ```
+    pub fn try_into_map<'a, K, V>(&'a self) -> std::result::Result<HashMap<K, V>, (ArrayKey, Error)>
+    where
+        K: TryFrom<ArrayKey> + Eq + Hash,
+        V: FromZval<'a>,
+    {
+        let mut map = HashMap::with_capacity(self.len());
+        for element in self.raw_elements() {
+            let val = element.val;
+            let (key, key_ty) = match element.key {
+                Some(key) => match key.as_str() {
+                    Ok(key) => (ArrayKey::String(key.to_string()), DataType::String),
+                    Err(_) => {
+                        let key = String::from_utf8_lossy(key.as_bytes()).into_owned();
+                        return Err((ArrayKey::String(key), Error::InvalidUtf8));
+                    }
+                },
+                None => (ArrayKey::Long(element.h as i64), DataType::Long),
+            };
+            let k = match K::try_from(key.clone()) {
+                Ok(k) => k,
+                Err(_) => return Err((key, Error::ZvalConversion(key_ty))),
+            };
+            let v = match V::from_zval(val) {
+                Some(v) => v,
+                None => return Err((key, Error::ZvalConversion(val.get_type()))),
+            };
+            map.insert(k, v);
+        }
+        Ok(map)
+    }
 }
 
 unsafe impl ZBoxable for ZendHashTable {
     fn free(&mut self) {
         // SAFETY: ZBox has immutable access to `self`.
-        unsafe { zend_array_destroy(self) }
+        unsafe {
+            if self.is_persistent() {
+                zend_hash_destroy(self);
+                drop(Box::from_raw(
+                    (self as *mut Self).cast::<MaybeUninit<Self>>(),
+                ));
+            } else {
+                zend_array_destroy(self)
+            }
+        }
+    }
+
+    fn is_request_bound(&self) -> bool {
+        !self.is_persistent()
     }
 }
 
+/// Parses a string key which is an integer in canonical form, and is
+/// therefore stored by PHP as an integer key.
+pub(crate) fn canonical_index(key: &str) -> Option<u64> {
+    let index: i64 = key.parse().ok()?;
+    (index.to_string() == key).then_some(index as u64)
+}
+
 impl Debug for ZendHashTable {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_map()
@@ -552,6 +1531,61 @@ impl Debug for ZendHashTable {
     }
 }
 
+/// Returns the value at a string key.
+///
+/// # Panics
+///
+/// Panics if the key is not present in the hashtable. Use
+/// [`ZendHashTable::get`] to handle missing keys.
+impl Index<&str> for ZendHashTable {
+    type Output = Zval;
+
+    fn index(&self, key: &str) -> &Zval {
+        self.get(key)
+            .unwrap_or_else(|| panic!("No value found for key `{}`", key))
+    }
+}
+
+/// Returns a mutable reference to the value at a string key.
+///
+/// # Panics
+///
+/// Panics if the key is not present in the hashtable.
+impl IndexMut<&str> for ZendHashTable {
+    fn index_mut(&mut self, key: &str) -> &mut Zval {
+        self.get_mut(key)
+            .unwrap_or_else(|| panic!("No value found for key `{}`", key))
+    }
+}
+
+/// Returns the value at an index. Negative indexes refer to negative keys,
+/// rather than counting from the end of the hashtable.
+///
+/// # Panics
+///
+/// Panics if the index is not present in the hashtable. Use
+/// [`ZendHashTable::get_index`] to handle missing indexes.
+impl Index<i64> for ZendHashTable {
+    type Output = Zval;
+
+    fn index(&self, key: i64) -> &Zval {
+        self.get_index(key as u64)
+            .unwrap_or_else(|| panic!("No value found for index {}", key))
+    }
+}
+
+/// Returns a mutable reference to the value at an index.
+///
+/// # Panics
+///
+/// Panics if the index is not present in the hashtable.
+impl IndexMut<i64> for ZendHashTable {
+    fn index_mut(&mut self, key: i64) -> &mut Zval {
+        self.get_index_mut(key as u64)
+            .unwrap_or_else(|| panic!("No value found for index {}", key))
+    }
+}
+
 impl ToOwned for ZendHashTable {
     type Owned = ZBox<ZendHashTable>;
 
@@ -576,7 +1610,30 @@ pub struct Iter<'a> {
     pos: HashPosition,
 }
 
-#[derive(Debug, PartialEq)]
+/// An element of a hashtable, read directly from the underlying storage by
+/// [`ZendHashTable::raw_elements`].
+pub(crate) struct RawElement<'a> {
+    /// The position of the element in the underlying storage.
+    pub pos: usize,
+    /// The integer key of the element, or the hash of its string key.
+    pub h: u64,
+    /// The string key of the element, or [`None`] for integer keys.
+    pub key: Option<&'a ZendStr>,
+    /// The value of the element.
+    pub val: &'a Zval,
+}
+
+/// An element of a hashtable, read directly from the underlying storage by
+/// [`ZendHashTable::raw_elements_mut`].
+pub(crate) struct RawElementMut<'a> {
+    /// The pointer to the string key of the element, or [`None`] for integer
+    /// keys.
+    pub key: Option<&'a mut *mut ZendStr>,
+    /// The value of the element.
+    pub val: &'a mut Zval,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum ArrayKey {
     Long(i64),
     String(String),
@@ -606,6 +1663,26 @@ impl Display for ArrayKey {
     }
 }
 
+impl From<ArrayKey> for String {
+    fn from(key: ArrayKey) -> Self {
+        match key {
+            ArrayKey::Long(key) => key.to_string(),
+            ArrayKey::String(key) => key,
+        }
+    }
+}
+
+impl TryFrom<ArrayKey> for i64 {
+    type Error = Error;
+
+    fn try_from(key: ArrayKey) -> Result<Self> {
+        match key {
+            ArrayKey::Long(key) => Ok(key),
+            ArrayKey::String(_) => Err(Error::ZvalConversion(DataType::String)),
+        }
+    }
+}
+
 impl<'a> FromZval<'a> for ArrayKey {
     const TYPE: DataType = DataType::String;
 
@@ -823,6 +1900,30 @@ impl Default for ZBox<ZendHashTable> {
     }
 }
 
+impl ZBox<ZendHashTable> {
+    /// Returns a mutable reference to the hashtable, first replacing it with a
+    /// copy if it is shared with other values or immutable. The reference
+    /// held by the box on the shared hashtable is released.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    /// let _ = ht.make_unique().push(1);
+    /// ```
+    pub fn make_unique(&mut self) -> &mut ZendHashTable {
+        if !self.is_unique() {
+            let shared = std::mem::replace(self, (**self).to_owned()).into_raw();
+            if unsafe { shared.gc.u.type_info } & GC_IMMUTABLE == 0 {
+                shared.gc.refcount -= 1;
+            }
+        }
+        self
+    }
+}
+
 impl Clone for ZBox<ZendHashTable> {
     fn clone(&self) -> Self {
         (**self).to_owned()
@@ -878,16 +1979,28 @@ where
     type Error = Error;
 
     fn try_from(value: HashMap<K, V>) -> Result<Self> {
-        let mut ht = ZendHashTable::with_capacity(
-            value.len().try_into().map_err(|_| Error::IntegerOverflow)?,
-        );
-
-        for (k, v) in value.into_iter() {
-            ht.insert(k.as_ref(), v)?;
-        }
+        hashmap_into_ht(value, false)
+    }
+}
 
-        Ok(ht)
+fn hashmap_into_ht<K, V>(value: HashMap<K, V>, persistent: bool) -> Result<ZBox<ZendHashTable>>
+where
+    K: AsRef<str>,
+    V: IntoZval,
+{
+    let size = value.len().try_into().map_err(|_| Error::IntegerOverflow)?;
+    let mut ht = if persistent {
+        // SAFETY: Persistent zvals are only stored in persistent hashtables.
+        unsafe { ZendHashTable::with_capacity_persistent(size) }
+    } else {
+        ZendHashTable::with_capacity(size)
+    };
+
+    for (k, v) in value.into_iter() {
+        ht.insert(k.as_ref(), v)?;
     }
+
+    Ok(ht)
 }
 
 impl<K, V> IntoZval for HashMap<K, V>
@@ -897,8 +2010,8 @@ where
 {
     const TYPE: DataType = DataType::Array;
 
-    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
-        let arr = self.try_into()?;
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        let arr = hashmap_into_ht(self, persistent)?;
         zv.set_hashtable(arr);
         Ok(())
     }
@@ -927,9 +2040,16 @@ where
 
     fn try_from(value: &'a ZendHashTable) -> Result<Self> {
         let mut vec = Vec::with_capacity(value.len());
-
-        for (_, val) in value {
+        let mut push = |val: &'a Zval| -> Result<()> {
             vec.push(T::from_zval(val).ok_or_else(|| Error::ZvalConversion(val.get_type()))?);
+            Ok(())
+        };
+
+        // Lists are packed, so their values can be read without going through the
+        // hashtable iterator.
+        match value.packed_values() {
+            Some(mut values) => values.try_for_each(&mut push)?,
+            None => value.values().try_for_each(&mut push)?,
         }
 
         Ok(vec)
@@ -943,16 +2063,30 @@ where
     type Error = Error;
 
     fn try_from(value: Vec<T>) -> Result<Self> {
-        let mut ht = ZendHashTable::with_capacity(
-            value.len().try_into().map_err(|_| Error::IntegerOverflow)?,
-        );
-
-        for val in value.into_iter() {
-            ht.push(val)?;
-        }
+        vec_into_ht(value, false)
+    }
+}
 
-        Ok(ht)
+fn vec_into_ht<T>(value: Vec<T>, persistent: bool) -> Result<ZBox<ZendHashTable>>
+where
+    T: IntoZval,
+{
+    let size = value.len().try_into().map_err(|_| Error::IntegerOverflow)?;
+    let mut ht = if persistent {
+        // SAFETY: Persistent zvals are only stored in persistent hashtables.
+        unsafe { ZendHashTable::with_capacity_persistent(size) }
+    } else {
+        ZendHashTable::with_capacity(size)
+    };
+
+    if size == 0 {
+        return Ok(ht);
     }
+
+    // The hashtable was sized to fit all of the values, which are written directly into
+    // it rather than being inserted one at a time.
+    ht.append_packed(value.into_iter())?;
+    Ok(ht)
 }
 
 impl<T> IntoZval for Vec<T>
@@ -961,13 +2095,24 @@ where
 {
     const TYPE: DataType = DataType::Array;
 
-    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
-        let arr = self.try_into()?;
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        let arr = vec_into_ht(self, persistent)?;
         zv.set_hashtable(arr);
         Ok(())
     }
 }
 
+impl<T, const N: usize> IntoZval for [T; N]
+where
+    T: IntoZval,
+{
+    const TYPE: DataType = DataType::Array;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        Vec::from(self).set_zval(zv, persistent)
+    }
+}
+
 impl<'a, T> FromZval<'a> for Vec<T>
 where
     T: FromZval<'a>,
@@ -2,6 +2,7 @@
 //! by classes, allowing users to store Rust data inside a PHP object.
 
 use std::{
+    any::TypeId,
     fmt::Debug,
     mem,
     ops::{Deref, DerefMut},
@@ -11,16 +12,17 @@ use std::{
 
 use crate::{
     boxed::{ZBox, ZBoxable},
-    class::RegisteredClass,
-    convert::{FromZendObject, FromZendObjectMut, FromZval, FromZvalMut, IntoZval},
+    class::{ClassHandlers, RegisteredClass},
+    convert::{FromZendObject, FromZendObjectMut, FromZval, FromZvalMut, IntoZval, IntoZvalDyn},
     error::{Error, Result},
     ffi::{
         ext_php_rs_zend_object_alloc, ext_php_rs_zend_object_release, object_properties_init,
-        zend_object, zend_object_std_init, zend_objects_clone_members,
+        zend_call_known_function, zend_function, zend_hash_str_find_ptr_lc, zend_object,
+        zend_object_std_init, zend_objects_clone_members,
     },
-    flags::DataType,
+    flags::{DataType, ZvalTypeFlags},
     types::{ZendObject, Zval},
-    zend::ClassEntry,
+    zend::{ClassEntry, ExecutorGlobals},
 };
 
 /// Representation of a Zend class object in memory.
@@ -139,9 +141,14 @@ impl<T: RegisteredClass> ZendClassObject<T> {
         self.obj.replace(val)
     }
 
-    /// Returns a mutable reference to the [`ZendClassObject`] of a given zend
-    /// object `obj`. Returns [`None`] if the given object is not of the
-    /// type `T`.
+    /// Returns a reference to the [`ZendClassObject`] of a given zend object
+    /// `obj`. Returns [`None`] if the given object is not of the type `T`.
+    ///
+    /// Instances of Rust classes extending `T` with `#[extends_class]` are
+    /// laid out as the child class, so [`None`] is also returned for them. Use
+    /// [`upcast`] to get the value of `T` from these objects.
+    ///
+    /// [`upcast`]: #method.upcast
     ///
     /// # Parameters
     ///
@@ -152,7 +159,10 @@ impl<T: RegisteredClass> ZendClassObject<T> {
 
     /// Returns a mutable reference to the [`ZendClassObject`] of a given zend
     /// object `obj`. Returns [`None`] if the given object is not of the
-    /// type `T`.
+    /// type `T`, including instances of Rust classes extending `T`. See
+    /// [`from_zend_obj`].
+    ///
+    /// [`from_zend_obj`]: #method.from_zend_obj
     ///
     /// # Parameters
     ///
@@ -169,18 +179,213 @@ impl<T: RegisteredClass> ZendClassObject<T> {
             (ptr as *mut Self).as_mut()?
         };
 
-        if ptr.std.instance_of(T::get_metadata().ce()) {
+        // Instances of `T` always use the handlers of `T`. Checking the class would also
+        // accept instances of Rust classes extending `T`, whose layout is different.
+        if ptr::eq(ptr.std.handlers, T::get_metadata().handlers()) {
             Some(ptr)
         } else {
             None
         }
     }
 
+    /// Returns a reference to the Rust value of type `T` stored in the given
+    /// object. Unlike [`from_zend_obj`], the object can also be an instance of
+    /// a Rust class which extends `T` with `#[extends_class]`.
+    ///
+    /// Returns [`None`] if the object is not an instance of `T`, or if it has
+    /// not been initialized.
+    ///
+    /// [`from_zend_obj`]: #method.from_zend_obj
+    ///
+    /// # Parameters
+    ///
+    /// * `std` - The zend object to get the value of `T` from.
+    pub fn upcast(std: &zend_object) -> Option<&T> {
+        let handlers = Self::class_handlers(std)?;
+        (handlers.upcast)(std, TypeId::of::<T>())?.downcast_ref()
+    }
+
+    /// Returns a mutable reference to the Rust value of type `T` stored in
+    /// the given object. See [`upcast`].
+    ///
+    /// [`upcast`]: #method.upcast
+    ///
+    /// # Parameters
+    ///
+    /// * `std` - The zend object to get the value of `T` from.
+    pub fn upcast_mut(std: &mut zend_object) -> Option<&mut T> {
+        let handlers = Self::class_handlers(std)?;
+        (handlers.upcast_mut)(std, TypeId::of::<T>())?.downcast_mut()
+    }
+
+    /// Returns the handlers of the Rust class of an instance of `T`.
+    fn class_handlers(std: &zend_object) -> Option<&'static ClassHandlers> {
+        // Objects can use other handlers than the handlers of their class, such as
+        // objects cloned with the standard handlers of the engine.
+        let meta = T::get_metadata();
+        if ptr::eq(std.handlers, meta.handlers()) {
+            return Some(meta.class_handlers());
+        }
+        if !std.instance_of(meta.ce()) {
+            return None;
+        }
+        ClassHandlers::of(std)
+    }
+
+    /// Invalidates the cached value of a property whose getter is cached, so
+    /// the getter is called again the next time the property is read. Does
+    /// nothing if no value is cached.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property.
+    pub fn invalidate_prop(&mut self, name: &str) {
+        if let Some(slot) = self.std.declared_property_mut(name) {
+            let mut undef = Zval::new();
+            undef.u1.type_info = ZvalTypeFlags::Undef.bits();
+            // The cached value is released when it is dropped.
+            drop(mem::replace(slot, undef));
+        }
+    }
+
     /// Returns a mutable reference to the underlying Zend object.
     pub fn get_mut_zend_obj(&mut self) -> &mut zend_object {
         &mut self.std
     }
 
+    /// Calls a method of the parent class of `T` on the object, as
+    /// `parent::method()` does in PHP. This is used to run the behaviour of
+    /// the parent class, such as the constructor of `Exception` when the
+    /// class extends it.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the method.
+    /// * `params` - The arguments to pass to the method.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidScope`] if the class does not have a parent.
+    /// * [`Error::Callable`] if the parent does not have the method.
+    /// * [`Error::Exception`] if the method threw an exception.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::{boxed::ZBox, prelude::*, types::ZendClassObject, zend::ce};
+    ///
+    /// #[php_class]
+    /// #[extends(ce::exception())]
+    /// pub struct HttpError {
+    ///     status: i64,
+    /// }
+    ///
+    /// #[php_function]
+    /// pub fn not_found() -> PhpResult<ZBox<ZendClassObject<HttpError>>> {
+    ///     let mut err = ZendClassObject::new(HttpError { status: 404 });
+    ///     err.call_parent_method("__construct", vec![&"Not found", &404])?;
+    ///     Ok(err)
+    /// }
+    /// # #[php_module]
+    /// # pub fn get_module(module: ModuleBuilder) -> ModuleBuilder {
+    /// #     module
+    /// # }
+    /// ```
+    pub fn call_parent_method(
+        &mut self,
+        name: &str,
+        params: Vec<&dyn IntoZvalDyn>,
+    ) -> Result<Zval> {
+        let parent = T::get_metadata().ce().parent().ok_or(Error::InvalidScope)?;
+        let params = params
+            .into_iter()
+            .map(|val| val.as_zval(false))
+            .collect::<Result<Vec<_>>>()?;
+        let mut retval = Zval::new();
+
+        unsafe {
+            let func = zend_hash_str_find_ptr_lc(
+                &parent.function_table,
+                name.as_ptr() as *const c_char,
+                name.len(),
+            ) as *mut zend_function;
+            if func.is_null() {
+                return Err(Error::Callable);
+            }
+            // The called scope is kept as the class of the object, as `parent::` does.
+            zend_call_known_function(
+                func,
+                &mut self.std,
+                self.std.ce,
+                &mut retval,
+                params.len() as _,
+                params.as_ptr() as *mut _,
+                ptr::null_mut(),
+            )
+        };
+
+        match ExecutorGlobals::take_exception() {
+            Some(e) => Err(Error::Exception(e)),
+            None => Ok(retval),
+        }
+    }
+
+    /// Calls a method on the object from the scope of `T`, as `$this->method()`
+    /// does inside a method of the class in PHP. Unlike
+    /// [`ZendObject::try_call_method`], visibility is checked, so private and
+    /// protected methods of the class can be called while private methods of
+    /// subclasses cannot. Private methods of `T` are called even if a subclass
+    /// declares a method with the same name.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the method.
+    /// * `params` - The arguments to pass to the method.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::InvalidScope`] if the object does not have handlers.
+    /// * [`Error::Exception`] if the method could not be called from the scope
+    ///   of `T` or threw an exception.
+    /// * [`Error::Callable`] if the method could not be found.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::{prelude::*, types::ZendClassObject};
+    ///
+    /// #[php_class]
+    /// pub struct Counter {
+    ///     count: i64,
+    /// }
+    ///
+    /// #[php_impl]
+    /// impl Counter {
+    ///     pub fn increment(#[this] this: &mut ZendClassObject<Counter>) -> PhpResult<()> {
+    ///         // `validate()` is a protected method declared by a subclass in PHP.
+    ///         this.call_method_scoped("validate", vec![])?;
+    ///         this.count += 1;
+    ///         Ok(())
+    ///     }
+    /// }
+    /// # #[php_module]
+    /// # pub fn get_module(module: ModuleBuilder) -> ModuleBuilder {
+    /// #     module
+    /// # }
+    /// ```
+    pub fn call_method_scoped(
+        &mut self,
+        name: &str,
+        params: Vec<&dyn IntoZvalDyn>,
+    ) -> Result<Zval> {
+        let params = params
+            .into_iter()
+            .map(|val| val.as_zval(false))
+            .collect::<Result<Vec<_>>>()?;
+        self.std
+            .call_method_in_scope(name, &params, Some(T::get_metadata().ce()))
+    }
+
     /// Returns the offset of the `std` property in the class object.
     pub(crate) fn std_offset() -> usize {
         unsafe {
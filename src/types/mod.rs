@@ -11,8 +11,11 @@ mod iterator;
 mod long;
 mod object;
 mod string;
+mod writer;
 mod zval;
 
+#[cfg(feature = "serde")]
+pub(crate) use array::{canonical_index, RawElement, Values};
 pub use array::{ArrayKey, ZendHashTable};
 pub use callable::ZendCallable;
 pub use class_object::ZendClassObject;
@@ -20,7 +23,8 @@ pub use iterable::Iterable;
 pub use iterator::ZendIterator;
 pub use long::ZendLong;
 pub use object::{PropertyQuery, ZendObject};
-pub use string::ZendStr;
+pub use string::{PersistentString, ZendStr, ZendStrKey, ZendStrSplit};
+pub use writer::{ZendStrBuilder, ZvalWriter};
 pub use zval::Zval;
 
 use crate::{convert::FromZval, flags::DataType, macros::into_zval};
@@ -1,7 +1,7 @@
 //! Represents an object in PHP. Allows for overriding the internal object used
 //! by classes, allowing users to store Rust data inside a PHP object.
 
-use std::{convert::TryInto, fmt::Debug, ops::DerefMut, os::raw::c_char};
+use std::{convert::TryInto, fmt::Debug, mem, ops::DerefMut, os::raw::c_char, ptr};
 
 use crate::{
     boxed::{ZBox, ZBoxable},
@@ -9,8 +9,9 @@ use crate::{
     convert::{FromZendObject, FromZval, FromZvalMut, IntoZval, IntoZvalDyn},
     error::{Error, Result},
     ffi::{
-        ext_php_rs_zend_object_release, object_properties_init, zend_call_known_function,
-        zend_function, zend_hash_str_find_ptr_lc, zend_object, zend_objects_new, HashTable,
+        ext_php_rs_zend_object_release, object_init_ex, object_properties_init,
+        zend_call_known_function, zend_function, zend_hash_str_find_ptr_lc, zend_object,
+        zend_objects_new, HashTable, BP_VAR_R, IS_OBJ_DESTRUCTOR_CALLED, ZEND_ACC_STATIC,
         ZEND_ISEMPTY, ZEND_PROPERTY_EXISTS, ZEND_PROPERTY_ISSET,
     },
     flags::DataType,
@@ -84,6 +85,83 @@ impl ZendObject {
         Self::new(ce::stdclass())
     }
 
+    /// Creates a new instance of a class and calls its constructor with the
+    /// given arguments, as `new` does in PHP.
+    ///
+    /// # Parameters
+    ///
+    /// * `ce` - The class to instantiate.
+    /// * `args` - The arguments to pass to the constructor.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Exception`] if the class cannot be instantiated, e.g.
+    ///   because it is abstract or its constructor is private, or if the
+    ///   constructor threw an exception.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::{types::ZendObject, zend::ClassEntry};
+    ///
+    /// let ce = ClassEntry::try_find("DateTimeImmutable").unwrap();
+    /// let date = ZendObject::new_with_args(ce, vec![&"2000-01-01"]).unwrap();
+    /// ```
+    pub fn new_with_args(ce: &ClassEntry, args: Vec<&dyn IntoZvalDyn>) -> Result<ZBox<Self>> {
+        let args = args
+            .into_iter()
+            .map(|val| val.as_zval(false))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut zv = Zval::new();
+        // SAFETY: The class entry is not modified. On success the zval holds the only
+        // reference to the new object, which is moved into the box.
+        let mut obj = unsafe {
+            object_init_ex(&mut zv, ce as *const _ as *mut _);
+            if let Some(e) = ExecutorGlobals::take_exception() {
+                return Err(Error::Exception(e));
+            }
+            let obj = zv.object_mut().map(|obj| obj as *mut Self);
+            zv.release();
+            ZBox::from_raw(obj.ok_or(Error::Object)?)
+        };
+
+        let ctor = unsafe { obj.handlers()? }
+            .get_constructor
+            .map_or(std::ptr::null_mut(), |get| unsafe { get(obj.deref_mut()) });
+        if let Some(e) = ExecutorGlobals::take_exception() {
+            obj.mark_ctor_failed();
+            return Err(Error::Exception(e));
+        }
+        if ctor.is_null() {
+            return Ok(obj);
+        }
+
+        let mut retval = Zval::new();
+        unsafe {
+            zend_call_known_function(
+                ctor,
+                obj.deref_mut(),
+                obj.ce,
+                &mut retval,
+                args.len() as _,
+                args.as_ptr() as *mut _,
+                std::ptr::null_mut(),
+            )
+        };
+        if let Some(e) = ExecutorGlobals::take_exception() {
+            obj.mark_ctor_failed();
+            return Err(Error::Exception(e));
+        }
+        Ok(obj)
+    }
+
+    /// Marks the object as not constructed, so its destructor is not called
+    /// when it is released, as PHP does when a constructor fails.
+    fn mark_ctor_failed(&mut self) {
+        unsafe { self.gc.u.type_info |= IS_OBJ_DESTRUCTOR_CALLED };
+    }
+
     /// Converts a class object into an owned [`ZendObject`]. This removes any
     /// possibility of accessing the underlying attached Rust struct.
     pub fn from_class_object<T: RegisteredClass>(obj: ZBox<ZendClassObject<T>>) -> ZBox<Self> {
@@ -114,6 +192,46 @@ impl ZendObject {
         }
     }
 
+    /// Returns the slot in the properties table storing a property declared
+    /// on the class of the object, regardless of its visibility. Returns
+    /// [`None`] if the property is not declared.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property.
+    pub(crate) fn declared_property(&self, name: &str) -> Option<&Zval> {
+        let offset = self.declared_property_offset(name)?;
+        // SAFETY: The offset is the offset of the slot from the start of the object.
+        unsafe { ((self as *const Self as *const u8).add(offset) as *const Zval).as_ref() }
+    }
+
+    /// Returns the slot in the properties table storing a property declared
+    /// on the class of the object, regardless of its visibility. Returns
+    /// [`None`] if the property is not declared.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property.
+    pub(crate) fn declared_property_mut(&mut self, name: &str) -> Option<&mut Zval> {
+        let offset = self.declared_property_offset(name)?;
+        // SAFETY: The offset is the offset of the slot from the start of the object.
+        unsafe { ((self as *mut Self as *mut u8).add(offset) as *mut Zval).as_mut() }
+    }
+
+    /// Returns the offset of the slot of a declared property from the start of
+    /// the object, mirroring the `OBJ_PROP` macro.
+    fn declared_property_offset(&self, name: &str) -> Option<usize> {
+        let info = self.get_class_entry().properties_info.get(name)?;
+
+        // SAFETY: The values of the table are pointers to property infos.
+        let info = unsafe { info.ptr::<crate::ffi::zend_property_info>()?.as_ref()? };
+        // Static properties are stored on the class rather than the object.
+        if info.flags & ZEND_ACC_STATIC != 0 {
+            return None;
+        }
+        Some(info.offset as usize)
+    }
+
     /// Returns whether this object is an instance of the given [`ClassEntry`].
     ///
     /// This method checks the class and interface inheritance chain.
@@ -129,10 +247,48 @@ impl ZendObject {
     /// Rust type `T`.
     ///
     /// This method doesn't check the class and interface inheritance chain.
+    /// Use [`ZendObject::downcast_ref`] to also accept instances of classes
+    /// extending `T`.
     pub fn is_instance<T: RegisteredClass>(&self) -> bool {
         (self.ce as *const ClassEntry).eq(&(T::get_metadata().ce() as *const _))
     }
 
+    /// Returns a reference to the Rust value of the registered class `T`
+    /// stored in the object. The object can be an instance of `T`, of a PHP
+    /// class extending `T`, or of a Rust class extending `T` with
+    /// `#[extends_class]`.
+    ///
+    /// Returns [`None`] if the object is not an instance of `T`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::{prelude::*, types::ZendObject};
+    ///
+    /// #[php_class]
+    /// pub struct Shape {
+    ///     sides: i64,
+    /// }
+    ///
+    /// #[php_function]
+    /// pub fn sides(obj: &ZendObject) -> Option<i64> {
+    ///     obj.downcast_ref::<Shape>().map(|shape| shape.sides)
+    /// }
+    /// # #[php_module]
+    /// # pub fn get_module(module: ModuleBuilder) -> ModuleBuilder {
+    /// #     module
+    /// # }
+    /// ```
+    pub fn downcast_ref<T: RegisteredClass>(&self) -> Option<&T> {
+        ZendClassObject::<T>::upcast(self)
+    }
+
+    /// Returns a mutable reference to the Rust value of the registered class
+    /// `T` stored in the object. See [`ZendObject::downcast_ref`].
+    pub fn downcast_mut<T: RegisteredClass>(&mut self) -> Option<&mut T> {
+        ZendClassObject::<T>::upcast_mut(self)
+    }
+
     /// Returns whether this object is an instance of \Traversable
     ///
     /// # Panics
@@ -296,6 +452,101 @@ impl ZendObject {
         format!("{:016x}0000000000000000", self.handle)
     }
 
+    /// Calls a method on the object, resolving the method as `$obj->method()`
+    /// does, with visibility checked against the given scope or the scope of
+    /// the executing function.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the method.
+    /// * `params` - The arguments to pass to the method.
+    /// * `scope` - The class the method is called from, or [`None`] to use the
+    ///   scope of the executing function.
+    pub(crate) fn call_method_in_scope(
+        &mut self,
+        name: &str,
+        params: &[Zval],
+        scope: Option<&ClassEntry>,
+    ) -> Result<Zval> {
+        let get_method = unsafe { self.handlers()? }
+            .get_method
+            .ok_or(Error::InvalidScope)?;
+        let mut method = ZendStr::new(name, false);
+        let mut obj: *mut ZendObject = self;
+        let mut retval = Zval::new();
+
+        // The method is resolved with a fake scope, as the engine does when
+        // accessing properties from the scope of a class. The globals are not
+        // held while calling into the engine.
+        let prev_scope = scope.map(|scope| {
+            let scope = scope as *const ClassEntry as *mut ClassEntry;
+            mem::replace(&mut ExecutorGlobals::get_mut().fake_scope, scope)
+        });
+        let func = unsafe { get_method(&mut obj, method.deref_mut(), ptr::null()) };
+        if let Some(prev_scope) = prev_scope {
+            ExecutorGlobals::get_mut().fake_scope = prev_scope;
+        }
+
+        if let Some(e) = ExecutorGlobals::take_exception() {
+            return Err(Error::Exception(e));
+        }
+        if func.is_null() {
+            return Err(Error::Callable);
+        }
+
+        unsafe {
+            zend_call_known_function(
+                func,
+                obj,
+                (*obj).ce,
+                &mut retval,
+                params.len() as _,
+                params.as_ptr() as *mut _,
+                ptr::null_mut(),
+            )
+        };
+
+        match ExecutorGlobals::take_exception() {
+            Some(e) => Err(Error::Exception(e)),
+            None => Ok(retval),
+        }
+    }
+
+    /// Reads a property of the object as `$obj->name` does from the scope of
+    /// the executing function, including dynamic properties and properties
+    /// returned by `__get()`.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property.
+    pub(crate) fn read_property(&self, name: &str) -> Result<Zval> {
+        let read_property = unsafe { self.handlers()? }
+            .read_property
+            .ok_or(Error::InvalidScope)?;
+        let mut name = ZendStr::new(name, false);
+        let mut rv = Zval::new();
+
+        let zv = unsafe {
+            read_property(
+                self.mut_ptr(),
+                name.deref_mut(),
+                BP_VAR_R as _,
+                ptr::null_mut(),
+                &mut rv,
+            )
+            .as_ref()
+        };
+
+        if let Some(e) = ExecutorGlobals::take_exception() {
+            return Err(Error::Exception(e));
+        }
+        // The returned zval may be `rv` itself, which is released when dropped.
+        // Properties holding references are read as their value, as `__get()`
+        // would return them.
+        zv.map(|zv| zv.dereference().shallow_clone())
+            .ok_or(Error::InvalidProperty)
+    }
+
     /// Attempts to retrieve a reference to the object handlers.
     #[inline]
     unsafe fn handlers(&self) -> Result<&ZendObjectHandlers> {
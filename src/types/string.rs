@@ -2,27 +2,32 @@
 //! reference counted and contains the length of the string.
 
 use std::{
-    borrow::Cow,
+    borrow::{Borrow, Cow},
     convert::TryFrom,
-    ffi::{CStr, CString},
+    ffi::{CStr, CString, OsStr, OsString},
     fmt::Debug,
-    slice,
+    hash::{Hash, Hasher},
+    ops::{Index, RangeBounds},
+    path::{Path, PathBuf},
+    slice::{self, SliceIndex},
 };
 
 use parking_lot::{const_mutex, Mutex};
 
+#[cfg(php82)]
+use crate::ffi::zend_string_toupper_ex;
 use crate::{
     boxed::{ZBox, ZBoxable},
     convert::{FromZval, IntoZval},
     error::{Error, Result},
     ffi::{
         ext_php_rs_is_known_valid_utf8, ext_php_rs_set_known_valid_utf8,
-        ext_php_rs_zend_string_init, ext_php_rs_zend_string_release, zend_string,
-        zend_string_init_interned,
+        ext_php_rs_zend_string_init, ext_php_rs_zend_string_release, zend_hash_func, zend_string,
+        zend_string_init_interned, zend_string_tolower_ex, GC_PERSISTENT,
     },
     flags::DataType,
     macros::try_from_zval,
-    types::Zval,
+    types::{ZendCallable, Zval},
 };
 
 /// A borrowed Zend string.
@@ -88,6 +93,35 @@ impl ZendStr {
         }
     }
 
+    /// Creates a new persistent Zend string, which is allocated outside of
+    /// the request heap and so can outlive the request.
+    ///
+    /// Persistent strings must not be stored in zvals passed to PHP, as the
+    /// engine assumes values in the request are allocated on the request heap.
+    /// Nothing prevents the returned string from being stored in a zval, so
+    /// strings which are kept across requests should be wrapped in a
+    /// [`PersistentString`], which is copied when converted into a zval.
+    ///
+    /// # Parameters
+    ///
+    /// * `str` - String content.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the function was unable to allocate memory for the Zend
+    /// string.
+    pub fn new_persistent(str: impl AsRef<[u8]>) -> ZBox<Self> {
+        Self::new(str, true)
+    }
+
+    /// Creates a new Zend string from a [`str`], marking it as valid UTF-8 so
+    /// it is not validated again when read with [`ZendStr::as_str`].
+    pub(crate) fn new_str(str: &str, persistent: bool) -> ZBox<Self> {
+        let mut s = Self::new(str, persistent);
+        unsafe { ext_php_rs_set_known_valid_utf8(s.as_mut_ptr()) };
+        s
+    }
+
     /// Creates a new Zend string from a [`CStr`].
     ///
     /// # Parameters
@@ -293,21 +327,283 @@ impl ZendStr {
     /// let s = ZendStr::new("hello, world!", false);
     /// assert!(s.as_str().is_ok());
     /// ```
+    ///
+    /// The result of the validation is cached on the string, so later calls
+    /// for the same string do not validate it again. With the `simdutf8`
+    /// feature, the validation uses SIMD instructions where available.
     pub fn as_str(&self) -> Result<&str> {
         if unsafe { ext_php_rs_is_known_valid_utf8(self.as_ptr()) } {
-            let str = unsafe { std::str::from_utf8_unchecked(self.as_bytes()) };
-            return Ok(str);
+            // SAFETY: The string has already been validated.
+            return Ok(unsafe { self.as_str_unchecked() });
         }
-        let str = std::str::from_utf8(self.as_bytes()).map_err(|_| Error::InvalidUtf8)?;
+        let str = validate_utf8(self.as_bytes()).ok_or(Error::InvalidUtf8)?;
         unsafe { ext_php_rs_set_known_valid_utf8(self.as_ptr() as *mut _) };
         Ok(str)
     }
 
+    /// Returns a reference to the contents of the Zend string as a [`str`],
+    /// without checking that it is valid UTF-8.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure the string is valid UTF-8, for example because
+    /// it was created from a [`str`] or was previously checked by
+    /// [`ZendStr::as_str`].
+    pub unsafe fn as_str_unchecked(&self) -> &str {
+        std::str::from_utf8_unchecked(self.as_bytes())
+    }
+
     /// Returns a reference to the underlying bytes inside the Zend string.
     pub fn as_bytes(&self) -> &[u8] {
         unsafe { slice::from_raw_parts(self.val.as_ptr().cast(), self.len()) }
     }
 
+    /// Creates a new Zend string from part of the string, copying the bytes
+    /// directly from this string. The new string is allocated on the request
+    /// heap.
+    ///
+    /// To read part of the string without allocating, index the string
+    /// instead, e.g. `&s[2..5]`.
+    ///
+    /// # Parameters
+    ///
+    /// * `range` - The byte range to copy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds of the string.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendStr;
+    ///
+    /// let s = ZendStr::new("hello, world!", false);
+    /// assert_eq!(s.substr(7..12).as_bytes(), b"world");
+    /// assert_eq!(&s[..5], b"hello");
+    /// ```
+    pub fn substr(&self, range: impl RangeBounds<usize>) -> ZBox<ZendStr> {
+        let range = (range.start_bound().cloned(), range.end_bound().cloned());
+        Self::new(&self.as_bytes()[range], false)
+    }
+
+    /// Returns whether the string was allocated with the persistent
+    /// allocator, and therefore survives the end of the request.
+    pub fn is_persistent(&self) -> bool {
+        let type_info = unsafe { self.gc.u.type_info };
+        type_info & GC_PERSISTENT != 0
+    }
+
+    /// Returns the string with ASCII letters converted to lowercase, in the
+    /// same way as PHP's `strtolower()`. Other bytes, including non-ASCII
+    /// characters, are left unchanged, so the result can be used for
+    /// case-insensitive lookups of identifiers such as class and function
+    /// names.
+    ///
+    /// If the string is already lowercase, it is borrowed instead of being
+    /// copied.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendStr;
+    ///
+    /// let s = ZendStr::new("ArrayAccess", false);
+    /// assert_eq!(s.to_lowercase().as_bytes(), b"arrayaccess");
+    /// ```
+    pub fn to_lowercase(&self) -> Cow<'_, ZendStr> {
+        if !self.as_bytes().iter().any(u8::is_ascii_uppercase) {
+            return Cow::Borrowed(self);
+        }
+        if self.is_persistent() {
+            return Cow::Owned(Self::new(self.as_bytes().to_ascii_lowercase(), false));
+        }
+        // SAFETY: The string contains uppercase letters, so the engine returns a new
+        // string owned by the caller.
+        Cow::Owned(unsafe {
+            Self::from_case_conversion(zend_string_tolower_ex(self.as_ptr() as *mut _, false))
+        })
+    }
+
+    /// Returns the string with ASCII letters converted to uppercase, in the
+    /// same way as PHP's `strtoupper()`. Other bytes are left unchanged.
+    ///
+    /// As with [`ZendStr::to_lowercase`], a string which is already uppercase
+    /// is borrowed instead of being copied.
+    pub fn to_uppercase(&self) -> Cow<'_, ZendStr> {
+        if !self.as_bytes().iter().any(u8::is_ascii_lowercase) {
+            return Cow::Borrowed(self);
+        }
+        cfg_if::cfg_if! {
+            if #[cfg(php82)] {
+                if self.is_persistent() {
+                    return Cow::Owned(Self::new(self.as_bytes().to_ascii_uppercase(), false));
+                }
+                // SAFETY: The string contains lowercase letters, so the engine returns a
+                // new string owned by the caller.
+                Cow::Owned(unsafe {
+                    Self::from_case_conversion(zend_string_toupper_ex(self.as_ptr() as *mut _, false))
+                })
+            } else {
+                Cow::Owned(Self::new(self.as_bytes().to_ascii_uppercase(), false))
+            }
+        }
+    }
+
+    /// Takes ownership of the string returned by a Zend case conversion
+    /// function.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must be a new string owned by the caller, which is not
+    /// shared with any other value.
+    unsafe fn from_case_conversion(ptr: *mut ZendStr) -> ZBox<ZendStr> {
+        ZBox::from_raw(
+            ptr.as_mut()
+                .expect("Failed to allocate memory for new Zend string"),
+        )
+    }
+
+    /// Returns whether the string starts with the given bytes. The
+    /// comparison is binary-safe and does not validate the string as UTF-8.
+    ///
+    /// # Parameters
+    ///
+    /// * `prefix` - The bytes to look for.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendStr;
+    ///
+    /// let s = ZendStr::new("https://php.net", false);
+    /// assert!(s.starts_with("https://"));
+    /// ```
+    pub fn starts_with(&self, prefix: impl AsRef<[u8]>) -> bool {
+        self.as_bytes().starts_with(prefix.as_ref())
+    }
+
+    /// Returns whether the string ends with the given bytes. The comparison
+    /// is binary-safe and does not validate the string as UTF-8.
+    ///
+    /// # Parameters
+    ///
+    /// * `suffix` - The bytes to look for.
+    pub fn ends_with(&self, suffix: impl AsRef<[u8]>) -> bool {
+        self.as_bytes().ends_with(suffix.as_ref())
+    }
+
+    /// Returns the byte offset of the first occurrence of the given bytes in
+    /// the string, or [`None`] if they do not occur. An empty needle is found
+    /// at offset 0.
+    ///
+    /// # Parameters
+    ///
+    /// * `needle` - The bytes to look for.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendStr;
+    ///
+    /// let s = ZendStr::new("key=value", false);
+    /// assert_eq!(s.find("="), Some(3));
+    /// assert_eq!(s.find("\0"), None);
+    /// ```
+    pub fn find(&self, needle: impl AsRef<[u8]>) -> Option<usize> {
+        find_bytes(self.as_bytes(), needle.as_ref())
+    }
+
+    /// Returns an iterator over the parts of the string separated by the given
+    /// bytes, in the same way as PHP's `explode()` without a limit.
+    ///
+    /// # Parameters
+    ///
+    /// * `separator` - The bytes separating the parts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the separator is empty.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendStr;
+    ///
+    /// let s = ZendStr::new("a,b,,c", false);
+    /// let parts: Vec<&[u8]> = s.split(",").collect();
+    /// assert_eq!(parts, [&b"a"[..], b"b", b"", b"c"]);
+    /// ```
+    pub fn split<'a>(&'a self, separator: &'a (impl AsRef<[u8]> + ?Sized)) -> ZendStrSplit<'a> {
+        let separator = separator.as_ref();
+        assert!(!separator.is_empty(), "Separator must not be empty");
+        ZendStrSplit {
+            rest: Some(self.as_bytes()),
+            separator,
+        }
+    }
+
+    /// Converts the string between character encodings, returning a new
+    /// string in the target encoding.
+    ///
+    /// The conversion is performed by `mb_convert_encoding()` if the mbstring
+    /// extension is loaded, falling back to `iconv()` otherwise. Any encoding
+    /// name understood by the extension performing the conversion can be
+    /// used. This can only be called while a request is active.
+    ///
+    /// # Parameters
+    ///
+    /// * `from` - The encoding of the string.
+    /// * `to` - The encoding to convert the string into.
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::EncodingUnavailable`] if neither mbstring nor iconv is
+    ///   loaded.
+    /// * [`Error::EncodingConversion`] if the string could not be converted,
+    ///   e.g. because it contains characters which are invalid in `from`.
+    /// * [`Error::Exception`] if the conversion threw an exception, e.g.
+    ///   because an encoding is unknown.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendStr;
+    ///
+    /// let latin1 = ZendStr::new(b"caf\xe9", false);
+    /// let utf8 = latin1.convert_encoding("ISO-8859-1", "UTF-8").unwrap();
+    /// assert_eq!(utf8.as_str().unwrap(), "café");
+    /// ```
+    pub fn convert_encoding(&self, from: &str, to: &str) -> Result<ZBox<ZendStr>> {
+        let bytes = self.as_bytes();
+        let result = if let Ok(convert) = ZendCallable::try_from_name("mb_convert_encoding") {
+            convert.try_call(vec![&bytes, &to, &from])?
+        } else if let Ok(iconv) = ZendCallable::try_from_name("iconv") {
+            iconv.try_call(vec![&from, &to, &bytes])?
+        } else {
+            return Err(Error::EncodingUnavailable);
+        };
+
+        result
+            .zend_str()
+            .map(ToOwned::to_owned)
+            .ok_or(Error::EncodingConversion)
+    }
+
+    /// Returns the hash PHP uses for the string as an array key.
+    ///
+    /// Interned strings such as array keys and identifiers always carry their
+    /// hash, in which case it is returned without reading the contents of the
+    /// string. Otherwise the hash is computed on every call, as it cannot be
+    /// stored on a string which may be shared.
+    pub fn hash_value(&self) -> u64 {
+        if self.h != 0 {
+            return self.h;
+        }
+        // SAFETY: The pointer and length refer to the contents of the string.
+        unsafe { zend_hash_func(self.val.as_ptr(), self.len) }
+    }
+
     /// Returns a raw pointer to this object
     pub fn as_ptr(&self) -> *const ZendStr {
         self as *const _
@@ -319,10 +615,264 @@ impl ZendStr {
     }
 }
 
+impl<I> Index<I> for ZendStr
+where
+    I: SliceIndex<[u8], Output = [u8]>,
+{
+    type Output = [u8];
+
+    fn index(&self, index: I) -> &[u8] {
+        &self.as_bytes()[index]
+    }
+}
+
+/// A borrowed Zend string which can be used as the key of a [`HashMap`],
+/// without copying the string into a [`String`].
+///
+/// The key hashes the same as the [`str`] it contains, so maps can be
+/// queried with a [`str`] through the [`Borrow`] implementation. Comparisons
+/// first check the hashes PHP has already computed for the strings, which
+/// makes keys taken from PHP arrays cheap to compare.
+///
+/// Keys borrow the string they were created from, so cannot outlive the
+/// request.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::collections::HashMap;
+/// use ext_php_rs::types::{ZendHashTable, ZendStrKey};
+///
+/// fn count_words<'a>(words: &'a ZendHashTable) -> HashMap<ZendStrKey<'a>, usize> {
+///     let mut counts = HashMap::new();
+///     for word in words.values().filter_map(|zv| zv.extract::<ZendStrKey>()) {
+///         *counts.entry(word).or_insert(0) += 1;
+///     }
+///     counts
+/// }
+/// ```
+///
+/// [`HashMap`]: std::collections::HashMap
+#[derive(Clone, Copy)]
+pub struct ZendStrKey<'a>(&'a ZendStr);
+
+impl<'a> ZendStrKey<'a> {
+    /// Creates a key from a Zend string.
+    ///
+    /// # Parameters
+    ///
+    /// * `s` - The string to borrow.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidUtf8`] if the string is not valid UTF-8.
+    pub fn new(s: &'a ZendStr) -> Result<Self> {
+        s.as_str()?;
+        Ok(Self(s))
+    }
+
+    /// Returns the contents of the key.
+    pub fn as_str(&self) -> &'a str {
+        // SAFETY: The string was validated when the key was created.
+        unsafe { self.0.as_str_unchecked() }
+    }
+
+    /// Returns the Zend string the key borrows.
+    pub fn as_zend_str(&self) -> &'a ZendStr {
+        self.0
+    }
+}
+
+impl PartialEq for ZendStrKey<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        if std::ptr::eq(self.0, other.0) {
+            return true;
+        }
+        if self.0.h != 0 && other.0.h != 0 && self.0.h != other.0.h {
+            return false;
+        }
+        self.0.as_bytes() == other.0.as_bytes()
+    }
+}
+
+impl Eq for ZendStrKey<'_> {}
+
+impl Hash for ZendStrKey<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl Borrow<str> for ZendStrKey<'_> {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Debug for ZendStrKey<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl<'a> TryFrom<&'a ZendStr> for ZendStrKey<'a> {
+    type Error = Error;
+
+    fn try_from(value: &'a ZendStr) -> Result<Self> {
+        Self::new(value)
+    }
+}
+
+impl<'a> FromZval<'a> for ZendStrKey<'a> {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        zval.zend_str().and_then(|s| Self::new(s).ok())
+    }
+}
+
+/// A persistent Zend string, which lives outside of the request heap and can
+/// be used in caches shared between requests and threads.
+///
+/// The string can be read through [`Deref`], but is never handed to PHP
+/// directly. Converting it into a zval copies it, by default onto the request
+/// heap, so the persistent string cannot be released by the engine at the end
+/// of a request or be shared between threads through its reference count.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::sync::Mutex;
+/// use ext_php_rs::{prelude::*, types::{PersistentString, ZendStr}, boxed::ZBox};
+///
+/// static LAST_NAME: Mutex<Option<PersistentString>> = Mutex::new(None);
+///
+/// #[php_function]
+/// pub fn remember(name: &str) -> Option<ZBox<ZendStr>> {
+///     let mut last = LAST_NAME.lock().unwrap();
+///     let previous = last.as_ref().map(PersistentString::to_request_str);
+///     *last = Some(PersistentString::new(name));
+///     previous
+/// }
+/// # fn main() {}
+/// ```
+///
+/// [`Deref`]: std::ops::Deref
+pub struct PersistentString(ZBox<ZendStr>);
+
+// SAFETY: The string is only released when dropped and is never shared with
+// PHP, so its reference count is never modified while it is shared. Its hash and
+// whether it is valid UTF-8 are stored on creation, while the string is not
+// shared, so reading it does not write to the string.
+unsafe impl Send for PersistentString {}
+unsafe impl Sync for PersistentString {}
+
+impl PersistentString {
+    /// Creates a new persistent string.
+    ///
+    /// # Parameters
+    ///
+    /// * `str` - String content.
+    pub fn new(str: impl AsRef<[u8]>) -> Self {
+        let mut s = ZendStr::new_persistent(str);
+        s.h = s.hash_value();
+        // `as_str` marks valid strings as such the first time it is called, so
+        // it is called before the string can be shared between threads.
+        let _ = s.as_str();
+        Self(s)
+    }
+
+    /// Copies the string onto the request heap, returning a string which can
+    /// be passed to PHP.
+    pub fn to_request_str(&self) -> ZBox<ZendStr> {
+        ZendStr::new(self.as_bytes(), false)
+    }
+}
+
+impl std::ops::Deref for PersistentString {
+    type Target = ZendStr;
+
+    fn deref(&self) -> &ZendStr {
+        &self.0
+    }
+}
+
+impl Clone for PersistentString {
+    fn clone(&self) -> Self {
+        Self::new(self.as_bytes())
+    }
+}
+
+impl Debug for PersistentString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl IntoZval for &PersistentString {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        zv.set_bytes(self.as_bytes(), persistent);
+        Ok(())
+    }
+}
+
+/// Returns the offset of the first occurrence of `needle` in `haystack`.
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    match needle {
+        [] => Some(0),
+        [byte] => haystack.iter().position(|b| b == byte),
+        _ => haystack
+            .windows(needle.len())
+            .position(|window| window == needle),
+    }
+}
+
+/// An iterator over the parts of a [`ZendStr`], created by
+/// [`ZendStr::split`].
+#[derive(Debug, Clone)]
+pub struct ZendStrSplit<'a> {
+    rest: Option<&'a [u8]>,
+    separator: &'a [u8],
+}
+
+impl<'a> Iterator for ZendStrSplit<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.rest?;
+        match find_bytes(rest, self.separator) {
+            Some(pos) => {
+                self.rest = Some(&rest[pos + self.separator.len()..]);
+                Some(&rest[..pos])
+            }
+            None => self.rest.take(),
+        }
+    }
+}
+
+impl std::iter::FusedIterator for ZendStrSplit<'_> {}
+
+/// Validates that the given bytes are UTF-8, returning them as a [`str`].
+fn validate_utf8(bytes: &[u8]) -> Option<&str> {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "simdutf8")] {
+            simdutf8::basic::from_utf8(bytes).ok()
+        } else {
+            std::str::from_utf8(bytes).ok()
+        }
+    }
+}
+
 unsafe impl ZBoxable for ZendStr {
     fn free(&mut self) {
         unsafe { ext_php_rs_zend_string_release(self) };
     }
+
+    fn is_request_bound(&self) -> bool {
+        !self.is_persistent()
+    }
 }
 
 impl Debug for ZendStr {
@@ -398,13 +948,13 @@ impl From<CString> for ZBox<ZendStr> {
 
 impl From<&str> for ZBox<ZendStr> {
     fn from(value: &str) -> Self {
-        ZendStr::new(value.as_bytes(), false)
+        ZendStr::new_str(value, false)
     }
 }
 
 impl From<String> for ZBox<ZendStr> {
     fn from(value: String) -> Self {
-        ZendStr::new(value.as_str(), false)
+        ZendStr::new_str(value.as_str(), false)
     }
 }
 
@@ -420,6 +970,15 @@ impl From<Cow<'_, ZendStr>> for ZBox<ZendStr> {
     }
 }
 
+impl IntoZval for ZBox<ZendStr> {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        zv.set_zend_string(self);
+        Ok(())
+    }
+}
+
 macro_rules! try_into_zval_str {
     ($type: ty) => {
         impl TryFrom<$type> for Zval {
@@ -454,6 +1013,124 @@ impl<'a> FromZval<'a> for &'a str {
     }
 }
 
+/// Byte slices are converted into binary-safe PHP strings. `Vec<u8>` is
+/// converted into an array of integers like any other [`Vec`], so use
+/// [`Binary<u8>`](crate::binary::Binary) for owned bytes.
+impl IntoZval for &[u8] {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        zv.set_bytes(self, persistent);
+        Ok(())
+    }
+}
+
+impl<'a> FromZval<'a> for &'a [u8] {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        zval.bytes()
+    }
+}
+
+/// Returns the platform string stored in a PHP string. PHP strings are byte
+/// strings, so any string is a valid path on Unix, while other platforms
+/// require the string to be valid UTF-8.
+#[cfg(unix)]
+fn os_str_from_bytes(bytes: &[u8]) -> Option<&OsStr> {
+    use std::os::unix::ffi::OsStrExt;
+
+    Some(OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+fn os_str_from_bytes(bytes: &[u8]) -> Option<&OsStr> {
+    std::str::from_utf8(bytes).ok().map(OsStr::new)
+}
+
+/// Returns the bytes of a platform string to store in a PHP string, without
+/// any lossy conversion.
+#[cfg(unix)]
+fn os_str_bytes(s: &OsStr) -> Result<&[u8]> {
+    use std::os::unix::ffi::OsStrExt;
+
+    Ok(s.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn os_str_bytes(s: &OsStr) -> Result<&[u8]> {
+    s.to_str().map(str::as_bytes).ok_or(Error::InvalidUtf8)
+}
+
+macro_rules! into_zval_os_str {
+    ($type: ty) => {
+        impl IntoZval for $type {
+            const TYPE: DataType = DataType::String;
+
+            fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+                zv.set_bytes(os_str_bytes(self.as_ref())?, persistent);
+                Ok(())
+            }
+        }
+    };
+}
+
+into_zval_os_str!(&OsStr);
+into_zval_os_str!(OsString);
+into_zval_os_str!(&Path);
+into_zval_os_str!(PathBuf);
+
+impl<'a> FromZval<'a> for &'a OsStr {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        zval.bytes().and_then(os_str_from_bytes)
+    }
+}
+
+impl FromZval<'_> for OsString {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &Zval) -> Option<Self> {
+        <&OsStr>::from_zval(zval).map(OsStr::to_os_string)
+    }
+}
+
+impl<'a> FromZval<'a> for &'a Path {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        <&OsStr>::from_zval(zval).map(Path::new)
+    }
+}
+
+impl FromZval<'_> for PathBuf {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &Zval) -> Option<Self> {
+        <&Path>::from_zval(zval).map(Path::to_path_buf)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl IntoZval for bytes::Bytes {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        zv.set_bytes(&self, persistent);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl FromZval<'_> for bytes::Bytes {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &Zval) -> Option<Self> {
+        zval.bytes().map(bytes::Bytes::copy_from_slice)
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "embed")]
 mod tests {
@@ -0,0 +1,248 @@
+//! An incremental writer producing PHP strings.
+
+use std::{fmt, io, ptr, slice};
+
+use crate::{
+    boxed::ZBox,
+    convert::IntoZval,
+    error::{Error, Result},
+    ffi::{
+        ext_php_rs_zend_string_release, smart_str, smart_str_erealloc, zval_try_get_string_func,
+    },
+    flags::DataType,
+    types::{ZendStr, Zval},
+    zend::ExecutorGlobals,
+};
+
+/// Builds a [`ZendStr`] from fragments. This is the same type as
+/// [`ZvalWriter`], named for code which uses the finished string directly
+/// rather than returning it to PHP.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::types::{ZendStrBuilder, Zval};
+///
+/// fn join(parts: &[Zval]) -> ext_php_rs::error::Result<String> {
+///     let mut builder = ZendStrBuilder::new();
+///     for (i, part) in parts.iter().enumerate() {
+///         if i > 0 {
+///             builder.push_str(", ");
+///         }
+///         builder.push_zval_str(part)?;
+///     }
+///     Ok(builder.finish().as_str()?.to_owned())
+/// }
+/// ```
+pub type ZendStrBuilder = ZvalWriter;
+
+/// Builds a PHP string incrementally, without copying it into a Zend string
+/// once complete.
+///
+/// The writer is backed by the engine's `smart_str`, which grows its buffer
+/// geometrically, and writes directly into the Zend string which is returned
+/// to PHP. This makes it suitable for functions producing large outputs, such
+/// as CSV or JSON documents.
+///
+/// The string is allocated on the request heap, so the writer must not
+/// outlive the request it was created in.
+///
+/// Both [`io::Write`] and [`fmt::Write`] are implemented, so the writer can be
+/// used with `write!` in place of `format!`. Only one of the traits should be
+/// imported where `write!` is used, as the macro is ambiguous otherwise.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{prelude::*, types::ZvalWriter};
+/// use std::io::Write;
+///
+/// #[php_function]
+/// pub fn csv(rows: i64) -> PhpResult<ZvalWriter> {
+///     let mut out = ZvalWriter::new();
+///     for i in 0..rows {
+///         writeln!(out, "{},row {}", i, i).map_err(|e| e.to_string())?;
+///     }
+///     Ok(out)
+/// }
+/// # fn main() {}
+/// ```
+pub struct ZvalWriter {
+    buf: smart_str,
+}
+
+impl ZvalWriter {
+    /// Creates a new, empty writer. No memory is allocated until data is
+    /// written.
+    pub fn new() -> Self {
+        Self {
+            buf: smart_str {
+                s: ptr::null_mut(),
+                a: 0,
+            },
+        }
+    }
+
+    /// Creates a new writer with space for at least `capacity` bytes.
+    ///
+    /// # Parameters
+    ///
+    /// * `capacity` - The number of bytes to allocate.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut writer = Self::new();
+        unsafe { smart_str_erealloc(&mut writer.buf, capacity) };
+        writer
+    }
+
+    /// Returns the number of bytes written.
+    pub fn len(&self) -> usize {
+        unsafe { self.buf.s.as_ref() }.map_or(0, ZendStr::len)
+    }
+
+    /// Returns whether no bytes have been written.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the bytes written so far.
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe { self.buf.s.as_ref() }.map_or(&[], ZendStr::as_bytes)
+    }
+
+    /// Appends bytes to the string.
+    ///
+    /// # Parameters
+    ///
+    /// * `bytes` - The bytes to append.
+    pub fn push_bytes(&mut self, bytes: &[u8]) {
+        let len = self.len();
+        let new_len = len + bytes.len();
+        if self.buf.s.is_null() || new_len >= self.buf.a {
+            unsafe { smart_str_erealloc(&mut self.buf, new_len) };
+        }
+
+        // SAFETY: The buffer was grown to hold at least `new_len` bytes, plus the
+        // terminating null byte.
+        unsafe {
+            let s = &mut *self.buf.s;
+            ptr::copy_nonoverlapping(
+                bytes.as_ptr(),
+                s.val.as_mut_ptr().cast::<u8>().add(len),
+                bytes.len(),
+            );
+            s.len = new_len;
+        }
+    }
+
+    /// Appends a string.
+    ///
+    /// # Parameters
+    ///
+    /// * `s` - The string to append.
+    pub fn push_str(&mut self, s: &str) {
+        self.push_bytes(s.as_bytes());
+    }
+
+    /// Appends the string representation of a zval, as PHP would when
+    /// concatenating it. Strings are copied directly, while other values are
+    /// converted, calling `__toString()` on objects.
+    ///
+    /// Returns an error if the value cannot be converted to a string, such as
+    /// an object without a `__toString()` method.
+    ///
+    /// # Parameters
+    ///
+    /// * `zv` - The zval to append.
+    pub fn push_zval_str(&mut self, zv: &Zval) -> Result<()> {
+        if let Some(bytes) = zv.bytes() {
+            self.push_bytes(bytes);
+            return Ok(());
+        }
+
+        let s = unsafe { zval_try_get_string_func(zv as *const Zval as *mut Zval) };
+        if let Some(e) = ExecutorGlobals::take_exception() {
+            if !s.is_null() {
+                unsafe { ext_php_rs_zend_string_release(s) };
+            }
+            return Err(Error::Exception(e));
+        }
+        let s = unsafe { s.as_ref() }.ok_or_else(|| Error::ZvalConversion(zv.get_type()))?;
+        self.push_bytes(s.as_bytes());
+        unsafe { ext_php_rs_zend_string_release(s as *const ZendStr as *mut ZendStr) };
+        Ok(())
+    }
+
+    /// Returns the `smart_str` backing the writer, for engine functions which
+    /// write into one.
+    pub(crate) fn as_smart_str(&mut self) -> &mut smart_str {
+        &mut self.buf
+    }
+
+    /// Consumes the writer, returning the string which was built.
+    pub fn finish(mut self) -> ZBox<ZendStr> {
+        let s = std::mem::replace(&mut self.buf.s, ptr::null_mut());
+        match unsafe { s.as_mut() } {
+            Some(s) => {
+                // SAFETY: `smart_str` always allocates space for the terminating null byte.
+                unsafe {
+                    let val = slice::from_raw_parts_mut(s.val.as_mut_ptr(), s.len + 1);
+                    val[s.len] = 0;
+                    ZBox::from_raw(s)
+                }
+            }
+            None => ZendStr::new("", false),
+        }
+    }
+}
+
+impl Default for ZvalWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for ZvalWriter {
+    fn drop(&mut self) {
+        if !self.buf.s.is_null() {
+            unsafe { ext_php_rs_zend_string_release(self.buf.s) };
+        }
+    }
+}
+
+impl io::Write for ZvalWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.push_bytes(buf);
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.push_bytes(buf);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl fmt::Write for ZvalWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+impl IntoZval for ZvalWriter {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        // The buffer is allocated on the request heap, so persistent values
+        // need a copy.
+        if persistent {
+            zv.set_zend_string(ZendStr::new(self.as_bytes(), true));
+        } else {
+            zv.set_zend_string(self.finish());
+        }
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@
 //! contains is determined by a property inside the struct. The content of the
 //! Zval is stored in a union.
 
-use std::{convert::TryInto, ffi::c_void, fmt::Debug, ptr};
+use std::{convert::TryInto, ffi::c_void, fmt::Debug, mem, ptr};
 
 use crate::types::iterable::Iterable;
 use crate::types::ZendIterator;
@@ -13,8 +13,11 @@ use crate::{
     convert::{FromZval, FromZvalMut, IntoZval, IntoZvalDyn},
     error::{Error, Result},
     ffi::{
-        _zval_struct__bindgen_ty_1, _zval_struct__bindgen_ty_2, zend_is_callable,
-        zend_is_identical, zend_is_iterable, zend_resource, zend_value, zval, zval_ptr_dtor,
+        __zend_malloc, _zend_ast_kind_ZEND_AST_CLASS_CONST, _zend_ast_kind_ZEND_AST_ZVAL,
+        _zval_struct__bindgen_ty_1, _zval_struct__bindgen_ty_2, ext_php_rs_zend_string_release,
+        zend_ast, zend_ast_ref, zend_ast_zval, zend_is_callable, zend_is_identical,
+        zend_is_iterable, zend_resource, zend_value, zval, zval_ptr_dtor, GC_IMMUTABLE,
+        GC_NOT_COLLECTABLE, GC_PERSISTENT, IS_CONSTANT_AST, IS_STR_PERMANENT,
     },
     flags::DataType,
     flags::ZvalTypeFlags,
@@ -129,6 +132,14 @@ impl Zval {
         self.zend_str().and_then(|zs| zs.as_str().ok())
     }
 
+    /// Returns the raw bytes of the zval if it is a string.
+    ///
+    /// Unlike [`str()`](#method.str), the contents of the string are not
+    /// validated as UTF-8, so this can be used for binary data.
+    pub fn bytes(&self) -> Option<&[u8]> {
+        self.zend_str().map(ZendStr::as_bytes)
+    }
+
     /// Returns the value of the zval if it is a string and can be unpacked into
     /// a vector of a given type. Similar to the [`unpack`] function in PHP,
     /// except you can only unpack one type.
@@ -192,6 +203,10 @@ impl Zval {
 
     /// Returns a mutable reference to the underlying zval hashtable if the zval
     /// contains an array.
+    ///
+    /// The array may be shared with other zvals, in which case modifying it
+    /// modifies all of them. Use [`Zval::separate_array`] to modify the array
+    /// held by this zval only.
     pub fn array_mut(&mut self) -> Option<&mut ZendHashTable> {
         if self.is_array() {
             unsafe { self.value.arr.as_mut() }
@@ -200,6 +215,34 @@ impl Zval {
         }
     }
 
+    /// Returns a mutable reference to the underlying zval hashtable if the zval
+    /// contains an array, first replacing it with a copy if it is shared with
+    /// other zvals or immutable. This is the equivalent of `SEPARATE_ARRAY`,
+    /// and should be used before modifying arrays received by value.
+    ///
+    /// References are not followed, so [`Zval::dereference_mut`] should be
+    /// used first if the zval may be a reference.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::Zval;
+    ///
+    /// fn append(zv: &mut Zval) {
+    ///     if let Some(arr) = zv.separate_array() {
+    ///         let _ = arr.push("appended");
+    ///     }
+    /// }
+    /// ```
+    pub fn separate_array(&mut self) -> Option<&mut ZendHashTable> {
+        let arr = self.array()?;
+        if !arr.is_unique() {
+            let owned = arr.to_owned();
+            self.set_hashtable(owned);
+        }
+        self.array_mut()
+    }
+
     /// Returns the value of the zval if it is an object.
     pub fn object(&self) -> Option<&ZendObject> {
         if self.is_object() {
@@ -330,6 +373,12 @@ impl Zval {
         self.get_type() == DataType::Long
     }
 
+    /// Returns true if the zval is undefined, false otherwise. Declared
+    /// properties which have not been initialized are undefined.
+    pub fn is_undef(&self) -> bool {
+        self.get_type() == DataType::Undef
+    }
+
     /// Returns true if the zval is null, false otherwise.
     pub fn is_null(&self) -> bool {
         self.get_type() == DataType::Null
@@ -431,10 +480,21 @@ impl Zval {
     /// * `val` - The value to set the zval as.
     /// * `persistent` - Whether the string should persist between requests.
     pub fn set_string(&mut self, val: &str, persistent: bool) -> Result<()> {
-        self.set_zend_string(ZendStr::new(val, persistent));
+        self.set_zend_string(ZendStr::new_str(val, persistent));
         Ok(())
     }
 
+    /// Sets the value of the zval as a binary-safe string, copying the given
+    /// bytes directly into a new Zend string.
+    ///
+    /// # Parameters
+    ///
+    /// * `val` - String content.
+    /// * `persistent` - Whether the string should persist between requests.
+    pub fn set_bytes(&mut self, val: &[u8], persistent: bool) {
+        self.set_zend_string(ZendStr::new(val, persistent));
+    }
+
     /// Sets the value of the zval as a Zend string.
     ///
     /// # Parameters
@@ -577,6 +637,55 @@ impl Zval {
         self.value.ptr = ptr as *mut c_void;
     }
 
+    /// Sets the value of the zval as a constant expression fetching a class
+    /// constant, like `Foo::BAR` in PHP. The engine evaluates the expression
+    /// the first time the value is read during a request, which allows class
+    /// constants and property defaults declared during module startup to refer
+    /// to values only available during a request, such as the cases of enums.
+    ///
+    /// The expression is allocated persistently and its names are interned, so
+    /// it must be created during module startup.
+    ///
+    /// # Parameters
+    ///
+    /// * `class` - The name of the class declaring the constant.
+    /// * `name` - The name of the constant.
+    pub fn set_class_constant_expr(&mut self, class: &str, name: &str) {
+        // The expression is laid out as the engine lays out the expressions of
+        // enum cases: the reference, the class constant node and the nodes of the
+        // two names, in a single allocation.
+        let node_size = mem::size_of::<zend_ast>() + mem::size_of::<*mut zend_ast>();
+        let size = mem::size_of::<zend_ast_ref>() + node_size + 2 * mem::size_of::<zend_ast_zval>();
+        // SAFETY: The allocation is large enough to hold the reference followed by
+        // the nodes, which are all aligned to pointers.
+        unsafe {
+            let reference = __zend_malloc(size) as *mut zend_ast_ref;
+            (*reference).gc.refcount = 1;
+            (*reference).gc.u.type_info =
+                IS_CONSTANT_AST | GC_NOT_COLLECTABLE | GC_PERSISTENT | GC_IMMUTABLE;
+
+            let ast = reference.add(1) as *mut zend_ast;
+            (*ast).kind = _zend_ast_kind_ZEND_AST_CLASS_CONST as _;
+            (*ast).attr = 0;
+            (*ast).lineno = 0;
+
+            let names = (ast as *mut u8).add(node_size) as *mut zend_ast_zval;
+            for (i, name) in [class, name].iter().enumerate() {
+                let node = names.add(i);
+                (*node).kind = _zend_ast_kind_ZEND_AST_ZVAL as _;
+                (*node).attr = 0;
+                ptr::write(&mut (*node).val, Zval::new());
+                (*node).val.value.str_ = ZendStr::new_interned(name, true).into_raw();
+                (*node).val.u1.type_info = ZvalTypeFlags::InternedStringEx.bits();
+                (*node).val.u2.lineno = 0;
+                *(*ast).child.as_mut_ptr().add(i) = node as *mut zend_ast;
+            }
+
+            self.change_type(ZvalTypeFlags::ConstantAstEx);
+            self.value.ast = reference;
+        }
+    }
+
     /// Used to drop the Zval but keep the value of the zval intact.
     ///
     /// This is important when copying the value of the zval, as the actual
@@ -597,10 +706,46 @@ impl Zval {
     fn change_type(&mut self, ty: ZvalTypeFlags) {
         // SAFETY: we have exclusive mutable access to this zval so can free the
         // contents.
-        unsafe { zval_ptr_dtor(self) };
+        unsafe {
+            if self.is_persistent() {
+                persistent_zval_dtor(self);
+            } else {
+                zval_ptr_dtor(self);
+            }
+        };
         self.u1.type_info = ty.bits();
     }
 
+    /// Returns whether the value of the zval is reference counted and was
+    /// allocated with the persistent allocator.
+    fn is_persistent(&self) -> bool {
+        let flags = ZvalTypeFlags::from_bits_retain(unsafe { self.u1.type_info });
+        flags.contains(ZvalTypeFlags::RefCounted)
+            && unsafe { self.value.counted.as_ref() }
+                .is_some_and(|counted| unsafe { counted.gc.u.type_info } & GC_PERSISTENT != 0)
+    }
+
+    /// Returns whether the value of the zval outlives the request, and can
+    /// therefore be stored in a persistent hashtable. Scalars, permanent
+    /// interned strings and persistent strings and arrays outlive the request.
+    pub(crate) fn outlives_request(&self) -> bool {
+        match self.get_type() {
+            DataType::Undef
+            | DataType::Null
+            | DataType::False
+            | DataType::True
+            | DataType::Bool
+            | DataType::Long
+            | DataType::Double => true,
+            DataType::String => unsafe { self.value.str_.as_ref() }.is_some_and(|s| {
+                let type_info = unsafe { s.gc.u.type_info };
+                type_info & (GC_PERSISTENT | IS_STR_PERMANENT) != 0
+            }),
+            DataType::Array => self.is_persistent(),
+            _ => false,
+        }
+    }
+
     /// Extracts some type from a `Zval`.
     ///
     /// This is a wrapper function around `TryFrom`.
@@ -682,6 +827,27 @@ impl Debug for Zval {
     }
 }
 
+/// Destroys the value of a zval which was allocated with the persistent
+/// allocator, and which therefore cannot be freed by the engine's request
+/// destructors.
+///
+/// Only strings and arrays can be persistent. This is used as the element
+/// destructor of persistent hashtables.
+pub(crate) unsafe extern "C" fn persistent_zval_dtor(zv: *mut Zval) {
+    let zv = &mut *zv;
+    match zv.get_type() {
+        DataType::String => ext_php_rs_zend_string_release(zv.value.str_),
+        DataType::Array => {
+            let ht = &mut *zv.value.arr;
+            ht.gc.refcount -= 1;
+            if ht.gc.refcount == 0 {
+                drop(ZBox::from_raw(ht));
+            }
+        }
+        _ => {}
+    }
+}
+
 impl Drop for Zval {
     fn drop(&mut self) {
         self.change_type(ZvalTypeFlags::Null);
@@ -1,5 +1,11 @@
 #include "wrapper.h"
 
+#if PHP_VERSION_ID >= 80200
+#include "ext/random/php_random.h"
+#else
+#include "ext/standard/php_random.h"
+#endif
+
 zend_string *ext_php_rs_zend_string_init(const char *str, size_t len, bool persistent) {
   return zend_string_init(str, len, persistent);
 }
@@ -76,6 +82,18 @@ sapi_module_struct *ext_php_rs_sapi_module() {
   return &sapi_module;
 }
 
+php_json_error_code ext_php_rs_json_error_code() {
+  return JSON_G(error_code);
+}
+
+zend_result ext_php_rs_random_bytes(void *bytes, size_t size) {
+  return php_random_bytes(bytes, size, true);
+}
+
+zend_result ext_php_rs_random_int(zend_long min, zend_long max, zend_long *result) {
+  return php_random_int(min, max, result, true);
+}
+
 bool ext_php_rs_zend_try_catch(void* (*callback)(void *), void *ctx, void **result) {
   zend_try {
     *result = callback(ctx);
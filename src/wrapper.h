@@ -18,12 +18,20 @@
 
 #include "ext/standard/info.h"
 #include "ext/standard/php_var.h"
+#include "ext/standard/php_array.h"
 #include "ext/standard/file.h"
+#include "ext/json/php_json.h"
+#include "zend_attributes.h"
+#if PHP_VERSION_ID >= 80100
+#include "zend_enum.h"
+#endif
 #include "zend_exceptions.h"
 #include "zend_inheritance.h"
 #include "zend_interfaces.h"
+#include "zend_observer.h"
 #include "php_variables.h"
 #include "zend_ini.h"
+#include "zend_smart_str.h"
 #include "main/SAPI.h"
 
 zend_string *ext_php_rs_zend_string_init(const char *str, size_t len, bool persistent);
@@ -39,6 +47,9 @@ php_core_globals *ext_php_rs_process_globals();
 sapi_globals_struct *ext_php_rs_sapi_globals();
 php_file_globals *ext_php_rs_file_globals();
 sapi_module_struct *ext_php_rs_sapi_module();
+php_json_error_code ext_php_rs_json_error_code();
+zend_result ext_php_rs_random_bytes(void *bytes, size_t size);
+zend_result ext_php_rs_random_int(zend_long min, zend_long max, zend_long *result);
 bool ext_php_rs_zend_try_catch(void* (*callback)(void *), void *ctx, void **result);
 bool ext_php_rs_zend_first_try_catch(void* (*callback)(void *), void *ctx, void **result);
 void ext_php_rs_zend_bailout();
@@ -2,8 +2,8 @@ use std::{ffi::c_void, ptr};
 
 use crate::{
     ffi::{
-        zend_type, IS_MIXED, MAY_BE_ANY, MAY_BE_BOOL, _IS_BOOL, _ZEND_IS_VARIADIC_BIT,
-        _ZEND_SEND_MODE_SHIFT, _ZEND_TYPE_NULLABLE_BIT,
+        zend_type, _IS_BOOL, _ZEND_IS_VARIADIC_BIT, _ZEND_SEND_MODE_SHIFT, _ZEND_TYPE_NULLABLE_BIT,
+        IS_MIXED, MAY_BE_ANY, MAY_BE_BOOL,
     },
     flags::DataType,
 };
@@ -18,9 +18,9 @@ impl ZendType {
     ///
     /// * `pass_by_ref` - Whether the value should be passed by reference.
     /// * `is_variadic` - Whether this type represents a variadic argument.
-    pub fn empty(pass_by_ref: bool, is_variadic: bool) -> Self {
+    pub const fn empty(pass_by_ref: bool, is_variadic: bool) -> Self {
         Self {
-            ptr: ptr::null::<c_void>() as *mut c_void,
+            ptr: ptr::null_mut::<c_void>(),
             type_mask: Self::arg_info_flags(pass_by_ref, is_variadic),
         }
     }
@@ -58,6 +58,35 @@ impl ZendType {
         }
     }
 
+    /// Creates a zend type for a given datatype in a constant context, so it
+    /// can be stored in static memory.
+    ///
+    /// Returns [`None`] if the data type was a class object, as the class
+    /// name must be converted into a C string at runtime. Use
+    /// [`ZendType::empty_from_type`] for these types instead.
+    ///
+    /// # Parameters
+    ///
+    /// * `type_` - Data type to create zend type for.
+    /// * `pass_by_ref` - Whether the type should be passed by reference.
+    /// * `is_variadic` - Whether the type is for a variadic argument.
+    /// * `allow_null` - Whether the type should allow null to be passed in
+    ///   place.
+    pub const fn try_from_type_const(
+        type_: DataType,
+        pass_by_ref: bool,
+        is_variadic: bool,
+        allow_null: bool,
+    ) -> Option<Self> {
+        if matches!(type_, DataType::Object(Some(_))) {
+            return None;
+        }
+        Some(Self {
+            ptr: ptr::null_mut::<c_void>(),
+            type_mask: Self::type_init_code(type_, pass_by_ref, is_variadic, allow_null),
+        })
+    }
+
     /// Attempts to create a zend type for a class object type. Returns an
     /// option containing the type if successful.
     ///
@@ -129,7 +158,7 @@ impl ZendType {
     ///
     /// * `pass_by_ref` - Whether the value should be passed by reference.
     /// * `is_variadic` - Whether this type represents a variadic argument.
-    pub(crate) fn arg_info_flags(pass_by_ref: bool, is_variadic: bool) -> u32 {
+    pub(crate) const fn arg_info_flags(pass_by_ref: bool, is_variadic: bool) -> u32 {
         ((pass_by_ref as u32) << _ZEND_SEND_MODE_SHIFT)
             | (if is_variadic {
                 _ZEND_IS_VARIADIC_BIT
@@ -147,7 +176,7 @@ impl ZendType {
     /// * `pass_by_ref` - Whether the value should be passed by reference.
     /// * `is_variadic` - Whether this type represents a variadic argument.
     /// * `allow_null` - Whether the value can be null.
-    pub(crate) fn type_init_code(
+    pub(crate) const fn type_init_code(
         type_: DataType,
         pass_by_ref: bool,
         is_variadic: bool,
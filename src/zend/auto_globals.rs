@@ -0,0 +1,104 @@
+//! Registration of custom auto-globals, also known as superglobals.
+
+use std::collections::BTreeMap;
+
+use parking_lot::{const_rwlock, RwLock};
+
+use crate::{
+    convert::IntoZval,
+    error::{Error, Result},
+    ffi::{zend_register_auto_global, zend_string, ZEND_RESULT_CODE_SUCCESS},
+    types::{ZendStr, Zval},
+};
+
+use super::ExecutorGlobals;
+
+/// A Rust callback producing the value of an auto-global.
+type AutoGlobalInit = Box<dyn Fn() -> Result<Zval> + Send + Sync>;
+
+/// Callbacks registered with [`register_auto_global`], keyed by the name of
+/// the variable.
+static AUTO_GLOBALS: RwLock<BTreeMap<String, AutoGlobalInit>> = const_rwlock(BTreeMap::new());
+
+/// Registers an auto-global, a variable which is available in every scope
+/// without being imported with `global`, like `$_SERVER`.
+///
+/// The callback is called once per request to populate the variable. When
+/// `jit` is `true`, it is only called for requests which compile a script
+/// referencing the variable, avoiding the cost of populating it otherwise.
+/// Variables registered with `jit` are not populated when accessed
+/// indirectly, e.g. through `$GLOBALS` or variable variables.
+///
+/// JIT auto-globals are armed by the compiler, which does not run for scripts
+/// served from OPcache. OPcache only remembers which of PHP's own auto-globals
+/// (`$_SERVER`, `$_ENV` and `$_REQUEST`) a cached script uses, so a JIT
+/// auto-global registered by an extension is left undefined in requests which
+/// only run cached scripts. Pass `false` for `jit` when the extension may be
+/// used alongside OPcache.
+///
+/// Auto-globals must be registered during module startup, before any scripts
+/// are compiled.
+///
+/// # Parameters
+///
+/// * `name` - The name of the variable, without the leading `$`.
+/// * `jit` - Whether the variable is populated only when a script uses it.
+/// * `init` - Returns the value of the variable for the current request.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{prelude::*, types::ZendHashTable, zend::register_auto_global};
+///
+/// #[php_startup]
+/// pub fn startup() {
+///     register_auto_global("_MYEXT", true, || {
+///         let mut info = ZendHashTable::new();
+///         info.insert("version", env!("CARGO_PKG_VERSION")).unwrap();
+///         info
+///     })
+///     .expect("failed to register auto-global");
+/// }
+/// # fn main() {}
+/// ```
+pub fn register_auto_global<F, T>(name: &str, jit: bool, init: F) -> Result<()>
+where
+    F: Fn() -> T + Send + Sync + 'static,
+    T: IntoZval,
+{
+    let mut globals = AUTO_GLOBALS.write();
+
+    // The name must be interned, as it is used as a key by the compiler for the
+    // lifetime of the process.
+    let key = ZendStr::new_interned(name, true).into_raw();
+    if unsafe { zend_register_auto_global(key, jit, Some(auto_global_callback)) }
+        != ZEND_RESULT_CODE_SUCCESS
+    {
+        return Err(Error::AutoGlobalRegistrationFailure);
+    }
+
+    globals.insert(name.to_string(), Box::new(move || init().into_zval(false)));
+    Ok(())
+}
+
+/// Called by PHP to populate an auto-global for the current request.
+///
+/// Returns whether the variable should remain armed, i.e. whether PHP should
+/// call the callback again the next time the variable is used.
+extern "C" fn auto_global_callback(name: *mut zend_string) -> bool {
+    let name = match unsafe { name.as_ref() }.and_then(|name| name.as_str().ok()) {
+        Some(name) => name,
+        None => return false,
+    };
+
+    let globals = AUTO_GLOBALS.read();
+    let init = match globals.get(name) {
+        Some(init) => init,
+        None => return false,
+    };
+
+    if let Ok(val) = init() {
+        let _ = ExecutorGlobals::get_mut().symbol_table.insert(name, val);
+    }
+    false
+}
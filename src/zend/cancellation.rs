@@ -0,0 +1,375 @@
+//! Cooperative cancellation of long-running Rust operations.
+
+use std::{
+    fmt::Display,
+    sync::{
+        atomic::{AtomicBool, AtomicI16, AtomicU8, Ordering},
+        Arc,
+    },
+};
+
+use crate::{
+    exception::PhpException,
+    ffi::{ext_php_rs_executor_globals, ext_php_rs_process_globals},
+    zend::ce,
+};
+
+/// Value of `PG(connection_status)` when the client has disconnected.
+const PHP_CONNECTION_ABORTED: i16 = 1;
+/// Value of `PG(connection_status)` when the request has timed out.
+const PHP_CONNECTION_TIMEOUT: i16 = 2;
+
+/// A token which signals that a long-running operation should stop.
+///
+/// PHP normally stops a script by interrupting the virtual machine, which does
+/// not happen while a Rust function is running. Long-running Rust code should
+/// instead periodically check a cancellation token, and return early when it
+/// has been cancelled.
+///
+/// A token created with [`CancellationToken::for_request`] is cancelled when
+/// any of the following happen:
+///
+/// * The script exceeds `max_execution_time`.
+/// * The client aborts the request.
+/// * The process receives `SIGINT` while running under the CLI SAPI (Unix
+///   only).
+///
+/// Tokens can be cloned and sent to other threads, cancelling one clone
+/// cancels all of them.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{prelude::*, zend::CancellationToken};
+///
+/// #[php_function]
+/// pub fn count_slowly(to: i64) -> PhpResult<i64> {
+///     let token = CancellationToken::for_request();
+///
+///     for i in 0..to {
+///         token.check()?;
+///         std::thread::sleep(std::time::Duration::from_millis(100));
+///     }
+///
+///     Ok(to)
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    cancelled: AtomicBool,
+    sources: Option<RequestSources>,
+}
+
+/// Pointers to the globals which are checked by a request token.
+#[derive(Debug)]
+struct RequestSources {
+    timed_out: *const AtomicU8,
+    connection_status: *const AtomicI16,
+    #[cfg(php_zts)]
+    thread: std::thread::ThreadId,
+    #[cfg(unix)]
+    sigint: Option<usize>,
+}
+
+// SAFETY: The pointers point to the executor and process globals. In non-ZTS
+// builds these are statically allocated, while in ZTS builds they are only
+// dereferenced on the thread that created the token.
+unsafe impl Send for RequestSources {}
+unsafe impl Sync for RequestSources {}
+
+impl CancellationToken {
+    /// Creates a new token which is only cancelled by calling
+    /// [`CancellationToken::cancel`].
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                cancelled: AtomicBool::new(false),
+                sources: None,
+            }),
+        }
+    }
+
+    /// Creates a new token which is cancelled when the current request times
+    /// out or is aborted, or when `SIGINT` is received under the CLI SAPI.
+    ///
+    /// Must be called from the thread which is running the request.
+    ///
+    /// When running under the CLI SAPI on Unix, a `SIGINT` handler is
+    /// installed until all clones of the token have been dropped. A second
+    /// `SIGINT` while the handler is installed restores the previous handler
+    /// and re-raises the signal, so an unresponsive operation can still be
+    /// killed.
+    pub fn for_request() -> Self {
+        // SAFETY: The globals are always valid while PHP is running. Only the address of
+        // the fields is taken here.
+        let (timed_out, connection_status) = unsafe {
+            let eg = ext_php_rs_executor_globals();
+            let pg = ext_php_rs_process_globals();
+            (
+                std::ptr::addr_of!((*eg).timed_out) as *const AtomicU8,
+                std::ptr::addr_of!((*pg).connection_status) as *const AtomicI16,
+            )
+        };
+
+        Self {
+            inner: Arc::new(Inner {
+                cancelled: AtomicBool::new(false),
+                sources: Some(RequestSources {
+                    timed_out,
+                    connection_status,
+                    #[cfg(php_zts)]
+                    thread: std::thread::current().id(),
+                    #[cfg(unix)]
+                    sigint: (super::php_sapi_name() == "cli").then(sigint::watch),
+                }),
+            }),
+        }
+    }
+
+    /// Cancels the token and all of its clones.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns whether the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.reason().is_some()
+    }
+
+    /// Returns the reason the token was cancelled, or [`None`] if it has not
+    /// been cancelled.
+    pub fn reason(&self) -> Option<CancellationReason> {
+        if self.inner.cancelled.load(Ordering::SeqCst) {
+            return Some(CancellationReason::Cancelled);
+        }
+
+        let reason = self.inner.sources.as_ref()?.poll()?;
+        self.cancel();
+        Some(reason)
+    }
+
+    /// Returns an error if the token has been cancelled.
+    ///
+    /// The error can be converted into a [`PhpException`], allowing the `?`
+    /// operator to be used inside functions returning a
+    /// [`PhpResult`](crate::exception::PhpResult).
+    pub fn check(&self) -> Result<(), CancellationReason> {
+        match self.reason() {
+            Some(reason) => Err(reason),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RequestSources {
+    fn poll(&self) -> Option<CancellationReason> {
+        #[cfg(unix)]
+        if let Some(generation) = self.sigint {
+            if sigint::received_since(generation) {
+                return Some(CancellationReason::Interrupted);
+            }
+        }
+
+        #[cfg(php_zts)]
+        if std::thread::current().id() != self.thread {
+            return None;
+        }
+
+        // SAFETY: See `unsafe impl Send for RequestSources`. The fields are written
+        // asynchronously by signal handlers and the SAPI, so they are read atomically.
+        let (timed_out, connection_status) = unsafe {
+            (
+                (*self.timed_out).load(Ordering::SeqCst),
+                (*self.connection_status).load(Ordering::SeqCst),
+            )
+        };
+
+        if timed_out != 0 || connection_status & PHP_CONNECTION_TIMEOUT != 0 {
+            Some(CancellationReason::TimedOut)
+        } else if connection_status & PHP_CONNECTION_ABORTED != 0 {
+            Some(CancellationReason::Aborted)
+        } else {
+            None
+        }
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(RequestSources {
+            sigint: Some(_), ..
+        }) = self.sources
+        {
+            sigint::unwatch();
+        }
+    }
+}
+
+/// The reason a [`CancellationToken`] was cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancellationReason {
+    /// The token was cancelled manually.
+    Cancelled,
+    /// The request exceeded `max_execution_time`.
+    TimedOut,
+    /// The client aborted the request.
+    Aborted,
+    /// The process received `SIGINT`.
+    Interrupted,
+}
+
+impl Display for CancellationReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CancellationReason::Cancelled => write!(f, "Operation was cancelled."),
+            CancellationReason::TimedOut => {
+                write!(
+                    f,
+                    "Operation was cancelled as the maximum execution time was exceeded."
+                )
+            }
+            CancellationReason::Aborted => {
+                write!(f, "Operation was cancelled as the request was aborted.")
+            }
+            CancellationReason::Interrupted => {
+                write!(f, "Operation was cancelled by an interrupt signal.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CancellationReason {}
+
+impl From<CancellationReason> for PhpException {
+    fn from(reason: CancellationReason) -> Self {
+        match reason {
+            CancellationReason::TimedOut => Self::new(reason.to_string(), 0, ce::error_exception()),
+            _ => Self::default(reason.to_string()),
+        }
+    }
+}
+
+/// Process-wide `SIGINT` handler shared by all request tokens.
+#[cfg(unix)]
+mod sigint {
+    use std::{
+        mem::MaybeUninit,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use parking_lot::{const_mutex, Mutex};
+
+    /// Number of `SIGINT`s received while the handler was installed.
+    static RECEIVED: AtomicUsize = AtomicUsize::new(0);
+    /// Value of `RECEIVED` when the handler was installed.
+    static INSTALLED_AT: AtomicUsize = AtomicUsize::new(0);
+    /// Number of tokens watching for `SIGINT`, and the handler which was
+    /// installed before ours.
+    static WATCHERS: Mutex<(usize, Option<libc::sigaction>)> = const_mutex((0, None));
+    /// Copy of the previous handler which can be read from the signal handler.
+    static mut PREVIOUS: MaybeUninit<libc::sigaction> = MaybeUninit::uninit();
+
+    extern "C" fn handler(_: libc::c_int) {
+        let received = RECEIVED.fetch_add(1, Ordering::SeqCst);
+
+        if received > INSTALLED_AT.load(Ordering::SeqCst) {
+            // SAFETY: `PREVIOUS` is initialized before the handler is installed, and
+            // `sigaction` and `raise` are async-signal-safe.
+            unsafe {
+                libc::sigaction(
+                    libc::SIGINT,
+                    std::ptr::addr_of!(PREVIOUS).cast(),
+                    std::ptr::null_mut(),
+                );
+                libc::raise(libc::SIGINT);
+            }
+        }
+    }
+
+    /// Installs the handler if it is not already installed, returning the
+    /// number of signals received so far.
+    pub fn watch() -> usize {
+        let mut watchers = WATCHERS.lock();
+        if watchers.0 == 0 {
+            // SAFETY: The handler only touches atomics and async-signal-safe functions.
+            // `PREVIOUS` is only written while the handler is not installed.
+            unsafe {
+                let mut action: libc::sigaction = std::mem::zeroed();
+                action.sa_sigaction = handler as extern "C" fn(libc::c_int) as libc::sighandler_t;
+                libc::sigemptyset(&mut action.sa_mask);
+
+                let mut previous = MaybeUninit::<libc::sigaction>::zeroed();
+                INSTALLED_AT.store(RECEIVED.load(Ordering::SeqCst), Ordering::SeqCst);
+                libc::sigaction(libc::SIGINT, std::ptr::null(), previous.as_mut_ptr());
+                std::ptr::addr_of_mut!(PREVIOUS).write(previous);
+                libc::sigaction(libc::SIGINT, &action, std::ptr::null_mut());
+                watchers.1 = Some(previous.assume_init());
+            }
+        }
+        watchers.0 += 1;
+        RECEIVED.load(Ordering::SeqCst)
+    }
+
+    /// Removes a watcher, restoring the previous handler when there are no
+    /// watchers left.
+    pub fn unwatch() {
+        let mut watchers = WATCHERS.lock();
+        watchers.0 -= 1;
+        if watchers.0 == 0 {
+            if let Some(previous) = watchers.1.take() {
+                // SAFETY: Restores the handler which was installed before ours.
+                unsafe { libc::sigaction(libc::SIGINT, &previous, std::ptr::null_mut()) };
+            }
+        }
+    }
+
+    /// Returns whether a `SIGINT` was received after `generation`.
+    pub fn received_since(generation: usize) -> bool {
+        RECEIVED.load(Ordering::SeqCst) > generation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::{CancellationReason, CancellationToken};
+    use crate::task::TaskPool;
+
+    #[test]
+    fn test_cancel_pending_task() {
+        let pool = TaskPool::new(1);
+        let token = CancellationToken::new();
+        let (started, wait_started) = mpsc::channel();
+
+        let task_token = token.clone();
+        let handle = pool.spawn(move || {
+            started.send(()).unwrap();
+            loop {
+                if let Err(reason) = task_token.check() {
+                    return reason;
+                }
+                std::thread::yield_now();
+            }
+        });
+
+        wait_started.recv().unwrap();
+        assert!(!token.is_cancelled());
+        token.cancel();
+
+        assert_eq!(handle.join(), Ok(CancellationReason::Cancelled));
+        assert_eq!(token.reason(), Some(CancellationReason::Cancelled));
+    }
+}
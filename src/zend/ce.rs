@@ -3,12 +3,12 @@
 #![allow(clippy::unwrap_used)]
 
 use crate::ffi::{
-    zend_ce_aggregate, zend_ce_argument_count_error, zend_ce_arithmetic_error, zend_ce_arrayaccess,
-    zend_ce_compile_error, zend_ce_countable, zend_ce_division_by_zero_error,
-    zend_ce_error_exception, zend_ce_exception, zend_ce_iterator, zend_ce_parse_error,
-    zend_ce_serializable, zend_ce_stringable, zend_ce_throwable, zend_ce_traversable,
-    zend_ce_type_error, zend_ce_unhandled_match_error, zend_ce_value_error,
-    zend_standard_class_def,
+    php_json_exception_ce, zend_ce_aggregate, zend_ce_argument_count_error,
+    zend_ce_arithmetic_error, zend_ce_arrayaccess, zend_ce_compile_error, zend_ce_countable,
+    zend_ce_division_by_zero_error, zend_ce_error, zend_ce_error_exception, zend_ce_exception,
+    zend_ce_iterator, zend_ce_parse_error, zend_ce_serializable, zend_ce_stringable,
+    zend_ce_throwable, zend_ce_traversable, zend_ce_type_error, zend_ce_unhandled_match_error,
+    zend_ce_value_error, zend_standard_class_def,
 };
 
 use super::ClassEntry;
@@ -28,6 +28,11 @@ pub fn exception() -> &'static ClassEntry {
     unsafe { zend_ce_exception.as_ref() }.unwrap()
 }
 
+/// Returns the base [`Error`](https://www.php.net/manual/en/class.error.php) class.
+pub fn error() -> &'static ClassEntry {
+    unsafe { zend_ce_error.as_ref() }.unwrap()
+}
+
 /// Returns the base [`ErrorException`](https://www.php.net/manual/en/class.errorexception.php) class.
 pub fn error_exception() -> &'static ClassEntry {
     unsafe { zend_ce_error_exception.as_ref() }.unwrap()
@@ -73,6 +78,11 @@ pub fn unhandled_match_error() -> &'static ClassEntry {
     unsafe { zend_ce_unhandled_match_error.as_ref() }.unwrap()
 }
 
+/// Returns the [`JsonException`](https://www.php.net/manual/en/class.jsonexception.php) class.
+pub fn json_exception() -> &'static ClassEntry {
+    unsafe { php_json_exception_ce.as_ref() }.unwrap()
+}
+
 /// Returns the [`Traversable`](https://www.php.net/manual/en/class.traversable.php) interface.
 pub fn traversable() -> &'static ClassEntry {
     unsafe { zend_ce_traversable.as_ref() }.unwrap()
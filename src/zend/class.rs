@@ -4,10 +4,15 @@ use crate::ffi::instanceof_function_slow;
 use crate::types::{ZendIterator, Zval};
 use crate::{
     boxed::ZBox,
-    ffi::zend_class_entry,
+    convert::{IntoZval, IntoZvalDyn},
+    error::{Error, Result},
+    ffi::{zend_class_entry, ZEND_USER_CLASS},
     flags::ClassFlags,
     types::{ZendObject, ZendStr},
-    zend::ExecutorGlobals,
+    zend::{
+        reflection::{attributes, find_ptr, members},
+        Attribute, ClassConstant, ExecutorGlobals, Function, PropertyInfo,
+    },
 };
 use std::{convert::TryInto, fmt::Debug, ops::DerefMut};
 
@@ -21,6 +26,10 @@ impl ClassEntry {
     ///
     /// Returns a reference to the class if found, or [`None`] if the class
     /// could not be found or the class table has not been initialized.
+    ///
+    /// The registered autoloaders are run if the class has not been loaded
+    /// yet. An exception thrown by an autoloader is left for PHP to throw; use
+    /// [`ClassEntry::find_or_autoload`] to receive it as an error instead.
     pub fn try_find(name: &str) -> Option<&'static Self> {
         ExecutorGlobals::get().class_table()?;
         let mut name = ZendStr::new(name, false);
@@ -30,6 +39,43 @@ impl ClassEntry {
         }
     }
 
+    /// Finds a class in the global class table, running the autoloaders
+    /// registered with `spl_autoload_register()` when the class has not been
+    /// loaded yet, as PHP does when a class is first used.
+    ///
+    /// Returns [`Error::ClassNotFound`] if the class could not be found or
+    /// loaded, or [`Error::Exception`] if an autoloader threw an exception.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The fully qualified name of the class, which may start with
+    ///   a backslash.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::zend::ClassEntry;
+    ///
+    /// let ce = ClassEntry::find_or_autoload("GuzzleHttp\\Client").unwrap();
+    /// let client = ce.new();
+    /// ```
+    pub fn find_or_autoload(name: &str) -> Result<&'static Self> {
+        let not_found = || Error::ClassNotFound(name.trim_start_matches('\\').into());
+        if ExecutorGlobals::get().class_table().is_none() {
+            return Err(not_found());
+        }
+        let mut zend_name = ZendStr::new(name, false);
+
+        let ce = unsafe {
+            crate::ffi::zend_lookup_class_ex(zend_name.deref_mut(), std::ptr::null_mut(), 0)
+                .as_ref()
+        };
+        if let Some(e) = ExecutorGlobals::take_exception() {
+            return Err(Error::Exception(e));
+        }
+        ce.ok_or_else(not_found)
+    }
+
     /// Creates a new [`ZendObject`], returned inside an [`ZBox<ZendObject>`]
     /// wrapper.
     ///
@@ -41,6 +87,25 @@ impl ClassEntry {
         ZendObject::new(self)
     }
 
+    /// Creates a new instance of the class and calls its constructor with the
+    /// given arguments. See [`ZendObject::new_with_args`].
+    ///
+    /// # Parameters
+    ///
+    /// * `args` - The arguments to pass to the constructor.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::zend::ClassEntry;
+    ///
+    /// let ce = ClassEntry::try_find("ArrayObject").unwrap();
+    /// let obj = ce.instantiate(vec![&vec![1, 2, 3]]).unwrap();
+    /// ```
+    pub fn instantiate(&self, args: Vec<&dyn IntoZvalDyn>) -> Result<ZBox<ZendObject>> {
+        ZendObject::new_with_args(self, args)
+    }
+
     /// Returns the class flags.
     pub fn flags(&self) -> ClassFlags {
         ClassFlags::from_bits_truncate(self.ce_flags)
@@ -52,6 +117,11 @@ impl ClassEntry {
         self.flags().contains(ClassFlags::Interface)
     }
 
+    /// Returns `true` if the class entry is a trait, and `false` otherwise.
+    pub fn is_trait(&self) -> bool {
+        self.flags().contains(ClassFlags::Trait)
+    }
+
     /// Checks if the class is an instance of another class or interface.
     ///
     /// # Parameters
@@ -111,9 +181,122 @@ impl ClassEntry {
         unsafe { iterator.as_mut() }
     }
 
+    /// Returns the value of a static property of the class, or [`None`] if
+    /// the class does not have a static property with the given name.
+    ///
+    /// The property is read from the scope of the class, so protected and
+    /// private properties can be read.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the static property.
+    pub fn get_static_property(&self, name: &str) -> Option<&Zval> {
+        let mut name = ZendStr::new(name, false);
+        let ptr = self as *const Self as *mut Self;
+
+        unsafe { crate::ffi::zend_read_static_property_ex(ptr, name.deref_mut(), true).as_ref() }
+    }
+
+    /// Sets the value of a static property of the class. The property is
+    /// written from the scope of the class, so protected and private
+    /// properties can be written.
+    ///
+    /// Returns an error if the class does not have a static property with the
+    /// given name, or the value is not of the type of the property.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the static property.
+    /// * `value` - The new value of the property.
+    pub fn set_static_property(&self, name: &str, value: impl IntoZval) -> Result<()> {
+        let mut name = ZendStr::new(name, false);
+        let mut value = value.into_zval(false)?;
+        let ptr = self as *const Self as *mut Self;
+
+        let result = unsafe {
+            crate::ffi::zend_update_static_property_ex(ptr, name.deref_mut(), &mut value)
+        };
+        if result != crate::ffi::ZEND_RESULT_CODE_SUCCESS {
+            return Err(Error::InvalidProperty);
+        }
+        Ok(())
+    }
+
     pub fn name(&self) -> Option<&str> {
         unsafe { self.name.as_ref().and_then(|s| s.as_str().ok()) }
     }
+
+    /// Returns an iterator over the methods of the class, including the
+    /// methods inherited from parent classes, interfaces and traits.
+    pub fn methods(&self) -> impl Iterator<Item = &Function> {
+        // SAFETY: The function table only contains pointers to functions.
+        unsafe { members::<Function>(&self.function_table) }.map(|(_, func)| func)
+    }
+
+    /// Returns the method of the class with the given name, or [`None`] if
+    /// the class does not have such a method. The name is case-insensitive.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the method.
+    pub fn method(&self, name: &str) -> Option<&Function> {
+        unsafe { find_ptr(&self.function_table, name) }
+    }
+
+    /// Returns an iterator over the properties declared by the class and its
+    /// parent classes, yielding the name of each property along with its
+    /// information. Dynamic properties are not included.
+    pub fn properties(&self) -> impl Iterator<Item = (String, &PropertyInfo)> {
+        // SAFETY: The property table only contains pointers to property
+        // information.
+        unsafe { members(&self.properties_info) }
+    }
+
+    /// Returns the information of the declared property with the given name,
+    /// or [`None`] if the class does not declare such a property.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property, which is case-sensitive.
+    pub fn property_info(&self, name: &str) -> Option<&PropertyInfo> {
+        self.properties_info
+            .get(name)
+            .and_then(|zv| unsafe { zv.ptr::<PropertyInfo>()?.as_ref() })
+    }
+
+    /// Returns an iterator over the constants of the class, including enum
+    /// cases and the constants inherited from parent classes and interfaces,
+    /// yielding the name of each constant along with the constant.
+    pub fn constants(&self) -> impl Iterator<Item = (String, &ClassConstant)> {
+        // SAFETY: The constant table only contains pointers to constants.
+        unsafe { members(&self.constants_table) }
+    }
+
+    /// Returns the constant of the class with the given name, or [`None`] if
+    /// the class does not have such a constant.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the constant, which is case-sensitive.
+    pub fn constant(&self, name: &str) -> Option<&ClassConstant> {
+        self.constants_table
+            .get(name)
+            .and_then(|zv| unsafe { zv.ptr::<ClassConstant>()?.as_ref() })
+    }
+
+    /// Returns an iterator over the attributes applied to the class.
+    pub fn attributes(&self) -> impl Iterator<Item = &Attribute> {
+        attributes(self.attributes)
+    }
+
+    /// Returns the doc comment of the class, or [`None`] if the class does
+    /// not have one or was not declared in PHP code.
+    pub fn doc_comment(&self) -> Option<&ZendStr> {
+        if self.type_ as u32 != ZEND_USER_CLASS {
+            return None;
+        }
+        unsafe { self.info.user.doc_comment.as_ref() }
+    }
 }
 
 impl PartialEq for ClassEntry {
@@ -0,0 +1,87 @@
+//! Lookup of constants defined by PHP, extensions and scripts.
+
+use crate::{
+    convert::FromZval,
+    ffi::{zend_get_class_constant_ex, zend_get_constant_str, ZEND_FETCH_CLASS_SILENT},
+    types::{ZendStr, Zval},
+};
+
+use super::ClassEntry;
+
+/// Reads the values of global and class constants.
+///
+/// Constants are returned as owned values, so they can be converted into any
+/// type implementing [`FromZval`] which does not borrow from the zval, such
+/// as [`String`] or [`i64`].
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::zend::Constant;
+///
+/// let eol: Option<String> = Constant::get("PHP_EOL");
+/// let max: Option<i64> = Constant::get("PHP_INT_MAX");
+/// ```
+pub struct Constant;
+
+impl Constant {
+    /// Returns the value of a global constant, or [`None`] if the constant is
+    /// not defined or cannot be converted into `T`.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the constant, including its namespace.
+    pub fn get<T>(name: &str) -> Option<T>
+    where
+        for<'a> T: FromZval<'a>,
+    {
+        T::from_zval(&Self::get_zval(name)?)
+    }
+
+    /// Returns the value of a global constant as a zval, or [`None`] if the
+    /// constant is not defined.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the constant, including its namespace.
+    pub fn get_zval(name: &str) -> Option<Zval> {
+        let zv = unsafe { zend_get_constant_str(name.as_ptr().cast(), name.len()).as_ref() }?;
+        Some(zv.shallow_clone())
+    }
+
+    /// Returns the value of a class constant, or [`None`] if the constant is
+    /// not defined or cannot be converted into `T`.
+    ///
+    /// Constants whose values are expressions are evaluated when first read,
+    /// which may throw an exception.
+    ///
+    /// # Parameters
+    ///
+    /// * `ce` - The class the constant belongs to.
+    /// * `name` - The name of the constant.
+    pub fn get_class_constant<T>(ce: &ClassEntry, name: &str) -> Option<T>
+    where
+        for<'a> T: FromZval<'a>,
+    {
+        T::from_zval(&Self::get_class_constant_zval(ce, name)?)
+    }
+
+    /// Returns the value of a class constant as a zval, or [`None`] if the
+    /// constant is not defined.
+    ///
+    /// # Parameters
+    ///
+    /// * `ce` - The class the constant belongs to.
+    /// * `name` - The name of the constant.
+    pub fn get_class_constant_zval(ce: &ClassEntry, name: &str) -> Option<Zval> {
+        let mut name = ZendStr::new(name, false);
+        let ce_ptr = ce as *const ClassEntry as *mut ClassEntry;
+
+        // The class itself is used as the scope, so private constants are visible.
+        let zv = unsafe {
+            zend_get_class_constant_ex(ce.name, name.as_mut_ptr(), ce_ptr, ZEND_FETCH_CLASS_SILENT)
+                .as_ref()
+        }?;
+        Some(zv.shallow_clone())
+    }
+}
@@ -201,11 +201,41 @@ impl ExecuteData {
         unsafe { self.func.as_ref() }
     }
 
+    /// Returns the opcode of the instruction currently being executed, or
+    /// [`None`] if there is no current instruction.
+    pub fn opcode(&self) -> Option<u8> {
+        unsafe { self.opline.as_ref() }.map(|op| op.opcode)
+    }
+
     /// Attempt to retrieve the previous execute data on the call stack.
     pub fn previous(&self) -> Option<&Self> {
         unsafe { self.prev_execute_data.as_ref() }
     }
 
+    /// Returns the number of arguments passed to the function.
+    pub fn num_args(&self) -> u32 {
+        // SAFETY: All fields of the `u2` union are the same type.
+        unsafe { self.This.u2.num_args }
+    }
+
+    /// Returns the argument at index `n`, or [`None`] if fewer arguments were
+    /// passed. Used by the fast argument parsing path generated by
+    /// [`macro@crate::php_function`].
+    ///
+    /// # Safety
+    ///
+    /// The returned reference is not bound to the lifetime of `self`, so the
+    /// caller must ensure it does not outlive the function call. The caller
+    /// must also not hold more than one reference to the same argument.
+    #[doc(hidden)]
+    pub unsafe fn arg_unchecked<'a>(&self, n: usize) -> Option<&'a mut Zval> {
+        if n < self.num_args() as usize {
+            self.zend_call_arg(n)
+        } else {
+            None
+        }
+    }
+
     /// Translation of macro `ZEND_CALL_ARG(call, n)`
     /// zend_compile.h:578
     ///
@@ -231,7 +261,7 @@ impl ExecuteData {
     /// Translation of macro `ZEND_CALL_FRAME_SLOT`
     /// zend_compile:573
     #[doc(hidden)]
-    fn zend_call_frame_slot() -> isize {
+    pub(crate) fn zend_call_frame_slot() -> isize {
         (Self::zend_mm_aligned_size::<Self>() + Self::zend_mm_aligned_size::<Zval>() - 1)
             / Self::zend_mm_aligned_size::<Zval>()
     }
@@ -1,19 +1,24 @@
 //! Builder for creating functions and methods in PHP.
 
-use std::{fmt::Debug, os::raw::c_char, ptr};
+use std::{fmt::Debug, mem, os::raw::c_char, ptr};
 
 use crate::{
+    args::ArgInfo,
+    builders::{function::FunctionPointerHandler, FunctionHandler},
     convert::IntoZvalDyn,
     error::Result,
     ffi::{
         zend_call_known_function, zend_fetch_function_str, zend_function, zend_function_entry,
         zend_hash_str_find_ptr_lc,
     },
-    flags::FunctionType,
-    types::Zval,
+    flags::{FunctionType, MethodFlags},
+    types::{ZendStr, Zval},
 };
 
-use super::ClassEntry;
+use super::{
+    reflection::{attributes, find_ptr},
+    Attribute, ClassEntry, ExecutorGlobals,
+};
 
 /// A Zend function entry.
 pub type FunctionEntry = zend_function_entry;
@@ -31,7 +36,7 @@ impl Debug for FunctionEntry {
 
 impl FunctionEntry {
     /// Returns an empty function entry, signifing the end of a function list.
-    pub fn end() -> Self {
+    pub const fn end() -> Self {
         Self {
             fname: ptr::null() as *const c_char,
             handler: None,
@@ -45,6 +50,56 @@ impl FunctionEntry {
         }
     }
 
+    /// Creates a function entry in a constant context, from a table of
+    /// argument information which is stored in static memory. Unlike
+    /// [`FunctionBuilder`], this does not allocate when the module is started.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the function, terminated with a NUL byte.
+    /// * `handler` - The handler to be called when the function is invoked from
+    ///   PHP.
+    /// * `arg_info` - The argument information of the function, as created by
+    ///   [`ArgInfo::table`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not terminated with a NUL byte, or `arg_info` does
+    /// not contain a header. When evaluated in a constant, this is a compile
+    /// error.
+    ///
+    /// [`FunctionBuilder`]: crate::builders::FunctionBuilder
+    pub const fn new_static(
+        name: &'static str,
+        handler: FunctionHandler,
+        arg_info: &'static [ArgInfo],
+    ) -> Self {
+        let bytes = name.as_bytes();
+        assert!(
+            !bytes.is_empty() && bytes[bytes.len() - 1] == 0,
+            "function name must be NUL-terminated"
+        );
+        assert!(
+            !arg_info.is_empty(),
+            "argument information must have a header"
+        );
+
+        Self {
+            fname: name.as_ptr().cast(),
+            // SAFETY: See `FunctionBuilder::new`.
+            handler: Some(unsafe {
+                mem::transmute::<FunctionHandler, FunctionPointerHandler>(handler)
+            }),
+            arg_info: arg_info.as_ptr(),
+            num_args: (arg_info.len() - 1) as u32,
+            flags: 0,
+            #[cfg(php84)]
+            doc_comment: ptr::null(),
+            #[cfg(php84)]
+            frameless_function_infos: ptr::null(),
+        }
+    }
+
     /// Converts the function entry into a raw and pointer, releasing it to the
     /// C world.
     pub fn into_raw(self) -> *mut Self {
@@ -59,6 +114,52 @@ impl Function {
         FunctionType::from(unsafe { self.type_ })
     }
 
+    /// Returns the name of the function, or [`None`] if the function is the
+    /// main body of a script.
+    pub fn name(&self) -> Option<&ZendStr> {
+        unsafe { self.common.function_name.as_ref() }
+    }
+
+    /// Returns the class the function was declared in, or [`None`] if the
+    /// function is not a method.
+    pub fn scope(&self) -> Option<&ClassEntry> {
+        unsafe { self.common.scope.as_ref() }
+    }
+
+    /// Finds a function in the global function table, or returns [`None`] if
+    /// no function with the given name is defined. The name is
+    /// case-insensitive.
+    ///
+    /// Unlike [`Function::try_from_function`], the function is not copied.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the function, which may be namespaced.
+    pub fn find(name: &str) -> Option<&'static Self> {
+        let globals = ExecutorGlobals::get();
+        let table = globals.function_table()?;
+        unsafe { find_ptr(table, name.trim_start_matches('\\')) }
+    }
+
+    /// Returns the flags of the function, such as the visibility of a method.
+    pub fn flags(&self) -> MethodFlags {
+        MethodFlags::from_bits_truncate(unsafe { self.common.fn_flags })
+    }
+
+    /// Returns an iterator over the attributes applied to the function.
+    pub fn attributes(&self) -> impl Iterator<Item = &Attribute> {
+        attributes(unsafe { self.common.attributes })
+    }
+
+    /// Returns the doc comment of the function, or [`None`] if the function
+    /// does not have one or was not declared in PHP code.
+    pub fn doc_comment(&self) -> Option<&ZendStr> {
+        if self.function_type() != FunctionType::User {
+            return None;
+        }
+        unsafe { self.op_array.doc_comment.as_ref() }
+    }
+
     pub fn try_from_function(name: &str) -> Option<Self> {
         unsafe {
             let res = zend_fetch_function_str(name.as_ptr() as *const c_char, name.len());
@@ -0,0 +1,135 @@
+//! Types used to expose values held by Rust objects to PHP's cycle collector.
+
+use std::{collections::HashMap, ptr};
+
+use crate::{
+    boxed::ZBox,
+    ffi::{zend_get_gc_buffer, zend_get_gc_buffer_create, zend_get_gc_buffer_grow},
+    flags::ZvalTypeFlags,
+    types::{ZendObject, Zval},
+};
+
+/// A buffer of zvals which is handed to the cycle collector.
+///
+/// Objects which hold zvals in Rust fields must add them to the buffer,
+/// otherwise reference cycles running through the object can never be
+/// collected.
+pub type GcBuffer = zend_get_gc_buffer;
+
+impl GcBuffer {
+    /// Creates a new buffer, or returns the buffer which is being filled by
+    /// the cycle collector. The buffer is owned by the engine and is reset
+    /// before each use.
+    pub(crate) fn new<'a>() -> &'a mut Self {
+        // SAFETY: The engine always returns a pointer to its global buffer.
+        unsafe { &mut *zend_get_gc_buffer_create() }
+    }
+
+    /// Adds a zval to the buffer. Values which are not reference counted, such
+    /// as integers and interned strings, are ignored.
+    ///
+    /// # Parameters
+    ///
+    /// * `zv` - The zval to add.
+    pub fn add_zval(&mut self, zv: &Zval) {
+        let flags = ZvalTypeFlags::from_bits_retain(unsafe { zv.u1.type_info });
+        if !flags.contains(ZvalTypeFlags::RefCounted) {
+            return;
+        }
+
+        self.reserve();
+        // SAFETY: `reserve` ensures `cur` points to an allocated slot. The zval is copied
+        // without incrementing the refcount, as the collector only borrows it.
+        unsafe {
+            ptr::copy_nonoverlapping(zv, self.cur, 1);
+            self.cur = self.cur.add(1);
+        }
+    }
+
+    /// Adds an object to the buffer.
+    ///
+    /// # Parameters
+    ///
+    /// * `obj` - The object to add.
+    pub fn add_object(&mut self, obj: &ZendObject) {
+        self.reserve();
+        // SAFETY: See `add_zval`.
+        unsafe {
+            let cur = &mut *self.cur;
+            cur.u1.type_info = ZvalTypeFlags::ObjectEx.bits();
+            cur.value.obj = obj as *const ZendObject as *mut ZendObject;
+            self.cur = self.cur.add(1);
+        }
+    }
+
+    /// Returns the buffered zvals as a pointer and length pair, in the form
+    /// expected by the `get_gc` object handler.
+    pub(crate) fn as_table(&mut self) -> (*mut Zval, i32) {
+        // SAFETY: `cur` and `start` point into the same allocation.
+        let len = unsafe { self.cur.offset_from(self.start) };
+        (self.start, len as i32)
+    }
+
+    fn reserve(&mut self) {
+        if self.cur == self.end {
+            unsafe { zend_get_gc_buffer_grow(self) };
+        }
+    }
+}
+
+/// Implemented on types which hold PHP values that may take part in
+/// reference cycles.
+///
+/// Fields of a `#[php_class]` struct can be marked with `#[gc]` to have them
+/// added to the buffer automatically, or the struct can implement this trait
+/// itself and be annotated with `#[php_class(gc)]`.
+pub trait CollectGarbage {
+    /// Adds the PHP values held by `self` to the buffer.
+    ///
+    /// # Parameters
+    ///
+    /// * `buf` - The buffer to add the values to.
+    fn collect_garbage(&self, buf: &mut GcBuffer);
+}
+
+impl CollectGarbage for Zval {
+    fn collect_garbage(&self, buf: &mut GcBuffer) {
+        buf.add_zval(self);
+    }
+}
+
+impl CollectGarbage for ZBox<ZendObject> {
+    fn collect_garbage(&self, buf: &mut GcBuffer) {
+        buf.add_object(self);
+    }
+}
+
+impl<T: CollectGarbage> CollectGarbage for Option<T> {
+    fn collect_garbage(&self, buf: &mut GcBuffer) {
+        if let Some(val) = self {
+            val.collect_garbage(buf);
+        }
+    }
+}
+
+impl<T: CollectGarbage> CollectGarbage for Box<T> {
+    fn collect_garbage(&self, buf: &mut GcBuffer) {
+        (**self).collect_garbage(buf);
+    }
+}
+
+impl<T: CollectGarbage> CollectGarbage for Vec<T> {
+    fn collect_garbage(&self, buf: &mut GcBuffer) {
+        for val in self {
+            val.collect_garbage(buf);
+        }
+    }
+}
+
+impl<K, V: CollectGarbage> CollectGarbage for HashMap<K, V> {
+    fn collect_garbage(&self, buf: &mut GcBuffer) {
+        for val in self.values() {
+            val.collect_garbage(buf);
+        }
+    }
+}
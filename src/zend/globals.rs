@@ -2,28 +2,36 @@
 
 use std::collections::HashMap;
 use std::ffi::CStr;
+use std::io::{self, Read};
+use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
+use std::ptr;
 use std::slice;
 use std::str;
 
 use parking_lot::{const_rwlock, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use crate::boxed::ZBox;
+use crate::convert::IntoZval;
+use crate::error::Result;
 use crate::exception::PhpResult;
 #[cfg(php82)]
 use crate::ffi::zend_atomic_bool_store;
 use crate::ffi::{
-    _sapi_module_struct, _zend_executor_globals, ext_php_rs_executor_globals,
-    ext_php_rs_file_globals, ext_php_rs_process_globals, ext_php_rs_sapi_globals,
-    ext_php_rs_sapi_module, php_core_globals, php_file_globals, sapi_globals_struct,
-    sapi_header_struct, sapi_headers_struct, sapi_request_info, zend_ini_entry,
-    zend_is_auto_global, TRACK_VARS_COOKIE, TRACK_VARS_ENV, TRACK_VARS_FILES, TRACK_VARS_GET,
-    TRACK_VARS_POST, TRACK_VARS_SERVER,
+    _php_stream_read, _php_stream_seek, _php_stream_write, _sapi_module_struct,
+    _zend_executor_globals, ext_php_rs_executor_globals, ext_php_rs_file_globals,
+    ext_php_rs_process_globals, ext_php_rs_sapi_globals, ext_php_rs_sapi_module, php_core_globals,
+    php_file_globals, sapi_globals_struct, sapi_header_struct, sapi_headers_struct,
+    sapi_read_post_block, sapi_request_info, zend_ini_entry, zend_is_auto_global, SEEK_END,
+    SEEK_SET, TRACK_VARS_COOKIE, TRACK_VARS_ENV, TRACK_VARS_FILES, TRACK_VARS_GET, TRACK_VARS_POST,
+    TRACK_VARS_SERVER,
 };
 
+use crate::flags::ZvalTypeFlags;
 use crate::types::{ZendHashTable, ZendObject, ZendStr};
 
 use super::linked_list::ZendLinkedListIterator;
+use super::Stream;
 
 /// Stores global variables used in the PHP executor.
 pub type ExecutorGlobals = _zend_executor_globals;
@@ -310,6 +318,117 @@ impl ProcessGlobals {
             .array()
             .expect("Type is not a ZendArray")
     }
+
+    /// Get a mutable reference to the HTTP Server variables. Equivalent of
+    /// $_SERVER.
+    ///
+    /// The array is separated first if it is shared, see `http_vars_mut`.
+    pub fn http_server_vars_mut(&mut self) -> &mut ZendHashTable {
+        Self::arm_auto_global("_SERVER");
+        self.http_vars_mut(TRACK_VARS_SERVER, "_SERVER")
+    }
+
+    /// Get a mutable reference to the HTTP Environment variables. Equivalent
+    /// of $_ENV.
+    ///
+    /// The array is separated first if it is shared, see `http_vars_mut`.
+    pub fn http_env_vars_mut(&mut self) -> &mut ZendHashTable {
+        Self::arm_auto_global("_ENV");
+        self.http_vars_mut(TRACK_VARS_ENV, "_ENV")
+    }
+
+    /// Get a mutable reference to the HTTP GET variables. Equivalent of
+    /// $_GET.
+    ///
+    /// The array is separated first if it is shared, see `http_vars_mut`.
+    pub fn http_get_vars_mut(&mut self) -> &mut ZendHashTable {
+        self.http_vars_mut(TRACK_VARS_GET, "_GET")
+    }
+
+    /// Get a mutable reference to the HTTP POST variables. Equivalent of
+    /// $_POST.
+    ///
+    /// The array is separated first if it is shared, see `http_vars_mut`.
+    pub fn http_post_vars_mut(&mut self) -> &mut ZendHashTable {
+        self.http_vars_mut(TRACK_VARS_POST, "_POST")
+    }
+
+    /// Get a mutable reference to the HTTP Cookie variables. Equivalent of
+    /// $_COOKIE.
+    ///
+    /// The array is separated first if it is shared, see `http_vars_mut`.
+    pub fn http_cookie_vars_mut(&mut self) -> &mut ZendHashTable {
+        self.http_vars_mut(TRACK_VARS_COOKIE, "_COOKIE")
+    }
+
+    /// Sets a HTTP Server variable. Equivalent of `$_SERVER[$key] = $value`.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The name of the variable.
+    /// * `value` - The value of the variable.
+    pub fn set_server_var<V: IntoZval>(&mut self, key: &str, value: V) -> Result<()> {
+        self.http_server_vars_mut().insert(key, value)
+    }
+
+    /// Sets a HTTP Environment variable. Equivalent of `$_ENV[$key] =
+    /// $value`.
+    ///
+    /// This only changes the value seen through $_ENV, not the environment of
+    /// the process as seen by `getenv()`.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The name of the variable.
+    /// * `value` - The value of the variable.
+    pub fn set_env_var<V: IntoZval>(&mut self, key: &str, value: V) -> Result<()> {
+        self.http_env_vars_mut().insert(key, value)
+    }
+
+    /// Populates a lazily initialized superglobal, so values written to it
+    /// are not overwritten when it is first accessed.
+    fn arm_auto_global(name: &str) {
+        let mut name = ZendStr::new(name, false);
+        unsafe { zend_is_auto_global(name.as_mut_ptr()) };
+    }
+
+    /// Returns a mutable reference to the array of the given superglobal.
+    ///
+    /// Arrays shared with PHP variables are separated before being returned,
+    /// so copies of the superglobal held by scripts, such as `$server =
+    /// $_SERVER`, are not modified. Sharing with the global symbol table does
+    /// not count, as it holds the superglobal itself.
+    fn http_vars_mut(&mut self, track: u32, name: &str) -> &mut ZendHashTable {
+        let mut eg = ExecutorGlobals::get_mut();
+        let symbols = &mut eg.symbol_table;
+        let zv = &mut self.http_globals[track as usize];
+
+        let shared = match zv.array() {
+            Some(arr) => {
+                // The superglobal is normally referenced by both the process globals and the
+                // global symbol table. Any other reference is a copy made by a script.
+                let published = symbols
+                    .get(name)
+                    .and_then(|val| val.array())
+                    .is_some_and(|val| ptr::eq(val, arr));
+                let flags = ZvalTypeFlags::from_bits_retain(unsafe { zv.u1.type_info });
+                !flags.contains(ZvalTypeFlags::RefCounted) || arr.gc.refcount > 1 + published as u32
+            }
+            None => true,
+        };
+
+        if shared {
+            let arr = zv
+                .array()
+                .map_or_else(ZendHashTable::new, ToOwned::to_owned);
+            zv.set_hashtable(arr);
+
+            // Publish the separated array so scripts see the new values.
+            let _ = symbols.insert(name, zv.shallow_clone());
+        }
+
+        zv.array_mut().expect("Superglobal is not an array")
+    }
 }
 
 /// Stores global variables used in the SAPI.
@@ -384,6 +503,52 @@ impl<'a> SapiHeader {
 
 pub type SapiRequestInfo = sapi_request_info;
 
+/// A reader over the raw body of the current request, returned by
+/// [`SapiRequestInfo::request_body`].
+///
+/// Each reader starts at the beginning of the body. Parts of the body which
+/// the SAPI has not read yet are read on demand, so large bodies can be
+/// processed without first being copied into a PHP string.
+pub struct RequestBody<'a> {
+    stream: *mut Stream,
+    position: i64,
+    _info: PhantomData<&'a SapiRequestInfo>,
+}
+
+impl Read for RequestBody<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        // This mirrors how `php://input` reads the body, so both can be used in the same
+        // request.
+        unsafe {
+            let sg = &*ext_php_rs_sapi_globals();
+            if sg.post_read == 0 && sg.read_post_bytes < self.position + buf.len() as i64 {
+                let read = sapi_read_post_block(buf.as_mut_ptr().cast(), buf.len());
+                if read > 0 {
+                    _php_stream_seek(self.stream, 0, SEEK_END as _);
+                    _php_stream_write(self.stream, buf.as_ptr().cast(), read);
+                }
+            }
+
+            // Filtered streams are not seekable, and are always read sequentially.
+            if (*self.stream).readfilters.head.is_null() {
+                _php_stream_seek(self.stream, self.position, SEEK_SET as _);
+            }
+
+            let read = _php_stream_read(self.stream, buf.as_mut_ptr().cast(), buf.len());
+            if read < 0 {
+                return Err(io::Error::other("Failed to read the request body"));
+            }
+
+            self.position += read as i64;
+            Ok(read as usize)
+        }
+    }
+}
+
 impl SapiRequestInfo {
     pub fn request_method(&self) -> Option<&str> {
         if self.request_method.is_null() {
@@ -424,7 +589,18 @@ impl SapiRequestInfo {
         unsafe { CStr::from_ptr(self.request_uri).to_str().ok() }
     }
 
-    // Todo: request_body _php_stream
+    /// Returns a reader over the raw body of the request, equivalent to
+    /// `php://input`, or [`None`] if the SAPI has not provided a body.
+    pub fn request_body(&self) -> Option<RequestBody<'_>> {
+        if self.request_body.is_null() {
+            return None;
+        }
+        Some(RequestBody {
+            stream: self.request_body,
+            position: 0,
+            _info: PhantomData,
+        })
+    }
 
     pub fn content_type(&self) -> Option<&str> {
         if self.content_type.is_null() {
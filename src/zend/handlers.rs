@@ -1,20 +1,43 @@
-use std::{ffi::c_void, mem::MaybeUninit, os::raw::c_int, ptr};
+use std::{
+    cmp::Ordering, collections::HashSet, convert::TryFrom, ffi::c_void, mem::MaybeUninit,
+    os::raw::c_int, ptr,
+};
 
 use crate::{
-    class::RegisteredClass,
-    exception::PhpResult,
+    class::{CastResult, CastType, RegisteredClass},
+    exception::{PhpException, PhpResult},
     ffi::{
-        std_object_handlers, zend_is_true, zend_object_handlers, zend_object_std_dtor,
-        zend_std_get_properties, zend_std_has_property, zend_std_read_property,
-        zend_std_write_property,
+        std_object_handlers, zend_get_property_info, zend_hash_update, zend_is_true,
+        zend_object_handlers, zend_object_std_dtor, zend_property_info, zend_result,
+        zend_std_cast_object_tostring, zend_std_compare_objects, zend_std_get_properties,
+        zend_std_get_property_ptr_ptr, zend_std_has_property, zend_std_read_property,
+        zend_std_unset_property, zend_std_write_property, BP_VAR_IS, BP_VAR_RW, BP_VAR_UNSET,
+        BP_VAR_W, ZEND_ACC_STATIC, ZEND_RESULT_CODE_FAILURE, ZEND_RESULT_CODE_SUCCESS,
+        ZEND_UNCOMPARABLE,
     },
     flags::ZvalTypeFlags,
+    props::Property,
     types::{ZendClassObject, ZendHashTable, ZendObject, ZendStr, Zval},
 };
 
+use super::{ce, ExecutorGlobals, GcBuffer};
+
 /// A set of functions associated with a PHP class.
 pub type ZendObjectHandlers = zend_object_handlers;
 
+/// Returned by `zend_get_property_info` when a declared property cannot be
+/// accessed from the executing scope.
+const ZEND_WRONG_PROPERTY_INFO: *mut zend_property_info = -1isize as *mut zend_property_info;
+
+/// Returns whether the property with the given name can be accessed from the
+/// executing scope, based on the visibility it was declared with.
+///
+/// Inaccessible properties backed by struct fields are handed to the standard
+/// handlers, which throw the same errors as for properties declared in PHP.
+unsafe fn is_accessible(object: *mut ZendObject, member: *mut ZendStr) -> bool {
+    zend_get_property_info((*object).ce, member, 1) != ZEND_WRONG_PROPERTY_INFO
+}
+
 impl ZendObjectHandlers {
     /// Creates a new set of object handlers based on the standard object
     /// handlers.
@@ -49,6 +72,26 @@ impl ZendObjectHandlers {
         (*ptr).write_property = Some(Self::write_property::<T>);
         (*ptr).get_properties = Some(Self::get_properties::<T>);
         (*ptr).has_property = Some(Self::has_property::<T>);
+        (*ptr).unset_property = Some(Self::unset_property::<T>);
+        (*ptr).get_property_ptr_ptr = Some(Self::get_property_ptr_ptr::<T>);
+        if T::GC.is_some() {
+            (*ptr).get_gc = Some(Self::get_gc::<T>);
+        }
+        if T::DEBUG_INFO.is_some() {
+            (*ptr).get_debug_info = Some(Self::get_debug_info::<T>);
+        }
+        if T::CAST.is_some() {
+            (*ptr).cast_object = Some(Self::cast_object::<T>);
+        }
+        if T::COMPARE.is_some() {
+            (*ptr).compare = Some(Self::compare::<T>);
+        }
+        if T::DIMENSIONS.is_some() {
+            (*ptr).read_dimension = Some(Self::read_dimension::<T>);
+            (*ptr).write_dimension = Some(Self::write_dimension::<T>);
+            (*ptr).has_dimension = Some(Self::has_dimension::<T>);
+            (*ptr).unset_dimension = Some(Self::unset_dimension::<T>);
+        }
     }
 
     unsafe extern "C" fn free_obj<T: RegisteredClass>(object: *mut ZendObject) {
@@ -63,6 +106,290 @@ impl ZendObjectHandlers {
         zend_object_std_dtor(object)
     }
 
+    unsafe extern "C" fn get_gc<T: RegisteredClass>(
+        object: *mut ZendObject,
+        table: *mut *mut Zval,
+        n: *mut c_int,
+    ) -> *mut ZendHashTable {
+        let buf = GcBuffer::new();
+
+        if let (Some(gc), Some(obj)) = (
+            T::GC,
+            object
+                .as_ref()
+                .and_then(|obj| ZendClassObject::<T>::from_zend_obj(obj))
+                .and_then(|obj| obj.obj.as_ref()),
+        ) {
+            gc(obj, buf);
+        }
+
+        (*table, *n) = buf.as_table();
+
+        // The properties table also contains the declared properties of the object, which
+        // are visited by the collector alongside the buffered values.
+        zend_std_get_properties(object)
+    }
+
+    unsafe extern "C" fn get_debug_info<T: RegisteredClass>(
+        object: *mut ZendObject,
+        is_temp: *mut c_int,
+    ) -> *mut ZendHashTable {
+        #[inline(always)]
+        unsafe fn internal<T: RegisteredClass>(
+            object: *mut ZendObject,
+            info: &mut ZendHashTable,
+        ) -> PhpResult {
+            let obj = object
+                .as_ref()
+                .and_then(|obj| ZendClassObject::<T>::from_zend_obj(obj))
+                .and_then(|obj| obj.obj.as_ref())
+                .ok_or("Invalid object pointer given")?;
+
+            match T::DEBUG_INFO {
+                Some(debug_info) => debug_info(obj, info),
+                None => Ok(()),
+            }
+        }
+
+        let mut info = ZendHashTable::new();
+        if let Err(e) = internal::<T>(object, &mut info) {
+            let _ = e.throw();
+        }
+
+        // The table is built for each call, so PHP must release it once it has been
+        // printed.
+        *is_temp = 1;
+        info.into_raw()
+    }
+
+    unsafe extern "C" fn cast_object<T: RegisteredClass>(
+        object: *mut ZendObject,
+        retval: *mut Zval,
+        ty: c_int,
+    ) -> zend_result {
+        #[inline(always)]
+        unsafe fn internal<T: RegisteredClass>(
+            object: *mut ZendObject,
+            ty: CastType,
+        ) -> CastResult {
+            let obj = object
+                .as_ref()
+                .and_then(|obj| ZendClassObject::<T>::from_zend_obj(obj))
+                .and_then(|obj| obj.obj.as_ref())
+                .ok_or("Invalid object pointer given")?;
+
+            match T::CAST {
+                Some(cast) => cast(obj, ty),
+                None => Ok(None),
+            }
+        }
+
+        let cast_ty = match CastType::try_from(ty as u32) {
+            Ok(cast_ty) => cast_ty,
+            Err(_) => return zend_std_cast_object_tostring(object, retval, ty),
+        };
+
+        match internal::<T>(object, cast_ty) {
+            Ok(Some(zv)) if cast_ty.accepts(&zv) => {
+                // `retval` is uninitialized, so it must not be dropped.
+                retval.write(zv);
+                ZEND_RESULT_CODE_SUCCESS
+            }
+            Ok(Some(_)) => ZEND_RESULT_CODE_FAILURE,
+            Ok(None) => zend_std_cast_object_tostring(object, retval, ty),
+            Err(e) => {
+                let _ = e.throw();
+                ZEND_RESULT_CODE_FAILURE
+            }
+        }
+    }
+
+    unsafe extern "C" fn compare<T: RegisteredClass>(o1: *mut Zval, o2: *mut Zval) -> c_int {
+        let obj = |zv: *mut Zval| {
+            zv.as_ref()
+                .and_then(|zv| zv.object())
+                .and_then(|obj| ZendClassObject::<T>::from_zend_obj(obj))
+                .and_then(|obj| obj.obj.as_ref())
+        };
+
+        match (T::COMPARE, obj(o1), obj(o2)) {
+            (Some(compare), Some(a), Some(b)) => match compare(a, b) {
+                Some(Ordering::Less) => -1,
+                Some(Ordering::Equal) => 0,
+                Some(Ordering::Greater) => 1,
+                None => ZEND_UNCOMPARABLE as c_int,
+            },
+            _ => zend_std_compare_objects(o1, o2),
+        }
+    }
+
+    unsafe extern "C" fn read_dimension<T: RegisteredClass>(
+        object: *mut ZendObject,
+        offset: *mut Zval,
+        type_: c_int,
+        rv: *mut Zval,
+    ) -> *mut Zval {
+        #[inline(always)]
+        unsafe fn internal<T: RegisteredClass>(
+            object: *mut ZendObject,
+            offset: *mut Zval,
+            type_: c_int,
+        ) -> PhpResult<Zval> {
+            let obj = object
+                .as_ref()
+                .and_then(|obj| ZendClassObject::<T>::from_zend_obj(obj))
+                .and_then(|obj| obj.obj.as_ref())
+                .ok_or("Invalid object pointer given")?;
+            let dims = T::DIMENSIONS.ok_or("Class does not support array access")?;
+            let offset = offset.as_ref().ok_or("Cannot use [] for reading")?;
+
+            // Reads from `isset()` and the null coalescing operator must not fail when the
+            // offset does not exist.
+            if type_ as u32 == BP_VAR_IS && !(dims.exists)(obj, offset)? {
+                return Ok(Zval::new());
+            }
+
+            let zv = (dims.get)(obj, offset)?;
+
+            // Nested writes such as `$obj['a'][] = 1` modify the returned value, which
+            // would be lost unless it is an object or a reference.
+            if matches!(type_ as u32, BP_VAR_W | BP_VAR_RW | BP_VAR_UNSET)
+                && !zv.is_object()
+                && !zv.is_reference()
+            {
+                return Err(PhpException::new(
+                    format!(
+                        "Indirect modification of element of {} is not supported",
+                        T::CLASS_NAME
+                    ),
+                    0,
+                    ce::error(),
+                ));
+            }
+
+            Ok(zv)
+        }
+
+        match internal::<T>(object, offset, type_) {
+            Ok(zv) => {
+                // `rv` is uninitialized, so it must not be dropped.
+                rv.write(zv);
+                rv
+            }
+            Err(e) => {
+                // The engine treats a null pointer as a failed read once an exception has
+                // been thrown.
+                let _ = e.throw();
+                ptr::null_mut()
+            }
+        }
+    }
+
+    unsafe extern "C" fn write_dimension<T: RegisteredClass>(
+        object: *mut ZendObject,
+        offset: *mut Zval,
+        value: *mut Zval,
+    ) {
+        #[inline(always)]
+        unsafe fn internal<T: RegisteredClass>(
+            object: *mut ZendObject,
+            offset: *mut Zval,
+            value: *mut Zval,
+        ) -> PhpResult {
+            let obj = object
+                .as_mut()
+                .and_then(|obj| ZendClassObject::<T>::from_zend_obj_mut(obj))
+                .and_then(|obj| obj.obj.as_mut())
+                .ok_or("Invalid object pointer given")?;
+            let dims = T::DIMENSIONS.ok_or("Class does not support array access")?;
+            let value = value.as_ref().ok_or("Invalid value given")?;
+
+            (dims.set)(obj, offset.as_ref(), value)
+        }
+
+        if let Err(e) = internal::<T>(object, offset, value) {
+            let _ = e.throw();
+        }
+    }
+
+    unsafe extern "C" fn has_dimension<T: RegisteredClass>(
+        object: *mut ZendObject,
+        offset: *mut Zval,
+        check_empty: c_int,
+    ) -> c_int {
+        #[inline(always)]
+        unsafe fn internal<T: RegisteredClass>(
+            object: *mut ZendObject,
+            offset: *mut Zval,
+            check_empty: c_int,
+        ) -> PhpResult<bool> {
+            let obj = object
+                .as_ref()
+                .and_then(|obj| ZendClassObject::<T>::from_zend_obj(obj))
+                .and_then(|obj| obj.obj.as_ref())
+                .ok_or("Invalid object pointer given")?;
+            let dims = T::DIMENSIONS.ok_or("Class does not support array access")?;
+            let offset = offset.as_ref().ok_or("Invalid offset given")?;
+
+            if !(dims.exists)(obj, offset)? {
+                return Ok(false);
+            }
+
+            // `empty()` also requires the value to be truthy, while `isset()` only checks
+            // that the offset exists.
+            if check_empty != 0 {
+                let mut zv = (dims.get)(obj, offset)?;
+
+                cfg_if::cfg_if! {
+                    if #[cfg(php84)] {
+                        #[allow(clippy::unnecessary_mut_passed)]
+                        let truthy = zend_is_true(&mut zv);
+                    } else {
+                        #[allow(clippy::unnecessary_mut_passed)]
+                        let truthy = zend_is_true(&mut zv) == 1;
+                    }
+                }
+
+                return Ok(truthy);
+            }
+
+            Ok(true)
+        }
+
+        match internal::<T>(object, offset, check_empty) {
+            Ok(exists) => exists as c_int,
+            Err(e) => {
+                let _ = e.throw();
+                0
+            }
+        }
+    }
+
+    unsafe extern "C" fn unset_dimension<T: RegisteredClass>(
+        object: *mut ZendObject,
+        offset: *mut Zval,
+    ) {
+        #[inline(always)]
+        unsafe fn internal<T: RegisteredClass>(
+            object: *mut ZendObject,
+            offset: *mut Zval,
+        ) -> PhpResult {
+            let obj = object
+                .as_mut()
+                .and_then(|obj| ZendClassObject::<T>::from_zend_obj_mut(obj))
+                .and_then(|obj| obj.obj.as_mut())
+                .ok_or("Invalid object pointer given")?;
+            let dims = T::DIMENSIONS.ok_or("Class does not support array access")?;
+            let offset = offset.as_ref().ok_or("Invalid offset given")?;
+
+            (dims.unset)(obj, offset)
+        }
+
+        if let Err(e) = internal::<T>(object, offset) {
+            let _ = e.throw();
+        }
+    }
+
     unsafe extern "C" fn read_property<T: RegisteredClass>(
         object: *mut ZendObject,
         member: *mut ZendStr,
@@ -85,20 +412,52 @@ impl ZendObjectHandlers {
             let prop_name = member
                 .as_ref()
                 .ok_or("Invalid property name pointer given")?;
-            let self_ = &mut **obj;
-            let props = T::get_metadata().get_properties();
-            let prop = props.get(prop_name.as_str()?);
+            let prop = T::get_metadata().find_property(prop_name);
 
             // retval needs to be treated as initialized, so we set the type to null
             let rv_mut = rv.as_mut().ok_or("Invalid return zval given")?;
             rv_mut.u1.type_info = ZvalTypeFlags::Null.bits();
 
-            Ok(match prop {
-                Some(prop) => {
-                    prop.get(self_, rv_mut)?;
+            // Until the constructor has initialized the struct, properties read the
+            // defaults declared on the class.
+            Ok(match (prop, obj.obj.as_mut()) {
+                (Some(prop), Some(self_)) if is_accessible(object, member) => {
+                    let cache = if prop.is_cached() {
+                        let name = prop_name.as_str().ok();
+                        name.and_then(|name| (*object).declared_property_mut(name))
+                    } else {
+                        None
+                    };
+
+                    match cache {
+                        Some(cache) if !cache.is_undef() => *rv_mut = cache.shallow_clone(),
+                        Some(cache) => {
+                            prop.get(self_, rv_mut)?;
+                            *cache = rv_mut.shallow_clone();
+                        }
+                        None => prop.get(self_, rv_mut)?,
+                    }
+
+                    // Fetches for writing, e.g. `$obj->prop[] = 1` or `$ref = &$obj->prop`,
+                    // would modify a copy of the field, so they are rejected unless the
+                    // value is an object.
+                    if matches!(type_ as u32, BP_VAR_W | BP_VAR_RW | BP_VAR_UNSET)
+                        && !rv_mut.is_object()
+                        && !rv_mut.is_reference()
+                    {
+                        return Err(PhpException::new(
+                            format!(
+                                "Cannot indirectly modify or reference property {}::${}",
+                                T::CLASS_NAME,
+                                prop_name.as_str().unwrap_or_default()
+                            ),
+                            0,
+                            ce::error(),
+                        ));
+                    }
                     rv
                 }
-                None => zend_std_read_property(object, member, type_, cache_slot, rv),
+                _ => zend_std_read_property(object, member, type_, cache_slot, rv),
             })
         }
 
@@ -133,17 +492,48 @@ impl ZendObjectHandlers {
                 .as_ref()
                 .ok_or("Invalid property name pointer given")?;
             let self_ = &mut **obj;
-            let props = T::get_metadata().get_properties();
-            let prop = props.get(prop_name.as_str()?);
+            let prop = T::get_metadata().find_property(prop_name);
             let value_mut = value.as_mut().ok_or("Invalid return zval given")?;
 
-            Ok(match prop {
-                Some(prop) => {
+            let (rv, changed) = match prop {
+                Some(prop) if is_accessible(object, member) => {
                     prop.set(self_, value_mut)?;
-                    value
+                    if prop.is_cached() {
+                        if let Ok(name) = prop_name.as_str() {
+                            obj.invalidate_prop(name);
+                        }
+                    }
+                    (value, true)
                 }
-                None => zend_std_write_property(object, member, value, cache_slot),
-            })
+                Some(_) => (
+                    zend_std_write_property(object, member, value, cache_slot),
+                    false,
+                ),
+                None => {
+                    // Writes to dynamic properties are not reported to the observer.
+                    let declared = prop_name.as_str().is_ok_and(|name| {
+                        obj.std
+                            .get_class_entry()
+                            .properties_info
+                            .get(name)
+                            .is_some()
+                    });
+                    (
+                        zend_std_write_property(object, member, value, cache_slot),
+                        declared,
+                    )
+                }
+            };
+
+            if let (Some(observer), true) = (T::PROPERTY_CHANGED, changed) {
+                if let (Some(self_), Ok(name)) = (obj.obj.as_mut(), prop_name.as_str()) {
+                    if !ExecutorGlobals::has_exception() {
+                        observer(self_, name);
+                    }
+                }
+            }
+
+            Ok(rv)
         }
 
         match internal::<T>(object, member, value, cache_slot) {
@@ -168,11 +558,55 @@ impl ZendObjectHandlers {
                 .and_then(|obj| ZendClassObject::<T>::from_zend_obj_mut(obj))
                 .ok_or("Invalid object pointer given")?;
             let self_ = &mut **obj;
-            let struct_props = T::get_metadata().get_properties();
+            let meta = T::get_metadata();
 
-            for (name, val) in struct_props {
+            // Only properties backed by fields are inserted. Properties backed by
+            // getters are computed when they are read, and the values of cached
+            // getters are already stored in the property table by the engine.
+            //
+            // Declared properties are inserted in declaration order, under the name
+            // stored in their property info. Protected and private properties are
+            // stored under their mangled names, which contain NUL bytes and are
+            // how the engine knows to hide them from other scopes.
+            let mut inserted = HashSet::new();
+            let declared = (*object).ce.as_ref().map(|ce| &ce.properties_info);
+            for element in declared
+                .into_iter()
+                .flat_map(|declared| declared.raw_elements())
+            {
+                let (name, info) = match (element.key, element.val.ptr::<zend_property_info>()) {
+                    (Some(name), Some(info)) => (name, &*info),
+                    _ => continue,
+                };
+                if info.flags & ZEND_ACC_STATIC != 0 {
+                    continue;
+                }
+                let prop = match meta.find_property(name) {
+                    Some(prop @ Property::Field(_)) => prop,
+                    _ => continue,
+                };
                 let mut zv = Zval::new();
-                if val.get(self_, &mut zv).is_err() {
+                if prop.get(self_, &mut zv).is_err() {
+                    continue;
+                }
+                zend_hash_update(props, info.name, &mut zv);
+                zv.release();
+                inserted.insert(name.as_bytes());
+            }
+
+            // Properties which are not declared on the class are inserted under their
+            // plain names, sorted so the order does not change between requests.
+            let mut undeclared = meta
+                .get_properties()
+                .iter()
+                .filter(|(name, prop)| {
+                    matches!(prop, Property::Field(_)) && !inserted.contains(name.as_bytes())
+                })
+                .collect::<Vec<_>>();
+            undeclared.sort_by_key(|(name, _)| *name);
+            for (name, prop) in undeclared {
+                let mut zv = Zval::new();
+                if prop.get(self_, &mut zv).is_err() {
                     continue;
                 }
                 props.insert(name, zv).map_err(|e| {
@@ -215,8 +649,9 @@ impl ZendObjectHandlers {
             let prop_name = member
                 .as_ref()
                 .ok_or("Invalid property name pointer given")?;
-            let props = T::get_metadata().get_properties();
-            let prop = props.get(prop_name.as_str()?);
+            let prop = T::get_metadata()
+                .find_property(prop_name)
+                .filter(|_| is_accessible(object, member));
             let self_ = &mut **obj;
 
             match has_set_exists {
@@ -282,4 +717,67 @@ impl ZendObjectHandlers {
             }
         }
     }
+
+    unsafe extern "C" fn unset_property<T: RegisteredClass>(
+        object: *mut ZendObject,
+        member: *mut ZendStr,
+        cache_slot: *mut *mut c_void,
+    ) {
+        #[inline(always)]
+        unsafe fn internal<T: RegisteredClass>(
+            object: *mut ZendObject,
+            member: *mut ZendStr,
+            cache_slot: *mut *mut c_void,
+        ) -> PhpResult {
+            let prop_name = member
+                .as_ref()
+                .ok_or("Invalid property name pointer given")?;
+
+            // Properties backed by struct fields cannot be removed from the object.
+            if T::get_metadata().find_property(prop_name).is_some() && is_accessible(object, member)
+            {
+                return Err(PhpException::new(
+                    format!(
+                        "Cannot unset property {}::${}",
+                        T::CLASS_NAME,
+                        String::from_utf8_lossy(prop_name.as_bytes())
+                    ),
+                    0,
+                    ce::error(),
+                ));
+            }
+
+            zend_std_unset_property(object, member, cache_slot);
+            Ok(())
+        }
+
+        if let Err(e) = internal::<T>(object, member, cache_slot) {
+            let _ = e.throw();
+        }
+    }
+
+    unsafe extern "C" fn get_property_ptr_ptr<T: RegisteredClass>(
+        object: *mut ZendObject,
+        member: *mut ZendStr,
+        type_: c_int,
+        cache_slot: *mut *mut c_void,
+    ) -> *mut Zval {
+        let is_rust_prop = member
+            .as_ref()
+            .is_some_and(|name| T::get_metadata().find_property(name).is_some());
+
+        // Properties backed by struct fields do not live in the properties table, so
+        // there is no zval to point to. Returning null makes the engine fall back to
+        // `read_property` and `write_property`. Compound assignments such as `+=` work
+        // through the fallback, while references and nested writes are rejected by
+        // `read_property`, as they would only modify a copy of the field.
+        if is_rust_prop {
+            return ptr::null_mut();
+        }
+
+        // Other declared properties are modified through the returned pointer without
+        // calling `write_property`, so these writes are not reported to
+        // `PhpPropertyObserver`.
+        zend_std_get_property_ptr_ptr(object, member, type_, cache_slot)
+    }
 }
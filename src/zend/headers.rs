@@ -0,0 +1,103 @@
+//! Functions for emitting HTTP response headers through the SAPI.
+//!
+//! These are the Rust equivalents of PHP's `header()`, `header_remove()` and
+//! `http_response_code()`. Headers are buffered by the SAPI and sent with the
+//! first output of the request, so all operations fail once output has been
+//! sent.
+
+use std::{convert::TryFrom, ffi::c_void, ptr};
+
+use crate::{
+    error::{Error, Result},
+    ffi::{
+        sapi_header_line, sapi_header_op, sapi_header_op_enum, sapi_header_op_enum_SAPI_HEADER_ADD,
+        sapi_header_op_enum_SAPI_HEADER_DELETE, sapi_header_op_enum_SAPI_HEADER_DELETE_ALL,
+        sapi_header_op_enum_SAPI_HEADER_REPLACE, sapi_header_op_enum_SAPI_HEADER_SET_STATUS,
+        ZEND_RESULT_CODE_SUCCESS,
+    },
+};
+
+use super::SapiGlobals;
+
+/// Performs a header operation with the given header line.
+fn header_line_op(op: sapi_header_op_enum, line: &str) -> Result<()> {
+    let mut ctr = sapi_header_line {
+        line: line.as_ptr().cast(),
+        line_len: line.len(),
+        response_code: 0,
+    };
+    header_op(op, &mut ctr as *mut sapi_header_line as *mut c_void)
+}
+
+fn header_op(op: sapi_header_op_enum, arg: *mut c_void) -> Result<()> {
+    // SAFETY: The SAPI copies the header line, so it does not need to outlive the call.
+    if unsafe { sapi_header_op(op, arg) } == ZEND_RESULT_CODE_SUCCESS as _ {
+        Ok(())
+    } else {
+        Err(Error::HeaderOperationFailure)
+    }
+}
+
+/// Adds a response header, keeping any existing headers with the same name.
+/// Equivalent to `header($header, false)`.
+///
+/// # Parameters
+///
+/// * `header` - The header line, e.g. `Set-Cookie: a=b`.
+pub fn add(header: &str) -> Result<()> {
+    header_line_op(sapi_header_op_enum_SAPI_HEADER_ADD, header)
+}
+
+/// Adds a response header, replacing any existing headers with the same name.
+/// Equivalent to `header($header)`.
+///
+/// As with `header()`, `Location` headers and `HTTP/` status lines also change
+/// the response code.
+///
+/// # Parameters
+///
+/// * `header` - The header line, e.g. `Content-Type: application/json`.
+pub fn replace(header: &str) -> Result<()> {
+    header_line_op(sapi_header_op_enum_SAPI_HEADER_REPLACE, header)
+}
+
+/// Removes all response headers with the given name. Equivalent to
+/// `header_remove($name)`.
+///
+/// # Parameters
+///
+/// * `name` - The name of the header, without a colon.
+pub fn remove(name: &str) -> Result<()> {
+    header_line_op(sapi_header_op_enum_SAPI_HEADER_DELETE, name)
+}
+
+/// Removes all response headers which have been set. Equivalent to
+/// `header_remove()`.
+pub fn remove_all() -> Result<()> {
+    header_op(sapi_header_op_enum_SAPI_HEADER_DELETE_ALL, ptr::null_mut())
+}
+
+/// Sets the HTTP response code. Equivalent to `http_response_code($code)`.
+///
+/// # Parameters
+///
+/// * `code` - The response code, e.g. `404`.
+pub fn set_response_code(code: u16) -> Result<()> {
+    header_op(
+        sapi_header_op_enum_SAPI_HEADER_SET_STATUS,
+        code as usize as *mut c_void,
+    )
+}
+
+/// Returns the HTTP response code which will be sent, or [`None`] if no
+/// response code has been set, e.g. under the CLI SAPI.
+pub fn response_code() -> Option<u16> {
+    let code = SapiGlobals::get().sapi_headers().http_response_code;
+    u16::try_from(code).ok().filter(|code| *code != 0)
+}
+
+/// Returns whether the response headers have already been sent, after which
+/// they can no longer be changed.
+pub fn headers_sent() -> bool {
+    SapiGlobals::get().headers_sent != 0
+}
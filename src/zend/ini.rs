@@ -0,0 +1,289 @@
+//! Typed access to the values of INI directives.
+
+use crate::{
+    error::{Error, Result},
+    ffi::{
+        zend_alter_ini_entry_ex, zend_ini_entry, zend_restore_ini_entry, PHP_INI_PERDIR,
+        PHP_INI_SYSTEM, PHP_INI_USER, ZEND_INI_STAGE_ACTIVATE, ZEND_INI_STAGE_HTACCESS,
+        ZEND_INI_STAGE_RUNTIME, ZEND_INI_STAGE_STARTUP, ZEND_RESULT_CODE_SUCCESS,
+    },
+    types::ZendStr,
+};
+
+use super::ExecutorGlobals;
+
+/// The stage at which the value of an ini entry is changed. This determines
+/// which entries can be changed, based on the permissions they were
+/// registered with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IniStage {
+    /// Module startup, equivalent to setting the value in `php.ini`. All
+    /// entries can be changed, and the value applies to every request.
+    Startup,
+    /// Request startup, as used by SAPIs applying per-request configuration
+    /// such as `php_admin_value`. All entries can be changed, and the value is
+    /// reset at the end of the request.
+    Activate,
+    /// Per-directory configuration, such as `.htaccess` files. Entries
+    /// registered with [`IniEntryPermission::PerDir`] or
+    /// [`IniEntryPermission::User`] can be changed.
+    ///
+    /// [`IniEntryPermission::PerDir`]: crate::flags::IniEntryPermission::PerDir
+    /// [`IniEntryPermission::User`]: crate::flags::IniEntryPermission::User
+    Htaccess,
+    /// Script execution, equivalent to `ini_set()`. Only entries registered
+    /// with [`IniEntryPermission::User`] can be changed, and the value is reset
+    /// at the end of the request.
+    ///
+    /// [`IniEntryPermission::User`]: crate::flags::IniEntryPermission::User
+    Runtime,
+}
+
+impl IniStage {
+    /// Returns the permission required to change an entry at this stage.
+    fn modify_type(self) -> u32 {
+        match self {
+            Self::Startup | Self::Activate => PHP_INI_SYSTEM,
+            Self::Htaccess => PHP_INI_PERDIR,
+            Self::Runtime => PHP_INI_USER,
+        }
+    }
+
+    fn stage(self) -> u32 {
+        match self {
+            Self::Startup => ZEND_INI_STAGE_STARTUP,
+            Self::Activate => ZEND_INI_STAGE_ACTIVATE,
+            Self::Htaccess => ZEND_INI_STAGE_HTACCESS,
+            Self::Runtime => ZEND_INI_STAGE_RUNTIME,
+        }
+    }
+}
+
+/// Reads the current values of INI directives, as returned by `ini_get()`.
+///
+/// Directives which are not registered return [`None`], as do values which
+/// cannot be parsed as the requested type.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::zend::Ini;
+///
+/// let display_errors = Ini::get_bool("display_errors").unwrap_or(false);
+/// let memory_limit = Ini::get_int("memory_limit");
+/// ```
+pub struct Ini;
+
+impl Ini {
+    /// Returns the current value of a directive as a string.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the directive, e.g. `error_log`.
+    pub fn get(name: &str) -> Option<String> {
+        Self::with_value(name, str::to_owned)
+    }
+
+    /// Returns the current value of a directive as a boolean, using the same
+    /// rules as PHP. See [`Ini::parse_bool`].
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the directive, e.g. `display_errors`.
+    pub fn get_bool(name: &str) -> Option<bool> {
+        Self::with_value(name, Self::parse_bool)
+    }
+
+    /// Returns the current value of a directive as an integer, accepting the
+    /// shorthand notation used for sizes. See [`Ini::parse_quantity`].
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the directive, e.g. `memory_limit`.
+    pub fn get_int(name: &str) -> Option<i64> {
+        Self::with_value(name, Self::parse_quantity).flatten()
+    }
+
+    /// Returns the current value of a directive as a float.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the directive, e.g. `precision`.
+    pub fn get_float(name: &str) -> Option<f64> {
+        Self::with_value(name, |val| val.trim().parse().ok()).flatten()
+    }
+
+    /// Changes the value of a directive, equivalent to `ini_set()` when called
+    /// with [`IniStage::Runtime`].
+    ///
+    /// Returns an error if the directive does not exist, cannot be changed at
+    /// the given stage, or its `on_modify` handler rejects the value.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the directive, e.g. `myext.mode`.
+    /// * `value` - The new value of the directive.
+    /// * `stage` - The stage at which the value is being changed.
+    pub fn set(name: &str, value: &str, stage: IniStage) -> Result<()> {
+        let modifiable = Self::with_entry(name, |entry| entry.modifiable as u32)
+            .ok_or(Error::IniEntryNotFound)?;
+        if modifiable & stage.modify_type() == 0 {
+            return Err(Error::IniEntryNotModifiable);
+        }
+
+        // Values set at startup outlive the request, so must not use the request
+        // allocator.
+        let persistent = stage == IniStage::Startup;
+        let mut name = ZendStr::new(name, persistent);
+        let mut value = ZendStr::new(value, persistent);
+
+        let result = unsafe {
+            zend_alter_ini_entry_ex(
+                name.as_mut_ptr(),
+                value.as_mut_ptr(),
+                stage.modify_type() as _,
+                stage.stage() as _,
+                false,
+            )
+        };
+        if result != ZEND_RESULT_CODE_SUCCESS {
+            return Err(Error::IniValueRejected);
+        }
+        Ok(())
+    }
+
+    /// Restores the value of a directive to the value it had before it was
+    /// changed during the current request, equivalent to `ini_restore()`.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the directive.
+    pub fn restore(name: &str) -> Result<()> {
+        let mut name = ZendStr::new(name, false);
+        let result =
+            unsafe { zend_restore_ini_entry(name.as_mut_ptr(), ZEND_INI_STAGE_RUNTIME as _) };
+        if result != ZEND_RESULT_CODE_SUCCESS {
+            return Err(Error::IniEntryNotFound);
+        }
+        Ok(())
+    }
+
+    /// Parses an INI value as a boolean. `true`, `yes` and `on` are true in any
+    /// case, otherwise the value is true if it starts with a non-zero integer.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The value to parse.
+    pub fn parse_bool(value: &str) -> bool {
+        if ["true", "yes", "on"]
+            .iter()
+            .any(|truthy| value.eq_ignore_ascii_case(truthy))
+        {
+            return true;
+        }
+
+        // Mirrors `atoi()`, which ignores anything after the leading integer.
+        let value = value.trim_start();
+        let digits = value
+            .strip_prefix(|c| c == '+' || c == '-')
+            .unwrap_or(value);
+        digits
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .any(|c| c != '0')
+    }
+
+    /// Parses an INI value as an integer quantity, such as `128M`.
+    ///
+    /// The value may be prefixed with `0x`, `0o` or `0b`, and may be followed
+    /// by one of the multipliers `K`, `M` or `G` in either case, which
+    /// multiply the value by 1024, 1024² and 1024³ respectively. Returns
+    /// [`None`] if the value is not a valid quantity or overflows.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The value to parse.
+    pub fn parse_quantity(value: &str) -> Option<i64> {
+        let value = value.trim();
+        let (value, shift) = match value.chars().last()? {
+            'k' | 'K' => (&value[..value.len() - 1], 10),
+            'm' | 'M' => (&value[..value.len() - 1], 20),
+            'g' | 'G' => (&value[..value.len() - 1], 30),
+            _ => (value, 0),
+        };
+
+        let (negative, digits) = match value.trim_end().as_bytes().first()? {
+            b'-' => (true, &value[1..]),
+            b'+' => (false, &value[1..]),
+            _ => (false, value),
+        };
+        let digits = digits.trim_end();
+
+        let radix_prefix = digits.get(..2).map(str::to_ascii_lowercase);
+        let (digits, radix) = match radix_prefix.as_deref() {
+            Some("0x") => (&digits[2..], 16),
+            Some("0o") => (&digits[2..], 8),
+            Some("0b") => (&digits[2..], 2),
+            _ => (digits, 10),
+        };
+
+        // `from_str_radix` accepts a sign, which has already been handled.
+        if digits.starts_with(['+', '-']) {
+            return None;
+        }
+
+        let magnitude = i64::from_str_radix(digits, radix).ok()?;
+        let magnitude = magnitude.checked_mul(1 << shift)?;
+        Some(if negative { -magnitude } else { magnitude })
+    }
+
+    /// Calls the given function with the current value of a directive.
+    fn with_value<T>(name: &str, f: impl FnOnce(&str) -> T) -> Option<T> {
+        Self::with_entry(name, |entry| {
+            let value = unsafe { entry.value.as_ref() }?;
+            Some(f(value.as_str().ok()?))
+        })
+        .flatten()
+    }
+
+    /// Calls the given function with the entry of a directive.
+    fn with_entry<T>(name: &str, f: impl FnOnce(&zend_ini_entry) -> T) -> Option<T> {
+        let eg = ExecutorGlobals::get();
+        let directives = unsafe { eg.ini_directives.as_ref() }?;
+        // SAFETY: The values of the directives table are pointers to ini entries.
+        let entry = unsafe { directives.get(name)?.ptr::<zend_ini_entry>()?.as_ref() }?;
+        Some(f(entry))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Ini;
+
+    #[test]
+    fn test_parse_bool() {
+        for truthy in ["1", "On", "YES", "true", " 2", "-1", "10abc"] {
+            assert!(Ini::parse_bool(truthy), "{}", truthy);
+        }
+        for falsy in ["", "0", "off", "no", "false", "abc", "0x1"] {
+            assert!(!Ini::parse_bool(falsy), "{}", falsy);
+        }
+    }
+
+    #[test]
+    fn test_parse_quantity() {
+        assert_eq!(Ini::parse_quantity("128"), Some(128));
+        assert_eq!(Ini::parse_quantity("-1"), Some(-1));
+        assert_eq!(Ini::parse_quantity(" 8k "), Some(8 * 1024));
+        assert_eq!(Ini::parse_quantity("128M"), Some(128 * 1024 * 1024));
+        assert_eq!(Ini::parse_quantity("2g"), Some(2 * 1024 * 1024 * 1024));
+        assert_eq!(Ini::parse_quantity("0x10"), Some(16));
+        assert_eq!(Ini::parse_quantity("0b101"), Some(5));
+        assert_eq!(Ini::parse_quantity("0o17K"), Some(15 * 1024));
+        assert_eq!(Ini::parse_quantity(""), None);
+        assert_eq!(Ini::parse_quantity("M"), None);
+        assert_eq!(Ini::parse_quantity("12Q"), None);
+        assert_eq!(Ini::parse_quantity("--1"), None);
+        assert_eq!(Ini::parse_quantity("9223372036854775807G"), None);
+    }
+}
@@ -1,9 +1,24 @@
 //! Builder for creating inis and methods in PHP.
 //! See <https://www.phpinternalsbook.com/php7/extensions_design/ini_settings.html> for details.
 
-use std::{ffi::CString, os::raw::c_char, ptr};
+use std::{
+    ffi::{c_void, CString},
+    os::raw::{c_char, c_int},
+    ptr,
+};
 
-use crate::{ffi::zend_ini_entry_def, ffi::zend_register_ini_entries, flags::IniEntryPermission};
+use crate::{
+    ffi::zend_ini_entry_def,
+    ffi::{
+        zend_ini_entry, zend_register_ini_entries, zend_string, ZEND_RESULT_CODE_FAILURE,
+        ZEND_RESULT_CODE_SUCCESS,
+    },
+    flags::IniEntryPermission,
+    types::ZendStr,
+};
+
+/// A Rust callback validating a new value of an ini entry.
+type OnModify = Box<dyn Fn(&str) -> bool + Send + Sync>;
 
 /// A Zend ini entry definition.
 ///
@@ -27,6 +42,25 @@ impl IniEntryDef {
         template
     }
 
+    /// Sets a function which is called whenever the value of the entry changes,
+    /// including when the default value is set at registration. Returning
+    /// `false` rejects the new value, keeping the previous one.
+    ///
+    /// # Parameters
+    ///
+    /// * `callback` - Called with the new value of the entry.
+    pub fn on_modify<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        let callback: OnModify = Box::new(callback);
+        // The entry is never unregistered, so the callback lives for the rest of the
+        // process.
+        self.mh_arg1 = Box::into_raw(Box::new(callback)) as *mut c_void;
+        self.on_modify = Some(on_modify);
+        self
+    }
+
     /// Returns an empty ini entry def, signifying the end of a ini list.
     pub fn end() -> Self {
         Self {
@@ -56,3 +90,28 @@ impl IniEntryDef {
         unsafe { zend_register_ini_entries(entries, module_number) };
     }
 }
+
+/// Called by PHP when the value of an entry with a Rust callback changes.
+unsafe extern "C" fn on_modify(
+    _entry: *mut zend_ini_entry,
+    new_value: *mut zend_string,
+    mh_arg1: *mut c_void,
+    _mh_arg2: *mut c_void,
+    _mh_arg3: *mut c_void,
+    _stage: c_int,
+) -> c_int {
+    let callback = match (mh_arg1 as *const OnModify).as_ref() {
+        Some(callback) => callback,
+        None => return ZEND_RESULT_CODE_SUCCESS as _,
+    };
+    let value = (new_value as *const ZendStr)
+        .as_ref()
+        .and_then(|val| val.as_str().ok())
+        .unwrap_or_default();
+
+    if callback(value) {
+        ZEND_RESULT_CODE_SUCCESS as _
+    } else {
+        ZEND_RESULT_CODE_FAILURE as _
+    }
+}
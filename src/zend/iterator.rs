@@ -0,0 +1,157 @@
+//! Iterators used by `foreach` on objects of classes which provide a
+//! [`RegisteredClass::ITERATOR`].
+
+use std::{alloc::Layout, mem, os::raw::c_int, ptr};
+
+use crate::{
+    alloc::emalloc,
+    class::{NativeIter, RegisteredClass},
+    exception::PhpException,
+    ffi::{
+        zend_iterator_init, zend_object_iterator_funcs, ZEND_RESULT_CODE_FAILURE,
+        ZEND_RESULT_CODE_SUCCESS,
+    },
+    types::{ZendClassObject, ZendIterator, Zval},
+};
+
+use super::{ce, ClassEntry};
+
+/// An engine iterator which yields the elements of the Rust iterator of an
+/// object.
+#[repr(C)]
+struct NativeIterator {
+    // Must be the first field, as PHP only knows about the iterator header.
+    std: ZendIterator,
+    /// Creates the Rust iterator from the iterated object.
+    source: fn(&Zval) -> Option<NativeIter>,
+    iter: Option<NativeIter>,
+    /// The key and value of the current element.
+    current: Option<(Zval, Zval)>,
+}
+
+static FUNCS: zend_object_iterator_funcs = zend_object_iterator_funcs {
+    dtor: Some(dtor),
+    valid: Some(valid),
+    get_current_data: Some(get_current_data),
+    get_current_key: Some(get_current_key),
+    move_forward: Some(move_forward),
+    rewind: Some(rewind),
+    invalidate_current: None,
+    get_gc: None,
+};
+
+/// Creates an iterator over an object of the class `T`. Installed as the
+/// `get_iterator` function of classes which provide an iterator.
+pub(crate) unsafe extern "C" fn get_iterator<T: RegisteredClass>(
+    _: *mut ClassEntry,
+    object: *mut Zval,
+    by_ref: c_int,
+) -> *mut ZendIterator {
+    if by_ref != 0 {
+        let _ = PhpException::new(
+            "An iterator cannot be used with foreach by reference".into(),
+            0,
+            ce::error(),
+        )
+        .throw();
+        return ptr::null_mut();
+    }
+
+    let it = emalloc(Layout::new::<NativeIterator>()) as *mut NativeIterator;
+    ptr::write(
+        it,
+        NativeIterator {
+            // SAFETY: The header is a raw C type, and is initialized below.
+            std: mem::zeroed(),
+            source: source::<T>,
+            iter: None,
+            current: None,
+        },
+    );
+    zend_iterator_init(&mut (*it).std);
+    // The iterator holds a reference to the object for as long as it is alive.
+    ptr::write(&mut (*it).std.data, (*object).shallow_clone());
+    (*it).std.funcs = &FUNCS;
+    &mut (*it).std
+}
+
+fn source<T: RegisteredClass>(object: &Zval) -> Option<NativeIter> {
+    let obj = object
+        .object()
+        .and_then(|obj| ZendClassObject::<T>::upcast(obj))?;
+    Some(T::ITERATOR?(obj))
+}
+
+/// # Safety
+///
+/// The iterator must have been created by [`get_iterator`].
+unsafe fn native<'a>(iter: *mut ZendIterator) -> &'a mut NativeIterator {
+    &mut *(iter as *mut NativeIterator)
+}
+
+/// Moves to the next element of the Rust iterator, throwing an exception and
+/// ending the iteration if the element could not be converted.
+fn advance(it: &mut NativeIterator) {
+    it.current = match it.iter.as_mut().and_then(Iterator::next) {
+        Some(Ok(entry)) => Some(entry),
+        Some(Err(e)) => {
+            let _ = PhpException::from(e).throw();
+            it.iter = None;
+            None
+        }
+        None => None,
+    };
+}
+
+unsafe extern "C" fn dtor(iter: *mut ZendIterator) {
+    // The memory of the iterator is freed by PHP once this returns.
+    #[cfg(feature = "alloc-tracking")]
+    crate::alloc::tracking::release(iter.cast());
+    let it = native(iter);
+    ptr::drop_in_place(&mut it.iter);
+    ptr::drop_in_place(&mut it.current);
+    ptr::drop_in_place(&mut it.std.data);
+}
+
+unsafe extern "C" fn valid(iter: *mut ZendIterator) -> c_int {
+    if native(iter).current.is_some() {
+        ZEND_RESULT_CODE_SUCCESS
+    } else {
+        ZEND_RESULT_CODE_FAILURE
+    }
+}
+
+unsafe extern "C" fn get_current_data(iter: *mut ZendIterator) -> *mut Zval {
+    match &mut native(iter).current {
+        Some((_, value)) => value,
+        None => ptr::null_mut(),
+    }
+}
+
+unsafe extern "C" fn get_current_key(iter: *mut ZendIterator, key: *mut Zval) {
+    let key_zv = match &native(iter).current {
+        Some((key, _)) => key.shallow_clone(),
+        None => Zval::new(),
+    };
+    ptr::write(key, key_zv);
+}
+
+unsafe extern "C" fn move_forward(iter: *mut ZendIterator) {
+    advance(native(iter));
+}
+
+unsafe extern "C" fn rewind(iter: *mut ZendIterator) {
+    let it = native(iter);
+    it.current = None;
+    it.iter = (it.source)(&it.std.data);
+    if it.iter.is_none() {
+        let _ = PhpException::new(
+            "Object is not initialized and cannot be iterated".into(),
+            0,
+            ce::error(),
+        )
+        .throw();
+        return;
+    }
+    advance(it);
+}
@@ -0,0 +1,281 @@
+//! JSON encoding and decoding through PHP's JSON extension.
+//!
+//! These functions produce and accept exactly the same JSON as
+//! `json_encode()` and `json_decode()`, including the handling of
+//! `JsonSerializable` objects, the `JSON_*` options and depth limits.
+
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display},
+};
+
+use crate::{
+    boxed::ZBox,
+    error::{Error, Result},
+    exception::PhpException,
+    ffi::{
+        ext_php_rs_json_error_code, php_json_decode_ex, php_json_encode_ex,
+        php_json_error_code_PHP_JSON_ERROR_CTRL_CHAR, php_json_error_code_PHP_JSON_ERROR_DEPTH,
+        php_json_error_code_PHP_JSON_ERROR_INF_OR_NAN,
+        php_json_error_code_PHP_JSON_ERROR_INVALID_PROPERTY_NAME,
+        php_json_error_code_PHP_JSON_ERROR_NONE, php_json_error_code_PHP_JSON_ERROR_RECURSION,
+        php_json_error_code_PHP_JSON_ERROR_STATE_MISMATCH,
+        php_json_error_code_PHP_JSON_ERROR_SYNTAX,
+        php_json_error_code_PHP_JSON_ERROR_UNSUPPORTED_TYPE,
+        php_json_error_code_PHP_JSON_ERROR_UTF16, php_json_error_code_PHP_JSON_ERROR_UTF8,
+        ZEND_RESULT_CODE_SUCCESS,
+    },
+    flags::JsonFlags,
+    types::{ZendStr, Zval, ZvalWriter},
+    zend::{ce, ExecutorGlobals},
+};
+
+/// The default nesting depth of `json_encode()` and `json_decode()`.
+pub const DEFAULT_DEPTH: usize = 512;
+
+/// An error reported by PHP's JSON extension, as returned by
+/// `json_last_error()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum JsonError {
+    /// The maximum nesting depth was exceeded.
+    Depth,
+    /// The JSON is invalid or malformed.
+    StateMismatch,
+    /// A control character was found, possibly incorrectly encoded.
+    CtrlChar,
+    /// The JSON has a syntax error.
+    Syntax,
+    /// A string contains malformed UTF-8 characters.
+    Utf8,
+    /// The value to encode contains a recursive reference.
+    Recursion,
+    /// The value to encode contains `INF` or `NAN`.
+    InfOrNan,
+    /// The value to encode has a type which cannot be encoded, e.g. a
+    /// resource.
+    UnsupportedType,
+    /// A decoded object has a property name starting with a null byte.
+    InvalidPropertyName,
+    /// A unicode escape contains a single unpaired UTF-16 surrogate.
+    Utf16,
+    /// The value to encode contains a non-backed enum.
+    #[cfg(any(php81, php82))]
+    NonBackedEnum,
+    /// An error code not known to ext-php-rs.
+    Unknown(u32),
+}
+
+impl JsonError {
+    /// Returns the error with the given error code, or [`None`] for
+    /// `JSON_ERROR_NONE`.
+    ///
+    /// # Parameters
+    ///
+    /// * `code` - The error code, as returned by `json_last_error()`.
+    pub fn from_code(code: u32) -> Option<Self> {
+        #[allow(non_upper_case_globals)]
+        Some(match code {
+            php_json_error_code_PHP_JSON_ERROR_NONE => return None,
+            php_json_error_code_PHP_JSON_ERROR_DEPTH => Self::Depth,
+            php_json_error_code_PHP_JSON_ERROR_STATE_MISMATCH => Self::StateMismatch,
+            php_json_error_code_PHP_JSON_ERROR_CTRL_CHAR => Self::CtrlChar,
+            php_json_error_code_PHP_JSON_ERROR_SYNTAX => Self::Syntax,
+            php_json_error_code_PHP_JSON_ERROR_UTF8 => Self::Utf8,
+            php_json_error_code_PHP_JSON_ERROR_RECURSION => Self::Recursion,
+            php_json_error_code_PHP_JSON_ERROR_INF_OR_NAN => Self::InfOrNan,
+            php_json_error_code_PHP_JSON_ERROR_UNSUPPORTED_TYPE => Self::UnsupportedType,
+            php_json_error_code_PHP_JSON_ERROR_INVALID_PROPERTY_NAME => Self::InvalidPropertyName,
+            php_json_error_code_PHP_JSON_ERROR_UTF16 => Self::Utf16,
+            #[cfg(any(php81, php82))]
+            crate::ffi::php_json_error_code_PHP_JSON_ERROR_NON_BACKED_ENUM => Self::NonBackedEnum,
+            code => Self::Unknown(code),
+        })
+    }
+
+    /// Returns the error code, as returned by `json_last_error()`.
+    pub fn code(&self) -> u32 {
+        match self {
+            Self::Depth => php_json_error_code_PHP_JSON_ERROR_DEPTH,
+            Self::StateMismatch => php_json_error_code_PHP_JSON_ERROR_STATE_MISMATCH,
+            Self::CtrlChar => php_json_error_code_PHP_JSON_ERROR_CTRL_CHAR,
+            Self::Syntax => php_json_error_code_PHP_JSON_ERROR_SYNTAX,
+            Self::Utf8 => php_json_error_code_PHP_JSON_ERROR_UTF8,
+            Self::Recursion => php_json_error_code_PHP_JSON_ERROR_RECURSION,
+            Self::InfOrNan => php_json_error_code_PHP_JSON_ERROR_INF_OR_NAN,
+            Self::UnsupportedType => php_json_error_code_PHP_JSON_ERROR_UNSUPPORTED_TYPE,
+            Self::InvalidPropertyName => php_json_error_code_PHP_JSON_ERROR_INVALID_PROPERTY_NAME,
+            Self::Utf16 => php_json_error_code_PHP_JSON_ERROR_UTF16,
+            #[cfg(any(php81, php82))]
+            Self::NonBackedEnum => crate::ffi::php_json_error_code_PHP_JSON_ERROR_NON_BACKED_ENUM,
+            Self::Unknown(code) => *code,
+        }
+    }
+}
+
+impl Display for JsonError {
+    /// Formats the error with the message returned by `json_last_error_msg()`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Depth => write!(f, "Maximum stack depth exceeded"),
+            Self::StateMismatch => write!(f, "State mismatch (invalid or malformed JSON)"),
+            Self::CtrlChar => write!(f, "Control character error, possibly incorrectly encoded"),
+            Self::Syntax => write!(f, "Syntax error"),
+            Self::Utf8 => write!(
+                f,
+                "Malformed UTF-8 characters, possibly incorrectly encoded"
+            ),
+            Self::Recursion => write!(f, "Recursion detected"),
+            Self::InfOrNan => write!(f, "Inf and NaN cannot be JSON encoded"),
+            Self::UnsupportedType => write!(f, "Type is not supported"),
+            Self::InvalidPropertyName => write!(f, "The decoded property name is invalid"),
+            Self::Utf16 => write!(f, "Single unpaired UTF-16 surrogate in unicode escape"),
+            #[cfg(any(php81, php82))]
+            Self::NonBackedEnum => write!(f, "Non-backed enums have no default serialization"),
+            Self::Unknown(_) => write!(f, "Unknown error"),
+        }
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+impl From<JsonError> for PhpException {
+    /// Converts the error into a `JsonException`, as thrown by `json_encode()`
+    /// and `json_decode()` with `JSON_THROW_ON_ERROR`.
+    fn from(err: JsonError) -> Self {
+        Self::new(err.to_string(), err.code() as _, ce::json_exception())
+    }
+}
+
+/// Encodes a value as JSON, as `json_encode()` does.
+///
+/// Returns [`Error::Json`] if the value cannot be encoded, or
+/// [`Error::Exception`] if the `jsonSerialize()` method of an object threw an
+/// exception. With [`JsonFlags::PartialOutputOnError`], values which cannot
+/// be encoded are replaced with `null` instead.
+///
+/// # Parameters
+///
+/// * `value` - The value to encode.
+/// * `flags` - Options for encoding the value.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{flags::JsonFlags, types::Zval, zend::json};
+///
+/// fn pretty(value: &Zval) -> ext_php_rs::error::Result<String> {
+///     let json = json::encode(value, JsonFlags::PrettyPrint | JsonFlags::UnescapedSlashes)?;
+///     Ok(json.as_str()?.to_owned())
+/// }
+/// ```
+pub fn encode(value: &Zval, flags: JsonFlags) -> Result<ZBox<ZendStr>> {
+    encode_with_depth(value, flags, DEFAULT_DEPTH)
+}
+
+/// Encodes a value as JSON with the given maximum nesting depth, as
+/// `json_encode()` does.
+///
+/// See [`encode`] for the errors which are returned.
+///
+/// # Parameters
+///
+/// * `value` - The value to encode.
+/// * `flags` - Options for encoding the value.
+/// * `depth` - The maximum nesting depth of the value.
+pub fn encode_with_depth(value: &Zval, flags: JsonFlags, depth: usize) -> Result<ZBox<ZendStr>> {
+    let depth = i64::try_from(depth).map_err(|_| Error::IntegerOverflow)?;
+    let mut writer = ZvalWriter::new();
+
+    let result = unsafe {
+        php_json_encode_ex(
+            writer.as_smart_str(),
+            value as *const Zval as *mut Zval,
+            flags.bits() as _,
+            depth as _,
+        )
+    };
+
+    if let Some(e) = ExecutorGlobals::take_exception() {
+        return Err(Error::Exception(e));
+    }
+    if result != ZEND_RESULT_CODE_SUCCESS {
+        let code = unsafe { ext_php_rs_json_error_code() };
+        return Err(Error::Json(
+            JsonError::from_code(code).unwrap_or(JsonError::Unknown(code)),
+        ));
+    }
+    Ok(writer.finish())
+}
+
+/// Decodes a JSON string, as `json_decode()` does.
+///
+/// Objects are decoded into `stdClass` objects, or into arrays with
+/// [`JsonFlags::ObjectAsArray`].
+///
+/// Returns [`Error::Json`] if the string is not valid JSON. With
+/// [`JsonFlags::ThrowOnError`], a `JsonException` is returned as
+/// [`Error::Exception`] instead.
+///
+/// # Parameters
+///
+/// * `json` - The JSON string to decode.
+/// * `flags` - Options for decoding the string.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{flags::JsonFlags, zend::json};
+///
+/// fn port(config: &str) -> Option<i64> {
+///     let config = json::decode(config, JsonFlags::ObjectAsArray).ok()?;
+///     config.array()?.get("port")?.long()
+/// }
+/// ```
+pub fn decode<T: AsRef<[u8]>>(json: T, flags: JsonFlags) -> Result<Zval> {
+    decode_with_depth(json, flags, DEFAULT_DEPTH)
+}
+
+/// Decodes a JSON string with the given maximum nesting depth, as
+/// `json_decode()` does.
+///
+/// See [`decode`] for the errors which are returned. The depth must be
+/// greater than zero.
+///
+/// # Parameters
+///
+/// * `json` - The JSON string to decode.
+/// * `flags` - Options for decoding the string.
+/// * `depth` - The maximum nesting depth of the decoded value.
+pub fn decode_with_depth<T: AsRef<[u8]>>(json: T, flags: JsonFlags, depth: usize) -> Result<Zval> {
+    let json = json.as_ref();
+    if depth == 0 || i32::try_from(depth).is_err() {
+        return Err(Error::IntegerOverflow);
+    }
+    // `json_decode()` rejects empty strings before parsing them.
+    if json.is_empty() {
+        return Err(Error::Json(JsonError::Syntax));
+    }
+
+    let mut retval = Zval::new();
+    let result = unsafe {
+        php_json_decode_ex(
+            &mut retval,
+            json.as_ptr().cast(),
+            json.len(),
+            flags.bits() as _,
+            depth as _,
+        )
+    };
+
+    if let Some(e) = ExecutorGlobals::take_exception() {
+        return Err(Error::Exception(e));
+    }
+    if result != ZEND_RESULT_CODE_SUCCESS {
+        let code = unsafe { ext_php_rs_json_error_code() };
+        return Err(Error::Json(
+            JsonError::from_code(code).unwrap_or(JsonError::Unknown(code)),
+        ));
+    }
+    Ok(retval)
+}
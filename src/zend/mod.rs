@@ -1,16 +1,30 @@
 //! Types used to interact with the Zend engine.
 
 mod _type;
+mod auto_globals;
+mod cancellation;
 pub mod ce;
 mod class;
+mod constant;
 mod ex;
 mod function;
+mod gc;
 mod globals;
 mod handlers;
+pub mod headers;
+mod ini;
 mod ini_entry_def;
+mod iterator;
+pub mod json;
 mod linked_list;
 mod module;
+mod observer;
+pub mod opcache;
+mod opcode;
+pub mod random;
+mod reflection;
 mod streams;
+pub mod timezone;
 mod try_catch;
 
 use crate::{
@@ -20,23 +34,34 @@ use crate::{
 use std::ffi::CString;
 
 pub use _type::ZendType;
+pub use auto_globals::register_auto_global;
+pub use cancellation::{CancellationReason, CancellationToken};
 pub use class::ClassEntry;
+pub use constant::Constant;
 pub use ex::ExecuteData;
 pub use function::Function;
 pub use function::FunctionEntry;
+pub use gc::{CollectGarbage, GcBuffer};
 pub use globals::ExecutorGlobals;
 pub use globals::FileGlobals;
 pub use globals::ProcessGlobals;
+pub use globals::RequestBody;
 pub use globals::SapiGlobals;
 pub use globals::SapiModule;
 pub use handlers::ZendObjectHandlers;
+pub use ini::{Ini, IniStage};
 pub use ini_entry_def::IniEntryDef;
+pub(crate) use iterator::get_iterator;
 pub use linked_list::ZendLinkedList;
 pub use module::ModuleEntry;
+pub use observer::{register_fcall_observer, FcallObserver};
+pub use opcode::{remove_user_opcode_handler, set_user_opcode_handler, OpcodeAction};
+pub use reflection::{Attribute, ClassConstant, PropertyInfo};
 pub use streams::*;
 #[cfg(feature = "embed")]
 pub(crate) use try_catch::panic_wrapper;
 pub use try_catch::{bailout, try_catch, try_catch_first};
+pub(crate) use try_catch::{catch_panic, report_panic};
 
 // Used as the format string for `php_printf`.
 const FORMAT_STR: &[u8] = b"%s\0";
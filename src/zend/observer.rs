@@ -0,0 +1,163 @@
+//! Bindings to the Zend observer API, which allows extensions to be notified
+//! when functions are called and return.
+
+use parking_lot::{const_rwlock, RwLock};
+
+use crate::{
+    ffi::{zend_observer_fcall_handlers, zend_observer_fcall_register, zval},
+    types::Zval,
+};
+
+use super::{catch_panic, report_panic, ExecuteData};
+
+/// Observers registered with [`register_fcall_observer`].
+static OBSERVERS: RwLock<Vec<Box<dyn FcallObserver>>> = const_rwlock(Vec::new());
+
+/// Receives notifications when PHP functions are called and return.
+///
+/// This is the building block for profilers, tracers and other APM tools.
+/// Both user-defined and internal functions are observed.
+///
+/// Observers are shared between all requests, and in ZTS builds between all
+/// threads, so they must be [`Send`] and [`Sync`]. A panic in any of the
+/// methods is caught and reported as a PHP warning.
+///
+/// # Example
+///
+/// ```no_run
+/// # #![cfg_attr(windows, feature(abi_vectorcall))]
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use ext_php_rs::{prelude::*, zend::{ExecuteData, FcallObserver}};
+///
+/// #[derive(Default)]
+/// struct CallCounter(AtomicUsize);
+///
+/// impl FcallObserver for CallCounter {
+///     fn begin(&self, _: &ExecuteData) {
+///         self.0.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// #[php_startup]
+/// pub fn startup() {
+///     ext_php_rs::zend::register_fcall_observer(CallCounter::default());
+/// }
+/// # fn main() {}
+/// ```
+pub trait FcallObserver: Send + Sync + 'static {
+    /// Returns whether calls to the function being executed should be
+    /// observed.
+    ///
+    /// PHP calls this the first time each function is called, and caches the
+    /// result for the rest of the request. As the result is shared between all
+    /// registered observers, [`FcallObserver::begin`] and
+    /// [`FcallObserver::end`] may still be called for functions this observer
+    /// is not interested in if another observer is.
+    ///
+    /// # Parameters
+    ///
+    /// * `execute_data` - The execute data of the first call to the function.
+    fn should_observe(&self, execute_data: &ExecuteData) -> bool {
+        let _ = execute_data;
+        true
+    }
+
+    /// Called before an observed function starts executing.
+    ///
+    /// # Parameters
+    ///
+    /// * `execute_data` - The execute data of the call, containing the
+    ///   function and its arguments.
+    fn begin(&self, execute_data: &ExecuteData) {
+        let _ = execute_data;
+    }
+
+    /// Called after an observed function has finished executing, including
+    /// when it is unwound by an exception.
+    ///
+    /// # Parameters
+    ///
+    /// * `execute_data` - The execute data of the call.
+    /// * `retval` - The return value of the function, if any.
+    fn end(&self, execute_data: &ExecuteData, retval: Option<&Zval>) {
+        let _ = (execute_data, retval);
+    }
+}
+
+/// Registers an observer which is notified when functions are called and
+/// return.
+///
+/// PHP only allows observers to be registered during module startup, so this
+/// must be called from the startup function of the extension, e.g. one
+/// annotated with `#[php_startup]`. Observers registered at any other time are
+/// ignored by PHP.
+///
+/// # Parameters
+///
+/// * `observer` - The observer to register.
+pub fn register_fcall_observer<O: FcallObserver>(observer: O) {
+    let mut observers = OBSERVERS.write();
+    if observers.is_empty() {
+        unsafe { zend_observer_fcall_register(Some(observer_init)) };
+    }
+    observers.push(Box::new(observer));
+}
+
+/// Called by PHP on the first call to each function to retrieve the handlers
+/// for that function.
+extern "C" fn observer_init(execute_data: *mut ExecuteData) -> zend_observer_fcall_handlers {
+    let mut panics = Vec::new();
+    let observed = unsafe { execute_data.as_ref() }.is_some_and(|ex| {
+        OBSERVERS.read().iter().any(|observer| {
+            catch_panic(|| observer.should_observe(ex)).unwrap_or_else(|message| {
+                panics.push(message);
+                false
+            })
+        })
+    });
+    report_panics("FcallObserver::should_observe", panics);
+
+    if observed {
+        zend_observer_fcall_handlers {
+            begin: Some(observer_begin),
+            end: Some(observer_end),
+        }
+    } else {
+        zend_observer_fcall_handlers {
+            begin: None,
+            end: None,
+        }
+    }
+}
+
+extern "C" fn observer_begin(execute_data: *mut ExecuteData) {
+    if let Some(ex) = unsafe { execute_data.as_ref() } {
+        let panics = OBSERVERS
+            .read()
+            .iter()
+            .filter_map(|observer| catch_panic(|| observer.begin(ex)).err())
+            .collect();
+        report_panics("FcallObserver::begin", panics);
+    }
+}
+
+extern "C" fn observer_end(execute_data: *mut ExecuteData, retval: *mut zval) {
+    if let Some(ex) = unsafe { execute_data.as_ref() } {
+        let retval = unsafe { retval.as_ref() };
+        let panics = OBSERVERS
+            .read()
+            .iter()
+            .filter_map(|observer| catch_panic(|| observer.end(ex, retval)).err())
+            .collect();
+        report_panics("FcallObserver::end", panics);
+    }
+}
+
+/// Reports the panics of observers once the lock on the observers has been
+/// released, as the warnings may call a user error handler which is itself
+/// observed.
+fn report_panics(callback: &str, panics: Vec<String>) {
+    for message in panics {
+        report_panic(callback, &message);
+    }
+}
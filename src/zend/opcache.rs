@@ -0,0 +1,131 @@
+//! Helpers for cooperating with the OPcache extension.
+//!
+//! OPcache caches compiled scripts in shared memory. Extensions which generate
+//! or rewrite PHP files can use these functions to check whether a script is
+//! cached, and to invalidate it after it has changed.
+//!
+//! Classes registered by extensions are internal classes, which live in
+//! process memory rather than in OPcache's shared memory. Classes built with
+//! [`ClassBuilder`](crate::builders::ClassBuilder) are marked as immutable
+//! ([`ClassFlags::Immutable`](crate::flags::ClassFlags::Immutable)) once
+//! built, as are the classes OPcache stores.
+//!
+//! All functions gracefully handle OPcache not being loaded, in which case no
+//! scripts are cached.
+
+use super::Function;
+use crate::{
+    convert::IntoZvalDyn,
+    error::Result,
+    types::{ZendCallable, ZendHashTable, Zval},
+};
+
+/// Calls an OPcache function by name.
+fn call(name: &str, params: Vec<&dyn IntoZvalDyn>) -> Result<Zval> {
+    ZendCallable::try_from_name(name)?.try_call(params)
+}
+
+/// Returns whether the OPcache extension is loaded.
+///
+/// OPcache may be loaded but disabled, e.g. for the CLI SAPI. Use
+/// [`is_enabled`] to check whether scripts are actually cached.
+pub fn is_loaded() -> bool {
+    Function::try_from_function("opcache_get_status").is_some()
+}
+
+/// Returns whether OPcache is loaded and enabled for the current SAPI.
+pub fn is_enabled() -> bool {
+    status().is_some_and(|status| status.enabled)
+}
+
+/// Returns whether a script is stored in the OPcache.
+///
+/// # Parameters
+///
+/// * `path` - The path to the script.
+pub fn is_script_cached(path: &str) -> bool {
+    call("opcache_is_script_cached", vec![&path]).is_ok_and(|val| val.is_true())
+}
+
+/// Invalidates a cached script, causing it to be recompiled the next time it
+/// is included.
+///
+/// Returns `true` if the script was invalidated, or `false` if OPcache is not
+/// enabled or the script was not cached.
+///
+/// # Parameters
+///
+/// * `path` - The path to the script.
+/// * `force` - Whether to invalidate the script even if its modification time
+///   has not changed.
+pub fn invalidate(path: &str, force: bool) -> bool {
+    call("opcache_invalidate", vec![&path, &force]).is_ok_and(|val| val.is_true())
+}
+
+/// Resets the contents of the OPcache. Scripts are recompiled the next time
+/// they are included.
+///
+/// Returns `false` if OPcache is not enabled.
+pub fn reset() -> bool {
+    call("opcache_reset", vec![]).is_ok_and(|val| val.is_true())
+}
+
+/// Returns the current status of the OPcache, or [`None`] if OPcache is not
+/// loaded or is restricted by `opcache.restrict_api`.
+pub fn status() -> Option<OpcacheStatus> {
+    let status = call("opcache_get_status", vec![&false]).ok()?;
+    status.array().map(OpcacheStatus::from_array)
+}
+
+/// The status of the OPcache, as returned by `opcache_get_status()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct OpcacheStatus {
+    /// Whether OPcache is enabled.
+    pub enabled: bool,
+    /// Whether the cache has run out of memory or keys.
+    pub cache_full: bool,
+    /// Whether a restart of the cache is pending.
+    pub restart_pending: bool,
+    /// Whether the cache is currently restarting.
+    pub restart_in_progress: bool,
+    /// Shared memory used by the cache, in bytes.
+    pub used_memory: u64,
+    /// Shared memory available to the cache, in bytes.
+    pub free_memory: u64,
+    /// Shared memory occupied by invalidated scripts, in bytes.
+    pub wasted_memory: u64,
+    /// Number of scripts stored in the cache.
+    pub cached_scripts: u64,
+    /// Number of times a script was served from the cache.
+    pub hits: u64,
+    /// Number of times a script had to be compiled.
+    pub misses: u64,
+}
+
+impl OpcacheStatus {
+    fn from_array(arr: &ZendHashTable) -> Self {
+        let flag = |arr: &ZendHashTable, key| arr.get(key).is_some_and(|val| val.is_true());
+        let num = |arr: Option<&ZendHashTable>, key| {
+            arr.and_then(|arr| arr.get(key))
+                .and_then(|val| val.long())
+                .map_or(0, |val| val.max(0) as u64)
+        };
+
+        let memory = arr.get("memory_usage").and_then(|val| val.array());
+        let stats = arr.get("opcache_statistics").and_then(|val| val.array());
+
+        Self {
+            enabled: flag(arr, "opcache_enabled"),
+            cache_full: flag(arr, "cache_full"),
+            restart_pending: flag(arr, "restart_pending"),
+            restart_in_progress: flag(arr, "restart_in_progress"),
+            used_memory: num(memory, "used_memory"),
+            free_memory: num(memory, "free_memory"),
+            wasted_memory: num(memory, "wasted_memory"),
+            cached_scripts: num(stats, "num_cached_scripts"),
+            hits: num(stats, "hits"),
+            misses: num(stats, "misses"),
+        }
+    }
+}
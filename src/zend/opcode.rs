@@ -0,0 +1,168 @@
+//! Custom handlers for opcodes executed by the Zend virtual machine.
+
+use std::{collections::BTreeMap, os::raw::c_int, sync::Arc};
+
+use parking_lot::{const_rwlock, RwLock};
+
+use crate::{
+    error::{Error, Result},
+    ffi::{
+        user_opcode_handler_t, zend_get_user_opcode_handler, zend_set_user_opcode_handler,
+        ZEND_RESULT_CODE_SUCCESS, ZEND_USER_OPCODE_CONTINUE, ZEND_USER_OPCODE_DISPATCH,
+        ZEND_USER_OPCODE_DISPATCH_TO, ZEND_USER_OPCODE_ENTER, ZEND_USER_OPCODE_LEAVE,
+        ZEND_USER_OPCODE_RETURN,
+    },
+};
+
+use super::{catch_panic, report_panic, ExecuteData};
+
+/// A Rust callback handling an opcode. Handlers are reference counted so they
+/// can be called without holding the lock, as they may register or remove
+/// handlers themselves.
+type OpcodeHandler = Arc<dyn Fn(&mut ExecuteData) -> OpcodeAction + Send + Sync>;
+
+/// Registered handlers, keyed by opcode, along with the user opcode handler
+/// which was registered before them.
+static HANDLERS: RwLock<BTreeMap<u8, (OpcodeHandler, user_opcode_handler_t)>> =
+    const_rwlock(BTreeMap::new());
+
+/// Tells the virtual machine how to proceed after a user opcode handler has
+/// run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpcodeAction {
+    /// Continue execution from the current opline. The handler is responsible
+    /// for advancing the opline of the execute data, otherwise the same
+    /// opcode is handled again.
+    Continue,
+    /// Return from the function being executed.
+    Return,
+    /// Run the original handler of the opcode, as if no user handler was
+    /// registered.
+    Dispatch,
+    /// Run the original handler of another opcode on the current opline.
+    DispatchTo(u8),
+    /// Enter the execute data which has been set up by the handler.
+    Enter,
+    /// Leave the current execute data, returning to the caller.
+    Leave,
+}
+
+impl From<OpcodeAction> for c_int {
+    fn from(action: OpcodeAction) -> Self {
+        (match action {
+            OpcodeAction::Continue => ZEND_USER_OPCODE_CONTINUE,
+            OpcodeAction::Return => ZEND_USER_OPCODE_RETURN,
+            OpcodeAction::Dispatch => ZEND_USER_OPCODE_DISPATCH,
+            OpcodeAction::DispatchTo(opcode) => ZEND_USER_OPCODE_DISPATCH_TO | opcode as u32,
+            OpcodeAction::Enter => ZEND_USER_OPCODE_ENTER,
+            OpcodeAction::Leave => ZEND_USER_OPCODE_LEAVE,
+        }) as c_int
+    }
+}
+
+/// Registers a handler which is called whenever the virtual machine executes
+/// the given opcode, replacing any previous Rust handler for that opcode.
+///
+/// User opcode handlers affect every request, so they should be registered
+/// during module startup. If another extension had already registered a
+/// handler for the opcode, it is called when the Rust handler returns
+/// [`OpcodeAction::Dispatch`], allowing both extensions to coexist.
+///
+/// Opcode numbers are available as constants in the [`ffi`](crate::ffi)
+/// module, e.g. [`ZEND_ECHO`](crate::ffi::ZEND_ECHO).
+///
+/// Handlers may register or remove opcode handlers themselves, including their
+/// own. A handler which is replaced while it is running finishes running.
+///
+/// If the handler panics, the panic is reported as a PHP warning and the
+/// original handler of the opcode is run.
+///
+/// # Parameters
+///
+/// * `opcode` - The opcode to handle.
+/// * `handler` - The handler to call, which returns how execution should
+///   proceed.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{
+///     ffi::ZEND_INCLUDE_OR_EVAL,
+///     prelude::*,
+///     zend::{set_user_opcode_handler, OpcodeAction},
+/// };
+///
+/// #[php_startup]
+/// pub fn startup() {
+///     set_user_opcode_handler(ZEND_INCLUDE_OR_EVAL as u8, |_| {
+///         // Inspect the execute data and decide whether to allow the include.
+///         OpcodeAction::Dispatch
+///     })
+///     .expect("failed to register opcode handler");
+/// }
+/// # fn main() {}
+/// ```
+pub fn set_user_opcode_handler<F>(opcode: u8, handler: F) -> Result<()>
+where
+    F: Fn(&mut ExecuteData) -> OpcodeAction + Send + Sync + 'static,
+{
+    let mut handlers = HANDLERS.write();
+    let previous = match handlers.get(&opcode) {
+        Some((_, previous)) => *previous,
+        None => unsafe { zend_get_user_opcode_handler(opcode) },
+    };
+
+    if unsafe { zend_set_user_opcode_handler(opcode, Some(user_opcode_handler)) }
+        != ZEND_RESULT_CODE_SUCCESS
+    {
+        return Err(Error::OpcodeHandlerRegistrationFailure);
+    }
+
+    handlers.insert(opcode, (Arc::new(handler), previous));
+    Ok(())
+}
+
+/// Removes the Rust handler for the given opcode, restoring the user opcode
+/// handler which was registered before it, if any.
+///
+/// # Parameters
+///
+/// * `opcode` - The opcode to stop handling.
+pub fn remove_user_opcode_handler(opcode: u8) -> Result<()> {
+    let mut handlers = HANDLERS.write();
+    if let Some((_, previous)) = handlers.remove(&opcode) {
+        if unsafe { zend_set_user_opcode_handler(opcode, previous) } != ZEND_RESULT_CODE_SUCCESS {
+            return Err(Error::OpcodeHandlerRegistrationFailure);
+        }
+    }
+    Ok(())
+}
+
+/// Called by the virtual machine for every opcode which has a Rust handler.
+extern "C" fn user_opcode_handler(execute_data: *mut ExecuteData) -> c_int {
+    let ex = match unsafe { execute_data.as_mut() } {
+        Some(ex) => ex,
+        None => return OpcodeAction::Dispatch.into(),
+    };
+
+    // The lock is released before the handler is called, as the handler may
+    // register or remove handlers.
+    let (handler, previous) = match ex
+        .opcode()
+        .and_then(|opcode| HANDLERS.read().get(&opcode).cloned())
+    {
+        Some(entry) => entry,
+        None => return OpcodeAction::Dispatch.into(),
+    };
+
+    // A panicking handler must not unwind into the virtual machine, so the panic
+    // is reported and the opcode runs its original handler instead.
+    match (catch_panic(|| handler(ex)), previous) {
+        (Ok(OpcodeAction::Dispatch), Some(previous)) => unsafe { previous(execute_data) },
+        (Ok(action), _) => action.into(),
+        (Err(message), _) => {
+            report_panic("User opcode handler", &message);
+            ZEND_USER_OPCODE_DISPATCH as c_int
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! Random number generation through PHP's random extension.
+//!
+//! The functions in this module use the same cryptographically secure source
+//! of randomness as `random_bytes()` and `random_int()`, so extensions honour
+//! the way PHP was configured to gather randomness.
+//!
+//! On PHP 8.2 and later, [`Engine`] wraps `\Random\Engine` objects supplied by
+//! PHP code, such as a seeded `\Random\Engine\Mt19937` used in tests.
+
+use crate::{
+    error::{Error, Result},
+    ffi::{ext_php_rs_random_bytes, ext_php_rs_random_int},
+    types::ZendObject,
+    zend::{ClassEntry, ExecutorGlobals},
+};
+
+/// Fills a buffer with cryptographically secure random bytes, as
+/// `random_bytes()` does.
+///
+/// Returns [`Error::Exception`] if no source of randomness is available.
+///
+/// # Parameters
+///
+/// * `buf` - The buffer to fill.
+pub fn fill_bytes(buf: &mut [u8]) -> Result<()> {
+    if buf.is_empty() {
+        return Ok(());
+    }
+
+    unsafe { ext_php_rs_random_bytes(buf.as_mut_ptr().cast(), buf.len()) };
+    take_exception()
+}
+
+/// Returns a vector of cryptographically secure random bytes, as
+/// `random_bytes()` does.
+///
+/// See [`fill_bytes`] for the errors which are returned.
+///
+/// # Parameters
+///
+/// * `len` - The number of bytes to return.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::zend::random;
+///
+/// let token = random::bytes(32).unwrap();
+/// assert_eq!(token.len(), 32);
+/// ```
+pub fn bytes(len: usize) -> Result<Vec<u8>> {
+    let mut buf = vec![0; len];
+    fill_bytes(&mut buf)?;
+    Ok(buf)
+}
+
+/// Returns a cryptographically secure random integer between `min` and `max`
+/// inclusive, as `random_int()` does.
+///
+/// Returns [`Error::InvalidRange`] if `min` is greater than `max`, or
+/// [`Error::Exception`] if no source of randomness is available.
+///
+/// # Parameters
+///
+/// * `min` - The lowest value to return.
+/// * `max` - The highest value to return.
+pub fn int(min: i64, max: i64) -> Result<i64> {
+    if min > max {
+        return Err(Error::InvalidRange);
+    }
+
+    let mut value = 0;
+    unsafe { ext_php_rs_random_int(min, max, &mut value) };
+    take_exception()?;
+    Ok(value)
+}
+
+/// Returns the exception thrown by PHP when gathering randomness failed. PHP
+/// is asked to throw on every failure, so the result codes are not checked.
+fn take_exception() -> Result<()> {
+    match ExecutorGlobals::take_exception() {
+        Some(e) => Err(Error::Exception(e)),
+        None => Ok(()),
+    }
+}
+
+/// A `\Random\Engine` object, either one of the engines provided by PHP or an
+/// engine implemented in PHP code.
+///
+/// The interface was added in PHP 8.2, so no object can be wrapped on earlier
+/// versions.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{prelude::*, types::ZendObject, zend::random::Engine};
+///
+/// #[php_function]
+/// pub fn roll_die(engine: &mut ZendObject) -> PhpResult<u64> {
+///     let mut engine = Engine::new(engine).ok_or("Expected a random engine")?;
+///     Ok(engine.next_u64()? % 6 + 1)
+/// }
+/// # fn main() {}
+/// ```
+#[derive(Debug)]
+pub struct Engine<'a> {
+    object: &'a mut ZendObject,
+}
+
+impl<'a> Engine<'a> {
+    /// Wraps an object implementing `\Random\Engine`, or returns [`None`] if
+    /// the object does not implement the interface.
+    ///
+    /// # Parameters
+    ///
+    /// * `object` - The engine object.
+    pub fn new(object: &'a mut ZendObject) -> Option<Self> {
+        let ce = ClassEntry::try_find("Random\\Engine")?;
+        if !object.instance_of(ce) {
+            return None;
+        }
+        Some(Self { object })
+    }
+
+    /// Returns the bytes generated by a call to the `generate()` method of
+    /// the engine.
+    ///
+    /// Returns [`Error::BrokenRandomEngine`] if the engine did not return a
+    /// non-empty string, or [`Error::Exception`] if it threw an exception.
+    pub fn generate(&mut self) -> Result<Vec<u8>> {
+        let result = self.object.call_method_in_scope("generate", &[], None)?;
+        match result.zend_str() {
+            Some(bytes) if !bytes.is_empty() => Ok(bytes.as_bytes().to_vec()),
+            _ => Err(Error::BrokenRandomEngine),
+        }
+    }
+
+    /// Returns a random 64-bit integer built from the bytes generated by the
+    /// engine, calling `generate()` until 8 bytes were generated. The bytes
+    /// are read in little-endian order, as `\Random\Randomizer` does.
+    ///
+    /// See [`Engine::generate`] for the errors which are returned.
+    pub fn next_u64(&mut self) -> Result<u64> {
+        let mut buf = [0; 8];
+        self.fill_bytes(&mut buf)?;
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    /// Fills a buffer with bytes generated by the engine, calling
+    /// `generate()` as many times as required. Surplus bytes are discarded.
+    ///
+    /// See [`Engine::generate`] for the errors which are returned.
+    ///
+    /// # Parameters
+    ///
+    /// * `buf` - The buffer to fill.
+    pub fn fill_bytes(&mut self, buf: &mut [u8]) -> Result<()> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            let bytes = self.generate()?;
+            let len = bytes.len().min(buf.len() - filled);
+            buf[filled..filled + len].copy_from_slice(&bytes[..len]);
+            filled += len;
+        }
+        Ok(())
+    }
+
+    /// Returns the underlying engine object.
+    pub fn object(&mut self) -> &mut ZendObject {
+        self.object
+    }
+}
@@ -0,0 +1,202 @@
+//! Types describing the members of classes, used to inspect userland code
+//! from Rust.
+
+use std::{os::raw::c_char, ptr, slice};
+
+use crate::{
+    error::{Error, Result},
+    ffi::{
+        zend_attribute, zend_class_constant, zend_get_attribute_value, zend_hash_str_find_ptr_lc,
+        zend_property_info, HashTable, ZEND_RESULT_CODE_SUCCESS,
+    },
+    flags::{ConstantFlags, PropertyFlags},
+    types::{ZendHashTable, ZendStr, Zval},
+    zend::{ClassEntry, ExecutorGlobals, ZendType},
+};
+
+/// A property declared by a class, as returned by
+/// [`ClassEntry::properties`].
+pub type PropertyInfo = zend_property_info;
+
+impl PropertyInfo {
+    /// Returns the flags of the property, such as its visibility and whether
+    /// it is static.
+    pub fn flags(&self) -> PropertyFlags {
+        PropertyFlags::from_bits_truncate(self.flags)
+    }
+
+    /// Returns the class which declared the property.
+    pub fn class(&self) -> Option<&ClassEntry> {
+        unsafe { self.ce.as_ref() }
+    }
+
+    /// Returns the type of the property. Untyped properties have an empty
+    /// type.
+    pub fn ty(&self) -> &ZendType {
+        &self.type_
+    }
+
+    /// Returns the doc comment of the property, if it has one.
+    pub fn doc_comment(&self) -> Option<&ZendStr> {
+        unsafe { self.doc_comment.as_ref() }
+    }
+
+    /// Returns the attributes of the property.
+    pub fn attributes(&self) -> impl Iterator<Item = &Attribute> {
+        attributes(self.attributes)
+    }
+}
+
+/// A constant declared by a class, as returned by [`ClassEntry::constants`].
+/// Enum cases are also stored as class constants.
+pub type ClassConstant = zend_class_constant;
+
+impl ClassConstant {
+    /// Returns the value of the constant.
+    ///
+    /// Constants defined with a constant expression, such as `self::A + 1`,
+    /// hold the unevaluated expression until the constant is first accessed.
+    pub fn value(&self) -> &Zval {
+        &self.value
+    }
+
+    /// Returns the flags of the constant, such as its visibility.
+    pub fn flags(&self) -> ConstantFlags {
+        cfg_if::cfg_if! {
+            if #[cfg(any(php81, php82))] {
+                let flags = unsafe { self.value.u2.constant_flags };
+            } else {
+                let flags = unsafe { self.value.u2.access_flags };
+            }
+        }
+        ConstantFlags::from_bits_truncate(flags)
+    }
+
+    /// Returns the class which declared the constant.
+    pub fn class(&self) -> Option<&ClassEntry> {
+        unsafe { self.ce.as_ref() }
+    }
+
+    /// Returns the doc comment of the constant, if it has one.
+    pub fn doc_comment(&self) -> Option<&ZendStr> {
+        unsafe { self.doc_comment.as_ref() }
+    }
+
+    /// Returns the attributes of the constant.
+    pub fn attributes(&self) -> impl Iterator<Item = &Attribute> {
+        attributes(self.attributes)
+    }
+}
+
+/// An attribute applied to a class, function, property or constant.
+pub type Attribute = zend_attribute;
+
+impl Attribute {
+    /// Returns the name of the attribute class, as resolved when the
+    /// attribute was compiled. The class is not required to exist.
+    pub fn name(&self) -> &ZendStr {
+        unsafe { &*self.name }
+    }
+
+    /// Returns whether the attribute has the given class name, ignoring case
+    /// as PHP does.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The fully qualified name of the class, without a leading
+    ///   backslash.
+    pub fn is(&self, name: &str) -> bool {
+        unsafe { &*self.lcname }.as_bytes() == name.to_ascii_lowercase().as_bytes()
+    }
+
+    /// Returns the number of arguments passed to the attribute.
+    pub fn argument_count(&self) -> usize {
+        self.argc as _
+    }
+
+    /// Evaluates the arguments passed to the attribute, returning the value of
+    /// each argument along with its name for named arguments.
+    ///
+    /// Returns an error if evaluating a constant expression fails, e.g. when
+    /// it refers to an undefined constant.
+    ///
+    /// # Parameters
+    ///
+    /// * `scope` - The class used to resolve `self` and `static` in constant
+    ///   expressions, usually the class the attribute was applied to.
+    pub fn arguments(&self, scope: Option<&ClassEntry>) -> Result<Vec<(Option<&ZendStr>, Zval)>> {
+        // SAFETY: The arguments are allocated inline, after the attribute.
+        let args = unsafe { slice::from_raw_parts(self.args.as_ptr(), self.argument_count()) };
+        let scope = scope.map_or(ptr::null_mut(), |scope| {
+            scope as *const ClassEntry as *mut ClassEntry
+        });
+
+        args.iter()
+            .enumerate()
+            .map(|(i, arg)| {
+                let mut value = Zval::new();
+                let result = unsafe {
+                    zend_get_attribute_value(
+                        &mut value,
+                        self as *const Self as *mut Self,
+                        i as _,
+                        scope,
+                    )
+                };
+                if let Some(e) = ExecutorGlobals::take_exception() {
+                    return Err(Error::Exception(e));
+                }
+                if result != ZEND_RESULT_CODE_SUCCESS {
+                    return Err(Error::InvalidPointer);
+                }
+                Ok((unsafe { arg.name.as_ref() }, value))
+            })
+            .collect()
+    }
+}
+
+/// Returns an iterator over the attributes in an attribute table, which is
+/// null when no attributes were applied.
+///
+/// The attributes of the parameters of a function are stored in the table of
+/// the function with a non-zero offset, and are skipped.
+pub(crate) fn attributes<'a>(table: *const HashTable) -> impl Iterator<Item = &'a Attribute> {
+    unsafe { table.as_ref() }
+        .into_iter()
+        // SAFETY: Attribute tables only contain pointers to attributes.
+        .flat_map(|table| unsafe { members::<Attribute>(table) })
+        .map(|(_, attr)| attr)
+        .filter(|attr| attr.offset == 0)
+}
+
+/// Returns an iterator over a table of class members, yielding the name of
+/// each member along with the member.
+///
+/// # Safety
+///
+/// The table must only contain pointers to `T`.
+pub(crate) unsafe fn members<'a, T: 'a>(
+    table: &'a ZendHashTable,
+) -> impl Iterator<Item = (String, &'a T)> + 'a {
+    // Names which are not valid UTF-8 are converted lossily rather than skipped.
+    table.raw_elements().filter_map(|element| {
+        // SAFETY: Guaranteed by the caller.
+        let member = unsafe { element.val.ptr::<T>()?.as_ref()? };
+        let name = match element.key {
+            Some(key) => String::from_utf8_lossy(key.as_bytes()).into_owned(),
+            None => (element.h as i64).to_string(),
+        };
+        Some((name, member))
+    })
+}
+
+/// Finds a pointer in a hash table with lowercase keys, lowercasing the given
+/// key.
+///
+/// # Safety
+///
+/// The table must only contain pointers to `T`.
+pub(crate) unsafe fn find_ptr<'a, T>(table: *const HashTable, key: &str) -> Option<&'a T> {
+    let ptr = zend_hash_str_find_ptr_lc(table, key.as_ptr() as *const c_char, key.len());
+    (ptr as *const T).as_ref()
+}
@@ -1,14 +1,26 @@
-use std::ptr::{self, NonNull};
+use std::{
+    convert::TryFrom,
+    ffi::CString,
+    io::{self, Read, Seek, SeekFrom, Write},
+    ptr::{self, NonNull},
+};
 
 use crate::{
+    boxed::{ZBox, ZBoxable},
+    convert::FromZvalMut,
     error::Error,
     ffi::{
-        php_register_url_stream_wrapper, php_register_url_stream_wrapper_volatile, php_stream,
-        php_stream_context, php_stream_locate_url_wrapper, php_stream_wrapper,
-        php_stream_wrapper_ops, php_unregister_url_stream_wrapper,
-        php_unregister_url_stream_wrapper_volatile, zend_string,
+        _php_stream_flush, _php_stream_free, _php_stream_open_wrapper_ex, _php_stream_read,
+        _php_stream_seek, _php_stream_tell, _php_stream_write, php_file_le_pstream,
+        php_file_le_stream, php_register_url_stream_wrapper,
+        php_register_url_stream_wrapper_volatile, php_stream, php_stream_context,
+        php_stream_locate_url_wrapper, php_stream_wrapper, php_stream_wrapper_ops,
+        php_unregister_url_stream_wrapper, php_unregister_url_stream_wrapper_volatile,
+        zend_fetch_resource2, zend_string, PHP_STREAM_FREE_CLOSE, REPORT_ERRORS, SEEK_CUR,
+        SEEK_END, SEEK_SET,
     },
-    types::ZendStr,
+    flags::DataType,
+    types::{ZendStr, Zval},
 };
 
 pub type StreamWrapper = php_stream_wrapper;
@@ -94,8 +106,127 @@ impl StreamWrapper {
     }
 }
 
+/// A PHP stream, such as a file handle returned by `fopen()`.
+///
+/// Streams implement [`Read`], [`Write`] and [`Seek`], so they can be used
+/// with any Rust API operating on I/O objects. Functions can accept stream
+/// resources by taking a `&mut Stream` parameter.
 pub type Stream = php_stream;
 
+impl Stream {
+    /// Opens a stream through the registered stream wrappers, equivalent to
+    /// `fopen($url, $mode)`.
+    ///
+    /// Failures are reported to PHP as warnings, as with `fopen()`. The stream
+    /// is closed when the returned box is dropped.
+    ///
+    /// # Parameters
+    ///
+    /// * `url` - The path or URL to open, e.g. `php://memory`.
+    /// * `mode` - The mode to open the stream in, e.g. `rb`.
+    pub fn open(url: &str, mode: &str) -> Result<ZBox<Self>, Error> {
+        let url = CString::new(url)?;
+        let mode = CString::new(mode)?;
+
+        let stream = unsafe {
+            #[cfg(php_debug)]
+            {
+                _php_stream_open_wrapper_ex(
+                    url.as_ptr(),
+                    mode.as_ptr(),
+                    REPORT_ERRORS as _,
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    0,
+                    ptr::null(),
+                    0,
+                    ptr::null(),
+                    0,
+                )
+            }
+            #[cfg(not(php_debug))]
+            {
+                _php_stream_open_wrapper_ex(
+                    url.as_ptr(),
+                    mode.as_ptr(),
+                    REPORT_ERRORS as _,
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                )
+            }
+        };
+
+        match unsafe { stream.as_mut() } {
+            Some(stream) => Ok(unsafe { ZBox::from_raw(stream) }),
+            None => Err(Error::StreamOpenFailure),
+        }
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = unsafe { _php_stream_read(self, buf.as_mut_ptr().cast(), buf.len()) };
+        usize::try_from(read).map_err(|_| io::Error::other("Failed to read from the stream"))
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = unsafe { _php_stream_write(self, buf.as_ptr().cast(), buf.len()) };
+        usize::try_from(written).map_err(|_| io::Error::other("Failed to write to the stream"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match unsafe { _php_stream_flush(self, 0) } {
+            0 => Ok(()),
+            _ => Err(io::Error::other("Failed to flush the stream")),
+        }
+    }
+}
+
+impl Seek for Stream {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (offset, whence) = match pos {
+            SeekFrom::Start(offset) => (
+                i64::try_from(offset).map_err(|_| io::Error::other("Offset is too large"))?,
+                SEEK_SET,
+            ),
+            SeekFrom::End(offset) => (offset, SEEK_END),
+            SeekFrom::Current(offset) => (offset, SEEK_CUR),
+        };
+
+        if unsafe { _php_stream_seek(self, offset as _, whence as _) } != 0 {
+            return Err(io::Error::other("Failed to seek the stream"));
+        }
+
+        let pos = unsafe { _php_stream_tell(self) };
+        u64::try_from(pos).map_err(|_| io::Error::other("Failed to seek the stream"))
+    }
+}
+
+unsafe impl ZBoxable for Stream {
+    fn free(&mut self) {
+        unsafe { _php_stream_free(self, PHP_STREAM_FREE_CLOSE as _) };
+    }
+}
+
+impl<'a> FromZvalMut<'a> for &'a mut Stream {
+    const TYPE: DataType = DataType::Resource;
+
+    fn from_zval_mut(zval: &'a mut Zval) -> Option<Self> {
+        let res = zval.resource()?;
+        unsafe {
+            let stream = zend_fetch_resource2(
+                res,
+                ptr::null(),
+                php_file_le_stream(),
+                php_file_le_pstream(),
+            );
+            (stream as *mut Stream).as_mut()
+        }
+    }
+}
+
 pub type StreamWrapperOps = php_stream_wrapper_ops;
 
 impl StreamWrapperOps {}
@@ -0,0 +1,171 @@
+//! Time zones resolved through PHP's date extension.
+//!
+//! Time zones are looked up in the timezone database PHP was built with (or
+//! the one provided by the `timezonedb` extension), and the default time zone
+//! follows `date.timezone` and `date_default_timezone_set()`. Date logic in
+//! Rust therefore agrees with the interpreter, rather than with the tzdata
+//! bundled by a Rust crate or installed on the system.
+
+use crate::{
+    boxed::ZBox,
+    convert::IntoZval,
+    error::{Error, Result},
+    rc::PhpRc,
+    types::{ZendCallable, ZendObject, Zval},
+    zend::ClassEntry,
+};
+
+/// Returns the name of the default time zone, as returned by
+/// `date_default_timezone_get()`.
+pub fn default_name() -> Result<String> {
+    call("date_default_timezone_get")?
+        .string()
+        .ok_or(Error::Callable)
+}
+
+/// Returns the identifiers of all time zones in the timezone database, as
+/// returned by `timezone_identifiers_list()`.
+pub fn identifiers() -> Result<Vec<String>> {
+    let list = call("timezone_identifiers_list")?;
+    let list = list.array().ok_or(Error::Callable)?;
+    Ok(list.values().filter_map(|name| name.string()).collect())
+}
+
+/// Returns the version of the timezone database, as returned by
+/// `timezone_version_get()`.
+pub fn database_version() -> Result<String> {
+    call("timezone_version_get")?
+        .string()
+        .ok_or(Error::Callable)
+}
+
+/// Calls a function of the date extension without arguments.
+fn call(name: &str) -> Result<Zval> {
+    ZendCallable::try_from_name(name)?.try_call(vec![])
+}
+
+/// The offset of a time zone from UTC at a point in time.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Offset {
+    /// The offset from UTC in seconds, positive east of UTC.
+    pub seconds: i32,
+    /// Whether daylight saving time is in effect.
+    pub is_dst: bool,
+    /// The abbreviation of the time zone, e.g. `CEST`, or the offset when
+    /// the time zone has no abbreviation, e.g. `+03`.
+    pub abbreviation: String,
+}
+
+/// A `DateTimeZone` object.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::zend::timezone::TimeZone;
+///
+/// let mut tz = TimeZone::new("Europe/Amsterdam").unwrap();
+/// let offset = tz.offset_at(1_700_000_000).unwrap();
+/// assert_eq!(offset.seconds, 3600);
+/// assert_eq!(offset.abbreviation, "CET");
+/// ```
+#[derive(Debug)]
+pub struct TimeZone {
+    object: ZBox<ZendObject>,
+}
+
+impl TimeZone {
+    /// Creates a time zone from an identifier such as `Europe/Amsterdam`, an
+    /// abbreviation such as `EST` or an offset such as `+02:00`, as the
+    /// `DateTimeZone` constructor does.
+    ///
+    /// Returns [`Error::Exception`] if the time zone is unknown.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the time zone.
+    pub fn new(name: &str) -> Result<Self> {
+        let object = date_class("DateTimeZone")?.instantiate(vec![&name])?;
+        Ok(Self { object })
+    }
+
+    /// Returns the default time zone of the current request.
+    pub fn current() -> Result<Self> {
+        Self::new(&default_name()?)
+    }
+
+    /// Wraps a `DateTimeZone` object, e.g. one passed to a function by PHP
+    /// code, or returns [`None`] if the object is of another class. A
+    /// reference to the object is held by the time zone.
+    ///
+    /// # Parameters
+    ///
+    /// * `object` - The `DateTimeZone` object.
+    pub fn from_object(object: &mut ZendObject) -> Option<Self> {
+        if !object.instance_of(ClassEntry::try_find("DateTimeZone")?) {
+            return None;
+        }
+
+        object.inc_count();
+        // SAFETY: The reference added above is released when the box is
+        // dropped.
+        let object = unsafe { ZBox::from_raw(object as *mut ZendObject) };
+        Some(Self { object })
+    }
+
+    /// Returns the name of the time zone, as returned by
+    /// `DateTimeZone::getName()`.
+    pub fn name(&mut self) -> Result<String> {
+        self.object
+            .call_method_in_scope("getName", &[], None)?
+            .string()
+            .ok_or(Error::Callable)
+    }
+
+    /// Returns the offset of the time zone from UTC at the given point in
+    /// time, taking daylight saving time and historical changes into
+    /// account.
+    ///
+    /// Returns [`Error::InvalidTimeZoneOffset`] if the offset returned by the
+    /// date extension could not be parsed.
+    ///
+    /// # Parameters
+    ///
+    /// * `timestamp` - The point in time, as a Unix timestamp.
+    pub fn offset_at(&mut self, timestamp: i64) -> Result<Offset> {
+        let mut date =
+            date_class("DateTimeImmutable")?.instantiate(vec![&format!("@{}", timestamp)])?;
+        let mut tz = Zval::new();
+        tz.set_object(&mut self.object);
+        let mut date = date.call_method_in_scope("setTimezone", &[tz], None)?;
+        let formatted = date
+            .object_mut()
+            .ok_or(Error::Object)?
+            .call_method_in_scope("format", &["Z I T".into_zval(false)?], None)?;
+        let formatted = formatted
+            .string()
+            .ok_or_else(|| Error::ZvalConversion(formatted.get_type()))?;
+
+        let mut parts = formatted.splitn(3, ' ');
+        let seconds = parts.next().and_then(|s| s.parse().ok());
+        let is_dst = parts.next().map(|s| s == "1");
+        let abbreviation = parts.next().map(String::from);
+        match (seconds, is_dst, abbreviation) {
+            (Some(seconds), Some(is_dst), Some(abbreviation)) => Ok(Offset {
+                seconds,
+                is_dst,
+                abbreviation,
+            }),
+            _ => Err(Error::InvalidTimeZoneOffset(formatted)),
+        }
+    }
+
+    /// Returns the underlying `DateTimeZone` object.
+    pub fn into_object(self) -> ZBox<ZendObject> {
+        self.object
+    }
+}
+
+/// Returns a class of the date extension, which is always loaded.
+fn date_class(name: &str) -> Result<&'static ClassEntry> {
+    ClassEntry::try_find(name).ok_or_else(|| Error::ClassNotFound(name.into()))
+}
@@ -1,8 +1,10 @@
+use crate::error::php_error;
 use crate::ffi::{
     ext_php_rs_zend_bailout, ext_php_rs_zend_first_try_catch, ext_php_rs_zend_try_catch,
 };
+use crate::flags::ErrorType;
 use std::ffi::c_void;
-use std::panic::{catch_unwind, resume_unwind, RefUnwindSafe};
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe, RefUnwindSafe};
 use std::ptr::null_mut;
 
 #[derive(Debug)]
@@ -18,6 +20,35 @@ pub(crate) unsafe extern "C" fn panic_wrapper<R, F: FnMut() -> R + RefUnwindSafe
     Box::into_raw(Box::new(panic)) as *mut c_void
 }
 
+/// Calls `func` from a callback invoked by the engine, catching any panic as
+/// unwinding into C is undefined behaviour. Returns the message of the panic if
+/// one occurred, which should be passed to [`report_panic`].
+pub(crate) fn catch_panic<R>(func: impl FnOnce() -> R) -> Result<R, String> {
+    catch_unwind(AssertUnwindSafe(func)).map_err(|panic| {
+        panic
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".into())
+    })
+}
+
+/// Reports a panic caught by [`catch_panic`] as a PHP warning.
+///
+/// The warning may call a user error handler, so no locks which the handler
+/// could need must be held.
+///
+/// # Parameters
+///
+/// * `callback` - Description of the callback which panicked.
+/// * `message` - The message of the panic.
+pub(crate) fn report_panic(callback: &str, message: &str) {
+    php_error(
+        ErrorType::Warning,
+        &format!("{callback} panicked: {message}"),
+    );
+}
+
 /// PHP propose a try catch mechanism in C using setjmp and longjmp (bailout)
 /// It store the arg of setjmp into the bailout field of the global executor
 /// If a bailout is triggered, the executor will jump to the setjmp and restore
@@ -0,0 +1,48 @@
+#![cfg(feature = "alloc-tracking")]
+extern crate ext_php_rs;
+
+use std::alloc::Layout;
+
+use ext_php_rs::alloc::{efree, emalloc, tracking};
+use ext_php_rs::test;
+
+#[test]
+fn test_alloc_tracking() {
+    test::run(|| {
+        tracking::reset();
+
+        let line = line!() + 1;
+        let ptr = emalloc(Layout::new::<[u8; 32]>());
+        let outstanding = tracking::outstanding();
+        assert_eq!(outstanding.len(), 1);
+        assert_eq!(outstanding[0].size, 32);
+        assert_eq!(outstanding[0].location.file(), file!());
+        assert_eq!(outstanding[0].location.line(), line);
+
+        unsafe { efree(ptr) };
+        assert!(tracking::outstanding().is_empty());
+        assert_eq!(
+            tracking::stats(),
+            tracking::Stats {
+                allocations: 1,
+                frees: 1
+            }
+        );
+    });
+}
+
+#[test]
+fn test_alloc_tracking_report() {
+    test::run(|| {
+        tracking::reset();
+
+        let ptr = emalloc(Layout::new::<u64>());
+        assert!(tracking::report());
+        assert!(tracking::outstanding().is_empty());
+        assert!(!tracking::report());
+
+        // Untracked frees are ignored.
+        unsafe { efree(ptr) };
+        assert_eq!(tracking::stats().frees, 0);
+    });
+}
@@ -0,0 +1,91 @@
+#![cfg_attr(windows, feature(abi_vectorcall))]
+extern crate ext_php_rs;
+
+use ext_php_rs::args::Arg;
+use ext_php_rs::flags::DataType;
+use ext_php_rs::prelude::*;
+use ext_php_rs::test::{self, CallFrame};
+use ext_php_rs::types::Zval;
+
+#[test]
+fn test_call_function() {
+    assert_eq!(
+        test::call_function::<i64>(_internal_php_add, &[&1i64, &2i64]),
+        Ok(3)
+    );
+}
+
+#[test]
+fn test_call_function_string() {
+    assert_eq!(
+        test::call_function::<String>(_internal_php_hello_world, &[&"foo"]),
+        Ok("Hello, foo!".into())
+    );
+}
+
+#[test]
+fn test_call_function_argument_count() {
+    let error = test::call_function::<i64>(_internal_php_add, &[&1i64]).unwrap_err();
+
+    assert_eq!(error.class, "ArgumentCountError");
+    assert_eq!(error.message, "expects exactly 2 arguments, 1 given");
+}
+
+#[test]
+fn test_call_function_invalid_argument() {
+    let error = test::call_function::<i64>(_internal_php_add, &[&1i64, &vec![1i64]]).unwrap_err();
+
+    assert_eq!(error.class, "Exception");
+    assert_eq!(error.message, "Invalid value given for argument `b`.");
+}
+
+#[test]
+fn test_call_function_exception() {
+    assert_eq!(
+        test::call_function::<()>(_internal_php_check, &[&true]),
+        Ok(())
+    );
+
+    let error = test::call_function::<()>(_internal_php_check, &[&false]).unwrap_err();
+    assert_eq!(error.message, "Check failed");
+}
+
+#[test]
+fn test_call_frame() {
+    test::run(|| {
+        let mut value = Zval::new();
+        value.set_long(42);
+
+        let mut frame = CallFrame::new(vec![value]);
+        assert_eq!(frame.execute_data().num_args(), 1);
+        assert!(frame.execute_data().get_self().is_none());
+
+        let mut arg = Arg::new("value", DataType::Long);
+        assert!(frame.execute_data().parser().arg(&mut arg).parse().is_ok());
+        assert_eq!(arg.val::<i64>(), Some(42));
+    });
+}
+
+#[php_function]
+pub fn add(a: i64, b: i64) -> i64 {
+    a + b
+}
+
+#[php_function]
+pub fn hello_world(name: String) -> String {
+    format!("Hello, {}!", name)
+}
+
+#[php_function]
+pub fn check(ok: bool) -> PhpResult<()> {
+    if ok {
+        Ok(())
+    } else {
+        Err("Check failed".into())
+    }
+}
+
+#[php_module]
+pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+    module
+}
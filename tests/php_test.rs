@@ -0,0 +1,106 @@
+#![cfg_attr(windows, feature(abi_vectorcall))]
+#![cfg(feature = "embed")]
+extern crate ext_php_rs;
+
+use ext_php_rs::embed::Embed;
+use ext_php_rs::prelude::*;
+use ext_php_rs::types::ZendCallable;
+use ext_php_rs::zend::bailout;
+
+#[php_test]
+fn test_function() {
+    let result = Embed::eval("hello_world('foo');").unwrap();
+
+    assert_eq!(result.string().unwrap(), "Hello, foo!");
+}
+
+#[php_test]
+fn test_callable() {
+    let func = ZendCallable::try_from_name("hello_world").unwrap();
+    let result = func.try_call(vec![&"bar"]).unwrap();
+
+    assert_eq!(result.string().unwrap(), "Hello, bar!");
+}
+
+#[php_test]
+fn test_request_isolation() {
+    let _ = Embed::eval("$GLOBALS['isolated'] ??= 0; $GLOBALS['isolated']++;");
+    let result = Embed::eval("$GLOBALS['isolated'];").unwrap();
+
+    assert_eq!(result.long(), Some(1));
+}
+
+#[php_test]
+fn test_request_isolation_again() {
+    let _ = Embed::eval("$GLOBALS['isolated'] ??= 0; $GLOBALS['isolated']++;");
+    let result = Embed::eval("$GLOBALS['isolated'];").unwrap();
+
+    assert_eq!(result.long(), Some(1));
+}
+
+#[php_test]
+fn test_result() -> Result<(), String> {
+    let result = Embed::eval("hello_world('baz');").map_err(|e| format!("{:?}", e))?;
+
+    assert_eq!(result.string().unwrap(), "Hello, baz!");
+    Ok(())
+}
+
+#[php_test]
+#[should_panic]
+fn test_panic() {
+    panic!("test panic");
+}
+
+#[php_test]
+#[should_panic]
+fn test_bailout() {
+    unsafe { bailout() };
+}
+
+#[test]
+fn test_php_assert() {
+    php_assert!(get_module, "assert(hello_world('foo') === 'Hello, foo!');");
+    php_assert!(
+        r#"
+        $greetings = array_map('hello_world', ['foo', 'bar']);
+        assert($greetings === ['Hello, foo!', 'Hello, bar!']);
+        "#
+    );
+}
+
+#[test]
+#[should_panic(expected = "Uncaught AssertionError")]
+fn test_php_assert_failure() {
+    php_assert!("assert(hello_world('foo') === 'Hello, bar!');");
+}
+
+#[test]
+#[should_panic(expected = "Uncaught Exception: Failed")]
+fn test_php_assert_exception() {
+    php_assert!("echo 'output'; throw new Exception('Failed');");
+}
+
+#[test]
+fn test_embed_run() {
+    Embed::run(|| {
+        let result = Embed::eval("$foo = 'foo';");
+
+        assert!(result.is_ok());
+    });
+}
+
+/// Gives you a nice greeting!
+///
+/// @param string $name Your name.
+///
+/// @return string Nice greeting!
+#[php_function]
+pub fn hello_world(name: String) -> String {
+    format!("Hello, {}!", name)
+}
+
+#[php_module]
+pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+    module
+}
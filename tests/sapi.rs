@@ -2,7 +2,7 @@
 #![cfg(feature = "embed")]
 extern crate ext_php_rs;
 
-use ext_php_rs::builders::SapiBuilder;
+use ext_php_rs::builders::{SapiBuilder, SapiHeaderOp};
 use ext_php_rs::embed::{ext_php_rs_sapi_startup, Embed};
 use ext_php_rs::ffi::{
     php_module_shutdown, php_module_startup, php_request_shutdown, php_request_startup,
@@ -11,8 +11,11 @@ use ext_php_rs::ffi::{
 use ext_php_rs::prelude::*;
 use ext_php_rs::zend::try_catch_first;
 use std::ffi::c_char;
+use std::sync::Mutex;
 
 static mut LAST_OUTPUT: String = String::new();
+static HEADERS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+static LOGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
 
 extern "C" fn output_tester(str: *const c_char, str_length: usize) -> usize {
     let char = unsafe { std::slice::from_raw_parts(str as *const u8, str_length) };
@@ -30,7 +33,20 @@ extern "C" fn output_tester(str: *const c_char, str_length: usize) -> usize {
 #[test]
 fn test_sapi() {
     let mut builder = SapiBuilder::new("test", "Test");
-    builder = builder.ub_write_function(output_tester);
+    builder = builder
+        .ub_write_function(output_tester)
+        .header_handler(|header, op| {
+            if op == SapiHeaderOp::Replace {
+                HEADERS
+                    .lock()
+                    .unwrap()
+                    .push(String::from_utf8_lossy(header).into_owned());
+            }
+            true
+        })
+        .read_cookies(|| Some("flavour=oatmeal".into()))
+        .log_message(|message, _| LOGS.lock().unwrap().push(message.into()))
+        .ini_entries("precision=5");
 
     let sapi = builder.build().unwrap().into_raw();
     let module = get_module();
@@ -67,6 +83,24 @@ fn test_sapi() {
         let result = Embed::eval("var_dump($foo);");
 
         assert!(result.is_ok());
+
+        let result = Embed::eval("ini_get('precision');");
+
+        assert_eq!(result.unwrap().string().unwrap(), "5");
+
+        let result = Embed::eval("$_COOKIE['flavour'];");
+
+        assert_eq!(result.unwrap().string().unwrap(), "oatmeal");
+
+        let result = Embed::eval("header('X-Test: 1'); error_log('logged');");
+
+        assert!(result.is_ok());
+        assert_eq!(*HEADERS.lock().unwrap(), ["X-Test: 1"]);
+        assert!(LOGS
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|log| log.contains("logged")));
     });
 
     unsafe {
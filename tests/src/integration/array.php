@@ -11,6 +11,108 @@ assert(in_array('a', $array));
 assert(in_array('b', $array));
 assert(in_array('c', $array));
 
+// Tests packed numeric arrays
+assert(test_array_packed([1, 2, 3], [0.5, 1.5, 2.5], [true, false, true]) === [0.5, 0.0, 7.5]);
+assert(test_array_packed([], [], []) === []);
+$holes = [1, 2, 3];
+unset($holes[1]);
+assert(test_array_packed($holes, [2.0, 4.0], [true, true]) === [2.0, 12.0]);
+assert(test_array_packed(['a' => 2], [1.5], [true]) === [3.0]);
+assert_exception_thrown(fn () => test_array_packed([1, 'a'], [1.0, 2.0], [true, true]));
+$packed = test_array_packed([1, 2], [1.0, 1.0], [true, true]);
+$packed[] = 3.0;
+assert($packed === [1.0, 2.0, 3.0]);
+
+// Tests reserving and compacting arrays
+assert(test_array_capacity(1000) === [1024, 8]);
+$holes = ['a' => 1, 'b' => 2, 3, 4];
+unset($holes['a'], $holes[0]);
+assert(test_array_compact($holes, 100) === ['b' => 2, 1 => 4]);
+next($holes);
+assert(current(test_array_compact($holes, 100)) === 4);
+assert(test_array_compact(["\xff" => 1, 'a' => 2], 10) === ["\xff" => 1, 'a' => 2]);
+$list = test_array_compact([1, 2, 3], 100);
+assert($list === [1, 2, 3]);
+assert(array_is_list($list));
+$list[] = 4;
+assert($list === [1, 2, 3, 4]);
+
+// Tests building lists in one pass
+$list = test_array_batch([1, 2], [3]);
+assert($list === [1, 2, 3]);
+$list[] = 4;
+assert($list === [1, 2, 3, 4]);
+assert(test_array_list(20) === [0, 2, 4, 6, 8, 10, 12, 14, 16, 18]);
+assert(test_array_list(0) === []);
+$list = test_array([]);
+$list[] = 'a';
+assert($list === ['a']);
+
+// Tests nested array paths
+$config = ['db' => ['host' => 'a', 'servers' => ['x', 'y']]];
+$updated = test_array_path($config, 'b');
+assert($updated['db']['host'] === 'b');
+assert($updated['db']['servers'] === ['b', 'y']);
+assert($updated['db']['previous'] === ['host' => 'a']);
+assert($config['db'] === ['host' => 'a', 'servers' => ['x', 'y']]);
+assert(test_array_path([], 'b') === ['db' => ['host' => 'b', 'servers' => ['b'], 'previous' => ['host' => '']]]);
+assert_exception_thrown(fn () => test_array_path(['db' => 'a'], 'b'));
+try {
+    test_array_empty_path();
+    assert(false);
+} catch (Exception $e) {
+    assert($e->getMessage() === 'The path to insert the value at is empty');
+}
+
+// Tests merging arrays
+$a = ['x' => 1, 'y' => ['a'], 5 => 'five', 'z' => 2];
+$b = ['y' => ['b'], 'x' => 3, 7 => 'seven'];
+[$merged, $mergedRecursive, $diff] = test_array_merge($a, $b);
+assert($merged === array_merge($a, $b));
+assert($mergedRecursive === array_merge_recursive($a, $b));
+assert($diff === array_diff_key($a, $b));
+assert($diff === [5 => 'five', 'z' => 2]);
+assert($a === ['x' => 1, 'y' => ['a'], 5 => 'five', 'z' => 2]);
+assert(test_array_merge(["\xff" => 1, 'a' => 2], ['a' => 3])[2] === ["\xff" => 1]);
+
+// Tests extracting keys and values
+assert(test_array_keys([5 => 'a', 'b' => 'c', 'd']) === ['5', 'b', '6']);
+assert(test_array_keys(["\xff" => 1]) === ["\u{FFFD}"]);
+assert(test_array_values(['a' => 1, 'b' => 2]) === [1, 2]);
+try {
+    test_array_values([1, 2, 'three']);
+    assert(false);
+} catch (Exception $e) {
+    assert($e->getMessage() === 'Invalid value at position 2');
+}
+
+// Tests converting arrays into maps
+assert(test_array_options(['timeout' => 30, 'retries' => 3]) == ['retries' => 3, 'timeout' => 30]);
+assert(test_array_index_sum([2 => 3, 4 => 5]) === 26);
+foreach ([
+    [fn () => test_array_options(['timeout' => 30, 'retries' => 'three']), 'Invalid option retries'],
+    [fn () => test_array_index_sum([2 => 3, 'a' => 1]), 'Invalid index a'],
+    [fn () => test_array_options(["\xff" => 1]), "Invalid option \u{FFFD}"],
+] as [$callback, $message]) {
+    try {
+        $callback();
+        assert(false);
+    } catch (Exception $e) {
+        assert($e->getMessage() === $message);
+    }
+}
+
+// Tests arrays are separated before being modified
+$original = ['a', 'b'];
+$separated = test_array_separate($original);
+assert($separated === ['a', 'b', 'separated']);
+assert($original === ['a', 'b']);
+assert(test_array_separate(['a']) === ['a', 'separated']);
+
+// Tests indexing arrays
+assert(test_array_index(['https', 'host' => 'localhost', 'port' => 8080]) === 'https://localhost:8080');
+assert(test_array_index(['host' => 'localhost', 'port' => 'none']) === '://localhost:0');
+
 // Tests associative arrays
 $assoc = test_array_assoc([
     'a' => '1',
@@ -24,3 +126,11 @@ assert(array_key_exists('c', $assoc));
 assert(in_array('1', $assoc));
 assert(in_array('2', $assoc));
 assert(in_array('3', $assoc));
+
+test_persistent_cache_set('a', ['x', 'y']);
+test_persistent_cache_set('a', ['z']);
+assert(test_persistent_cache_get('a') === ['z']);
+assert(test_persistent_cache_get('b') === null);
+test_persistent_cache_set_zval('b', 1);
+assert_exception_thrown(fn () => test_persistent_cache_set_zval('b', ['x']));
+assert_exception_thrown(fn () => test_persistent_cache_set_zval('b', new stdClass()));
@@ -0,0 +1,37 @@
+<?php
+
+require('_utils.php');
+
+$loaded = [];
+spl_autoload_register(function (string $class) use (&$loaded) {
+    $loaded[] = $class;
+    if ($class === 'App\Service') {
+        eval('namespace App; class Service {}');
+    } elseif ($class === 'App\Other') {
+        eval('namespace App; class Other {}');
+    } elseif ($class === 'App\Broken') {
+        throw new RuntimeException('Cannot load ' . $class);
+    }
+});
+
+// Tests classes are loaded on demand
+assert(!class_exists('App\Service', false));
+assert(test_find_or_autoload('\App\Service') === 'App\Service');
+assert($loaded === ['App\Service']);
+assert(class_exists('App\Service', false));
+assert(test_find_or_autoload('app\service') === 'App\Service');
+assert($loaded === ['App\Service']);
+
+// Tests loaded classes are found without autoloading
+assert(test_find_or_autoload('ArrayObject') === 'ArrayObject');
+assert($loaded === ['App\Service']);
+
+// Tests missing classes and exceptions thrown by autoloaders
+assert_exception_thrown(fn () => test_find_or_autoload('App\Missing'));
+assert_exception_thrown(fn () => test_find_or_autoload('App\Broken'));
+assert($loaded === ['App\Service', 'App\Missing', 'App\Broken']);
+
+// Tests `try_find` also runs the autoloaders
+assert(test_try_find('App\Other') === 'App\Other');
+assert(test_try_find('App\Missing') === null);
+assert($loaded === ['App\Service', 'App\Missing', 'App\Broken', 'App\Other', 'App\Missing']);
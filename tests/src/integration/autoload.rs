@@ -0,0 +1,4 @@
+#[test]
+fn autoload_works() {
+    crate::integration::run_php("autoload.php");
+}
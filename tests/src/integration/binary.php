@@ -10,4 +10,19 @@ assert(in_array(1, $result));
 assert(in_array(2, $result));
 assert(in_array(3, $result));
 assert(in_array(4, $result));
-assert(in_array(5, $result));
\ No newline at end of file
+assert(in_array(5, $result));
+
+$bytes = "\xff\x00\xfe";
+assert(test_bytes($bytes) === $bytes);
+assert(test_bytes_len($bytes) === 3);
+
+// Tests the packer and unpacker are compatible with pack() and unpack()
+$packed = test_pack(513, 70000, 'ab', 1.5);
+assert($packed === pack('nVA4x2E', 513, 70000, 'ab', 1.5));
+assert(unpack('nkind/Vid/A4tag/x2/Eratio', $packed) === ['kind' => 513, 'id' => 70000, 'tag' => 'ab', 'ratio' => 1.5]);
+assert(test_pack(1, 2, 'truncated', 0.0) === pack('nVA4x2E', 1, 2, 'trun', 0.0));
+assert(test_unpack(pack('nVa4x2E', 7, 42, 'tag', -2.25)) === [7.0, 42.0, -2.25]);
+assert_exception_thrown(fn () => test_unpack(pack('nV', 7, 42)));
+$padded = "ab\0\0" . "cd \t\0\n";
+assert(test_unpack_trimmed($padded) === array_values(unpack('a4a/A6b', $padded)));
+assert(test_unpack_trimmed($padded) === ["ab\0\0", 'cd']);
@@ -1,4 +1,4 @@
 #[test]
 fn bool_works() {
-    assert!(crate::integration::run_php("bool.php"));
+    crate::integration::run_php("bool.php");
 }
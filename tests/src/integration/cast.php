@@ -0,0 +1,14 @@
+<?php
+
+require('_utils.php');
+
+$five = new TestCast(5);
+$zero = new TestCast(0);
+
+assert((int) $five === 5);
+assert((string) $five === 'value: 5');
+assert("$five" === 'value: 5');
+assert((bool) $five === true);
+assert((bool) $zero === false);
+assert(!$zero);
+assert($five + 1 === 6);
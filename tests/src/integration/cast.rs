@@ -0,0 +1,4 @@
+#[test]
+fn cast_works() {
+    crate::integration::run_php("cast.php");
+}
@@ -0,0 +1,43 @@
+<?php
+
+require('_utils.php');
+
+// Unbounded channel
+$channel = new ExtPhpRs\Channel();
+assert($channel instanceof Countable);
+
+$message = ['a' => 1, 'b' => [true, null, 1.5, "str\0ing"], 10 => 'ten'];
+$channel->send($message);
+assert(count($channel) === 1);
+assert($channel->recv() === $message);
+
+// Non-blocking receive
+assert($channel->tryRecv() === null);
+assert($channel->recvTimeout(0.01) === null);
+
+// Bounded channel
+$bounded = new ExtPhpRs\Channel(1);
+assert($bounded->trySend('first') === true);
+assert($bounded->trySend('second') === false);
+assert($bounded->tryRecv() === 'first');
+assert_exception_thrown(fn () => new ExtPhpRs\Channel(-1));
+
+// Messages sent from a Rust thread
+test_channel_produce($channel, 3);
+assert($channel->recv() === 0);
+assert($channel->recv() === 1);
+assert($channel->recv() === 2);
+
+// Objects cannot be sent
+assert_exception_thrown(fn () => $channel->send(new stdClass));
+assert_exception_thrown(fn () => $channel->send([new stdClass]));
+
+// Recursive arrays cannot be sent
+$recursive = [1];
+$recursive[] = &$recursive;
+assert_exception_thrown(fn () => $channel->send($recursive));
+assert(count($channel) === 0);
+
+// A timeout of zero does not wait
+assert($channel->recvTimeout(0) === null);
+assert_exception_thrown(fn () => $channel->recvTimeout(-1));
@@ -0,0 +1,4 @@
+#[test]
+fn channel_works() {
+    crate::integration::run_php("channel.php");
+}
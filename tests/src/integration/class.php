@@ -6,6 +6,13 @@ require('_utils.php');
 $class = test_class('lorem ipsum', 2022);
 assert($class instanceof TestClass);
 
+// Tests array constants
+assert(TestClass::TAGS === ['lorem', 'ipsum']);
+assert(TestClass::MATRIX[1][0] === 3);
+$tags = TestClass::TAGS;
+$tags[] = 'dolor';
+assert(count(TestClass::TAGS) === 2);
+
 // Tests getter/setter
 assert($class->getString() === 'lorem ipsum');
 $class->setString('dolor et');
@@ -19,3 +26,192 @@ assert($class->getNumber() === 2023);
 assert($class->boolean);
 $class->boolean = false;
 assert($class->boolean === false);
+
+// Tests #prop properties cannot be unset
+assert_exception_thrown(function () use ($class) {
+    unset($class->boolean);
+});
+assert($class->boolean === false);
+
+// Tests references to #prop properties are rejected, as they would detach
+// from the field
+assert_exception_thrown(function () use ($class) {
+    $ref = &$class->boolean;
+});
+assert($class->boolean === false);
+
+// Tests #prop properties are declared with the type of the field
+$boolean = new ReflectionProperty(TestClass::class, 'boolean');
+assert($boolean->hasType());
+assert((string) $boolean->getType() === 'bool');
+assert($boolean->isPublic());
+assert($boolean->getDocComment() === "/**\n * Whether the flag is set.\n */");
+assert((new ReflectionProperty(TestClass::class, 'secret'))->getDocComment() === false);
+assert((string) (new ReflectionProperty(TestCompare::class, 'amount'))->getType() === 'int');
+assert(array_keys(get_object_vars($class)) === ['boolean']);
+
+// Tests #prop defaults are declared on the class
+assert($boolean->hasDefaultValue());
+assert($boolean->getDefaultValue() === true);
+
+// Tests constructor arguments initializing #prop fields are promoted
+$amount = new ReflectionProperty(TestCompare::class, 'amount');
+assert($amount->isPromoted());
+assert(!$amount->hasDefaultValue());
+assert((new ReflectionMethod(TestCompare::class, '__construct'))->getParameters()[0]->isPromoted());
+assert(!$boolean->isPromoted());
+
+// Tests #prop visibility
+$secret = new ReflectionProperty(TestClass::class, 'secret');
+assert($secret->isProtected());
+assert_exception_thrown(fn () => $class->secret);
+assert_exception_thrown(function () use ($class) {
+    $class->secret = 1;
+});
+assert(!isset($class->secret));
+assert(!array_key_exists('secret', get_object_vars($class)));
+assert((fn () => $this->secret)->call($class) === 42);
+(function () {
+    $this->secret = 7;
+})->call($class);
+assert((fn () => $this->secret)->call($class) === 7);
+assert((fn () => get_object_vars($this)['secret'])->call($class) === 7);
+assert(((array) $class)["\0*\0secret"] === 7);
+
+// Tests cached getters
+$class->setNumber(1);
+assert($class->label === 'dolor et: 1');
+$class->setNumber(2);
+assert($class->label === 'dolor et: 1');
+$class->refreshLabel();
+assert($class->label === 'dolor et: 2');
+assert((string) (new ReflectionProperty(TestClass::class, 'label'))->getType() === 'string');
+
+// Tests flattened #prop fields
+$rect = new TestRect(1, 2, 3);
+assert($rect->origin_x === 1);
+$rect->origin_y = 5;
+assert($rect->origin_y === 5);
+assert(!isset($rect->origin));
+assert(get_object_vars($rect) === ['width' => 3, 'origin_x' => 1, 'origin_y' => 5]);
+assert((string) (new ReflectionProperty(TestRect::class, 'origin_x'))->getType() === 'int');
+
+// Tests static properties
+assert(TestClass::$instances === 1);
+test_class('lorem ipsum', 2022);
+assert(TestClass::$instances === 2);
+TestClass::$instances = 10;
+test_class('lorem ipsum', 2022);
+assert(TestClass::$instances === 11);
+assert_exception_thrown(function () {
+    TestClass::$instances = 'foo';
+});
+$instances = new ReflectionProperty(TestClass::class, 'instances');
+assert($instances->isStatic());
+assert((string) $instances->getType() === 'int');
+assert($instances->getDefaultValue() === 0);
+
+// Tests classes with methods built from closures at startup
+$greeter = new DynamicGreeter();
+assert($greeter->hello('world') === 'Hello, world!');
+assert($greeter->goodbye('world') === 'Goodbye, world!');
+assert($greeter->greeted === 2);
+assert(DynamicGreeter::greetings() === 2);
+assert((new ReflectionMethod(DynamicGreeter::class, 'hello'))->getNumberOfRequiredParameters() === 1);
+assert(DynamicGreeter::DEFAULTS === ['greeting' => 'Hello']);
+
+// Tests Rust classes extending Rust classes
+$dog = new TestDog('Rex', 3);
+assert($dog instanceof TestAnimal);
+assert(get_parent_class($dog) === 'TestAnimal');
+assert($dog->bark() === 'Rex barks');
+assert($dog->describe() === 'Rex is an animal');
+assert($dog->name === 'Rex');
+assert($dog->tricks === 3);
+$dog->rename('Max');
+assert($dog->name === 'Max');
+$dog->name = 'Fido';
+assert($dog->describe() === 'Fido is an animal');
+assert(test_animal_name($dog) === 'Fido');
+assert(test_animal_name(new TestAnimal('Tom')) === 'Tom');
+assert((new TestAnimal('Tom'))->objectName() === 'Tom');
+assert_exception_thrown(fn () => $dog->objectName());
+
+// Tests calling methods of the parent class from Rust
+$error = test_http_error(404);
+assert($error instanceof Exception);
+assert($error->getMessage() === 'HTTP error 404');
+assert($error->getCode() === 404);
+assert($error->status === 404);
+
+// Tests downcasting objects to Rust classes
+assert(test_dog_tricks(new TestDog('Rex', 3)) === 3);
+assert(test_dog_tricks(new TestAnimal('Tom')) === null);
+assert(test_dog_tricks(new stdClass) === null);
+assert(test_downcast_animal(new TestDog('Rex', 3)) === 'REX is an animal');
+assert(test_downcast_animal(new ArrayObject()) === null);
+
+// Tests unit and tuple structs
+assert((new TestMarker())->kind() === 'marker');
+$meters = new TestMeters(1.5);
+assert($meters->value === 1.5);
+assert($meters->unit === 'm');
+$meters->double();
+assert($meters->value === 3.0);
+$meters->value = 2.0;
+$meters->double();
+assert($meters->value === 4.0);
+
+// Tests using traits in Rust classes
+assert(trait_exists('TestGreets'));
+$user = new TestTraitUser();
+assert($user->greet() === 'Hello from TestTraitUser!');
+assert($user->farewell() === 'Farewell from the class!');
+assert(class_uses($user) === ['TestGreets' => 'TestGreets']);
+assert($user->greeting === 'Hello');
+assert($user->farewell === 'Bye');
+assert(TestTraitUser::LANGUAGE === 'en');
+
+// Tests calling methods from the scope of a Rust class
+class TestScopedChild extends TestScopedCaller
+{
+    public function secret()
+    {
+        return 'child';
+    }
+
+    protected function hook()
+    {
+        return 'hook';
+    }
+
+    private function hidden()
+    {
+        return 'hidden';
+    }
+}
+
+assert((new TestScopedCaller())->call('secret') === 'secret');
+assert((new TestScopedChild())->call('secret') === 'secret');
+assert((new TestScopedChild())->call('hook') === 'hook');
+assert_exception_thrown(fn () => (new TestScopedChild())->call('hidden'));
+assert_exception_thrown(fn () => (new TestScopedCaller())->call('missing'));
+
+// Tests forwarding calls to a delegate
+$delegate = new TestDelegate(new ArrayObject([1, 2, 3]));
+assert($delegate->describe() === 'delegate');
+assert($delegate->count() === 3);
+assert($delegate->getArrayCopy() === [1, 2, 3]);
+try {
+    $delegate->missing();
+    assert(false);
+} catch (Error $e) {
+    assert($e->getMessage() === 'Call to undefined method ArrayObject::missing()');
+}
+assert_exception_thrown(fn () => $delegate->setIteratorClass('NotAClass'));
+$inner = new stdClass();
+$inner->name = 'inner';
+assert((new TestDelegate($inner))->name === 'inner');
+$name = 'referenced';
+$inner->name = &$name;
+assert((new TestDelegate($inner))->name === 'referenced');
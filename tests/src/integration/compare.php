@@ -0,0 +1,22 @@
+<?php
+
+require('_utils.php');
+
+$one = new TestCompare(1);
+$two = new TestCompare(2);
+
+assert($one == new TestCompare(1));
+assert($one != $two);
+assert($one < $two);
+assert($two > $one);
+assert(($one <=> $two) === -1);
+
+$values = [new TestCompare(3), $two, $one];
+sort($values);
+assert(array_map(fn ($v) => $v->amount, $values) === [1, 2, 3]);
+
+// Unordered values
+$unordered = new TestCompare(-1);
+assert(!($unordered == $one));
+assert(!($unordered < $one));
+assert(!($unordered > $one));
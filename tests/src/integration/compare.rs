@@ -0,0 +1,4 @@
+#[test]
+fn compare_works() {
+    crate::integration::run_php("compare.php");
+}
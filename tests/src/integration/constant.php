@@ -0,0 +1,21 @@
+<?php
+
+require('_utils.php');
+
+const USER_CONSTANT = [1, 2, 3];
+
+class TestConstants
+{
+    const VALUE = 'class ' . PHP_EOL;
+    private const PRIVATE_VALUE = 'private';
+}
+
+assert(test_get_constant('PHP_EOL') === PHP_EOL);
+assert(test_get_constant('PHP_INT_MAX') === PHP_INT_MAX);
+assert(test_get_constant('USER_CONSTANT') === [1, 2, 3]);
+assert(test_get_constant('DOES_NOT_EXIST') === null);
+
+assert(test_get_class_constant('TestConstants', 'VALUE') === 'class ' . PHP_EOL);
+assert(test_get_class_constant('TestConstants', 'PRIVATE_VALUE') === 'private');
+assert(test_get_class_constant('TestConstants', 'DOES_NOT_EXIST') === null);
+assert(test_get_class_constant('DateTimeInterface', 'ATOM') === DateTimeInterface::ATOM);
@@ -0,0 +1,4 @@
+#[test]
+fn constant_works() {
+    crate::integration::run_php("constant.php");
+}
@@ -0,0 +1,9 @@
+<?php
+
+require('_utils.php');
+
+$obj = new TestDebugInfo('hunter2');
+$dump = print_r($obj, true);
+
+assert(str_contains($dump, '[length] => 7'));
+assert(!str_contains($dump, 'hunter2'));
@@ -0,0 +1,4 @@
+#[test]
+fn debug_info_works() {
+    crate::integration::run_php("debug_info.php");
+}
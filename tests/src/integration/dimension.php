@@ -0,0 +1,36 @@
+<?php
+
+require('_utils.php');
+
+$obj = new TestArrayAccess();
+$obj[] = 1;
+$obj[] = 0;
+$obj[] = 3;
+
+assert($obj[0] === 1);
+assert($obj[2] === 3);
+
+$obj[2] = 4;
+assert($obj[2] === 4);
+
+assert(isset($obj[1]));
+assert(!isset($obj[3]));
+assert(empty($obj[1]));
+assert(!empty($obj[0]));
+assert(($obj[5] ?? 'default') === 'default');
+
+unset($obj[0]);
+assert($obj[0] === 0);
+assert(!isset($obj[2]));
+
+assert(!$obj instanceof ArrayAccess);
+
+assert_exception_thrown(fn () => $obj[10]);
+assert_exception_thrown(fn () => $obj['a'] = 1);
+assert_exception_thrown(fn () => $obj[0] = 'a');
+
+// Nested writes would modify a copy of the value, so they are rejected.
+assert_exception_thrown(function () use ($obj) {
+    $obj[0][] = 1;
+});
+assert($obj[0] === 0);
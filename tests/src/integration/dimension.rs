@@ -0,0 +1,4 @@
+#[test]
+fn dimension_works() {
+    crate::integration::run_php("dimension.php");
+}
@@ -0,0 +1,83 @@
+<?php
+
+require('_utils.php');
+
+assert(enum_exists('TestSuit'));
+assert(TestSuit::Hearts instanceof BackedEnum);
+assert(TestSuit::Hearts->name === 'Hearts');
+assert(TestSuit::Hearts->value === 'H');
+assert(TestSuit::from('S') === TestSuit::Spades);
+assert(TestSuit::tryFrom('X') === null);
+assert(TestSuit::cases() === [TestSuit::Hearts, TestSuit::Spades]);
+
+// Cases iterated from Rust are the case objects returned by `cases()`.
+assert(test_enum_suit_cases() === array_combine(
+    array_column(TestSuit::cases(), 'name'),
+    array_map(fn ($case) => ['value' => $case->value, 'case' => $case], TestSuit::cases()),
+));
+
+// Methods are called on the case.
+assert(TestSuit::Hearts->color() === 'Red');
+assert(TestSuit::Spades->color() === 'Black');
+assert(TestSuit::symbols() === '♥♠');
+
+// Cases returned from Rust are the existing case objects.
+assert(TestSuit::Hearts->other() === TestSuit::Spades);
+assert(TestSuit::first() === TestSuit::Hearts);
+assert(test_enum_suit_identity(TestSuit::Spades) === TestSuit::Spades);
+assert_exception_thrown(fn () => test_enum_suit_identity(TestPriority::Low));
+assert(test_enum_suit_from_object(TestSuit::Hearts) === 'Hearts');
+try {
+    test_enum_suit_from_object(TestPriority::Low);
+    assert(false);
+} catch (Exception $e) {
+    assert($e->getMessage() === 'Object is not a case of enum TestSuit');
+}
+
+// Constants are declared on the enum.
+assert(TestSuit::JOKER === '🃏');
+assert((new ReflectionClassConstant(TestSuit::class, 'JOKER'))->isEnumCase() === false);
+assert(TestSuit::TRUMPS === TestSuit::Hearts);
+assert((new ReflectionClassConstant(TestSuit::class, 'TRUMPS'))->isEnumCase() === false);
+assert(TestClass::SUIT === TestSuit::Spades);
+
+// Attributes are applied to the cases.
+$attributes = (new ReflectionEnumUnitCase(TestSuit::class, 'Hearts'))->getAttributes();
+assert(count($attributes) === 1);
+assert($attributes[0]->getName() === 'Test\\Label');
+assert($attributes[0]->getArguments() === ['red', 1]);
+assert((new ReflectionEnumUnitCase(TestSuit::class, 'Spades'))->getAttributes() === []);
+
+// Interfaces are implemented by the enum.
+assert(TestSuit::Hearts instanceof Countable);
+assert(count(TestSuit::Spades) === 13);
+
+// Integer discriminants back the cases.
+assert(TestPriority::High->value === 10);
+assert(TestPriority::from(1) === TestPriority::Low);
+
+// Enums without values are unit enums.
+assert(TestDirection::Up instanceof UnitEnum);
+assert(!(TestDirection::Up instanceof BackedEnum));
+assert(TestDirection::cases() === [TestDirection::Up, TestDirection::Down]);
+
+// Cases are renamed with `rename_all` and `#[rename]`.
+assert(TestStatus::IN_PROGRESS->name === 'IN_PROGRESS');
+assert(TestStatus::cases() === [TestStatus::IN_PROGRESS, TestStatus::Finished]);
+assert(test_enum_status_name(TestStatus::IN_PROGRESS) === 'InProgress');
+assert(test_enum_status_name(TestStatus::Finished) === 'Done');
+
+// The Rust conversions agree with `from()`.
+foreach (TestSuit::cases() as $case) {
+    assert(test_enum_suit_from_value($case->value) === $case->name);
+}
+foreach (TestPriority::cases() as $case) {
+    assert(test_enum_priority_from_value($case->value) === $case->value);
+}
+assert_exception_thrown(fn () => test_enum_suit_from_value('X'));
+assert_exception_thrown(fn () => test_enum_priority_from_value(2));
+try {
+    test_enum_priority_from_value(2);
+} catch (Exception $e) {
+    assert($e->getMessage() === '2 is not a valid backing value for enum TestPriority');
+}
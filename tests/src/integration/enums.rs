@@ -0,0 +1,4 @@
+#[test]
+fn enums_works() {
+    crate::integration::run_php("enums.php");
+}
@@ -0,0 +1,42 @@
+<?php
+
+require('_utils.php');
+
+assert(TestPermissions::READ === 1);
+assert(TestPermissions::WRITE === 2);
+assert(TestPermissions::EXECUTE === 4);
+
+$none = new TestPermissions();
+assert($none->value() === 0);
+assert($none->has(0));
+assert(!$none->has(TestPermissions::READ));
+
+$perms = new TestPermissions(TestPermissions::READ | TestPermissions::WRITE);
+assert($perms->has(TestPermissions::READ));
+assert($perms->has(TestPermissions::READ | TestPermissions::WRITE));
+assert(!$perms->has(TestPermissions::EXECUTE));
+
+// Instances are immutable
+$all = $perms->with(TestPermissions::EXECUTE);
+assert($all instanceof TestPermissions);
+assert($all->value() === 7);
+assert($perms->value() === 3);
+
+$readOnly = $all->without(new TestPermissions(TestPermissions::WRITE | TestPermissions::EXECUTE));
+assert($readOnly->value() === TestPermissions::READ);
+assert($all->has($readOnly));
+
+// Unknown bits are rejected
+assert_exception_thrown(fn () => new TestPermissions(8));
+assert_exception_thrown(fn () => $perms->with(16));
+assert_exception_thrown(fn () => $perms->has('read'));
+
+// Subclasses may declare further properties
+class LabelledPermissions extends TestPermissions {
+    public $label = 'labelled';
+}
+
+$labelled = new LabelledPermissions(TestPermissions::EXECUTE);
+assert($labelled->has(TestPermissions::EXECUTE));
+assert($labelled->with(TestPermissions::READ)->value() === 5);
+assert($perms->with($labelled)->value() === 7);
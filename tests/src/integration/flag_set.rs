@@ -0,0 +1,4 @@
+#[test]
+fn flag_set_works() {
+    crate::integration::run_php("flag_set.php");
+}
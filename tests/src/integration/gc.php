@@ -0,0 +1,18 @@
+<?php
+
+require('_utils.php');
+
+gc_collect_cycles();
+
+// An object holding itself in a Rust field forms a cycle only the collector can free
+$holder = new TestGcHolder();
+$holder->hold($holder);
+unset($holder);
+assert(gc_collect_cycles() === 1);
+
+// Cycles running through a PHP array
+$holder = new TestGcHolder();
+$arr = [$holder];
+$holder->hold($arr);
+unset($holder, $arr);
+assert(gc_collect_cycles() >= 1);
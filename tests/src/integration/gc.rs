@@ -0,0 +1,4 @@
+#[test]
+fn gc_works() {
+    crate::integration::run_php("gc.php");
+}
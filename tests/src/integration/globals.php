@@ -0,0 +1,19 @@
+<?php
+
+require('_utils.php');
+
+$get = $_GET;
+
+test_set_superglobals();
+
+assert($_GET['ext'] === 'php-rs');
+assert($_POST['ext'] === 'php-rs');
+assert($_SERVER['EXT_PHP_RS'] === true);
+assert($_ENV['EXT_PHP_RS'] === '1');
+
+// Tests custom auto-globals are available in every scope
+assert($_EXT_PHP_RS['name'] === 'ext-php-rs');
+assert((fn () => $_EXT_PHP_RS['name'])() === 'ext-php-rs');
+
+// Copies made before the superglobals were changed are not modified
+assert(!isset($get['ext']));
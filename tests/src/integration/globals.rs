@@ -0,0 +1,4 @@
+#[test]
+fn globals_works() {
+    crate::integration::run_php("globals.php");
+}
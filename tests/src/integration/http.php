@@ -0,0 +1,177 @@
+<?php
+
+require('_utils.php');
+
+// Minimal PSR-7 and PSR-17 implementations, used through duck typing.
+class TestStream
+{
+    public function __construct(private string $contents) {}
+
+    public function __toString(): string
+    {
+        return $this->contents;
+    }
+}
+
+class TestUri
+{
+    public function __construct(private string $uri) {}
+
+    public function __toString(): string
+    {
+        return $this->uri;
+    }
+}
+
+abstract class TestMessage
+{
+    public string $version = '1.1';
+    public array $headers = [];
+    public TestStream $body;
+
+    public function getProtocolVersion(): string
+    {
+        return $this->version;
+    }
+
+    public function withProtocolVersion(string $version): static
+    {
+        $new = clone $this;
+        $new->version = $version;
+        return $new;
+    }
+
+    public function getHeaders(): array
+    {
+        return $this->headers;
+    }
+
+    public function withAddedHeader(string $name, string $value): static
+    {
+        $new = clone $this;
+        $new->headers[$name][] = $value;
+        return $new;
+    }
+
+    public function getBody(): TestStream
+    {
+        return $this->body;
+    }
+
+    public function withBody(TestStream $body): static
+    {
+        $new = clone $this;
+        $new->body = $body;
+        return $new;
+    }
+}
+
+class TestRequest extends TestMessage
+{
+    public function __construct(public string $method, public TestUri $uri)
+    {
+        $this->body = new TestStream('');
+    }
+
+    public function getMethod(): string
+    {
+        return $this->method;
+    }
+
+    public function getUri(): TestUri
+    {
+        return $this->uri;
+    }
+}
+
+class TestResponse extends TestMessage
+{
+    public function __construct(public int $status, public string $reason)
+    {
+        $this->body = new TestStream('');
+    }
+
+    public function getStatusCode(): int
+    {
+        return $this->status;
+    }
+}
+
+class TestFactory
+{
+    public function createRequest(string $method, string $uri): TestRequest
+    {
+        return new TestRequest($method, new TestUri($uri));
+    }
+
+    public function createResponse(int $code = 200, string $reason = ''): TestResponse
+    {
+        return new TestResponse($code, $reason);
+    }
+
+    public function createStream(string $contents = ''): TestStream
+    {
+        return new TestStream($contents);
+    }
+}
+
+$factory = new TestFactory();
+
+// Tests reading a request and creating a response
+$request = (new TestRequest('PUT', new TestUri('https://example.com/items?id=1')))
+    ->withProtocolVersion('2.0')
+    ->withAddedHeader('X-Request-Id', 'abc')
+    ->withAddedHeader('Accept', 'text/plain')
+    ->withAddedHeader('Accept', 'text/html')
+    ->withBody(new TestStream("hello\0world"));
+$response = test_psr7_echo($request, $factory, $factory);
+assert($response instanceof TestResponse);
+assert($response->getStatusCode() === 201);
+assert($response->reason === 'Created');
+assert($response->getProtocolVersion() === '2');
+assert($response->getHeaders() === [
+    'x-method' => ['PUT'],
+    'x-uri' => ['https://example.com/items?id=1'],
+    'x-request-id' => ['abc'],
+    'accept' => ['text/plain', 'text/html'],
+]);
+assert((string) $response->getBody() === "HELLO\0WORLD");
+
+// Tests reading a response and creating a request
+$response = (new TestResponse(404, 'Not Found'))
+    ->withProtocolVersion('1.0')
+    ->withBody(new TestStream('missing'));
+$request = test_psr7_forward($response, $factory, $factory);
+assert($request instanceof TestRequest);
+assert($request->getMethod() === 'POST');
+assert((string) $request->getUri() === 'https://example.com/404');
+assert($request->getProtocolVersion() === '1.1');
+assert($request->getHeaders() === ['content-type' => ['text/plain']]);
+assert((string) $request->getBody() === 'missing');
+
+// Tests invalid messages are rejected
+assert_exception_thrown(fn () => test_psr7_echo(new TestRequest('BAD METHOD', new TestUri('/')), $factory, $factory));
+assert_exception_thrown(fn () => test_psr7_echo($request->withProtocolVersion('4'), $factory, $factory));
+assert_exception_thrown(fn () => test_psr7_forward(new TestResponse(99, ''), $factory, $factory));
+assert_exception_thrown(fn () => test_psr7_echo(new stdClass(), $factory, $factory));
+assert_exception_thrown(fn () => test_psr7_echo($request->withAddedHeader("\xff", 'value'), $factory, $factory));
+
+// Tests numeric header names, which are stored as integer keys
+$response = test_psr7_echo($request->withAddedHeader('123', 'numeric'), $factory, $factory);
+assert($response->getHeaders()['123'] === ['numeric']);
+
+// Tests exceptions thrown by PSR-7 objects are rethrown
+class TestStreamException extends RuntimeException {}
+class TestThrowingStream extends TestStream
+{
+    public function __toString(): string
+    {
+        throw new TestStreamException('Stream is detached');
+    }
+}
+try {
+    test_psr7_echo($request->withBody(new TestThrowingStream('')), $factory, $factory);
+    assert(false);
+} catch (TestStreamException $e) {
+    assert($e->getMessage() === 'Stream is detached');
+}
@@ -0,0 +1,4 @@
+#[test]
+fn http_works() {
+    crate::integration::run_php("http.php");
+}
@@ -0,0 +1,19 @@
+<?php
+
+require('_utils.php');
+
+ini_set('memory_limit', '256M');
+assert(test_ini_get_int('memory_limit') === 256 * 1024 * 1024);
+
+ini_set('html_errors', 'On');
+assert(test_ini_get_bool('html_errors') === true);
+ini_set('html_errors', '0');
+assert(test_ini_get_bool('html_errors') === false);
+
+assert(test_ini_get_int('does.not.exist') === null);
+
+test_ini_set('precision', '10');
+assert(ini_get('precision') === '10');
+
+assert_exception_thrown(fn () => test_ini_set('does.not.exist', '1'));
+assert_exception_thrown(fn () => test_ini_set('extension_dir', '/tmp'));
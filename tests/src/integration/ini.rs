@@ -0,0 +1,4 @@
+#[test]
+fn ini_works() {
+    crate::integration::run_php("ini.php");
+}
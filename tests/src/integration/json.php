@@ -0,0 +1,121 @@
+<?php
+
+require('_utils.php');
+
+class TestJsonSerializable implements JsonSerializable
+{
+    public function __construct(private mixed $value) {}
+
+    public function jsonSerialize(): mixed
+    {
+        if ($this->value instanceof Throwable) {
+            throw $this->value;
+        }
+        return $this->value;
+    }
+}
+
+// Tests values are encoded exactly as `json_encode()` does
+$values = [
+    null,
+    true,
+    12,
+    1.0,
+    "<a href='/x'>\"é\"</a>\u{2028}",
+    [1, 2, 3],
+    ['a' => 1, 'b' => [true, null]],
+    [],
+    new stdClass(),
+    (object) ['x' => 1.5],
+    new TestJsonSerializable(['serialized' => true]),
+];
+$flags = [
+    0,
+    JSON_PRETTY_PRINT,
+    JSON_FORCE_OBJECT,
+    JSON_PRESERVE_ZERO_FRACTION,
+    JSON_HEX_TAG | JSON_HEX_AMP | JSON_HEX_APOS | JSON_HEX_QUOT,
+    JSON_UNESCAPED_SLASHES | JSON_UNESCAPED_UNICODE,
+    JSON_UNESCAPED_UNICODE | JSON_UNESCAPED_LINE_TERMINATORS,
+];
+foreach ($values as $value) {
+    foreach ($flags as $flag) {
+        assert(test_json_encode($value, $flag) === json_encode($value, $flag));
+    }
+}
+
+// Tests encoding errors are thrown as `JsonException`
+try {
+    test_json_encode(NAN, 0);
+    assert(false);
+} catch (JsonException $e) {
+    assert($e->getMessage() === 'Inf and NaN cannot be JSON encoded');
+    assert($e->getCode() === JSON_ERROR_INF_OR_NAN);
+}
+try {
+    test_json_encode("\xff", 0);
+    assert(false);
+} catch (JsonException $e) {
+    assert($e->getCode() === JSON_ERROR_UTF8);
+}
+assert(test_json_encode("a\xffb", JSON_INVALID_UTF8_SUBSTITUTE) === json_encode("a\xffb", JSON_INVALID_UTF8_SUBSTITUTE));
+assert(test_json_encode([NAN], JSON_PARTIAL_OUTPUT_ON_ERROR) === '[0]');
+
+// Tests the depth limit is applied when encoding
+assert(test_json_encode([[1]], 0, 2) === '[[1]]');
+try {
+    test_json_encode([[1]], 0, 1);
+    assert(false);
+} catch (JsonException $e) {
+    assert($e->getCode() === JSON_ERROR_DEPTH);
+}
+
+// Tests exceptions thrown by `jsonSerialize()` are rethrown
+try {
+    test_json_encode(new TestJsonSerializable(new RuntimeException('not serializable')), 0);
+    assert(false);
+} catch (RuntimeException $e) {
+    assert($e->getMessage() === 'not serializable');
+}
+
+// Tests strings are decoded exactly as `json_decode()` does
+$documents = [
+    'null',
+    '12',
+    '1.0',
+    '"é😀"',
+    '[1, "two", {"three": 3}]',
+    '{"a": {"b": [true, false]}}',
+    '12345678901234567890',
+];
+foreach ($documents as $json) {
+    assert(test_json_decode($json, 0) == json_decode($json));
+    assert(test_json_decode($json, JSON_OBJECT_AS_ARRAY) === json_decode($json, flags: JSON_OBJECT_AS_ARRAY));
+    assert(test_json_decode($json, JSON_BIGINT_AS_STRING) == json_decode($json, flags: JSON_BIGINT_AS_STRING));
+}
+assert(test_json_decode('{"a": 1}', 0) instanceof stdClass);
+assert(test_json_decode('12345678901234567890', JSON_BIGINT_AS_STRING) === '12345678901234567890');
+
+// Tests decoding errors are thrown as `JsonException`
+foreach (['', '{', '[1,]', "\"\x01\"", "\"\xff\"", '{"\u0000a": 1}'] as $json) {
+    json_decode($json);
+    $code = json_last_error();
+    try {
+        test_json_decode($json, 0);
+        assert(false);
+    } catch (JsonException $e) {
+        assert($e->getCode() === $code);
+        assert($e->getMessage() === json_last_error_msg());
+    }
+}
+assert_exception_thrown(fn () => test_json_decode('{', JSON_THROW_ON_ERROR));
+
+// Tests the depth limit is applied when decoding
+assert(test_json_decode('[[1]]', 0, 2) === [[1]]);
+try {
+    test_json_decode('[[1]]', 0, 1);
+    assert(false);
+} catch (JsonException $e) {
+    assert($e->getCode() === JSON_ERROR_DEPTH);
+}
+assert_exception_thrown(fn () => test_json_decode('1', 0, 0));
@@ -0,0 +1,4 @@
+#[test]
+fn json_works() {
+    crate::integration::run_php("json.php");
+}
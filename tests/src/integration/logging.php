@@ -0,0 +1,50 @@
+<?php
+
+require('_utils.php');
+
+$log = tempnam(sys_get_temp_dir(), 'ext-php-rs-log');
+ini_set('error_log', $log);
+
+function read_log(string $file): array
+{
+    $lines = array_filter(explode("\n", file_get_contents($file)));
+    file_put_contents($file, '');
+    // Strip the timestamp added by PHP
+    return array_map(fn ($line) => preg_replace('/^\[[^\]]+\] /', '', $line), array_values($lines));
+}
+
+// Tests records are written with their level and target
+test_log('error', 'broken');
+test_log('warn', 'careful');
+test_log('info', 'hello');
+test_log('debug', 'details');
+test_log('trace', 'ignored');
+assert(read_log($log) === [
+    'ERROR tests: broken',
+    'WARN tests: careful',
+    'INFO tests: hello',
+    'DEBUG tests: details',
+]);
+assert_exception_thrown(fn () => test_log('loud', 'invalid'));
+
+// Tests tracing events include their fields
+test_trace('traced', 3);
+assert(read_log($log) === ['WARN tests: traced count=3']);
+
+// Tests the request ID is added to records
+test_log_request_id('abc-123');
+test_log('info', 'in request');
+test_log_request_id(null);
+test_log('info', 'after request');
+assert(read_log($log) === [
+    'INFO tests [abc-123]: in request',
+    'INFO tests: after request',
+]);
+
+// Tests records from other threads are written when flushed
+test_log_from_thread('from thread');
+assert(read_log($log) === []);
+test_log_flush();
+assert(read_log($log) === ['ERROR tests: from thread']);
+
+unlink($log);
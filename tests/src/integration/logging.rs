@@ -0,0 +1,4 @@
+#[test]
+fn logging_works() {
+    crate::integration::run_php("logging.php");
+}
@@ -0,0 +1,31 @@
+<?php
+
+require('_utils.php');
+
+$range = new TestRange(1, 4);
+$values = [];
+foreach ($range as $key => $value) {
+    $values[$key] = $value;
+}
+assert($values === ['n1' => 1, 'n2' => 2, 'n3' => 3]);
+
+// Tests each loop starts from the first element
+$pairs = [];
+foreach ($range as $a) {
+    foreach ($range as $b) {
+        $pairs[] = $a * $b;
+    }
+}
+assert(count($pairs) === 9);
+assert($pairs[8] === 9);
+
+foreach (new TestRange(5, 5) as $value) {
+    assert(false);
+}
+
+// Tests objects are not traversable, and cannot be iterated by reference
+assert(!($range instanceof Traversable));
+assert_exception_thrown(function () use ($range) {
+    foreach ($range as &$value) {
+    }
+});
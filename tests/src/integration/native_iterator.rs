@@ -0,0 +1,4 @@
+#[test]
+fn native_iterator_works() {
+    crate::integration::run_php("native_iterator.php");
+}
@@ -6,6 +6,9 @@ require('_utils.php');
 assert(test_number_signed(-12) === -12);
 assert(test_number_signed(0) === 0);
 assert(test_number_signed(12) === 12);
+assert_exception_thrown(fn () => test_number_signed());
+assert_exception_thrown(fn () => test_number_signed(1, 2));
+assert_exception_thrown(fn () => test_number_signed('abc'));
 
 // Unsigned
 assert(test_number_unsigned(0) === 0);
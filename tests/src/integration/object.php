@@ -1,5 +1,7 @@
 <?php
 
+require('_utils.php');
+
 $obj = new stdClass;
 $obj->string = 'string';
 $obj->bool = true;
@@ -14,3 +16,47 @@ assert($test->string === 'string');
 assert($test->bool === true);
 assert($test->number === 2022);
 assert($test->array === [1, 2, 3]);
+
+// Tests renamed properties of derived conversions
+$record = new stdClass;
+$record->firstName = 'david';
+$record->years = 20;
+
+$test = test_record($record);
+
+assert($test->firstName === 'DAVID');
+assert($test->years === 21);
+assert(!isset($test->first_name));
+
+// Tests instantiating classes with constructor arguments
+$list = test_instantiate('ArrayObject', [[1, 2, 3]]);
+assert($list instanceof ArrayObject);
+assert(count($list) === 3);
+
+$date = test_instantiate('DateTimeImmutable', ['2000-01-02']);
+assert($date->format('Y-m-d') === '2000-01-02');
+
+class InstantiateFails
+{
+    public static $destructed = false;
+
+    public function __construct(public int $value)
+    {
+        if ($value < 0) {
+            throw new InvalidArgumentException('negative');
+        }
+    }
+
+    public function __destruct()
+    {
+        self::$destructed = true;
+    }
+}
+
+assert(test_instantiate('InstantiateFails', [5])->value === 5);
+InstantiateFails::$destructed = false;
+assert_exception_thrown(fn () => test_instantiate('InstantiateFails', [-1]));
+assert(InstantiateFails::$destructed === false);
+
+abstract class InstantiateAbstract {}
+assert_exception_thrown(fn () => test_instantiate('InstantiateAbstract', []));
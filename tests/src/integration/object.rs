@@ -1,4 +1,4 @@
 #[test]
 fn object_works() {
-    assert!(crate::integration::run_php("object.php"));
+    crate::integration::run_php("object.php");
 }
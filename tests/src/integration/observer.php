@@ -0,0 +1,34 @@
+<?php
+
+require('_utils.php');
+
+function observed_function(int $x): int {
+    return $x * 2;
+}
+
+function unobserved_function(int $x): int {
+    return $x;
+}
+
+assert(test_observer_counts() === [0, 0, 0]);
+
+observed_function(1);
+observed_function(2);
+unobserved_function(3);
+assert(test_observer_counts() === [2, 2, 6]);
+
+function panicking_function(int $x): int {
+    return $x;
+}
+
+// Panics of observers are reported as warnings
+$warnings = [];
+set_error_handler(function (int $errno, string $errstr) use (&$warnings) {
+    $warnings[] = $errstr;
+    return true;
+}, E_WARNING);
+assert(panicking_function(4) === 4);
+restore_error_handler();
+assert(count($warnings) === 1);
+assert(str_contains($warnings[0], 'FcallObserver::begin panicked: observer of panicking_function'));
+assert(test_observer_counts() === [2, 3, 10]);
@@ -0,0 +1,4 @@
+#[test]
+fn observer_works() {
+    crate::integration::run_php("observer.php");
+}
@@ -0,0 +1,18 @@
+<?php
+
+require('_utils.php');
+
+$status = test_opcache_status();
+$loaded = function_exists('opcache_get_status');
+$native = $loaded ? opcache_get_status(false) : false;
+
+assert($status['loaded'] === $loaded);
+assert($status['enabled'] === ($native !== false && $native['opcache_enabled']));
+assert($status['scripts'] === ($native !== false ? $native['opcache_statistics']['num_cached_scripts'] ?? 0 : null));
+
+// The file is not a PHP script which has been included, so it is never cached.
+assert($status['cached'] === false);
+assert($status['invalidated'] === false);
+
+// Classes registered by the extension are immutable.
+assert($status['immutable'] === true);
@@ -0,0 +1,4 @@
+#[test]
+fn opcache_works() {
+    crate::integration::run_php("opcache.php");
+}
@@ -0,0 +1,35 @@
+<?php
+
+require('_utils.php');
+
+// Handlers only apply to code compiled after they were registered.
+test_opcode_handler_install();
+
+ob_start();
+eval('echo "a"; echo "b";');
+assert(ob_get_clean() === 'ab');
+assert(test_opcode_echoes() === 11);
+
+test_opcode_handler_remove();
+
+ob_start();
+eval('echo "c";');
+assert(ob_get_clean() === 'c');
+assert(test_opcode_echoes() === 11);
+
+// Panics of handlers are reported as warnings and the opcode still runs.
+test_opcode_handler_panic();
+
+$warnings = [];
+set_error_handler(function (int $errno, string $errstr) use (&$warnings) {
+    $warnings[] = $errstr;
+    return true;
+}, E_WARNING);
+ob_start();
+eval('echo "d";');
+assert(ob_get_clean() === 'd');
+restore_error_handler();
+assert(count($warnings) === 1);
+assert(str_contains($warnings[0], 'User opcode handler panicked: opcode handler'));
+
+test_opcode_handler_remove();
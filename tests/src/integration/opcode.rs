@@ -0,0 +1,4 @@
+#[test]
+fn opcode_works() {
+    crate::integration::run_php("opcode.php");
+}
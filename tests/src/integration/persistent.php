@@ -0,0 +1,28 @@
+<?php
+
+require('_utils.php');
+
+// Tests values are created once and shared by later calls
+assert(test_persistent_get('counter') === null);
+assert(test_persistent_counter('counter') === 1);
+assert(test_persistent_counter('counter') === 2);
+assert(test_persistent_get('counter') === 2);
+
+// Tests values are identified by their key
+assert(test_persistent_counter('other') === 1);
+assert(test_persistent_get('counter') === 2);
+
+// Tests values are identified by their type
+assert(test_persistent_try_init('counter', false) === 'COUNTER');
+assert(test_persistent_get('counter') === 2);
+
+// Tests removed values are created again
+assert(test_persistent_remove('counter'));
+assert(!test_persistent_remove('counter'));
+assert(test_persistent_get('counter') === null);
+assert(test_persistent_counter('counter') === 1);
+
+// Tests failed initializations are not stored
+assert_exception_thrown(fn () => test_persistent_try_init('failed', true));
+assert(test_persistent_try_init('failed', false) === 'FAILED');
+assert(test_persistent_try_init('failed', true) === 'FAILED');
@@ -0,0 +1,4 @@
+#[test]
+fn persistent_works() {
+    crate::integration::run_php("persistent.php");
+}
@@ -0,0 +1,21 @@
+<?php
+
+require('_utils.php');
+
+$observed = new TestObserved();
+assert($observed->changes() === []);
+
+$observed->name = 'foo';
+$observed->label = 'bar';
+assert($observed->name === 'bar');
+assert($observed->changes() === ['name', 'label']);
+
+// Tests writes to dynamic properties are not reported
+@$observed->dynamic = 1;
+assert($observed->changes() === ['name', 'label']);
+
+// Tests failed writes are not reported
+assert_exception_thrown(function () use ($observed) {
+    $observed->name = [];
+});
+assert($observed->changes() === ['name', 'label']);
@@ -0,0 +1,4 @@
+#[test]
+fn property_observer_works() {
+    crate::integration::run_php("property_observer.php");
+}
@@ -0,0 +1,53 @@
+<?php
+
+require('_utils.php');
+
+use ExtPhpRs\Logger;
+
+class TestLogger
+{
+    public array $records = [];
+
+    public function log($level, string $message, array $context = []): void
+    {
+        if ($message === 'fail') {
+            throw new RuntimeException('logger failed');
+        }
+        $this->records[] = [$level, $message, $context];
+    }
+}
+
+$class = new ReflectionClass(Logger::class);
+assert($class->isFinal());
+
+// Tests records are dropped without a logger
+test_psr3_log('dropped', []);
+
+// Tests records are passed to the registered logger
+$logger = new TestLogger();
+Logger::register($logger);
+test_psr3_log('Hello {name}', ['name' => 'world']);
+assert($logger->records === [['warning', 'Hello {name}', ['name' => 'world']]]);
+
+// Tests records from other threads are buffered until flushed
+$logger->records = [];
+test_psr3_log_from_thread('from thread');
+assert($logger->records === []);
+Logger::flush();
+assert($logger->records === [['debug', 'from thread', []]]);
+
+// Tests buffered records are passed before the next record
+$logger->records = [];
+test_psr3_log_from_thread('first');
+test_psr3_log('second', []);
+assert($logger->records === [['debug', 'first', []], ['warning', 'second', []]]);
+
+// Tests exceptions thrown by the logger are rethrown
+assert_exception_thrown(fn () => test_psr3_log('fail', []));
+
+// Tests the logger can be unregistered
+$logger->records = [];
+Logger::register(null);
+test_psr3_log('dropped', []);
+assert($logger->records === []);
+assert_exception_thrown(fn () => Logger::register('not a logger'));
@@ -0,0 +1,4 @@
+#[test]
+fn psr3_works() {
+    crate::integration::run_php("psr3.php");
+}
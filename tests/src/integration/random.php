@@ -0,0 +1,53 @@
+<?php
+
+require('_utils.php');
+
+// Tests generating random bytes
+assert(test_random_bytes(0) === '');
+assert(strlen(test_random_bytes(32)) === 32);
+assert(test_random_bytes(16) !== test_random_bytes(16));
+
+// Tests generating random integers
+for ($i = 0; $i < 100; $i++) {
+    $value = test_random_int(-5, 5);
+    assert($value >= -5 && $value <= 5);
+}
+assert(test_random_int(7, 7) === 7);
+assert(test_random_int(PHP_INT_MIN, PHP_INT_MAX) !== null);
+assert_exception_thrown(fn () => test_random_int(1, 0));
+
+// Tests objects which are not engines are rejected
+assert(test_random_engine(new stdClass(), 8) === null);
+
+if (PHP_VERSION_ID < 80200) {
+    return;
+}
+
+// A userland engine returning a counter one byte at a time.
+class CounterEngine implements Random\Engine
+{
+    private int $next = 0;
+
+    public function generate(): string
+    {
+        return chr($this->next++);
+    }
+}
+
+class BrokenEngine implements Random\Engine
+{
+    public function generate(): string
+    {
+        return '';
+    }
+}
+
+// Tests userland engines
+assert(test_random_engine(new CounterEngine(), 4) === "\x00\x01\x02\x03");
+assert(test_random_engine_u64(new CounterEngine()) === '0706050403020100');
+assert_exception_thrown(fn () => test_random_engine(new BrokenEngine(), 1));
+
+// Tests native engines produce the same bytes as PHP
+$engine = new Random\Engine\Mt19937(42);
+$expected = $engine->generate() . $engine->generate();
+assert(test_random_engine(new Random\Engine\Mt19937(42), 8) === $expected);
@@ -0,0 +1,4 @@
+#[test]
+fn random_works() {
+    crate::integration::run_php("random.php");
+}
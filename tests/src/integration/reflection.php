@@ -0,0 +1,66 @@
+<?php
+
+require('_utils.php');
+
+#[Attribute]
+class Route
+{
+    public function __construct(public string $path, public array $methods = ['GET']) {}
+}
+
+#[Attribute]
+class Inject {}
+
+/** A test service. */
+#[Route('/service', methods: ['GET', 'POST'])]
+class TestService
+{
+    public const LIMIT = 10;
+    private const SECRET = 42;
+
+    #[Inject]
+    protected int $count = 0;
+    private static ?self $instance = null;
+
+    public function run(): void {}
+
+    private static function create(): self
+    {
+        return new self();
+    }
+}
+
+/** Does nothing. */
+#[Route('/noop')]
+function test_noop(#[Inject] $value = null) {}
+
+// Tests enumerating the members of a class
+$members = test_reflection_class('TestService');
+foreach ([
+    'doc /** A test service. */',
+    'attribute Route("\/service", methods: ["GET","POST"])',
+    'method run public',
+    'method create private static',
+    'property count protected',
+    'attribute Inject()',
+    'property instance private',
+    'constant LIMIT public = 10',
+    'constant SECRET private = 42',
+    'has_method true',
+    'has_property true',
+    'has_constant true',
+] as $member) {
+    assert(in_array($member, $members, true), $member);
+}
+
+// Tests internal classes have no doc comment and are not missing members
+$members = test_reflection_class('ArrayObject');
+assert(!in_array('doc', array_map(fn ($m) => substr($m, 0, 3), $members), true));
+assert(in_array('method getArrayCopy public', $members, true));
+assert(in_array('constant ARRAY_AS_PROPS public = 2', $members, true));
+
+// Tests looking up functions
+assert(test_reflection_function('\TEST_NOOP') === ['doc /** Does nothing. */', 'attribute Route("\/noop")']);
+assert(test_reflection_function('strlen') === []);
+assert(test_reflection_function('not_a_function') === null);
+assert_exception_thrown(fn () => test_reflection_class('NotAClass'));
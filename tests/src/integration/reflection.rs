@@ -0,0 +1,4 @@
+#[test]
+fn reflection_works() {
+    crate::integration::run_php("reflection.php");
+}
@@ -0,0 +1,44 @@
+<?php
+
+require('_utils.php');
+
+// Tests values round-trip through serde_json::Value
+$value = [
+    'name' => 'ext-php-rs',
+    'tags' => ['rust', 'php'],
+    'stars' => 42,
+    'ratio' => 0.5,
+    'stable' => false,
+    'license' => null,
+    'nested' => ['a' => ['b' => [1, 2, 3]]],
+];
+assert(test_serde_roundtrip($value) === $value);
+assert(test_serde_roundtrip('hello') === 'hello');
+assert(test_serde_roundtrip([]) === []);
+
+// Tests objects are deserialized from their properties
+$obj = new stdClass;
+$obj->host = 'localhost';
+$obj->ports = [443, 80];
+assert(test_serde_server($obj) === ['host' => 'localhost', 'ports' => [80, 443], 'tls' => null]);
+
+// Tests structs with derived implementations
+assert(test_serde_server(['host' => 'example.com', 'ports' => [8080], 'tls' => true])
+    === ['host' => 'example.com', 'ports' => [8080], 'tls' => true]);
+assert_exception_thrown(fn () => test_serde_server(['host' => 'localhost']));
+assert_exception_thrown(fn () => test_serde_server(['host' => 'localhost', 'ports' => [-1]]));
+
+// Tests only the public properties of objects are deserialized
+class SerdeCredentials
+{
+    public $user = 'admin';
+    protected $token = 'secret';
+    private $password = 'hunter2';
+}
+assert(test_serde_roundtrip(new SerdeCredentials) === ['user' => 'admin']);
+
+// Tests keys which are not valid UTF-8 and recursive arrays are rejected
+assert_exception_thrown(fn () => test_serde_roundtrip(["\xff" => 1]));
+$recursive = [];
+$recursive['self'] = &$recursive;
+assert_exception_thrown(fn () => test_serde_roundtrip($recursive));
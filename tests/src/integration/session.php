@@ -0,0 +1,41 @@
+<?php
+
+require('_utils.php');
+
+ini_set('session.use_cookies', '0');
+ini_set('session.use_strict_mode', '0');
+
+// Tests `$_SESSION` cannot be used before the session is started
+assert(test_session_status() === 'none');
+assert(test_session_get('user') === null);
+assert_exception_thrown(fn () => test_session_set('user', 'alice'));
+
+// Tests the save handler is implemented by the Rust handler
+assert(new ExtPhpRs\SessionHandler() instanceof SessionHandlerInterface);
+test_session_install();
+
+// Tests reading and writing `$_SESSION`
+session_id('first');
+test_session_start();
+assert(test_session_status() === 'active');
+assert($_SESSION === []);
+test_session_set('user', 'alice');
+test_session_set('roles', ['admin']);
+assert($_SESSION === ['user' => 'alice', 'roles' => ['admin']]);
+$_SESSION['visits'] = 1;
+assert(test_session_get('visits') === 1);
+assert(test_session_remove('roles'));
+assert(!test_session_remove('roles'));
+session_write_close();
+assert(test_session_stored('first') === 'user|s:5:"alice";visits|i:1;');
+
+// Tests sessions are read back from the handler
+session_id('first');
+session_start();
+assert($_SESSION === ['user' => 'alice', 'visits' => 1]);
+session_destroy();
+assert(test_session_stored('first') === null);
+
+// Tests errors of the handler are thrown
+session_id('broken');
+assert_exception_thrown(fn () => session_start());
@@ -0,0 +1,4 @@
+#[test]
+fn session_works() {
+    crate::integration::run_php("session.php");
+}
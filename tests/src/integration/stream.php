@@ -0,0 +1,17 @@
+<?php
+
+require('_utils.php');
+
+$handle = fopen('php://memory', 'w+');
+test_stream_write($handle, 'Hello, world!');
+assert(ftell($handle) === 13);
+
+rewind($handle);
+assert(test_stream_read($handle) === 'Hello, world!');
+
+fseek($handle, 7);
+assert(test_stream_read($handle) === 'world!');
+
+assert(test_stream_roundtrip('lorem ipsum') === 'lorem ipsum');
+
+assert_exception_thrown(fn () => test_stream_read('not a stream'));
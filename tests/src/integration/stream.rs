@@ -0,0 +1,4 @@
+#[test]
+fn stream_works() {
+    crate::integration::run_php("stream.php");
+}
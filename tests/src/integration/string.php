@@ -4,3 +4,76 @@ require('_utils.php');
 
 assert(test_str('abc') === 'abc');
 assert(test_string('abc') === 'abc');
+
+$out = test_writer(10000);
+assert(strlen($out) === 98890);
+assert(str_starts_with($out, "line 0\nline 1\n"));
+assert(str_ends_with($out, "line 9999\n"));
+assert(test_writer(0) === '');
+
+assert(test_fmt_writer('php', 2) === 'hello php #0;hello php #1;');
+assert(test_fmt_writer('php', 0) === '');
+
+// Tests strings are built from the string representation of values
+class BuilderPart
+{
+    public function __toString(): string
+    {
+        return 'obj';
+    }
+}
+
+assert(test_str_builder(['a', 1, 1.5, true, null, new BuilderPart]) === 'a, 1, 1.5, 1, , obj');
+assert(test_str_builder([]) === '');
+assert_exception_thrown(fn () => test_str_builder([new stdClass]));
+
+// Tests paths are passed as byte strings, including non UTF-8 names
+if (PHP_OS_FAMILY !== 'Windows') {
+    assert(test_path_join('/tmp', 'file.txt') === '/tmp/file.txt');
+    assert(test_path_join('/tmp', "caf\xe9") === "/tmp/caf\xe9");
+    assert(test_path_file_name("/tmp/caf\xe9") === "caf\xe9");
+    assert(test_path_file_name('/') === null);
+}
+
+// Tests persistent strings are copied into the request when returned
+assert(test_persistent_string_swap('first') === null);
+assert(test_persistent_string_swap('second') === 'first');
+$name = test_persistent_string_swap('third');
+assert($name === 'second');
+$name .= '!';
+assert(test_persistent_string_swap('fourth') === 'third');
+
+// Tests strings are searched and split without UTF-8 validation
+assert(test_str_search("\xff\x00abc\x00", "\x00") === [0, 1, 1]);
+assert(test_str_search('hello world', 'hello') === [1, 0, 0]);
+assert(test_str_search('hello world', 'world') === [0, 1, 6]);
+assert(test_str_search('hello', 'xyz') === [0, 0, -1]);
+assert(test_str_split("a,b,,\xff", ',') === explode(',', "a,b,,\xff"));
+assert(test_str_split('a::b::', '::') === ['a', 'b', '']);
+assert(test_str_split('', ',') === ['']);
+
+// Tests substrings are copied directly from the string
+assert(test_substr('hello, world!', 7, 12) === 'world');
+assert(test_substr("\xff\x00abc", 1, 3) === "\x00a");
+assert(test_substr('abc', 3, 3) === '');
+assert_exception_thrown(fn () => test_substr('abc', 2, 4));
+
+// Tests case conversion only affects ASCII letters, as strtolower() does
+assert(test_str_case('Hello World') === ['hello world', 'HELLO WORLD']);
+assert(test_str_case("Caf\xc3\xa9 \xff") === [strtolower("Caf\xc3\xa9 \xff"), strtoupper("Caf\xc3\xa9 \xff")]);
+assert(test_str_case('') === ['', '']);
+
+// Tests strings can be used as map keys without being copied
+$words = ['apple', 'pear', 'apple', str_repeat('ap', 1) . 'ple', 'fig'];
+assert(test_str_key_counts($words, 'apple') === [3, 3]);
+assert(test_str_key_counts($words, 'plum') === [3, 0]);
+assert(test_str_key_counts([], 'apple') === [0, 0]);
+
+// Tests strings are converted between encodings by mbstring or iconv
+if (extension_loaded('mbstring') || extension_loaded('iconv')) {
+    assert(test_convert_encoding("caf\xe9", 'ISO-8859-1', 'UTF-8') === 'café');
+    assert(test_convert_encoding('café', 'UTF-8', 'ISO-8859-1') === "caf\xe9");
+    assert_exception_thrown(fn () => test_convert_encoding('abc', 'UTF-8', 'not-an-encoding'));
+} else {
+    assert_exception_thrown(fn () => test_convert_encoding('abc', 'UTF-8', 'ISO-8859-1'));
+}
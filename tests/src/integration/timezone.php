@@ -0,0 +1,29 @@
+<?php
+
+require('_utils.php');
+
+// Tests the default time zone follows the configuration
+date_default_timezone_set('America/New_York');
+assert(test_timezone_default() === 'America/New_York');
+assert(test_timezone_offset(null, 0) === ['America/New_York', '-18000', 'false', 'EST']);
+
+// Tests the timezone database
+$identifiers = test_timezone_identifiers();
+assert($identifiers === timezone_identifiers_list());
+assert(in_array('Europe/Amsterdam', $identifiers, true));
+
+// Tests offsets follow daylight saving time
+assert(test_timezone_offset('Europe/Amsterdam', 1700000000) === ['Europe/Amsterdam', '3600', 'false', 'CET']);
+assert(test_timezone_offset('Europe/Amsterdam', 1690000000) === ['Europe/Amsterdam', '7200', 'true', 'CEST']);
+assert(test_timezone_offset('UTC', 1700000000) === ['UTC', '0', 'false', 'UTC']);
+
+// Tests offset and abbreviation time zones
+assert(test_timezone_offset('+05:30', 0) === ['+05:30', '19800', 'false', '+05:30']);
+assert(test_timezone_offset('EST', 0)[1] === '-18000');
+
+// Tests `DateTimeZone` objects passed from PHP
+assert(test_timezone_object(new DateTimeZone('Asia/Tokyo')) === 'Asia/Tokyo');
+assert_exception_thrown(fn () => test_timezone_object(new stdClass()));
+
+// Tests unknown time zones are rejected
+assert_exception_thrown(fn () => test_timezone_offset('Mars/Olympus_Mons', 0));
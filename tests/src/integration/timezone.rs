@@ -0,0 +1,4 @@
+#[test]
+fn timezone_works() {
+    crate::integration::run_php("timezone.php");
+}
@@ -1,6 +1,38 @@
 #![cfg_attr(windows, feature(abi_vectorcall))]
-use ext_php_rs::{binary::Binary, prelude::*, types::ZendObject, types::Zval};
+use ext_php_rs::{
+    args::Arg,
+    binary::Binary,
+    boxed::ZBox,
+    builders::{ClassBuilder, FunctionBuilder},
+    class::{
+        CastResult, CastType, PhpArrayAccess, PhpCast, PhpCompare, PhpDebugInfo, PhpNativeIterator,
+        PhpPropertyObserver, RegisteredClass,
+    },
+    convert::{FromZendObject, IntoZval, IntoZvalDyn},
+    enum_::{Discriminant, RegisteredEnum},
+    ffi::ZEND_ECHO,
+    flag_set::FlagSet,
+    flags::{ClassFlags, DataType, JsonFlags, MethodFlags, PropertyFlags},
+    pack::{Packer, Unpacker},
+    persistent::PersistentHandle,
+    prelude::*,
+    types::{
+        PersistentString, ZendHashTable, ZendStr, ZendStrBuilder, ZendStrKey, Zval, ZvalWriter,
+    },
+    types::{ZendClassObject, ZendObject},
+    zend::{
+        opcache, remove_user_opcode_handler, set_user_opcode_handler, ClassEntry, Constant,
+        ExecuteData, FcallObserver, Ini, IniStage, OpcodeAction, ProcessGlobals, Stream,
+    },
+};
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicI64, AtomicPtr, Ordering};
+use std::sync::Mutex;
+use tracing_subscriber::layer::SubscriberExt;
 
 #[php_function]
 pub fn test_str(a: &str) -> &str {
@@ -37,16 +69,302 @@ pub fn test_array(a: Vec<String>) -> Vec<String> {
     a
 }
 
+#[php_function]
+pub fn test_array_packed(ints: Vec<i64>, floats: Vec<f64>, bools: Vec<bool>) -> Vec<f64> {
+    ints.into_iter()
+        .zip(floats)
+        .zip(bools)
+        .map(|((i, f), b)| if b { i as f64 * f } else { 0.0 })
+        .collect()
+}
+
 #[php_function]
 pub fn test_array_assoc(a: HashMap<String, String>) -> HashMap<String, String> {
     a
 }
 
+#[php_function]
+pub fn test_array_compact(array: &ZendHashTable, additional: i64) -> ZBox<ZendHashTable> {
+    let mut array = array.to_owned();
+    array.reserve(additional as usize);
+    array.shrink_to_fit();
+    array
+}
+
+#[php_function]
+pub fn test_array_capacity(additional: i64) -> Vec<i64> {
+    let mut array = ZendHashTable::new();
+    array.push(1).unwrap();
+    array.reserve(additional as usize);
+    let reserved = array.capacity() as i64;
+    array.shrink_to_fit();
+    vec![reserved, array.capacity() as i64]
+}
+
+#[php_function]
+pub fn test_array_batch(a: Vec<i64>, b: Vec<i64>) -> PhpResult<ZBox<ZendHashTable>> {
+    let mut array = ZendHashTable::new();
+    array.push_batch(&a)?;
+    array.push_batch(&b)?;
+    Ok(array)
+}
+
+#[php_function]
+pub fn test_array_list(len: i64) -> PhpResult<ZBox<ZendHashTable>> {
+    Ok(ZendHashTable::from_iter_packed(
+        (0..len).filter(|i| i % 2 == 0),
+    )?)
+}
+
+#[php_function]
+pub fn test_array_path(config: &ZendHashTable, host: &str) -> PhpResult<ZBox<ZendHashTable>> {
+    let mut config = config.to_owned();
+    let previous = config
+        .get_path_as::<String>(&["db", "host"])
+        .unwrap_or_default();
+    config.insert_path(&["db", "host"], host)?;
+    config.insert_path(&["db", "servers", "0"], host)?;
+    config.insert_path(&["db", "previous", "host"], previous)?;
+    Ok(config)
+}
+
+#[php_function]
+pub fn test_array_empty_path() -> PhpResult {
+    ZendHashTable::new().insert_path(&[], 1)?;
+    Ok(())
+}
+
+#[php_function]
+pub fn test_array_merge(
+    a: &ZendHashTable,
+    b: &ZendHashTable,
+) -> PhpResult<Vec<ZBox<ZendHashTable>>> {
+    let mut merged = a.to_owned();
+    merged.merge(b)?;
+    let mut merged_recursive = a.to_owned();
+    merged_recursive.merge_recursive(b)?;
+    Ok(vec![merged, merged_recursive, a.diff_keys(b)])
+}
+
+#[php_function]
+pub fn test_array_keys(a: &ZendHashTable) -> Vec<String> {
+    a.keys_vec().iter().map(ToString::to_string).collect()
+}
+
+#[php_function]
+pub fn test_array_values(a: &ZendHashTable) -> PhpResult<Vec<i64>> {
+    a.values_to::<i64>()
+        .map_err(|(index, _)| format!("Invalid value at position {index}").into())
+}
+
+#[php_function]
+pub fn test_array_options(a: &ZendHashTable) -> PhpResult<HashMap<String, i64>> {
+    a.try_into_map::<String, i64>()
+        .map_err(|(key, _)| format!("Invalid option {key}").into())
+}
+
+#[php_function]
+pub fn test_array_index_sum(a: &ZendHashTable) -> PhpResult<i64> {
+    let map = a
+        .try_into_map::<i64, i64>()
+        .map_err(|(key, _)| format!("Invalid index {key}"))?;
+    Ok(map.into_iter().map(|(k, v)| k * v).sum())
+}
+
+#[php_function]
+pub fn test_array_separate(a: &Zval) -> PhpResult<Zval> {
+    let mut copy = a.shallow_clone();
+    copy.separate_array()
+        .ok_or("Expected an array")?
+        .push("separated")?;
+    Ok(copy)
+}
+
+#[php_function]
+pub fn test_array_index(config: &ZendHashTable) -> String {
+    let host = config["host"].str().unwrap_or_default();
+    let port = config.get_or_default::<i64>("port");
+    let scheme = config.get_index_or_default::<String>(0);
+    format!("{scheme}://{host}:{port}")
+}
+
+thread_local! {
+    static PERSISTENT_CACHE: RefCell<ZBox<ZendHashTable>> =
+        // SAFETY: The cache is only accessed from Rust, on the thread which created it.
+        RefCell::new(unsafe { ZendHashTable::new_persistent() });
+}
+
+#[php_function]
+pub fn test_persistent_cache_set(key: &str, value: Vec<String>) -> PhpResult {
+    PERSISTENT_CACHE.with(|cache| cache.borrow_mut().insert(key, value))?;
+    Ok(())
+}
+
+#[php_function]
+pub fn test_persistent_cache_set_zval(key: &str, value: &Zval) -> PhpResult {
+    PERSISTENT_CACHE.with(|cache| cache.borrow_mut().insert(key, value.shallow_clone()))?;
+    Ok(())
+}
+
+#[php_function]
+pub fn test_persistent_cache_get(key: &str) -> Option<Vec<String>> {
+    PERSISTENT_CACHE.with(|cache| cache.borrow().get(key).and_then(|val| val.extract()))
+}
+
+static PERSISTENT_NAME: Mutex<Option<PersistentString>> = Mutex::new(None);
+
+#[php_function]
+pub fn test_persistent_string_swap(name: &str) -> Option<ZBox<ZendStr>> {
+    let mut last = PERSISTENT_NAME.lock().unwrap();
+    let previous = last.as_ref().map(PersistentString::to_request_str);
+    *last = Some(PersistentString::new(name));
+    previous
+}
+
 #[php_function]
 pub fn test_binary(a: Binary<u32>) -> Binary<u32> {
     a
 }
 
+#[php_function]
+pub fn test_writer(lines: i64) -> PhpResult<ZvalWriter> {
+    let mut out = ZvalWriter::new();
+    for i in 0..lines {
+        writeln!(out, "line {}", i).map_err(|e| e.to_string())?;
+    }
+    Ok(out)
+}
+
+fn write_greetings(out: &mut impl std::fmt::Write, name: &str, count: i64) -> std::fmt::Result {
+    for i in 0..count {
+        write!(out, "hello {name} #{i};")?;
+    }
+    Ok(())
+}
+
+#[php_function]
+pub fn test_fmt_writer(name: &str, count: i64) -> PhpResult<ZvalWriter> {
+    let mut out = ZvalWriter::new();
+    write_greetings(&mut out, name, count).map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+#[php_function]
+pub fn test_str_builder(parts: &ZendHashTable) -> PhpResult<ZBox<ZendStr>> {
+    let mut builder = ZendStrBuilder::new();
+    for (i, part) in parts.values().enumerate() {
+        if i > 0 {
+            builder.push_str(", ");
+        }
+        builder.push_zval_str(part)?;
+    }
+    Ok(builder.finish())
+}
+
+#[php_function]
+pub fn test_str_key_counts(words: &ZendHashTable, word: &str) -> Vec<usize> {
+    let mut counts: HashMap<ZendStrKey, usize> = HashMap::new();
+    for key in words.values().filter_map(|zv| zv.extract::<ZendStrKey>()) {
+        *counts.entry(key).or_insert(0) += 1;
+    }
+    vec![counts.len(), counts.get(word).copied().unwrap_or_default()]
+}
+
+#[php_function]
+pub fn test_str_search(value: &Zval, needle: &[u8]) -> PhpResult<Vec<i64>> {
+    let s = value.zend_str().ok_or("Expected a string")?;
+    Ok(vec![
+        s.starts_with(needle) as i64,
+        s.ends_with(needle) as i64,
+        s.find(needle).map_or(-1, |pos| pos as i64),
+    ])
+}
+
+#[php_function]
+pub fn test_str_split(value: &Zval, separator: &[u8]) -> PhpResult<Vec<ZBox<ZendStr>>> {
+    let s = value.zend_str().ok_or("Expected a string")?;
+    Ok(s.split(separator)
+        .map(|part| ZendStr::new(part, false))
+        .collect())
+}
+
+#[php_function]
+pub fn test_substr(value: &Zval, start: usize, end: usize) -> PhpResult<ZBox<ZendStr>> {
+    let s = value.zend_str().ok_or("Expected a string")?;
+    if start > end || end > s.len() {
+        return Err("Range is out of bounds".into());
+    }
+    let sub = s.substr(start..end);
+    assert_eq!(&s[start..end], sub.as_bytes());
+    Ok(sub)
+}
+
+#[php_function]
+pub fn test_str_case(value: &Zval) -> PhpResult<Vec<ZBox<ZendStr>>> {
+    let s = value.zend_str().ok_or("Expected a string")?;
+    Ok(vec![
+        s.to_lowercase().into_owned(),
+        s.to_uppercase().into_owned(),
+    ])
+}
+
+#[php_function]
+pub fn test_convert_encoding(value: &Zval, from: &str, to: &str) -> PhpResult<ZBox<ZendStr>> {
+    let s = value.zend_str().ok_or("Expected a string")?;
+    Ok(s.convert_encoding(from, to)?)
+}
+
+#[php_function]
+pub fn test_path_join(base: PathBuf, name: &OsStr) -> PathBuf {
+    base.join(name)
+}
+
+#[php_function]
+pub fn test_path_file_name(path: &Path) -> Option<&OsStr> {
+    path.file_name()
+}
+
+#[php_function]
+pub fn test_pack(kind: u16, id: u32, tag: &[u8], ratio: f64) -> Packer {
+    Packer::new()
+        .u16_be(kind)
+        .u32_le(id)
+        .padded(tag, 4, b' ')
+        .nul(2)
+        .f64_be(ratio)
+}
+
+#[php_function]
+pub fn test_unpack(data: &[u8]) -> PhpResult<Vec<f64>> {
+    let mut data = Unpacker::new(data);
+    let kind = data.u16_be()?;
+    let id = data.u32_le()?;
+    data.skip(6)?;
+    let ratio = data.f64_be()?;
+    Ok(vec![kind.into(), id.into(), ratio])
+}
+
+#[php_function]
+pub fn test_unpack_trimmed(data: &[u8]) -> PhpResult<Vec<String>> {
+    let mut data = Unpacker::new(data);
+    let padded = data.bytes(4)?;
+    let trimmed = data.trimmed(6)?;
+    Ok(vec![
+        String::from_utf8_lossy(padded).into_owned(),
+        String::from_utf8_lossy(trimmed).into_owned(),
+    ])
+}
+
+#[php_function]
+pub fn test_bytes(a: &[u8]) -> &[u8] {
+    a
+}
+
+#[php_function]
+pub fn test_bytes_len(a: &[u8]) -> usize {
+    a.len()
+}
+
 #[php_function]
 pub fn test_nullable(a: Option<String>) -> Option<String> {
     a
@@ -57,6 +375,29 @@ pub fn test_object(a: &mut ZendObject) -> &mut ZendObject {
     a
 }
 
+#[php_function]
+pub fn test_instantiate(class: String, args: &ZendHashTable) -> PhpResult<ZBox<ZendObject>> {
+    let ce = ClassEntry::try_find(&class).ok_or("Class not found")?;
+    let args = args.values().map(|v| v as &dyn IntoZvalDyn).collect();
+    Ok(ce.instantiate(args)?)
+}
+
+#[derive(ZvalConvert)]
+#[php(rename_all = "camelCase")]
+pub struct TestRecord {
+    first_name: String,
+    #[php(rename = "years")]
+    age: i64,
+}
+
+#[php_function]
+pub fn test_record(record: TestRecord) -> TestRecord {
+    TestRecord {
+        first_name: record.first_name.to_uppercase(),
+        age: record.age + 1,
+    }
+}
+
 #[php_function]
 pub fn test_closure() -> Closure {
     Closure::wrap(Box::new(|a| a) as Box<dyn Fn(String) -> String>)
@@ -72,16 +413,158 @@ pub fn test_callable(call: ZendCallable, a: String) -> Zval {
     call.try_call(vec![&a]).expect("Failed to call function")
 }
 
+#[php_function]
+pub fn test_channel_produce(channel: &Channel, count: i64) {
+    let sender = channel.sender();
+    std::thread::spawn(move || {
+        for i in 0..count {
+            sender.send(i.into()).expect("Failed to send message");
+        }
+    })
+    .join()
+    .expect("Producer thread panicked");
+}
+
+static OBSERVED_BEGIN: AtomicI64 = AtomicI64::new(0);
+static OBSERVED_END: AtomicI64 = AtomicI64::new(0);
+static OBSERVED_RETURN: AtomicI64 = AtomicI64::new(0);
+
+struct TestObserver;
+
+impl FcallObserver for TestObserver {
+    fn should_observe(&self, execute_data: &ExecuteData) -> bool {
+        execute_data
+            .function()
+            .and_then(|func| func.name())
+            .is_some_and(|name| *name == "observed_function" || *name == "panicking_function")
+    }
+
+    fn begin(&self, execute_data: &ExecuteData) {
+        let name = execute_data.function().and_then(|func| func.name());
+        if name.is_some_and(|name| *name == "panicking_function") {
+            panic!("observer of panicking_function");
+        }
+        OBSERVED_BEGIN.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn end(&self, _: &ExecuteData, retval: Option<&Zval>) {
+        OBSERVED_END.fetch_add(1, Ordering::SeqCst);
+        if let Some(val) = retval.and_then(|retval| retval.long()) {
+            OBSERVED_RETURN.fetch_add(val, Ordering::SeqCst);
+        }
+    }
+}
+
+#[php_function]
+pub fn test_observer_counts() -> Vec<i64> {
+    vec![
+        OBSERVED_BEGIN.load(Ordering::SeqCst),
+        OBSERVED_END.load(Ordering::SeqCst),
+        OBSERVED_RETURN.load(Ordering::SeqCst),
+    ]
+}
+
+static ECHOES: AtomicI64 = AtomicI64::new(0);
+
+#[php_function]
+pub fn test_opcode_handler_install() -> PhpResult {
+    // The first handler replaces itself while it is running.
+    set_user_opcode_handler(ZEND_ECHO as u8, |_| {
+        ECHOES.fetch_add(1, Ordering::SeqCst);
+        set_user_opcode_handler(ZEND_ECHO as u8, |_| {
+            ECHOES.fetch_add(10, Ordering::SeqCst);
+            OpcodeAction::Dispatch
+        })
+        .expect("failed to replace opcode handler");
+        OpcodeAction::Dispatch
+    })?;
+    Ok(())
+}
+
+#[php_function]
+pub fn test_opcode_handler_panic() -> PhpResult {
+    set_user_opcode_handler(ZEND_ECHO as u8, |_| panic!("opcode handler"))?;
+    Ok(())
+}
+
+#[php_function]
+pub fn test_opcode_handler_remove() -> PhpResult {
+    remove_user_opcode_handler(ZEND_ECHO as u8)?;
+    Ok(())
+}
+
+#[php_function]
+pub fn test_opcode_echoes() -> i64 {
+    ECHOES.load(Ordering::SeqCst)
+}
+
+#[php_function]
+pub fn test_opcache_status() -> HashMap<String, Zval> {
+    let mut info = HashMap::new();
+    let mut set = |key: &str, val: &dyn IntoZvalDyn| {
+        info.insert(key.to_string(), val.as_zval(false).unwrap());
+    };
+    set("loaded", &opcache::is_loaded());
+    set("enabled", &opcache::is_enabled());
+    set("cached", &opcache::is_script_cached(file!()));
+    set("invalidated", &opcache::invalidate(file!(), true));
+    set(
+        "scripts",
+        &opcache::status().map(|status| status.cached_scripts as i64),
+    );
+    set(
+        "immutable",
+        &ClassEntry::try_find("TestClass")
+            .is_some_and(|ce| ce.flags().contains(ClassFlags::Immutable)),
+    );
+    info
+}
+
+#[derive(FlattenProps)]
+pub struct TestPoint {
+    #[prop]
+    x: i64,
+    #[prop]
+    y: i64,
+}
+
+#[php_class]
+pub struct TestRect {
+    #[prop]
+    width: i64,
+    #[prop(flatten, prefix = "origin_")]
+    origin: TestPoint,
+}
+
+#[php_impl]
+impl TestRect {
+    pub fn __construct(x: i64, y: i64, width: i64) -> Self {
+        Self {
+            width,
+            origin: TestPoint { x, y },
+        }
+    }
+}
+
 #[php_class]
 pub struct TestClass {
     string: String,
     number: i32,
-    #[prop]
+    /// Whether the flag is set.
+    #[prop(default = true)]
     boolean: bool,
+    #[prop(vis = "protected")]
+    secret: i64,
 }
 
 #[php_impl]
 impl TestClass {
+    const TAGS: [&'static str; 2] = ["lorem", "ipsum"];
+    const MATRIX: [[i64; 2]; 2] = [[1, 2], [3, 4]];
+    const SUIT: TestSuit = TestSuit::Spades;
+    #[static_prop(rename = "instances")]
+    const INSTANCES: i64 = 0;
+
     #[getter]
     pub fn get_string(&self) -> String {
         self.string.to_string()
@@ -101,65 +584,1164 @@ impl TestClass {
     pub fn set_number(&mut self, number: i32) {
         self.number = number;
     }
+
+    #[getter(cached)]
+    pub fn get_label(&self) -> String {
+        format!("{}: {}", self.string, self.number)
+    }
+
+    pub fn refresh_label(#[this] this: &mut ZendClassObject<TestClass>) {
+        this.invalidate_prop("label");
+    }
 }
 
 #[php_function]
 pub fn test_class(string: String, number: i32) -> TestClass {
+    let ce = TestClass::get_metadata().ce();
+    let instances = ce
+        .get_static_property("instances")
+        .and_then(|instances| instances.long())
+        .unwrap_or_default();
+    ce.set_static_property("instances", instances + 1)
+        .expect("failed to count instances");
+
     TestClass {
         string,
         number,
         boolean: true,
+        secret: 42,
     }
 }
 
-#[php_module]
-pub fn get_module(module: ModuleBuilder) -> ModuleBuilder {
-    module
+#[php_class]
+pub struct TestGcHolder {
+    #[gc]
+    value: Option<Zval>,
 }
 
-#[cfg(test)]
-mod integration {
-    use std::env;
+#[php_impl]
+impl TestGcHolder {
+    pub fn __construct() -> Self {
+        Self { value: None }
+    }
 
-    use std::process::Command;
-    use std::sync::Once;
+    pub fn hold(&mut self, value: &Zval) {
+        self.value = Some(value.shallow_clone());
+    }
+}
 
-    static BUILD: Once = Once::new();
+#[php_class]
+pub struct TestAnimal {
+    #[prop]
+    name: String,
+}
 
-    fn setup() {
-        BUILD.call_once(|| {
-            assert!(Command::new("cargo")
-                .arg("build")
-                .output()
-                .expect("failed to build extension")
-                .status
-                .success());
-        });
+#[php_impl]
+impl TestAnimal {
+    pub fn __construct(name: String) -> Self {
+        Self { name }
     }
 
-    pub fn run_php(file: &str) -> bool {
-        setup();
-        let mut path = env::current_dir().expect("Could not get cwd");
-        path.pop();
-        path.push("target");
-        path.push("debug");
-        path.push(if std::env::consts::DLL_EXTENSION == "dll" {
-            "tests"
-        } else {
-            "libtests"
-        });
-        path.set_extension(std::env::consts::DLL_EXTENSION);
-        let output = Command::new("php")
-            .arg(format!("-dextension={}", path.to_str().unwrap()))
-            .arg("-dassert.active=1")
-            .arg("-dassert.exception=1")
-            .arg("-dzend.assertions=1")
-            .arg(format!("src/integration/{}", file))
-            .output()
-            .expect("failed to run php file");
-        if output.status.success() {
-            true
-        } else {
+    pub fn describe(&self) -> String {
+        format!("{} is an animal", self.name)
+    }
+
+    pub fn rename(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn object_name(#[this] this: &mut ZendClassObject<TestAnimal>) -> String {
+        this.name.clone()
+    }
+}
+
+#[php_class]
+#[extends_class(TestAnimal)]
+pub struct TestDog {
+    animal: TestAnimal,
+    #[prop]
+    tricks: i64,
+}
+
+impl std::ops::Deref for TestDog {
+    type Target = TestAnimal;
+
+    fn deref(&self) -> &TestAnimal {
+        &self.animal
+    }
+}
+
+impl std::ops::DerefMut for TestDog {
+    fn deref_mut(&mut self) -> &mut TestAnimal {
+        &mut self.animal
+    }
+}
+
+#[php_impl]
+impl TestDog {
+    pub fn __construct(name: String, tricks: i64) -> Self {
+        Self {
+            animal: TestAnimal::__construct(name),
+            tricks,
+        }
+    }
+
+    pub fn bark(&self) -> String {
+        format!("{} barks", self.name)
+    }
+}
+
+#[php_function]
+pub fn test_animal_name(animal: &TestAnimal) -> String {
+    animal.name.clone()
+}
+
+#[php_function]
+pub fn test_dog_tricks(obj: &ZendObject) -> Option<i64> {
+    obj.downcast_ref::<TestDog>().map(|dog| dog.tricks)
+}
+
+#[php_function]
+pub fn test_downcast_animal(obj: &mut ZendObject) -> Option<String> {
+    let animal = obj.downcast_mut::<TestAnimal>()?;
+    animal.rename(animal.name.to_uppercase());
+    Some(animal.describe())
+}
+
+#[php_class]
+#[extends(ext_php_rs::zend::ce::exception())]
+pub struct TestHttpError {
+    #[prop]
+    status: i64,
+}
+
+#[php_function]
+pub fn test_http_error(status: i64) -> PhpResult<ZBox<ZendClassObject<TestHttpError>>> {
+    let mut err = ZendClassObject::new(TestHttpError { status });
+    err.call_parent_method(
+        "__construct",
+        vec![&format!("HTTP error {}", status), &status],
+    )?;
+    Ok(err)
+}
+
+#[php_class]
+pub struct TestScopedCaller;
+
+#[php_impl]
+impl TestScopedCaller {
+    pub fn __construct() -> Self {
+        Self
+    }
+
+    #[private]
+    pub fn secret(&self) -> &'static str {
+        "secret"
+    }
+
+    pub fn call(
+        #[this] this: &mut ZendClassObject<TestScopedCaller>,
+        method: String,
+    ) -> PhpResult<Zval> {
+        Ok(this.call_method_scoped(&method, vec![])?)
+    }
+}
+
+#[php_class]
+pub struct TestDelegate {
+    #[delegate]
+    #[gc]
+    inner: Zval,
+}
+
+#[php_impl]
+impl TestDelegate {
+    pub fn __construct(inner: &Zval) -> Self {
+        Self {
+            inner: inner.shallow_clone(),
+        }
+    }
+
+    pub fn describe(&self) -> &'static str {
+        "delegate"
+    }
+}
+
+#[php_class]
+pub struct TestMarker;
+
+#[php_impl]
+impl TestMarker {
+    pub fn __construct() -> Self {
+        Self
+    }
+
+    pub fn kind(&self) -> &'static str {
+        "marker"
+    }
+}
+
+#[php_class]
+pub struct TestMeters(#[prop(name = "value")] f64, #[prop(name = "unit")] String);
+
+#[php_impl]
+impl TestMeters {
+    pub fn __construct(value: f64) -> Self {
+        Self(value, "m".into())
+    }
+
+    pub fn double(&mut self) {
+        self.0 *= 2.0;
+    }
+}
+
+#[php_class(debug_info)]
+pub struct TestDebugInfo {
+    secret: String,
+}
+
+#[php_impl]
+impl TestDebugInfo {
+    pub fn __construct(secret: String) -> Self {
+        Self { secret }
+    }
+}
+
+impl PhpDebugInfo for TestDebugInfo {
+    fn debug_info(&self, info: &mut ZendHashTable) -> PhpResult {
+        info.insert("length", self.secret.len() as i64)?;
+        Ok(())
+    }
+}
+
+#[php_class(cast)]
+pub struct TestCast {
+    value: i64,
+}
+
+#[php_impl]
+impl TestCast {
+    pub fn __construct(value: i64) -> Self {
+        Self { value }
+    }
+}
+
+impl PhpCast for TestCast {
+    fn cast(&self, ty: CastType) -> CastResult {
+        Ok(match ty {
+            CastType::Long | CastType::Number => Some(self.value.into_zval(false)?),
+            CastType::String => Some(format!("value: {}", self.value).into_zval(false)?),
+            CastType::Bool => Some((self.value != 0).into_zval(false)?),
+            CastType::Double => None,
+        })
+    }
+}
+
+#[php_class(compare)]
+pub struct TestCompare {
+    #[prop(rename = "amount", default)]
+    value: i64,
+}
+
+#[php_impl]
+impl TestCompare {
+    pub fn __construct(value: i64) -> Self {
+        Self { value }
+    }
+}
+
+impl PhpCompare for TestCompare {
+    fn compare(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        // Negative values are treated as unordered.
+        if self.value < 0 || other.value < 0 {
+            return None;
+        }
+        Some(self.value.cmp(&other.value))
+    }
+}
+
+#[php_class(array_access)]
+pub struct TestArrayAccess {
+    values: Vec<i64>,
+}
+
+#[php_impl]
+impl TestArrayAccess {
+    pub fn __construct() -> Self {
+        Self { values: vec![] }
+    }
+}
+
+impl TestArrayAccess {
+    fn index(&self, offset: &Zval) -> Option<usize> {
+        offset
+            .long()
+            .and_then(|idx| usize::try_from(idx).ok())
+            .filter(|idx| *idx < self.values.len())
+    }
+}
+
+impl PhpArrayAccess for TestArrayAccess {
+    fn offset_exists(&self, offset: &Zval) -> PhpResult<bool> {
+        Ok(self.index(offset).is_some())
+    }
+
+    fn offset_get(&self, offset: &Zval) -> PhpResult<Zval> {
+        let idx = self.index(offset).ok_or("Undefined offset")?;
+        Ok(self.values[idx].into_zval(false)?)
+    }
+
+    fn offset_set(&mut self, offset: Option<&Zval>, value: &Zval) -> PhpResult {
+        let value = value.long().ok_or("Values must be integers")?;
+        match offset {
+            Some(offset) => {
+                let idx = self.index(offset).ok_or("Undefined offset")?;
+                self.values[idx] = value;
+            }
+            None => self.values.push(value),
+        }
+        Ok(())
+    }
+
+    fn offset_unset(&mut self, offset: &Zval) -> PhpResult {
+        if let Some(idx) = self.index(offset) {
+            self.values.remove(idx);
+        }
+        Ok(())
+    }
+}
+
+#[php_class(observe)]
+pub struct TestObserved {
+    #[prop]
+    name: String,
+    changes: Vec<String>,
+}
+
+#[php_impl]
+impl TestObserved {
+    pub fn __construct() -> Self {
+        Self {
+            name: String::new(),
+            changes: vec![],
+        }
+    }
+
+    #[setter]
+    pub fn set_label(&mut self, label: String) {
+        self.name = label;
+    }
+
+    pub fn changes(&self) -> Vec<String> {
+        self.changes.clone()
+    }
+}
+
+impl PhpPropertyObserver for TestObserved {
+    fn property_changed(&mut self, name: &str) {
+        self.changes.push(name.to_string());
+    }
+}
+
+#[php_class(iterator)]
+pub struct TestRange {
+    start: i64,
+    end: i64,
+}
+
+#[php_impl]
+impl TestRange {
+    pub fn __construct(start: i64, end: i64) -> Self {
+        Self { start, end }
+    }
+}
+
+impl PhpNativeIterator for TestRange {
+    type Key = String;
+    type Value = i64;
+
+    fn php_iter(&self) -> Box<dyn Iterator<Item = (String, i64)>> {
+        Box::new((self.start..self.end).map(|n| (format!("n{n}"), n)))
+    }
+}
+
+#[php_function]
+pub fn test_set_superglobals() -> PhpResult {
+    let mut globals = ProcessGlobals::get_mut();
+    globals.http_get_vars_mut().insert("ext", "php-rs")?;
+    globals.http_post_vars_mut().insert("ext", "php-rs")?;
+    globals.set_server_var("EXT_PHP_RS", true)?;
+    globals.set_env_var("EXT_PHP_RS", "1")?;
+    Ok(())
+}
+
+#[php_function]
+pub fn test_stream_read(stream: &mut Stream) -> PhpResult<String> {
+    let mut contents = String::new();
+    stream
+        .read_to_string(&mut contents)
+        .map_err(|e| e.to_string())?;
+    Ok(contents)
+}
+
+#[php_function]
+pub fn test_stream_write(stream: &mut Stream, data: String) -> PhpResult {
+    stream
+        .write_all(data.as_bytes())
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[php_function]
+pub fn test_stream_roundtrip(data: String) -> PhpResult<String> {
+    let mut stream = Stream::open("php://temp", "w+b")?;
+    stream
+        .write_all(data.as_bytes())
+        .map_err(|e| e.to_string())?;
+    stream.rewind().map_err(|e| e.to_string())?;
+    test_stream_read(&mut stream)
+}
+
+#[php_function]
+pub fn test_ini_set(name: String, value: String) -> PhpResult {
+    Ini::set(&name, &value, IniStage::Runtime)?;
+    Ok(())
+}
+
+#[php_function]
+pub fn test_ini_get_bool(name: String) -> Option<bool> {
+    Ini::get_bool(&name)
+}
+
+#[php_function]
+pub fn test_ini_get_int(name: String) -> Option<i64> {
+    Ini::get_int(&name)
+}
+
+#[php_function]
+pub fn test_get_constant(name: String) -> Option<Zval> {
+    Constant::get_zval(&name)
+}
+
+#[php_function]
+pub fn test_get_class_constant(class: String, name: String) -> Option<String> {
+    Constant::get_class_constant(ClassEntry::try_find(&class)?, &name)
+}
+
+#[php_function]
+pub fn test_psr7_echo(
+    request: &mut ZendObject,
+    response_factory: &mut ZendObject,
+    stream_factory: &mut ZendObject,
+) -> PhpResult<Zval> {
+    let request = ext_php_rs::http::request_from_psr7(request)?;
+    let mut response = http::Response::builder()
+        .status(http::StatusCode::CREATED)
+        .version(request.version())
+        .header("x-method", request.method().as_str())
+        .header("x-uri", request.uri().to_string());
+    for (name, value) in request.headers() {
+        response = response.header(name, value);
+    }
+    let response = response
+        .body(request.body().to_ascii_uppercase())
+        .map_err(|e| e.to_string())?;
+    Ok(ext_php_rs::http::response_into_psr7(
+        response,
+        response_factory,
+        stream_factory,
+    )?)
+}
+
+#[php_function]
+pub fn test_psr7_forward(
+    response: &mut ZendObject,
+    request_factory: &mut ZendObject,
+    stream_factory: &mut ZendObject,
+) -> PhpResult<Zval> {
+    let response = ext_php_rs::http::response_from_psr7(response)?;
+    let request = http::Request::post(format!(
+        "https://example.com/{}",
+        response.status().as_u16()
+    ))
+    .header("content-type", "text/plain")
+    .body(response.into_body())
+    .map_err(|e| e.to_string())?;
+    Ok(ext_php_rs::http::request_into_psr7(
+        request,
+        request_factory,
+        stream_factory,
+    )?)
+}
+
+#[php_function]
+pub fn test_log(level: &str, message: &str) -> PhpResult<()> {
+    let level = level
+        .parse::<log::Level>()
+        .map_err(|_| format!("invalid log level `{level}`"))?;
+    log::log!(target: "tests", level, "{message}");
+    Ok(())
+}
+
+#[php_function]
+pub fn test_trace(message: &str, count: i64) {
+    tracing::warn!(target: "tests", count, "{message}");
+}
+
+#[php_function]
+pub fn test_log_request_id(id: Option<String>) {
+    ext_php_rs::logging::set_request_id(id);
+}
+
+#[php_function]
+pub fn test_log_from_thread(message: String) {
+    std::thread::spawn(move || log::error!(target: "tests", "{message}"))
+        .join()
+        .expect("logging thread panicked");
+}
+
+#[php_function]
+pub fn test_log_flush() {
+    ext_php_rs::logging::flush();
+}
+
+#[php_function]
+pub fn test_psr3_log(message: &str, context: HashMap<String, String>) -> PhpResult<()> {
+    let context: Vec<_> = context
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect();
+    ext_php_rs::psr3::Logger::current().log(ext_php_rs::psr3::Level::Warning, message, &context)?;
+    Ok(())
+}
+
+#[php_function]
+pub fn test_psr3_log_from_thread(message: String) {
+    let logger = ext_php_rs::psr3::Logger::current();
+    let logged = std::thread::spawn(move || {
+        logger
+            .log(ext_php_rs::psr3::Level::Debug, &message, &[])
+            .is_ok()
+    });
+    assert!(logged.join().expect("logging thread panicked"));
+}
+
+#[php_function]
+pub fn test_json_encode(
+    value: &Zval,
+    flags: u32,
+    depth: Option<usize>,
+) -> PhpResult<ZBox<ZendStr>> {
+    let flags = JsonFlags::from_bits_retain(flags);
+    Ok(ext_php_rs::zend::json::encode_with_depth(
+        value,
+        flags,
+        depth.unwrap_or(ext_php_rs::zend::json::DEFAULT_DEPTH),
+    )?)
+}
+
+#[php_function]
+pub fn test_json_decode(json: Binary<u8>, flags: u32, depth: Option<usize>) -> PhpResult<Zval> {
+    let flags = JsonFlags::from_bits_retain(flags);
+    Ok(ext_php_rs::zend::json::decode_with_depth(
+        &*json,
+        flags,
+        depth.unwrap_or(ext_php_rs::zend::json::DEFAULT_DEPTH),
+    )?)
+}
+
+fn reflection_visibility(flags: u32) -> &'static str {
+    let flags = PropertyFlags::from_bits_truncate(flags);
+    if flags.contains(PropertyFlags::Private) {
+        "private"
+    } else if flags.contains(PropertyFlags::Protected) {
+        "protected"
+    } else {
+        "public"
+    }
+}
+
+fn reflection_attributes<'a>(
+    attributes: impl Iterator<Item = &'a ext_php_rs::zend::Attribute>,
+    scope: Option<&ClassEntry>,
+) -> PhpResult<Vec<String>> {
+    attributes
+        .map(|attr| {
+            let args = attr
+                .arguments(scope)?
+                .into_iter()
+                .map(|(name, value)| {
+                    let value = ext_php_rs::zend::json::encode(&value, JsonFlags::empty())?;
+                    Ok(match name {
+                        Some(name) => format!("{}: {}", name.as_str()?, value.as_str()?),
+                        None => value.as_str()?.to_owned(),
+                    })
+                })
+                .collect::<ext_php_rs::error::Result<Vec<_>>>()?;
+            Ok(format!(
+                "attribute {}({})",
+                attr.name().as_str()?,
+                args.join(", ")
+            ))
+        })
+        .collect()
+}
+
+#[php_function]
+pub fn test_reflection_class(name: &str) -> PhpResult<Vec<String>> {
+    let ce = ClassEntry::try_find(name).ok_or("Class not found")?;
+    let mut members = vec![];
+
+    if let Some(doc) = ce.doc_comment() {
+        members.push(format!("doc {}", doc.as_str()?));
+    }
+    members.extend(reflection_attributes(ce.attributes(), Some(ce))?);
+    for method in ce.methods() {
+        let flags = method.flags();
+        members.push(format!(
+            "method {} {}{}",
+            method.name().ok_or("Method has no name")?.as_str()?,
+            reflection_visibility(flags.bits()),
+            if flags.contains(MethodFlags::Static) {
+                " static"
+            } else {
+                ""
+            }
+        ));
+    }
+    for (name, prop) in ce.properties() {
+        members.push(format!(
+            "property {} {}",
+            name,
+            reflection_visibility(prop.flags().bits())
+        ));
+        members.extend(reflection_attributes(prop.attributes(), Some(ce))?);
+    }
+    for (name, constant) in ce.constants() {
+        members.push(format!(
+            "constant {} {} = {}",
+            name,
+            reflection_visibility(constant.flags().bits()),
+            constant
+                .value()
+                .long()
+                .ok_or("Constant is not an integer")?
+        ));
+    }
+
+    members.push(format!("has_method {}", ce.method("RUN").is_some()));
+    members.push(format!(
+        "has_property {}",
+        ce.property_info("count").is_some()
+    ));
+    members.push(format!("has_constant {}", ce.constant("LIMIT").is_some()));
+    Ok(members)
+}
+
+#[php_function]
+pub fn test_reflection_function(name: &str) -> PhpResult<Option<Vec<String>>> {
+    let function = match ext_php_rs::zend::Function::find(name) {
+        Some(function) => function,
+        None => return Ok(None),
+    };
+
+    let mut info = vec![];
+    if let Some(doc) = function.doc_comment() {
+        info.push(format!("doc {}", doc.as_str()?));
+    }
+    info.extend(reflection_attributes(function.attributes(), None)?);
+    Ok(Some(info))
+}
+
+#[php_function]
+pub fn test_try_find(name: &str) -> Option<String> {
+    ClassEntry::try_find(name).map(|ce| ce.name().unwrap_or_default().to_owned())
+}
+
+#[php_function]
+pub fn test_find_or_autoload(name: &str) -> PhpResult<String> {
+    let ce = ClassEntry::find_or_autoload(name)?;
+    Ok(ce.name().unwrap_or_default().to_owned())
+}
+
+#[php_function]
+pub fn test_random_bytes(len: usize) -> PhpResult<Binary<u8>> {
+    Ok(ext_php_rs::zend::random::bytes(len)?.into())
+}
+
+#[php_function]
+pub fn test_random_int(min: i64, max: i64) -> PhpResult<i64> {
+    Ok(ext_php_rs::zend::random::int(min, max)?)
+}
+
+#[php_function]
+pub fn test_random_engine(engine: &mut ZendObject, len: usize) -> PhpResult<Option<Binary<u8>>> {
+    let mut engine = match ext_php_rs::zend::random::Engine::new(engine) {
+        Some(engine) => engine,
+        None => return Ok(None),
+    };
+    let mut buf = vec![0; len];
+    engine.fill_bytes(&mut buf)?;
+    Ok(Some(buf.into()))
+}
+
+#[php_function]
+pub fn test_random_engine_u64(engine: &mut ZendObject) -> PhpResult<String> {
+    let mut engine = ext_php_rs::zend::random::Engine::new(engine).ok_or("Not an engine")?;
+    Ok(format!("{:016x}", engine.next_u64()?))
+}
+
+#[php_function]
+pub fn test_timezone_default() -> PhpResult<String> {
+    Ok(ext_php_rs::zend::timezone::default_name()?)
+}
+
+#[php_function]
+pub fn test_timezone_identifiers() -> PhpResult<Vec<String>> {
+    Ok(ext_php_rs::zend::timezone::identifiers()?)
+}
+
+#[php_function]
+pub fn test_timezone_offset(name: Option<&str>, timestamp: i64) -> PhpResult<Vec<String>> {
+    use ext_php_rs::zend::timezone::TimeZone;
+
+    let mut tz = match name {
+        Some(name) => TimeZone::new(name)?,
+        None => TimeZone::current()?,
+    };
+    let offset = tz.offset_at(timestamp)?;
+    Ok(vec![
+        tz.name()?,
+        offset.seconds.to_string(),
+        offset.is_dst.to_string(),
+        offset.abbreviation,
+    ])
+}
+
+#[php_function]
+pub fn test_timezone_object(tz: &mut ZendObject) -> PhpResult<String> {
+    use ext_php_rs::zend::timezone::TimeZone;
+
+    let mut tz = TimeZone::from_object(tz).ok_or("Not a time zone")?;
+    Ok(tz.name()?)
+}
+
+static TEST_SESSIONS: Mutex<Option<HashMap<String, Vec<u8>>>> = Mutex::new(None);
+
+pub struct TestSessionStore;
+
+impl ext_php_rs::session::SaveHandler for TestSessionStore {
+    fn read(&self, id: &str) -> PhpResult<Option<Vec<u8>>> {
+        if id == "broken" {
+            return Err("Session store is unavailable".into());
+        }
+        let sessions = TEST_SESSIONS.lock().unwrap();
+        Ok(sessions
+            .as_ref()
+            .and_then(|sessions| sessions.get(id).cloned()))
+    }
+
+    fn write(&self, id: &str, data: &[u8]) -> PhpResult<()> {
+        let mut sessions = TEST_SESSIONS.lock().unwrap();
+        sessions
+            .get_or_insert_with(HashMap::new)
+            .insert(id.into(), data.into());
+        Ok(())
+    }
+
+    fn destroy(&self, id: &str) -> PhpResult<()> {
+        let mut sessions = TEST_SESSIONS.lock().unwrap();
+        sessions.get_or_insert_with(HashMap::new).remove(id);
+        Ok(())
+    }
+
+    fn gc(&self, _max_lifetime: i64) -> PhpResult<i64> {
+        let mut sessions = TEST_SESSIONS.lock().unwrap();
+        let sessions = sessions.get_or_insert_with(HashMap::new);
+        let count = sessions.len();
+        sessions.clear();
+        Ok(count as i64)
+    }
+}
+
+#[php_function]
+pub fn test_session_status() -> &'static str {
+    use ext_php_rs::session::Status;
+
+    match ext_php_rs::session::status() {
+        Status::Disabled => "disabled",
+        Status::None => "none",
+        Status::Active => "active",
+    }
+}
+
+#[php_function]
+pub fn test_session_install() -> PhpResult<()> {
+    Ok(ext_php_rs::session::install()?)
+}
+
+#[php_function]
+pub fn test_session_start() -> PhpResult<()> {
+    Ok(ext_php_rs::session::start()?)
+}
+
+#[php_function]
+pub fn test_session_get(key: &str) -> Option<Zval> {
+    ext_php_rs::session::get(key)
+}
+
+#[php_function]
+pub fn test_session_set(key: &str, value: &Zval) -> PhpResult<()> {
+    Ok(ext_php_rs::session::set(key, value.shallow_clone())?)
+}
+
+#[php_function]
+pub fn test_session_remove(key: &str) -> PhpResult<bool> {
+    Ok(ext_php_rs::session::remove(key)?)
+}
+
+#[php_function]
+pub fn test_session_stored(id: &str) -> Option<Binary<u8>> {
+    let sessions = TEST_SESSIONS.lock().unwrap();
+    sessions
+        .as_ref()
+        .and_then(|sessions| sessions.get(id).cloned())
+        .map(Binary::from)
+}
+
+#[php_function]
+pub fn test_persistent_counter(key: &str) -> i64 {
+    let counter = PersistentHandle::get_or_init(key, || AtomicI64::new(0));
+    counter.fetch_add(1, Ordering::SeqCst) + 1
+}
+
+#[php_function]
+pub fn test_persistent_get(key: &str) -> Option<i64> {
+    PersistentHandle::<AtomicI64>::get(key).map(|counter| counter.load(Ordering::SeqCst))
+}
+
+#[php_function]
+pub fn test_persistent_remove(key: &str) -> bool {
+    PersistentHandle::<AtomicI64>::remove(key).is_some()
+}
+
+#[php_function]
+pub fn test_persistent_try_init(key: &str, fail: bool) -> PhpResult<String> {
+    let value = PersistentHandle::get_or_try_init(key, || {
+        if fail {
+            Err("Failed to create the value")
+        } else {
+            Ok(key.to_uppercase())
+        }
+    })?;
+    Ok(String::clone(&value))
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct TestServer {
+    host: String,
+    ports: Vec<u16>,
+    tls: Option<bool>,
+}
+
+#[php_function]
+pub fn test_serde_roundtrip(value: &Zval) -> PhpResult<Zval> {
+    let value: serde_json::Value = ext_php_rs::serde::from_zval(value)?;
+    Ok(ext_php_rs::serde::to_zval(&value)?)
+}
+
+#[php_function]
+pub fn test_serde_server(value: &Zval) -> PhpResult<Zval> {
+    let mut server: TestServer = ext_php_rs::serde::from_zval(value)?;
+    server.ports.sort_unstable();
+    Ok(ext_php_rs::serde::to_zval(&server)?)
+}
+
+bitflags::bitflags! {
+    pub struct TestPermissions: u32 {
+        const READ = 0b001;
+        const WRITE = 0b010;
+        const EXECUTE = 0b100;
+    }
+}
+
+/// Builds a class whose methods are generated from a list at startup.
+fn build_dynamic_class() -> ext_php_rs::error::Result<()> {
+    let mut builder = ClassBuilder::new("DynamicGreeter")
+        .property("greeted", 0, PropertyFlags::Public)
+        .constant("DEFAULTS", HashMap::from([("greeting", "Hello")]))?;
+    for greeting in ["Hello", "Goodbye"] {
+        let func = FunctionBuilder::new_dynamic(greeting.to_lowercase())
+            .arg(Arg::new("name", DataType::String))
+            .returns(DataType::String, false, false)
+            .build()?;
+        builder = builder.dynamic_method(func, MethodFlags::Public, move |ex, retval| {
+            let mut name = Arg::new("name", DataType::String);
+            let (parser, this) = ex.parser_object();
+            if parser.arg(&mut name).parse().is_err() {
+                return;
+            }
+            let name: String = name.val().unwrap_or_default();
+            if let Some(this) = this {
+                let greeted: i64 = this.get_property("greeted").unwrap_or_default();
+                let _ = this.set_property("greeted", greeted + 1);
+            }
+            let _ = retval.set_string(&format!("{}, {}!", greeting, name), false);
+        });
+    }
+    let count = FunctionBuilder::new_dynamic("greetings")
+        .returns(DataType::Long, false, false)
+        .build()?;
+    builder
+        .dynamic_method(
+            count,
+            MethodFlags::Public | MethodFlags::Static,
+            |_, retval| {
+                retval.set_long(2);
+            },
+        )
+        .build()?;
+    Ok(())
+}
+
+/// The `TestGreets` trait, built before the classes using it.
+static TEST_GREETS: AtomicPtr<ClassEntry> = AtomicPtr::new(std::ptr::null_mut());
+
+fn test_greets() -> &'static ClassEntry {
+    unsafe { TEST_GREETS.load(Ordering::Relaxed).as_ref() }.expect("`TestGreets` was not built")
+}
+
+fn build_greets_trait() -> ext_php_rs::error::Result<()> {
+    let mut builder = ClassBuilder::new("TestGreets")
+        .flags(ClassFlags::Trait)
+        .property("greeting", "Hello", PropertyFlags::Public)
+        .property("farewell", "Goodbye", PropertyFlags::Public)
+        .constant("LANGUAGE", "en")?;
+    for (name, greeting) in [("greet", "Hello"), ("farewell", "Goodbye")] {
+        let func = FunctionBuilder::new_dynamic(name)
+            .returns(DataType::String, false, false)
+            .build()?;
+        builder = builder.dynamic_method(func, MethodFlags::Public, move |ex, retval| {
+            let class = ex
+                .get_self()
+                .and_then(|this| this.get_class_name().ok())
+                .unwrap_or_default();
+            let _ = retval.set_string(&format!("{} from {}!", greeting, class), false);
+        });
+    }
+    TEST_GREETS.store(builder.build()?, Ordering::Relaxed);
+    Ok(())
+}
+
+#[php_class]
+#[use_trait(ce = test_greets(), stub = "TestGreets")]
+pub struct TestTraitUser {
+    #[prop]
+    farewell: String,
+}
+
+#[php_impl]
+impl TestTraitUser {
+    pub fn __construct() -> Self {
+        Self {
+            farewell: "Bye".into(),
+        }
+    }
+
+    pub fn farewell(&self) -> &'static str {
+        "Farewell from the class!"
+    }
+}
+
+/// A suit of playing cards.
+#[php_enum]
+#[derive(Clone)]
+#[implements(ext_php_rs::zend::ce::countable())]
+pub enum TestSuit {
+    #[value("H")]
+    #[attribute("Test\\Label", "red", 1)]
+    Hearts,
+    #[value("S")]
+    Spades,
+}
+
+#[php_impl]
+impl TestSuit {
+    /// The symbol of the joker.
+    const JOKER: &'static str = "🃏";
+    /// The suit which beats the others.
+    const TRUMPS: TestSuit = TestSuit::Hearts;
+
+    pub fn color(&self) -> &'static str {
+        match self {
+            Self::Hearts => "Red",
+            Self::Spades => "Black",
+        }
+    }
+
+    pub fn symbols() -> String {
+        "♥♠".into()
+    }
+
+    /// The number of cards of the suit in a deck.
+    pub fn count(&self) -> i64 {
+        13
+    }
+
+    pub fn other(&self) -> Self {
+        match self {
+            Self::Hearts => Self::Spades,
+            Self::Spades => Self::Hearts,
+        }
+    }
+
+    pub fn first() -> Self {
+        Self::Hearts
+    }
+}
+
+#[php_enum]
+pub enum TestPriority {
+    Low = 1,
+    High = 10,
+}
+
+#[php_function]
+pub fn test_enum_suit_from_value(value: &str) -> PhpResult<String> {
+    let suit = TestSuit::try_from(value)?;
+    assert_eq!(<&str>::from(suit.clone()), value);
+    Ok(suit.case().name.into())
+}
+
+#[php_function]
+pub fn test_enum_suit_cases() -> PhpResult<ZBox<ZendHashTable>> {
+    let mut cases = ZendHashTable::new();
+    for (case, obj) in TestSuit::iter_cases() {
+        let value = match case.discriminant {
+            Some(Discriminant::String(value)) => value,
+            _ => return Err("`TestSuit` cases must be backed by strings".into()),
+        };
+        let mut entry = ZendHashTable::new();
+        entry.insert("value", value)?;
+        entry.insert("case", obj)?;
+        cases.insert(case.name, entry)?;
+    }
+    Ok(cases)
+}
+
+#[php_function]
+pub fn test_enum_suit_identity(suit: TestSuit) -> TestSuit {
+    suit
+}
+
+#[php_function]
+pub fn test_enum_suit_from_object(obj: &ZendObject) -> PhpResult<String> {
+    Ok(TestSuit::from_zend_object(obj)?.case().name.into())
+}
+
+#[php_function]
+pub fn test_enum_priority_from_value(value: i64) -> PhpResult<i64> {
+    Ok(TestPriority::try_from(value)?.into())
+}
+
+#[php_enum]
+pub enum TestDirection {
+    Up,
+    Down,
+}
+
+#[php_enum(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TestStatus {
+    InProgress,
+    #[rename("Finished")]
+    Done,
+}
+
+#[php_function]
+pub fn test_enum_status_name(status: TestStatus) -> String {
+    match status {
+        TestStatus::InProgress => "InProgress".into(),
+        TestStatus::Done => "Done".into(),
+    }
+}
+
+#[php_startup(before)]
+pub fn startup() {
+    ext_php_rs::logging::PhpLogger::init(log::LevelFilter::Debug).expect("failed to set logger");
+    tracing::subscriber::set_global_default(
+        tracing_subscriber::registry().with(ext_php_rs::logging::PhpLayer::new()),
+    )
+    .expect("failed to set tracing subscriber");
+    build_greets_trait().expect("failed to build `TestGreets` trait");
+    build_dynamic_class().expect("failed to build `DynamicGreeter` class");
+    FlagSet::<TestPermissions>::builder("TestPermissions")
+        .and_then(|class| class.build())
+        .expect("failed to build `TestPermissions` class");
+    ext_php_rs::zend::register_fcall_observer(TestObserver);
+    ext_php_rs::session::set_save_handler(TestSessionStore);
+    ext_php_rs::zend::register_auto_global("_EXT_PHP_RS", true, || {
+        let mut info = ZendHashTable::new();
+        let _ = info.insert("name", "ext-php-rs");
+        info
+    })
+    .expect("failed to register auto-global");
+}
+
+#[php_module]
+pub fn get_module(module: ModuleBuilder) -> ModuleBuilder {
+    module
+}
+
+#[cfg(test)]
+mod integration {
+    /// Runs a PHP file of the `integration` directory in a request of the embed
+    /// SAPI, with the extension loaded.
+    #[cfg(feature = "embed")]
+    pub fn run_php(file: &str) {
+        ext_php_rs::php_assert!(&format!(
+            "require '{}/src/integration/{}';",
+            env!("CARGO_MANIFEST_DIR"),
+            file
+        ));
+    }
+
+    /// Runs a PHP file of the `integration` directory with the `php` binary,
+    /// loading the extension built from this crate. Used when `libphp` is not
+    /// available to embed the engine.
+    #[cfg(not(feature = "embed"))]
+    pub fn run_php(file: &str) {
+        use std::{env, process::Command, sync::Once};
+
+        static BUILD: Once = Once::new();
+
+        BUILD.call_once(|| {
+            assert!(Command::new("cargo")
+                .arg("build")
+                .output()
+                .expect("failed to build extension")
+                .status
+                .success());
+        });
+
+        let mut path = env::current_dir().expect("Could not get cwd");
+        path.pop();
+        path.push("target");
+        path.push("debug");
+        path.push(if std::env::consts::DLL_EXTENSION == "dll" {
+            "tests"
+        } else {
+            "libtests"
+        });
+        path.set_extension(std::env::consts::DLL_EXTENSION);
+        let output = Command::new("php")
+            .arg(format!("-dextension={}", path.to_str().unwrap()))
+            .arg("-dassert.active=1")
+            .arg("-dassert.exception=1")
+            .arg("-dzend.assertions=1")
+            .arg(format!("src/integration/{}", file))
+            .output()
+            .expect("failed to run php file");
+        if !output.status.success() {
             panic!(
                 "
                 status: {}
@@ -174,14 +1756,42 @@ mod integration {
     }
 
     mod array;
+    mod autoload;
     mod binary;
     mod bool;
     mod callable;
+    mod cast;
+    mod channel;
     mod class;
     mod closure;
+    mod compare;
+    mod constant;
+    mod debug_info;
+    mod dimension;
+    mod enums;
+    mod flag_set;
+    mod gc;
+    mod globals;
+    mod http;
+    mod ini;
+    mod json;
+    mod logging;
+    mod native_iterator;
     mod nullable;
     mod number;
     mod object;
+    mod observer;
+    mod opcache;
+    mod opcode;
+    mod persistent;
+    mod property_observer;
+    mod psr3;
+    mod random;
+    mod reflection;
+    mod serde;
+    mod session;
+    mod stream;
     mod string;
+    mod timezone;
     mod types;
 }
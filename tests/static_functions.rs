@@ -0,0 +1,56 @@
+#![cfg_attr(windows, feature(abi_vectorcall))]
+#![cfg(feature = "embed")]
+extern crate ext_php_rs;
+
+use ext_php_rs::embed::Embed;
+use ext_php_rs::ffi::zend_register_module_ex;
+use ext_php_rs::prelude::*;
+use ext_php_rs::types::Zval;
+
+#[test]
+fn test_static_functions() {
+    Embed::run(|| {
+        // Allow to load the module
+        unsafe { zend_register_module_ex(get_module()) };
+
+        let result = Embed::eval("static_greet('foo');").unwrap();
+        assert_eq!(result.string().unwrap(), "Hello, foo!");
+
+        let result = Embed::eval("static_greet('foo', 'Hi');").unwrap();
+        assert_eq!(result.string().unwrap(), "Hi, foo!");
+
+        let result = Embed::eval("static_sum(1, 2, 3);").unwrap();
+        assert_eq!(result.long(), Some(6));
+
+        let result = Embed::eval("static_maybe(null);").unwrap();
+        assert!(result.is_null());
+
+        let result = Embed::eval(
+            "(new ReflectionFunction('static_greet'))->getNumberOfRequiredParameters();",
+        )
+        .unwrap();
+        assert_eq!(result.long(), Some(1));
+
+        assert!(Embed::eval("static_greet();").is_err());
+    });
+}
+
+#[php_function(defaults(greeting = "Hello"))]
+pub fn static_greet(name: String, greeting: String) -> String {
+    format!("{}, {}!", greeting, name)
+}
+
+#[php_function]
+pub fn static_sum(numbers: &[&Zval]) -> i64 {
+    numbers.iter().filter_map(|zv| zv.long()).sum()
+}
+
+#[php_function]
+pub fn static_maybe(value: Option<i64>) -> Option<i64> {
+    value
+}
+
+#[php_module(static_functions)]
+pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+    module
+}
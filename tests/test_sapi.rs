@@ -0,0 +1,120 @@
+#![cfg_attr(windows, feature(abi_vectorcall))]
+#![cfg(feature = "embed")]
+extern crate ext_php_rs;
+
+use ext_php_rs::embed::{Embed, TestRequest, TestSapi};
+use ext_php_rs::prelude::*;
+
+fn sapi() -> TestSapi {
+    TestSapi::new(get_module)
+}
+
+#[test]
+fn test_request_info() {
+    let request = TestRequest::get("/path?foo=bar")
+        .header("Accept", "text/plain")
+        .server("SERVER_NAME", "example.com");
+    let response = sapi()
+        .run(request, || {
+            Embed::exec(
+                "echo $_SERVER['REQUEST_METHOD'], ' ', $_SERVER['REQUEST_URI'], ' ', $_GET['foo'], ' ', \
+                 $_SERVER['HTTP_ACCEPT'], ' ', $_SERVER['SERVER_NAME'];",
+            )
+        })
+        .unwrap();
+
+    assert!(matches!(response.result, Ok(Ok(()))));
+    assert_eq!(response.status, 200);
+    assert_eq!(
+        response.text(),
+        "GET /path?foo=bar bar text/plain example.com"
+    );
+}
+
+#[test]
+fn test_input() {
+    let request = TestRequest::post("/")
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .header("Cookie", "a=1")
+        .cookie("b", "2")
+        .body("name=foo");
+    let response = sapi()
+        .run(request, || {
+            Embed::exec(
+                "echo $_POST['name'], ' ', file_get_contents('php://input'), ' ', $_COOKIE['a'], \
+                 $_COOKIE['b'];",
+            )
+        })
+        .unwrap();
+
+    assert!(matches!(response.result, Ok(Ok(()))));
+    assert_eq!(response.text(), "foo name=foo 12");
+}
+
+#[test]
+fn test_headers() {
+    let response = sapi()
+        .run(TestRequest::get("/"), || {
+            Embed::exec(
+                "http_response_code(201); header('X-Foo: bar'); header('Content-Type: text/plain');",
+            )
+        })
+        .unwrap();
+
+    assert!(matches!(response.result, Ok(Ok(()))));
+    assert_eq!(response.status, 201);
+    assert_eq!(response.header("x-foo"), Some("bar"));
+    assert_eq!(response.header("Content-Type"), Some("text/plain"));
+    assert!(response.body.is_empty());
+}
+
+#[test]
+fn test_frozen_time_and_env() {
+    let request = TestRequest::get("/")
+        .time(1_700_000_000.0)
+        .env("APP_ENV", "test");
+    let response = sapi()
+        .run(request, || {
+            Embed::exec("echo $_SERVER['REQUEST_TIME'], ' ', getenv('APP_ENV');")
+        })
+        .unwrap();
+
+    assert!(matches!(response.result, Ok(Ok(()))));
+    assert_eq!(response.text(), "1700000000 test");
+}
+
+#[test]
+fn test_logs() {
+    let response = sapi()
+        .run(TestRequest::get("/"), || {
+            Embed::exec("trigger_error('oops', E_USER_WARNING); echo hello_world('foo');")
+        })
+        .unwrap();
+
+    assert!(matches!(response.result, Ok(Ok(()))));
+    assert_eq!(response.text(), "Hello, foo!");
+    assert!(response.logs.iter().any(|log| log.contains("oops")));
+}
+
+#[test]
+fn test_request_isolation() {
+    for _ in 0..2 {
+        let response = sapi()
+            .run(TestRequest::get("/"), || {
+                Embed::exec("$GLOBALS['isolated'] ??= 0; echo ++$GLOBALS['isolated'];")
+            })
+            .unwrap();
+
+        assert_eq!(response.text(), "1");
+    }
+}
+
+#[php_function]
+pub fn hello_world(name: String) -> String {
+    format!("Hello, {}!", name)
+}
+
+#[php_module]
+pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+    module
+}